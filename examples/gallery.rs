@@ -310,12 +310,9 @@ impl Application for MyApp {
                     self.main_window_elements.menu.open(None);
                 }
                 MyAction::ShowTooltip((info, _window_id)) => {
-                    self.main_window_elements.tooltip.show(
-                        &info.text,
-                        info.align,
-                        info.element_bounds,
-                        &mut cx.res,
-                    );
+                    self.main_window_elements
+                        .tooltip
+                        .show_info(&info, &mut cx.res);
                 }
                 MyAction::HideTooltip(_window_id) => {
                     self.main_window_elements.tooltip.hide();
@@ -0,0 +1,318 @@
+//! Helpers for animating element state, such as hover fades or a knob
+//! catching up to a new value.
+//!
+//! ```ignore
+//! ElementEvent::Pointer(PointerEvent::PointerEntered) => {
+//!     if self.hover_amount.set_target(1.0) {
+//!         cx.set_animating(true);
+//!     }
+//! }
+//! ElementEvent::Animation { delta_seconds } => {
+//!     if self.hover_amount.on_animation(delta_seconds, cx) {
+//!         cx.request_repaint();
+//!     }
+//! }
+//! ```
+
+use crate::element_system::element::ElementContext;
+
+/// The rate at which springs are simulated, in steps per second.
+///
+/// Springs are always simulated with this fixed timestep so that the result
+/// does not depend on the frame rate.
+pub const SPRING_STEPS_PER_SECOND: f64 = 240.0;
+
+/// How an [`AnimatedF32`] moves towards its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AnimationMode {
+    /// Move towards the target at a constant rate, reaching it after the
+    /// given number of seconds.
+    Linear { duration_seconds: f32 },
+    /// Move towards the target quickly at first and then slow down,
+    /// reaching it after the given number of seconds.
+    EaseOut { duration_seconds: f32 },
+    /// Move towards the target like a damped spring. The velocity is kept
+    /// when the target changes mid-animation.
+    ///
+    /// A `damping` of `2.0 * stiffness.sqrt()` is critically damped. Lower
+    /// values will overshoot the target.
+    Spring { stiffness: f32, damping: f32 },
+}
+
+impl Default for AnimationMode {
+    fn default() -> Self {
+        Self::EaseOut {
+            duration_seconds: 0.15,
+        }
+    }
+}
+
+/// An `f32` value which animates towards a target value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatedF32 {
+    pub mode: AnimationMode,
+    /// The animation is considered settled once the value is within this
+    /// distance of the target (and, for springs, once the velocity is
+    /// within this amount).
+    ///
+    /// By default this is set to `0.0001`.
+    pub epsilon: f32,
+
+    value: f32,
+    target: f32,
+    start: f32,
+    velocity: f32,
+    elapsed_seconds: f64,
+    spring_steps: u64,
+    settled: bool,
+}
+
+impl AnimatedF32 {
+    pub fn new(value: f32, mode: AnimationMode) -> Self {
+        Self {
+            mode,
+            epsilon: 0.0001,
+            value,
+            target: value,
+            start: value,
+            velocity: 0.0,
+            elapsed_seconds: 0.0,
+            spring_steps: 0,
+            settled: true,
+        }
+    }
+
+    /// The current value.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Set the value to animate towards.
+    ///
+    /// Returns `true` if an animation has started, in which case the element
+    /// should call `cx.set_animating(true)`.
+    pub fn set_target(&mut self, target: f32) -> bool {
+        if self.target == target {
+            return false;
+        }
+
+        self.target = target;
+        self.start = self.value;
+        self.elapsed_seconds = 0.0;
+        self.spring_steps = 0;
+
+        if (self.value - target).abs() <= self.epsilon && self.velocity.abs() <= self.epsilon {
+            self.snap_to_target();
+            return false;
+        }
+
+        self.settled = false;
+        true
+    }
+
+    /// Jump to the given value immediately without animating.
+    pub fn set_immediate(&mut self, value: f32) {
+        self.target = value;
+        self.snap_to_target();
+    }
+
+    /// Returns `true` if the value has reached its target.
+    pub fn is_settled(&self) -> bool {
+        self.settled
+    }
+
+    /// Advance the animation by the given amount of time.
+    ///
+    /// Returns `true` if the value has changed.
+    pub fn tick(&mut self, delta_seconds: f64) -> bool {
+        if self.settled {
+            return false;
+        }
+
+        let prev_value = self.value;
+        self.elapsed_seconds += delta_seconds.max(0.0);
+
+        match self.mode {
+            AnimationMode::Linear { duration_seconds } => {
+                let t = self.progress(duration_seconds);
+                self.value = self.start + (self.target - self.start) * t;
+
+                if t >= 1.0 {
+                    self.snap_to_target();
+                }
+            }
+            AnimationMode::EaseOut { duration_seconds } => {
+                let t = self.progress(duration_seconds);
+                let eased = 1.0 - (1.0 - t).powi(3);
+                self.value = self.start + (self.target - self.start) * eased;
+
+                if t >= 1.0 {
+                    self.snap_to_target();
+                }
+            }
+            AnimationMode::Spring { stiffness, damping } => {
+                // Count the steps from the total elapsed time rather than
+                // accumulating a remainder, so that splitting the same
+                // amount of time into different frames gives the same
+                // number of steps.
+                let total_steps =
+                    (self.elapsed_seconds * SPRING_STEPS_PER_SECOND + 1.0e-6).floor() as u64;
+                let dt = (1.0 / SPRING_STEPS_PER_SECOND) as f32;
+
+                while self.spring_steps < total_steps {
+                    self.spring_steps += 1;
+
+                    let accel = stiffness * (self.target - self.value) - damping * self.velocity;
+                    self.velocity += accel * dt;
+                    self.value += self.velocity * dt;
+
+                    if (self.value - self.target).abs() <= self.epsilon
+                        && self.velocity.abs() <= self.epsilon
+                    {
+                        self.snap_to_target();
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.value != prev_value
+    }
+
+    /// Advance the animation from an `ElementEvent::Animation` event, and
+    /// call `cx.set_animating(false)` once the value has settled.
+    ///
+    /// Returns `true` if the value has changed, in which case the element
+    /// will usually want to request a repaint.
+    pub fn on_animation<A: Clone + 'static>(
+        &mut self,
+        delta_seconds: f64,
+        cx: &mut ElementContext<'_, A>,
+    ) -> bool {
+        let changed = self.tick(delta_seconds);
+
+        if self.settled {
+            cx.set_animating(false);
+        }
+
+        changed
+    }
+
+    fn progress(&self, duration_seconds: f32) -> f32 {
+        if duration_seconds <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed_seconds / f64::from(duration_seconds)).min(1.0) as f32
+        }
+    }
+
+    fn snap_to_target(&mut self) {
+        self.value = self.target;
+        self.start = self.target;
+        self.velocity = 0.0;
+        self.settled = true;
+    }
+}
+
+impl Default for AnimatedF32 {
+    fn default() -> Self {
+        Self::new(0.0, AnimationMode::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(anim: &mut AnimatedF32, delta_seconds: f64, max_frames: usize) -> usize {
+        for frame in 0..max_frames {
+            if anim.is_settled() {
+                return frame;
+            }
+            anim.tick(delta_seconds);
+        }
+        max_frames
+    }
+
+    #[test]
+    fn test_animated_f32_converges() {
+        let modes = [
+            AnimationMode::Linear {
+                duration_seconds: 0.5,
+            },
+            AnimationMode::EaseOut {
+                duration_seconds: 0.5,
+            },
+            AnimationMode::Spring {
+                stiffness: 200.0,
+                damping: 2.0 * 200.0f32.sqrt(),
+            },
+            // Underdamped, so it overshoots before settling.
+            AnimationMode::Spring {
+                stiffness: 200.0,
+                damping: 8.0,
+            },
+        ];
+
+        for mode in modes {
+            let mut anim = AnimatedF32::new(0.0, mode);
+            assert!(anim.set_target(1.0));
+            assert!(!anim.is_settled());
+
+            let frames = run(&mut anim, 1.0 / 60.0, 60 * 10);
+            assert!(frames < 60 * 10, "{:?} did not settle", mode);
+            assert_eq!(anim.value(), 1.0);
+        }
+
+        let mut anim = AnimatedF32::new(
+            0.0,
+            AnimationMode::Linear {
+                duration_seconds: 1.0,
+            },
+        );
+        anim.set_target(1.0);
+        anim.tick(0.25);
+        assert!((anim.value() - 0.25).abs() < 1e-6);
+
+        // Setting the same target again does not restart the animation.
+        assert!(!anim.set_target(1.0));
+        anim.set_immediate(0.5);
+        assert!(anim.is_settled());
+        assert!(!anim.tick(1.0));
+        assert_eq!(anim.value(), 0.5);
+    }
+
+    #[test]
+    fn test_spring_is_frame_rate_independent() {
+        let mode = AnimationMode::Spring {
+            stiffness: 300.0,
+            damping: 10.0,
+        };
+
+        let mut a = AnimatedF32::new(0.0, mode);
+        let mut b = AnimatedF32::new(0.0, mode);
+        a.set_target(1.0);
+        b.set_target(1.0);
+
+        for _ in 0..4 {
+            a.tick(0.1);
+        }
+        for _ in 0..60 {
+            b.tick(0.4 / 60.0);
+        }
+        assert_eq!(a.value(), b.value());
+
+        // The same frames always produce the same result.
+        let mut c = AnimatedF32::new(0.0, mode);
+        c.set_target(1.0);
+        for _ in 0..4 {
+            c.tick(0.1);
+        }
+        assert_eq!(a.value().to_bits(), c.value().to_bits());
+    }
+}
@@ -8,13 +8,19 @@ use std::{
     time::{Duration, Instant},
 };
 
+mod accelerators;
+
+pub use accelerators::{AcceleratorPriority, Accelerators, CMD_OR_CTRL};
+
 use crate::{
     event::{AppWindowEvent, KeyboardEvent},
+    prelude::ElementStyle,
     prelude::{ActionReceiver, ActionSender},
-    style::StyleSystem,
+    style::{ClassID, StyleSystem},
     window::{
-        LinuxBackendType, OpenWindowError, PointerLockState, ScaleFactorConfig, WindowBackend,
-        WindowCloseRequest, WindowConfig, WindowContext, WindowID, WindowState,
+        FullscreenMode, LinuxBackendType, MonitorInfo, OpenWindowError, PointerLockState,
+        ResizeDirection, ScaleFactorConfig, WindowBackend, WindowCloseRequest, WindowConfig,
+        WindowContext, WindowGeometry, WindowID, WindowIcon, WindowState,
     },
     MAIN_WINDOW,
 };
@@ -72,14 +78,76 @@ impl Default for TimerInterval {
     }
 }
 
+impl TimerInterval {
+    /// The duration of this interval on a monitor with the given refresh rate.
+    pub fn to_duration(&self, refresh_rate_millihertz: u32) -> Duration {
+        match self {
+            Self::Fixed(interval) => *interval,
+            Self::PercentageOfFrameRate(percentage) => {
+                Duration::from_secs_f64(percentage * 1_000.0 / refresh_rate_millihertz as f64)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AppConfig {
     pub main_window_config: WindowConfig,
     pub tick_timer_interval: TimerInterval,
+    /// The minimum interval between processed pointer move events.
+    ///
+    /// This can be overridden per window with
+    /// `WindowConfig::pointer_debounce_interval` or
+    /// `AppContext::set_pointer_debounce_interval`.
     pub pointer_debounce_interval: TimerInterval,
     pub pointer_locking_enabled: bool,
     pub use_dark_theme: bool,
+    /// Whether to switch between the dark and light themes automatically
+    /// when the OS color scheme changes (overriding `use_dark_theme`).
+    ///
+    /// Either way, [`AppWindowEvent::ThemeChanged`] is sent when the OS
+    /// color scheme changes.
+    ///
+    /// This is only supported by the winit backend.
+    ///
+    /// By default this is set to `false`.
+    pub follow_system_theme: bool,
+    /// Only tick the application while there is work driven by the tick.
+    ///
+    /// When this is enabled, the tick timer is paused while no element in
+    /// any window is animating and no hover, scroll wheel, or long press
+    /// timeout is pending. The event loop then sleeps until the next input
+    /// event, which saves CPU for mostly static UIs. Note that
+    /// `Application::on_tick` is not called while the tick timer is paused.
+    ///
+    /// This does not affect repaints. A window which needs to be repainted
+    /// (i.e. because an element was marked dirty in response to an input
+    /// event) is still redrawn right away without waiting for a tick.
+    ///
+    /// This is only supported by the winit backend.
+    ///
+    /// By default this is set to `false`.
+    pub render_on_demand: bool,
+    /// The maximum number of frames per second to render in each window.
+    ///
+    /// A redraw which is requested sooner than `1 / max_fps` seconds after
+    /// the previous frame of the window is held back until then. This saves
+    /// GPU time when animating simple UIs on monitors with a high refresh
+    /// rate.
+    ///
+    /// By default this is set to `None` (no limit).
+    pub max_fps: Option<u32>,
+}
+
+impl AppConfig {
+    /// The minimum duration between two frames of a window (see
+    /// `AppConfig::max_fps`).
+    pub fn min_frame_interval(&self) -> Option<Duration> {
+        self.max_fps
+            .filter(|max_fps| *max_fps > 0)
+            .map(|max_fps| Duration::from_secs_f64(1.0 / max_fps as f64))
+    }
 }
 
 impl Default for AppConfig {
@@ -90,6 +158,9 @@ impl Default for AppConfig {
             pointer_debounce_interval: TimerInterval::PercentageOfFrameRate(2.0),
             pointer_locking_enabled: true,
             use_dark_theme: true,
+            follow_system_theme: false,
+            render_on_demand: false,
+            max_fps: None,
         }
     }
 }
@@ -118,6 +189,7 @@ pub struct AppContext<A: Clone + 'static> {
     pub(crate) main_window: WindowState<A>,
     pub(crate) window_map: FxHashMap<WindowID, WindowState<A>>,
     pub(crate) linux_backend_type: Option<LinuxBackendType>,
+    pub(crate) monitors: Vec<MonitorInfo>,
     /// The global resource cx
     pub res: ResourceCtx,
 
@@ -125,6 +197,9 @@ pub struct AppContext<A: Clone + 'static> {
     pub action_sender: ActionSender<A>,
     /// The receiving end of the action queue.
     pub action_receiver: ActionReceiver<A>,
+
+    /// The application-wide keyboard shortcuts.
+    pub accelerators: Accelerators<A>,
 }
 
 impl<A: Clone + 'static> AppContext<A> {
@@ -135,6 +210,7 @@ impl<A: Clone + 'static> AppContext<A> {
         main_window: WindowState<A>,
         res: ResourceCtx,
         linux_backend_type: Option<LinuxBackendType>,
+        monitors: Vec<MonitorInfo>,
     ) -> Self {
         Self {
             config,
@@ -143,8 +219,10 @@ impl<A: Clone + 'static> AppContext<A> {
             window_map: FxHashMap::default(),
             res,
             linux_backend_type,
+            monitors,
             action_sender,
             action_receiver,
+            accelerators: Accelerators::new(),
         }
     }
 
@@ -153,6 +231,7 @@ impl<A: Clone + 'static> AppContext<A> {
             &mut self.res,
             &mut self.action_sender,
             &mut self.action_receiver,
+            &self.accelerators,
         )
     }
 
@@ -162,6 +241,7 @@ impl<A: Clone + 'static> AppContext<A> {
                 &mut self.res,
                 &mut self.action_sender,
                 &mut self.action_receiver,
+                &self.accelerators,
             )
         })
     }
@@ -194,6 +274,151 @@ impl<A: Clone + 'static> AppContext<A> {
             .push((window_id, WindowRequest::SetTitle(title)));
     }
 
+    /// Set the icon of the window, or `None` to use the default icon.
+    ///
+    /// Note, this is not supported on all platforms (i.e. Wayland and MacOS).
+    pub fn set_window_icon(&mut self, window_id: WindowID, icon: Option<WindowIcon>) {
+        self.window_requests
+            .push((window_id, WindowRequest::SetIcon(icon)));
+    }
+
+    /// Set the fullscreen mode of the window, or `None` to make the window
+    /// windowed.
+    pub fn set_fullscreen(&mut self, window_id: WindowID, mode: Option<FullscreenMode>) {
+        self.window_requests
+            .push((window_id, WindowRequest::SetFullscreen(mode)));
+    }
+
+    /// Start moving the window with the pointer, as if the user had pressed
+    /// the title bar of the window.
+    ///
+    /// This is useful for custom title bars. It should be called while the
+    /// primary pointer button is pressed (i.e. in response to an element
+    /// receiving a `ButtonJustPressed` event).
+    pub fn drag_window(&mut self, window_id: WindowID) {
+        self.window_requests.push((window_id, WindowRequest::Drag));
+    }
+
+    /// Start resizing the window with the pointer, as if the user had pressed
+    /// on the given edge or corner of the window.
+    ///
+    /// This is useful for custom resize handles. It should be called while the
+    /// primary pointer button is pressed (i.e. in response to an element
+    /// receiving a `ButtonJustPressed` event).
+    pub fn drag_resize_window(&mut self, window_id: WindowID, direction: ResizeDirection) {
+        self.window_requests
+            .push((window_id, WindowRequest::DragResize(direction)));
+    }
+
+    /// Returns `true` if the given window is currently in fullscreen mode.
+    pub fn is_fullscreen(&self, window_id: WindowID) -> bool {
+        if window_id == MAIN_WINDOW {
+            self.main_window.is_fullscreen
+        } else {
+            self.window_map
+                .get(&window_id)
+                .map(|w| w.is_fullscreen)
+                .unwrap_or(false)
+        }
+    }
+
+    /// The position of the top-left corner of the given window (including its
+    /// decorations) in physical pixels relative to the top-left corner of the
+    /// desktop.
+    ///
+    /// Returns `None` if the window does not exist or if the backend does not
+    /// support querying the position of windows (i.e. Wayland and baseview).
+    pub fn outer_position(&self, window_id: WindowID) -> Option<PhysicalPoint> {
+        if window_id == MAIN_WINDOW {
+            self.main_window.outer_position
+        } else {
+            self.window_map
+                .get(&window_id)
+                .and_then(|w| w.outer_position)
+        }
+    }
+
+    /// The monitors which are currently connected to the system.
+    ///
+    /// The index of a monitor in this list is the index used by
+    /// `FullscreenMode::Borderless`.
+    ///
+    /// This is empty if the backend cannot enumerate monitors (i.e. baseview
+    /// and headless windows).
+    pub fn available_monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    /// The monitor the given window is currently on.
+    ///
+    /// Returns `None` if the window does not exist or if the backend cannot
+    /// determine the monitor.
+    pub fn current_monitor(&self, window_id: WindowID) -> Option<&MonitorInfo> {
+        if window_id == MAIN_WINDOW {
+            self.main_window.current_monitor.as_ref()
+        } else {
+            self.window_map
+                .get(&window_id)
+                .and_then(|w| w.current_monitor.as_ref())
+        }
+    }
+
+    /// The current position, size, and state of the given window, i.e. to
+    /// save it when the application closes and restore it with
+    /// `WindowConfig::geometry`.
+    ///
+    /// Returns `None` if the window does not exist.
+    pub fn window_geometry(&self, window_id: WindowID) -> Option<WindowGeometry> {
+        if window_id == MAIN_WINDOW {
+            Some(self.main_window.geometry())
+        } else {
+            self.window_map.get(&window_id).map(|w| w.geometry())
+        }
+    }
+
+    /// Move the top-left corner of the given window (including its
+    /// decorations) to the given position in physical pixels relative to the
+    /// top-left corner of the desktop.
+    ///
+    /// This is useful for restoring the position of a window when it is
+    /// reopened. Note, this is not supported on all platforms (i.e. Wayland).
+    pub fn set_outer_position(&mut self, window_id: WindowID, position: PhysicalPoint) {
+        self.window_requests
+            .push((window_id, WindowRequest::SetOuterPosition(position)));
+    }
+
+    /// Set the minimum interval between processed pointer move events in the
+    /// given window, or `None` to use `AppConfig::pointer_debounce_interval`.
+    ///
+    /// See `WindowConfig::pointer_debounce_interval`.
+    pub fn set_pointer_debounce_interval(
+        &mut self,
+        window_id: WindowID,
+        interval: Option<TimerInterval>,
+    ) {
+        let window_state = if window_id == MAIN_WINDOW {
+            &mut self.main_window
+        } else if let Some(window_state) = self.window_map.get_mut(&window_id) {
+            window_state
+        } else {
+            return;
+        };
+
+        window_state.pointer_debounce.interval = interval;
+    }
+
+    /// The pointer debounce interval of the given window, or `None` if it uses
+    /// `AppConfig::pointer_debounce_interval`.
+    pub fn pointer_debounce_interval(&self, window_id: WindowID) -> Option<TimerInterval> {
+        if window_id == MAIN_WINDOW {
+            self.main_window.pointer_debounce.interval
+        } else {
+            self.window_map
+                .get(&window_id)
+                .and_then(|w| w.pointer_debounce.interval)
+        }
+    }
+
     pub fn set_scale_factor_config(&mut self, window_id: WindowID, config: ScaleFactorConfig) {
         self.window_requests
             .push((window_id, WindowRequest::SetScaleFactor(config)));
@@ -216,16 +441,102 @@ impl<A: Clone + 'static> AppContext<A> {
         self.linux_backend_type
     }
 
+    /// Switch every window between the dark and light variants of all
+    /// styles at runtime. Every element in every window is restyled.
+    ///
+    /// The initial value is set by [`AppConfig::use_dark_theme`].
+    pub fn set_dark_theme(&mut self, use_dark_theme: bool) {
+        if self.res.style_system.set_dark_theme(use_dark_theme) {
+            self.config.use_dark_theme = use_dark_theme;
+            self.notify_style_change();
+        }
+    }
+
+    #[deprecated(note = "use `AppContext::set_dark_theme` instead")]
     pub fn use_dark_theme(&mut self, use_dark_theme: bool) {
-        if self.res.style_system.use_dark_theme != use_dark_theme {
-            self.res.style_system.use_dark_theme = use_dark_theme;
+        self.set_dark_theme(use_dark_theme);
+    }
 
-            for window_id in self.window_map.keys() {
-                self.window_requests
-                    .push((*window_id, WindowRequest::NotifyThemeChange));
-            }
+    pub fn is_dark_theme(&self) -> bool {
+        self.res.style_system.use_dark_theme
+    }
+
+    /// Set the multiplier applied to the font size and line height of all
+    /// text in every window (a "text zoom").
+    ///
+    /// This is independent of the scale factor of each window, and it does
+    /// not affect the sizes of element rectangles set by the application.
+    /// Elements which size themselves to their text (i.e. by using their
+    /// `desired_size` methods) should be laid out again after calling this.
+    ///
+    /// By default this is set to `1.0`.
+    pub fn set_text_scale(&mut self, text_scale: f32) {
+        if self.res.style_system.set_text_scale(text_scale) {
+            self.notify_style_change();
         }
     }
+
+    pub fn text_scale(&self) -> f32 {
+        self.res.style_system.text_scale()
+    }
+
+    /// Insert a new style with the given class ID for the given element type
+    /// and restyle every element in every window.
+    ///
+    /// Use [`StyleSystem::add`] instead when setting up styles before any
+    /// elements are built.
+    pub fn set_style<T: ElementStyle>(&mut self, class: ClassID, is_dark_theme: bool, style: T) {
+        self.res.style_system.add(class, is_dark_theme, style);
+
+        if is_dark_theme == self.res.style_system.use_dark_theme {
+            self.notify_style_change();
+        }
+    }
+
+    /// Load styles from a JSON style file on disk and restyle every element
+    /// in every window.
+    ///
+    /// See [`StyleSystem::load_from_str`] for the format of the file.
+    ///
+    /// Returns the number of styles that were loaded.
+    #[cfg(feature = "serde")]
+    pub fn reload_styles_from_path(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<usize, crate::style::StyleLoadError> {
+        let num_loaded = self.res.style_system.reload_from_path(path)?;
+
+        if num_loaded > 0 {
+            self.notify_style_change();
+        }
+
+        Ok(num_loaded)
+    }
+
+    fn notify_style_change(&mut self) {
+        self.window_requests
+            .push((MAIN_WINDOW, WindowRequest::NotifyThemeChange));
+
+        for window_id in self.window_map.keys() {
+            self.window_requests
+                .push((*window_id, WindowRequest::NotifyThemeChange));
+        }
+    }
+}
+
+/// How far `now` is between the tick at `prev_tick_instant` and the next one,
+/// in the range `[0.0, 1.0]`.
+pub(crate) fn interpolation_alpha(
+    prev_tick_instant: Instant,
+    tick_interval: Duration,
+    now: Instant,
+) -> f32 {
+    if tick_interval.is_zero() {
+        return 0.0;
+    }
+
+    let elapsed = now.saturating_duration_since(prev_tick_instant);
+    (elapsed.as_secs_f64() / tick_interval.as_secs_f64()).min(1.0) as f32
 }
 
 pub(crate) struct AppHandler<A: Application> {
@@ -242,6 +553,7 @@ impl<A: Application> AppHandler<A> {
         config: AppConfig,
         res: ResourceCtx,
         linux_backend_type: Option<LinuxBackendType>,
+        monitors: Vec<MonitorInfo>,
     ) -> Result<Self, Box<dyn Error>> {
         let mut cx = AppContext::new(
             config,
@@ -250,6 +562,7 @@ impl<A: Application> AppHandler<A> {
             main_window,
             res,
             linux_backend_type,
+            monitors,
         );
 
         let user_app = A::init(&mut cx)?;
@@ -266,6 +579,11 @@ impl<A: Application> AppHandler<A> {
         let dt = (now - self.prev_tick_instant).as_secs_f64();
         self.prev_tick_instant = now;
 
+        self.on_tick_with_delta(dt);
+    }
+
+    /// Tick the application with the given delta time in seconds.
+    pub fn on_tick_with_delta(&mut self, dt: f64) {
         self.user_app.on_tick(dt, &mut self.cx);
 
         self.cx.main_window.on_animation_tick(dt, &mut self.cx.res);
@@ -275,14 +593,39 @@ impl<A: Application> AppHandler<A> {
         }
     }
 
+    /// Query the available monitors and the monitor each window is on again,
+    /// i.e. after a window was moved.
+    pub fn update_monitors<B: WindowBackend>(&mut self, backend: &mut B) {
+        self.cx.monitors = backend.available_monitors();
+
+        self.cx.main_window.current_monitor = backend.current_monitor(MAIN_WINDOW);
+        for (window_id, window_state) in self.cx.window_map.iter_mut() {
+            window_state.current_monitor = backend.current_monitor(*window_id);
+        }
+    }
+
+    /// Returns `true` if any window has work which is driven by the tick
+    /// timer (see `AppConfig::render_on_demand`).
+    pub fn needs_ticks(&self) -> bool {
+        self.cx.main_window.element_system.needs_animation_ticks()
+            || self
+                .cx
+                .window_map
+                .values()
+                .any(|w| w.element_system.needs_animation_ticks())
+    }
+
     pub fn process_updates<B: WindowBackend>(&mut self, backend: &mut B) {
         self.drain_pointer_moved_events(backend);
 
+        let min_frame_interval = self.cx.config.min_frame_interval();
+
         loop {
             let any_actions_processed = self.poll_actions();
 
             self.drain_window_requests(backend);
 
+            let now = Instant::now();
             let mut any_updates_processed = false;
             for (window_id, window_state) in self
                 .cx
@@ -295,7 +638,13 @@ impl<A: Application> AppHandler<A> {
                 }
 
                 if window_state.needs_repaint() {
-                    backend.request_redraw(*window_id);
+                    let can_redraw = min_frame_interval
+                        .map(|interval| window_state.frame_limiter.can_redraw(now, interval))
+                        .unwrap_or(true);
+
+                    if can_redraw {
+                        backend.request_redraw(*window_id);
+                    }
                 }
             }
 
@@ -412,6 +761,10 @@ impl<A: Application> AppHandler<A> {
                 }
                 WindowRequest::Maximize(maximized) => {
                     backend.set_maximized(window_id, maximized);
+                    window_state.is_maximized = backend
+                        .current_geometry(window_id, window_state.scale_factor)
+                        .map(|geometry| geometry.maximized)
+                        .unwrap_or(maximized);
                 }
                 WindowRequest::Focus => {
                     backend.focus_window(window_id);
@@ -422,6 +775,31 @@ impl<A: Application> AppHandler<A> {
                 WindowRequest::SetTitle(title) => {
                     backend.set_window_title(window_id, title);
                 }
+                WindowRequest::SetIcon(icon) => {
+                    backend.set_window_icon(window_id, icon);
+                }
+                WindowRequest::Drag => {
+                    if backend.drag_window(window_id).is_err() {
+                        log::warn!("Failed to start dragging window {}", window_id);
+                    }
+                }
+                WindowRequest::DragResize(direction) => {
+                    if backend.drag_resize_window(window_id, direction).is_err() {
+                        log::warn!(
+                            "Failed to start resizing window {} in direction {:?}",
+                            window_id,
+                            direction
+                        );
+                    }
+                }
+                WindowRequest::SetFullscreen(mode) => {
+                    backend.set_fullscreen(window_id, mode);
+                    window_state.is_fullscreen = backend.is_fullscreen(window_id);
+                }
+                WindowRequest::SetOuterPosition(position) => {
+                    backend.set_outer_position(window_id, position);
+                    window_state.outer_position = backend.outer_position(window_id);
+                }
                 WindowRequest::SetScaleFactor(config) => {
                     if let Some(new_size) = window_state.set_scale_factor_config(config) {
                         match backend.resize(window_id, new_size, window_state.scale_factor) {
@@ -505,6 +883,16 @@ impl<A: Application> AppHandler<A> {
             if do_unlock_pointer {
                 backend.unlock_pointer(*window_id, window_state.pointer_lock_state());
 
+                // The pointer position is not updated while the pointer is
+                // locked, so this restores the pointer to where it was before
+                // the drag started.
+                if let Some(prev_pos) = window_state.prev_pointer_pos {
+                    let _ = backend.set_pointer_position(
+                        *window_id,
+                        crate::math::to_physical_point(prev_pos, window_state.scale_factor),
+                    );
+                }
+
                 window_state.set_pointer_locked(PointerLockState::NotLocked);
             }
 
@@ -513,6 +901,14 @@ impl<A: Application> AppHandler<A> {
                     backend.set_cursor_icon(*window_id, new_icon);
                 }
             }
+
+            if let Some(allowed) = window_state.new_ime_allowed_request() {
+                backend.set_ime_allowed(*window_id, allowed);
+            }
+
+            if let Some(area) = window_state.new_ime_cursor_area_request() {
+                backend.set_ime_cursor_area(*window_id, area, window_state.scale_factor);
+            }
         }
     }
 }
@@ -525,7 +921,35 @@ pub(crate) enum WindowRequest {
     Focus,
     Close,
     SetTitle(String),
+    SetIcon(Option<WindowIcon>),
+    SetFullscreen(Option<FullscreenMode>),
+    SetOuterPosition(PhysicalPoint),
+    Drag,
+    DragResize(ResizeDirection),
     SetScaleFactor(ScaleFactorConfig),
     Create(WindowConfig),
     NotifyThemeChange,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolation_alpha_is_the_fraction_of_the_tick_elapsed() {
+        let tick = Instant::now();
+        let interval = Duration::from_millis(20);
+
+        assert_eq!(interpolation_alpha(tick, interval, tick), 0.0);
+        assert!(
+            (interpolation_alpha(tick, interval, tick + Duration::from_millis(5)) - 0.25).abs()
+                < 1e-6
+        );
+        // A late tick doesn't extrapolate past the current state.
+        assert_eq!(
+            interpolation_alpha(tick, interval, tick + Duration::from_millis(50)),
+            1.0
+        );
+        assert_eq!(interpolation_alpha(tick, Duration::ZERO, tick), 0.0);
+    }
+}
@@ -0,0 +1,208 @@
+use rustc_hash::FxHashMap;
+
+use crate::action_queue::ActionSender;
+use crate::event::{Code, KeyState, KeyboardEvent, Modifiers};
+
+/// The platform's primary modifier for keyboard shortcuts: `Modifiers::META`
+/// (the Command key) on MacOS and `Modifiers::CONTROL` on other platforms.
+///
+/// Register shortcuts such as "Copy" and "Paste" with this modifier so they
+/// behave as expected on every platform.
+pub const CMD_OR_CTRL: Modifiers = if cfg!(target_os = "macos") {
+    Modifiers::META
+} else {
+    Modifiers::CONTROL
+};
+
+/// The modifiers which are taken into account when matching a keyboard
+/// shortcut. Lock keys such as Caps Lock and Num Lock are ignored.
+const SHORTCUT_MODIFIERS: Modifiers = Modifiers::CONTROL
+    .union(Modifiers::ALT)
+    .union(Modifiers::SHIFT)
+    .union(Modifiers::META);
+
+/// When a keyboard shortcut is matched relative to the elements in the
+/// window.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AcceleratorPriority {
+    /// The shortcut is matched before the keyboard event is sent to the
+    /// elements, so it takes precedence over a focused element (i.e. a text
+    /// input).
+    BeforeElements,
+    /// The shortcut is only matched if no element captured the keyboard
+    /// event.
+    #[default]
+    AfterElements,
+}
+
+/// A registry of application-wide keyboard shortcuts.
+///
+/// When a key is pressed in any window and its modifiers and code match a
+/// registered shortcut, then the shortcut's action is sent to the action
+/// queue and the keyboard event is considered captured (so it is not sent to
+/// `Application::on_keyboard_event`).
+///
+/// Auto-repeated key presses are also matched, so holding down a shortcut
+/// (i.e. "Undo") sends its action repeatedly.
+pub struct Accelerators<A: Clone + 'static> {
+    shortcuts: FxHashMap<(Modifiers, Code), (A, AcceleratorPriority)>,
+}
+
+impl<A: Clone + 'static> Accelerators<A> {
+    pub fn new() -> Self {
+        Self {
+            shortcuts: FxHashMap::default(),
+        }
+    }
+
+    /// Register a keyboard shortcut.
+    ///
+    /// * `modifiers` - The modifiers which must be held down. Use
+    /// [`CMD_OR_CTRL`] for the platform's primary shortcut modifier. Lock
+    /// keys are ignored.
+    /// * `code` - The physical key code.
+    /// * `action` - The action to send when the shortcut is pressed.
+    /// * `priority` - Whether the shortcut is matched before or after the
+    /// elements in the window handle the keyboard event.
+    ///
+    /// Returns the action previously registered to this shortcut (if any).
+    pub fn register(
+        &mut self,
+        modifiers: Modifiers,
+        code: Code,
+        action: impl Into<A>,
+        priority: AcceleratorPriority,
+    ) -> Option<A> {
+        self.shortcuts
+            .insert(
+                (modifiers & SHORTCUT_MODIFIERS, code),
+                (action.into(), priority),
+            )
+            .map(|(action, _)| action)
+    }
+
+    /// Remove a keyboard shortcut.
+    ///
+    /// Returns the action which was registered to this shortcut (if any).
+    pub fn unregister(&mut self, modifiers: Modifiers, code: Code) -> Option<A> {
+        self.shortcuts
+            .remove(&(modifiers & SHORTCUT_MODIFIERS, code))
+            .map(|(action, _)| action)
+    }
+
+    /// The action registered to the given shortcut (if any).
+    pub fn get(&self, modifiers: Modifiers, code: Code) -> Option<&A> {
+        self.shortcuts
+            .get(&(modifiers & SHORTCUT_MODIFIERS, code))
+            .map(|(action, _)| action)
+    }
+
+    /// Remove all keyboard shortcuts.
+    pub fn clear(&mut self) {
+        self.shortcuts.clear();
+    }
+
+    /// Send the action of the shortcut matching the given keyboard event (if
+    /// it has the given priority).
+    ///
+    /// Returns `true` if a shortcut was matched.
+    pub(crate) fn dispatch(
+        &self,
+        event: &KeyboardEvent,
+        priority: AcceleratorPriority,
+        action_sender: &mut ActionSender<A>,
+    ) -> bool {
+        if event.state != KeyState::Down || event.is_composing {
+            return false;
+        }
+
+        let Some((action, shortcut_priority)) = self
+            .shortcuts
+            .get(&(event.modifiers & SHORTCUT_MODIFIERS, event.code))
+        else {
+            return false;
+        };
+
+        if *shortcut_priority != priority {
+            return false;
+        }
+
+        if action_sender.send(action.clone()).is_err() {
+            log::debug!("Action receiver was dropped, discarding keyboard shortcut action");
+        }
+
+        true
+    }
+}
+
+impl<A: Clone + 'static> Default for Accelerators<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_queue::action_channel;
+
+    fn key_down(modifiers: Modifiers, code: Code) -> KeyboardEvent {
+        KeyboardEvent {
+            state: KeyState::Down,
+            code,
+            modifiers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matched_shortcuts_send_their_action() {
+        let (mut sender, mut receiver) = action_channel::<&'static str>();
+        let mut accelerators = Accelerators::new();
+        accelerators.register(
+            CMD_OR_CTRL,
+            Code::KeyS,
+            "save",
+            AcceleratorPriority::AfterElements,
+        );
+        accelerators.register(
+            CMD_OR_CTRL | Modifiers::SHIFT,
+            Code::KeyS,
+            "save as",
+            AcceleratorPriority::BeforeElements,
+        );
+
+        let mut dispatch =
+            |event: &KeyboardEvent, priority| accelerators.dispatch(event, priority, &mut sender);
+
+        // Lock keys are ignored.
+        let save = key_down(CMD_OR_CTRL | Modifiers::CAPS_LOCK, Code::KeyS);
+        assert!(!dispatch(&save, AcceleratorPriority::BeforeElements));
+        assert!(dispatch(&save, AcceleratorPriority::AfterElements));
+
+        let save_as = key_down(CMD_OR_CTRL | Modifiers::SHIFT, Code::KeyS);
+        assert!(dispatch(&save_as, AcceleratorPriority::BeforeElements));
+
+        // Released keys and unregistered shortcuts are not matched.
+        let released = KeyboardEvent {
+            state: KeyState::Up,
+            ..save.clone()
+        };
+        assert!(!dispatch(&released, AcceleratorPriority::AfterElements));
+        assert!(!dispatch(
+            &key_down(Modifiers::ALT, Code::KeyS),
+            AcceleratorPriority::AfterElements
+        ));
+
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec!["save", "save as"]
+        );
+
+        assert_eq!(
+            accelerators.unregister(CMD_OR_CTRL, Code::KeyS),
+            Some("save")
+        );
+        assert_eq!(accelerators.get(CMD_OR_CTRL, Code::KeyS), None);
+    }
+}
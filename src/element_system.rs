@@ -7,6 +7,7 @@ use rootvg::color::PackedSrgb;
 use rootvg::math::PhysicalSizeI32;
 use rootvg::math::SizeI32;
 use rootvg::math::Vector;
+use rootvg::PrimitiveGroup;
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
 use smallvec::SmallVec;
@@ -14,31 +15,50 @@ use thunderdome::Arena;
 
 use crate::action_queue::ActionSender;
 use crate::clipboard::Clipboard;
-use crate::event::{CanvasEvent, ElementEvent, EventCaptureStatus, KeyboardEvent, PointerEvent};
+use crate::event::{
+    CanvasEvent, Code, ElementEvent, EventCaptureStatus, KeyState, KeyboardEvent, Modifiers,
+    PointerButton, PointerEvent, PointerType, TouchEvent, TouchPhase,
+};
 use crate::layout::Align2;
-use crate::math::{Point, PointI32, Rect, RectI32, ScaleFactor, Size, ZIndex};
-use crate::prelude::TooltipData;
-use crate::prelude::{ClassID, ResourceCtx};
+use crate::math::{Point, PointI32, Rect, RectI32, ScaleFactor, Size, Transform, ZIndex};
+use crate::prelude::{ClassID, IconID, ResourceCtx};
+use crate::prelude::{TooltipData, TooltipInner, TooltipPositioning};
 use crate::stmpsc_queue;
 use crate::CursorIcon;
 use crate::WindowID;
 
 mod cache;
+mod debug_grid;
+mod debug_overlay;
+mod debug_z_index;
 pub mod element;
+mod render_backend;
+mod render_stats;
 mod scissor_rect;
+mod viewport;
 
+pub use self::debug_grid::DebugGridConfig;
+pub use self::debug_overlay::DebugOverlayFlags;
+pub use self::debug_z_index::DebugZIndexConfig;
 use self::element::ChangeFocusRequest;
 use self::element::RenderContext;
-pub use self::scissor_rect::ScissorRectID;
+pub(crate) use self::render_backend::WgpuRenderBackend;
+#[cfg(feature = "headless")]
+pub use self::render_backend::{HeadlessRenderBackend, HeadlessRenderBackendError, ImageBuffer};
+pub use self::render_backend::{RenderBackend, RenderBackendCtx};
+pub use self::render_stats::RenderStats;
+pub use self::scissor_rect::{FocusWrapMode, ScissorRectID};
+pub use self::viewport::Viewport;
 
 use self::cache::{
     sync_element_rect_cache, CachedElementPrimitives, CachedElementRectForPointerEvent,
 };
+use self::debug_overlay::DebugOverlayRects;
 use self::element::{
-    Element, ElementBuilder, ElementContext, ElementFlags, ElementHandle, ElementID,
-    ElementModification, ElementModificationType,
+    Element, ElementBuilder, ElementContext, ElementFlags, ElementHandle, ElementID, ElementInfo,
+    ElementKey, ElementModification, ElementModificationType,
 };
-use self::scissor_rect::ScissorRect;
+use self::scissor_rect::{AxisTransform, ScissorRect};
 
 #[cfg(feature = "custom-shaders")]
 mod custom_shaders;
@@ -52,6 +72,9 @@ pub(crate) struct ElementSystemConfig {
     /// The clear color.
     pub clear_color: PackedSrgb,
 
+    /// Whether or not to skip clearing the window before rendering.
+    pub skip_clear: bool,
+
     /// An estimate for how many elements are expected to be in this view in a
     /// typical use case. This is used to pre-allocate capacity to improve slightly
     /// improve load-up times.
@@ -66,6 +89,12 @@ pub(crate) struct ElementSystemConfig {
     pub hover_timeout_duration: Duration,
 
     pub scroll_wheel_timeout_duration: Duration,
+
+    /// How long a touch has to be held down before it sends a
+    /// `PointerEvent::ContextMenu` event.
+    ///
+    /// By default this is set to 0.5 seconds.
+    pub long_press_duration: Duration,
 }
 
 struct ElementSystemContext<A: Clone + 'static> {
@@ -73,48 +102,106 @@ struct ElementSystemContext<A: Clone + 'static> {
     prev_element_with_exclusive_focus: Option<ElementID>,
     mod_queue_sender: stmpsc_queue::Sender<ElementModification>,
     action_sender: ActionSender<A>,
+    action_receiver_dropped: bool,
     scale_factor: ScaleFactor,
     logical_size: Size,
     cursor_icon: CursorIcon,
+    /// The priority of the cursor icon requested during the current event,
+    /// or `None` if no element has requested one yet.
+    cursor_icon_priority: Option<i32>,
     pointer_lock_request: Option<bool>,
     pointer_locked: bool,
+    ime_cursor_area_request: Option<Rect>,
     window_id: WindowID,
+    /// The sum of the delta times of every animation tick.
+    animation_clock_secs: f64,
+}
+
+impl<A: Clone + 'static> ElementSystemContext<A> {
+    /// Send an action to the application.
+    ///
+    /// If the action receiver has been dropped (i.e. the application is
+    /// shutting down), then the action is discarded.
+    fn send_action(&mut self, action: A) {
+        if self.action_sender.send(action).is_err() && !self.action_receiver_dropped {
+            self.action_receiver_dropped = true;
+            log::debug!(
+                "Action receiver for window {} was dropped, discarding actions",
+                self.window_id
+            );
+        }
+    }
 }
 
 pub(crate) struct ElementSystem<A: Clone + 'static> {
     pub clear_color: PackedSrgb,
+    pub skip_clear: bool,
 
     context: ElementSystemContext<A>,
 
     element_arena: Arena<ElementEntry<A>>,
+    element_key_to_id_map: FxHashMap<ElementKey, ElementID>,
     scissor_rect_id_to_index_map: FxHashMap<ScissorRectID, usize>,
     scissor_rects: Vec<ScissorRect>,
+    /// Whether any scissoring rectangle has ever been given a parent or a
+    /// transform.
+    has_resolved_scissor_rects: bool,
+    interpolation_alpha: f32,
 
     mod_queue_receiver: stmpsc_queue::Receiver<ElementModification>,
+    mod_queue_batch: Vec<ElementModification>,
+    mod_coalescer: ModificationCoalescer,
 
+    /// The hovered elements and the instant at which their hover timeout
+    /// fires (if one was started).
     hovered_elements: FxHashMap<ElementID, Option<Instant>>,
     elements_with_scroll_wheel_timeout: FxHashMap<ElementID, Option<Instant>>,
     animating_elements: Vec<ElementID>,
 
     elements_listening_to_pointer_event: Vec<CachedElementRectForPointerEvent>,
     elements_listening_to_pointer_event_need_sorted: bool,
+    /// The element which captured each active touch along with the last
+    /// position of the touch, keyed by touch ID.
+    touch_targets: FxHashMap<u64, (ElementID, Point, PointerType)>,
     painted_elements: Vec<CachedElementPrimitives>,
+    painted_elements_need_sorted: bool,
+    next_creation_order: u64,
     elements_listening_to_clicked_off: FxHashSet<ElementID>,
+    /// The elements with the `ElementFlags::KEEPS_TOOLTIP_OPEN` flag.
+    elements_keeping_tooltip_open: FxHashSet<ElementID>,
     element_with_active_tooltip: Option<ActiveTooltipInfo>,
+    following_tooltip: Option<FollowingTooltip>,
 
     physical_size: PhysicalSizeI32,
     hover_timeout_duration: Duration,
     scroll_wheel_timeout_duration: Duration,
+    long_press_duration: Duration,
+    /// The touch which may become a long press.
+    pending_long_press: Option<PendingLongPress>,
     prev_pointer_pos: Option<Point>,
+    /// Whether the OS input method was last enabled for this window.
+    ime_allowed: bool,
 
     show_tooltip_action: Option<Box<dyn FnMut(TooltipInfo) -> A>>,
     hide_tooltip_action: Option<Box<dyn FnMut() -> A>>,
 
     needs_repaint: bool,
     window_visible: bool,
+    scale_factor_changed: bool,
+    #[cfg(feature = "accessibility")]
+    accessibility_tree_changed: bool,
 
     render_caches: FxHashMap<u32, Box<dyn ElementRenderCache>>,
 
+    debug_grid: Option<DebugGridConfig>,
+    debug_grid_primitives: PrimitiveGroup,
+    debug_z_index: Option<DebugZIndexConfig>,
+    debug_z_index_primitives: PrimitiveGroup,
+    debug_overlay: DebugOverlayFlags,
+    debug_overlay_primitives: PrimitiveGroup,
+    last_render_stats: RenderStats,
+    loading_regions: FxHashMap<ScissorRectID, ElementHandle>,
+
     #[cfg(feature = "custom-shaders")]
     custom_pipelines: CustomPipelines,
 }
@@ -129,9 +216,11 @@ impl<A: Clone + 'static> ElementSystem<A> {
     ) -> Self {
         let ElementSystemConfig {
             clear_color,
+            skip_clear,
             preallocate_for_this_many_elements,
             hover_timeout_duration,
             scroll_wheel_timeout_duration,
+            long_press_duration,
         } = config;
 
         assert!(scale_factor.0 > 0.0);
@@ -159,49 +248,79 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
         Self {
             clear_color,
+            skip_clear,
 
             context: ElementSystemContext {
                 current_focus_info: None,
                 prev_element_with_exclusive_focus: None,
                 mod_queue_sender,
                 action_sender,
+                action_receiver_dropped: false,
                 scale_factor,
                 logical_size,
                 cursor_icon: CursorIcon::Default,
+                cursor_icon_priority: None,
                 pointer_lock_request: None,
                 pointer_locked: false,
+                ime_cursor_area_request: None,
                 window_id,
+                animation_clock_secs: 0.0,
             },
 
             element_arena: Arena::with_capacity(capacity),
+            element_key_to_id_map: FxHashMap::default(),
             scissor_rect_id_to_index_map,
             scissor_rects,
+            has_resolved_scissor_rects: false,
+            interpolation_alpha: 0.0,
 
             mod_queue_receiver,
+            mod_queue_batch: Vec::with_capacity(capacity),
+            mod_coalescer: ModificationCoalescer::default(),
 
             hovered_elements: FxHashMap::default(),
             elements_with_scroll_wheel_timeout: FxHashMap::default(),
             animating_elements: Vec::with_capacity(capacity),
 
-            elements_listening_to_pointer_event: Vec::new(),
+            elements_listening_to_pointer_event: Vec::with_capacity(capacity),
             elements_listening_to_pointer_event_need_sorted: false,
-            painted_elements: Vec::new(),
+            touch_targets: FxHashMap::default(),
+            painted_elements: Vec::with_capacity(capacity),
+            painted_elements_need_sorted: false,
+            next_creation_order: 0,
             elements_listening_to_clicked_off: FxHashSet::default(),
+            elements_keeping_tooltip_open: FxHashSet::default(),
             element_with_active_tooltip: None,
+            following_tooltip: None,
 
             physical_size,
             hover_timeout_duration,
             scroll_wheel_timeout_duration,
+            long_press_duration,
+            pending_long_press: None,
             prev_pointer_pos: None,
+            ime_allowed: false,
 
             needs_repaint: true,
             window_visible: true,
+            scale_factor_changed: false,
+            #[cfg(feature = "accessibility")]
+            accessibility_tree_changed: true,
 
             show_tooltip_action: None,
             hide_tooltip_action: None,
 
             render_caches: FxHashMap::default(),
 
+            debug_grid: None,
+            debug_grid_primitives: PrimitiveGroup::new(),
+            debug_z_index: None,
+            debug_z_index_primitives: PrimitiveGroup::new(),
+            debug_overlay: DebugOverlayFlags::empty(),
+            debug_overlay_primitives: PrimitiveGroup::new(),
+            last_render_stats: RenderStats::default(),
+            loading_regions: FxHashMap::default(),
+
             #[cfg(feature = "custom-shaders")]
             custom_pipelines: CustomPipelines::new(),
         }
@@ -261,11 +380,262 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
         let i = self.get_scissor_rect_index(scissor_rect_id);
 
-        self.scissor_rects[i].update(
+        if self.scissor_rects[i].update(
             new_rect,
             new_scroll_offset,
             &mut self.context.mod_queue_sender,
+        ) {
+            self.resolve_scissor_rects(Some(i));
+            self.sync_loading_overlay(scissor_rect_id);
+        }
+    }
+
+    /// Nest the given scissoring rectangle inside of another one, or pass
+    /// `None` to un-nest it.
+    ///
+    /// The rectangle of a nested scissoring rectangle is relative to the
+    /// content of its parent, so it moves when the parent moves or
+    /// scrolls (the scroll offsets compose). Its elements are clipped to the
+    /// intersection of its rectangle and the clipping rectangle of its
+    /// parent.
+    ///
+    /// Returns `false` and does nothing if this would create a cycle, or if
+    /// `scissor_rect_id == ScissorRectID::DEFAULT`.
+    ///
+    /// If a scissoring rectangle with either ID does not exist, then one will
+    /// be created.
+    pub fn set_scissor_rect_parent(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        parent: Option<ScissorRectID>,
+    ) -> bool {
+        if scissor_rect_id == ScissorRectID::DEFAULT {
+            return false;
+        }
+
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        let parent_index = parent.map(|parent| self.get_scissor_rect_index(parent));
+
+        if let Some(parent_index) = parent_index {
+            let mut ancestor = Some(parent_index);
+            while let Some(a) = ancestor {
+                if a == i {
+                    log::error!(
+                        "Cannot nest scissor rect {:?} inside of {:?} because it would create a cycle",
+                        scissor_rect_id,
+                        parent
+                    );
+                    return false;
+                }
+                ancestor = self.scissor_rects[a].parent;
+            }
+        }
+
+        if self.scissor_rects[i].parent != parent_index {
+            self.scissor_rects[i].parent = parent_index;
+            self.has_resolved_scissor_rects |= parent_index.is_some();
+            self.resolve_scissor_rects(None);
+            self.sync_loading_overlay(scissor_rect_id);
+        }
+
+        true
+    }
+
+    /// The scissoring rectangle that the given one is nested inside of.
+    pub fn scissor_rect_parent(&mut self, scissor_rect_id: ScissorRectID) -> Option<ScissorRectID> {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        let parent_index = self.scissor_rects[i].parent?;
+
+        self.scissor_rect_id_to_index_map
+            .iter()
+            .find(|(_, index)| **index == parent_index)
+            .map(|(id, _)| *id)
+    }
+
+    /// Recompute where every nested or transformed scissoring rectangle is in
+    /// the window and what it clips to, and notify the elements of the ones
+    /// that changed.
+    ///
+    /// * `already_notified` - The index of a scissoring rectangle whose
+    /// elements have already been notified.
+    fn resolve_scissor_rects(&mut self, already_notified: Option<usize>) {
+        if !self.has_resolved_scissor_rects {
+            return;
+        }
+
+        for i in 0..self.scissor_rects.len() {
+            let resolved = self.resolve_scissor_rect(i);
+
+            if self.scissor_rects[i].set_resolved(resolved) && already_notified != Some(i) {
+                self.scissor_rects[i].notify_elements(&mut self.context.mod_queue_sender);
+            }
+        }
+    }
+
+    /// The rectangle in window coordinates, the clipping rectangle, and the
+    /// composed transform of the given scissoring rectangle, or `None` if it
+    /// has no parent and no transform.
+    fn resolve_scissor_rect(&self, index: usize) -> Option<(RectI32, RectI32, AxisTransform)> {
+        let scissor_rect = &self.scissor_rects[index];
+        if scissor_rect.parent.is_none() && scissor_rect.transform == Transform::identity() {
+            return None;
+        }
+
+        // The chain of ancestors, from the given scissoring rectangle up to
+        // the root. Cycles are prevented in `set_scissor_rect_parent`, but
+        // the length is bounded anyway so that a cycle can't hang.
+        let mut chain = vec![index];
+        while let Some(parent) = self.scissor_rects[*chain.last().unwrap()].parent {
+            if chain.len() > self.scissor_rects.len() {
+                break;
+            }
+            chain.push(parent);
+        }
+
+        // The clipping rectangles are transformed along with the elements.
+        let transform_rect = |transform: &AxisTransform, rect: RectI32| -> RectI32 {
+            transform.transform_rect(rect.cast()).round().cast::<i32>()
+        };
+
+        let root = &self.scissor_rects[chain.pop().unwrap()];
+        let mut window_rect = root.rect();
+        let mut transform = AxisTransform::new(&root.transform, window_rect.origin.cast());
+        let mut clip_rect = transform_rect(&transform, window_rect);
+        let mut scroll_offset = root.scroll_offset();
+
+        while let Some(i) = chain.pop() {
+            let scissor_rect = &self.scissor_rects[i];
+            let rect = scissor_rect.rect();
+
+            window_rect = RectI32::new(
+                window_rect.origin + rect.origin.to_vector() - scroll_offset.round().cast::<i32>(),
+                rect.size,
+            );
+            transform = AxisTransform::new(&scissor_rect.transform, window_rect.origin.cast())
+                .then(&transform);
+            clip_rect = clip_rect
+                .intersection(&transform_rect(&transform, window_rect))
+                .unwrap_or(RectI32::new(window_rect.origin, SizeI32::zero()));
+            scroll_offset = scissor_rect.scroll_offset();
+        }
+
+        Some((window_rect, clip_rect, transform))
+    }
+
+    /// Dim the given scissoring rectangle, show a spinner in its center, and
+    /// block all pointer input to the elements inside of it. If one of those
+    /// elements has focus, then it will lose focus, and none of them can be
+    /// reached with the Tab key until loading is finished.
+    ///
+    /// This will *NOT* trigger an update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    ///
+    /// If a scissoring rectangle with the given ID does not exist, then
+    /// one will be created.
+    pub fn set_region_loading(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        loading: bool,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) {
+        if self.loading_regions.contains_key(&scissor_rect_id) == loading {
+            return;
+        }
+
+        if !loading {
+            // The overlay element is removed when its handle is dropped.
+            self.loading_regions.remove(&scissor_rect_id);
+            return;
+        }
+
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+
+        let focused_element_id = self
+            .context
+            .current_focus_info
+            .as_ref()
+            .map(|info| info.element_id);
+        if let Some(element_id) = focused_element_id {
+            if self
+                .element_arena
+                .get(element_id.0)
+                .is_some_and(|entry| entry.stack_data.scissor_rect_index == i)
+            {
+                self.element_release_focus(element_id, res, clipboard);
+            }
+        }
+
+        let overlay = self.add_element(
+            crate::elements::loading_overlay::loading_overlay_builder(
+                self.loading_overlay_rect(i),
+                scissor_rect_id,
+            ),
+            res,
+            clipboard,
         );
+
+        self.loading_regions.insert(scissor_rect_id, overlay);
+    }
+
+    /// Returns `true` if the given scissoring rectangle is currently loading.
+    ///
+    /// See [`ElementSystem::set_region_loading`].
+    pub fn region_is_loading(&self, scissor_rect_id: ScissorRectID) -> bool {
+        self.loading_regions.contains_key(&scissor_rect_id)
+    }
+
+    /// The rectangle covering the visible area of the given scissoring
+    /// rectangle, relative to its scrolled content.
+    fn loading_overlay_rect(&self, scissor_rect_index: usize) -> Rect {
+        let scissor_rect = &self.scissor_rects[scissor_rect_index];
+
+        Rect::new(
+            scissor_rect.scroll_offset().to_point(),
+            scissor_rect.rect().size.cast(),
+        )
+    }
+
+    fn sync_loading_overlay(&mut self, scissor_rect_id: ScissorRectID) {
+        if !self.loading_regions.contains_key(&scissor_rect_id) {
+            return;
+        }
+
+        let rect = self.loading_overlay_rect(self.get_scissor_rect_index(scissor_rect_id));
+
+        if let Some(overlay) = self.loading_regions.get_mut(&scissor_rect_id) {
+            overlay.set_rect(rect);
+        }
+    }
+
+    /// Drop every element, i.e. when switching between pages of an app.
+    ///
+    /// Elements with the `ElementFlags::LISTENS_TO_ON_DROPPED` flag are
+    /// notified. The scissoring rectangles and the configuration are kept,
+    /// but any loading regions are cleared. The handles of the dropped
+    /// elements can still be dropped safely afterwards.
+    ///
+    /// Returns the number of elements that were dropped.
+    pub fn clear(&mut self, res: &mut ResourceCtx, clipboard: &mut Clipboard) -> usize {
+        // Apply any pending changes first so that they don't refer to the
+        // elements after they are gone.
+        self.process_updates(res, clipboard);
+
+        self.loading_regions.clear();
+
+        let element_ids: Vec<ElementID> = self
+            .element_arena
+            .iter()
+            .map(|(index, _)| ElementID(index))
+            .collect();
+
+        for element_id in element_ids.iter() {
+            self.drop_element(*element_id, res, clipboard);
+        }
+
+        self.needs_repaint = true;
+
+        element_ids.len()
     }
 
     pub fn add_element(
@@ -282,19 +652,29 @@ impl<A: Clone + 'static> ElementSystem<A> {
             scissor_rect,
             class,
             flags,
+            key,
+            hit_tolerance,
         } = element_builder;
 
         let scissor_rect_index = self.get_scissor_rect_index(scissor_rect);
 
+        let creation_order = self.next_creation_order;
+        self.next_creation_order += 1;
+
         let mut stack_data = EntryStackData {
             rect,
             visible_rect: None,
+            hit_rect: None,
+            hit_tolerance: hit_tolerance.max(0.0),
             offset_from_scissor_rect_origin: rect.origin.to_vector(),
+            untransformed_size: rect.size,
             scissor_rect_index,
             z_index,
+            creation_order,
             flags,
             manually_hidden,
             class,
+            key,
             animating: false,
             index_in_painted_list: 0,
             index_in_pointer_event_list: 0,
@@ -314,6 +694,10 @@ impl<A: Clone + 'static> ElementSystem<A> {
             element,
         }));
 
+        if let Some(key) = key {
+            self.insert_element_key(key, element_id);
+        }
+
         let element_entry = self.element_arena.get_mut(element_id.0).unwrap();
 
         self.scissor_rects[scissor_rect_index]
@@ -322,16 +706,34 @@ impl<A: Clone + 'static> ElementSystem<A> {
         if element_entry
             .stack_data
             .flags
-            .contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+            .contains(ElementFlags::STICKY)
+        {
+            self.scissor_rects[scissor_rect_index].set_sticky_element(
+                element_id,
+                element_entry.stack_data.offset_from_scissor_rect_origin.y,
+                &mut self.context.mod_queue_sender,
+            );
+        }
+
+        if element_entry
+            .stack_data
+            .flags
+            .contains(ElementFlags::KEEPS_TOOLTIP_OPEN)
         {
+            self.elements_keeping_tooltip_open.insert(element_id);
+        }
+
+        if element_entry.stack_data.flags.is_hit_tested() {
             element_entry.stack_data.index_in_pointer_event_list =
                 self.elements_listening_to_pointer_event.len() as u32;
 
             self.elements_listening_to_pointer_event
                 .push(CachedElementRectForPointerEvent {
                     z_index: element_entry.stack_data.z_index,
+                    creation_order,
+                    scissor_rect_index,
                     element_id,
-                    visible_rect: element_entry.stack_data.visible_rect,
+                    hit_rect: element_entry.stack_data.hit_rect,
                 });
             self.elements_listening_to_pointer_event_need_sorted = true;
         }
@@ -346,9 +748,15 @@ impl<A: Clone + 'static> ElementSystem<A> {
                 element_id,
                 element_entry.stack_data.rect.origin.to_vector(),
                 element_entry.stack_data.z_index,
+                creation_order,
                 element_entry.stack_data.scissor_rect_index,
+                !element_entry
+                    .stack_data
+                    .flags
+                    .contains(ElementFlags::DRAWS_OUTSIDE_SCISSOR_RECT),
                 element_entry.stack_data.visible(),
             ));
+            self.painted_elements_need_sorted = true;
         }
 
         if element_entry
@@ -388,92 +796,769 @@ impl<A: Clone + 'static> ElementSystem<A> {
     ///
     /// If the element has been dropped, then this will return `None`.
     pub fn element_rect(&self, handle: &ElementHandle) -> Option<Rect> {
+        self.element_rect_by_id(handle.id())
+    }
+
+    /// Iterate over all of the element instances in arbitrary order.
+    pub fn iter_elements(&self) -> impl Iterator<Item = ElementInfo> + '_ {
         self.element_arena
-            .get(handle.id().0)
+            .iter()
+            .map(|(index, element_entry)| ElementInfo {
+                id: ElementID(index),
+                rect: element_entry.stack_data.rect,
+                z_index: element_entry.stack_data.z_index,
+                visible: element_entry.stack_data.visible(),
+                class: element_entry.stack_data.class,
+                manually_hidden: element_entry.stack_data.manually_hidden,
+            })
+    }
+
+    /// The lowest and highest z index of the elements assigned to the given
+    /// scissoring rectangle.
+    ///
+    /// Returns `None` if no elements are assigned to it.
+    pub fn z_index_extents(&mut self, scissor_rect_id: ScissorRectID) -> Option<(ZIndex, ZIndex)> {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        self.z_index_extents_in(i, None)
+    }
+
+    /// The lowest and highest z index of the other elements assigned to the
+    /// same scissoring rectangle as the given element.
+    ///
+    /// Returns `None` if the element has been dropped or if it is the only
+    /// element in its scissoring rectangle.
+    pub fn sibling_z_index_extents(&self, element_id: ElementID) -> Option<(ZIndex, ZIndex)> {
+        let entry = self.element_arena.get(element_id.0)?;
+        self.z_index_extents_in(entry.stack_data.scissor_rect_index, Some(element_id))
+    }
+
+    fn z_index_extents_in(
+        &self,
+        scissor_rect_index: usize,
+        exclude: Option<ElementID>,
+    ) -> Option<(ZIndex, ZIndex)> {
+        self.scissor_rects[scissor_rect_index]
+            .assigned_elements()
+            .iter()
+            .filter(|id| Some(**id) != exclude)
+            .filter_map(|id| self.element_arena.get(id.0))
+            .map(|entry| entry.stack_data.z_index)
+            .fold(None, |extents, z_index| match extents {
+                None => Some((z_index, z_index)),
+                Some((min, max)) => Some((min.min(z_index), max.max(z_index))),
+            })
+    }
+
+    pub fn element_rect_by_id(&self, element_id: ElementID) -> Option<Rect> {
+        self.element_arena
+            .get(element_id.0)
             .map(|entry| entry.stack_data.rect)
     }
 
-    pub fn auto_hide_tooltip(&mut self) {
-        if let Some(info) = &mut self.element_with_active_tooltip {
-            info.auto_hide = true;
-        }
+    /// Returns the ID of the element with the given key.
+    ///
+    /// If no element with that key exists, then this will return `None`.
+    pub fn element_with_key(&self, key: ElementKey) -> Option<ElementID> {
+        self.element_key_to_id_map.get(&key).copied()
     }
 
-    pub fn needs_repaint(&self) -> bool {
-        self.needs_repaint
+    /// Returns the key assigned to the given element.
+    ///
+    /// If the element has been dropped or has no key, then this will return `None`.
+    pub fn element_key(&self, handle: &ElementHandle) -> Option<ElementKey> {
+        self.element_arena
+            .get(handle.id().0)
+            .and_then(|entry| entry.stack_data.key)
     }
 
-    pub fn element_is_hovered(&self, element: &ElementHandle) -> bool {
-        let Some(element_entry) = self.element_arena.get(element.id().0) else {
-            return false;
+    /// Assign a stable key to the given element, or `None` to remove its key.
+    pub fn set_element_key(&mut self, handle: &ElementHandle, key: Option<ElementKey>) {
+        let element_id = handle.id();
+
+        let Some(element_entry) = self.element_arena.get_mut(element_id.0) else {
+            return;
         };
 
-        if let Some(pos) = self.prev_pointer_pos {
-            if let Some(visible_rect) = &element_entry.stack_data.visible_rect {
-                return visible_rect.contains(pos);
+        let prev_key = std::mem::replace(&mut element_entry.stack_data.key, key);
+
+        if let Some(prev_key) = prev_key {
+            if self.element_key_to_id_map.get(&prev_key) == Some(&element_id) {
+                self.element_key_to_id_map.remove(&prev_key);
             }
         }
 
-        false
+        if let Some(key) = key {
+            self.insert_element_key(key, element_id);
+        }
     }
 
-    pub fn on_pointer_locked(&mut self, locked: bool) {
-        self.context.pointer_locked = locked;
-        self.context.pointer_lock_request = None;
+    fn insert_element_key(&mut self, key: ElementKey, element_id: ElementID) {
+        if let Some(prev_element_id) = self.element_key_to_id_map.insert(key, element_id) {
+            if prev_element_id != element_id {
+                log::warn!("More than one element was assigned the key {}", key);
+
+                if let Some(prev_entry) = self.element_arena.get_mut(prev_element_id.0) {
+                    prev_entry.stack_data.key = None;
+                }
+            }
+        }
     }
 
-    pub fn resize(&mut self, physical_size: PhysicalSizeI32, scale_factor: ScaleFactor) {
-        self.physical_size = physical_size;
-        self.context.scale_factor = scale_factor;
-        self.context.logical_size = crate::math::to_logical_size_i32(physical_size, scale_factor);
+    /// Show or hide the debug grid overlay.
+    pub fn set_debug_grid(&mut self, config: Option<DebugGridConfig>) {
+        if self.debug_grid != config {
+            self.debug_grid = config;
+            self.needs_repaint = true;
+        }
+    }
 
-        self.scissor_rects[0].update(
-            Some(RectI32::new(
-                PointI32::default(),
-                SizeI32::new(
-                    self.context.logical_size.width.round() as i32,
-                    self.context.logical_size.height.round() as i32,
-                ),
-            )),
-            None,
-            &mut self.context.mod_queue_sender,
-        );
+    pub fn debug_grid(&self) -> Option<&DebugGridConfig> {
+        self.debug_grid.as_ref()
+    }
 
-        self.needs_repaint = true;
+    /// Show or hide the z index heatmap overlay.
+    pub fn set_debug_z_index(&mut self, config: Option<DebugZIndexConfig>) {
+        if self.debug_z_index != config {
+            self.debug_z_index = config;
+            self.needs_repaint = true;
+        }
     }
 
-    pub fn on_theme_changed(&mut self, res: &mut ResourceCtx, clipboard: &mut Clipboard) {
-        let mut element_ids = Vec::new();
-        for (element_id, element_entry) in self.element_arena.iter_mut() {
-            let element_id = ElementID(element_id);
+    pub fn debug_z_index(&self) -> Option<&DebugZIndexConfig> {
+        self.debug_z_index.as_ref()
+    }
 
-            send_event_to_element(
-                ElementEvent::StyleChanged,
-                element_entry,
-                element_id,
-                &mut self.context,
-                res,
-                clipboard,
-            );
+    /// Show the given layers of the debug overlay, or
+    /// `DebugOverlayFlags::empty()` to hide it.
+    pub fn set_debug_overlay(&mut self, flags: DebugOverlayFlags) {
+        if self.debug_overlay != flags {
+            self.debug_overlay = flags;
+            self.needs_repaint = true;
+        }
+    }
 
-            element_ids.push(element_id);
+    pub fn debug_overlay(&self) -> DebugOverlayFlags {
+        self.debug_overlay
+    }
+
+    /// Statistics about the last frame that was rendered to the window.
+    ///
+    /// Frames rendered to a [`Viewport`] are not included.
+    pub fn last_render_stats(&self) -> RenderStats {
+        self.last_render_stats
+    }
+
+    fn debug_overlay_rects(&self) -> DebugOverlayRects {
+        let visible_rect = |element_id: &ElementID| {
+            self.element_arena
+                .get(element_id.0)
+                .and_then(|entry| entry.stack_data.visible_rect)
+        };
+
+        DebugOverlayRects {
+            element_bounds: self
+                .element_arena
+                .iter()
+                .filter_map(|(_, entry)| entry.stack_data.visible_rect)
+                .collect(),
+            scissor_rects: self
+                .scissor_rects
+                .iter()
+                .map(|scissor_rect| scissor_rect.clip_rect().cast())
+                .collect(),
+            hovered: self
+                .hovered_elements
+                .keys()
+                .filter_map(visible_rect)
+                .collect(),
+            focused: self
+                .context
+                .current_focus_info
+                .as_ref()
+                .and_then(|info| visible_rect(&info.element_id)),
         }
+    }
 
-        for element_id in element_ids.iter().copied() {
-            self.mark_element_dirty(element_id);
+    /// The visible rectangle and z index of every visible painted element,
+    /// in render order.
+    fn debug_z_index_elements(&self) -> Vec<(Rect, ZIndex)> {
+        self.painted_elements
+            .iter()
+            .filter(|cache| cache.visible)
+            .filter_map(|cache| {
+                let element_entry = self.element_arena.get(cache.element_id.0)?;
+                let visible_rect = element_entry.stack_data.visible_rect?;
+                Some((visible_rect, cache.z_index(&self.scissor_rects)))
+            })
+            .collect()
+    }
+
+    pub fn auto_hide_tooltip(&mut self) {
+        if let Some(info) = &mut self.element_with_active_tooltip {
+            info.auto_hide = true;
         }
     }
 
-    pub fn handle_event(
+    pub fn needs_repaint(&self) -> bool {
+        self.needs_repaint
+    }
+
+    /// Returns `true` if there is work which is driven by animation events,
+    /// i.e. an animating element or a pending hover, scroll wheel, or long
+    /// press timeout.
+    ///
+    /// This is independent of [`ElementSystem::needs_repaint`], a repaint
+    /// does not need an animation event.
+    pub fn needs_animation_ticks(&self) -> bool {
+        !self.animating_elements.is_empty()
+            || self.pending_long_press.is_some()
+            || self.hovered_elements.values().any(Option::is_some)
+            || self
+                .elements_with_scroll_wheel_timeout
+                .values()
+                .any(Option::is_some)
+    }
+
+    /// Returns `true` if the accessibility tree may have changed since the
+    /// last call to [`ElementSystem::accessibility_tree_update`].
+    #[cfg(feature = "accessibility")]
+    pub fn accessibility_tree_changed(&self) -> bool {
+        self.accessibility_tree_changed
+    }
+
+    /// Build the full accessibility tree from the nodes of all visible
+    /// elements.
+    ///
+    /// The root node represents the window, and the elements are its children
+    /// in the order they were created. The bounds of the nodes are in physical
+    /// pixels relative to the window.
+    #[cfg(feature = "accessibility")]
+    pub fn accessibility_tree_update(&mut self) -> accesskit::TreeUpdate {
+        self.accessibility_tree_changed = false;
+
+        let scale = f64::from(self.context.scale_factor.0);
+
+        let mut entries: Vec<(u64, accesskit::NodeId, accesskit::Node)> = self
+            .element_arena
+            .iter()
+            .filter_map(|(index, element_entry)| {
+                let visible_rect = element_entry.stack_data.visible_rect?;
+                let mut node = element_entry.element.accessibility_node()?;
+
+                node.set_bounds(accesskit::Rect {
+                    x0: f64::from(visible_rect.min_x()) * scale,
+                    y0: f64::from(visible_rect.min_y()) * scale,
+                    x1: f64::from(visible_rect.max_x()) * scale,
+                    y1: f64::from(visible_rect.max_y()) * scale,
+                });
+
+                Some((
+                    element_entry.stack_data.creation_order,
+                    accessibility_node_id(ElementID(index)),
+                    node,
+                ))
+            })
+            .collect();
+        entries.sort_unstable_by_key(|(creation_order, _, _)| *creation_order);
+
+        let mut root = accesskit::Node::new(accesskit::Role::Window);
+        for (_, node_id, _) in entries.iter() {
+            root.push_child(*node_id);
+        }
+
+        let focus = self
+            .context
+            .current_focus_info
+            .as_ref()
+            .map(|info| accessibility_node_id(info.element_id))
+            .filter(|focus_id| entries.iter().any(|(_, node_id, _)| node_id == focus_id))
+            .unwrap_or(ACCESSIBILITY_ROOT_ID);
+
+        let mut nodes = Vec::with_capacity(entries.len() + 1);
+        nodes.push((ACCESSIBILITY_ROOT_ID, root));
+        nodes.extend(
+            entries
+                .into_iter()
+                .map(|(_, node_id, node)| (node_id, node)),
+        );
+
+        accesskit::TreeUpdate {
+            nodes,
+            tree: Some(accesskit::Tree::new(ACCESSIBILITY_ROOT_ID)),
+            focus,
+        }
+    }
+
+    /// Perform an action requested by assistive technology.
+    ///
+    /// `Focus` gives focus to a focusable element, and `Click` sends a press
+    /// and a release of the primary pointer button at the center of the
+    /// visible part of the element, regardless of what is on top of it.
+    ///
+    /// Returns `false` if the action is not supported by the target element,
+    /// or if the element no longer exists.
+    #[cfg(feature = "accessibility")]
+    pub fn handle_accessibility_action(
         &mut self,
-        event: &CanvasEvent,
+        request: &accesskit::ActionRequest,
         res: &mut ResourceCtx,
         clipboard: &mut Clipboard,
-    ) -> EventCaptureStatus {
-        match event {
-            CanvasEvent::Animation {
-                delta_seconds,
-                pointer_position,
+    ) -> bool {
+        let Some(element_id) = thunderdome::Index::from_bits(request.target.0).map(ElementID)
+        else {
+            return false;
+        };
+        let Some(element_entry) = self.element_arena.get(element_id.0) else {
+            return false;
+        };
+        let flags = element_entry.stack_data.flags;
+
+        match request.action {
+            accesskit::Action::Focus => {
+                if !flags.contains(ElementFlags::FOCUSABLE) {
+                    return false;
+                }
+
+                self.element_steal_focus(element_id, false, res, clipboard);
+            }
+            accesskit::Action::Click => {
+                let Some(visible_rect) = element_entry.stack_data.visible_rect else {
+                    return false;
+                };
+                if !flags.contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS) {
+                    return false;
+                }
+
+                let position = visible_rect.center();
+                for event in [
+                    PointerEvent::ButtonJustPressed {
+                        position,
+                        button: PointerButton::Primary,
+                        pointer_type: PointerType::default(),
+                        click_count: 1,
+                        modifiers: Modifiers::empty(),
+                    },
+                    PointerEvent::ButtonJustReleased {
+                        position,
+                        button: PointerButton::Primary,
+                        pointer_type: PointerType::default(),
+                        click_count: 1,
+                        modifiers: Modifiers::empty(),
+                    },
+                ] {
+                    let Some(element_entry) = self.element_arena.get_mut(element_id.0) else {
+                        break;
+                    };
+
+                    send_event_to_element(
+                        ElementEvent::Pointer(event),
+                        element_entry,
+                        element_id,
+                        &mut self.context,
+                        res,
+                        clipboard,
+                    );
+                }
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// The elements with the `ElementFlags::FOCUSABLE` flag which are
+    /// currently visible, in tab order.
+    ///
+    /// Elements inside of a loading region (see
+    /// [`ElementSystem::set_region_loading`]) are skipped.
+    pub fn focusable_elements_in_tab_order(&self) -> Vec<ElementID> {
+        // The overlay of a loading region is assigned to that region.
+        let loading_scissor_rects: Vec<usize> = self
+            .loading_regions
+            .values()
+            .map(|overlay| {
+                self.element_arena[overlay.id().0]
+                    .stack_data
+                    .scissor_rect_index
+            })
+            .collect();
+
+        let mut entries: Vec<(u64, ElementID)> = self
+            .element_arena
+            .iter()
+            .filter(|(_, element_entry)| {
+                element_entry
+                    .stack_data
+                    .flags
+                    .contains(ElementFlags::FOCUSABLE)
+                    && element_entry.stack_data.visible()
+                    && !loading_scissor_rects.contains(&element_entry.stack_data.scissor_rect_index)
+            })
+            .map(|(index, element_entry)| {
+                (element_entry.stack_data.creation_order, ElementID(index))
+            })
+            .collect();
+        entries.sort_unstable_by_key(|(creation_order, _)| *creation_order);
+
+        entries.into_iter().map(|(_, id)| id).collect()
+    }
+
+    pub fn focused_element(&self) -> Option<ElementID> {
+        self.context
+            .current_focus_info
+            .as_ref()
+            .map(|info| info.element_id)
+    }
+
+    pub fn element_is_focused(&self, element: &ElementHandle) -> bool {
+        self.focused_element() == Some(element.id())
+    }
+
+    /// A handle to the given element which does not own it, so dropping the
+    /// handle does not drop the element.
+    pub fn non_owning_handle(&self, element_id: ElementID) -> ElementHandle {
+        let stack_data = &self.element_arena[element_id.0].stack_data;
+
+        let opacity = if stack_data.flags.contains(ElementFlags::PAINTS) {
+            self.painted_elements[stack_data.index_in_painted_list as usize].opacity
+        } else {
+            1.0
+        };
+
+        self::element::new_non_owning_element_handle(
+            element_id,
+            self.context.mod_queue_sender.clone(),
+            stack_data.rect,
+            stack_data.z_index,
+            stack_data.manually_hidden,
+            stack_data.class,
+            opacity,
+        )
+    }
+
+    /// Give focus to the next (or the previous if `reverse` is `true`)
+    /// focusable element in tab order.
+    ///
+    /// If the focused element belongs to a focus scope (see
+    /// [`ElementSystem::set_focus_wrap_mode`]), then focus moves within that
+    /// scope first.
+    ///
+    /// Returns the newly focused element, or `None` if focus did not move
+    /// (there are no focusable elements, or focus stopped at the end).
+    pub fn focus_next(
+        &mut self,
+        reverse: bool,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) -> Option<ElementID> {
+        let element_id = self.next_focus_target(reverse)?;
+
+        self.element_steal_focus(element_id, false, res, clipboard);
+
+        Some(element_id)
+    }
+
+    fn next_focus_target(&self, reverse: bool) -> Option<ElementID> {
+        let tab_order = self.focusable_elements_in_tab_order();
+        let window_wrap =
+            self.scissor_rects[0].focus_wrap_mode.unwrap_or_default() == FocusWrapMode::Wrap;
+
+        let current = self
+            .focused_element()
+            .and_then(|focused_id| tab_order.iter().position(|id| *id == focused_id));
+
+        let Some(current) = current else {
+            return next_tab_index(tab_order.len(), None, reverse, window_wrap)
+                .map(|i| tab_order[i]);
+        };
+
+        let scissor_rect_index =
+            |id: &ElementID| self.element_arena[id.0].stack_data.scissor_rect_index;
+        let scope = scissor_rect_index(&tab_order[current]);
+
+        let scope_mode = if scope == 0 {
+            None
+        } else {
+            self.scissor_rects[scope].focus_wrap_mode
+        };
+
+        let Some(scope_mode) = scope_mode else {
+            return next_tab_index(tab_order.len(), Some(current), reverse, window_wrap)
+                .map(|i| tab_order[i]);
+        };
+
+        let in_scope: Vec<ElementID> = tab_order
+            .iter()
+            .filter(|id| scissor_rect_index(id) == scope)
+            .copied()
+            .collect();
+        let pos = in_scope.iter().position(|id| *id == tab_order[current]);
+
+        if let Some(i) = next_tab_index(
+            in_scope.len(),
+            pos,
+            reverse,
+            scope_mode == FocusWrapMode::Wrap,
+        ) {
+            return Some(in_scope[i]);
+        }
+
+        // Exit the scope, continuing from the focused element in the order
+        // of the elements outside of it.
+        let outside: Vec<ElementID> = tab_order
+            .iter()
+            .enumerate()
+            .filter(|(i, id)| *i == current || scissor_rect_index(id) != scope)
+            .map(|(_, id)| *id)
+            .collect();
+        let pos = outside.iter().position(|id| *id == tab_order[current]);
+
+        next_tab_index(outside.len(), pos, reverse, window_wrap)
+            .map(|i| outside[i])
+            .filter(|id| *id != tab_order[current])
+    }
+
+    /// Set what happens when tab traversal reaches the end of the given
+    /// scissoring rectangle.
+    ///
+    /// Setting this to `Some` makes the scissoring rectangle a focus scope:
+    /// while one of its elements has focus, Tab and Shift+Tab move between
+    /// the elements of the scope before moving anywhere else. Setting it
+    /// to `None` removes the scope.
+    ///
+    /// The whole window (`ScissorRectID::DEFAULT`) is always a scope that
+    /// wraps by default. Setting it to `None` resets it to wrapping.
+    pub fn set_focus_wrap_mode(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        mode: Option<FocusWrapMode>,
+    ) {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        self.scissor_rects[i].focus_wrap_mode = mode;
+    }
+
+    /// Set the z index offset of every element assigned to the given
+    /// scissoring rectangle.
+    ///
+    /// Elements are rendered and hit tested in the order of their z index
+    /// plus this offset. Giving one scissoring rectangle (i.e. a popup) a
+    /// base z index higher than the highest z index of the elements in
+    /// another one makes all of its elements appear above all of the
+    /// elements of the other one, regardless of their individual z indexes.
+    ///
+    /// By default this is set to `0`.
+    pub fn set_scissor_rect_base_z_index(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        base_z_index: ZIndex,
+    ) {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        if self.scissor_rects[i].base_z_index == base_z_index {
+            return;
+        }
+        self.scissor_rects[i].base_z_index = base_z_index;
+
+        self.elements_listening_to_pointer_event_need_sorted = true;
+        self.painted_elements_need_sorted = true;
+        self.needs_repaint = true;
+    }
+
+    pub fn scissor_rect_base_z_index(&mut self, scissor_rect_id: ScissorRectID) -> ZIndex {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        self.scissor_rects[i].base_z_index
+    }
+
+    /// Set the opacity and the transform that the given scissoring
+    /// rectangle and all of its elements are drawn with. This is useful for
+    /// animating transitions (i.e. a panel sliding, zooming, or fading in)
+    /// without touching the individual elements.
+    ///
+    /// If `opacity` or `transform` is `None`, then the current respecting
+    /// value will not be changed. The opacity is clamped to the range
+    /// `[0.0, 1.0]`, and both values are composed with the values of the
+    /// scissoring rectangles this one is nested in.
+    ///
+    /// The transform is relative to the origin of the scissoring rectangle.
+    /// Only its scale and its translation are used, since rotated or skewed
+    /// rectangles can't be clipped to. The transform is applied to the
+    /// layout, so the clipping rectangle, the rectangles of the elements, and
+    /// hit testing all follow it. The elements are rendered with their
+    /// transformed size, but the size of their text and icons is not scaled.
+    ///
+    /// The opacity is passed to each element in its `RenderContext`, and
+    /// elements which are completely transparent are not painted. Like
+    /// with [`ElementHandle::set_opacity`], the elements must apply the
+    /// opacity to their primitives themselves, so custom elements which
+    /// ignore it are not faded.
+    ///
+    /// If a scissoring rectangle with the given ID does not exist, then
+    /// one will be created.
+    ///
+    /// If `scissor_rect_id == ScissorRectID::DEFAULT`, then this
+    /// will do nothing.
+    pub fn update_scissor_rect_transform(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        opacity: Option<f32>,
+        transform: Option<Transform>,
+    ) {
+        if scissor_rect_id == ScissorRectID::DEFAULT {
+            return;
+        }
+
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+
+        if let Some(transform) = transform {
+            if self.scissor_rects[i].transform != transform {
+                self.scissor_rects[i].transform = transform;
+                self.has_resolved_scissor_rects = true;
+                self.resolve_scissor_rects(None);
+                self.needs_repaint = true;
+            }
+        }
+
+        if let Some(opacity) = opacity.map(|opacity| opacity.clamp(0.0, 1.0)) {
+            if self.scissor_rects[i].opacity != opacity {
+                self.scissor_rects[i].opacity = opacity;
+
+                // The elements apply the opacity to their primitives.
+                let scissor_rects = &self.scissor_rects;
+                for cache in self.painted_elements.iter_mut() {
+                    if scissor_rect_is_within(scissor_rects, cache.scissor_rect_index, i) {
+                        cache.dirty = true;
+                    }
+                }
+
+                self.needs_repaint = true;
+            }
+        }
+    }
+
+    /// Get the opacity and the transform of the given scissoring
+    /// rectangle, not including those of the scissoring rectangles it is
+    /// nested in.
+    ///
+    /// If a scissoring rectangle with the given ID does not exist, then
+    /// one will be created.
+    pub fn scissor_rect_transform(&mut self, scissor_rect_id: ScissorRectID) -> (f32, Transform) {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        (
+            self.scissor_rects[i].opacity,
+            self.scissor_rects[i].transform,
+        )
+    }
+
+    /// The opacity of every scissoring rectangle, composed with the
+    /// opacities of its ancestors.
+    fn scissor_rect_opacities(&self) -> Vec<f32> {
+        self.scissor_rects
+            .iter()
+            .map(|scissor_rect| {
+                let mut opacity = scissor_rect.opacity;
+
+                let mut ancestor = scissor_rect.parent;
+                while let Some(a) = ancestor {
+                    opacity *= self.scissor_rects[a].opacity;
+                    ancestor = self.scissor_rects[a].parent;
+                }
+
+                opacity
+            })
+            .collect()
+    }
+
+    pub fn focus_wrap_mode(&mut self, scissor_rect_id: ScissorRectID) -> Option<FocusWrapMode> {
+        let i = self.get_scissor_rect_index(scissor_rect_id);
+        let mode = self.scissor_rects[i].focus_wrap_mode;
+
+        if i == 0 {
+            Some(mode.unwrap_or_default())
+        } else {
+            mode
+        }
+    }
+
+    pub fn element_is_hovered(&self, element: &ElementHandle) -> bool {
+        let Some(element_entry) = self.element_arena.get(element.id().0) else {
+            return false;
+        };
+
+        if let Some(pos) = self.prev_pointer_pos {
+            if let Some(hit_rect) = &element_entry.stack_data.hit_rect {
+                return hit_rect.contains(pos);
+            }
+        }
+
+        false
+    }
+
+    pub fn on_pointer_locked(&mut self, locked: bool) {
+        self.context.pointer_locked = locked;
+        self.context.pointer_lock_request = None;
+    }
+
+    pub fn resize(&mut self, physical_size: PhysicalSizeI32, scale_factor: ScaleFactor) {
+        if self.context.scale_factor != scale_factor {
+            // The elements are notified in the next call to `process_updates`.
+            self.scale_factor_changed = true;
+        }
+
+        self.physical_size = physical_size;
+        self.context.scale_factor = scale_factor;
+        self.context.logical_size = crate::math::to_logical_size_i32(physical_size, scale_factor);
+
+        if self.scissor_rects[0].update(
+            Some(RectI32::new(
+                PointI32::default(),
+                SizeI32::new(
+                    self.context.logical_size.width.round() as i32,
+                    self.context.logical_size.height.round() as i32,
+                ),
+            )),
+            None,
+            &mut self.context.mod_queue_sender,
+        ) {
+            self.resolve_scissor_rects(Some(0));
+            self.sync_loading_overlay(ScissorRectID::DEFAULT);
+        }
+
+        self.needs_repaint = true;
+    }
+
+    pub fn on_theme_changed(&mut self, res: &mut ResourceCtx, clipboard: &mut Clipboard) {
+        let mut element_ids = Vec::new();
+        for (element_id, element_entry) in self.element_arena.iter_mut() {
+            let element_id = ElementID(element_id);
+
+            send_event_to_element(
+                ElementEvent::StyleChanged,
+                element_entry,
+                element_id,
+                &mut self.context,
+                res,
+                clipboard,
+            );
+
+            element_ids.push(element_id);
+        }
+
+        for element_id in element_ids.iter().copied() {
+            self.mark_element_dirty(element_id);
+        }
+    }
+
+    pub fn handle_event(
+        &mut self,
+        event: &CanvasEvent,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) -> EventCaptureStatus {
+        self.context.cursor_icon_priority = None;
+
+        match event {
+            CanvasEvent::Animation {
+                delta_seconds,
+                pointer_position,
             } => {
                 self.handle_animation_event(*delta_seconds, *pointer_position, res, clipboard);
 
@@ -481,8 +1566,24 @@ impl<A: Clone + 'static> ElementSystem<A> {
                 EventCaptureStatus::NotCaptured
             }
             CanvasEvent::Pointer(pointer_event) => {
-                self.handle_pointer_event(pointer_event, res, clipboard)
+                let mut capture_status = self.handle_pointer_event(pointer_event, res, clipboard);
+
+                if let PointerEvent::ButtonJustPressed {
+                    position,
+                    button: PointerButton::Secondary,
+                    ..
+                } = pointer_event
+                {
+                    if let EventCaptureStatus::Captured =
+                        self.handle_context_menu(*position, res, clipboard)
+                    {
+                        capture_status = EventCaptureStatus::Captured;
+                    }
+                }
+
+                capture_status
             }
+            CanvasEvent::Touch(touch_event) => self.handle_touch_event(touch_event, res, clipboard),
             CanvasEvent::Keyboard(keyboard_event) => {
                 self.handle_keyboard_event(keyboard_event, res, clipboard)
             }
@@ -606,12 +1707,33 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
         if let Some(_) = self.element_with_active_tooltip.take() {
             if let Some(action) = self.hide_tooltip_action.as_mut() {
-                self.context.action_sender.send((action)()).unwrap();
+                self.context.send_action((action)());
             }
         }
     }
 
     fn handle_window_unfocused(&mut self, res: &mut ResourceCtx, clipboard: &mut Clipboard) {
+        self.pending_long_press = None;
+
+        for (touch_id, (element_id, position, pointer_type)) in self.touch_targets.drain() {
+            if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
+                send_event_to_element(
+                    ElementEvent::Touch(TouchEvent {
+                        id: touch_id,
+                        phase: TouchPhase::Cancelled,
+                        position,
+                        pointer_type,
+                        modifiers: Modifiers::empty(),
+                    }),
+                    element_entry,
+                    element_id,
+                    &mut self.context,
+                    res,
+                    clipboard,
+                );
+            }
+        }
+
         for (element_id, _) in self.hovered_elements.iter() {
             if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
                 send_event_to_element(
@@ -656,7 +1778,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
         if let Some(_) = self.element_with_active_tooltip.take() {
             if let Some(action) = self.hide_tooltip_action.as_mut() {
-                self.context.action_sender.send((action)()).unwrap();
+                self.context.send_action((action)());
             }
         }
 
@@ -672,6 +1794,8 @@ impl<A: Clone + 'static> ElementSystem<A> {
         res: &mut ResourceCtx,
         clipboard: &mut Clipboard,
     ) {
+        self.context.animation_clock_secs += delta_seconds;
+
         for element_id in self.animating_elements.iter() {
             let element_entry = self.element_arena.get_mut(element_id.0).unwrap();
 
@@ -685,32 +1809,35 @@ impl<A: Clone + 'static> ElementSystem<A> {
             );
         }
 
+        let now = Instant::now();
         let pos = pointer_position.unwrap_or_default();
-        for (element_id, hover_start_instant) in self.hovered_elements.iter_mut() {
+        for (element_id, hover_timeout_deadline) in self.hovered_elements.iter_mut() {
             if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
-                if let Some(visible_rect) = element_entry.stack_data.visible_rect {
-                    if visible_rect.contains(pos) {
-                        if let Some(instant) = hover_start_instant.take() {
-                            if instant.elapsed() >= self.hover_timeout_duration {
-                                send_event_to_element(
-                                    ElementEvent::Pointer(PointerEvent::HoverTimeout {
-                                        position: pos,
-                                    }),
-                                    element_entry,
-                                    *element_id,
-                                    &mut self.context,
-                                    res,
-                                    clipboard,
-                                );
-                            } else {
-                                *hover_start_instant = Some(instant)
-                            }
-                        }
+                if let Some(hit_rect) = element_entry.stack_data.hit_rect {
+                    if hit_rect.contains(pos) && hover_timeout_elapsed(hover_timeout_deadline, now)
+                    {
+                        send_event_to_element(
+                            ElementEvent::Pointer(PointerEvent::HoverTimeout { position: pos }),
+                            element_entry,
+                            *element_id,
+                            &mut self.context,
+                            res,
+                            clipboard,
+                        );
                     }
                 }
             }
         }
 
+        // A long press is a hover timeout of a touch which stays in place.
+        if let Some(long_press) = self.pending_long_press.as_mut() {
+            if hover_timeout_elapsed(&mut long_press.hover_timeout_deadline, now) {
+                let position = long_press.position;
+                self.pending_long_press = None;
+                self.handle_context_menu(position, res, clipboard);
+            }
+        }
+
         for (element_id, start_instant) in self.elements_with_scroll_wheel_timeout.iter_mut() {
             if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
                 if let Some(instant) = start_instant.take() {
@@ -734,12 +1861,8 @@ impl<A: Clone + 'static> ElementSystem<A> {
             let mut hide_tooltip = true;
 
             if info.auto_hide {
-                if let Some(element_entry) = self.element_arena.get(info.element_id.0) {
-                    if let Some(pos) = self.prev_pointer_pos {
-                        if let Some(visible_rect) = &element_entry.stack_data.visible_rect {
-                            hide_tooltip = !visible_rect.contains(pos);
-                        }
-                    }
+                if let Some(pos) = self.prev_pointer_pos {
+                    hide_tooltip = !self.pointer_keeps_tooltip_open(info.element_id, pos);
                 }
             } else {
                 hide_tooltip = false;
@@ -748,7 +1871,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
             if hide_tooltip {
                 self.element_with_active_tooltip = None;
                 if let Some(action) = self.hide_tooltip_action.as_mut() {
-                    self.context.action_sender.send((action)()).unwrap();
+                    self.context.send_action((action)());
                 }
             }
         }
@@ -760,32 +1883,60 @@ impl<A: Clone + 'static> ElementSystem<A> {
         res: &mut ResourceCtx,
         clipboard: &mut Clipboard,
     ) -> EventCaptureStatus {
-        let pos = event.position();
-
-        match event {
-            PointerEvent::Moved { .. } => {
-                self.context.cursor_icon = CursorIcon::Default;
+        if let PointerEvent::DeltaMoved { .. } = event {
+            // Relative motion is only sent to the element that locked the pointer.
+            let focused_element_id = self
+                .context
+                .current_focus_info
+                .as_ref()
+                .map(|focused_data| focused_data.element_id);
+
+            if let Some(element_id) = focused_element_id.filter(|_| self.context.pointer_locked) {
+                if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
+                    return send_event_to_element(
+                        ElementEvent::Pointer(event.clone()),
+                        element_entry,
+                        element_id,
+                        &mut self.context,
+                        res,
+                        clipboard,
+                    );
+                }
+            }
+
+            return EventCaptureStatus::NotCaptured;
+        }
+
+        let pos = event.position();
+
+        match event {
+            PointerEvent::Moved { .. } => {
+                self.context.cursor_icon = CursorIcon::Default;
 
                 if let Some(info) = self.element_with_active_tooltip {
                     if info.auto_hide {
-                        let mut hide_tooltip = true;
-
-                        if let Some(element_entry) = self.element_arena.get(info.element_id.0) {
-                            if let Some(visible_rect) = element_entry.stack_data.visible_rect {
-                                hide_tooltip = !visible_rect.contains(pos);
-                            }
-                        }
+                        let hide_tooltip = !self.pointer_keeps_tooltip_open(info.element_id, pos);
 
                         if hide_tooltip {
                             self.element_with_active_tooltip = None;
                             if let Some(action) = self.hide_tooltip_action.as_mut() {
-                                self.context.action_sender.send((action)()).unwrap();
+                                self.context.send_action((action)());
                             }
                         }
                     }
                 }
 
                 self.prev_pointer_pos = Some(pos);
+
+                self.move_following_tooltip(pos);
+
+                if self
+                    .debug_grid
+                    .is_some_and(|grid| grid.show_pointer_position)
+                    || self.debug_overlay.contains(DebugOverlayFlags::HOVERED)
+                {
+                    self.needs_repaint = true;
+                }
             }
             PointerEvent::PointerLeft => {
                 for (element_id, _) in self.hovered_elements.iter_mut() {
@@ -804,22 +1955,28 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
                 if let Some(_) = self.element_with_active_tooltip.take() {
                     if let Some(action) = self.hide_tooltip_action.as_mut() {
-                        self.context.action_sender.send((action)()).unwrap();
+                        self.context.send_action((action)());
                     }
                 }
 
                 self.prev_pointer_pos = None;
 
+                if self.debug_grid.is_some()
+                    || self.debug_overlay.contains(DebugOverlayFlags::HOVERED)
+                {
+                    self.needs_repaint = true;
+                }
+
                 return EventCaptureStatus::NotCaptured;
             }
             _ => {}
         }
 
         let mut unhovered_elements: SmallVec<[ElementID; 4]> = SmallVec::new();
-        for (element_id, hover_start_instant) in self.hovered_elements.iter_mut() {
+        for (element_id, hover_timeout_deadline) in self.hovered_elements.iter_mut() {
             if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
-                let unhovered = if let Some(visible_rect) = element_entry.stack_data.visible_rect {
-                    !visible_rect.contains(pos)
+                let unhovered = if let Some(hit_rect) = element_entry.stack_data.hit_rect {
+                    !hit_rect.contains(pos)
                 } else {
                     true
                 };
@@ -835,8 +1992,8 @@ impl<A: Clone + 'static> ElementSystem<A> {
                         res,
                         clipboard,
                     );
-                } else if let Some(instant) = hover_start_instant.take() {
-                    if instant.elapsed() >= self.hover_timeout_duration {
+                } else if let Some(deadline) = hover_timeout_deadline.take() {
+                    if Instant::now() >= deadline {
                         send_event_to_element(
                             ElementEvent::Pointer(PointerEvent::HoverTimeout { position: pos }),
                             element_entry,
@@ -846,7 +2003,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
                             clipboard,
                         );
                     } else {
-                        *hover_start_instant = Some(instant)
+                        *hover_timeout_deadline = Some(deadline)
                     }
                 }
             } else {
@@ -861,12 +2018,11 @@ impl<A: Clone + 'static> ElementSystem<A> {
             let mut clicked_off_elements: SmallVec<[ElementID; 4]> = SmallVec::new();
             for element_id in self.elements_listening_to_clicked_off.iter() {
                 if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
-                    let clicked_off =
-                        if let Some(visible_rect) = element_entry.stack_data.visible_rect {
-                            !visible_rect.contains(pos)
-                        } else {
-                            true
-                        };
+                    let clicked_off = if let Some(hit_rect) = element_entry.stack_data.hit_rect {
+                        !hit_rect.contains(pos)
+                    } else {
+                        true
+                    };
 
                     if clicked_off {
                         clicked_off_elements.push(*element_id);
@@ -929,8 +2085,8 @@ impl<A: Clone + 'static> ElementSystem<A> {
                     .get_mut(focused_data.element_id.0)
                     .unwrap();
 
-                if let Some(visible_rect) = element_entry.stack_data.visible_rect {
-                    let in_bounds = visible_rect.contains(pos);
+                if let Some(hit_rect) = element_entry.stack_data.hit_rect {
+                    let in_bounds = hit_rect.contains(pos);
 
                     let send_event = if focused_data.listens_to_pointer_outside_bounds {
                         true
@@ -965,22 +2121,12 @@ impl<A: Clone + 'static> ElementSystem<A> {
             }
         }
 
-        if self.elements_listening_to_pointer_event_need_sorted {
-            self.elements_listening_to_pointer_event_need_sorted = false;
-            self.elements_listening_to_pointer_event
-                .sort_unstable_by(|a, b| a.z_index.cmp(&b.z_index));
-
-            for (i, cache) in self.elements_listening_to_pointer_event.iter().enumerate() {
-                if let Some(element_entry) = self.element_arena.get_mut(cache.element_id.0) {
-                    element_entry.stack_data.index_in_pointer_event_list = i as u32;
-                }
-            }
-        }
+        self.sort_pointer_event_list();
 
         // Iterate z indexes from highest to lowest.
         for cached_rect in self.elements_listening_to_pointer_event.iter().rev() {
-            if let Some(visible_rect) = &cached_rect.visible_rect {
-                if !visible_rect.contains(pos) {
+            if let Some(hit_rect) = &cached_rect.hit_rect {
+                if !hit_rect.contains(pos) {
                     continue;
                 }
 
@@ -989,6 +2135,15 @@ impl<A: Clone + 'static> ElementSystem<A> {
                     continue;
                 };
 
+                // This list also contains elements which only listen to touch events.
+                if !element_entry
+                    .stack_data
+                    .flags
+                    .contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+                {
+                    continue;
+                }
+
                 let mut did_just_enter = false;
                 self.hovered_elements
                     .entry(cached_rect.element_id)
@@ -1014,6 +2169,201 @@ impl<A: Clone + 'static> ElementSystem<A> {
         EventCaptureStatus::NotCaptured
     }
 
+    /// Send a `PointerEvent::ContextMenu` event to the focused element and
+    /// then to the elements under the given position from highest to lowest
+    /// z index, until one of them captures it.
+    fn handle_context_menu(
+        &mut self,
+        position: Point,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) -> EventCaptureStatus {
+        let event = ElementEvent::Pointer(PointerEvent::ContextMenu { position });
+
+        let focused_element_id = self
+            .context
+            .current_focus_info
+            .as_ref()
+            .filter(|focused_data| {
+                focused_data.listens_to_pointer_inside_bounds
+                    || focused_data.listens_to_pointer_outside_bounds
+            })
+            .map(|focused_data| focused_data.element_id);
+
+        if let Some(element_id) = focused_element_id {
+            if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
+                if let EventCaptureStatus::Captured = send_event_to_element(
+                    event.clone(),
+                    element_entry,
+                    element_id,
+                    &mut self.context,
+                    res,
+                    clipboard,
+                ) {
+                    return EventCaptureStatus::Captured;
+                }
+            }
+        }
+
+        self.sort_pointer_event_list();
+
+        // Iterate z indexes from highest to lowest.
+        for cached_rect in self.elements_listening_to_pointer_event.iter().rev() {
+            if Some(cached_rect.element_id) == focused_element_id {
+                continue;
+            }
+            if !cached_rect
+                .hit_rect
+                .is_some_and(|hit_rect| hit_rect.contains(position))
+            {
+                continue;
+            }
+
+            let Some(element_entry) = self.element_arena.get_mut(cached_rect.element_id.0) else {
+                continue;
+            };
+
+            // This list also contains elements which only listen to touch events.
+            if !element_entry
+                .stack_data
+                .flags
+                .contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+            {
+                continue;
+            }
+
+            if let EventCaptureStatus::Captured = send_event_to_element(
+                event.clone(),
+                element_entry,
+                cached_rect.element_id,
+                &mut self.context,
+                res,
+                clipboard,
+            ) {
+                return EventCaptureStatus::Captured;
+            }
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    /// Track the first touch so that a `PointerEvent::ContextMenu` event can
+    /// be sent if it is held down long enough without moving.
+    fn track_long_press(&mut self, event: &TouchEvent) {
+        match event.phase {
+            TouchPhase::Started => {
+                if self.pending_long_press.is_none() {
+                    self.pending_long_press = Some(PendingLongPress {
+                        touch_id: event.id,
+                        position: event.position,
+                        hover_timeout_deadline: Some(Instant::now() + self.long_press_duration),
+                    });
+                }
+            }
+            TouchPhase::Moved => {
+                if self.pending_long_press.is_some_and(|long_press| {
+                    long_press.touch_id == event.id
+                        && (event.position - long_press.position).length() > LONG_PRESS_SLOP
+                }) {
+                    self.pending_long_press = None;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                if self
+                    .pending_long_press
+                    .is_some_and(|long_press| long_press.touch_id == event.id)
+                {
+                    self.pending_long_press = None;
+                }
+            }
+        }
+    }
+
+    fn handle_touch_event(
+        &mut self,
+        event: &TouchEvent,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) -> EventCaptureStatus {
+        self.track_long_press(event);
+
+        if event.phase != TouchPhase::Started {
+            // Every other phase is only sent to the element which captured the
+            // start of the touch.
+            let element_id = if let TouchPhase::Ended | TouchPhase::Cancelled = event.phase {
+                self.touch_targets.remove(&event.id).map(|(id, _, _)| id)
+            } else {
+                self.touch_targets
+                    .get_mut(&event.id)
+                    .map(|(id, position, pointer_type)| {
+                        *position = event.position;
+                        *pointer_type = event.pointer_type;
+                        *id
+                    })
+            };
+
+            let Some(element_id) = element_id else {
+                return EventCaptureStatus::NotCaptured;
+            };
+
+            if let Some(element_entry) = self.element_arena.get_mut(element_id.0) {
+                send_event_to_element(
+                    ElementEvent::Touch(event.clone()),
+                    element_entry,
+                    element_id,
+                    &mut self.context,
+                    res,
+                    clipboard,
+                );
+            }
+
+            return EventCaptureStatus::Captured;
+        }
+
+        self.sort_pointer_event_list();
+
+        // Iterate z indexes from highest to lowest.
+        for cached_rect in self.elements_listening_to_pointer_event.iter().rev() {
+            let Some(hit_rect) = &cached_rect.hit_rect else {
+                continue;
+            };
+            if !hit_rect.contains(event.position) {
+                continue;
+            }
+
+            let Some(element_entry) = self.element_arena.get_mut(cached_rect.element_id.0) else {
+                continue;
+            };
+
+            if !element_entry
+                .stack_data
+                .flags
+                .contains(ElementFlags::LISTENS_TO_TOUCH)
+            {
+                continue;
+            }
+
+            let capture_status = send_event_to_element(
+                ElementEvent::Touch(event.clone()),
+                element_entry,
+                cached_rect.element_id,
+                &mut self.context,
+                res,
+                clipboard,
+            );
+
+            if let EventCaptureStatus::Captured = capture_status {
+                self.touch_targets.insert(
+                    event.id,
+                    (cached_rect.element_id, event.position, event.pointer_type),
+                );
+                return EventCaptureStatus::Captured;
+            }
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
     fn handle_keyboard_event(
         &mut self,
         event: &KeyboardEvent,
@@ -1042,6 +2392,22 @@ impl<A: Clone + 'static> ElementSystem<A> {
             }
         }
 
+        if event.state == KeyState::Down
+            && event.code == Code::Tab
+            && !event
+                .modifiers
+                .intersects(Modifiers::CONTROL | Modifiers::ALT | Modifiers::META)
+        {
+            // Only take the Tab key away from the application when there is
+            // a focus chain to move through.
+            if !self.focusable_elements_in_tab_order().is_empty() {
+                let reverse = event.modifiers.contains(Modifiers::SHIFT);
+                self.focus_next(reverse, res, clipboard);
+
+                return EventCaptureStatus::Captured;
+            }
+        }
+
         EventCaptureStatus::NotCaptured
     }
 
@@ -1079,180 +2445,326 @@ impl<A: Clone + 'static> ElementSystem<A> {
     /// Returns `true` if any updates were processed.
     pub fn process_updates(&mut self, res: &mut ResourceCtx, clipboard: &mut Clipboard) -> bool {
         let mut processed_update = false;
-        while let Some(modification) = self.mod_queue_receiver.try_recv() {
-            processed_update = true;
-            match modification.type_ {
-                ElementModificationType::CustomStateChanged => {
-                    self.handle_element_custom_state_changed(
-                        modification.element_id,
-                        res,
-                        clipboard,
-                    );
-                }
-                ElementModificationType::MarkDirty => {
-                    self.mark_element_dirty(modification.element_id);
-                }
-                ElementModificationType::RectChanged(new_rect) => {
-                    self.update_element_rect(modification.element_id, new_rect, res, clipboard);
-                }
-                ElementModificationType::ScissorRectChanged => {
-                    self.handle_scissor_rect_changed_for_element(
-                        modification.element_id,
-                        res,
-                        clipboard,
-                    );
-                }
-                ElementModificationType::ZIndexChanged(new_z_index) => {
-                    self.update_element_z_index(
-                        modification.element_id,
-                        new_z_index,
-                        res,
-                        clipboard,
-                    );
-                }
-                ElementModificationType::ExplicitlyHiddenChanged(manually_hidden) => {
-                    self.update_element_manually_hidden(
-                        modification.element_id,
-                        manually_hidden,
-                        res,
-                        clipboard,
-                    );
-                }
-                ElementModificationType::ClassChanged(new_class) => {
-                    self.handle_element_class_changed(
-                        modification.element_id,
-                        new_class,
-                        res,
-                        clipboard,
-                    );
-                }
-                ElementModificationType::SetAnimating(animating) => {
-                    self.set_element_animating(modification.element_id, animating);
-                }
-                ElementModificationType::ChangeFocus(req) => match req {
-                    ChangeFocusRequest::StealFocus => {
-                        self.element_steal_focus(modification.element_id, false, res, clipboard);
-                    }
-                    ChangeFocusRequest::StealTemporaryFocus => {
-                        self.element_steal_focus(modification.element_id, true, res, clipboard);
-                    }
-                    ChangeFocusRequest::ReleaseFocus => {
-                        self.element_release_focus(modification.element_id, res, clipboard);
-                    }
-                },
-                ElementModificationType::HandleDropped => {
-                    self.drop_element(modification.element_id, res, clipboard);
-                }
-                ElementModificationType::ListenToClickOff => {
-                    self.handle_element_listen_to_click_off(modification.element_id);
-                }
-                ElementModificationType::StartHoverTimeout => {
-                    self.handle_element_start_hover_timeout(modification.element_id);
-                }
-                ElementModificationType::StartScrollWheelTimeout => {
-                    self.handle_element_start_scroll_wheel_timeout(modification.element_id);
-                }
-                ElementModificationType::ShowTooltip { data, auto_hide } => {
-                    self.handle_element_show_tooltip(modification.element_id, data, auto_hide);
-                }
-                ElementModificationType::UpdateScissorRect(req) => {
-                    self.update_scissor_rect(
-                        req.scissor_rect_id,
-                        req.new_rect,
-                        req.new_scroll_offset,
-                    );
-                }
-            }
-        }
 
-        processed_update
-    }
+        self.context.cursor_icon_priority = None;
 
-    fn handle_element_listen_to_click_off(&mut self, element_id: ElementID) {
-        if self.element_arena.contains(element_id.0) {
-            self.elements_listening_to_clicked_off.insert(element_id);
+        if self.scale_factor_changed {
+            self.scale_factor_changed = false;
+            self.handle_scale_factor_changed(res, clipboard);
+            processed_update = true;
         }
-    }
 
-    fn handle_element_start_hover_timeout(&mut self, element_id: ElementID) {
-        if self.element_arena.contains(element_id.0) {
-            if let Some(hover_start_instant) = self.hovered_elements.get_mut(&element_id) {
-                *hover_start_instant = Some(Instant::now());
+        // Modifications are processed in batches so that redundant ones can be
+        // coalesced. Handling a modification may push new ones onto the queue,
+        // and those are handled in the next batch.
+        loop {
+            let mut batch = std::mem::take(&mut self.mod_queue_batch);
+            while let Some(modification) = self.mod_queue_receiver.try_recv() {
+                batch.push(modification);
+            }
+            if batch.is_empty() {
+                self.mod_queue_batch = batch;
+                break;
             }
-        }
-    }
-
-    fn handle_element_start_scroll_wheel_timeout(&mut self, element_id: ElementID) {
-        if self.element_arena.contains(element_id.0) {
-            self.elements_with_scroll_wheel_timeout
-                .insert(element_id, Some(Instant::now()));
-        }
-    }
-
-    fn handle_element_show_tooltip(
-        &mut self,
-        element_id: ElementID,
-        data: TooltipData,
-        auto_hide: bool,
-    ) {
-        let Some(element_entry) = self.element_arena.get(element_id.0) else {
-            // Element has been dropped. Do nothing and return.
-            return;
-        };
 
-        self.element_with_active_tooltip = Some(ActiveTooltipInfo {
-            element_id,
-            auto_hide,
-        });
+            processed_update = true;
+            self.mod_coalescer.coalesce(&mut batch);
 
-        if let Some(action) = self.show_tooltip_action.as_mut() {
-            let info = TooltipInfo {
-                text: data.text,
-                align: data.align,
-                element_bounds: element_entry.stack_data.rect,
-                window_id: self.context.window_id,
-            };
+            for modification in batch.drain(..) {
+                self.handle_modification(modification, res, clipboard);
+            }
 
-            self.context.action_sender.send((action)(info)).unwrap();
+            self.mod_queue_batch = batch;
         }
-    }
 
-    fn handle_element_class_changed(
-        &mut self,
-        element_id: ElementID,
-        new_class: ClassID,
-        res: &mut ResourceCtx,
-        clipboard: &mut Clipboard,
-    ) {
-        let Some(element_entry) = self.element_arena.get_mut(element_id.0) else {
-            // Element has been dropped. Do nothing and return.
-            return;
-        };
-
-        element_entry.stack_data.class = new_class;
+        #[cfg(feature = "accessibility")]
+        if processed_update {
+            self.accessibility_tree_changed = true;
+        }
 
-        send_event_to_element(
-            ElementEvent::StyleChanged,
-            element_entry,
-            element_id,
-            &mut self.context,
-            res,
-            clipboard,
-        );
+        // Rects, visibility, and focus may have changed.
+        if processed_update && !self.debug_overlay.is_empty() {
+            self.needs_repaint = true;
+        }
 
-        self.mark_element_dirty(element_id);
+        processed_update
     }
 
-    fn handle_element_custom_state_changed(
+    fn handle_modification(
         &mut self,
-        element_id: ElementID,
+        modification: ElementModification,
         res: &mut ResourceCtx,
         clipboard: &mut Clipboard,
     ) {
-        let Some(element_entry) = self.element_arena.get_mut(element_id.0) else {
-            // Element has been dropped. Do nothing and return.
-            return;
-        };
+        match modification.type_ {
+            ElementModificationType::CustomStateChanged => {
+                self.handle_element_custom_state_changed(modification.element_id, res, clipboard);
+            }
+            ElementModificationType::MarkDirty => {
+                self.mark_element_dirty(modification.element_id);
+            }
+            ElementModificationType::RectChanged(new_rect) => {
+                self.update_element_rect(modification.element_id, new_rect, res, clipboard);
+            }
+            ElementModificationType::ScissorRectChanged => {
+                self.handle_scissor_rect_changed_for_element(
+                    modification.element_id,
+                    res,
+                    clipboard,
+                );
+            }
+            ElementModificationType::ZIndexChanged(new_z_index) => {
+                self.update_element_z_index(modification.element_id, new_z_index, res, clipboard);
+            }
+            ElementModificationType::ExplicitlyHiddenChanged(manually_hidden) => {
+                self.update_element_manually_hidden(
+                    modification.element_id,
+                    manually_hidden,
+                    res,
+                    clipboard,
+                );
+            }
+            ElementModificationType::OpacityChanged(opacity) => {
+                self.update_element_opacity(modification.element_id, opacity);
+            }
+            ElementModificationType::ClassChanged(new_class) => {
+                self.handle_element_class_changed(
+                    modification.element_id,
+                    new_class,
+                    res,
+                    clipboard,
+                );
+            }
+            ElementModificationType::SetAnimating(animating) => {
+                self.set_element_animating(modification.element_id, animating);
+            }
+            ElementModificationType::ChangeFocus(req) => match req {
+                ChangeFocusRequest::StealFocus => {
+                    self.element_steal_focus(modification.element_id, false, res, clipboard);
+                }
+                ChangeFocusRequest::StealTemporaryFocus => {
+                    self.element_steal_focus(modification.element_id, true, res, clipboard);
+                }
+                ChangeFocusRequest::ReleaseFocus => {
+                    self.element_release_focus(modification.element_id, res, clipboard);
+                }
+            },
+            ElementModificationType::HandleDropped => {
+                self.drop_element(modification.element_id, res, clipboard);
+            }
+            ElementModificationType::ListenToClickOff => {
+                self.handle_element_listen_to_click_off(modification.element_id);
+            }
+            ElementModificationType::StartHoverTimeout { delay } => {
+                self.handle_element_start_hover_timeout(modification.element_id, delay);
+            }
+            ElementModificationType::StartScrollWheelTimeout => {
+                self.handle_element_start_scroll_wheel_timeout(modification.element_id);
+            }
+            ElementModificationType::ShowTooltip { data, auto_hide } => {
+                self.handle_element_show_tooltip(modification.element_id, data, auto_hide);
+            }
+            ElementModificationType::UpdateScissorRect(req) => {
+                self.update_scissor_rect(req.scissor_rect_id, req.new_rect, req.new_scroll_offset);
+            }
+        }
+    }
+
+    fn handle_scale_factor_changed(&mut self, res: &mut ResourceCtx, clipboard: &mut Clipboard) {
+        let scale_factor = self.context.scale_factor;
+
+        let mut element_ids = Vec::new();
+        for (element_id, element_entry) in self.element_arena.iter_mut() {
+            if !element_entry
+                .stack_data
+                .flags
+                .contains(ElementFlags::LISTENS_TO_SCALE_CHANGE)
+            {
+                continue;
+            }
+
+            let element_id = ElementID(element_id);
+
+            send_event_to_element(
+                ElementEvent::ScaleFactorChanged(scale_factor),
+                element_entry,
+                element_id,
+                &mut self.context,
+                res,
+                clipboard,
+            );
+
+            element_ids.push(element_id);
+        }
+
+        for element_id in element_ids.iter().copied() {
+            self.mark_element_dirty(element_id);
+        }
+    }
+
+    fn handle_element_listen_to_click_off(&mut self, element_id: ElementID) {
+        if self.element_arena.contains(element_id.0) {
+            self.elements_listening_to_clicked_off.insert(element_id);
+        }
+    }
+
+    fn handle_element_start_hover_timeout(
+        &mut self,
+        element_id: ElementID,
+        delay: Option<Duration>,
+    ) {
+        if self.element_arena.contains(element_id.0) {
+            if let Some(hover_timeout_deadline) = self.hovered_elements.get_mut(&element_id) {
+                let delay = delay.unwrap_or(self.hover_timeout_duration);
+                *hover_timeout_deadline = Some(Instant::now() + delay);
+            }
+        }
+    }
+
+    fn handle_element_start_scroll_wheel_timeout(&mut self, element_id: ElementID) {
+        if self.element_arena.contains(element_id.0) {
+            self.elements_with_scroll_wheel_timeout
+                .insert(element_id, Some(Instant::now()));
+        }
+    }
+
+    /// Returns `true` if the pointer at the given position should keep the
+    /// tooltip of the given element open.
+    ///
+    /// This is the case when the pointer is over the element, or over an
+    /// element with the `ElementFlags::KEEPS_TOOLTIP_OPEN` flag (or the gap
+    /// between the two).
+    fn pointer_keeps_tooltip_open(&self, element_id: ElementID, pos: Point) -> bool {
+        let Some(hit_rect) = self
+            .element_arena
+            .get(element_id.0)
+            .and_then(|element_entry| element_entry.stack_data.hit_rect)
+        else {
+            return false;
+        };
+
+        if hit_rect.contains(pos) {
+            return true;
+        }
+
+        self.elements_keeping_tooltip_open.iter().any(|id| {
+            self.element_arena
+                .get(id.0)
+                .and_then(|element_entry| element_entry.stack_data.visible_rect)
+                .map(|rect| rect.union(&hit_rect).contains(pos))
+                .unwrap_or(false)
+        })
+    }
+
+    fn handle_element_show_tooltip(
+        &mut self,
+        element_id: ElementID,
+        data: TooltipData,
+        auto_hide: bool,
+    ) {
+        let Some(element_entry) = self.element_arena.get(element_id.0) else {
+            // Element has been dropped. Do nothing and return.
+            return;
+        };
+
+        self.element_with_active_tooltip = Some(ActiveTooltipInfo {
+            element_id,
+            auto_hide,
+        });
+
+        let mut info = TooltipInfo {
+            text: data.text,
+            icon: data.icon,
+            description: data.description,
+            element_bounds: element_entry.stack_data.rect,
+            align: Align2::default(),
+            pointer_position: None,
+            window_id: self.context.window_id,
+        };
+
+        self.following_tooltip = match data.positioning {
+            TooltipPositioning::AnchoredToElement(align) => {
+                info.align = align;
+                None
+            }
+            TooltipPositioning::FollowCursor { offset } => {
+                let pointer_pos = self
+                    .prev_pointer_pos
+                    .unwrap_or(element_entry.stack_data.rect.center());
+                info.follow_cursor(pointer_pos, offset);
+
+                Some(FollowingTooltip {
+                    element_id,
+                    offset,
+                    info: info.clone(),
+                })
+            }
+        };
+
+        if let Some(action) = self.show_tooltip_action.as_mut() {
+            self.context.send_action((action)(info));
+        }
+    }
+
+    /// Move a tooltip in `TooltipPositioning::FollowCursor` mode to the new
+    /// position of the pointer.
+    fn move_following_tooltip(&mut self, pointer_pos: Point) {
+        let Some(following) = &mut self.following_tooltip else {
+            return;
+        };
+
+        if self
+            .element_with_active_tooltip
+            .map_or(true, |info| info.element_id != following.element_id)
+        {
+            // The tooltip has been hidden.
+            self.following_tooltip = None;
+            return;
+        }
+
+        following.info.follow_cursor(pointer_pos, following.offset);
+
+        if let Some(action) = self.show_tooltip_action.as_mut() {
+            self.context.send_action((action)(following.info.clone()));
+        }
+    }
+
+    fn handle_element_class_changed(
+        &mut self,
+        element_id: ElementID,
+        new_class: ClassID,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) {
+        let Some(element_entry) = self.element_arena.get_mut(element_id.0) else {
+            // Element has been dropped. Do nothing and return.
+            return;
+        };
+
+        element_entry.stack_data.class = new_class;
+
+        send_event_to_element(
+            ElementEvent::StyleChanged,
+            element_entry,
+            element_id,
+            &mut self.context,
+            res,
+            clipboard,
+        );
+
+        self.mark_element_dirty(element_id);
+    }
+
+    fn handle_element_custom_state_changed(
+        &mut self,
+        element_id: ElementID,
+        res: &mut ResourceCtx,
+        clipboard: &mut Clipboard,
+    ) {
+        let Some(element_entry) = self.element_arena.get_mut(element_id.0) else {
+            // Element has been dropped. Do nothing and return.
+            return;
+        };
 
         send_event_to_element(
             ElementEvent::CustomStateChanged,
@@ -1284,6 +2796,35 @@ impl<A: Clone + 'static> ElementSystem<A> {
         self.needs_repaint = true;
     }
 
+    fn update_element_opacity(&mut self, element_id: ElementID, opacity: f32) {
+        let Some(element_entry) = self.element_arena.get(element_id.0) else {
+            // Element has been dropped. Do nothing and return.
+            return;
+        };
+
+        if !element_entry
+            .stack_data
+            .flags
+            .contains(ElementFlags::PAINTS)
+        {
+            return;
+        }
+
+        let cache =
+            &mut self.painted_elements[element_entry.stack_data.index_in_painted_list as usize];
+        if cache.opacity == opacity {
+            return;
+        }
+
+        cache.opacity = opacity;
+        // The element applies the opacity to its primitives.
+        cache.dirty = true;
+
+        if element_entry.stack_data.visible() {
+            self.needs_repaint = true;
+        }
+    }
+
     fn update_element_rect(
         &mut self,
         element_id: ElementID,
@@ -1298,14 +2839,27 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
         let pos_changed =
             element_entry.stack_data.offset_from_scissor_rect_origin != new_rect.origin.to_vector();
-        let size_changed = element_entry.stack_data.rect.size != new_rect.size;
+        let size_changed = element_entry.stack_data.untransformed_size != new_rect.size;
 
         if !(pos_changed || size_changed) {
             return;
         }
 
         element_entry.stack_data.offset_from_scissor_rect_origin = new_rect.origin.to_vector();
-        element_entry.stack_data.rect.size = new_rect.size;
+        element_entry.stack_data.untransformed_size = new_rect.size;
+
+        if element_entry
+            .stack_data
+            .flags
+            .contains(ElementFlags::STICKY)
+        {
+            self.scissor_rects[element_entry.stack_data.scissor_rect_index].set_sticky_element(
+                element_id,
+                new_rect.origin.y,
+                &mut self.context.mod_queue_sender,
+            );
+        }
+
         element_entry.stack_data.update_layout(&self.scissor_rects);
 
         let old_visibility = element_entry.stack_data.visible();
@@ -1397,6 +2951,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
             return;
         };
 
+        let old_size = element_entry.stack_data.rect.size;
         element_entry.stack_data.update_layout(&self.scissor_rects);
 
         let old_visibility = element_entry.stack_data.visible();
@@ -1405,7 +2960,9 @@ impl<A: Clone + 'static> ElementSystem<A> {
             .update_visibility(&self.scissor_rects, self.window_visible);
         let visibility_changed = element_entry.stack_data.visible() != old_visibility;
 
-        let mark_dirty = visibility_changed && element_entry.stack_data.visible();
+        // The size changes when the scale of the scissoring rectangle does.
+        let mark_dirty = (visibility_changed || old_size != element_entry.stack_data.rect.size)
+            && element_entry.stack_data.visible();
 
         sync_element_rect_cache(
             &element_entry.stack_data,
@@ -1462,11 +3019,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
         }
         element_entry.stack_data.z_index = new_z_index;
 
-        if element_entry
-            .stack_data
-            .flags
-            .contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
-        {
+        if element_entry.stack_data.flags.is_hit_tested() {
             self.elements_listening_to_pointer_event
                 [element_entry.stack_data.index_in_pointer_event_list as usize]
                 .z_index = new_z_index;
@@ -1480,6 +3033,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
         {
             self.painted_elements[element_entry.stack_data.index_in_painted_list as usize]
                 .z_index = new_z_index;
+            self.painted_elements_need_sorted = true;
         }
 
         if element_entry
@@ -1648,6 +3202,10 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
         let element_entry = self.element_arena.get_mut(element_id.0).unwrap();
 
+        // The focus is part of the accessibility tree.
+        #[cfg(feature = "accessibility")]
+        self.accessibility_tree_changed = true;
+
         self.context.current_focus_info = Some(FocusInfo {
             element_id,
             listens_to_pointer_inside_bounds: element_entry
@@ -1701,6 +3259,9 @@ impl<A: Clone + 'static> ElementSystem<A> {
             return;
         };
 
+        #[cfg(feature = "accessibility")]
+        self.accessibility_tree_changed = true;
+
         release_focus_for_element(element_id, element_entry, &mut self.context, res, clipboard);
     }
 
@@ -1721,6 +3282,15 @@ impl<A: Clone + 'static> ElementSystem<A> {
             return;
         };
 
+        self.touch_targets.retain(|_, (id, _, _)| *id != element_id);
+        self.elements_keeping_tooltip_open.remove(&element_id);
+
+        if let Some(key) = element_entry.stack_data.key {
+            if self.element_key_to_id_map.get(&key) == Some(&element_id) {
+                self.element_key_to_id_map.remove(&key);
+            }
+        }
+
         release_focus_for_element(
             element_id,
             &mut element_entry,
@@ -1759,11 +3329,7 @@ impl<A: Clone + 'static> ElementSystem<A> {
             }
         }
 
-        if element_entry
-            .stack_data
-            .flags
-            .contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
-        {
+        if element_entry.stack_data.flags.is_hit_tested() {
             let _ = self
                 .elements_listening_to_pointer_event
                 .swap_remove(element_entry.stack_data.index_in_pointer_event_list as usize);
@@ -1808,17 +3374,28 @@ impl<A: Clone + 'static> ElementSystem<A> {
                     .stack_data
                     .index_in_painted_list = element_entry.stack_data.index_in_painted_list;
             }
+
+            self.painted_elements_need_sorted = true;
         }
 
         self.scissor_rects[usize::from(element_entry.stack_data.scissor_rect_index)]
             .remove_element(&element_entry.stack_data, &mut self.element_arena);
 
+        if element_entry
+            .stack_data
+            .flags
+            .contains(ElementFlags::STICKY)
+        {
+            self.scissor_rects[element_entry.stack_data.scissor_rect_index]
+                .remove_sticky_element(element_id, &mut self.context.mod_queue_sender);
+        }
+
         if let Some(info) = &self.element_with_active_tooltip {
             if element_id == info.element_id {
                 self.element_with_active_tooltip = None;
 
                 if let Some(action) = self.hide_tooltip_action.as_mut() {
-                    self.context.action_sender.send((action)()).unwrap();
+                    self.context.send_action((action)());
                 }
             }
         }
@@ -1831,39 +3408,92 @@ impl<A: Clone + 'static> ElementSystem<A> {
         }
     }
 
-    #[allow(unused)]
-    pub fn render<P: FnOnce()>(
+    /// Set how far the next frame is between the last tick and the next one.
+    ///
+    /// See [`RenderContext::interpolation_alpha`]. Elements which are
+    /// animating are repainted when this changes, since they are the ones
+    /// that can interpolate between ticks.
+    pub fn set_interpolation_alpha(&mut self, alpha: f32) {
+        let alpha = alpha.clamp(0.0, 1.0);
+        if self.interpolation_alpha == alpha {
+            return;
+        }
+        self.interpolation_alpha = alpha;
+
+        for i in 0..self.animating_elements.len() {
+            self.mark_element_dirty(self.animating_elements[i]);
+        }
+    }
+
+    pub fn render<B: RenderBackend, P: FnOnce()>(
         &mut self,
-        surface: &wgpu::Surface,
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        texture_format: wgpu::TextureFormat,
-        multisample: wgpu::MultisampleState,
-        vg: &mut rootvg::Canvas,
+        backend: &mut B,
         pre_present_notify: P,
         res: &mut ResourceCtx,
-    ) -> Result<(), wgpu::SurfaceError> {
+    ) -> Result<(), B::Error> {
         if !self.needs_repaint {
             return Ok(());
         }
 
-        // Set up the frame and wgpu encoder.
-        let frame = surface.get_current_texture()?;
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        self.render_to(backend, None, pre_present_notify, res)?;
+
+        self.needs_repaint = false;
+
+        Ok(())
+    }
+
+    /// Render the elements assigned to the given scissoring rectangle into
+    /// a secondary target.
+    ///
+    /// The primitives recorded for the main window are reused. Only elements
+    /// whose primitives are out of date are rendered again.
+    pub fn render_viewport<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        viewport: &Viewport,
+        res: &mut ResourceCtx,
+    ) -> Result<(), B::Error> {
+        self.get_scissor_rect_index(viewport.scissor_rect);
+
+        self.render_to(backend, Some(viewport), || {}, res)
+    }
+
+    fn render_to<B: RenderBackend, P: FnOnce()>(
+        &mut self,
+        backend: &mut B,
+        viewport: Option<&Viewport>,
+        pre_present_notify: P,
+        res: &mut ResourceCtx,
+    ) -> Result<(), B::Error> {
+        let scissor_rect_index =
+            viewport.map(|v| self.scissor_rect_id_to_index_map[&v.scissor_rect]);
+        let (physical_size, scale_factor) = viewport
+            .map(|v| (v.physical_size, v.scale_factor))
+            .unwrap_or((self.physical_size, self.context.scale_factor));
+
+        // Acquire the target texture for this frame.
+        backend.begin_frame()?;
 
         for render_cache in self.render_caches.values_mut() {
             render_cache.pre_render();
         }
 
+        self.sort_painted_elements();
+
+        let mut stats = RenderStats::default();
+        let scissor_rect_opacities = self.scissor_rect_opacities();
+
         {
-            let mut vg = vg.begin(self.physical_size, self.context.scale_factor);
+            let backend_cx = backend.ctx();
+            let mut vg = backend_cx.canvas.begin(physical_size, scale_factor);
 
             for cache in self.painted_elements.iter_mut() {
-                if !cache.visible {
+                let opacity = cache.opacity * scissor_rect_opacities[cache.scissor_rect_index];
+
+                if !cache.visible
+                    || opacity <= 0.0
+                    || scissor_rect_index.is_some_and(|i| cache.scissor_rect_index != i)
+                {
                     continue;
                 }
 
@@ -1874,6 +3504,16 @@ impl<A: Clone + 'static> ElementSystem<A> {
 
                     let element_entry = self.element_arena.get_mut(cache.element_id.0).unwrap();
 
+                    let bounds_size = element_entry.stack_data.rect.size;
+                    let visible_bounds = element_entry.stack_data.visible_rect.unwrap();
+
+                    stats.elements_painted += 1;
+                    stats.dirty_region_area += visible_bounds.area();
+                    if cache.painted_size != Some(bounds_size) {
+                        cache.painted_size = Some(bounds_size);
+                        stats.elements_reshaped += 1;
+                    }
+
                     let render_cache = if let Some(render_cache_id) =
                         element_entry.element.global_render_cache_id()
                     {
@@ -1885,12 +3525,14 @@ impl<A: Clone + 'static> ElementSystem<A> {
                     element_entry.element.render(
                         RenderContext {
                             res,
-                            bounds_size: element_entry.stack_data.rect.size,
+                            bounds_size,
                             bounds_origin: element_entry.stack_data.rect.origin,
-                            visible_bounds: element_entry.stack_data.visible_rect.unwrap(),
+                            visible_bounds,
                             scale: self.context.scale_factor,
                             window_size: self.context.logical_size,
                             render_cache,
+                            opacity,
+                            interpolation_alpha: self.interpolation_alpha,
                             class: element_entry.stack_data.class,
                             // For some reason the borrow checker doesn't like `vg` being
                             // borrwed mutably here, even though it's fine with it being
@@ -1899,51 +3541,169 @@ impl<A: Clone + 'static> ElementSystem<A> {
                             #[cfg(feature = "custom-shaders")]
                             custom_pipelines: &mut self.custom_pipelines,
                             #[cfg(feature = "custom-shaders")]
-                            device,
+                            device: backend_cx.device,
                             #[cfg(feature = "custom-shaders")]
-                            queue,
+                            queue: backend_cx.queue,
                             #[cfg(feature = "custom-shaders")]
-                            texture_format,
+                            texture_format: backend_cx.texture_format,
                             #[cfg(feature = "custom-shaders")]
-                            multisample,
+                            multisample: backend_cx.multisample,
                         },
                         &mut cache.primitives,
                     );
                 }
+            }
 
-                vg.set_z_index(cache.z_index);
-                vg.set_scissor_rect(self.scissor_rects[cache.scissor_rect_index].rect());
-                vg.add_group_with_offset(&cache.primitives, cache.offset);
+            for (z_index, clip_rect, offset, primitives) in self.frame_groups(viewport) {
+                vg.set_z_index(z_index);
+                vg.set_scissor_rect(clip_rect);
+                vg.add_group_with_offset(primitives, offset);
             }
-        }
 
-        // Render the view to the target texture.
-        vg.render_to_target(
-            Some(self.clear_color),
-            device,
-            queue,
-            &mut encoder,
-            &view,
-            self.physical_size,
-            &mut res.font_system,
-            #[cfg(feature = "svg-icons")]
-            &mut res.svg_icon_system,
-        )
-        .unwrap(); // TODO: handle this error properly.
+            if let Some(debug_z_index) = self.debug_z_index.as_ref().filter(|_| viewport.is_none())
+            {
+                let elements = self.debug_z_index_elements();
 
-        for render_cache in self.render_caches.values_mut() {
-            render_cache.post_render();
-        }
+                self.debug_z_index_primitives.clear();
+                debug_z_index.build_primitives(&elements, &mut self.debug_z_index_primitives);
 
-        pre_present_notify();
+                vg.set_z_index(ZIndex::MAX);
+                vg.set_scissor_rect(self.scissor_rects[0].rect());
+                vg.add_group_with_offset(&self.debug_z_index_primitives, Vector::zero());
+            }
 
-        // Submit the commands and present the frame.
-        queue.submit(Some(encoder.finish()));
-        frame.present();
+            if !self.debug_overlay.is_empty() && viewport.is_none() {
+                let rects = self.debug_overlay_rects();
 
-        self.needs_repaint = false;
+                self.debug_overlay_primitives.clear();
+                self.debug_overlay
+                    .build_primitives(&rects, &mut self.debug_overlay_primitives);
 
-        Ok(())
+                vg.set_z_index(ZIndex::MAX);
+                vg.set_scissor_rect(self.scissor_rects[0].rect());
+                vg.add_group_with_offset(&self.debug_overlay_primitives, Vector::zero());
+            }
+
+            // Draw the debug grid overlay on top of everything else.
+            if let Some(debug_grid) = self.debug_grid.as_ref().filter(|_| viewport.is_none()) {
+                self.debug_grid_primitives.clear();
+                debug_grid.build_primitives(
+                    self.context.logical_size,
+                    self.context.scale_factor.recip(),
+                    self.prev_pointer_pos,
+                    &mut self.debug_grid_primitives,
+                );
+
+                vg.set_z_index(ZIndex::MAX);
+                vg.set_scissor_rect(self.scissor_rects[0].rect());
+                vg.add_group_with_offset(&self.debug_grid_primitives, Vector::zero());
+            }
+        }
+
+        // Render the view to the target texture.
+        let clear_color = if let Some(viewport) = viewport {
+            viewport.clear_color
+        } else if self.skip_clear {
+            None
+        } else {
+            Some(self.clear_color)
+        };
+        let render_start = Instant::now();
+        backend.render_frame(clear_color, physical_size, res)?;
+        stats.frame_duration = render_start.elapsed();
+
+        if viewport.is_none() {
+            self.last_render_stats = stats;
+        }
+
+        for render_cache in self.render_caches.values_mut() {
+            render_cache.post_render();
+        }
+
+        backend.present_frame(pre_present_notify);
+
+        Ok(())
+    }
+
+    /// The primitive groups of the visible elements in the order they are
+    /// painted, along with the z index, clipping rectangle, and offset to
+    /// draw them with.
+    ///
+    /// If `viewport` is `Some`, then only the elements assigned to its
+    /// scissoring rectangle are returned, placed within the viewport.
+    fn frame_groups<'s>(
+        &'s self,
+        viewport: Option<&'s Viewport>,
+    ) -> impl Iterator<Item = (ZIndex, RectI32, Vector, &'s PrimitiveGroup)> + 's {
+        let scissor_rect_index =
+            viewport.map(|v| self.scissor_rect_id_to_index_map[&v.scissor_rect]);
+        let scissor_rect_opacities = self.scissor_rect_opacities();
+
+        self.painted_elements.iter().filter_map(move |cache| {
+            if !cache.visible
+                || cache.opacity * scissor_rect_opacities[cache.scissor_rect_index] <= 0.0
+                || scissor_rect_index.is_some_and(|i| cache.scissor_rect_index != i)
+            {
+                return None;
+            }
+
+            let (clip_rect, offset) = if let Some(viewport) = viewport {
+                viewport.place(
+                    &self.scissor_rects[cache.scissor_rect_index],
+                    cache.offset,
+                    cache.clip_to_scissor_rect,
+                )
+            } else {
+                (cache.clip_rect(&self.scissor_rects), cache.offset)
+            };
+
+            Some((
+                cache.z_index(&self.scissor_rects),
+                clip_rect,
+                offset,
+                &cache.primitives,
+            ))
+        })
+    }
+
+    /// Sort the list of elements listening to pointer events so that the
+    /// element which is painted on top comes last.
+    fn sort_pointer_event_list(&mut self) {
+        if !self.elements_listening_to_pointer_event_need_sorted {
+            return;
+        }
+        self.elements_listening_to_pointer_event_need_sorted = false;
+
+        // The sort keys are unique, so an unstable sort is deterministic here.
+        let scissor_rects = &self.scissor_rects;
+        self.elements_listening_to_pointer_event
+            .sort_unstable_by_key(|cache| cache.order(scissor_rects));
+
+        for (i, cache) in self.elements_listening_to_pointer_event.iter().enumerate() {
+            if let Some(element_entry) = self.element_arena.get_mut(cache.element_id.0) {
+                element_entry.stack_data.index_in_pointer_event_list = i as u32;
+            }
+        }
+    }
+
+    /// Sort the list of painted elements into the order they are rendered in.
+    ///
+    /// This must use the same ordering as [`Self::sort_pointer_event_list`].
+    fn sort_painted_elements(&mut self) {
+        if !self.painted_elements_need_sorted {
+            return;
+        }
+        self.painted_elements_need_sorted = false;
+
+        let scissor_rects = &self.scissor_rects;
+        self.painted_elements
+            .sort_unstable_by_key(|cache| cache.order(scissor_rects));
+
+        for (i, cache) in self.painted_elements.iter().enumerate() {
+            if let Some(element_entry) = self.element_arena.get_mut(cache.element_id.0) {
+                element_entry.stack_data.index_in_painted_list = i as u32;
+            }
+        }
     }
 
     pub fn cursor_icon(&self) -> CursorIcon {
@@ -1953,6 +3713,119 @@ impl<A: Clone + 'static> ElementSystem<A> {
     pub fn pointer_lock_request(&mut self) -> Option<bool> {
         self.context.pointer_lock_request.take()
     }
+
+    pub fn ime_cursor_area_request(&mut self) -> Option<Rect> {
+        self.context.ime_cursor_area_request.take()
+    }
+
+    /// Returns whether the OS input method should be enabled if this has
+    /// changed since the last call, i.e. when an element which listens to
+    /// text composition events gains or loses focus.
+    pub fn ime_allowed_request(&mut self) -> Option<bool> {
+        let ime_allowed = self
+            .context
+            .current_focus_info
+            .as_ref()
+            .is_some_and(|focus_info| focus_info.listens_to_text_composition);
+
+        if self.ime_allowed != ime_allowed {
+            self.ime_allowed = ime_allowed;
+            Some(ime_allowed)
+        } else {
+            None
+        }
+    }
+}
+
+/// The ID of the root node of the accessibility tree, which represents the
+/// window.
+#[cfg(feature = "accessibility")]
+const ACCESSIBILITY_ROOT_ID: accesskit::NodeId = accesskit::NodeId(u64::MAX);
+
+#[cfg(feature = "accessibility")]
+fn accessibility_node_id(element_id: ElementID) -> accesskit::NodeId {
+    accesskit::NodeId(element_id.0.to_bits())
+}
+
+/// Removes redundant modifications from a batch of the modification queue.
+///
+/// Only the latest `RectChanged` and a single `MarkDirty` per element are kept.
+/// The order of all other modifications (i.e. focus changes) is preserved.
+#[derive(Default)]
+struct ModificationCoalescer {
+    /// The index of the last `RectChanged` and `MarkDirty` modification of
+    /// each element in the batch.
+    last_rect_changed: FxHashMap<ElementID, usize>,
+    last_marked_dirty: FxHashMap<ElementID, usize>,
+}
+
+impl ModificationCoalescer {
+    fn coalesce(&mut self, batch: &mut Vec<ElementModification>) {
+        self.last_rect_changed.clear();
+        self.last_marked_dirty.clear();
+
+        for (i, modification) in batch.iter().enumerate() {
+            match modification.type_ {
+                ElementModificationType::RectChanged(_) => {
+                    self.last_rect_changed.insert(modification.element_id, i);
+                }
+                ElementModificationType::MarkDirty => {
+                    self.last_marked_dirty.insert(modification.element_id, i);
+                }
+                _ => {}
+            }
+        }
+
+        let mut i = 0;
+        batch.retain(|modification| {
+            let last = match modification.type_ {
+                ElementModificationType::RectChanged(_) => {
+                    self.last_rect_changed.get(&modification.element_id)
+                }
+                ElementModificationType::MarkDirty => {
+                    self.last_marked_dirty.get(&modification.element_id)
+                }
+                _ => None,
+            };
+            let keep = last.map(|last| *last == i).unwrap_or(true);
+            i += 1;
+            keep
+        });
+    }
+}
+
+/// Whether the scissoring rectangle at `index` is the one at `ancestor` or
+/// is nested (directly or indirectly) inside of it.
+fn scissor_rect_is_within(scissor_rects: &[ScissorRect], index: usize, ancestor: usize) -> bool {
+    let mut current = Some(index);
+    while let Some(i) = current {
+        if i == ancestor {
+            return true;
+        }
+        current = scissor_rects[i].parent;
+    }
+    false
+}
+
+/// The index of the element in the tab order which should receive focus
+/// next, wrapping around at the ends.
+///
+/// * `len` - The number of focusable elements.
+/// * `current` - The index of the currently focused element, if it is one
+/// of the focusable elements.
+fn next_tab_index(len: usize, current: Option<usize>, reverse: bool, wrap: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    match (current, reverse) {
+        (None, false) => Some(0),
+        (None, true) => Some(len - 1),
+        (Some(i), false) if i + 1 < len => Some(i + 1),
+        (Some(i), true) if i > 0 => Some(i - 1),
+        (Some(_), false) => wrap.then_some(0),
+        (Some(_), true) => wrap.then_some(len - 1),
+    }
 }
 
 struct ElementEntry<A: Clone + 'static> {
@@ -1971,12 +3844,21 @@ struct ElementEntry<A: Clone + 'static> {
 struct EntryStackData {
     rect: Rect,
     visible_rect: Option<Rect>,
+    /// The area in which this element receives pointer events. This is the
+    /// visible area expanded by `hit_tolerance`.
+    hit_rect: Option<Rect>,
+    hit_tolerance: f32,
     offset_from_scissor_rect_origin: Vector,
+    /// The size of the element before the transform of its scissoring
+    /// rectangle is applied.
+    untransformed_size: Size,
 
     scissor_rect_index: usize,
     z_index: ZIndex,
+    creation_order: u64,
 
     class: ClassID,
+    key: Option<ElementKey>,
 
     flags: ElementFlags,
     manually_hidden: bool,
@@ -1996,6 +3878,27 @@ impl EntryStackData {
 
         self.rect.origin = scissor_rect_origin + self.offset_from_scissor_rect_origin
             - scissor_rect.scroll_offset();
+        self.rect.size = self.untransformed_size;
+
+        if self.flags.contains(ElementFlags::STICKY) {
+            // The y position relative to the top of the scissoring rectangle.
+            let natural_y = self.rect.origin.y - scissor_rect_origin.y;
+
+            let mut pinned_y: f32 = 0.0;
+            if let Some(next_offset_y) =
+                scissor_rect.next_sticky_offset(self.offset_from_scissor_rect_origin.y)
+            {
+                // Get pushed out of the way by the next sticky element.
+                let next_natural_y = next_offset_y - scissor_rect.scroll_offset().y;
+                pinned_y = pinned_y.min(next_natural_y - self.rect.height());
+            }
+
+            self.rect.origin.y = scissor_rect_origin.y + natural_y.max(pinned_y);
+        }
+
+        if let Some(transform) = scissor_rect.window_transform() {
+            self.rect = transform.transform_rect(self.rect);
+        }
     }
 
     fn update_visibility(&mut self, scissor_rects: &[ScissorRect], window_visible: bool) {
@@ -2006,9 +3909,16 @@ impl EntryStackData {
         {
             None
         } else {
-            let scissor_rect: Rect = scissor_rects[self.scissor_rect_index].rect().cast();
+            let scissor_rect: Rect = scissor_rects[self.scissor_rect_index].clip_rect().cast();
             scissor_rect.intersection(&self.rect)
         };
+
+        self.hit_rect = if self.visible_rect.is_some() && self.hit_tolerance > 0.0 {
+            let scissor_rect: Rect = scissor_rects[self.scissor_rect_index].clip_rect().cast();
+            scissor_rect.intersection(&self.rect.inflate(self.hit_tolerance, self.hit_tolerance))
+        } else {
+            self.visible_rect
+        };
     }
 
     fn visible(&self) -> bool {
@@ -2019,11 +3929,48 @@ impl EntryStackData {
 #[derive(Debug, Clone, PartialEq)]
 pub struct TooltipInfo {
     pub text: String,
+    /// An optional icon to show alongside the text.
+    pub icon: Option<IconID>,
+    /// An optional secondary line of text, i.e. the current value of a
+    /// parameter.
+    pub description: Option<String>,
+    /// The bounds to align the tooltip to.
+    ///
+    /// If the tooltip follows the pointer, then this is an empty rectangle at
+    /// the pointer position plus the offset.
     pub element_bounds: Rect,
     pub align: Align2,
+    /// The position of the pointer if the tooltip follows it
+    /// (`TooltipPositioning::FollowCursor`), `None` otherwise.
+    pub pointer_position: Option<Point>,
     pub window_id: WindowID,
 }
 
+impl TooltipInfo {
+    /// The text followed by the description (if any), i.e.
+    /// "Cutoff \u{2014} 1.2 kHz".
+    pub fn display_text(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{} \u{2014} {}", self.text, description),
+            None => self.text.clone(),
+        }
+    }
+
+    fn follow_cursor(&mut self, pointer_pos: Point, offset: Vector) {
+        self.element_bounds = Rect::new(pointer_pos + offset, Size::zero());
+        self.align = Align2::BOTTOM_LEFT;
+        self.pointer_position = Some(pointer_pos);
+    }
+}
+
+/// A tooltip in `TooltipPositioning::FollowCursor` mode which is sent again
+/// whenever the pointer moves.
+struct FollowingTooltip {
+    element_id: ElementID,
+    offset: Vector,
+    info: TooltipInfo,
+}
+
 #[derive(Clone, Copy)]
 struct FocusInfo {
     element_id: ElementID,
@@ -2033,6 +3980,30 @@ struct FocusInfo {
     listens_to_keys: bool,
 }
 
+/// How far in points a touch can move before it no longer counts as a long
+/// press.
+const LONG_PRESS_SLOP: f32 = 10.0;
+
+#[derive(Clone, Copy)]
+struct PendingLongPress {
+    touch_id: u64,
+    position: Point,
+    /// Handled the same way as the hover timeouts in
+    /// `ElementSystem::hovered_elements`.
+    hover_timeout_deadline: Option<Instant>,
+}
+
+/// Returns `true` if the given hover timeout has elapsed, in which case it is
+/// cleared so that the timeout event is only sent once.
+fn hover_timeout_elapsed(deadline: &mut Option<Instant>, now: Instant) -> bool {
+    if deadline.is_some_and(|deadline| now >= deadline) {
+        *deadline = None;
+        true
+    } else {
+        false
+    }
+}
+
 #[derive(Clone, Copy)]
 struct ActiveTooltipInfo {
     element_id: ElementID,
@@ -2056,6 +4027,7 @@ fn send_event_to_element<A: Clone + 'static>(
     let mut el_cx = ElementContext::new(
         element_entry.stack_data.rect,
         element_entry.stack_data.visible_rect,
+        element_entry.stack_data.hit_rect,
         view_cx.logical_size,
         element_entry.stack_data.z_index,
         element_entry.stack_data.manually_hidden,
@@ -2065,6 +4037,7 @@ fn send_event_to_element<A: Clone + 'static>(
         view_cx.cursor_icon,
         view_cx.window_id,
         view_cx.pointer_locked,
+        view_cx.animation_clock_secs,
         element_entry.stack_data.class,
         &mut view_cx.action_sender,
         res,
@@ -2073,12 +4046,29 @@ fn send_event_to_element<A: Clone + 'static>(
 
     let capture_status = element_entry.element.on_event(event, &mut el_cx);
 
-    view_cx.cursor_icon = el_cx.cursor_icon;
+    // Elements which set `cursor_icon` directly request it with a priority
+    // of zero.
+    let cursor_icon_request = el_cx
+        .requested_cursor_icon
+        .or_else(|| (el_cx.cursor_icon != view_cx.cursor_icon).then_some((el_cx.cursor_icon, 0)));
+    if let Some((icon, priority)) = cursor_icon_request {
+        if view_cx
+            .cursor_icon_priority
+            .map_or(true, |prev_priority| priority >= prev_priority)
+        {
+            view_cx.cursor_icon = icon;
+            view_cx.cursor_icon_priority = Some(priority);
+        }
+    }
 
     if let Some(req) = el_cx.pointer_lock_request {
         view_cx.pointer_lock_request = Some(req);
     }
 
+    if let Some(area) = el_cx.ime_cursor_area_request {
+        view_cx.ime_cursor_area_request = Some(area);
+    }
+
     if el_cx.listen_to_pointer_clicked_off {
         view_cx.mod_queue_sender.send_to_front(ElementModification {
             element_id,
@@ -2124,7 +4114,9 @@ fn send_event_to_element<A: Clone + 'static>(
     if el_cx.hover_timeout_requested {
         view_cx.mod_queue_sender.send_to_front(ElementModification {
             element_id,
-            type_: ElementModificationType::StartHoverTimeout,
+            type_: ElementModificationType::StartHoverTimeout {
+                delay: el_cx.hover_timeout_delay,
+            },
         });
     }
 
@@ -2201,3 +4193,2428 @@ fn release_focus_for_element<A: Clone + 'static>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action_queue::{action_channel, ActionReceiver};
+    use crate::clipboard::State as ClipboardState;
+    use crate::style::StyleSystem;
+    use rootvg::text::FontSystem;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct TestElement;
+
+    impl Element<()> for TestElement {}
+
+    struct TestHarness {
+        element_system: ElementSystem<()>,
+        res: ResourceCtx,
+        clipboard: Clipboard,
+        action_receiver: Option<ActionReceiver<()>>,
+    }
+
+    impl TestHarness {
+        fn new() -> Self {
+            let (action_sender, action_receiver) = action_channel();
+
+            Self {
+                element_system: ElementSystem::new(
+                    PhysicalSizeI32::new(400, 300),
+                    1.0f64.into(),
+                    ElementSystemConfig {
+                        clear_color: PackedSrgb::BLACK,
+                        skip_clear: false,
+                        preallocate_for_this_many_elements: 0,
+                        hover_timeout_duration: Duration::from_millis(500),
+                        scroll_wheel_timeout_duration: Duration::from_millis(250),
+                        long_press_duration: Duration::from_millis(500),
+                    },
+                    action_sender,
+                    0,
+                ),
+                res: ResourceCtx {
+                    style_system: StyleSystem::new(true),
+                    font_system: FontSystem::new(),
+                    #[cfg(feature = "svg-icons")]
+                    svg_icon_system: Default::default(),
+                },
+                clipboard: Clipboard {
+                    state: ClipboardState::Unavailable,
+                },
+                action_receiver: Some(action_receiver),
+            }
+        }
+
+        fn add(&mut self, builder: ElementBuilder<()>) -> ElementHandle {
+            self.element_system
+                .add_element(builder, &mut self.res, &mut self.clipboard)
+        }
+
+        fn process_updates(&mut self) {
+            self.element_system
+                .process_updates(&mut self.res, &mut self.clipboard);
+        }
+    }
+
+    fn overlapping_element() -> ElementBuilder<()> {
+        ElementBuilder::new(TestElement)
+            .rect(Rect::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0)))
+            .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+    }
+
+    /// The order elements are painted in, from bottom to top.
+    fn paint_order(h: &mut TestHarness) -> Vec<ElementID> {
+        h.element_system.sort_painted_elements();
+        h.element_system
+            .painted_elements
+            .iter()
+            .map(|cache| cache.element_id)
+            .collect()
+    }
+
+    /// The order elements receive pointer events in, from last to first.
+    fn hit_test_order(h: &mut TestHarness) -> Vec<ElementID> {
+        h.element_system.sort_pointer_event_list();
+        h.element_system
+            .elements_listening_to_pointer_event
+            .iter()
+            .map(|cache| cache.element_id)
+            .collect()
+    }
+
+    fn topmost_hit(h: &mut TestHarness, pos: Point) -> Option<ElementID> {
+        h.element_system.sort_pointer_event_list();
+        h.element_system
+            .elements_listening_to_pointer_event
+            .iter()
+            .rev()
+            .find(|cache| cache.hit_rect.is_some_and(|r| r.contains(pos)))
+            .map(|cache| cache.element_id)
+    }
+
+    #[test]
+    fn test_same_z_index_paints_and_hits_in_creation_order() {
+        let mut h = TestHarness::new();
+
+        let a = h.add(overlapping_element());
+        let b = h.add(overlapping_element());
+        let c = h.add(overlapping_element());
+
+        assert_eq!(paint_order(&mut h), vec![a.id(), b.id(), c.id()]);
+        assert_eq!(hit_test_order(&mut h), vec![a.id(), b.id(), c.id()]);
+        assert_eq!(topmost_hit(&mut h, Point::new(20.0, 20.0)), Some(c.id()));
+    }
+
+    #[test]
+    fn test_base_z_index_orders_scissor_rects_as_a_whole() {
+        let mut h = TestHarness::new();
+        let pos = Point::new(20.0, 20.0);
+        let popup = ScissorRectID(1);
+        h.element_system.update_scissor_rect(
+            popup,
+            Some(Rect::new(Point::zero(), Size::new(100.0, 100.0))),
+            None,
+        );
+        h.process_updates();
+
+        let below = h.add(overlapping_element().z_index(10));
+        let in_popup = h.add(overlapping_element().z_index(1).scissor_rect(popup));
+        h.process_updates();
+
+        assert_eq!(topmost_hit(&mut h, pos), Some(below.id()));
+
+        h.element_system.set_scissor_rect_base_z_index(popup, 100);
+        assert_eq!(h.element_system.scissor_rect_base_z_index(popup), 100);
+        assert_eq!(paint_order(&mut h), vec![below.id(), in_popup.id()]);
+        assert_eq!(hit_test_order(&mut h), vec![below.id(), in_popup.id()]);
+        assert_eq!(topmost_hit(&mut h, pos), Some(in_popup.id()));
+
+        // The offset is applied to the z index that the primitives are
+        // rendered with.
+        let z_indexes: Vec<ZIndex> = h
+            .element_system
+            .frame_groups(None)
+            .map(|(z_index, ..)| z_index)
+            .collect();
+        assert_eq!(z_indexes, vec![10, 101]);
+    }
+
+    #[test]
+    fn test_nested_scissor_rects_compose_offsets_and_clipping() {
+        let mut h = TestHarness::new();
+        let (outer, inner) = (ScissorRectID(1), ScissorRectID(2));
+
+        h.element_system.update_scissor_rect(
+            outer,
+            Some(Rect::new(Point::new(100.0, 100.0), Size::new(100.0, 100.0))),
+            Some(Vector::new(0.0, 20.0)),
+        );
+        h.element_system.update_scissor_rect(
+            inner,
+            Some(Rect::new(Point::new(10.0, 50.0), Size::new(80.0, 100.0))),
+            None,
+        );
+        assert!(h.element_system.set_scissor_rect_parent(inner, Some(outer)));
+        assert_eq!(h.element_system.scissor_rect_parent(inner), Some(outer));
+        h.process_updates();
+
+        let square = |y: f32| {
+            ElementBuilder::new(TestElement)
+                .rect(Rect::new(Point::new(0.0, y), Size::new(20.0, 20.0)))
+                .scissor_rect(inner)
+                .flags(ElementFlags::PAINTS)
+        };
+        let top = h.add(square(0.0));
+        let bottom = h.add(square(80.0));
+        h.process_updates();
+
+        let visible_rect = |h: &TestHarness, handle: &ElementHandle| {
+            h.element_system.element_arena[handle.id().0]
+                .stack_data
+                .visible_rect
+        };
+
+        // 100 + 10 in x, and 100 - 20 + 50 in y.
+        assert_eq!(
+            h.element_system.element_rect(&top),
+            Some(Rect::new(Point::new(110.0, 130.0), Size::new(20.0, 20.0)))
+        );
+        // The inner rectangle extends past the bottom of the outer one, which
+        // clips it.
+        assert_eq!(visible_rect(&h, &bottom), None);
+
+        // Scrolling the outer rectangle moves the elements of the inner one.
+        h.element_system
+            .update_scissor_rect(outer, None, Some(Vector::new(0.0, 40.0)));
+        h.process_updates();
+        assert_eq!(
+            h.element_system.element_rect(&top).map(|r| r.origin),
+            Some(Point::new(110.0, 110.0))
+        );
+        assert_eq!(
+            visible_rect(&h, &bottom),
+            Some(Rect::new(Point::new(110.0, 190.0), Size::new(20.0, 10.0)))
+        );
+
+        // Cycles are rejected.
+        assert!(!h.element_system.set_scissor_rect_parent(outer, Some(inner)));
+        assert!(!h.element_system.set_scissor_rect_parent(inner, Some(inner)));
+        assert_eq!(h.element_system.scissor_rect_parent(outer), None);
+
+        // Un-nesting makes the rectangle relative to the window again.
+        assert!(h.element_system.set_scissor_rect_parent(inner, None));
+        h.process_updates();
+        assert_eq!(
+            h.element_system.element_rect(&top).map(|r| r.origin),
+            Some(Point::new(10.0, 50.0))
+        );
+    }
+
+    #[test]
+    fn test_paint_order_matches_hit_test_order_after_z_index_changes() {
+        let mut h = TestHarness::new();
+        let pos = Point::new(20.0, 20.0);
+
+        let mut handles: Vec<ElementHandle> =
+            (0..8).map(|_| h.add(overlapping_element())).collect();
+
+        // A simple deterministic pseudo-random sequence.
+        let mut seed: u32 = 12345;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as usize
+        };
+
+        for _ in 0..200 {
+            match next() % 4 {
+                0 => {
+                    // Drop an element and create a new one in its place.
+                    let i = next() % handles.len();
+                    handles.swap_remove(i);
+                    h.process_updates();
+                    handles.push(h.add(overlapping_element()));
+                }
+                _ => {
+                    let i = next() % handles.len();
+                    handles[i].set_z_index((next() % 4) as ZIndex);
+                    h.process_updates();
+                }
+            }
+
+            let paint_order = paint_order(&mut h);
+            assert_eq!(paint_order, hit_test_order(&mut h));
+            assert_eq!(topmost_hit(&mut h, pos), paint_order.last().copied());
+        }
+    }
+
+    #[test]
+    fn test_element_lookup_by_key() {
+        let mut h = TestHarness::new();
+
+        let a = h.add(ElementBuilder::new(TestElement).key(1));
+        let b = h.add(ElementBuilder::new(TestElement).key(2));
+        let c = h.add(ElementBuilder::new(TestElement));
+
+        assert_eq!(h.element_system.element_with_key(1), Some(a.id()));
+        assert_eq!(h.element_system.element_with_key(2), Some(b.id()));
+        assert_eq!(h.element_system.element_key(&c), None);
+
+        // Dropping an element removes its key.
+        drop(a);
+        h.process_updates();
+        assert_eq!(h.element_system.element_with_key(1), None);
+
+        // Recreating an element with the same key makes it reachable again.
+        let a = h.add(ElementBuilder::new(TestElement).key(1));
+        assert_eq!(h.element_system.element_with_key(1), Some(a.id()));
+        assert_eq!(h.element_system.element_key(&a), Some(1));
+
+        // Keys can be assigned and removed after an element is created.
+        h.element_system.set_element_key(&c, Some(3));
+        assert_eq!(h.element_system.element_with_key(3), Some(c.id()));
+
+        h.element_system.set_element_key(&b, None);
+        assert_eq!(h.element_system.element_with_key(2), None);
+        assert_eq!(h.element_system.element_key(&b), None);
+
+        // Reassigning a key takes it away from the previous element.
+        h.element_system.set_element_key(&b, Some(1));
+        assert_eq!(h.element_system.element_with_key(1), Some(b.id()));
+        assert_eq!(h.element_system.element_key(&a), None);
+    }
+
+    #[test]
+    fn test_transparent_elements_are_not_painted() {
+        let mut h = TestHarness::new();
+        let mut faded = h.add(overlapping_element());
+        let _opaque = h.add(overlapping_element());
+        h.process_updates();
+
+        let painted_count = |h: &TestHarness| h.element_system.frame_groups(None).count();
+        let cache_of = |h: &TestHarness, handle: &ElementHandle| {
+            let index = h.element_system.element_arena[handle.id().0]
+                .stack_data
+                .index_in_painted_list as usize;
+            (
+                h.element_system.painted_elements[index].opacity,
+                h.element_system.painted_elements[index].dirty,
+            )
+        };
+        assert_eq!(painted_count(&h), 2);
+
+        for cache in h.element_system.painted_elements.iter_mut() {
+            cache.dirty = false;
+        }
+
+        // Out of range values are clamped.
+        assert!(!faded.set_opacity(1.5));
+        assert!(faded.set_opacity(0.5));
+        assert!(!faded.set_opacity(0.5));
+        h.process_updates();
+        assert_eq!(cache_of(&h, &faded), (0.5, true));
+        assert_eq!(painted_count(&h), 2);
+
+        assert!(faded.set_opacity(-1.0));
+        assert_eq!(faded.opacity(), 0.0);
+        h.process_updates();
+        assert_eq!(cache_of(&h, &faded).0, 0.0);
+        assert_eq!(painted_count(&h), 1);
+    }
+
+    #[test]
+    fn test_scissor_rect_transform_is_applied_to_its_elements() {
+        let mut h = TestHarness::new();
+        let panel = ScissorRectID(1);
+        let nested = ScissorRectID(2);
+        h.element_system.update_scissor_rect(
+            panel,
+            Some(Rect::new(Point::zero(), Size::new(100.0, 100.0))),
+            None,
+        );
+        h.element_system.update_scissor_rect(
+            nested,
+            Some(Rect::new(Point::zero(), Size::new(80.0, 80.0))),
+            None,
+        );
+        assert!(h
+            .element_system
+            .set_scissor_rect_parent(nested, Some(panel)));
+        h.process_updates();
+
+        let outside = h.add(overlapping_element());
+        let in_panel = h.add(overlapping_element().scissor_rect(panel));
+        let in_nested = h.add(overlapping_element().scissor_rect(nested));
+        h.process_updates();
+
+        let groups = |h: &TestHarness| -> Vec<(RectI32, Vector)> {
+            h.element_system
+                .frame_groups(None)
+                .map(|(_, clip_rect, offset, _)| (clip_rect, offset))
+                .collect()
+        };
+        let dirty_of = |h: &TestHarness, handle: &ElementHandle| {
+            let index = h.element_system.element_arena[handle.id().0]
+                .stack_data
+                .index_in_painted_list as usize;
+            h.element_system.painted_elements[index].dirty
+        };
+        let clear_dirty = |h: &mut TestHarness| {
+            for cache in h.element_system.painted_elements.iter_mut() {
+                cache.dirty = false;
+            }
+        };
+        let before = groups(&h);
+        assert_eq!(before.len(), 3);
+        clear_dirty(&mut h);
+
+        h.element_system.update_scissor_rect_transform(
+            panel,
+            None,
+            Some(Transform::translation(20.0, 0.0)),
+        );
+        h.element_system.update_scissor_rect_transform(
+            nested,
+            None,
+            Some(Transform::translation(0.0, 5.0)),
+        );
+        h.process_updates();
+        assert_eq!(
+            h.element_system.scissor_rect_transform(panel),
+            (1.0, Transform::translation(20.0, 0.0))
+        );
+
+        // The translations of nested scissoring rectangles are composed, and
+        // the clipping rectangles move along with the elements.
+        let after = groups(&h);
+        assert_eq!(after[0], before[0]);
+        for (i, translation) in [(1, Vector::new(20.0, 0.0)), (2, Vector::new(20.0, 5.0))] {
+            assert_eq!(after[i].0, before[i].0.translate(translation.cast()));
+            assert_eq!(after[i].1, before[i].1 + translation);
+        }
+
+        // Hit testing follows the transformed elements.
+        assert_eq!(
+            topmost_hit(&mut h, Point::new(15.0, 15.0)),
+            Some(outside.id())
+        );
+        assert_eq!(
+            topmost_hit(&mut h, Point::new(35.0, 20.0)),
+            Some(in_nested.id())
+        );
+
+        // A scale is relative to the origin of the scissoring rectangle, and
+        // it scales the size of the elements and of the clipping rectangles.
+        clear_dirty(&mut h);
+        h.element_system.update_scissor_rect_transform(
+            panel,
+            None,
+            Some(Transform::scale(2.0, 2.0)),
+        );
+        h.process_updates();
+        assert!(!dirty_of(&h, &outside));
+        assert!(dirty_of(&h, &in_panel));
+        assert!(dirty_of(&h, &in_nested));
+        assert_eq!(
+            h.element_system.element_rect_by_id(in_panel.id()),
+            Some(Rect::new(Point::new(20.0, 20.0), Size::new(100.0, 100.0)))
+        );
+        assert_eq!(
+            h.element_system.element_rect_by_id(in_nested.id()),
+            Some(Rect::new(Point::new(20.0, 30.0), Size::new(100.0, 100.0)))
+        );
+        let after = groups(&h);
+        assert_eq!(
+            after[1].0,
+            RectI32::new(PointI32::zero(), SizeI32::new(200, 200))
+        );
+        assert_eq!(
+            after[2].0,
+            RectI32::new(PointI32::new(0, 10), SizeI32::new(160, 160))
+        );
+        assert_eq!(
+            topmost_hit(&mut h, Point::new(110.0, 110.0)),
+            Some(in_nested.id())
+        );
+        clear_dirty(&mut h);
+
+        // Changing the opacity repaints the elements of the scissoring
+        // rectangle and of the ones nested in it.
+        h.element_system
+            .update_scissor_rect_transform(panel, Some(0.5), None);
+        assert!(!dirty_of(&h, &outside));
+        assert!(dirty_of(&h, &in_panel));
+        assert!(dirty_of(&h, &in_nested));
+        assert_eq!(h.element_system.frame_groups(None).count(), 3);
+
+        // Completely transparent scissoring rectangles are not painted.
+        h.element_system
+            .update_scissor_rect_transform(panel, Some(-1.0), None);
+        assert_eq!(h.element_system.scissor_rect_transform(panel).0, 0.0);
+        assert_eq!(h.element_system.frame_groups(None).count(), 1);
+    }
+
+    #[test]
+    fn test_draws_outside_scissor_rect_is_not_clipped() {
+        let mut h = TestHarness::new();
+
+        let scissor_rect_id = ScissorRectID(1);
+        h.element_system.update_scissor_rect(
+            scissor_rect_id,
+            Some(Rect::new(Point::new(100.0, 100.0), Size::new(50.0, 50.0))),
+            None,
+        );
+        h.process_updates();
+
+        let clipped = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(Rect::new(Point::new(0.0, 0.0), Size::new(50.0, 50.0)))
+                .scissor_rect(scissor_rect_id)
+                .flags(ElementFlags::PAINTS),
+        );
+        let unclipped = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(Rect::new(Point::new(0.0, 0.0), Size::new(50.0, 50.0)))
+                .scissor_rect(scissor_rect_id)
+                .flags(ElementFlags::PAINTS | ElementFlags::DRAWS_OUTSIDE_SCISSOR_RECT),
+        );
+        h.process_updates();
+
+        let clip_rect_of = |h: &TestHarness, handle: &ElementHandle| {
+            h.element_system
+                .painted_elements
+                .iter()
+                .find(|cache| cache.element_id == handle.id())
+                .map(|cache| cache.clip_rect(&h.element_system.scissor_rects))
+                .unwrap()
+        };
+
+        // Both elements are still positioned relative to the scissoring rectangle.
+        assert_eq!(
+            h.element_system.element_rect(&clipped).unwrap().origin,
+            Point::new(100.0, 100.0)
+        );
+        assert_eq!(
+            h.element_system.element_rect(&unclipped).unwrap().origin,
+            Point::new(100.0, 100.0)
+        );
+
+        // A primitive extending past the scissoring rectangle is only visible for
+        // the element which opted out of clipping.
+        let decoration = PointI32::new(155, 125);
+        assert!(!clip_rect_of(&h, &clipped).contains(decoration));
+        assert!(clip_rect_of(&h, &unclipped).contains(decoration));
+        assert_eq!(
+            clip_rect_of(&h, &unclipped),
+            h.element_system.scissor_rects[0].rect()
+        );
+    }
+
+    #[test]
+    fn test_viewport_reuses_the_primitives_of_its_scissor_rect() {
+        let mut h = TestHarness::new();
+
+        let scissor_rect_id = ScissorRectID(1);
+        h.element_system.update_scissor_rect(
+            scissor_rect_id,
+            Some(Rect::new(Point::new(100.0, 100.0), Size::new(50.0, 50.0))),
+            None,
+        );
+        h.process_updates();
+
+        h.add(overlapping_element());
+        for (pos, z_index) in [(Point::new(0.0, 0.0), 1), (Point::new(10.0, 20.0), 0)] {
+            h.add(
+                ElementBuilder::new(TestElement)
+                    .rect(Rect::new(pos, Size::new(20.0, 20.0)))
+                    .z_index(z_index)
+                    .scissor_rect(scissor_rect_id)
+                    .flags(ElementFlags::PAINTS),
+            );
+        }
+        h.process_updates();
+
+        let mut viewport = Viewport::new(
+            scissor_rect_id,
+            PhysicalSizeI32::new(120, 120),
+            ScaleFactor::from(1.0f64),
+        );
+        viewport.offset = Vector::new(5.0, 5.0);
+
+        h.element_system.sort_painted_elements();
+        let groups = |viewport: Option<&Viewport>| {
+            h.element_system
+                .frame_groups(viewport)
+                .map(|(z_index, clip_rect, offset, primitives)| {
+                    (
+                        z_index,
+                        clip_rect,
+                        offset,
+                        primitives as *const PrimitiveGroup,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The main render's groups for the scissoring rectangle, moved so that
+        // its top-left corner lies at the viewport's offset.
+        let scissor_rect = RectI32::new(PointI32::new(100, 100), SizeI32::new(50, 50));
+        let expected: Vec<_> = groups(None)
+            .into_iter()
+            .filter(|(_, clip_rect, _, _)| *clip_rect == scissor_rect)
+            .map(|(z_index, _, offset, primitives)| {
+                (
+                    z_index,
+                    RectI32::new(PointI32::new(5, 5), SizeI32::new(50, 50)),
+                    offset - Vector::new(95.0, 95.0),
+                    primitives,
+                )
+            })
+            .collect();
+
+        assert_eq!(expected.len(), 2);
+        assert_eq!(groups(Some(&viewport)), expected);
+        assert_eq!(expected[0].2, Vector::new(15.0, 25.0));
+    }
+
+    #[test]
+    fn test_window_config_preallocates_elements() {
+        let (action_sender, _action_receiver) = action_channel::<()>();
+
+        let config = crate::window::WindowConfig {
+            preallocate_for_this_many_elements: 64,
+            ..Default::default()
+        };
+
+        let element_system = ElementSystem::new(
+            PhysicalSizeI32::new(400, 300),
+            1.0f64.into(),
+            config.element_system_config(),
+            action_sender,
+            0,
+        );
+
+        assert!(element_system.element_arena.capacity() >= 64);
+        assert!(element_system.painted_elements.capacity() >= 64);
+        assert!(
+            element_system
+                .elements_listening_to_pointer_event
+                .capacity()
+                >= 64
+        );
+    }
+
+    #[test]
+    fn test_hide_tooltip_after_action_receiver_dropped() {
+        let mut h = TestHarness::new();
+        h.element_system.set_tooltip_actions(|_| (), || ());
+
+        let el = h.add(overlapping_element());
+        h.process_updates();
+
+        h.element_system.element_with_active_tooltip = Some(ActiveTooltipInfo {
+            element_id: el.id(),
+            auto_hide: true,
+        });
+
+        h.action_receiver = None;
+
+        // Dropping the element hides its active tooltip.
+        drop(el);
+        h.process_updates();
+
+        assert!(h.element_system.element_with_active_tooltip.is_none());
+        assert!(h.element_system.context.action_receiver_dropped);
+    }
+
+    #[test]
+    fn test_sending_actions_after_the_receiver_is_dropped_does_not_panic() {
+        struct ActionElement;
+
+        impl Element<()> for ActionElement {
+            fn on_event(
+                &mut self,
+                event: ElementEvent,
+                cx: &mut ElementContext<'_, ()>,
+            ) -> EventCaptureStatus {
+                if let ElementEvent::Pointer(PointerEvent::ButtonJustPressed { .. }) = event {
+                    cx.send_action(());
+                    return EventCaptureStatus::Captured;
+                }
+
+                EventCaptureStatus::NotCaptured
+            }
+        }
+
+        let mut h = TestHarness::new();
+        let _el = h.add(
+            ElementBuilder::new(ActionElement)
+                .rect(Rect::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0)))
+                .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+        );
+        h.process_updates();
+
+        h.action_receiver = None;
+
+        let status = h.element_system.handle_event(
+            &CanvasEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position: Point::new(20.0, 20.0),
+                button: PointerButton::Primary,
+                pointer_type: PointerType::Mouse,
+                click_count: 1,
+                modifiers: Modifiers::empty(),
+            }),
+            &mut h.res,
+            &mut h.clipboard,
+        );
+        assert_eq!(status, EventCaptureStatus::Captured);
+    }
+
+    #[test]
+    fn test_on_dropped_builder_action_is_sent_when_the_element_is_dropped() {
+        use crate::derive::element_builder_on_drop;
+
+        #[element_builder_on_drop]
+        #[derive_where::derive_where(Default)]
+        struct DroppableBuilder<A: Clone + 'static> {}
+
+        struct DroppableElement<A: Clone + 'static> {
+            on_dropped: Option<Box<dyn FnMut() -> A>>,
+        }
+
+        impl<A: Clone + 'static> Element<A> for DroppableElement<A> {
+            fn on_dropped(&mut self, action_sender: &mut ActionSender<A>) {
+                if let Some(f) = self.on_dropped.as_mut() {
+                    action_sender.send(f()).unwrap();
+                }
+            }
+        }
+
+        let build = |builder: DroppableBuilder<()>| {
+            let mut flags = ElementFlags::PAINTS;
+            if builder.on_dropped.is_some() {
+                flags |= ElementFlags::LISTENS_TO_ON_DROPPED;
+            }
+
+            ElementBuilder::new(DroppableElement {
+                on_dropped: builder.on_dropped,
+            })
+            .rect(Rect::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0)))
+            .flags(flags)
+        };
+
+        let calls = Rc::new(RefCell::new(0));
+        let calls_clone = Rc::clone(&calls);
+
+        let mut h = TestHarness::new();
+        let el = h.add(build(
+            DroppableBuilder::default().on_dropped(move || *calls_clone.borrow_mut() += 1),
+        ));
+        let silent_el = h.add(build(DroppableBuilder::default()));
+        h.process_updates();
+
+        drop(silent_el);
+        h.process_updates();
+        assert_eq!(*calls.borrow(), 0);
+
+        drop(el);
+        h.process_updates();
+        assert_eq!(*calls.borrow(), 1);
+        assert!(h.action_receiver.as_mut().unwrap().try_recv().is_ok());
+        assert!(h.action_receiver.as_mut().unwrap().try_recv().is_err());
+    }
+
+    #[test]
+    fn test_clear_drops_every_element_but_keeps_scissor_rects() {
+        struct DropCountingElement(Rc<RefCell<usize>>);
+
+        impl Element<()> for DropCountingElement {
+            fn on_dropped(&mut self, _action_sender: &mut ActionSender<()>) {
+                *self.0.borrow_mut() += 1;
+            }
+        }
+
+        let mut h = TestHarness::new();
+        let scroll_area = ScissorRectID(1);
+        let scissor_rect = Rect::new(Point::new(100.0, 50.0), Size::new(80.0, 80.0));
+        h.element_system
+            .update_scissor_rect(scroll_area, Some(scissor_rect), None);
+
+        let dropped = Rc::new(RefCell::new(0));
+        let el = h.add(
+            ElementBuilder::new(DropCountingElement(Rc::clone(&dropped)))
+                .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_ON_DROPPED),
+        );
+        let scrolled_el = h.add(overlapping_element().scissor_rect(scroll_area));
+        h.process_updates();
+
+        assert_eq!(h.element_system.clear(&mut h.res, &mut h.clipboard), 2);
+        assert_eq!(*dropped.borrow(), 1);
+        assert_eq!(h.element_system.iter_elements().count(), 0);
+        assert!(h.element_system.painted_elements.is_empty());
+        assert!(h
+            .element_system
+            .elements_listening_to_pointer_event
+            .is_empty());
+
+        // Dropping the stale handles afterwards is harmless.
+        drop(el);
+        drop(scrolled_el);
+        h.process_updates();
+        assert_eq!(*dropped.borrow(), 1);
+
+        // The scissoring rectangle can be reused by the next page.
+        assert_eq!(
+            h.element_system.scissor_rect(scroll_area),
+            RectI32::new(PointI32::new(100, 50), SizeI32::new(80, 80))
+        );
+        let new_el = h.add(overlapping_element().scissor_rect(scroll_area));
+        h.process_updates();
+        assert_eq!(
+            h.element_system.element_rect(&new_el).map(|r| r.origin),
+            Some(Point::new(110.0, 60.0))
+        );
+    }
+
+    #[test]
+    fn test_shared_and_leaked_handles_keep_the_element_alive() {
+        let mut h = TestHarness::new();
+        let exists = |h: &TestHarness, id: ElementID| h.element_system.element_arena.contains(id.0);
+
+        let shared = h.add(overlapping_element()).into_shared();
+        let shared_clone = shared.clone();
+        assert_eq!(shared.handle_count(), 2);
+
+        drop(shared);
+        h.process_updates();
+        assert!(exists(&h, shared_clone.id()));
+
+        shared_clone.borrow_mut().set_pos(Point::new(30.0, 30.0));
+        h.process_updates();
+        assert_eq!(
+            h.element_system
+                .element_rect(&shared_clone.borrow())
+                .map(|r| r.origin),
+            Some(Point::new(30.0, 30.0))
+        );
+
+        let shared_id = shared_clone.id();
+        drop(shared_clone);
+        h.process_updates();
+        assert!(!exists(&h, shared_id));
+
+        let leaked_id = h.add(overlapping_element()).leak();
+        h.process_updates();
+        assert!(exists(&h, leaked_id));
+
+        assert_eq!(h.element_system.clear(&mut h.res, &mut h.clipboard), 1);
+        assert!(!exists(&h, leaked_id));
+    }
+
+    #[test]
+    fn test_tooltip_info_carries_the_icon_and_description() {
+        let mut h = TestHarness::new();
+
+        let shown: Rc<RefCell<Option<TooltipInfo>>> = Rc::new(RefCell::new(None));
+        let shown_clone = Rc::clone(&shown);
+        h.element_system.set_tooltip_actions(
+            move |info| *RefCell::borrow_mut(&shown_clone) = Some(info),
+            || {},
+        );
+
+        let mut el = h.add(overlapping_element());
+        h.process_updates();
+
+        el.show_tooltip_data(
+            TooltipData::new("Cutoff", Align2::TOP_CENTER)
+                .with_icon(3)
+                .with_description("1.2 kHz"),
+            true,
+        );
+        h.process_updates();
+
+        let info = shown.borrow_mut().take().unwrap();
+        assert_eq!(info.text, "Cutoff");
+        assert_eq!(info.icon, Some(3));
+        assert_eq!(info.description.as_deref(), Some("1.2 kHz"));
+        assert_eq!(info.element_bounds, el.rect());
+        assert_eq!(info.display_text(), "Cutoff \u{2014} 1.2 kHz");
+
+        // The plain string constructor has no extra content.
+        el.show_tooltip("Resonance", Align2::TOP_CENTER, true);
+        h.process_updates();
+
+        let info = shown.borrow_mut().take().unwrap();
+        assert_eq!(info.icon, None);
+        assert_eq!(info.display_text(), "Resonance");
+    }
+
+    #[test]
+    fn test_following_tooltip_moves_with_the_pointer() {
+        let mut h = TestHarness::new();
+
+        let shown: Rc<RefCell<Vec<TooltipInfo>>> = Rc::new(RefCell::new(Vec::new()));
+        let shown_clone = Rc::clone(&shown);
+        h.element_system.set_tooltip_actions(
+            move |info| RefCell::borrow_mut(&shown_clone).push(info),
+            || {},
+        );
+
+        let mut el = h.add(overlapping_element());
+        h.process_updates();
+
+        let move_pointer = |h: &mut TestHarness, x: f32, y: f32| {
+            h.element_system.handle_event(
+                &CanvasEvent::Pointer(PointerEvent::Moved {
+                    position: Point::new(x, y),
+                    delta: None,
+                    is_locked: false,
+                    pointer_type: PointerType::Mouse,
+                    modifiers: Modifiers::empty(),
+                    just_entered: false,
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            );
+        };
+
+        move_pointer(&mut h, 20.0, 20.0);
+        el.show_tooltip_data(
+            TooltipData::follow_cursor("Value", Vector::new(5.0, 8.0)),
+            true,
+        );
+        h.process_updates();
+        move_pointer(&mut h, 30.0, 40.0);
+
+        {
+            let shown = shown.borrow();
+            assert_eq!(shown.len(), 2);
+            assert_eq!(shown[0].pointer_position, Some(Point::new(20.0, 20.0)));
+            assert_eq!(
+                shown[0].element_bounds,
+                Rect::new(Point::new(25.0, 28.0), Size::zero())
+            );
+            assert_eq!(shown[1].pointer_position, Some(Point::new(30.0, 40.0)));
+            assert_eq!(shown[1].element_bounds.origin, Point::new(35.0, 48.0));
+            assert_eq!(shown[1].align, Align2::BOTTOM_LEFT);
+        }
+
+        // Anchored tooltips do not move with the pointer.
+        el.show_tooltip("Value", Align2::TOP_CENTER, true);
+        h.process_updates();
+        move_pointer(&mut h, 31.0, 40.0);
+
+        let shown = shown.borrow();
+        assert_eq!(shown.len(), 3);
+        assert_eq!(shown[2].pointer_position, None);
+        assert_eq!(shown[2].element_bounds, el.rect());
+    }
+
+    struct TooltipOwnerElement {
+        tooltip_inner: TooltipInner,
+    }
+
+    impl Element<()> for TooltipOwnerElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            self.tooltip_inner.handle_event(&event, false, cx);
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    #[test]
+    fn test_tooltip_delay_overrides_the_hover_timeout() {
+        let mut h = TestHarness::new();
+
+        let shown: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let shown_clone = Rc::clone(&shown);
+        h.element_system.set_tooltip_actions(
+            move |info| RefCell::borrow_mut(&shown_clone).push(info.text),
+            || {},
+        );
+
+        let add_owner = |h: &mut TestHarness, x: f32, data: TooltipData| {
+            h.add(
+                ElementBuilder::new(TooltipOwnerElement {
+                    tooltip_inner: TooltipInner::new(Some(data)),
+                })
+                .rect(Rect::new(Point::new(x, 0.0), Size::new(50.0, 50.0)))
+                .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+            )
+        };
+        let _instant = add_owner(
+            &mut h,
+            0.0,
+            TooltipData::new("Instant", Align2::TOP_CENTER).delay(Duration::ZERO),
+        );
+        let _default = add_owner(
+            &mut h,
+            100.0,
+            TooltipData::new("Default", Align2::TOP_CENTER),
+        );
+        h.process_updates();
+
+        let hover = |h: &mut TestHarness, x: f32| {
+            let position = Point::new(x, 25.0);
+            h.element_system.handle_event(
+                &CanvasEvent::Pointer(PointerEvent::Moved {
+                    position,
+                    delta: None,
+                    is_locked: false,
+                    pointer_type: PointerType::Mouse,
+                    modifiers: Modifiers::empty(),
+                    just_entered: false,
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            );
+            h.process_updates();
+            h.element_system.handle_event(
+                &CanvasEvent::Animation {
+                    delta_seconds: 0.016,
+                    pointer_position: Some(position),
+                },
+                &mut h.res,
+                &mut h.clipboard,
+            );
+            h.process_updates();
+        };
+
+        hover(&mut h, 25.0);
+        assert_eq!(*shown.borrow(), vec![String::from("Instant")]);
+
+        // The window's 500ms hover timeout has not elapsed yet.
+        hover(&mut h, 125.0);
+        assert_eq!(*shown.borrow(), vec![String::from("Instant")]);
+    }
+
+    struct AnimatingElement;
+
+    impl Element<()> for AnimatingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Init = event {
+                cx.set_animating(true);
+            }
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    #[test]
+    fn test_animation_ticks_are_only_needed_while_there_is_pending_work() {
+        let mut h = TestHarness::new();
+
+        let _still = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(Rect::new(Point::new(0.0, 0.0), Size::new(50.0, 50.0)))
+                .flags(ElementFlags::PAINTS),
+        );
+        let _owner = h.add(
+            ElementBuilder::new(TooltipOwnerElement {
+                tooltip_inner: TooltipInner::new(Some(TooltipData::new(
+                    "Tooltip",
+                    Align2::TOP_CENTER,
+                ))),
+            })
+            .rect(Rect::new(Point::new(100.0, 0.0), Size::new(50.0, 50.0)))
+            .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+        );
+        h.process_updates();
+        assert!(!h.element_system.needs_animation_ticks());
+
+        let move_pointer = |h: &mut TestHarness, x: f32| {
+            h.element_system.handle_event(
+                &CanvasEvent::Pointer(PointerEvent::Moved {
+                    position: Point::new(x, 25.0),
+                    delta: None,
+                    is_locked: false,
+                    pointer_type: PointerType::Mouse,
+                    modifiers: Modifiers::empty(),
+                    just_entered: false,
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            );
+            h.process_updates();
+        };
+
+        // A pending hover timeout needs ticks until the pointer leaves.
+        move_pointer(&mut h, 125.0);
+        assert!(h.element_system.needs_animation_ticks());
+        move_pointer(&mut h, 300.0);
+        assert!(!h.element_system.needs_animation_ticks());
+
+        // So does an animating element until it is removed.
+        let animating = h.add(
+            ElementBuilder::new(AnimatingElement)
+                .rect(Rect::new(Point::new(0.0, 60.0), Size::new(20.0, 20.0)))
+                .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_INIT),
+        );
+        h.process_updates();
+        assert!(h.element_system.needs_animation_ticks());
+
+        drop(animating);
+        h.process_updates();
+        assert!(!h.element_system.needs_animation_ticks());
+    }
+
+    #[test]
+    fn test_interpolation_alpha_changes_repaint_animating_elements() {
+        let mut h = TestHarness::new();
+        let rect = Rect::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0));
+
+        let animating = h.add(
+            ElementBuilder::new(AnimatingElement)
+                .rect(rect)
+                .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_INIT),
+        );
+        let still = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(rect)
+                .flags(ElementFlags::PAINTS),
+        );
+        h.process_updates();
+
+        let dirty_of = |h: &TestHarness, id: ElementID| {
+            h.element_system
+                .painted_elements
+                .iter()
+                .find(|cache| cache.element_id == id)
+                .unwrap()
+                .dirty
+        };
+        let clear_dirty = |h: &mut TestHarness| {
+            for cache in h.element_system.painted_elements.iter_mut() {
+                cache.dirty = false;
+            }
+            h.element_system.needs_repaint = false;
+        };
+
+        clear_dirty(&mut h);
+        h.element_system.set_interpolation_alpha(0.5);
+        assert!(dirty_of(&h, animating.id()));
+        assert!(!dirty_of(&h, still.id()));
+        assert!(h.element_system.needs_repaint);
+
+        // Nothing is repainted if the alpha stays the same.
+        clear_dirty(&mut h);
+        h.element_system.set_interpolation_alpha(0.5);
+        assert!(!dirty_of(&h, animating.id()));
+        assert!(!h.element_system.needs_repaint);
+    }
+
+    #[cfg(feature = "headless")]
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn test_render_stats_count_painted_and_reshaped_elements() {
+        let mut h = TestHarness::new();
+        let mut backend =
+            HeadlessRenderBackend::new(PhysicalSizeI32::new(400, 300), &mut h.res.font_system)
+                .unwrap();
+
+        let painted_element = |rect| {
+            ElementBuilder::new(TestElement)
+                .rect(rect)
+                .flags(ElementFlags::PAINTS)
+        };
+        let repainted = h.add(painted_element(Rect::new(
+            Point::new(10.0, 10.0),
+            Size::new(50.0, 50.0),
+        )));
+        let mut resized = h.add(painted_element(Rect::new(
+            Point::new(100.0, 100.0),
+            Size::new(20.0, 10.0),
+        )));
+        let _clean = h.add(painted_element(Rect::new(
+            Point::new(200.0, 100.0),
+            Size::new(30.0, 30.0),
+        )));
+        h.process_updates();
+
+        // Every element is painted for the first time.
+        h.element_system
+            .render(&mut backend, || {}, &mut h.res)
+            .unwrap();
+        let stats = h.element_system.last_render_stats();
+        assert_eq!(stats.elements_painted, 3);
+        assert_eq!(stats.elements_reshaped, 3);
+        assert_eq!(stats.dirty_region_area, 2500.0 + 200.0 + 900.0);
+
+        // Repaint one element without resizing it, and resize another one.
+        let index = h.element_system.element_arena[repainted.id().0]
+            .stack_data
+            .index_in_painted_list as usize;
+        h.element_system.painted_elements[index].dirty = true;
+        h.element_system.needs_repaint = true;
+        resized.set_rect(Rect::new(Point::new(100.0, 100.0), Size::new(40.0, 10.0)));
+        h.process_updates();
+
+        h.element_system
+            .render(&mut backend, || {}, &mut h.res)
+            .unwrap();
+        let stats = h.element_system.last_render_stats();
+        assert_eq!(stats.elements_painted, 2);
+        assert_eq!(stats.elements_reshaped, 1);
+        assert_eq!(stats.dirty_region_area, 2500.0 + 400.0);
+    }
+
+    #[test]
+    fn test_layout_transaction_sends_a_single_rect_change() {
+        let mut h = TestHarness::new();
+        let mut el = h.add(overlapping_element());
+        h.process_updates();
+
+        let pending_rect_changes = |h: &mut TestHarness| {
+            let mut rects = Vec::new();
+            while let Some(modification) = h.element_system.mod_queue_receiver.try_recv() {
+                if let ElementModificationType::RectChanged(rect) = modification.type_ {
+                    rects.push(rect);
+                }
+            }
+            rects
+        };
+
+        {
+            let mut layout = el.begin_layout();
+            layout.set_x(5.0).set_y(6.0).set_width(7.0);
+            layout.set_height(8.0);
+            assert_eq!(pending_rect_changes(&mut h), vec![]);
+        }
+
+        let rect = Rect::new(Point::new(5.0, 6.0), Size::new(7.0, 8.0));
+        assert_eq!(el.rect(), rect);
+        assert_eq!(pending_rect_changes(&mut h), vec![rect]);
+
+        // Nothing is sent if the rectangle ends up unchanged.
+        let mut layout = el.begin_layout();
+        layout.set_x(0.0).set_x(5.0);
+        assert!(!layout.commit());
+        assert_eq!(pending_rect_changes(&mut h), vec![]);
+    }
+
+    #[test]
+    fn test_redundant_rect_and_dirty_modifications_are_coalesced() {
+        let mut arena = Arena::new();
+        let a = ElementID(arena.insert(()));
+        let b = ElementID(arena.insert(()));
+
+        let rect = |x: f32| Rect::new(Point::new(x, 0.0), Size::new(10.0, 10.0));
+        let modification = |element_id, type_| ElementModification { element_id, type_ };
+
+        let mut batch = vec![
+            modification(a, ElementModificationType::RectChanged(rect(1.0))),
+            modification(a, ElementModificationType::MarkDirty),
+            modification(
+                a,
+                ElementModificationType::ChangeFocus(ChangeFocusRequest::StealFocus),
+            ),
+            modification(b, ElementModificationType::RectChanged(rect(2.0))),
+            modification(a, ElementModificationType::RectChanged(rect(3.0))),
+            modification(a, ElementModificationType::MarkDirty),
+            modification(
+                b,
+                ElementModificationType::ChangeFocus(ChangeFocusRequest::StealFocus),
+            ),
+            modification(b, ElementModificationType::MarkDirty),
+        ];
+
+        ModificationCoalescer::default().coalesce(&mut batch);
+
+        let summary: Vec<_> = batch
+            .iter()
+            .map(|modification| {
+                let kind = match modification.type_ {
+                    ElementModificationType::RectChanged(rect) => format!("rect {}", rect.min_x()),
+                    ElementModificationType::MarkDirty => "dirty".to_string(),
+                    ElementModificationType::ChangeFocus(_) => "focus".to_string(),
+                    _ => unreachable!(),
+                };
+                (modification.element_id, kind)
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (a, "focus".to_string()),
+                (b, "rect 2".to_string()),
+                (a, "rect 3".to_string()),
+                (a, "dirty".to_string()),
+                (b, "focus".to_string()),
+                (b, "dirty".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sticky_header_is_pinned_until_pushed_by_the_next() {
+        let mut h = TestHarness::new();
+
+        let list = ScissorRectID(1);
+        h.element_system.update_scissor_rect(
+            list,
+            Some(Rect::new(Point::new(0.0, 30.0), Size::new(100.0, 100.0))),
+            Some(Vector::zero()),
+        );
+
+        let header = |y: f32| {
+            ElementBuilder::new(TestElement)
+                .rect(Rect::new(Point::new(0.0, y), Size::new(100.0, 20.0)))
+                .scissor_rect(list)
+                .flags(ElementFlags::PAINTS | ElementFlags::STICKY)
+        };
+
+        let first = h.add(header(0.0));
+        let second = h.add(header(200.0));
+        h.process_updates();
+
+        let mut rendered_y_at_scroll = |scroll_y: f32| {
+            h.element_system
+                .update_scissor_rect(list, None, Some(Vector::new(0.0, scroll_y)));
+            h.process_updates();
+
+            let rendered_y = |id: ElementID| {
+                h.element_system
+                    .painted_elements
+                    .iter()
+                    .find(|cache| cache.element_id == id)
+                    .map(|cache| cache.offset.y)
+                    .unwrap()
+            };
+
+            (rendered_y(first.id()), rendered_y(second.id()))
+        };
+
+        // Not scrolled yet.
+        assert_eq!(rendered_y_at_scroll(0.0), (30.0, 230.0));
+        // The first header stays pinned to the top.
+        assert_eq!(rendered_y_at_scroll(50.0), (30.0, 180.0));
+        assert_eq!(rendered_y_at_scroll(180.0), (30.0, 50.0));
+        // The second header pushes the first one out of the way.
+        assert_eq!(rendered_y_at_scroll(190.0), (20.0, 40.0));
+        // The second header is now pinned.
+        assert_eq!(rendered_y_at_scroll(250.0), (-40.0, 30.0));
+    }
+
+    struct ClickCaptureElement;
+
+    impl Element<()> for ClickCaptureElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Pointer(PointerEvent::ButtonJustPressed { .. }) = event {
+                EventCaptureStatus::Captured
+            } else {
+                EventCaptureStatus::NotCaptured
+            }
+        }
+    }
+
+    fn click(h: &mut TestHarness, position: Point) -> EventCaptureStatus {
+        h.element_system.handle_event(
+            &CanvasEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position,
+                button: PointerButton::Primary,
+                pointer_type: crate::event::PointerType::default(),
+                click_count: 1,
+                modifiers: keyboard_types::Modifiers::empty(),
+            }),
+            &mut h.res,
+            &mut h.clipboard,
+        )
+    }
+
+    #[test]
+    fn test_hit_tolerance_expands_pointer_bounds_but_not_visual_bounds() {
+        let mut h = TestHarness::new();
+
+        let splitter_rect = Rect::new(Point::new(100.0, 100.0), Size::new(2.0, 40.0));
+
+        let splitter = h.add(
+            ElementBuilder::new(ClickCaptureElement)
+                .rect(splitter_rect)
+                .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+                .hit_tolerance(4.0),
+        );
+        h.process_updates();
+
+        // 3px to the right of the visual edge.
+        assert_eq!(
+            click(&mut h, Point::new(105.0, 120.0)),
+            EventCaptureStatus::Captured
+        );
+        // 3px to the left of the visual edge.
+        assert_eq!(
+            click(&mut h, Point::new(97.0, 120.0)),
+            EventCaptureStatus::Captured
+        );
+        // Beyond the tolerance.
+        assert_eq!(
+            click(&mut h, Point::new(107.0, 120.0)),
+            EventCaptureStatus::NotCaptured
+        );
+
+        // The tolerance is only used for pointer testing.
+        assert_eq!(
+            h.element_system.element_rect_by_id(splitter.id()),
+            Some(splitter_rect)
+        );
+
+        // Without a tolerance, the same click misses.
+        let _plain = h.add(
+            ElementBuilder::new(ClickCaptureElement)
+                .rect(Rect::new(Point::new(200.0, 100.0), Size::new(2.0, 40.0)))
+                .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+        );
+        h.process_updates();
+
+        assert_eq!(
+            click(&mut h, Point::new(205.0, 120.0)),
+            EventCaptureStatus::NotCaptured
+        );
+    }
+
+    struct ScaleRecordingElement {
+        received: Rc<RefCell<Vec<ScaleFactor>>>,
+    }
+
+    impl Element<()> for ScaleRecordingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::ScaleFactorChanged(scale_factor) = event {
+                RefCell::borrow_mut(&self.received).push(scale_factor);
+            }
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    #[test]
+    fn test_scale_factor_changed_is_sent_to_listening_elements() {
+        let mut h = TestHarness::new();
+
+        let listening = Rc::new(RefCell::new(Vec::new()));
+        let not_listening = Rc::new(RefCell::new(Vec::new()));
+
+        let _a = h.add(
+            ElementBuilder::new(ScaleRecordingElement {
+                received: Rc::clone(&listening),
+            })
+            .flags(ElementFlags::LISTENS_TO_SCALE_CHANGE),
+        );
+        let _b = h.add(ElementBuilder::new(ScaleRecordingElement {
+            received: Rc::clone(&not_listening),
+        }));
+        h.process_updates();
+
+        // Resizing without changing the scale factor does not send the event.
+        h.element_system
+            .resize(PhysicalSizeI32::new(500, 300), 1.0f64.into());
+        h.process_updates();
+        assert!(listening.borrow().is_empty());
+
+        h.element_system
+            .resize(PhysicalSizeI32::new(1000, 600), 2.0f64.into());
+        h.process_updates();
+        assert_eq!(*listening.borrow(), vec![ScaleFactor::from(2.0f64)]);
+        assert!(not_listening.borrow().is_empty());
+
+        // The event is only sent once per change.
+        h.process_updates();
+        assert_eq!(listening.borrow().len(), 1);
+    }
+
+    #[cfg(feature = "accessibility")]
+    struct AccessibleButton;
+
+    #[cfg(feature = "accessibility")]
+    impl Element<()> for AccessibleButton {
+        fn accessibility_node(&self) -> Option<accesskit::Node> {
+            let mut node = accesskit::Node::new(accesskit::Role::Button);
+            node.set_label("Play");
+            Some(node)
+        }
+    }
+
+    #[cfg(feature = "accessibility")]
+    #[test]
+    fn test_accessibility_tree_contains_visible_elements() {
+        let mut h = TestHarness::new();
+
+        let button = h.add(
+            ElementBuilder::new(AccessibleButton)
+                .rect(Rect::new(Point::new(10.0, 20.0), Size::new(50.0, 30.0))),
+        );
+        // Elements without a node are not part of the tree.
+        let _other = h.add(overlapping_element());
+        h.process_updates();
+
+        assert!(h.element_system.accessibility_tree_changed());
+        let update = h.element_system.accessibility_tree_update();
+        assert!(!h.element_system.accessibility_tree_changed());
+
+        assert_eq!(update.nodes.len(), 2);
+        assert_eq!(update.focus, ACCESSIBILITY_ROOT_ID);
+
+        let button_id = accessibility_node_id(button.id());
+        let (_, root) = &update.nodes[0];
+        assert_eq!(root.children(), &[button_id]);
+
+        let (id, node) = &update.nodes[1];
+        assert_eq!(*id, button_id);
+        assert_eq!(node.role(), accesskit::Role::Button);
+        assert_eq!(
+            node.bounds(),
+            Some(accesskit::Rect {
+                x0: 10.0,
+                y0: 20.0,
+                x1: 60.0,
+                y1: 50.0,
+            })
+        );
+
+        // Focused elements map to the AccessKit focus.
+        h.element_system
+            .element_steal_focus(button.id(), false, &mut h.res, &mut h.clipboard);
+        assert_eq!(
+            h.element_system.accessibility_tree_update().focus,
+            button_id
+        );
+    }
+
+    #[cfg(feature = "accessibility")]
+    #[test]
+    fn test_accessibility_actions_are_dispatched_to_elements() {
+        let mut h = TestHarness::new();
+
+        let clicks = Rc::new(RefCell::new(0));
+        let element = h.add(
+            ElementBuilder::new(ClickCountingElement {
+                clicks: Rc::clone(&clicks),
+            })
+            .rect(Rect::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0)))
+            .flags(ElementFlags::FOCUSABLE | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+        );
+        // Clicks are sent to the target even if it is covered.
+        let _cover = h.add(overlapping_element());
+        h.process_updates();
+        h.element_system.accessibility_tree_update();
+
+        let request = |action| accesskit::ActionRequest {
+            action,
+            target: accessibility_node_id(element.id()),
+            data: None,
+        };
+
+        assert!(h.element_system.handle_accessibility_action(
+            &request(accesskit::Action::Click),
+            &mut h.res,
+            &mut h.clipboard,
+        ));
+        assert_eq!(*clicks.borrow(), 1);
+
+        // Focus changes are pushed to the accessibility tree.
+        assert!(h.element_system.handle_accessibility_action(
+            &request(accesskit::Action::Focus),
+            &mut h.res,
+            &mut h.clipboard,
+        ));
+        assert_eq!(h.element_system.focused_element(), Some(element.id()));
+        assert!(h.element_system.accessibility_tree_changed());
+        assert_eq!(
+            h.element_system.accessibility_tree_update().focus,
+            accessibility_node_id(element.id())
+        );
+
+        assert!(!h.element_system.handle_accessibility_action(
+            &request(accesskit::Action::ScrollDown),
+            &mut h.res,
+            &mut h.clipboard,
+        ));
+        assert!(!h.element_system.handle_accessibility_action(
+            &accesskit::ActionRequest {
+                action: accesskit::Action::Click,
+                target: ACCESSIBILITY_ROOT_ID,
+                data: None,
+            },
+            &mut h.res,
+            &mut h.clipboard,
+        ));
+    }
+
+    struct ClickCountingElement {
+        clicks: Rc<RefCell<u32>>,
+    }
+
+    impl Element<()> for ClickCountingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Pointer(PointerEvent::ButtonJustPressed { .. }) = event {
+                *RefCell::borrow_mut(&self.clicks) += 1;
+                EventCaptureStatus::Captured
+            } else {
+                EventCaptureStatus::NotCaptured
+            }
+        }
+    }
+
+    #[test]
+    fn test_loading_region_dims_and_blocks_clicks() {
+        let mut h = TestHarness::new();
+
+        let region = ScissorRectID(2);
+        let region_rect = Rect::new(Point::new(50.0, 50.0), Size::new(100.0, 100.0));
+        h.element_system
+            .update_scissor_rect(region, Some(region_rect), Some(Vector::zero()));
+
+        let clicks = Rc::new(RefCell::new(0));
+        let _button = h.add(
+            ElementBuilder::new(ClickCountingElement {
+                clicks: Rc::clone(&clicks),
+            })
+            .rect(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)))
+            .scissor_rect(region)
+            .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+        );
+        h.process_updates();
+
+        click(&mut h, Point::new(100.0, 100.0));
+        assert_eq!(*clicks.borrow(), 1);
+
+        h.element_system
+            .set_region_loading(region, true, &mut h.res, &mut h.clipboard);
+        h.process_updates();
+        assert!(h.element_system.region_is_loading(region));
+
+        let overlay_id = h.element_system.loading_regions.get(&region).unwrap().id();
+        // The overlay covers the whole region above its contents.
+        assert_eq!(
+            h.element_system.element_rect_by_id(overlay_id),
+            Some(region_rect)
+        );
+        let overlay_cache = h
+            .element_system
+            .painted_elements
+            .iter()
+            .find(|cache| cache.element_id == overlay_id)
+            .unwrap();
+        assert!(overlay_cache.visible);
+        assert_eq!(overlay_cache.z_index, ZIndex::MAX);
+        // The spinner is animating.
+        assert!(h.element_system.animating_elements.contains(&overlay_id));
+
+        assert_eq!(
+            click(&mut h, Point::new(100.0, 100.0)),
+            EventCaptureStatus::Captured
+        );
+        assert_eq!(*clicks.borrow(), 1);
+
+        // The overlay follows the region when it is scrolled.
+        h.element_system
+            .update_scissor_rect(region, None, Some(Vector::new(0.0, 40.0)));
+        h.process_updates();
+        assert_eq!(
+            h.element_system.element_rect_by_id(overlay_id),
+            Some(region_rect)
+        );
+
+        h.element_system
+            .set_region_loading(region, false, &mut h.res, &mut h.clipboard);
+        h.process_updates();
+        assert!(!h.element_system.region_is_loading(region));
+        assert!(h.element_system.element_rect_by_id(overlay_id).is_none());
+        assert!(h
+            .element_system
+            .painted_elements
+            .iter()
+            .all(|cache| cache.element_id != overlay_id));
+
+        click(&mut h, Point::new(100.0, 100.0));
+        assert_eq!(*clicks.borrow(), 2);
+    }
+
+    #[test]
+    fn test_loading_region_blocks_tab_focus() {
+        let mut h = TestHarness::new();
+
+        let region = ScissorRectID(2);
+        h.element_system.update_scissor_rect(
+            region,
+            Some(Rect::new(Point::new(50.0, 50.0), Size::new(100.0, 100.0))),
+            Some(Vector::zero()),
+        );
+
+        let rect = Rect::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0));
+        let inside = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(rect)
+                .scissor_rect(region)
+                .flags(ElementFlags::FOCUSABLE),
+        );
+        let outside = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(rect)
+                .flags(ElementFlags::FOCUSABLE),
+        );
+        h.process_updates();
+
+        h.element_system
+            .set_region_loading(region, true, &mut h.res, &mut h.clipboard);
+        h.process_updates();
+        assert_eq!(
+            h.element_system.focusable_elements_in_tab_order(),
+            vec![outside.id()]
+        );
+
+        let tab = |h: &mut TestHarness| {
+            h.element_system.handle_event(
+                &CanvasEvent::Keyboard(KeyboardEvent {
+                    state: KeyState::Down,
+                    code: Code::Tab,
+                    ..Default::default()
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            )
+        };
+        tab(&mut h);
+        tab(&mut h);
+        assert!(h.element_system.element_is_focused(&outside));
+
+        h.element_system
+            .set_region_loading(region, false, &mut h.res, &mut h.clipboard);
+        h.process_updates();
+        tab(&mut h);
+        assert!(h.element_system.element_is_focused(&inside));
+    }
+
+    #[test]
+    fn test_loading_region_blocks_touches() {
+        let mut h = TestHarness::new();
+
+        let region = ScissorRectID(2);
+        h.element_system.update_scissor_rect(
+            region,
+            Some(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0))),
+            Some(Vector::zero()),
+        );
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let _element = h.add(
+            ElementBuilder::new(TouchRecordingElement {
+                received: Rc::clone(&received),
+            })
+            .rect(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)))
+            .scissor_rect(region)
+            .flags(ElementFlags::LISTENS_TO_TOUCH),
+        );
+        h.process_updates();
+
+        h.element_system
+            .set_region_loading(region, true, &mut h.res, &mut h.clipboard);
+        h.process_updates();
+
+        // The overlay captures the touch instead.
+        assert_eq!(
+            touch(&mut h, 1, TouchPhase::Started, 50.0),
+            EventCaptureStatus::Captured
+        );
+        touch(&mut h, 1, TouchPhase::Moved, 60.0);
+        touch(&mut h, 1, TouchPhase::Ended, 60.0);
+        assert!(received.borrow().is_empty());
+    }
+
+    struct TouchRecordingElement {
+        received: Rc<RefCell<Vec<(u64, TouchPhase)>>>,
+    }
+
+    impl Element<()> for TouchRecordingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Touch(touch) = event {
+                RefCell::borrow_mut(&self.received).push((touch.id, touch.phase));
+                EventCaptureStatus::Captured
+            } else {
+                EventCaptureStatus::NotCaptured
+            }
+        }
+    }
+
+    fn touch(h: &mut TestHarness, id: u64, phase: TouchPhase, x: f32) -> EventCaptureStatus {
+        h.element_system.handle_event(
+            &CanvasEvent::Touch(TouchEvent {
+                id,
+                phase,
+                position: Point::new(x, 50.0),
+                pointer_type: PointerType::Touch,
+                modifiers: Modifiers::empty(),
+            }),
+            &mut h.res,
+            &mut h.clipboard,
+        )
+    }
+
+    #[test]
+    fn test_each_touch_is_routed_to_the_element_it_started_on() {
+        let mut h = TestHarness::new();
+
+        let left = Rc::new(RefCell::new(Vec::new()));
+        let right = Rc::new(RefCell::new(Vec::new()));
+
+        let _left = h.add(
+            ElementBuilder::new(TouchRecordingElement {
+                received: Rc::clone(&left),
+            })
+            .rect(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)))
+            .flags(ElementFlags::LISTENS_TO_TOUCH),
+        );
+        let _right = h.add(
+            ElementBuilder::new(TouchRecordingElement {
+                received: Rc::clone(&right),
+            })
+            .rect(Rect::new(Point::new(100.0, 0.0), Size::new(100.0, 100.0)))
+            .flags(ElementFlags::LISTENS_TO_TOUCH),
+        );
+        // Does not opt in to touch events.
+        let _mouse_only = h.add(
+            ElementBuilder::new(ClickCaptureElement)
+                .rect(Rect::new(Point::new(200.0, 0.0), Size::new(100.0, 100.0)))
+                .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+        );
+        h.process_updates();
+
+        assert_eq!(
+            touch(&mut h, 1, TouchPhase::Started, 50.0),
+            EventCaptureStatus::Captured
+        );
+        assert_eq!(
+            touch(&mut h, 2, TouchPhase::Started, 150.0),
+            EventCaptureStatus::Captured
+        );
+        // Touch 1 moves over the right element, but it stays with the left one.
+        touch(&mut h, 1, TouchPhase::Moved, 150.0);
+        touch(&mut h, 2, TouchPhase::Ended, 150.0);
+        touch(&mut h, 1, TouchPhase::Ended, 150.0);
+
+        assert_eq!(
+            *left.borrow(),
+            vec![
+                (1, TouchPhase::Started),
+                (1, TouchPhase::Moved),
+                (1, TouchPhase::Ended)
+            ]
+        );
+        assert_eq!(
+            *right.borrow(),
+            vec![(2, TouchPhase::Started), (2, TouchPhase::Ended)]
+        );
+
+        // The touch has ended, so further events are not routed anywhere.
+        assert_eq!(
+            touch(&mut h, 1, TouchPhase::Moved, 50.0),
+            EventCaptureStatus::NotCaptured
+        );
+        assert_eq!(
+            touch(&mut h, 3, TouchPhase::Started, 250.0),
+            EventCaptureStatus::NotCaptured
+        );
+        // Mouse-only elements still receive pointer events.
+        assert_eq!(
+            click(&mut h, Point::new(250.0, 50.0)),
+            EventCaptureStatus::Captured
+        );
+        // Touch-only elements do not.
+        assert_eq!(
+            click(&mut h, Point::new(50.0, 50.0)),
+            EventCaptureStatus::NotCaptured
+        );
+    }
+
+    struct CursorElement {
+        icon: CursorIcon,
+        priority: Option<i32>,
+    }
+
+    impl Element<()> for CursorElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Pointer(PointerEvent::Moved { .. }) = event {
+                match self.priority {
+                    Some(priority) => cx.request_cursor_icon(self.icon, priority),
+                    None => cx.cursor_icon = self.icon,
+                }
+            }
+
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    #[test]
+    fn test_highest_priority_cursor_icon_request_wins() {
+        let mut h = TestHarness::new();
+
+        let add = |h: &mut TestHarness, icon, priority, z_index| {
+            h.add(
+                ElementBuilder::new(CursorElement { icon, priority })
+                    .rect(Rect::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0)))
+                    .z_index(z_index)
+                    .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS),
+            )
+        };
+
+        let move_pointer = |h: &mut TestHarness, x: f32| {
+            h.element_system.handle_event(
+                &CanvasEvent::Pointer(PointerEvent::Moved {
+                    position: Point::new(x, 20.0),
+                    delta: None,
+                    is_locked: false,
+                    pointer_type: PointerType::Mouse,
+                    modifiers: Modifiers::empty(),
+                    just_entered: false,
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            );
+            h.element_system.cursor_icon()
+        };
+
+        // The top element is sent the event first, but its request has a
+        // higher priority than the direct assignment of the bottom element.
+        let _top = add(&mut h, CursorIcon::EwResize, Some(1), 2);
+        let mut bottom = add(&mut h, CursorIcon::Pointer, None, 1);
+        h.process_updates();
+        assert_eq!(move_pointer(&mut h, 20.0), CursorIcon::EwResize);
+
+        // The icon is reset once the pointer moves away.
+        assert_eq!(move_pointer(&mut h, 200.0), CursorIcon::Default);
+
+        // Requests of equal priority behave like direct assignments, where the
+        // last one wins.
+        drop(bottom);
+        bottom = add(&mut h, CursorIcon::Pointer, Some(1), 1);
+        h.process_updates();
+        assert_eq!(move_pointer(&mut h, 20.0), CursorIcon::Pointer);
+        drop(bottom);
+    }
+
+    struct ContextMenuRecordingElement {
+        received: Rc<RefCell<Vec<Point>>>,
+    }
+
+    impl Element<()> for ContextMenuRecordingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            match event {
+                ElementEvent::Pointer(PointerEvent::ContextMenu { position }) => {
+                    RefCell::borrow_mut(&self.received).push(position);
+                    EventCaptureStatus::Captured
+                }
+                // Touches are captured so that they also exercise the
+                // touch routing.
+                ElementEvent::Touch(_) => EventCaptureStatus::Captured,
+                _ => EventCaptureStatus::NotCaptured,
+            }
+        }
+    }
+
+    #[test]
+    fn test_context_menu_is_sent_on_secondary_click_and_long_press() {
+        let mut h = TestHarness::new();
+        h.element_system.long_press_duration = Duration::ZERO;
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let _el = h.add(
+            ElementBuilder::new(ContextMenuRecordingElement {
+                received: Rc::clone(&received),
+            })
+            .rect(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)))
+            .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS | ElementFlags::LISTENS_TO_TOUCH),
+        );
+        h.process_updates();
+
+        let tick = |h: &mut TestHarness| {
+            h.element_system.handle_event(
+                &CanvasEvent::Animation {
+                    delta_seconds: 1.0 / 60.0,
+                    pointer_position: None,
+                },
+                &mut h.res,
+                &mut h.clipboard,
+            );
+        };
+
+        let status = h.element_system.handle_event(
+            &CanvasEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position: Point::new(20.0, 30.0),
+                button: PointerButton::Secondary,
+                pointer_type: PointerType::Mouse,
+                click_count: 1,
+                modifiers: Modifiers::empty(),
+            }),
+            &mut h.res,
+            &mut h.clipboard,
+        );
+        assert_eq!(status, EventCaptureStatus::Captured);
+        assert_eq!(*received.borrow(), vec![Point::new(20.0, 30.0)]);
+
+        // Primary clicks do not open a context menu.
+        click(&mut h, Point::new(20.0, 30.0));
+        assert_eq!(received.borrow().len(), 1);
+
+        // A touch which is held in place.
+        touch(&mut h, 1, TouchPhase::Started, 40.0);
+        touch(&mut h, 1, TouchPhase::Moved, 45.0);
+        tick(&mut h);
+        assert_eq!(received.borrow()[1], Point::new(40.0, 50.0));
+        // It is only sent once per touch.
+        tick(&mut h);
+        touch(&mut h, 1, TouchPhase::Ended, 45.0);
+        assert_eq!(received.borrow().len(), 2);
+
+        // A touch which is dragged or lifted is not a long press.
+        touch(&mut h, 2, TouchPhase::Started, 40.0);
+        touch(&mut h, 2, TouchPhase::Moved, 80.0);
+        tick(&mut h);
+        touch(&mut h, 2, TouchPhase::Ended, 80.0);
+        touch(&mut h, 3, TouchPhase::Started, 40.0);
+        touch(&mut h, 3, TouchPhase::Ended, 40.0);
+        tick(&mut h);
+        assert_eq!(received.borrow().len(), 2);
+    }
+
+    struct TextMeasuringElement {
+        inner: Rc<RefCell<crate::elements::label::LabelInner>>,
+    }
+
+    impl Element<()> for TextMeasuringElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::StyleChanged = event {
+                let style = cx.res.style_system.get(cx.class());
+                RefCell::borrow_mut(&self.inner).sync_new_style(style, &mut cx.res.font_system);
+            }
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_text_scale_enlarges_text_but_not_element_rects() {
+        use crate::elements::label::{LabelInner, LabelStyle, TextIconLayout};
+        use crate::style::{IconScale, CLASS_DEFAULT};
+
+        let mut h = TestHarness::new();
+
+        let style = h.res.style_system.get::<LabelStyle>(CLASS_DEFAULT).clone();
+        let inner = Rc::new(RefCell::new(LabelInner::new(
+            Some("Hello world"),
+            None,
+            Vector::default(),
+            Vector::default(),
+            None,
+            IconScale::default(),
+            TextIconLayout::default(),
+            &style,
+            &mut h.res.font_system,
+        )));
+
+        let rect = Rect::new(Point::new(10.0, 10.0), Size::new(80.0, 20.0));
+        let handle = h.add(
+            ElementBuilder::new(TextMeasuringElement {
+                inner: Rc::clone(&inner),
+            })
+            .rect(rect),
+        );
+        h.process_updates();
+
+        let unscaled_size = RefCell::borrow_mut(&inner).unclipped_text_size();
+        assert!(!unscaled_size.is_empty());
+
+        assert!(h.res.style_system.set_text_scale(1.5));
+        assert!(!h.res.style_system.set_text_scale(1.5));
+        h.element_system
+            .on_theme_changed(&mut h.res, &mut h.clipboard);
+        h.process_updates();
+
+        let scaled_style = h.res.style_system.get::<LabelStyle>(CLASS_DEFAULT).clone();
+        assert_eq!(
+            scaled_style.text_properties.metrics.font_size,
+            style.text_properties.metrics.font_size * 1.5
+        );
+        assert_eq!(
+            scaled_style.text_properties.metrics.line_height,
+            style.text_properties.metrics.line_height * 1.5
+        );
+        assert_eq!(scaled_style.text_color, style.text_color);
+
+        // The rectangle set by the application is untouched.
+        assert_eq!(handle.rect(), rect);
+
+        let scaled_size = RefCell::borrow_mut(&inner).unclipped_text_size();
+        assert!(scaled_size.height > unscaled_size.height);
+        assert!(scaled_size.width > unscaled_size.width);
+
+        // Resetting the text scale restores the original style.
+        assert!(h.res.style_system.set_text_scale(1.0));
+        assert_eq!(h.res.style_system.get::<LabelStyle>(CLASS_DEFAULT), &style);
+    }
+
+    struct PenRecordingElement {
+        received: Rc<RefCell<Vec<(TouchPhase, PointerType)>>>,
+    }
+
+    impl Element<()> for PenRecordingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Touch(touch) = event {
+                RefCell::borrow_mut(&self.received).push((touch.phase, touch.pointer_type));
+                EventCaptureStatus::Captured
+            } else {
+                EventCaptureStatus::NotCaptured
+            }
+        }
+    }
+
+    #[test]
+    fn test_pen_pressure_is_delivered_with_touch_events() {
+        let mut h = TestHarness::new();
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let _canvas = h.add(
+            ElementBuilder::new(PenRecordingElement {
+                received: Rc::clone(&received),
+            })
+            .rect(Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)))
+            .flags(ElementFlags::LISTENS_TO_TOUCH),
+        );
+        h.process_updates();
+
+        let pen = |pressure: f32| PointerType::Pen {
+            pressure,
+            tilt: (0.5, 0.0),
+        };
+
+        for (phase, pressure) in [(TouchPhase::Started, 0.2), (TouchPhase::Moved, 0.8)] {
+            h.element_system.handle_event(
+                &CanvasEvent::Touch(TouchEvent {
+                    id: 1,
+                    phase,
+                    position: Point::new(50.0, 50.0),
+                    pointer_type: pen(pressure),
+                    modifiers: Modifiers::empty(),
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            );
+        }
+        // The cancelled touch keeps the last reported pen state.
+        h.element_system
+            .handle_event(&CanvasEvent::WindowUnfocused, &mut h.res, &mut h.clipboard);
+
+        let received = received.borrow();
+        assert_eq!(
+            received.iter().map(|(phase, _)| *phase).collect::<Vec<_>>(),
+            vec![
+                TouchPhase::Started,
+                TouchPhase::Moved,
+                TouchPhase::Cancelled
+            ]
+        );
+        assert_eq!(received[0].1.pressure(), 0.2);
+        assert_eq!(received[1].1.pressure(), 0.8);
+        assert_eq!(received[2].1, pen(0.8));
+        assert_eq!(received[2].1.tilt(), (0.5, 0.0));
+        assert!(received[2].1.is_same_type(&pen(0.0)));
+
+        // Other pointer types are always at full pressure.
+        assert_eq!(PointerType::Mouse.pressure(), 1.0);
+        assert_eq!(PointerType::Touch.pressure(), 1.0);
+        assert!(!PointerType::Mouse.is_same_type(&PointerType::Touch));
+    }
+
+    struct FocusRecordingElement {
+        focused: Rc<RefCell<Vec<(usize, bool)>>>,
+        index: usize,
+    }
+
+    impl Element<()> for FocusRecordingElement {
+        fn on_event(
+            &mut self,
+            event: ElementEvent,
+            _cx: &mut ElementContext<'_, ()>,
+        ) -> EventCaptureStatus {
+            if let ElementEvent::Focus(focused) = event {
+                RefCell::borrow_mut(&self.focused).push((self.index, focused));
+            }
+
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    #[test]
+    fn test_focus_next_cycles_through_elements_in_tab_order() {
+        let mut h = TestHarness::new();
+        let focused = Rc::new(RefCell::new(Vec::new()));
+
+        let add = |h: &mut TestHarness, index: usize, flags: ElementFlags, hidden: bool| {
+            h.add(
+                ElementBuilder::new(FocusRecordingElement {
+                    focused: Rc::clone(&focused),
+                    index,
+                })
+                .rect(Rect::new(
+                    // Lay the elements out in reverse so that tab order does
+                    // not just follow position.
+                    Point::new(300.0 - index as f32 * 50.0, 10.0),
+                    Size::new(40.0, 20.0),
+                ))
+                .hidden(hidden)
+                .flags(flags | ElementFlags::LISTENS_TO_FOCUS_CHANGE),
+            )
+        };
+
+        let e0 = add(&mut h, 0, ElementFlags::FOCUSABLE, false);
+        let _not_focusable = add(&mut h, 1, ElementFlags::empty(), false);
+        let e2 = add(&mut h, 2, ElementFlags::FOCUSABLE, false);
+        let _hidden = add(&mut h, 3, ElementFlags::FOCUSABLE, true);
+        let e4 = add(&mut h, 4, ElementFlags::FOCUSABLE, false);
+        h.process_updates();
+
+        assert_eq!(
+            h.element_system.focusable_elements_in_tab_order(),
+            vec![e0.id(), e2.id(), e4.id()]
+        );
+
+        let focus_next = |h: &mut TestHarness, reverse: bool| {
+            h.element_system
+                .focus_next(reverse, &mut h.res, &mut h.clipboard)
+        };
+
+        assert_eq!(focus_next(&mut h, false), Some(e0.id()));
+        assert_eq!(focus_next(&mut h, false), Some(e2.id()));
+        assert_eq!(focus_next(&mut h, false), Some(e4.id()));
+        // Focus wraps around at the end.
+        assert_eq!(focus_next(&mut h, false), Some(e0.id()));
+        assert_eq!(focus_next(&mut h, true), Some(e4.id()));
+        assert_eq!(h.element_system.focused_element(), Some(e4.id()));
+
+        // Shift+Tab uses the same logic.
+        let status = h.element_system.handle_event(
+            &CanvasEvent::Keyboard(KeyboardEvent {
+                state: KeyState::Down,
+                code: Code::Tab,
+                modifiers: Modifiers::SHIFT,
+                ..Default::default()
+            }),
+            &mut h.res,
+            &mut h.clipboard,
+        );
+        assert_eq!(status, EventCaptureStatus::Captured);
+        assert_eq!(h.element_system.focused_element(), Some(e2.id()));
+        assert!(h.element_system.element_is_focused(&e2));
+        assert!(!h.element_system.element_is_focused(&e4));
+
+        assert_eq!(
+            *RefCell::borrow(&focused),
+            vec![
+                (0, true),
+                (0, false),
+                (2, true),
+                (2, false),
+                (4, true),
+                (4, false),
+                (0, true),
+                (0, false),
+                (4, true),
+                (4, false),
+                (2, true),
+            ]
+        );
+
+        assert_eq!(next_tab_index(0, None, false, true), None);
+    }
+
+    #[test]
+    fn test_ime_is_only_allowed_while_a_text_element_is_focused() {
+        let mut h = TestHarness::new();
+        let rect = Rect::new(Point::new(10.0, 10.0), Size::new(40.0, 20.0));
+
+        let text = h.add(ElementBuilder::new(TestElement).rect(rect).flags(
+            ElementFlags::FOCUSABLE | ElementFlags::LISTENS_TO_TEXT_COMPOSITION_WHEN_FOCUSED,
+        ));
+        let _other = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(rect.translate(Vector::new(50.0, 0.0)))
+                .flags(ElementFlags::FOCUSABLE),
+        );
+        h.process_updates();
+        assert_eq!(h.element_system.ime_allowed_request(), None);
+
+        let focus_next = |h: &mut TestHarness| {
+            h.element_system
+                .focus_next(false, &mut h.res, &mut h.clipboard)
+        };
+
+        assert_eq!(focus_next(&mut h), Some(text.id()));
+        assert_eq!(h.element_system.ime_allowed_request(), Some(true));
+        // Only changes are reported.
+        assert_eq!(h.element_system.ime_allowed_request(), None);
+
+        focus_next(&mut h);
+        assert_eq!(h.element_system.ime_allowed_request(), Some(false));
+        assert_eq!(h.element_system.ime_allowed_request(), None);
+    }
+
+    #[test]
+    fn test_focus_wrap_mode_wraps_or_stops_at_the_ends() {
+        let mut h = TestHarness::new();
+        let focused = Rc::new(RefCell::new(Vec::new()));
+
+        let scope = ScissorRectID(1);
+        h.element_system.update_scissor_rect(
+            scope,
+            Some(Rect::new(Point::new(0.0, 100.0), Size::new(400.0, 100.0))),
+            None,
+        );
+
+        let add = |h: &mut TestHarness, index: usize, scissor_rect: ScissorRectID| {
+            h.add(
+                ElementBuilder::new(FocusRecordingElement {
+                    focused: Rc::clone(&focused),
+                    index,
+                })
+                .rect(Rect::new(
+                    Point::new(10.0 + index as f32 * 50.0, 10.0),
+                    Size::new(40.0, 20.0),
+                ))
+                .scissor_rect(scissor_rect)
+                .flags(ElementFlags::FOCUSABLE | ElementFlags::LISTENS_TO_FOCUS_CHANGE),
+            )
+        };
+
+        let d0 = add(&mut h, 0, ScissorRectID::DEFAULT);
+        let s1 = add(&mut h, 1, scope);
+        let s2 = add(&mut h, 2, scope);
+        let d3 = add(&mut h, 3, ScissorRectID::DEFAULT);
+        h.process_updates();
+
+        let focus_next = |h: &mut TestHarness, reverse: bool| {
+            h.element_system
+                .focus_next(reverse, &mut h.res, &mut h.clipboard)
+        };
+
+        // Stopping at the ends of the window leaves focus unchanged.
+        h.element_system
+            .set_focus_wrap_mode(ScissorRectID::DEFAULT, Some(FocusWrapMode::Stop));
+        assert_eq!(focus_next(&mut h, false), Some(d0.id()));
+        assert_eq!(focus_next(&mut h, true), None);
+        assert_eq!(h.element_system.focused_element(), Some(d0.id()));
+
+        // A wrapping scope cycles from its last element back to its first.
+        h.element_system
+            .set_focus_wrap_mode(scope, Some(FocusWrapMode::Wrap));
+        assert_eq!(focus_next(&mut h, false), Some(s1.id()));
+        assert_eq!(focus_next(&mut h, false), Some(s2.id()));
+        assert_eq!(focus_next(&mut h, false), Some(s1.id()));
+        assert_eq!(focus_next(&mut h, true), Some(s2.id()));
+
+        // A stopping scope exits to the elements outside of it.
+        h.element_system
+            .set_focus_wrap_mode(scope, Some(FocusWrapMode::Stop));
+        assert_eq!(focus_next(&mut h, false), Some(d3.id()));
+        assert_eq!(focus_next(&mut h, false), None);
+        assert_eq!(h.element_system.focused_element(), Some(d3.id()));
+        assert_eq!(focus_next(&mut h, true), Some(s2.id()));
+        assert_eq!(focus_next(&mut h, true), Some(s1.id()));
+        assert_eq!(focus_next(&mut h, true), Some(d0.id()));
+
+        // The window wraps by default.
+        h.element_system
+            .set_focus_wrap_mode(ScissorRectID::DEFAULT, None);
+        assert_eq!(
+            h.element_system.focus_wrap_mode(ScissorRectID::DEFAULT),
+            Some(FocusWrapMode::Wrap)
+        );
+        assert_eq!(focus_next(&mut h, true), Some(d3.id()));
+
+        assert_eq!(next_tab_index(3, Some(2), false, false), None);
+        assert_eq!(next_tab_index(3, Some(0), true, true), Some(2));
+    }
+
+    #[test]
+    fn test_interactive_tooltip_stays_open_while_hovered() {
+        let mut h = TestHarness::new();
+        h.element_system.set_tooltip_actions(|_| (), || ());
+
+        let owner = h.add(overlapping_element());
+        let _tooltip = h.add(
+            ElementBuilder::new(TestElement)
+                .rect(Rect::new(Point::new(10.0, 70.0), Size::new(100.0, 60.0)))
+                .flags(
+                    ElementFlags::PAINTS
+                        | ElementFlags::KEEPS_TOOLTIP_OPEN
+                        | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS,
+                ),
+        );
+        h.process_updates();
+
+        h.element_system.element_with_active_tooltip = Some(ActiveTooltipInfo {
+            element_id: owner.id(),
+            auto_hide: true,
+        });
+
+        let move_pointer = |h: &mut TestHarness, x: f32, y: f32| {
+            h.element_system.handle_event(
+                &CanvasEvent::Pointer(PointerEvent::Moved {
+                    position: Point::new(x, y),
+                    delta: None,
+                    is_locked: false,
+                    pointer_type: PointerType::default(),
+                    modifiers: Modifiers::empty(),
+                    just_entered: false,
+                }),
+                &mut h.res,
+                &mut h.clipboard,
+            );
+        };
+
+        // Move from the element, across the gap, and onto the tooltip.
+        move_pointer(&mut h, 30.0, 30.0);
+        move_pointer(&mut h, 30.0, 65.0);
+        move_pointer(&mut h, 90.0, 100.0);
+        assert!(h.element_system.element_with_active_tooltip.is_some());
+
+        // Leaving both closes the tooltip.
+        move_pointer(&mut h, 300.0, 250.0);
+        assert!(h.element_system.element_with_active_tooltip.is_none());
+    }
+}
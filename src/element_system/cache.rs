@@ -1,13 +1,27 @@
 use rootvg::PrimitiveGroup;
 
-use crate::math::{Rect, Vector, ZIndex};
+use crate::math::{Rect, RectI32, Size, Vector, ZIndex};
 
+use super::scissor_rect::ScissorRect;
 use super::{ElementFlags, ElementID, EntryStackData};
 
 pub(super) struct CachedElementRectForPointerEvent {
     pub z_index: ZIndex,
+    pub creation_order: u64,
+    pub scissor_rect_index: usize,
     pub element_id: ElementID,
-    pub visible_rect: Option<Rect>,
+    pub hit_rect: Option<Rect>,
+}
+
+impl CachedElementRectForPointerEvent {
+    /// The key that this list is sorted by. This must match the order that
+    /// painted elements are rendered in (see [`CachedElementPrimitives::order`]).
+    pub fn order(&self, scissor_rects: &[ScissorRect]) -> (ZIndex, u64) {
+        (
+            scissor_rects[self.scissor_rect_index].z_index_of(self.z_index),
+            self.creation_order,
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -15,9 +29,14 @@ pub(super) struct CachedElementPrimitives {
     pub element_id: ElementID,
     pub offset: Vector,
     pub z_index: ZIndex,
+    pub creation_order: u64,
     pub scissor_rect_index: usize,
+    pub opacity: f32,
+    pub clip_to_scissor_rect: bool,
     pub visible: bool,
     pub dirty: bool,
+    /// The size of the element the last time it was painted.
+    pub painted_size: Option<Size>,
     pub primitives: PrimitiveGroup,
 }
 
@@ -26,19 +45,52 @@ impl CachedElementPrimitives {
         element_id: ElementID,
         offset: Vector,
         z_index: ZIndex,
+        creation_order: u64,
         scissor_rect_index: usize,
+        clip_to_scissor_rect: bool,
         visible: bool,
     ) -> Self {
         Self {
             element_id,
             offset,
             z_index,
+            creation_order,
             scissor_rect_index,
+            opacity: 1.0,
+            clip_to_scissor_rect,
             visible,
             dirty: true,
+            painted_size: None,
             primitives: PrimitiveGroup::new(),
         }
     }
+
+    /// The key that this list is sorted by. Elements are sorted by their z
+    /// index offset by the base z index of their scissoring rectangle, and
+    /// elements with the same z index are painted in the order they were
+    /// created, so that the element that
+    /// is painted on top is also the first one to receive pointer events.
+    pub fn order(&self, scissor_rects: &[ScissorRect]) -> (ZIndex, u64) {
+        (self.z_index(scissor_rects), self.creation_order)
+    }
+
+    /// The z index that the primitives of this element are rendered with,
+    /// which includes the base z index of its scissoring rectangle.
+    pub fn z_index(&self, scissor_rects: &[ScissorRect]) -> ZIndex {
+        scissor_rects[self.scissor_rect_index].z_index_of(self.z_index)
+    }
+
+    /// The rectangle that the primitives of this element are clipped to.
+    ///
+    /// Elements with the `ElementFlags::DRAWS_OUTSIDE_SCISSOR_RECT` flag are
+    /// only clipped to the bounds of the window.
+    pub fn clip_rect(&self, scissor_rects: &[ScissorRect]) -> RectI32 {
+        if self.clip_to_scissor_rect {
+            scissor_rects[self.scissor_rect_index].clip_rect()
+        } else {
+            scissor_rects[0].rect()
+        }
+    }
 }
 
 pub(super) fn sync_element_rect_cache(
@@ -47,13 +99,10 @@ pub(super) fn sync_element_rect_cache(
     painted_elements: &mut Vec<CachedElementPrimitives>,
     mark_dirty: bool,
 ) {
-    if entry_stack_data
-        .flags
-        .contains(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
-    {
+    if entry_stack_data.flags.is_hit_tested() {
         elements_listening_to_pointer_event
             [entry_stack_data.index_in_pointer_event_list as usize]
-            .visible_rect = entry_stack_data.visible_rect;
+            .hit_rect = entry_stack_data.hit_rect;
     }
 
     if entry_stack_data.flags.contains(ElementFlags::PAINTS) {
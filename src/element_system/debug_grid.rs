@@ -0,0 +1,210 @@
+use rootvg::color::{self, RGBA8};
+use rootvg::math::{Point, Rect, Size};
+use rootvg::PrimitiveGroup;
+
+use crate::style::{Background, BorderStyle, QuadStyle};
+use crate::vg::quad::QuadFlags;
+
+/// The configuration of the debug grid overlay.
+///
+/// This is a developer tool for pixel-precise layout work. The overlay is
+/// drawn on top of every element and it does not receive any events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugGridConfig {
+    /// The spacing between grid lines in logical points.
+    ///
+    /// By default this is set to `10.0`.
+    pub spacing: f32,
+
+    /// The color of the grid lines.
+    pub line_color: RGBA8,
+
+    /// Whether or not to draw rulers along the top and left edges of the
+    /// window.
+    ///
+    /// By default this is set to `true`.
+    pub show_rulers: bool,
+    /// The thickness of the rulers in logical points.
+    ///
+    /// By default this is set to `12.0`.
+    pub ruler_size: f32,
+    pub ruler_bg_color: RGBA8,
+    pub ruler_tick_color: RGBA8,
+
+    /// Whether or not to draw a crosshair marking the current position of
+    /// the pointer.
+    ///
+    /// By default this is set to `true`.
+    pub show_pointer_position: bool,
+    pub pointer_marker_color: RGBA8,
+}
+
+impl Default for DebugGridConfig {
+    fn default() -> Self {
+        Self {
+            spacing: 10.0,
+            line_color: color::rgba(255, 0, 255, 40),
+            show_rulers: true,
+            ruler_size: 12.0,
+            ruler_bg_color: color::rgba(0, 0, 0, 180),
+            ruler_tick_color: color::rgba(255, 255, 255, 180),
+            show_pointer_position: true,
+            pointer_marker_color: color::rgba(0, 255, 255, 160),
+        }
+    }
+}
+
+impl DebugGridConfig {
+    /// Returns the bounds of every grid line in the window, with the vertical
+    /// lines first followed by the horizontal lines.
+    pub(crate) fn grid_lines(&self, window_size: Size, line_width: f32) -> Vec<Rect> {
+        let mut lines = Vec::new();
+
+        if self.spacing <= 0.0 {
+            return lines;
+        }
+
+        let mut x = 0.0;
+        while x < window_size.width {
+            lines.push(Rect::new(
+                Point::new(x, 0.0),
+                Size::new(line_width, window_size.height),
+            ));
+            x += self.spacing;
+        }
+
+        let mut y = 0.0;
+        while y < window_size.height {
+            lines.push(Rect::new(
+                Point::new(0.0, y),
+                Size::new(window_size.width, line_width),
+            ));
+            y += self.spacing;
+        }
+
+        lines
+    }
+
+    pub(crate) fn build_primitives(
+        &self,
+        window_size: Size,
+        line_width: f32,
+        pointer_pos: Option<Point>,
+        primitives: &mut PrimitiveGroup,
+    ) {
+        for line in self.grid_lines(window_size, line_width) {
+            primitives.add(solid_quad(self.line_color).create_primitive(line));
+        }
+
+        if self.show_rulers && self.ruler_size > 0.0 {
+            primitives.add(solid_quad(self.ruler_bg_color).create_primitive(Rect::new(
+                Point::new(0.0, 0.0),
+                Size::new(window_size.width, self.ruler_size),
+            )));
+            primitives.add(solid_quad(self.ruler_bg_color).create_primitive(Rect::new(
+                Point::new(0.0, self.ruler_size),
+                Size::new(self.ruler_size, window_size.height - self.ruler_size),
+            )));
+
+            if self.spacing > 0.0 {
+                let tick_style = solid_quad(self.ruler_tick_color);
+
+                let mut i: u32 = 0;
+                let mut x = 0.0;
+                while x < window_size.width {
+                    // Make every tenth tick span the whole ruler.
+                    let tick_len = if i % 10 == 0 {
+                        self.ruler_size
+                    } else {
+                        self.ruler_size * 0.4
+                    };
+
+                    primitives.add(tick_style.create_primitive(Rect::new(
+                        Point::new(x, self.ruler_size - tick_len),
+                        Size::new(line_width, tick_len),
+                    )));
+
+                    i += 1;
+                    x += self.spacing;
+                }
+
+                let mut i: u32 = 0;
+                let mut y = 0.0;
+                while y < window_size.height {
+                    let tick_len = if i % 10 == 0 {
+                        self.ruler_size
+                    } else {
+                        self.ruler_size * 0.4
+                    };
+
+                    primitives.add(tick_style.create_primitive(Rect::new(
+                        Point::new(self.ruler_size - tick_len, y),
+                        Size::new(tick_len, line_width),
+                    )));
+
+                    i += 1;
+                    y += self.spacing;
+                }
+            }
+        }
+
+        if self.show_pointer_position {
+            if let Some(pos) = pointer_pos {
+                let marker_style = solid_quad(self.pointer_marker_color);
+
+                primitives.add(marker_style.create_primitive(Rect::new(
+                    Point::new(pos.x, 0.0),
+                    Size::new(line_width, window_size.height),
+                )));
+                primitives.add(marker_style.create_primitive(Rect::new(
+                    Point::new(0.0, pos.y),
+                    Size::new(window_size.width, line_width),
+                )));
+            }
+        }
+    }
+}
+
+fn solid_quad(color: RGBA8) -> QuadStyle {
+    QuadStyle {
+        bg: Background::Solid(color),
+        border: BorderStyle::TRANSPARENT,
+        flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_grid_lines_follow_spacing() {
+        let config = DebugGridConfig {
+            spacing: 20.0,
+            ..Default::default()
+        };
+
+        let lines = config.grid_lines(Size::new(100.0, 50.0), 1.0);
+
+        let vertical: Vec<f32> = lines
+            .iter()
+            .filter(|r| r.height() == 50.0)
+            .map(|r| r.min_x())
+            .collect();
+        let horizontal: Vec<f32> = lines
+            .iter()
+            .filter(|r| r.width() == 100.0)
+            .map(|r| r.min_y())
+            .collect();
+
+        assert_eq!(vertical, vec![0.0, 20.0, 40.0, 60.0, 80.0]);
+        assert_eq!(horizontal, vec![0.0, 20.0, 40.0]);
+        assert!(lines.iter().all(|r| r.width() == 1.0 || r.height() == 1.0));
+
+        let empty = DebugGridConfig {
+            spacing: 0.0,
+            ..Default::default()
+        };
+        assert!(empty.grid_lines(Size::new(100.0, 50.0), 1.0).is_empty());
+    }
+}
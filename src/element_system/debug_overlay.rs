@@ -0,0 +1,146 @@
+use rootvg::color::RGBA8;
+use rootvg::math::Rect;
+use rootvg::PrimitiveGroup;
+
+use crate::style::{Background, BorderStyle, QuadStyle};
+use crate::vg::quad::QuadFlags;
+
+bitflags::bitflags! {
+    /// The layers of the debug overlay to show.
+    ///
+    /// This is a developer tool for inspecting the layout of elements. The
+    /// overlay is drawn on top of every element and it does not receive any
+    /// events.
+    ///
+    /// By default all these flags are disabled (the overlay is hidden).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct DebugOverlayFlags: u8 {
+        /// Outline the visible area of every element.
+        const ELEMENT_BOUNDS = 1 << 0;
+
+        /// Outline the clipping area of every scissoring rectangle.
+        const SCISSOR_RECTS = 1 << 1;
+
+        /// Highlight the elements which are currently hovered.
+        const HOVERED = 1 << 2;
+
+        /// Highlight the element which currently has exclusive focus.
+        const FOCUSED = 1 << 3;
+    }
+}
+
+const ELEMENT_BOUNDS_COLOR: RGBA8 = RGBA8::new(0, 200, 255, 180);
+const SCISSOR_RECT_COLOR: RGBA8 = RGBA8::new(255, 0, 220, 220);
+const HOVERED_COLOR: RGBA8 = RGBA8::new(255, 210, 0, 220);
+const HOVERED_FILL_COLOR: RGBA8 = RGBA8::new(255, 210, 0, 40);
+const FOCUSED_COLOR: RGBA8 = RGBA8::new(0, 255, 80, 255);
+
+/// The rectangles shown in the debug overlay, in window coordinates.
+pub(crate) struct DebugOverlayRects {
+    /// The visible area of every visible element.
+    pub element_bounds: Vec<Rect>,
+    /// The clipping area of every scissoring rectangle.
+    pub scissor_rects: Vec<Rect>,
+    /// The visible area of every hovered element.
+    pub hovered: Vec<Rect>,
+    /// The visible area of the focused element.
+    pub focused: Option<Rect>,
+}
+
+impl DebugOverlayFlags {
+    /// The quads of the enabled layers, from bottom to top.
+    pub(crate) fn quads(&self, rects: &DebugOverlayRects) -> Vec<(Rect, QuadStyle)> {
+        let outline = |color: RGBA8, width: f32| QuadStyle {
+            bg: Background::TRANSPARENT,
+            border: BorderStyle {
+                color,
+                width,
+                ..BorderStyle::TRANSPARENT
+            },
+            flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+        };
+
+        let mut quads = Vec::new();
+
+        if self.contains(Self::SCISSOR_RECTS) {
+            let style = outline(SCISSOR_RECT_COLOR, 2.0);
+            quads.extend(rects.scissor_rects.iter().map(|rect| (*rect, style)));
+        }
+
+        if self.contains(Self::ELEMENT_BOUNDS) {
+            let style = outline(ELEMENT_BOUNDS_COLOR, 1.0);
+            quads.extend(rects.element_bounds.iter().map(|rect| (*rect, style)));
+        }
+
+        if self.contains(Self::HOVERED) {
+            let style = QuadStyle {
+                bg: Background::Solid(HOVERED_FILL_COLOR),
+                ..outline(HOVERED_COLOR, 1.0)
+            };
+            quads.extend(rects.hovered.iter().map(|rect| (*rect, style)));
+        }
+
+        if self.contains(Self::FOCUSED) {
+            if let Some(rect) = rects.focused {
+                quads.push((rect, outline(FOCUSED_COLOR, 2.0)));
+            }
+        }
+
+        quads
+    }
+
+    pub(crate) fn build_primitives(
+        &self,
+        rects: &DebugOverlayRects,
+        primitives: &mut PrimitiveGroup,
+    ) {
+        for (rect, style) in self.quads(rects) {
+            primitives.add(style.create_primitive(rect));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rootvg::math::{Point, Size};
+
+    #[test]
+    fn test_only_enabled_layers_are_shown() {
+        let rect = |x: f32| Rect::new(Point::new(x, 0.0), Size::new(10.0, 10.0));
+        let rects = DebugOverlayRects {
+            element_bounds: vec![rect(0.0), rect(10.0)],
+            scissor_rects: vec![rect(20.0)],
+            hovered: vec![rect(10.0)],
+            focused: Some(rect(0.0)),
+        };
+
+        assert!(DebugOverlayFlags::empty().quads(&rects).is_empty());
+
+        let quads = DebugOverlayFlags::all().quads(&rects);
+        let colors: Vec<RGBA8> = quads.iter().map(|(_, style)| style.border.color).collect();
+        assert_eq!(
+            colors,
+            vec![
+                SCISSOR_RECT_COLOR,
+                ELEMENT_BOUNDS_COLOR,
+                ELEMENT_BOUNDS_COLOR,
+                HOVERED_COLOR,
+                FOCUSED_COLOR,
+            ]
+        );
+
+        let quads = (DebugOverlayFlags::SCISSOR_RECTS | DebugOverlayFlags::FOCUSED).quads(&rects);
+        assert_eq!(
+            quads.iter().map(|(rect, _)| *rect).collect::<Vec<_>>(),
+            vec![rect(20.0), rect(0.0)]
+        );
+
+        // Nothing is focused.
+        let rects = DebugOverlayRects {
+            focused: None,
+            ..rects
+        };
+        assert!(DebugOverlayFlags::FOCUSED.quads(&rects).is_empty());
+    }
+}
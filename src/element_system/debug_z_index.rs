@@ -0,0 +1,122 @@
+use rootvg::color::{self, RGBA8};
+use rootvg::math::{Rect, ZIndex};
+use rootvg::PrimitiveGroup;
+
+use crate::style::{Background, BorderStyle, QuadStyle};
+use crate::vg::quad::QuadFlags;
+
+/// The configuration of the z index heatmap overlay.
+///
+/// This is a developer tool for verifying the stacking order of elements.
+/// Every visible painted element is tinted with a color between
+/// `low_color` and `high_color` depending on its z index. The overlay is
+/// drawn on top of every element and it does not receive any events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugZIndexConfig {
+    /// The tint of the elements with the lowest z index.
+    pub low_color: RGBA8,
+    /// The tint of the elements with the highest z index.
+    pub high_color: RGBA8,
+}
+
+impl Default for DebugZIndexConfig {
+    fn default() -> Self {
+        Self {
+            low_color: color::rgba(0, 80, 255, 70),
+            high_color: color::rgba(255, 40, 0, 70),
+        }
+    }
+}
+
+impl DebugZIndexConfig {
+    /// The tint of an element with the given z index, where `min_z_index` and
+    /// `max_z_index` are the lowest and highest z indexes of all visible
+    /// elements.
+    pub(crate) fn tint_color(
+        &self,
+        z_index: ZIndex,
+        min_z_index: ZIndex,
+        max_z_index: ZIndex,
+    ) -> RGBA8 {
+        let t = if max_z_index > min_z_index {
+            (z_index as f32 - min_z_index as f32) / (max_z_index as f32 - min_z_index as f32)
+        } else {
+            0.0
+        };
+
+        let lerp = |a: u8, b: u8| -> u8 { (a as f32 + (b as f32 - a as f32) * t).round() as u8 };
+
+        RGBA8::new(
+            lerp(self.low_color.r, self.high_color.r),
+            lerp(self.low_color.g, self.high_color.g),
+            lerp(self.low_color.b, self.high_color.b),
+            lerp(self.low_color.a, self.high_color.a),
+        )
+    }
+
+    /// Returns the tinted rectangle of each of the given elements, in the same
+    /// order.
+    pub(crate) fn tints(&self, elements: &[(Rect, ZIndex)]) -> Vec<(Rect, RGBA8)> {
+        let Some(min_z_index) = elements.iter().map(|(_, z)| *z).min() else {
+            return Vec::new();
+        };
+        let max_z_index = elements.iter().map(|(_, z)| *z).max().unwrap();
+
+        elements
+            .iter()
+            .map(|(rect, z_index)| (*rect, self.tint_color(*z_index, min_z_index, max_z_index)))
+            .collect()
+    }
+
+    pub(crate) fn build_primitives(
+        &self,
+        elements: &[(Rect, ZIndex)],
+        primitives: &mut PrimitiveGroup,
+    ) {
+        for (rect, color) in self.tints(elements) {
+            let style = QuadStyle {
+                bg: Background::Solid(color),
+                border: BorderStyle::TRANSPARENT,
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            };
+
+            primitives.add(style.create_primitive(rect));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rootvg::math::{Point, Size};
+
+    #[test]
+    fn test_z_index_tint_is_monotonic() {
+        let config = DebugZIndexConfig {
+            low_color: color::rgba(0, 0, 0, 50),
+            high_color: color::rgba(255, 0, 0, 150),
+        };
+
+        let rect = Rect::new(Point::new(0.0, 0.0), Size::new(10.0, 10.0));
+        let elements: Vec<(Rect, ZIndex)> = [3, 0, 7, 1, 5].map(|z| (rect, z)).to_vec();
+
+        let mut tints: Vec<(ZIndex, RGBA8)> = elements
+            .iter()
+            .map(|(_, z)| *z)
+            .zip(config.tints(&elements).into_iter().map(|(_, c)| c))
+            .collect();
+        tints.sort_by_key(|(z, _)| *z);
+
+        assert_eq!(tints.first().unwrap().1, config.low_color);
+        assert_eq!(tints.last().unwrap().1, config.high_color);
+        for pair in tints.windows(2) {
+            assert!(pair[0].1.r < pair[1].1.r);
+            assert!(pair[0].1.a < pair[1].1.a);
+        }
+
+        // A single layer uses the low color.
+        let tints = config.tints(&[(rect, 4)]);
+        assert_eq!(tints[0].1, config.low_color);
+        assert!(config.tints(&[]).is_empty());
+    }
+}
@@ -3,11 +3,12 @@ mod flags;
 mod handle;
 
 use std::any::Any;
+use std::time::Duration;
 
 use context::UpdateScissorRectRequest;
 pub use context::{ElementContext, RenderContext};
 pub use flags::ElementFlags;
-pub use handle::ElementHandle;
+pub use handle::{ElementHandle, LayoutTransaction, SharedElementHandle};
 use rootvg::math::Point;
 use rootvg::PrimitiveGroup;
 
@@ -15,14 +16,42 @@ use super::ScissorRectID;
 use crate::action_queue::ActionSender;
 use crate::event::{ElementEvent, EventCaptureStatus};
 use crate::math::{Rect, Size, ZIndex};
-use crate::prelude::TooltipData;
+use crate::prelude::{ResourceCtx, TooltipData};
 use crate::style::ClassID;
 use crate::{stmpsc_queue, WindowContext};
 
 pub(crate) use context::ChangeFocusRequest;
+pub(crate) use handle::CustomStateNotifier;
 
+/// The unique identifier of an element instance.
+///
+/// Unlike an [`ElementHandle`], this does not own the element, so it can be
+/// freely copied and compared (i.e. to check which element has focus).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct ElementID(pub thunderdome::Index);
+pub struct ElementID(pub(crate) thunderdome::Index);
+
+/// An optional stable key assigned to an element instance by the application.
+///
+/// Unlike the internal ID of an element, a key is entirely controlled by the
+/// application, so it stays the same when an element is dropped and recreated
+/// (useful for things like record/replay testing).
+pub type ElementKey = u64;
+
+/// A read-only snapshot of the state of an element instance.
+///
+/// See [`WindowContext::iter_elements`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementInfo {
+    pub id: ElementID,
+    /// The bounding rectangle of the element in window coordinates.
+    pub rect: Rect,
+    pub z_index: ZIndex,
+    /// Whether any part of the element is currently visible.
+    pub visible: bool,
+    pub class: ClassID,
+    /// Whether the element has been explicitly hidden by its handle.
+    pub manually_hidden: bool,
+}
 
 pub trait Element<A: Clone + 'static> {
     #[allow(unused)]
@@ -55,6 +84,33 @@ pub trait Element<A: Clone + 'static> {
     fn global_render_cache(&self) -> Option<Box<dyn ElementRenderCache>> {
         None
     }
+
+    /// The node describing this element in the accessibility tree (role,
+    /// label, value, etc.), or `None` if this element should not be exposed
+    /// to assistive technologies such as screen readers.
+    ///
+    /// The bounds of the node are filled in automatically from the visible
+    /// area of the element.
+    #[cfg(feature = "accessibility")]
+    fn accessibility_node(&self) -> Option<accesskit::Node> {
+        None
+    }
+}
+
+/// A handle to an element which can report how large it wants to be.
+///
+/// This makes it possible to lay out elements based on their actual
+/// content, i.e. by feeding the measured sizes into
+/// [`layout_flex`](crate::layout::layout_flex). See also
+/// [`WindowContext::measure`].
+pub trait SizableElement {
+    /// The size (in points) that the element wants to be when it is given
+    /// the `available` space.
+    ///
+    /// Elements whose content wraps (i.e. paragraphs) use the available
+    /// width to compute their height. Other elements return their desired
+    /// size, which may be larger than `available`.
+    fn measure(&self, available: Size, res: &mut ResourceCtx) -> Size;
 }
 
 pub trait ElementRenderCache {
@@ -72,6 +128,8 @@ pub struct ElementBuilder<A: Clone + 'static> {
     pub scissor_rect: ScissorRectID,
     pub class: ClassID,
     pub flags: ElementFlags,
+    pub key: Option<ElementKey>,
+    pub hit_tolerance: f32,
 }
 
 impl<A: Clone + 'static> ElementBuilder<A> {
@@ -84,6 +142,8 @@ impl<A: Clone + 'static> ElementBuilder<A> {
             scissor_rect: ScissorRectID::DEFAULT,
             class: 0,
             flags: ElementFlags::empty(),
+            key: None,
+            hit_tolerance: 0.0,
         }
     }
 
@@ -130,6 +190,28 @@ impl<A: Clone + 'static> ElementBuilder<A> {
         self
     }
 
+    /// Assign a stable key to this element instance.
+    ///
+    /// If another element with the same key already exists, then that element
+    /// will no longer be reachable by that key.
+    pub const fn key(mut self, key: ElementKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Expand the area in which this element receives pointer events by this
+    /// many logical points beyond its visual bounds. This does not affect
+    /// rendering.
+    ///
+    /// This is useful to make thin elements (i.e. a 2px splitter line) easier
+    /// to grab.
+    ///
+    /// By default this is set to `0.0`.
+    pub const fn hit_tolerance(mut self, hit_tolerance: f32) -> Self {
+        self.hit_tolerance = hit_tolerance;
+        self
+    }
+
     pub fn build(self, window_cx: &mut WindowContext<A>) -> ElementHandle {
         window_cx.add_element(self)
     }
@@ -145,6 +227,23 @@ pub trait ElementStyle: Default + Any {
     fn default_light_style() -> Self {
         Self::default()
     }
+
+    /// Returns a copy of this style with the font size and line height of all
+    /// of its text scaled by the given multiplier, or `None` if this style
+    /// contains no text.
+    ///
+    /// See [`StyleSystem::set_text_scale`](crate::style::StyleSystem::set_text_scale).
+    fn with_text_scale(&self, _text_scale: f32) -> Option<Self> {
+        None
+    }
+
+    /// Multiply the alpha channel of every color in this style by the given
+    /// multiplier. This is used to apply the opacity of an element.
+    ///
+    /// The default implementation leaves the style unchanged.
+    ///
+    /// See [`StyleSystem::get_with_opacity`](crate::style::StyleSystem::get_with_opacity).
+    fn multiply_alpha(&mut self, _multiplier: f32) {}
 }
 
 pub(super) struct ElementModification {
@@ -159,12 +258,13 @@ pub(super) enum ElementModificationType {
     ScissorRectChanged,
     ZIndexChanged(ZIndex),
     ExplicitlyHiddenChanged(bool),
+    OpacityChanged(f32),
     ClassChanged(ClassID),
     SetAnimating(bool),
     ChangeFocus(ChangeFocusRequest),
     HandleDropped,
     ListenToClickOff,
-    StartHoverTimeout,
+    StartHoverTimeout { delay: Option<Duration> },
     StartScrollWheelTimeout,
     ShowTooltip { data: TooltipData, auto_hide: bool },
     UpdateScissorRect(UpdateScissorRectRequest),
@@ -189,3 +289,23 @@ pub(super) fn new_element_handle(
         class,
     )
 }
+
+pub(super) fn new_non_owning_element_handle(
+    element_id: ElementID,
+    mod_queue_sender: stmpsc_queue::Sender<ElementModification>,
+    rect: Rect,
+    z_index: ZIndex,
+    manually_hidden: bool,
+    class: ClassID,
+    opacity: f32,
+) -> ElementHandle {
+    ElementHandle::new(
+        element_id,
+        mod_queue_sender,
+        rect,
+        z_index,
+        manually_hidden,
+        class,
+    )
+    .non_owning(opacity)
+}
@@ -1,4 +1,4 @@
-use std::sync::mpsc;
+use std::time::Duration;
 
 use rootvg::math::{Point, Size, Vector};
 
@@ -34,6 +34,9 @@ pub struct ElementContext<'a, A: Clone + 'static> {
     /// The cursor icon. Mutate this to change the cursor icon.
     ///
     /// The icon is reset once the cursor moves.
+    ///
+    /// Setting this is the same as calling
+    /// [`ElementContext::request_cursor_icon`] with a priority of `0`.
     pub cursor_icon: CursorIcon,
     /// A sender for the action queue.
     pub action_sender: &'a mut ActionSender<A>,
@@ -49,6 +52,7 @@ pub struct ElementContext<'a, A: Clone + 'static> {
 
     pub(crate) rect: Rect,
     pub(crate) visible_rect: Option<Rect>,
+    pub(crate) hit_rect: Option<Rect>,
     pub(crate) window_size: Size,
     pub(crate) z_index: ZIndex,
     pub(crate) manually_hidden: bool,
@@ -56,12 +60,16 @@ pub struct ElementContext<'a, A: Clone + 'static> {
     pub(crate) repaint_requested: bool,
     pub(crate) has_focus: bool,
     pub(crate) hover_timeout_requested: bool,
+    pub(crate) hover_timeout_delay: Option<Duration>,
     pub(crate) scroll_wheel_timeout_requested: bool,
     pub(crate) scale_factor: ScaleFactor,
     pub(crate) window_id: WindowID,
     pub(crate) pointer_lock_request: Option<bool>,
+    pub(crate) ime_cursor_area_request: Option<Rect>,
     pub(crate) update_scissor_rect_req: Option<UpdateScissorRectRequest>,
+    pub(crate) requested_cursor_icon: Option<(CursorIcon, i32)>,
     pointer_locked: bool,
+    animation_clock_secs: f64,
     class: ClassID,
 }
 
@@ -69,6 +77,7 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
     pub(crate) fn new(
         rect: Rect,
         visible_rect: Option<Rect>,
+        hit_rect: Option<Rect>,
         window_size: Size,
         z_index: ZIndex,
         manually_hidden: bool,
@@ -78,6 +87,7 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
         cursor_icon: CursorIcon,
         window_id: WindowID,
         pointer_locked: bool,
+        animation_clock_secs: f64,
         class: ClassID,
         action_sender: &'a mut ActionSender<A>,
         res: &'a mut ResourceCtx,
@@ -89,6 +99,7 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
             res,
             rect,
             visible_rect,
+            hit_rect,
             window_size,
             z_index,
             manually_hidden,
@@ -98,19 +109,32 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
             scale_factor,
             window_id,
             pointer_lock_request: None,
+            ime_cursor_area_request: None,
             pointer_locked,
+            animation_clock_secs,
             listen_to_pointer_clicked_off: false,
             hover_timeout_requested: false,
+            hover_timeout_delay: None,
             scroll_wheel_timeout_requested: false,
             requested_rect: None,
             requested_show_tooltip: None,
             change_focus_request: None,
             update_scissor_rect_req: None,
+            requested_cursor_icon: None,
             class,
             clipboard,
         }
     }
 
+    /// A clock shared by every element in the window, in seconds.
+    ///
+    /// This increases by the `delta_seconds` of every animation tick, so
+    /// elements which blink or pulse can derive their phase from it to stay
+    /// in sync with each other (i.e. the cursors of multiple text inputs).
+    pub fn animation_clock_secs(&self) -> f64 {
+        self.animation_clock_secs
+    }
+
     /// The rectangular area assigned to this element instance.
     ///
     /// Note, the rectangle may have a position and size of zero if the element
@@ -167,6 +191,24 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
         self.has_focus
     }
 
+    /// Request to change the cursor icon with the given priority.
+    ///
+    /// If multiple elements request a cursor icon while handling the same
+    /// event (i.e. a focused element which is being dragged and the element
+    /// under the pointer), then the request with the highest priority is
+    /// used. Among requests with the same priority, the last one is used.
+    ///
+    /// Like `cursor_icon`, the icon is reset once the cursor moves.
+    pub fn request_cursor_icon(&mut self, icon: CursorIcon, priority: i32) {
+        if self
+            .requested_cursor_icon
+            .map_or(true, |(_, prev_priority)| priority >= prev_priority)
+        {
+            self.requested_cursor_icon = Some((icon, priority));
+            self.cursor_icon = icon;
+        }
+    }
+
     /// Request to repaint this element this frame.
     ///
     /// This will also cause all child elements to be repainted.
@@ -238,16 +280,42 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
             .unwrap_or(false)
     }
 
+    /// Whether or not the point lies within the area in which this element
+    /// receives pointer events. This is the visible area expanded by the
+    /// element's hit tolerance (see [`ElementBuilder::hit_tolerance`]).
+    ///
+    /// [`ElementBuilder::hit_tolerance`]: crate::prelude::ElementBuilder::hit_tolerance
+    pub fn is_point_within_hit_bounds(&self, point: Point) -> bool {
+        self.hit_rect.map(|r| r.contains(point)).unwrap_or(false)
+    }
+
     pub fn set_rect(&mut self, rect: Rect) {
         self.requested_rect = Some(rect);
     }
 
-    pub fn send_action(&mut self, action: impl Into<A>) -> Result<(), mpsc::SendError<A>> {
-        self.action_sender.send(action)
+    /// Send an action to the application.
+    ///
+    /// If the action receiver has been dropped (i.e. the application is
+    /// shutting down), then the action is discarded.
+    pub fn send_action(&mut self, action: impl Into<A>) {
+        if self.action_sender.send(action).is_err() {
+            log::debug!(
+                "Action receiver for window {} was dropped, discarding action",
+                self.window_id
+            );
+        }
     }
 
     pub fn start_hover_timeout(&mut self) {
         self.hover_timeout_requested = true;
+        self.hover_timeout_delay = None;
+    }
+
+    /// Start the hover timeout with the given delay instead of the window's
+    /// `hover_timeout_duration`.
+    pub fn start_hover_timeout_with_delay(&mut self, delay: Duration) {
+        self.hover_timeout_requested = true;
+        self.hover_timeout_delay = Some(delay);
     }
 
     pub fn start_scroll_wheel_timeout(&mut self) {
@@ -273,6 +341,15 @@ impl<'a, A: Clone + 'static> ElementContext<'a, A> {
         self.pointer_lock_request = Some(lock);
     }
 
+    /// Tell the OS where the text cursor is, so that the IME candidate window
+    /// can be shown next to it.
+    ///
+    /// The area is relative to the top-left corner of this element's
+    /// rectangle.
+    pub fn set_ime_cursor_area(&mut self, area: Rect) {
+        self.ime_cursor_area_request = Some(area.translate(self.rect.origin.to_vector()));
+    }
+
     /// Whether or not the pointer is currently locked in place.
     pub fn is_pointer_locked(&self) -> bool {
         self.pointer_locked
@@ -331,6 +408,26 @@ pub struct RenderContext<'a, 'b> {
     pub window_size: Size,
     /// The optional global render cache.
     pub render_cache: Option<&'a mut Box<dyn ElementRenderCache>>,
+    /// The opacity assigned to this element with
+    /// [`ElementHandle::set_opacity`](super::ElementHandle::set_opacity), in
+    /// the range `[0.0, 1.0]`.
+    ///
+    /// Elements are not rendered while their opacity is `0.0`. Note, the
+    /// built-in rootvg pipelines don't support fading a whole primitive group,
+    /// so elements must apply this to the colors of their primitives. The
+    /// easiest way to do this is to get the style of the element with
+    /// [`StyleSystem::get_with_opacity`](crate::style::StyleSystem::get_with_opacity).
+    pub opacity: f32,
+    /// How far this frame is between the last tick and the next one, in the
+    /// range `[0.0, 1.0]`.
+    ///
+    /// Elements which advance a simulation once per tick (i.e. in response
+    /// to `ElementEvent::Animation`) can use this to render in between the
+    /// previous and the current state of the simulation. The length of a
+    /// tick is set by [`AppConfig::tick_timer_interval`](crate::AppConfig::tick_timer_interval),
+    /// so this is only ever non-zero when frames are rendered more often
+    /// than ticks happen. Elements that don't interpolate can ignore this.
+    pub interpolation_alpha: f32,
 
     /// The RootVG Canvas context
     ///
@@ -5,7 +5,7 @@ bitflags::bitflags! {
     ///
     /// By default all these flags are disabled.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-    pub struct ElementFlags: u16 {
+    pub struct ElementFlags: u32 {
         /// Whether or not this element paints anything to the screen.
         const PAINTS = 1 << 0;
 
@@ -66,5 +66,60 @@ bitflags::bitflags! {
         /// Whether or not this element should receive an `init` event when it gets
         /// added to the view.
         const LISTENS_TO_INIT = 1 << 12;
+
+        /// Whether or not this element "sticks" to the top edge of its assigned
+        /// scissoring rectangle when it is scrolled past, like a section header
+        /// in a scrollable list.
+        ///
+        /// The element stays pinned until the next sticky element in the same
+        /// scissoring rectangle scrolls up and pushes it out of the way.
+        const STICKY = 1 << 13;
+
+        /// Whether or not this element's primitives may be drawn outside of its
+        /// assigned scissoring rectangle, such as a focus ring or a selection
+        /// handle that sits on the edge of a scroll area.
+        ///
+        /// The element is still positioned relative to its scissoring rectangle,
+        /// and it is still only rendered while its bounds are at least partially
+        /// visible within that rectangle.
+        const DRAWS_OUTSIDE_SCISSOR_RECT = 1 << 14;
+
+        /// Whether or not this element should receive an event when the scale
+        /// factor of the window changes.
+        ///
+        /// This can be useful for elements which cache geometry that is snapped
+        /// to physical pixels.
+        const LISTENS_TO_SCALE_CHANGE = 1 << 15;
+
+        /// Whether or not this element listens to touch events which start
+        /// within the assigned rectangular area of the element.
+        ///
+        /// If the element captures the `TouchPhase::Started` event, then all
+        /// further events with the same touch ID are sent to it, even when
+        /// the touch moves outside of its bounds.
+        const LISTENS_TO_TOUCH = 1 << 16;
+
+        /// Whether or not this element can be given exclusive focus with the
+        /// Tab key (or `WindowContext::focus_next()`).
+        ///
+        /// Elements are focused in tab order, which is the order in which they
+        /// were created. Elements which are hidden are skipped.
+        const FOCUSABLE = 1 << 17;
+
+        /// Whether or not the active tooltip should stay open while the pointer
+        /// is over this element (or the gap between this element and the
+        /// element that the tooltip belongs to).
+        ///
+        /// This is used by tooltips with interactive content, so that the
+        /// pointer can be moved onto the tooltip without it closing.
+        const KEEPS_TOOLTIP_OPEN = 1 << 18;
+    }
+}
+
+impl ElementFlags {
+    /// Whether or not the element needs to be hit tested against the
+    /// pointer or touch position.
+    pub(crate) fn is_hit_tested(&self) -> bool {
+        self.intersects(Self::LISTENS_TO_POINTER_INSIDE_BOUNDS | Self::LISTENS_TO_TOUCH)
     }
 }
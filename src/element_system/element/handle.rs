@@ -1,3 +1,6 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
 use super::ElementModificationType;
 use crate::element_system::{ElementID, ElementModification};
 use crate::layout::Align2;
@@ -7,6 +10,16 @@ use crate::stmpsc_queue;
 use crate::style::ClassID;
 use crate::WindowContext;
 
+/// The unique owner of an element instance.
+///
+/// The element is dropped as soon as its handle is dropped. To share an
+/// element between several owners, use [`SharedElementHandle`] (see
+/// [`ElementHandle::into_shared`]). To keep an element alive without a
+/// handle, use [`ElementHandle::leak`].
+///
+/// The handles returned by the focus methods of [`WindowContext`] (i.e.
+/// [`WindowContext::focused_element`]) only refer to an existing element.
+/// Dropping one of those does not drop the element.
 pub struct ElementHandle {
     element_id: ElementID,
     mod_queue_sender: stmpsc_queue::Sender<ElementModification>,
@@ -15,6 +28,8 @@ pub struct ElementHandle {
     z_index: ZIndex,
     manually_hidden: bool,
     class: ClassID,
+    opacity: f32,
+    leaked: bool,
 }
 
 impl ElementHandle {
@@ -33,9 +48,19 @@ impl ElementHandle {
             z_index,
             manually_hidden,
             class,
+            opacity: 1.0,
+            leaked: false,
         }
     }
 
+    /// Turn this into a handle which refers to the element without owning
+    /// it, so that dropping it does not drop the element.
+    pub(super) fn non_owning(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self.leaked = true;
+        self
+    }
+
     /// Get the bounding rectangle of this element instance.
     ///
     /// This is cached directly in the handle so this is very cheap to call frequently.
@@ -229,6 +254,18 @@ impl ElementHandle {
         }
     }
 
+    /// Begin a batch of changes to the rectangular area of this element
+    /// instance.
+    ///
+    /// The changes are sent to the view as a single update when the returned
+    /// transaction is dropped, and only if the rectangle has changed.
+    pub fn begin_layout(&mut self) -> LayoutTransaction<'_> {
+        LayoutTransaction {
+            rect: self.rect,
+            handle: self,
+        }
+    }
+
     /// Offset the element's rectangular area.
     ///
     /// Note, this will *always* cause an element update even if the offset
@@ -262,6 +299,52 @@ impl ElementHandle {
         }
     }
 
+    /// Move this element above all of the other elements assigned to the same
+    /// scissoring rectangle by setting its z index to one more than the
+    /// highest z index among them.
+    ///
+    /// If this element is already above all of them, then its z index is
+    /// left unchanged. If the highest z index is already `ZIndex::MAX`, then
+    /// this element is painted above the other elements with that z index
+    /// only if it was created after them.
+    ///
+    /// Returns the new z index.
+    pub fn bring_to_front<A: Clone + 'static>(
+        &mut self,
+        window_cx: &mut WindowContext<'_, A>,
+    ) -> ZIndex {
+        if let Some((_, max)) = window_cx.sibling_z_index_extents(self) {
+            if self.z_index <= max {
+                self.set_z_index(max.saturating_add(1));
+            }
+        }
+
+        self.z_index
+    }
+
+    /// Move this element below all of the other elements assigned to the same
+    /// scissoring rectangle by setting its z index to one less than the
+    /// lowest z index among them.
+    ///
+    /// If this element is already below all of them, then its z index is
+    /// left unchanged. If the lowest z index is already `ZIndex::MIN`, then
+    /// this element is painted below the other elements with that z index
+    /// only if it was created before them.
+    ///
+    /// Returns the new z index.
+    pub fn send_to_back<A: Clone + 'static>(
+        &mut self,
+        window_cx: &mut WindowContext<'_, A>,
+    ) -> ZIndex {
+        if let Some((min, _)) = window_cx.sibling_z_index_extents(self) {
+            if self.z_index >= min {
+                self.set_z_index(min.saturating_sub(1));
+            }
+        }
+
+        self.z_index
+    }
+
     /// Set to hide or show this element instance.
     ///
     /// Note, there is no need to hide elements just because they appear outside
@@ -287,6 +370,45 @@ impl ElementHandle {
         }
     }
 
+    /// The opacity of this element instance in the range `[0.0, 1.0]`.
+    ///
+    /// This is cached directly in the handle so this is very cheap to call frequently.
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Set the opacity of this element instance in the range `[0.0, 1.0]`,
+    /// i.e. to fade it in or out.
+    ///
+    /// An element with an opacity of `0.0` is not painted at all, but it is
+    /// still hit tested. The opacity is passed to the element in
+    /// [`RenderContext::opacity`](super::RenderContext::opacity).
+    ///
+    /// Note, the opacity is *NOT* applied to the primitives of the element
+    /// by the view, since rootvg can't fade a whole primitive group. The
+    /// element itself must apply it to the colors of its primitives. All of
+    /// the built-in elements do this, but a custom element which ignores
+    /// `RenderContext::opacity` is drawn fully opaque at any opacity above
+    /// `0.0`.
+    ///
+    /// Returns `true` if the opacity has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is very cheap to call frequently.
+    pub fn set_opacity(&mut self, opacity: f32) -> bool {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if self.opacity != opacity {
+            self.opacity = opacity;
+            self.mod_queue_sender.send(ElementModification {
+                element_id: self.element_id,
+                type_: ElementModificationType::OpacityChanged(opacity),
+            });
+            true
+        } else {
+            false
+        }
+    }
+
     /// Show a tooltip on the element
     ///
     /// * `text` - The tooltip text
@@ -297,15 +419,24 @@ impl ElementHandle {
         self.mod_queue_sender.send(ElementModification {
             element_id: self.element_id,
             type_: ElementModificationType::ShowTooltip {
-                data: TooltipData {
-                    text: text.into(),
-                    align,
-                },
+                data: TooltipData::new(text, align),
                 auto_hide,
             },
         })
     }
 
+    /// Show a tooltip with rich content (i.e. a description line) on the
+    /// element
+    ///
+    /// * `auto_hide` - Whether or not the tooltip should automatically hide when
+    /// the mouse pointer is no longer over the element.
+    pub fn show_tooltip_data(&mut self, data: TooltipData, auto_hide: bool) {
+        self.mod_queue_sender.send(ElementModification {
+            element_id: self.element_id,
+            type_: ElementModificationType::ShowTooltip { data, auto_hide },
+        })
+    }
+
     /// The current style class of the element.
     ///
     /// This is cached directly in the handle so this is very cheap to call frequently.
@@ -352,16 +483,198 @@ impl ElementHandle {
         window_cx.element_rect(self).unwrap()
     }
 
-    pub(crate) fn id(&self) -> ElementID {
+    /// Whether or not the pointer is currently over this element.
+    pub fn is_hovered<A: Clone + 'static>(&self, window_cx: &WindowContext<'_, A>) -> bool {
+        window_cx.element_is_hovered(self)
+    }
+
+    /// Whether or not this element currently has exclusive focus.
+    pub fn is_focused<A: Clone + 'static>(&self, window_cx: &WindowContext<'_, A>) -> bool {
+        window_cx.element_is_focused(self)
+    }
+
+    /// The unique identifier of this element instance.
+    pub fn id(&self) -> ElementID {
+        self.element_id
+    }
+
+    /// Detach the lifetime of the element from this handle.
+    ///
+    /// The element is no longer dropped with this handle. Instead it lives
+    /// until the window is closed or all of its elements are dropped with
+    /// [`WindowContext::clear_elements`].
+    ///
+    /// Note, the handle is the only way to change an element, so a leaked
+    /// element can no longer be moved, hidden, or updated. The returned ID
+    /// can only be used to identify the element, i.e. in
+    /// [`WindowContext::iter_elements`] or [`WindowContext::focused_element`].
+    /// Only leak elements which never change, such as static decorations.
+    pub fn leak(mut self) -> ElementID {
+        self.leaked = true;
         self.element_id
     }
+
+    /// Turn this handle into a reference-counted handle, so that the element
+    /// is only dropped once every clone of the handle is dropped.
+    pub fn into_shared(self) -> SharedElementHandle {
+        SharedElementHandle(Rc::new(RefCell::new(self)))
+    }
+
+    /// A notifier which can send a custom state change to this element
+    /// without owning the handle (i.e. from inside another element).
+    pub(crate) fn custom_state_notifier(&self) -> CustomStateNotifier {
+        CustomStateNotifier {
+            element_id: self.element_id,
+            mod_queue_sender: self.mod_queue_sender.clone(),
+        }
+    }
+}
+
+/// A batch of changes to the rectangular area of an element instance.
+///
+/// This is obtained from [`ElementHandle::begin_layout`]. The new rectangle
+/// is sent to the view as a single update when this is dropped.
+pub struct LayoutTransaction<'a> {
+    handle: &'a mut ElementHandle,
+    rect: Rect,
+}
+
+impl<'a> LayoutTransaction<'a> {
+    /// The rectangular area with all of the changes made so far.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn set_rect(&mut self, rect: Rect) -> &mut Self {
+        self.rect = rect;
+        self
+    }
+
+    pub fn set_pos(&mut self, pos: Point) -> &mut Self {
+        self.rect.origin = pos;
+        self
+    }
+
+    pub fn set_size(&mut self, size: Size) -> &mut Self {
+        self.rect.size = size;
+        self
+    }
+
+    pub fn set_x(&mut self, x: f32) -> &mut Self {
+        self.rect.origin.x = x;
+        self
+    }
+
+    pub fn set_y(&mut self, y: f32) -> &mut Self {
+        self.rect.origin.y = y;
+        self
+    }
+
+    pub fn set_width(&mut self, width: f32) -> &mut Self {
+        self.rect.size.width = width;
+        self
+    }
+
+    pub fn set_height(&mut self, height: f32) -> &mut Self {
+        self.rect.size.height = height;
+        self
+    }
+
+    pub fn offset_pos(&mut self, offset: Vector) -> &mut Self {
+        self.rect.origin += offset;
+        self
+    }
+
+    /// Send the changes to the view now instead of when this is dropped.
+    ///
+    /// Returns `true` if the rectangle has changed.
+    pub fn commit(self) -> bool {
+        self.handle.set_rect(self.rect)
+    }
+}
+
+impl<'a> Drop for LayoutTransaction<'a> {
+    fn drop(&mut self) {
+        self.handle.set_rect(self.rect);
+    }
+}
+
+/// Notifies an element that its custom state has changed.
+///
+/// Unlike [`ElementHandle`], dropping this does not drop the element. If the
+/// element has already been dropped then notifying it does nothing.
+#[derive(Clone)]
+pub(crate) struct CustomStateNotifier {
+    element_id: ElementID,
+    mod_queue_sender: stmpsc_queue::Sender<ElementModification>,
+}
+
+impl CustomStateNotifier {
+    pub fn notify(&mut self) {
+        self.mod_queue_sender.send(ElementModification {
+            element_id: self.element_id,
+            type_: ElementModificationType::CustomStateChanged,
+        });
+    }
 }
 
 impl Drop for ElementHandle {
     fn drop(&mut self) {
+        if self.leaked {
+            return;
+        }
+
         self.mod_queue_sender.send(ElementModification {
             element_id: self.element_id,
             type_: ElementModificationType::HandleDropped,
         });
     }
 }
+
+/// A reference-counted [`ElementHandle`].
+///
+/// Cloning this handle does not clone the element. The element is only
+/// dropped once the last clone of this handle is dropped.
+#[derive(Clone)]
+pub struct SharedElementHandle(Rc<RefCell<ElementHandle>>);
+
+impl SharedElementHandle {
+    pub fn new(handle: ElementHandle) -> Self {
+        handle.into_shared()
+    }
+
+    /// The unique identifier of the element instance.
+    pub fn id(&self) -> ElementID {
+        RefCell::borrow(&self.0).id()
+    }
+
+    /// Borrow the inner handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inner handle is currently mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, ElementHandle> {
+        RefCell::borrow(&self.0)
+    }
+
+    /// Mutably borrow the inner handle, i.e. to set its rectangle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inner handle is currently borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, ElementHandle> {
+        RefCell::borrow_mut(&self.0)
+    }
+
+    /// The number of clones of this handle which are keeping the element
+    /// alive.
+    pub fn handle_count(&self) -> usize {
+        Rc::strong_count(&self.0)
+    }
+}
+
+impl From<ElementHandle> for SharedElementHandle {
+    fn from(handle: ElementHandle) -> Self {
+        handle.into_shared()
+    }
+}
@@ -0,0 +1,185 @@
+use rootvg::color::PackedSrgb;
+use rootvg::math::{PhysicalSizeI32, ScaleFactor};
+use rootvg::surface::DefaultSurface;
+use rootvg::text::FontSystem;
+
+use crate::prelude::ResourceCtx;
+
+#[cfg(feature = "headless")]
+mod headless;
+#[cfg(feature = "headless")]
+pub use headless::{HeadlessRenderBackend, HeadlessRenderBackendError, ImageBuffer};
+
+/// The canvas (and GPU context) that the primitives of a frame are recorded
+/// into.
+pub struct RenderBackendCtx<'a> {
+    /// The RootVG canvas
+    pub canvas: &'a mut rootvg::Canvas,
+
+    /// The wgpu device
+    #[cfg(feature = "custom-shaders")]
+    pub device: &'a wgpu::Device,
+    /// The wgpu queue
+    #[cfg(feature = "custom-shaders")]
+    pub queue: &'a wgpu::Queue,
+    /// The wgpu texture format
+    #[cfg(feature = "custom-shaders")]
+    pub texture_format: wgpu::TextureFormat,
+    /// The wgpu multisample state
+    #[cfg(feature = "custom-shaders")]
+    pub multisample: wgpu::MultisampleState,
+}
+
+/// The backend that a window is rendered with.
+///
+/// A frame is rendered in this order:
+/// 1. [`RenderBackend::begin_frame`] acquires the target to render into.
+/// 2. The elements record their primitives into the canvas returned by
+/// [`RenderBackend::ctx`].
+/// 3. [`RenderBackend::render_frame`] renders the recorded primitives into
+/// the target.
+/// 4. [`RenderBackend::present_frame`] presents the target.
+pub trait RenderBackend {
+    type Error;
+
+    /// Acquire the target texture for the next frame.
+    fn begin_frame(&mut self) -> Result<(), Self::Error>;
+
+    /// The canvas that the primitives of the current frame are recorded into.
+    fn ctx(&mut self) -> RenderBackendCtx<'_>;
+
+    /// Render the recorded primitives into the target acquired in
+    /// [`RenderBackend::begin_frame`].
+    ///
+    /// If `clear_color` is `None`, then the target must not be cleared
+    /// before rendering.
+    fn render_frame(
+        &mut self,
+        clear_color: Option<PackedSrgb>,
+        physical_size: PhysicalSizeI32,
+        res: &mut ResourceCtx,
+    ) -> Result<(), Self::Error>;
+
+    /// Present the rendered frame.
+    ///
+    /// `pre_present_notify` must be called right before the frame is
+    /// presented.
+    fn present_frame<P: FnOnce()>(&mut self, pre_present_notify: P);
+}
+
+struct WgpuFrame {
+    texture: wgpu::SurfaceTexture,
+    view: wgpu::TextureView,
+    encoder: wgpu::CommandEncoder,
+}
+
+/// The default render backend which renders to a wgpu surface.
+pub(crate) struct WgpuRenderBackend {
+    surface: DefaultSurface<'static>,
+    canvas: rootvg::Canvas,
+    #[cfg_attr(not(feature = "custom-shaders"), allow(unused))]
+    multisample: wgpu::MultisampleState,
+    frame: Option<WgpuFrame>,
+}
+
+impl WgpuRenderBackend {
+    pub fn new(surface: DefaultSurface<'static>, font_system: &mut FontSystem) -> Self {
+        let canvas_config = surface.canvas_config();
+
+        let canvas = rootvg::Canvas::new(
+            &surface.device,
+            &surface.queue,
+            surface.format(),
+            canvas_config,
+            font_system,
+        );
+
+        Self {
+            surface,
+            canvas,
+            multisample: canvas_config.multisample,
+            frame: None,
+        }
+    }
+
+    pub fn resize(&mut self, physical_size: PhysicalSizeI32, scale_factor: ScaleFactor) {
+        self.surface.resize(physical_size, scale_factor);
+    }
+}
+
+impl RenderBackend for WgpuRenderBackend {
+    type Error = wgpu::SurfaceError;
+
+    fn begin_frame(&mut self) -> Result<(), Self::Error> {
+        let texture = self.surface.surface.get_current_texture()?;
+        let view = texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let encoder = self
+            .surface
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        self.frame = Some(WgpuFrame {
+            texture,
+            view,
+            encoder,
+        });
+
+        Ok(())
+    }
+
+    fn ctx(&mut self) -> RenderBackendCtx<'_> {
+        RenderBackendCtx {
+            canvas: &mut self.canvas,
+            #[cfg(feature = "custom-shaders")]
+            device: &self.surface.device,
+            #[cfg(feature = "custom-shaders")]
+            queue: &self.surface.queue,
+            #[cfg(feature = "custom-shaders")]
+            texture_format: self.surface.format(),
+            #[cfg(feature = "custom-shaders")]
+            multisample: self.multisample,
+        }
+    }
+
+    fn render_frame(
+        &mut self,
+        clear_color: Option<PackedSrgb>,
+        physical_size: PhysicalSizeI32,
+        res: &mut ResourceCtx,
+    ) -> Result<(), Self::Error> {
+        let frame = self
+            .frame
+            .as_mut()
+            .expect("begin_frame must be called before render_frame");
+
+        self.canvas
+            .render_to_target(
+                clear_color,
+                &self.surface.device,
+                &self.surface.queue,
+                &mut frame.encoder,
+                &frame.view,
+                physical_size,
+                &mut res.font_system,
+                #[cfg(feature = "svg-icons")]
+                &mut res.svg_icon_system,
+            )
+            .unwrap(); // TODO: handle this error properly.
+
+        Ok(())
+    }
+
+    fn present_frame<P: FnOnce()>(&mut self, pre_present_notify: P) {
+        let Some(frame) = self.frame.take() else {
+            return;
+        };
+
+        pre_present_notify();
+
+        // Submit the commands and present the frame.
+        self.surface.queue.submit(Some(frame.encoder.finish()));
+        frame.texture.present();
+    }
+}
@@ -0,0 +1,393 @@
+use rootvg::color::PackedSrgb;
+use rootvg::math::PhysicalSizeI32;
+use rootvg::text::FontSystem;
+
+use crate::prelude::ResourceCtx;
+
+use super::{RenderBackend, RenderBackendCtx};
+
+/// The format of the offscreen target. The captured pixels are always RGBA8.
+#[cfg(feature = "web-colors")]
+const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+#[cfg(not(feature = "web-colors"))]
+const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// An error that occurred while creating a [`HeadlessRenderBackend`] or
+/// while capturing a frame.
+#[derive(Debug, thiserror::Error)]
+pub enum HeadlessRenderBackendError {
+    #[error("No suitable graphics adapter was found")]
+    NoAdapter,
+    #[error("{0}")]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+    #[error("Failed to read back the rendered frame: {0}")]
+    Capture(#[from] wgpu::BufferAsyncError),
+}
+
+/// The pixels of a captured frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBuffer {
+    /// The size of the image in physical pixels.
+    pub size: PhysicalSizeI32,
+    /// The pixels in RGBA8 format, row by row starting from the top-left
+    /// corner.
+    pub data: Vec<u8>,
+}
+
+impl ImageBuffer {
+    /// The RGBA value of the pixel at the given physical coordinates.
+    ///
+    /// Returns `None` if the coordinates lie outside of the image.
+    pub fn pixel(&self, x: i32, y: i32) -> Option<[u8; 4]> {
+        if x < 0 || y < 0 || x >= self.size.width || y >= self.size.height {
+            return None;
+        }
+
+        let i = ((y * self.size.width + x) * 4) as usize;
+        self.data.get(i..i + 4).map(|p| [p[0], p[1], p[2], p[3]])
+    }
+}
+
+struct HeadlessFrame {
+    view: wgpu::TextureView,
+    encoder: wgpu::CommandEncoder,
+}
+
+/// A render backend which renders into an offscreen texture instead of a
+/// window surface.
+///
+/// This is useful for CI and snapshot tests. Use [`HeadlessRenderBackend::capture`]
+/// to read back the pixels of the last rendered frame.
+pub struct HeadlessRenderBackend {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    canvas: rootvg::Canvas,
+    #[cfg_attr(not(feature = "custom-shaders"), allow(unused))]
+    multisample: wgpu::MultisampleState,
+    texture: wgpu::Texture,
+    physical_size: PhysicalSizeI32,
+    frame: Option<HeadlessFrame>,
+    last_frame_cleared: bool,
+}
+
+impl HeadlessRenderBackend {
+    /// Create a new headless render backend using any available graphics
+    /// adapter (including software adapters).
+    pub fn new(
+        physical_size: PhysicalSizeI32,
+        font_system: &mut FontSystem,
+    ) -> Result<Self, HeadlessRenderBackendError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        }))
+        .ok_or(HeadlessRenderBackendError::NoAdapter)?;
+
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))?;
+
+        let canvas_config = rootvg::CanvasConfig::default();
+
+        let canvas =
+            rootvg::Canvas::new(&device, &queue, TEXTURE_FORMAT, canvas_config, font_system);
+
+        let texture = create_texture(&device, physical_size);
+
+        Ok(Self {
+            device,
+            queue,
+            canvas,
+            multisample: canvas_config.multisample,
+            texture,
+            physical_size,
+            frame: None,
+            last_frame_cleared: false,
+        })
+    }
+
+    pub fn resize(&mut self, physical_size: PhysicalSizeI32) {
+        if self.physical_size == physical_size {
+            return;
+        }
+
+        self.physical_size = physical_size;
+        self.texture = create_texture(&self.device, physical_size);
+    }
+
+    /// Whether or not the target was cleared before the last frame was
+    /// rendered.
+    pub fn last_frame_cleared(&self) -> bool {
+        self.last_frame_cleared
+    }
+
+    /// Read back the pixels of the last rendered frame.
+    ///
+    /// This blocks until the GPU has finished rendering.
+    pub fn capture(&mut self) -> Result<ImageBuffer, HeadlessRenderBackendError> {
+        let size = self.texture.size();
+
+        let unpadded_bytes_per_row = size.width * 4;
+        let padded_bytes_per_row = unpadded_bytes_per_row
+            .div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("yarrow headless capture buffer"),
+            size: u64::from(padded_bytes_per_row) * u64::from(size.height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(size.height),
+                },
+            },
+            size,
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        let _ = self.device.poll(wgpu::Maintain::Wait);
+
+        // The callback is dropped without being called if the device is lost.
+        receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+        let mut data = Vec::with_capacity((unpadded_bytes_per_row * size.height) as usize);
+        {
+            let mapped = slice.get_mapped_range();
+            for row in mapped.chunks(padded_bytes_per_row as usize) {
+                data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        Ok(ImageBuffer {
+            size: PhysicalSizeI32::new(size.width as i32, size.height as i32),
+            data,
+        })
+    }
+}
+
+impl RenderBackend for HeadlessRenderBackend {
+    type Error = std::convert::Infallible;
+
+    fn begin_frame(&mut self) -> Result<(), Self::Error> {
+        let view = self
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        self.frame = Some(HeadlessFrame { view, encoder });
+
+        Ok(())
+    }
+
+    fn ctx(&mut self) -> RenderBackendCtx<'_> {
+        RenderBackendCtx {
+            canvas: &mut self.canvas,
+            #[cfg(feature = "custom-shaders")]
+            device: &self.device,
+            #[cfg(feature = "custom-shaders")]
+            queue: &self.queue,
+            #[cfg(feature = "custom-shaders")]
+            texture_format: TEXTURE_FORMAT,
+            #[cfg(feature = "custom-shaders")]
+            multisample: self.multisample,
+        }
+    }
+
+    fn render_frame(
+        &mut self,
+        clear_color: Option<PackedSrgb>,
+        physical_size: PhysicalSizeI32,
+        res: &mut ResourceCtx,
+    ) -> Result<(), Self::Error> {
+        let frame = self
+            .frame
+            .as_mut()
+            .expect("begin_frame must be called before render_frame");
+
+        self.last_frame_cleared = clear_color.is_some();
+
+        self.canvas
+            .render_to_target(
+                clear_color,
+                &self.device,
+                &self.queue,
+                &mut frame.encoder,
+                &frame.view,
+                physical_size,
+                &mut res.font_system,
+                #[cfg(feature = "svg-icons")]
+                &mut res.svg_icon_system,
+            )
+            .unwrap(); // TODO: handle this error properly.
+
+        Ok(())
+    }
+
+    fn present_frame<P: FnOnce()>(&mut self, pre_present_notify: P) {
+        let Some(frame) = self.frame.take() else {
+            return;
+        };
+
+        pre_present_notify();
+
+        // There is nothing to present, just submit the commands.
+        self.queue.submit(Some(frame.encoder.finish()));
+    }
+}
+
+fn create_texture(device: &wgpu::Device, physical_size: PhysicalSizeI32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("yarrow headless target"),
+        size: wgpu::Extent3d {
+            width: physical_size.width.max(1) as u32,
+            height: physical_size.height.max(1) as u32,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: TEXTURE_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rootvg::color::RGBA8;
+    use std::time::Duration;
+
+    use crate::action_channel;
+    use crate::element_system::{ElementSystem, ElementSystemConfig};
+    use crate::style::StyleSystem;
+
+    use super::*;
+
+    #[test]
+    fn test_image_buffer_pixel() {
+        let image = ImageBuffer {
+            size: PhysicalSizeI32::new(2, 2),
+            data: vec![
+                1, 2, 3, 4, 5, 6, 7, 8, //
+                9, 10, 11, 12, 13, 14, 15, 16,
+            ],
+        };
+
+        assert_eq!(image.pixel(0, 0), Some([1, 2, 3, 4]));
+        assert_eq!(image.pixel(1, 0), Some([5, 6, 7, 8]));
+        assert_eq!(image.pixel(0, 1), Some([9, 10, 11, 12]));
+        assert_eq!(image.pixel(1, 1), Some([13, 14, 15, 16]));
+        assert_eq!(image.pixel(2, 0), None);
+        assert_eq!(image.pixel(0, -1), None);
+    }
+
+    /// An element system with no elements which renders into a headless
+    /// backend.
+    fn headless_fixture(
+        physical_size: PhysicalSizeI32,
+        skip_clear: bool,
+    ) -> (ResourceCtx, HeadlessRenderBackend, ElementSystem<()>) {
+        let mut res = ResourceCtx {
+            style_system: StyleSystem::new(true),
+            font_system: FontSystem::new(),
+            #[cfg(feature = "svg-icons")]
+            svg_icon_system: Default::default(),
+        };
+
+        let backend = HeadlessRenderBackend::new(physical_size, &mut res.font_system).unwrap();
+
+        let (action_sender, _action_receiver) = action_channel::<()>();
+        let element_system = ElementSystem::new(
+            physical_size,
+            1.0f64.into(),
+            ElementSystemConfig {
+                clear_color: PackedSrgb::BLACK,
+                skip_clear,
+                preallocate_for_this_many_elements: 0,
+                hover_timeout_duration: Duration::from_millis(500),
+                scroll_wheel_timeout_duration: Duration::from_millis(250),
+                long_press_duration: Duration::from_millis(500),
+            },
+            action_sender,
+            0,
+        );
+
+        (res, backend, element_system)
+    }
+
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn test_headless_backend_captures_clear_color() {
+        let physical_size = PhysicalSizeI32::new(16, 8);
+        let (mut res, mut backend, mut element_system) = headless_fixture(physical_size, false);
+
+        element_system
+            .render(&mut backend, || {}, &mut res)
+            .unwrap();
+
+        let image = backend.capture().unwrap();
+
+        let stats = element_system.last_render_stats();
+        assert_eq!(stats.elements_painted, 0);
+        assert_eq!(stats.dirty_region_area, 0.0);
+
+        assert!(backend.last_frame_cleared());
+        assert_eq!(image.size, physical_size);
+        assert_eq!(image.data.len(), 16 * 8 * 4);
+        assert!(image.data.chunks(4).all(|p| p == [0, 0, 0, 255]));
+    }
+
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn test_headless_backend_skip_clear_keeps_the_previous_frame() {
+        let physical_size = PhysicalSizeI32::new(16, 8);
+        let (mut res, mut backend, mut element_system) = headless_fixture(physical_size, false);
+
+        element_system
+            .render(&mut backend, || {}, &mut res)
+            .unwrap();
+        assert!(backend.last_frame_cleared());
+
+        // The previous black frame shows through instead of the new clear
+        // color.
+        element_system.clear_color = RGBA8::new(255, 255, 255, 255).into();
+        element_system.skip_clear = true;
+        element_system.needs_repaint = true;
+        element_system
+            .render(&mut backend, || {}, &mut res)
+            .unwrap();
+        assert!(!backend.last_frame_cleared());
+        let image = backend.capture().unwrap();
+        assert!(image.data.chunks(4).all(|p| p == [0, 0, 0, 255]));
+
+        element_system.skip_clear = false;
+        element_system.needs_repaint = true;
+        element_system
+            .render(&mut backend, || {}, &mut res)
+            .unwrap();
+        assert!(backend.last_frame_cleared());
+        let image = backend.capture().unwrap();
+        assert!(image.data.chunks(4).all(|p| p == [255, 255, 255, 255]));
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+/// Statistics about the last frame that was rendered to a window.
+///
+/// This is a developer tool for finding out why a frame is slow to render.
+/// See [`WindowContext::last_render_stats`](crate::WindowContext::last_render_stats).
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct RenderStats {
+    /// The number of elements whose primitives were out of date and were
+    /// rendered again.
+    pub elements_painted: usize,
+    /// The number of painted elements whose size changed since the last time
+    /// they were painted (including elements which were painted for the
+    /// first time).
+    pub elements_reshaped: usize,
+    /// How long it took the render backend to render the recorded
+    /// primitives into the target.
+    pub frame_duration: Duration,
+    /// The total area of the visible rectangles of the painted elements, in
+    /// logical points.
+    ///
+    /// Overlapping elements are counted more than once.
+    pub dirty_region_area: f32,
+}
@@ -4,7 +4,7 @@ use thunderdome::Arena;
 
 use super::{ElementEntry, ElementID, EntryStackData};
 use crate::element_system::element::{ElementModification, ElementModificationType};
-use crate::math::{PointI32, RectI32, Vector};
+use crate::math::{Point, PointI32, Rect, RectI32, Transform, Vector, ZIndex};
 use crate::stmpsc_queue;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -22,10 +22,106 @@ impl Default for ScissorRectID {
     }
 }
 
+/// What happens when tab traversal reaches the end of a focus scope.
+///
+/// See [`WindowContext::set_focus_wrap_mode`](crate::WindowContext::set_focus_wrap_mode).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FocusWrapMode {
+    /// Focus wraps around from the last element to the first (and from the
+    /// first to the last in reverse).
+    #[default]
+    Wrap,
+    /// Focus stops at the ends. For the window this leaves focus unchanged,
+    /// and for any other scope focus moves to the next element outside of
+    /// it.
+    Stop,
+}
+
+/// The scale and the translation of a [`Transform`], which is the part of it
+/// that keeps rectangles axis-aligned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct AxisTransform {
+    pub scale: Vector,
+    pub translation: Vector,
+}
+
+impl AxisTransform {
+    pub const IDENTITY: Self = Self {
+        scale: Vector::new(1.0, 1.0),
+        translation: Vector::new(0.0, 0.0),
+    };
+
+    /// The scale and the translation of the given transform, applied relative
+    /// to `origin` instead of relative to `(0, 0)`.
+    pub fn new(transform: &Transform, origin: Point) -> Self {
+        Self {
+            scale: Vector::new(transform.m11, transform.m22),
+            translation: Vector::new(
+                origin.x * (1.0 - transform.m11) + transform.m31,
+                origin.y * (1.0 - transform.m22) + transform.m32,
+            ),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// This transform followed by `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            scale: Vector::new(self.scale.x * other.scale.x, self.scale.y * other.scale.y),
+            translation: Vector::new(
+                self.translation.x * other.scale.x + other.translation.x,
+                self.translation.y * other.scale.y + other.translation.y,
+            ),
+        }
+    }
+
+    pub fn transform_point(&self, point: Point) -> Point {
+        Point::new(
+            point.x * self.scale.x + self.translation.x,
+            point.y * self.scale.y + self.translation.y,
+        )
+    }
+
+    /// Transform the given rectangle. A negative scale flips the rectangle,
+    /// so the result is normalized.
+    pub fn transform_rect(&self, rect: Rect) -> Rect {
+        let a = self.transform_point(rect.min());
+        let b = self.transform_point(rect.max());
+        Rect::new(a.min(b), (a.max(b) - a.min(b)).to_size())
+    }
+}
+
 pub(super) struct ScissorRect {
     rect: RectI32,
     scroll_offset: Vector,
     assigned_elements: Vec<ElementID>,
+    /// The sticky elements assigned to this scissoring rectangle, along with
+    /// their y offset from the origin of the rectangle (before scrolling).
+    sticky_elements: Vec<(ElementID, f32)>,
+    /// If this is `Some`, then tab traversal is scoped to the elements
+    /// assigned to this scissoring rectangle.
+    pub focus_wrap_mode: Option<FocusWrapMode>,
+    /// The offset added to the z index of every element assigned to this
+    /// scissoring rectangle.
+    pub base_z_index: ZIndex,
+    /// The index of the scissoring rectangle that this one is nested in.
+    pub parent: Option<usize>,
+    /// The opacity applied to every element assigned to this scissoring
+    /// rectangle (and to the scissoring rectangles nested in it).
+    pub opacity: f32,
+    /// The transform that this scissoring rectangle and its elements are laid
+    /// out with, relative to the origin of the rectangle. Only the scale and
+    /// the translation are used.
+    pub transform: Transform,
+    /// If this scissoring rectangle has a parent or a transform, then this is
+    /// its rectangle in window coordinates (before it is transformed), the
+    /// rectangle it clips to (the intersection with the clipping rectangles
+    /// of its ancestors), and its transform composed with the transforms of
+    /// its ancestors.
+    resolved: Option<(RectI32, RectI32, AxisTransform)>,
 }
 
 impl ScissorRect {
@@ -37,13 +133,71 @@ impl ScissorRect {
             rect,
             scroll_offset,
             assigned_elements: Vec::new(),
+            sticky_elements: Vec::new(),
+            focus_wrap_mode: None,
+            base_z_index: 0,
+            parent: None,
+            opacity: 1.0,
+            transform: Transform::identity(),
+            resolved: None,
         }
     }
 
+    /// The rectangle as it was set. If this scissoring rectangle has a parent,
+    /// then this is relative to the content of the parent.
     pub fn rect(&self) -> RectI32 {
         self.rect
     }
 
+    /// The rectangle in window coordinates.
+    pub fn window_rect(&self) -> RectI32 {
+        self.resolved
+            .map_or(self.rect, |(window_rect, _, _)| window_rect)
+    }
+
+    /// The rectangle that the elements assigned to this scissoring rectangle
+    /// are clipped to, in window coordinates.
+    pub fn clip_rect(&self) -> RectI32 {
+        self.resolved
+            .map_or(self.rect, |(_, clip_rect, _)| clip_rect)
+    }
+
+    /// The transform from window coordinates to where the elements assigned
+    /// to this scissoring rectangle are laid out, or `None` if neither this
+    /// scissoring rectangle nor any of its ancestors are transformed.
+    pub fn window_transform(&self) -> Option<AxisTransform> {
+        self.resolved
+            .map(|(_, _, transform)| transform)
+            .filter(|transform| !transform.is_identity())
+    }
+
+    /// Set the rectangle in window coordinates, the clipping rectangle, and
+    /// the transform resolved from the ancestors of this scissoring
+    /// rectangle, or `None` if it has no parent and no transform.
+    ///
+    /// Returns `true` if any of them has changed.
+    pub fn set_resolved(&mut self, resolved: Option<(RectI32, RectI32, AxisTransform)>) -> bool {
+        let changed = self.window_rect() != resolved.map_or(self.rect, |r| r.0)
+            || self.clip_rect() != resolved.map_or(self.rect, |r| r.1)
+            || self.window_transform() != resolved.map(|r| r.2).filter(|t| !t.is_identity());
+        self.resolved = resolved;
+        changed
+    }
+
+    /// Notify every element assigned to this scissoring rectangle that it has
+    /// changed.
+    pub fn notify_elements(
+        &self,
+        mod_queue_sender: &mut stmpsc_queue::Sender<ElementModification>,
+    ) {
+        for element_id in self.assigned_elements.iter() {
+            mod_queue_sender.send(ElementModification {
+                element_id: *element_id,
+                type_: ElementModificationType::ScissorRectChanged,
+            });
+        }
+    }
+
     /// Returns `true` if the rect changed, `false` otherwise.
     ///
     /// # Panics
@@ -75,19 +229,33 @@ impl ScissorRect {
         }
 
         if changed {
-            for element_id in self.assigned_elements.iter() {
-                mod_queue_sender.send(ElementModification {
-                    element_id: *element_id,
-                    type_: ElementModificationType::ScissorRectChanged,
-                });
-            }
+            self.notify_elements(mod_queue_sender);
         }
 
         changed
     }
 
+    /// The z index of an element with the given z index in this scissoring
+    /// rectangle.
+    pub fn z_index_of(&self, element_z_index: ZIndex) -> ZIndex {
+        self.base_z_index.saturating_add(element_z_index)
+    }
+
+    pub fn assigned_elements(&self) -> &[ElementID] {
+        &self.assigned_elements
+    }
+
+    /// The origin of the rectangle in window coordinates.
     pub fn origin(&self) -> PointI32 {
-        self.rect.origin
+        self.window_rect().origin
+    }
+
+    /// The origin of the rectangle in window coordinates, with the transforms
+    /// of this scissoring rectangle and its ancestors applied.
+    pub fn transformed_origin(&self) -> Point {
+        let origin = self.origin().cast();
+        self.window_transform()
+            .map_or(origin, |transform| transform.transform_point(origin))
     }
 
     pub fn scroll_offset(&self) -> Vector {
@@ -100,6 +268,68 @@ impl ScissorRect {
         self.assigned_elements.push(element_id);
     }
 
+    /// Add a sticky element to this scissoring rectangle, or update its offset
+    /// if it was already added.
+    pub fn set_sticky_element(
+        &mut self,
+        element_id: ElementID,
+        offset_y: f32,
+        mod_queue_sender: &mut stmpsc_queue::Sender<ElementModification>,
+    ) {
+        if let Some(entry) = self
+            .sticky_elements
+            .iter_mut()
+            .find(|(id, _)| *id == element_id)
+        {
+            if entry.1 == offset_y {
+                return;
+            }
+            entry.1 = offset_y;
+        } else {
+            self.sticky_elements.push((element_id, offset_y));
+        }
+
+        self.notify_sticky_elements(mod_queue_sender);
+    }
+
+    pub fn remove_sticky_element(
+        &mut self,
+        element_id: ElementID,
+        mod_queue_sender: &mut stmpsc_queue::Sender<ElementModification>,
+    ) {
+        let prev_len = self.sticky_elements.len();
+        self.sticky_elements.retain(|(id, _)| *id != element_id);
+
+        if self.sticky_elements.len() != prev_len {
+            self.notify_sticky_elements(mod_queue_sender);
+        }
+    }
+
+    /// The offset of the first sticky element below the given offset. This is
+    /// the element which pushes the sticky element at `offset_y` out of the
+    /// way.
+    pub fn next_sticky_offset(&self, offset_y: f32) -> Option<f32> {
+        self.sticky_elements
+            .iter()
+            .map(|(_, y)| *y)
+            .filter(|y| *y > offset_y)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    /// The position of a sticky element depends on the position of the other
+    /// sticky elements, so relayout all of them.
+    fn notify_sticky_elements(
+        &self,
+        mod_queue_sender: &mut stmpsc_queue::Sender<ElementModification>,
+    ) {
+        for (element_id, _) in self.sticky_elements.iter() {
+            mod_queue_sender.send(ElementModification {
+                element_id: *element_id,
+                type_: ElementModificationType::ScissorRectChanged,
+            });
+        }
+    }
+
     pub fn remove_element<A: Clone + 'static>(
         &mut self,
         entry_stack_data: &EntryStackData,
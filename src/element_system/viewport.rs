@@ -0,0 +1,79 @@
+use rootvg::color::PackedSrgb;
+
+use crate::math::{to_logical_size_i32, PhysicalSizeI32, PointI32, RectI32, ScaleFactor, Vector};
+
+use super::scissor_rect::{ScissorRect, ScissorRectID};
+
+/// A secondary render target which shows the contents of a single
+/// scissoring rectangle, i.e. a detachable preview pane.
+///
+/// The elements are not rendered again for the viewport. The primitives
+/// that were recorded for the main window are reused, so rendering a
+/// viewport is cheap.
+///
+/// See [`WindowContext::render_viewport`](crate::WindowContext::render_viewport).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// The scissoring rectangle whose elements are rendered.
+    pub scissor_rect: ScissorRectID,
+    /// The position of the top-left corner of the scissoring rectangle in
+    /// the target, in logical points.
+    ///
+    /// By default this is set to `Vector::zero()`.
+    pub offset: Vector,
+    /// The size of the target in physical pixels.
+    pub physical_size: PhysicalSizeI32,
+    /// The scale factor of the target.
+    pub scale_factor: ScaleFactor,
+    /// The color to clear the target with. If this is `None`, then the target
+    /// is not cleared before rendering.
+    pub clear_color: Option<PackedSrgb>,
+}
+
+impl Viewport {
+    pub fn new(
+        scissor_rect: ScissorRectID,
+        physical_size: PhysicalSizeI32,
+        scale_factor: ScaleFactor,
+    ) -> Self {
+        Self {
+            scissor_rect,
+            offset: Vector::zero(),
+            physical_size,
+            scale_factor,
+            clear_color: Some(PackedSrgb::BLACK),
+        }
+    }
+
+    /// The rectangle that the primitives of an element are clipped to, and
+    /// the offset they are drawn at, in the target.
+    ///
+    /// * `offset` - The offset of the element's primitives in the window.
+    /// * `clip_to_scissor_rect` - If `false`, then the primitives are only
+    /// clipped to the bounds of the target.
+    pub(super) fn place(
+        &self,
+        scissor_rect: &ScissorRect,
+        offset: Vector,
+        clip_to_scissor_rect: bool,
+    ) -> (RectI32, Vector) {
+        let clip_rect = if clip_to_scissor_rect {
+            RectI32::new(
+                PointI32::new(self.offset.x.round() as i32, self.offset.y.round() as i32),
+                scissor_rect.rect().size,
+            )
+        } else {
+            RectI32::new(
+                PointI32::zero(),
+                to_logical_size_i32(self.physical_size, self.scale_factor)
+                    .ceil()
+                    .cast(),
+            )
+        };
+
+        // The translation of the scissoring rectangle itself is not shown.
+        let origin = scissor_rect.transformed_origin().to_vector();
+
+        (clip_rect, offset - origin + self.offset)
+    }
+}
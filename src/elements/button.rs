@@ -28,8 +28,14 @@ impl ButtonState {
 
 /// The style of a [`Button`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct ButtonStyle {
     /// The properties of the text
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
 
     /// The width and height of the icon in points (if the user hasn't
@@ -296,6 +302,8 @@ impl ButtonStyle {
             icon_padding: self.icon_padding,
             text_icon_spacing: self.text_icon_spacing,
             vertical_align: Align::Center,
+            horizontal_align: None,
+            truncate_with_ellipsis: false,
         }
     }
 }
@@ -313,6 +321,31 @@ impl ElementStyle for ButtonStyle {
             ..Self::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.text_color_hover.multiply_alpha(multiplier);
+        self.text_color_down.multiply_alpha(multiplier);
+        self.text_color_disabled.multiply_alpha(multiplier);
+        self.icon_color.multiply_alpha(multiplier);
+        self.icon_color_hover.multiply_alpha(multiplier);
+        self.icon_color_down.multiply_alpha(multiplier);
+        self.icon_color_disabled.multiply_alpha(multiplier);
+        self.back_bg.multiply_alpha(multiplier);
+        self.back_bg_hover.multiply_alpha(multiplier);
+        self.back_bg_down.multiply_alpha(multiplier);
+        self.back_bg_disabled.multiply_alpha(multiplier);
+        self.back_border_color.multiply_alpha(multiplier);
+        self.back_border_color_hover.multiply_alpha(multiplier);
+        self.back_border_color_down.multiply_alpha(multiplier);
+        self.back_border_color_disabled.multiply_alpha(multiplier);
+    }
 }
 
 /// A reusable button struct that can be used by other elements.
@@ -478,6 +511,7 @@ impl ButtonInner {
 #[element_builder_hidden]
 #[element_builder_disabled]
 #[element_builder_tooltip]
+#[element_builder_on_drop]
 #[derive_where(Default)]
 pub struct ButtonBuilder<A: Clone + 'static> {
     pub on_select_action: Option<A>,
@@ -592,6 +626,7 @@ impl<A: Clone + 'static> ButtonBuilder<A> {
             manually_hidden,
             scissor_rect,
             tooltip_data,
+            on_dropped,
         } = self;
 
         let style = window_cx
@@ -616,15 +651,21 @@ impl<A: Clone + 'static> ButtonBuilder<A> {
             tooltip_inner: TooltipInner::new(tooltip_data),
         }));
 
+        let mut flags = ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS;
+        if on_dropped.is_some() {
+            flags |= ElementFlags::LISTENS_TO_ON_DROPPED;
+        }
+
         let el = ElementBuilder::new(ButtonElement {
             shared_state: Rc::clone(&shared_state),
             on_select_action,
+            on_dropped,
             cursor_icon,
         })
         .builder_values(z_index, scissor_rect, class, window_cx)
         .rect(rect)
         .hidden(manually_hidden)
-        .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+        .flags(flags)
         .build(window_cx);
 
         Button { el, shared_state }
@@ -635,6 +676,7 @@ impl<A: Clone + 'static> ButtonBuilder<A> {
 struct ButtonElement<A: Clone + 'static> {
     shared_state: Rc<RefCell<SharedState>>,
     on_select_action: Option<A>,
+    on_dropped: Option<Box<dyn FnMut() -> A>>,
     cursor_icon: Option<CursorIcon>,
 }
 
@@ -657,6 +699,9 @@ impl<A: Clone + 'static> Element<A> for ButtonElement<A> {
             ElementEvent::StyleChanged => {
                 let style = cx.res.style_system.get::<ButtonStyle>(cx.class());
                 self.cursor_icon = style.cursor_icon;
+                shared_state
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
             }
             ElementEvent::Pointer(PointerEvent::Moved { .. }) => {
                 if shared_state.inner.state == ButtonState::Disabled {
@@ -702,7 +747,7 @@ impl<A: Clone + 'static> Element<A> for ButtonElement<A> {
                     }
 
                     if let Some(action) = &self.on_select_action {
-                        cx.send_action(action.clone()).unwrap();
+                        cx.send_action(action.clone());
                     }
 
                     return EventCaptureStatus::Captured;
@@ -715,7 +760,7 @@ impl<A: Clone + 'static> Element<A> for ButtonElement<A> {
                     && (shared_state.inner.state == ButtonState::Down
                         || shared_state.inner.state == ButtonState::Hovered)
                 {
-                    let new_state = if cx.is_point_within_visible_bounds(position) {
+                    let new_state = if cx.is_point_within_hit_bounds(position) {
                         ButtonState::Hovered
                     } else {
                         ButtonState::Idle
@@ -736,12 +781,38 @@ impl<A: Clone + 'static> Element<A> for ButtonElement<A> {
         EventCaptureStatus::NotCaptured
     }
 
+    fn on_dropped(&mut self, action_sender: &mut ActionSender<A>) {
+        if let Some(f) = self.on_dropped.as_mut() {
+            // The action receiver may already be gone if the window is closing.
+            let _ = action_sender.send(f());
+        }
+    }
+
+    #[cfg(feature = "accessibility")]
+    fn accessibility_node(&self) -> Option<accesskit::Node> {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        let mut node = accesskit::Node::new(accesskit::Role::Button);
+        if let Some(text) = shared_state.inner.text() {
+            node.set_label(text);
+        }
+        if shared_state.inner.disabled() {
+            node.set_disabled();
+        } else {
+            node.add_action(accesskit::Action::Click);
+        }
+
+        Some(node)
+    }
+
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
         let label_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
-            cx.res.style_system.get(cx.class),
+            &cx.res
+                .style_system
+                .get_with_opacity::<ButtonStyle>(cx.class, cx.opacity),
             &mut cx.res.font_system,
         );
 
@@ -0,0 +1,726 @@
+use derive_where::derive_where;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::prelude::*;
+
+/// The shape of the mark drawn inside of a checked [`Checkbox`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CheckmarkShape {
+    /// A tick mark made of two line segments.
+    ///
+    /// Note, this requires the `mesh` feature. If that feature is disabled,
+    /// then this is drawn as [`CheckmarkShape::Square`].
+    #[default]
+    Tick,
+    /// A filled square inset into the box.
+    Square,
+}
+
+/// The style of a [`Checkbox`] element
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct CheckboxStyle {
+    /// The width and height of the box.
+    ///
+    /// By default this is set to `18.0`.
+    pub size: f32,
+
+    /// The quad of the box when it is unchecked.
+    pub box_quad: QuadStyle,
+    /// The quad of the box when it is unchecked and hovered.
+    ///
+    /// If this is `None`, then `box_quad` will be used.
+    pub box_quad_hover: Option<QuadStyle>,
+    /// The quad of the box when it is checked (or indeterminate).
+    ///
+    /// If this is `None`, then `box_quad` will be used.
+    pub box_quad_checked: Option<QuadStyle>,
+    /// The quad of the box when it is checked (or indeterminate) and hovered.
+    ///
+    /// If this is `None`, then `box_quad_checked` will be used.
+    pub box_quad_checked_hover: Option<QuadStyle>,
+    /// The quad of the box when it has keyboard focus. This takes priority
+    /// over the hovered quads.
+    ///
+    /// If this is `None`, then the focus state will not be shown.
+    pub box_quad_focused: Option<QuadStyle>,
+    pub box_bg_disabled: DisabledBackground,
+    pub box_border_color_disabled: DisabledColor,
+
+    pub checkmark_shape: CheckmarkShape,
+    pub checkmark_color: RGBA8,
+    pub checkmark_color_hover: Option<RGBA8>,
+    pub checkmark_color_disabled: DisabledColor,
+    /// The padding between the edges of the box and the checkmark.
+    ///
+    /// By default this is set to `4.0`.
+    pub checkmark_padding: f32,
+    /// The thickness of the lines of the checkmark (and of the dash shown
+    /// in the indeterminate state).
+    ///
+    /// By default this is set to `2.0`.
+    pub checkmark_line_width: f32,
+
+    /// The cursor icon to show when the user hovers over this element.
+    ///
+    /// If this is `None`, then the cursor icon will not be changed.
+    ///
+    /// By default this is set to `None`.
+    pub cursor_icon: Option<CursorIcon>,
+
+    /// Additional flags for the checkmark quad primitives.
+    ///
+    /// By default this is set to `QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL`.
+    pub quad_flags: QuadFlags,
+}
+
+impl Default for CheckboxStyle {
+    fn default() -> Self {
+        Self {
+            size: 18.0,
+            box_quad: QuadStyle::TRANSPARENT,
+            box_quad_hover: None,
+            box_quad_checked: None,
+            box_quad_checked_hover: None,
+            box_quad_focused: None,
+            box_bg_disabled: Default::default(),
+            box_border_color_disabled: Default::default(),
+            checkmark_shape: CheckmarkShape::default(),
+            checkmark_color: color::WHITE,
+            checkmark_color_hover: None,
+            checkmark_color_disabled: Default::default(),
+            checkmark_padding: 4.0,
+            checkmark_line_width: 2.0,
+            cursor_icon: None,
+            quad_flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+        }
+    }
+}
+
+impl CheckboxStyle {
+    fn box_quad_style(
+        &self,
+        marked: bool,
+        hovered: bool,
+        focused: bool,
+        disabled: bool,
+    ) -> QuadStyle {
+        let unchecked = if hovered && !disabled {
+            self.box_quad_hover.as_ref().unwrap_or(&self.box_quad)
+        } else {
+            &self.box_quad
+        };
+
+        let quad = if marked {
+            let checked = self.box_quad_checked.as_ref().unwrap_or(&self.box_quad);
+
+            if hovered && !disabled {
+                self.box_quad_checked_hover.as_ref().unwrap_or(checked)
+            } else {
+                checked
+            }
+        } else {
+            unchecked
+        };
+
+        let quad = if focused && !disabled {
+            self.box_quad_focused.as_ref().unwrap_or(quad)
+        } else {
+            quad
+        };
+
+        if disabled {
+            QuadStyle {
+                bg: self.box_bg_disabled.get(quad.bg),
+                border: BorderStyle {
+                    color: self.box_border_color_disabled.get(quad.border.color),
+                    ..quad.border
+                },
+                flags: quad.flags,
+            }
+        } else {
+            quad.clone()
+        }
+    }
+
+    fn checkmark_color(&self, hovered: bool, disabled: bool) -> RGBA8 {
+        if disabled {
+            self.checkmark_color_disabled.get(self.checkmark_color)
+        } else if hovered {
+            self.checkmark_color_hover.unwrap_or(self.checkmark_color)
+        } else {
+            self.checkmark_color
+        }
+    }
+
+    fn solid_quad(&self, color: RGBA8) -> QuadStyle {
+        QuadStyle {
+            bg: Background::Solid(color),
+            border: BorderStyle::TRANSPARENT,
+            flags: self.quad_flags,
+        }
+    }
+
+    /// Add the primitives of the checkmark (or the indeterminate dash) inside
+    /// of the given box bounds.
+    fn add_checkmark_primitives(
+        &self,
+        box_bounds: Rect,
+        indeterminate: bool,
+        color: RGBA8,
+        primitives: &mut PrimitiveGroup,
+    ) {
+        let inner = box_bounds.inflate(-self.checkmark_padding, -self.checkmark_padding);
+        if inner.is_empty() {
+            return;
+        }
+
+        if indeterminate {
+            let dash = Rect::new(
+                Point::new(
+                    inner.min_x(),
+                    inner.center().y - (self.checkmark_line_width * 0.5),
+                ),
+                Size::new(inner.width(), self.checkmark_line_width),
+            );
+
+            primitives.add(self.solid_quad(color).create_primitive(dash));
+            return;
+        }
+
+        match self.checkmark_shape {
+            #[cfg(feature = "mesh")]
+            CheckmarkShape::Tick => {
+                let points = [
+                    Point::new(inner.min_x(), inner.min_y() + (inner.height() * 0.55)),
+                    Point::new(
+                        inner.min_x() + (inner.width() * 0.38),
+                        inner.min_y() + (inner.height() * 0.9),
+                    ),
+                    Point::new(inner.max_x(), inner.min_y() + (inner.height() * 0.1)),
+                ];
+
+                for segment in points.windows(2) {
+                    primitives.add(line_segment_mesh(
+                        segment[0],
+                        segment[1],
+                        self.checkmark_line_width,
+                        color,
+                    ));
+                }
+            }
+            _ => {
+                primitives.add(self.solid_quad(color).create_primitive(inner));
+            }
+        }
+    }
+}
+
+/// A mesh of a line from `from` to `to` with the given thickness.
+#[cfg(feature = "mesh")]
+fn line_segment_mesh(
+    from: Point,
+    to: Point,
+    width: f32,
+    color: RGBA8,
+) -> rootvg::mesh::MeshPrimitive {
+    use rootvg::mesh::{MeshPrimitive, SolidMeshPrimitive};
+
+    let delta = to - from;
+
+    // Extend each end by half the line width so the segments overlap at the
+    // joint.
+    let half_width = width * 0.5;
+    let rect = Rect::new(
+        Point::new(-half_width, -half_width),
+        Size::new(delta.length() + width, width),
+    );
+
+    let mut mesh = MeshPrimitive::Solid(SolidMeshPrimitive::from_rect(rect, color));
+    mesh.set_offset(from.to_vector());
+    mesh.set_transform(Transform::identity().then_rotate(radians(delta.y.atan2(delta.x))));
+
+    mesh
+}
+
+impl ElementStyle for CheckboxStyle {
+    const ID: &'static str = "chkbx";
+
+    fn default_dark_style() -> Self {
+        Self::default()
+    }
+
+    fn default_light_style() -> Self {
+        Self::default()
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.box_quad.multiply_alpha(multiplier);
+        self.box_quad_hover.multiply_alpha(multiplier);
+        self.box_quad_checked.multiply_alpha(multiplier);
+        self.box_quad_checked_hover.multiply_alpha(multiplier);
+        self.box_quad_focused.multiply_alpha(multiplier);
+        self.box_bg_disabled.multiply_alpha(multiplier);
+        self.box_border_color_disabled.multiply_alpha(multiplier);
+        self.checkmark_color.multiply_alpha(multiplier);
+        self.checkmark_color_hover.multiply_alpha(multiplier);
+        self.checkmark_color_disabled.multiply_alpha(multiplier);
+    }
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[element_builder_disabled]
+#[element_builder_tooltip]
+#[derive_where(Default)]
+pub struct CheckboxBuilder<A: Clone + 'static> {
+    pub on_toggled: Option<Box<dyn FnMut(bool) -> A>>,
+    pub checked: bool,
+    pub indeterminate: bool,
+}
+
+impl<A: Clone + 'static> CheckboxBuilder<A> {
+    /// The action to send when the user toggles the checkbox. The new checked
+    /// state is passed to the closure.
+    pub fn on_toggled<F: FnMut(bool) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_toggled = Some(Box::new(f));
+        self
+    }
+
+    pub const fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Show the checkbox in the indeterminate (neither checked nor unchecked)
+    /// state. This is useful for a checkbox which represents a group of
+    /// checkboxes where only some are checked.
+    ///
+    /// The indeterminate state is cleared once the user toggles the checkbox.
+    ///
+    /// By default this is set to `false`.
+    pub const fn indeterminate(mut self, indeterminate: bool) -> Self {
+        self.indeterminate = indeterminate;
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> Checkbox {
+        let CheckboxBuilder {
+            on_toggled,
+            checked,
+            indeterminate,
+            tooltip_data,
+            disabled,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let style = window_cx
+            .res
+            .style_system
+            .get::<CheckboxStyle>(window_cx.builder_class(class));
+        let cursor_icon = style.cursor_icon;
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            checked,
+            indeterminate,
+            disabled,
+            tooltip_inner: TooltipInner::new(tooltip_data),
+        }));
+
+        let el = ElementBuilder::new(CheckboxElement {
+            shared_state: Rc::clone(&shared_state),
+            on_toggled,
+            hovered: false,
+            focused: false,
+            cursor_icon,
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(
+            ElementFlags::PAINTS
+                | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+                | ElementFlags::LISTENS_TO_KEYS_WHEN_FOCUSED
+                | ElementFlags::LISTENS_TO_FOCUS_CHANGE
+                | ElementFlags::FOCUSABLE,
+        )
+        .build(window_cx);
+
+        Checkbox { el, shared_state }
+    }
+}
+
+struct CheckboxElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    on_toggled: Option<Box<dyn FnMut(bool) -> A>>,
+    hovered: bool,
+    focused: bool,
+    cursor_icon: Option<CursorIcon>,
+}
+
+impl<A: Clone + 'static> CheckboxElement<A> {
+    fn toggle(&mut self, shared_state: &mut SharedState, cx: &mut ElementContext<'_, A>) {
+        let checked = shared_state.toggle();
+        cx.request_repaint();
+
+        if let Some(on_toggled) = &mut self.on_toggled {
+            cx.send_action((on_toggled)(checked));
+        }
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for CheckboxElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let shared_state_rc = Rc::clone(&self.shared_state);
+        let mut shared_state = RefCell::borrow_mut(&shared_state_rc);
+
+        shared_state
+            .tooltip_inner
+            .handle_event(&event, shared_state.disabled, cx);
+
+        match event {
+            ElementEvent::CustomStateChanged => {
+                if shared_state.disabled && cx.has_focus() {
+                    cx.release_focus();
+                }
+
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<CheckboxStyle>(cx.class());
+                self.cursor_icon = style.cursor_icon;
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { .. }) => {
+                if shared_state.disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if let Some(cursor_icon) = self.cursor_icon {
+                    cx.cursor_icon = cursor_icon;
+                }
+
+                if !self.hovered {
+                    self.hovered = true;
+                    cx.request_repaint();
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(PointerEvent::PointerLeft) => {
+                if shared_state.disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if self.hovered {
+                    self.hovered = false;
+                    cx.request_repaint();
+
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed { button, .. }) => {
+                if shared_state.disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if button == PointerButton::Primary {
+                    self.toggle(&mut shared_state, cx);
+
+                    // Receive keyboard events so the checkbox can be toggled
+                    // with the space bar.
+                    if !cx.has_focus() {
+                        cx.steal_focus();
+                    }
+
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Keyboard(KeyboardEvent {
+                state: KeyState::Down,
+                code: Code::Space,
+                repeat: false,
+                ..
+            }) => {
+                if !shared_state.disabled {
+                    self.toggle(&mut shared_state, cx);
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Focus(focused) => {
+                if focused {
+                    cx.listen_to_pointer_clicked_off();
+                }
+
+                if self.focused != focused {
+                    self.focused = focused;
+                    cx.request_repaint();
+                }
+            }
+            ElementEvent::ClickedOff => {
+                if cx.has_focus() {
+                    cx.release_focus();
+                }
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    #[cfg(feature = "accessibility")]
+    fn accessibility_node(&self) -> Option<accesskit::Node> {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        let mut node = accesskit::Node::new(accesskit::Role::CheckBox);
+        node.set_toggled(if shared_state.indeterminate {
+            accesskit::Toggled::Mixed
+        } else if shared_state.checked {
+            accesskit::Toggled::True
+        } else {
+            accesskit::Toggled::False
+        });
+        if shared_state.disabled {
+            node.set_disabled();
+        } else {
+            node.add_action(accesskit::Action::Click);
+        }
+
+        Some(node)
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<CheckboxStyle>(cx.class, cx.opacity);
+
+        let marked = shared_state.checked || shared_state.indeterminate;
+
+        let box_quad_style =
+            style.box_quad_style(marked, self.hovered, self.focused, shared_state.disabled);
+
+        let bounds_rect = Rect::from_size(cx.bounds_size);
+        let box_bounds = centered_rect(bounds_rect.center(), Size::new(style.size, style.size));
+
+        primitives.add(box_quad_style.create_primitive(box_bounds));
+
+        if marked {
+            primitives.set_z_index(1);
+            style.add_checkmark_primitives(
+                box_bounds,
+                shared_state.indeterminate,
+                style.checkmark_color(self.hovered, shared_state.disabled),
+                primitives,
+            );
+        }
+    }
+}
+
+/// A handle to a [`CheckboxElement`].
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_set_tooltip]
+pub struct Checkbox {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+struct SharedState {
+    checked: bool,
+    indeterminate: bool,
+    disabled: bool,
+    tooltip_inner: TooltipInner,
+}
+
+impl SharedState {
+    /// Toggle the checked state as if the user clicked the checkbox.
+    ///
+    /// Returns the new checked state.
+    fn toggle(&mut self) -> bool {
+        // An indeterminate checkbox becomes checked.
+        self.checked = self.indeterminate || !self.checked;
+        self.indeterminate = false;
+        self.checked
+    }
+}
+
+impl Checkbox {
+    pub fn builder<A: Clone + 'static>() -> CheckboxBuilder<A> {
+        CheckboxBuilder::default()
+    }
+
+    pub fn desired_size(&self, res: &mut ResourceCtx) -> Size {
+        let size = res.style_system.get::<CheckboxStyle>(self.el.class()).size;
+        Size::new(size, size)
+    }
+
+    /// Set the checked state of this element. This also clears the
+    /// indeterminate state.
+    ///
+    /// Returns `true` if the state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_checked(&mut self, checked: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.checked != checked || shared_state.indeterminate {
+            shared_state.checked = checked;
+            shared_state.indeterminate = false;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        RefCell::borrow(&self.shared_state).checked
+    }
+
+    /// Set the indeterminate state of this element.
+    ///
+    /// Returns `true` if the state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_indeterminate(&mut self, indeterminate: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.indeterminate != indeterminate {
+            shared_state.indeterminate = indeterminate;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_indeterminate(&self) -> bool {
+        RefCell::borrow(&self.shared_state).indeterminate
+    }
+
+    /// Set the disabled state of this element.
+    ///
+    /// Returns `true` if the disabled state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_disabled(&mut self, disabled: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.disabled != disabled {
+            shared_state.disabled = disabled;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn disabled(&self) -> bool {
+        RefCell::borrow(&self.shared_state).disabled
+    }
+
+    /// Layout out the element (with the top-left corner of the bounds set to `origin`).
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout(&mut self, origin: Point, res: &mut ResourceCtx) -> bool {
+        let size = self.desired_size(res);
+        self.el.set_rect(Rect::new(origin, size))
+    }
+
+    /// Layout out the element aligned to the given point.
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout_aligned(&mut self, point: Point, align: Align2, res: &mut ResourceCtx) -> bool {
+        let size = self.desired_size(res);
+        self.el.set_rect(align.align_rect_to_point(point, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(checked: bool, indeterminate: bool) -> SharedState {
+        SharedState {
+            checked,
+            indeterminate,
+            disabled: false,
+            tooltip_inner: TooltipInner::new(None),
+        }
+    }
+
+    #[test]
+    fn test_checkbox_toggle_clears_indeterminate_state() {
+        let mut s = state(false, false);
+        assert!(s.toggle());
+        assert!(!s.toggle());
+
+        let mut s = state(false, true);
+        assert!(s.toggle());
+        assert!(!s.indeterminate);
+        assert!(!s.toggle());
+
+        // An indeterminate checkbox always becomes checked first.
+        let mut s = state(true, true);
+        assert!(s.toggle());
+        assert!(!s.indeterminate);
+    }
+
+    #[test]
+    fn test_checkbox_disabled_box_quad() {
+        let style = CheckboxStyle {
+            box_quad: QuadStyle {
+                bg: Background::Solid(color::WHITE),
+                border: BorderStyle {
+                    color: color::WHITE,
+                    width: 1.0,
+                    radius: 2.0.into(),
+                },
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            },
+            box_quad_focused: Some(QuadStyle::TRANSPARENT),
+            box_bg_disabled: DisabledBackground::Custom(Background::Solid(color::BLACK)),
+            box_border_color_disabled: DisabledColor::Custom(color::BLACK),
+            ..Default::default()
+        };
+
+        // The focused quad is ignored when disabled.
+        let quad = style.box_quad_style(false, true, true, true);
+        assert_eq!(quad.bg, Background::Solid(color::BLACK));
+        assert_eq!(quad.border.color, color::BLACK);
+        assert_eq!(quad.border.width, 1.0);
+
+        assert_eq!(
+            style.box_quad_style(false, false, true, false),
+            QuadStyle::TRANSPARENT
+        );
+    }
+}
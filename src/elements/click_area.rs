@@ -144,7 +144,7 @@ impl<A: Clone + 'static> Element<A> for ClickAreaElement<A> {
                 }
 
                 if let Some(t) = self.pointer_type {
-                    if pointer_type != t {
+                    if !pointer_type.is_same_type(&t) {
                         return EventCaptureStatus::NotCaptured;
                     }
                 }
@@ -154,8 +154,7 @@ impl<A: Clone + 'static> Element<A> for ClickAreaElement<A> {
                     cx.send_action((f)(ClickAreaInfo {
                         element_bounds,
                         click_position: position,
-                    }))
-                    .unwrap();
+                    }));
 
                     return EventCaptureStatus::Captured;
                 }
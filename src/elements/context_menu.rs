@@ -0,0 +1,867 @@
+use derive_where::derive_where;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::prelude::*;
+use crate::vg::{
+    quad::{SolidQuadBuilder, SolidQuadPrimitive},
+    text::TextPrimitive,
+};
+
+use super::drop_down::DEFAULT_POPUP_Z_INDEX;
+use super::label::{LabelInner, LabelStyle};
+
+/// The text shown on the right side of items which open a submenu.
+const SUBMENU_INDICATOR: &str = "›";
+
+/// An entry in a [`ContextMenu`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContextMenuEntry {
+    Item(MenuItem),
+    Separator,
+}
+
+impl From<MenuItem> for ContextMenuEntry {
+    fn from(item: MenuItem) -> Self {
+        Self::Item(item)
+    }
+}
+
+/// An item in a [`ContextMenu`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MenuItem {
+    pub label: String,
+    /// The ID passed to `on_item_selected` when this item is selected.
+    pub id: usize,
+    /// Disabled items are shown but cannot be selected.
+    ///
+    /// By default this is set to `true`.
+    pub enabled: bool,
+    /// The text shown on the right side of the item, i.e. "Ctrl+C".
+    ///
+    /// By default this is set to `None`.
+    pub shortcut_hint: Option<String>,
+    /// If this is not empty, then this item opens a submenu instead of
+    /// being selected.
+    ///
+    /// By default this is empty.
+    pub submenu: Vec<ContextMenuEntry>,
+}
+
+impl MenuItem {
+    pub fn new(label: impl Into<String>, id: usize) -> Self {
+        Self {
+            label: label.into(),
+            id,
+            enabled: true,
+            shortcut_hint: None,
+            submenu: Vec::new(),
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn shortcut_hint(mut self, hint: impl Into<String>) -> Self {
+        self.shortcut_hint = Some(hint.into());
+        self
+    }
+
+    pub fn submenu(mut self, entries: Vec<ContextMenuEntry>) -> Self {
+        self.submenu = entries;
+        self
+    }
+}
+
+enum PaneEntry {
+    Item {
+        label: LabelInner,
+        right_label: Option<LabelInner>,
+        id: usize,
+        enabled: bool,
+        submenu: Vec<ContextMenuEntry>,
+        start_y: f32,
+        end_y: f32,
+    },
+    Separator {
+        y: f32,
+    },
+}
+
+impl PaneEntry {
+    fn is_selectable(&self) -> bool {
+        matches!(self, Self::Item { enabled: true, .. })
+    }
+
+    fn has_submenu(&self) -> bool {
+        matches!(self, Self::Item { submenu, .. } if !submenu.is_empty())
+    }
+}
+
+/// One menu of the cascade of open menus.
+struct Pane {
+    entries: Vec<PaneEntry>,
+    /// The bounds of this menu in window coordinates.
+    rect: Rect,
+    hovered: Option<usize>,
+    /// The index of the entry whose submenu is open.
+    open_submenu: Option<usize>,
+}
+
+impl Pane {
+    fn new(
+        entries: &[ContextMenuEntry],
+        style: &DropDownMenuStyle,
+        font_system: &mut FontSystem,
+    ) -> Self {
+        let (left_style, right_style) = style.label_styles(false);
+        let text_row_height = style.text_row_height();
+
+        let mut max_width: f32 = 0.0;
+        let mut total_height: f32 = style.outer_padding;
+
+        let entries = entries
+            .iter()
+            .map(|entry| match entry {
+                ContextMenuEntry::Item(item) => {
+                    let mut label = new_label(item.label.clone(), &left_style, font_system);
+                    let right_text = if item.submenu.is_empty() {
+                        item.shortcut_hint.clone()
+                    } else {
+                        Some(String::from(SUBMENU_INDICATOR))
+                    };
+                    let mut right_label =
+                        right_text.map(|t| new_label(t, &right_style, font_system));
+
+                    let width = label.desired_size(|| style.left_padding_info()).width
+                        + right_label
+                            .as_mut()
+                            .map(|l| l.desired_size(|| style.right_padding_info()).width)
+                            .unwrap_or(0.0);
+                    max_width = max_width.max(width);
+
+                    let start_y = total_height;
+                    total_height += text_row_height;
+
+                    PaneEntry::Item {
+                        label,
+                        right_label,
+                        id: item.id,
+                        enabled: item.enabled,
+                        submenu: item.submenu.clone(),
+                        start_y,
+                        end_y: total_height,
+                    }
+                }
+                ContextMenuEntry::Separator => {
+                    let y = total_height + style.divider_padding;
+                    total_height += style.divider_width + (style.divider_padding * 2.0);
+
+                    PaneEntry::Separator { y }
+                }
+            })
+            .collect();
+
+        Self {
+            entries,
+            rect: Rect::from_size(Size::new(
+                max_width.ceil() + (style.outer_padding * 2.0),
+                total_height + style.outer_padding,
+            )),
+            hovered: None,
+            open_submenu: None,
+        }
+    }
+
+    /// The index of the item at the given position in window coordinates.
+    fn item_at(&self, pos: Point) -> Option<usize> {
+        if !self.rect.contains(pos) {
+            return None;
+        }
+
+        let y = pos.y - self.rect.min_y();
+        self.entries.iter().position(|entry| match entry {
+            PaneEntry::Item { start_y, end_y, .. } => y >= *start_y && y < *end_y,
+            PaneEntry::Separator { .. } => false,
+        })
+    }
+
+    /// Move the highlighted item up (`-1`) or down (`1`), skipping separators
+    /// and disabled items.
+    fn move_hovered(&mut self, direction: isize) -> bool {
+        let selectable: Vec<bool> = self.entries.iter().map(|e| e.is_selectable()).collect();
+        let new_hovered = next_selectable(&selectable, self.hovered, direction);
+
+        if new_hovered.is_some() && self.hovered != new_hovered {
+            self.hovered = new_hovered;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn new_label(text: String, style: &LabelStyle, font_system: &mut FontSystem) -> LabelInner {
+    LabelInner::new(
+        Some(text),
+        None,
+        Vector::default(),
+        Vector::default(),
+        None,
+        IconScale::default(),
+        Default::default(),
+        style,
+        font_system,
+    )
+}
+
+/// The index of the next selectable entry in the given direction, stopping
+/// at the ends.
+fn next_selectable(selectable: &[bool], current: Option<usize>, direction: isize) -> Option<usize> {
+    let options: Vec<usize> = (0..selectable.len()).filter(|i| selectable[*i]).collect();
+    if options.is_empty() {
+        return None;
+    }
+
+    match current.and_then(|current| options.iter().position(|&i| i == current)) {
+        Some(pos) => {
+            let new_pos = (pos as isize + direction).clamp(0, options.len() as isize - 1);
+            Some(options[new_pos as usize])
+        }
+        None if direction < 0 => options.last().copied(),
+        None => Some(options[0]),
+    }
+}
+
+/// Place the root menu at the given position, flipping it to the other side
+/// of the position if it doesn't fit in the window.
+fn place_root(pos: Point, size: Size, window_size: Size) -> Rect {
+    let x = if pos.x + size.width > window_size.width {
+        pos.x - size.width
+    } else {
+        pos.x
+    };
+    let y = if pos.y + size.height > window_size.height {
+        pos.y - size.height
+    } else {
+        pos.y
+    };
+
+    clamp_to_window(Rect::new(Point::new(x, y), size), window_size)
+}
+
+/// Place a submenu to the right of its parent menu (or to the left if it
+/// doesn't fit), lined up with the item at `item_y`.
+fn place_submenu(parent: Rect, item_y: f32, size: Size, window_size: Size) -> Rect {
+    let x = if parent.max_x() + size.width > window_size.width {
+        parent.min_x() - size.width
+    } else {
+        parent.max_x()
+    };
+
+    clamp_to_window(Rect::new(Point::new(x, item_y), size), window_size)
+}
+
+fn clamp_to_window(rect: Rect, window_size: Size) -> Rect {
+    let x = rect.min_x().min(window_size.width - rect.width()).max(0.0);
+    let y = rect
+        .min_y()
+        .min(window_size.height - rect.height())
+        .max(0.0);
+
+    Rect::new(Point::new(x, y), rect.size)
+}
+
+#[element_builder]
+#[element_builder_class]
+#[derive_where(Default)]
+pub struct ContextMenuBuilder<A: Clone + 'static> {
+    pub action: Option<Box<dyn FnMut(usize) -> A>>,
+    pub entries: Vec<ContextMenuEntry>,
+}
+
+impl<A: Clone + 'static> ContextMenuBuilder<A> {
+    /// The action to send when the user selects an item. The ID of the item
+    /// is passed to the closure.
+    pub fn on_item_selected<F: FnMut(usize) -> A + 'static>(mut self, f: F) -> Self {
+        self.action = Some(Box::new(f));
+        self
+    }
+
+    pub fn entries(mut self, entries: Vec<ContextMenuEntry>) -> Self {
+        self.entries = entries;
+        self
+    }
+
+    /// Build the menu. The menu starts out closed.
+    ///
+    /// If no scissoring rectangle is set, then the menu is assigned to the
+    /// window's scissoring rectangle so that it can be shown above other
+    /// content. If no z index is set, then [`DEFAULT_POPUP_Z_INDEX`] is used.
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> ContextMenu {
+        let ContextMenuBuilder {
+            action,
+            entries,
+            class,
+            z_index,
+            scissor_rect,
+        } = self;
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            new_entries: None,
+            open_requested: None,
+            close_requested: false,
+            open: false,
+        }));
+
+        let cursor_icon = window_cx
+            .res
+            .style_system
+            .get::<DropDownMenuStyle>(window_cx.builder_class(class))
+            .cursor_icon;
+
+        let el = ElementBuilder::new(ContextMenuElement {
+            shared_state: Rc::clone(&shared_state),
+            action,
+            entries,
+            panes: Vec::new(),
+            cursor_icon,
+        })
+        .builder_values(
+            Some(z_index.unwrap_or(DEFAULT_POPUP_Z_INDEX)),
+            Some(scissor_rect.unwrap_or(ScissorRectID::DEFAULT)),
+            class,
+            window_cx,
+        )
+        .flags(
+            ElementFlags::PAINTS
+                | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+                | ElementFlags::LISTENS_TO_FOCUS_CHANGE
+                | ElementFlags::LISTENS_TO_POINTER_OUTSIDE_BOUNDS_WHEN_FOCUSED
+                | ElementFlags::LISTENS_TO_KEYS_WHEN_FOCUSED,
+        )
+        .build(window_cx);
+
+        ContextMenu { el, shared_state }
+    }
+}
+
+struct ContextMenuElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    action: Option<Box<dyn FnMut(usize) -> A>>,
+    entries: Vec<ContextMenuEntry>,
+    /// The open menus, starting with the root menu.
+    panes: Vec<Pane>,
+    cursor_icon: Option<CursorIcon>,
+}
+
+impl<A: Clone + 'static> ContextMenuElement<A> {
+    fn open_root(&mut self, pos: Point, cx: &mut ElementContext<'_, A>) {
+        let style = cx.res.style_system.get::<DropDownMenuStyle>(cx.class());
+        let mut pane = Pane::new(&self.entries, style, &mut cx.res.font_system);
+        pane.rect = place_root(pos, pane.rect.size, cx.window_size());
+
+        self.panes.clear();
+        self.panes.push(pane);
+        self.sync_rect(cx);
+    }
+
+    /// Open the submenu of the given item, closing any submenus that were
+    /// open below its menu.
+    fn open_submenu(
+        &mut self,
+        pane_index: usize,
+        entry_index: usize,
+        cx: &mut ElementContext<'_, A>,
+    ) {
+        self.panes.truncate(pane_index + 1);
+
+        let parent = &mut self.panes[pane_index];
+        let Some(PaneEntry::Item {
+            submenu, start_y, ..
+        }) = parent.entries.get(entry_index)
+        else {
+            return;
+        };
+        if submenu.is_empty() {
+            return;
+        }
+        parent.open_submenu = Some(entry_index);
+
+        let style = cx.res.style_system.get::<DropDownMenuStyle>(cx.class());
+        let item_y = parent.rect.min_y() + *start_y - style.outer_padding;
+        let parent_rect = parent.rect;
+
+        let mut pane = Pane::new(submenu, style, &mut cx.res.font_system);
+        pane.rect = place_submenu(parent_rect, item_y, pane.rect.size, cx.window_size());
+
+        self.panes.push(pane);
+        self.sync_rect(cx);
+    }
+
+    /// Close the submenus below the given menu.
+    fn close_submenus(&mut self, pane_index: usize, cx: &mut ElementContext<'_, A>) {
+        self.panes.truncate(pane_index + 1);
+        self.panes[pane_index].open_submenu = None;
+        self.sync_rect(cx);
+    }
+
+    /// Set the bounds of the element to the union of the open menus.
+    fn sync_rect(&mut self, cx: &mut ElementContext<'_, A>) {
+        let rect = self
+            .panes
+            .iter()
+            .map(|pane| pane.rect)
+            .reduce(|a, b| a.union(&b))
+            .unwrap_or_else(|| Rect::new(cx.rect().origin, Size::zero()));
+
+        cx.set_rect(rect);
+        cx.request_repaint();
+    }
+
+    /// The index of the topmost open menu containing the given position.
+    fn pane_at(&self, pos: Point) -> Option<usize> {
+        self.panes.iter().rposition(|pane| pane.rect.contains(pos))
+    }
+
+    /// Select the given item. Items with a submenu open it instead.
+    ///
+    /// Returns `false` if the item cannot be selected.
+    fn activate(
+        &mut self,
+        pane_index: usize,
+        entry_index: usize,
+        from_keyboard: bool,
+        cx: &mut ElementContext<'_, A>,
+    ) -> bool {
+        let entry = &self.panes[pane_index].entries[entry_index];
+        if !entry.is_selectable() {
+            return false;
+        }
+
+        if entry.has_submenu() {
+            if self.panes[pane_index].open_submenu != Some(entry_index) {
+                self.open_submenu(pane_index, entry_index, cx);
+            }
+            if from_keyboard {
+                if let Some(pane) = self.panes.get_mut(pane_index + 1) {
+                    pane.move_hovered(1);
+                }
+            }
+
+            return true;
+        }
+
+        let PaneEntry::Item { id, .. } = entry else {
+            return false;
+        };
+        let id = *id;
+
+        if let Some(action) = &mut self.action {
+            cx.send_action((action)(id));
+        }
+
+        cx.release_focus();
+        cx.cursor_icon = CursorIcon::Default;
+
+        true
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for ContextMenuElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        match event {
+            ElementEvent::CustomStateChanged => {
+                let (new_entries, open_requested, close_requested, was_open) = {
+                    let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+                    let close_requested = shared_state.close_requested;
+                    shared_state.close_requested = false;
+
+                    (
+                        shared_state.new_entries.take(),
+                        shared_state.open_requested.take(),
+                        close_requested,
+                        shared_state.open,
+                    )
+                };
+
+                if let Some(new_entries) = new_entries {
+                    self.entries = new_entries;
+
+                    if let Some(root) = self.panes.first() {
+                        let pos = root.rect.origin;
+                        self.open_root(pos, cx);
+                    }
+                }
+
+                if let Some(pos) = open_requested {
+                    self.open_root(pos, cx);
+
+                    if !was_open {
+                        RefCell::borrow_mut(&self.shared_state).open = true;
+                        cx.steal_temporary_focus();
+                        cx.listen_to_pointer_clicked_off();
+                    }
+                } else if close_requested && was_open {
+                    cx.release_focus();
+                }
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<DropDownMenuStyle>(cx.class());
+                self.cursor_icon = style.cursor_icon;
+            }
+            ElementEvent::ClickedOff => {
+                cx.release_focus();
+            }
+            ElementEvent::Focus(false) => {
+                RefCell::borrow_mut(&self.shared_state).open = false;
+                self.panes.clear();
+                self.sync_rect(cx);
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { position, .. }) => {
+                if self.panes.is_empty() {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let Some(pane_index) = self.pane_at(position) else {
+                    return EventCaptureStatus::Captured;
+                };
+
+                let hovered = self.panes[pane_index].item_at(position);
+                if self.panes[pane_index].hovered != hovered {
+                    self.panes[pane_index].hovered = hovered;
+                    cx.request_repaint();
+                }
+
+                // Hovering over an item changes which submenu of its menu is open.
+                if let Some(i) = hovered {
+                    let entry = &self.panes[pane_index].entries[i];
+                    if entry.is_selectable() && entry.has_submenu() {
+                        if self.panes[pane_index].open_submenu != Some(i) {
+                            self.open_submenu(pane_index, i, cx);
+                        }
+                    } else if self.panes[pane_index].open_submenu.is_some() {
+                        self.close_submenus(pane_index, cx);
+                    }
+
+                    if let Some(cursor_icon) = self.cursor_icon {
+                        if self.panes[pane_index].entries[i].is_selectable() {
+                            cx.cursor_icon = cursor_icon;
+                        }
+                    }
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                button, position, ..
+            }) => {
+                if self.panes.is_empty() {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let item = self.pane_at(position).and_then(|pane_index| {
+                    self.panes[pane_index]
+                        .item_at(position)
+                        .map(|i| (pane_index, i))
+                });
+
+                match item {
+                    Some((pane_index, entry_index)) => {
+                        if button == PointerButton::Primary {
+                            self.activate(pane_index, entry_index, false, cx);
+                        }
+                    }
+                    // The bounds of the element can include areas between
+                    // the menus, so treat those like a click off.
+                    None if self.pane_at(position).is_none() => cx.release_focus(),
+                    None => {}
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Keyboard(KeyboardEvent {
+                state: KeyState::Down,
+                code,
+                ..
+            }) => {
+                let Some(pane_index) = self.panes.len().checked_sub(1) else {
+                    return EventCaptureStatus::NotCaptured;
+                };
+
+                match code {
+                    Code::ArrowDown | Code::ArrowUp => {
+                        let direction = if code == Code::ArrowDown { 1 } else { -1 };
+                        if self.panes[pane_index].move_hovered(direction) {
+                            cx.request_repaint();
+                        }
+                    }
+                    Code::ArrowRight => {
+                        if let Some(i) = self.panes[pane_index].hovered {
+                            if self.panes[pane_index].entries[i].has_submenu() {
+                                self.activate(pane_index, i, true, cx);
+                            }
+                        }
+                    }
+                    Code::Enter | Code::NumpadEnter | Code::Space => {
+                        if let Some(i) = self.panes[pane_index].hovered {
+                            self.activate(pane_index, i, true, cx);
+                        }
+                    }
+                    Code::ArrowLeft => {
+                        if pane_index > 0 {
+                            self.close_submenus(pane_index - 1, cx);
+                        }
+                    }
+                    Code::Escape => {
+                        if pane_index > 0 {
+                            self.close_submenus(pane_index - 1, cx);
+                        } else {
+                            cx.release_focus();
+                        }
+                    }
+                    _ => {}
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(..) => {
+                if self.panes.is_empty() {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<DropDownMenuStyle>(cx.class, cx.opacity);
+
+        let (left_style_idle, right_style_idle) = style.label_styles(false);
+        let (left_style_hover, right_style_hover) = style.label_styles(true);
+        let style_text_disabled = |color: RGBA8| style.text_color_disabled.get(color);
+        let left_style_disabled = LabelStyle {
+            text_color: style_text_disabled(left_style_idle.text_color),
+            icon_color: left_style_idle.icon_color.map(style_text_disabled),
+            ..left_style_idle.clone()
+        };
+        let right_style_disabled = LabelStyle {
+            text_color: style_text_disabled(right_style_idle.text_color),
+            ..right_style_idle.clone()
+        };
+
+        for (pane_index, pane) in self.panes.iter_mut().enumerate() {
+            // Submenus are drawn on top of their parent menus.
+            let z_index = pane_index as ZIndex * 3;
+            let pane_rect = pane.rect.translate(-cx.bounds_origin.to_vector());
+
+            let label_size = Size::new(
+                pane_rect.width() - (style.outer_padding * 2.0),
+                style.text_row_height(),
+            );
+
+            let mut text_primitives: Vec<TextPrimitive> =
+                Vec::with_capacity(pane.entries.len() * 2);
+            let mut divider_primitives: Vec<SolidQuadPrimitive> = Vec::new();
+
+            primitives.set_z_index(z_index);
+            primitives.add(style.back_quad.create_primitive(pane_rect));
+
+            for (i, entry) in pane.entries.iter_mut().enumerate() {
+                match entry {
+                    PaneEntry::Item {
+                        label,
+                        right_label,
+                        enabled,
+                        start_y,
+                        ..
+                    } => {
+                        let hovered =
+                            *enabled && (pane.hovered == Some(i) || pane.open_submenu == Some(i));
+
+                        let (left_style, right_style) = if !*enabled {
+                            (&left_style_disabled, &right_style_disabled)
+                        } else if hovered {
+                            (&left_style_hover, &right_style_hover)
+                        } else {
+                            (&left_style_idle, &right_style_idle)
+                        };
+
+                        let label_rect = Rect::new(
+                            Point::new(
+                                pane_rect.min_x() + style.outer_padding,
+                                pane_rect.min_y() + *start_y,
+                            ),
+                            label_size,
+                        );
+
+                        if hovered {
+                            primitives.set_z_index(z_index + 1);
+                            primitives.add(style.entry_bg_quad_hover.create_primitive(label_rect));
+                        }
+
+                        let left_primitives =
+                            label.render(label_rect, left_style, &mut cx.res.font_system);
+                        if let Some(p) = left_primitives.text {
+                            text_primitives.push(p);
+                        }
+
+                        if let Some(right_label) = right_label {
+                            let right_x = pane_rect.max_x()
+                                - style.outer_padding
+                                - right_label
+                                    .desired_size(|| style.right_padding_info())
+                                    .width;
+
+                            let right_primitives = right_label.render(
+                                Rect::new(Point::new(right_x, label_rect.min_y()), label_size),
+                                right_style,
+                                &mut cx.res.font_system,
+                            );
+                            if let Some(p) = right_primitives.text {
+                                text_primitives.push(p);
+                            }
+                        }
+                    }
+                    PaneEntry::Separator { y } => divider_primitives.push(
+                        SolidQuadBuilder::new(Size::new(label_size.width, style.divider_width))
+                            .bg_color(style.divider_color)
+                            .position(Point::new(
+                                pane_rect.min_x() + style.outer_padding,
+                                pane_rect.min_y() + *y,
+                            ))
+                            .into(),
+                    ),
+                }
+            }
+
+            primitives.set_z_index(z_index + 2);
+
+            // It is more efficient to batch primitives together.
+            primitives.add_text_batch(text_primitives);
+            primitives.add_solid_quad_batch(divider_primitives);
+        }
+    }
+}
+
+struct SharedState {
+    new_entries: Option<Vec<ContextMenuEntry>>,
+    open_requested: Option<Point>,
+    close_requested: bool,
+    open: bool,
+}
+
+/// A handle to a [`ContextMenuElement`], a popup menu with nested submenus.
+///
+/// The menu is styled with a [`DropDownMenuStyle`]. It closes when the user
+/// selects an item, clicks outside of it, or presses Escape.
+#[element_handle]
+#[element_handle_class]
+pub struct ContextMenu {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl ContextMenu {
+    pub fn builder<A: Clone + 'static>() -> ContextMenuBuilder<A> {
+        ContextMenuBuilder::default()
+    }
+
+    /// Open the menu at the given position in window coordinates, i.e. the
+    /// position of a right click.
+    ///
+    /// The menu is moved so that it stays within the bounds of the window.
+    /// If the menu is already open, then it is moved to the new position.
+    pub fn open(&mut self, position: Point) {
+        RefCell::borrow_mut(&self.shared_state).open_requested = Some(position);
+        self.el.notify_custom_state_change();
+    }
+
+    pub fn close(&mut self) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        if shared_state.open {
+            shared_state.close_requested = true;
+            self.el.notify_custom_state_change();
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        RefCell::borrow(&self.shared_state).open
+    }
+
+    /// Set the entries of the menu. If the menu is open, then its submenus
+    /// are closed.
+    ///
+    /// Note this will *always* trigger an element update, so use
+    /// this method sparingly.
+    pub fn set_entries(&mut self, entries: Vec<ContextMenuEntry>) {
+        RefCell::borrow_mut(&self.shared_state).new_entries = Some(entries);
+        self.el.notify_custom_state_change();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menus_stay_within_the_window() {
+        let window_size = Size::new(400.0, 300.0);
+        let size = Size::new(100.0, 80.0);
+
+        // The root menu opens below and to the right, or flips at the edges.
+        assert_eq!(
+            place_root(Point::new(10.0, 20.0), size, window_size).origin,
+            Point::new(10.0, 20.0)
+        );
+        assert_eq!(
+            place_root(Point::new(350.0, 250.0), size, window_size).origin,
+            Point::new(250.0, 170.0)
+        );
+        // Menus larger than the window are pinned to its top-left corner.
+        assert_eq!(
+            place_root(Point::new(50.0, 50.0), Size::new(500.0, 80.0), window_size).origin,
+            Point::new(0.0, 50.0)
+        );
+
+        let parent = Rect::new(Point::new(10.0, 20.0), size);
+        assert_eq!(
+            place_submenu(parent, 40.0, size, window_size).origin,
+            Point::new(110.0, 40.0)
+        );
+
+        // A submenu which doesn't fit on the right opens on the left.
+        let parent = Rect::new(Point::new(250.0, 20.0), size);
+        assert_eq!(
+            place_submenu(parent, 260.0, size, window_size).origin,
+            Point::new(150.0, 220.0)
+        );
+    }
+
+    #[test]
+    fn test_keyboard_navigation_skips_disabled_items() {
+        let selectable = [false, true, false, true, true, false];
+
+        assert_eq!(next_selectable(&selectable, None, 1), Some(1));
+        assert_eq!(next_selectable(&selectable, None, -1), Some(4));
+        assert_eq!(next_selectable(&selectable, Some(1), 1), Some(3));
+        assert_eq!(next_selectable(&selectable, Some(4), 1), Some(4));
+        assert_eq!(next_selectable(&selectable, Some(3), -1), Some(1));
+        assert_eq!(next_selectable(&[false, false], None, 1), None);
+    }
+}
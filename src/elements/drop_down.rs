@@ -0,0 +1,459 @@
+use derive_where::derive_where;
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::element_system::element::CustomStateNotifier;
+use crate::prelude::*;
+
+use super::button::{ButtonInner, ButtonState};
+use super::drop_down_menu::DropDownMenuOpener;
+
+/// The default z index of the popup menu of a [`DropDown`], which is the
+/// base of [`ZLayer::Popup`].
+pub const DEFAULT_POPUP_Z_INDEX: ZIndex = ZLayer::Popup.base();
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[element_builder_disabled]
+#[element_builder_tooltip]
+#[derive_where(Default)]
+pub struct DropDownBuilder<A: Clone + 'static> {
+    pub on_selected: Option<Box<dyn FnMut(usize) -> A>>,
+    pub options: Vec<String>,
+    pub selected_index: usize,
+    pub popup_class: Option<ClassID>,
+    pub popup_z_index: Option<ZIndex>,
+    pub text_offset: Vector,
+}
+
+impl<A: Clone + 'static> DropDownBuilder<A> {
+    /// The action to send when the user selects an option. The index of the
+    /// option is passed to the closure.
+    pub fn on_selected<F: FnMut(usize) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_selected = Some(Box::new(f));
+        self
+    }
+
+    pub fn options(mut self, options: Vec<String>) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub const fn selected_index(mut self, index: usize) -> Self {
+        self.selected_index = index;
+        self
+    }
+
+    /// The style class of the popup menu (a [`DropDownMenuStyle`]).
+    ///
+    /// If this is `None`, then the class of this element will be used.
+    ///
+    /// By default this is set to `None`.
+    pub const fn popup_class(mut self, class: ClassID) -> Self {
+        self.popup_class = Some(class);
+        self
+    }
+
+    /// The z index of the popup menu. The popup menu is always assigned to
+    /// the window's scissoring rectangle so that it can be shown above
+    /// other content.
+    ///
+    /// If this is `None`, then [`DEFAULT_POPUP_Z_INDEX`] will be used.
+    ///
+    /// By default this is set to `None`.
+    pub const fn popup_z_index(mut self, z_index: ZIndex) -> Self {
+        self.popup_z_index = Some(z_index);
+        self
+    }
+
+    /// An offset that can be used mainly to correct the position of text.
+    /// This does not effect the position of the background quad.
+    ///
+    /// By default this is set to an offset of zero.
+    pub const fn text_offset(mut self, offset: Vector) -> Self {
+        self.text_offset = offset;
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> DropDown {
+        let DropDownBuilder {
+            on_selected,
+            options,
+            selected_index,
+            popup_class,
+            popup_z_index,
+            text_offset,
+            tooltip_data,
+            disabled,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let selected_index = clamp_selected_index(selected_index, options.len());
+
+        let style = window_cx
+            .res
+            .style_system
+            .get::<ButtonStyle>(window_cx.builder_class(class));
+        let cursor_icon = style.cursor_icon;
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            inner: ButtonInner::new(
+                options.get(selected_index),
+                None,
+                text_offset,
+                Vector::default(),
+                None,
+                IconScale::default(),
+                disabled,
+                TextIconLayout::default(),
+                &style,
+                &mut window_cx.res.font_system,
+            ),
+            options,
+            selected_index,
+            text_changed: false,
+            tooltip_inner: TooltipInner::new(tooltip_data),
+        }));
+
+        // The header notifier is only known once the header is built, so
+        // share it with the popup's selection callback through a cell.
+        let header_notifier: Rc<RefCell<Option<CustomStateNotifier>>> = Default::default();
+
+        let popup = {
+            let shared_state = Rc::clone(&shared_state);
+            let header_notifier = Rc::clone(&header_notifier);
+
+            let mut builder = DropDownMenu::builder()
+                .entries(menu_entries(&RefCell::borrow(&shared_state).options))
+                .z_index(popup_z_index.unwrap_or(DEFAULT_POPUP_Z_INDEX))
+                .scissor_rect(ScissorRectID::DEFAULT)
+                .class(popup_class.unwrap_or_else(|| window_cx.builder_class(class)));
+
+            builder.on_entry_selected_internal = Some(Box::new(move |index| {
+                let mut shared_state = RefCell::borrow_mut(&shared_state);
+                if shared_state.selected_index != index {
+                    shared_state.selected_index = index;
+                    shared_state.text_changed = true;
+
+                    if let Some(notifier) = RefCell::borrow_mut(&header_notifier).as_mut() {
+                        notifier.notify();
+                    }
+                }
+            }));
+
+            builder.action = on_selected;
+
+            builder.build(window_cx)
+        };
+
+        let el = ElementBuilder::new(DropDownElement {
+            shared_state: Rc::clone(&shared_state),
+            popup_opener: popup.opener(),
+            cursor_icon,
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+        .build(window_cx);
+
+        *RefCell::borrow_mut(&header_notifier) = Some(el.custom_state_notifier());
+
+        DropDown {
+            el,
+            shared_state,
+            popup,
+        }
+    }
+}
+
+/// A box showing the selected option which opens a popup menu with all of
+/// the options when clicked.
+///
+/// The box is styled with a [`ButtonStyle`], and the popup menu is styled
+/// with a [`DropDownMenuStyle`].
+struct DropDownElement {
+    shared_state: Rc<RefCell<SharedState>>,
+    popup_opener: DropDownMenuOpener,
+    cursor_icon: Option<CursorIcon>,
+}
+
+impl<A: Clone + 'static> Element<A> for DropDownElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        shared_state
+            .tooltip_inner
+            .handle_event(&event, shared_state.inner.disabled(), cx);
+
+        match event {
+            ElementEvent::CustomStateChanged => {
+                if shared_state.text_changed {
+                    shared_state.text_changed = false;
+
+                    let text_properties = cx
+                        .res
+                        .style_system
+                        .get::<ButtonStyle>(cx.class())
+                        .text_properties;
+
+                    let SharedState {
+                        inner,
+                        options,
+                        selected_index,
+                        ..
+                    } = &mut *shared_state;
+
+                    inner.set_text(
+                        options.get(*selected_index),
+                        &mut cx.res.font_system,
+                        || text_properties,
+                    );
+                }
+
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<ButtonStyle>(cx.class());
+                self.cursor_icon = style.cursor_icon;
+                shared_state
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { .. }) => {
+                if shared_state.inner.state() == ButtonState::Disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if let Some(cursor_icon) = self.cursor_icon {
+                    cx.cursor_icon = cursor_icon;
+                }
+
+                if shared_state.inner.state() == ButtonState::Idle
+                    && shared_state.inner.set_state(ButtonState::Hovered)
+                {
+                    cx.request_repaint();
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(PointerEvent::PointerLeft) => {
+                if shared_state.inner.state() == ButtonState::Hovered
+                    || shared_state.inner.state() == ButtonState::Down
+                {
+                    if shared_state.inner.set_state(ButtonState::Idle) {
+                        cx.request_repaint();
+                    }
+
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed { button, .. }) => {
+                if shared_state.inner.state() == ButtonState::Disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if button == PointerButton::Primary {
+                    if !shared_state.options.is_empty() {
+                        let rect = cx.rect();
+                        self.popup_opener.open(
+                            Point::new(rect.min_x(), rect.max_y()),
+                            Some(shared_state.selected_index),
+                        );
+                    }
+
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        let label_primitives = shared_state.inner.render(
+            Rect::from_size(cx.bounds_size),
+            &cx.res
+                .style_system
+                .get_with_opacity::<ButtonStyle>(cx.class, cx.opacity),
+            &mut cx.res.font_system,
+        );
+
+        if let Some(quad_primitive) = label_primitives.bg_quad {
+            primitives.add(quad_primitive);
+        }
+
+        if let Some(p) = label_primitives.text {
+            primitives.set_z_index(1);
+            primitives.add_text(p);
+        }
+    }
+}
+
+/// A handle to a [`DropDownElement`], a combo box which shows a list of
+/// options in a popup menu.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_set_tooltip]
+pub struct DropDown {
+    shared_state: Rc<RefCell<SharedState>>,
+    popup: DropDownMenu,
+}
+
+struct SharedState {
+    inner: ButtonInner,
+    options: Vec<String>,
+    selected_index: usize,
+    text_changed: bool,
+    tooltip_inner: TooltipInner,
+}
+
+impl DropDown {
+    pub fn builder<A: Clone + 'static>() -> DropDownBuilder<A> {
+        DropDownBuilder::default()
+    }
+
+    /// Returns the size of the padded background rectangle if it were to
+    /// cover the text of the selected option.
+    ///
+    /// This size is automatically cached, so it should be relatively
+    /// inexpensive to call.
+    pub fn desired_size(&self, res: &mut ResourceCtx) -> Size {
+        RefCell::borrow_mut(&self.shared_state)
+            .inner
+            .desired_size(|| {
+                res.style_system
+                    .get::<ButtonStyle>(self.el.class())
+                    .padding_info()
+            })
+    }
+
+    /// Set the list of options.
+    ///
+    /// If the selected index is out of range of the new options, then the
+    /// first option will be selected.
+    ///
+    /// Note this will *always* trigger an element update, so use this method
+    /// sparingly.
+    pub fn set_options(&mut self, options: Vec<String>) {
+        self.popup.set_entries(menu_entries(&options));
+
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        shared_state.selected_index =
+            clamp_selected_index(shared_state.selected_index, options.len());
+        shared_state.options = options;
+        shared_state.text_changed = true;
+        self.el.notify_custom_state_change();
+    }
+
+    pub fn options<'a>(&'a self) -> Ref<'a, [String]> {
+        Ref::map(RefCell::borrow(&self.shared_state), |s| {
+            s.options.as_slice()
+        })
+    }
+
+    /// Set the selected option. This does not send the `on_selected` action.
+    ///
+    /// If the index is out of range, then the first option will be selected.
+    ///
+    /// Returns `true` if the selected index has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_selected(&mut self, index: usize) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        let index = clamp_selected_index(index, shared_state.options.len());
+        if shared_state.selected_index != index {
+            shared_state.selected_index = index;
+            shared_state.text_changed = true;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn selected_index(&self) -> usize {
+        RefCell::borrow(&self.shared_state).selected_index
+    }
+
+    /// Set the disabled state of this element.
+    ///
+    /// Returns `true` if the disabled state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_disabled(&mut self, disabled: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if disabled && shared_state.inner.state() != ButtonState::Disabled {
+            shared_state.inner.set_state(ButtonState::Disabled);
+            self.el.notify_custom_state_change();
+            true
+        } else if !disabled && shared_state.inner.state() == ButtonState::Disabled {
+            shared_state.inner.set_state(ButtonState::Idle);
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn disabled(&self) -> bool {
+        RefCell::borrow(&self.shared_state).inner.disabled()
+    }
+
+    /// Layout out the element (with the top-left corner of the bounds set to `origin`).
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout(&mut self, origin: Point, res: &mut ResourceCtx) -> bool {
+        let size = self.desired_size(res);
+        self.el.set_rect(Rect::new(origin, size))
+    }
+
+    /// Layout out the element aligned to the given point.
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout_aligned(&mut self, point: Point, align: Align2, res: &mut ResourceCtx) -> bool {
+        let size = self.desired_size(res);
+        self.el.set_rect(align.align_rect_to_point(point, size))
+    }
+}
+
+fn menu_entries(options: &[String]) -> Vec<MenuEntry> {
+    options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| MenuEntry::option(option.clone(), i))
+        .collect()
+}
+
+fn clamp_selected_index(index: usize, num_options: usize) -> usize {
+    if index >= num_options {
+        0
+    } else {
+        index
+    }
+}
@@ -3,6 +3,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::derive::*;
+use crate::element_system::element::CustomStateNotifier;
 use crate::prelude::*;
 use crate::theme::DEFAULT_ICON_SIZE;
 use crate::vg::{
@@ -18,7 +19,6 @@ use super::label::{LabelInner, LabelPaddingInfo, LabelStyle};
 //   * option A: use scroll wheel
 //   * option B: stack horizontally
 // * nested menus
-// * keyboard navigation
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -91,13 +91,20 @@ enum MenuEntryInner {
 
 /// The style of a [`DropDownMenu`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct DropDownMenuStyle {
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
     /// The properties of the right text.
     ///
     /// If this is `None`, then `text_properties` will be used.
     ///
     /// By default this is set to `None`.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub right_text_properties: Option<TextProperties>,
 
     /// The width and height of the icon in points
@@ -148,6 +155,13 @@ pub struct DropDownMenuStyle {
     /// By default this is set to `None`.
     pub right_text_color_hover: Option<RGBA8>,
 
+    /// How to style the text and icon of disabled entries.
+    ///
+    /// This is only used by [`ContextMenu`](super::context_menu::ContextMenu).
+    ///
+    /// By default this is set to `DisabledColor::default()`.
+    pub text_color_disabled: DisabledColor,
+
     pub back_quad: QuadStyle,
     pub entry_bg_quad_hover: QuadStyle,
 
@@ -195,6 +209,7 @@ impl Default for DropDownMenuStyle {
             icon_color_hover: None,
             right_text_color: None,
             right_text_color_hover: None,
+            text_color_disabled: DisabledColor::default(),
             back_quad: QuadStyle::TRANSPARENT,
             entry_bg_quad_hover: QuadStyle::TRANSPARENT,
             outer_padding: 0.0,
@@ -211,7 +226,7 @@ impl Default for DropDownMenuStyle {
 }
 
 impl DropDownMenuStyle {
-    fn label_styles(&self, hovered: bool) -> (LabelStyle, LabelStyle) {
+    pub(super) fn label_styles(&self, hovered: bool) -> (LabelStyle, LabelStyle) {
         (
             LabelStyle {
                 text_properties: self.text_properties,
@@ -257,13 +272,13 @@ impl DropDownMenuStyle {
         )
     }
 
-    fn text_row_height(&self) -> f32 {
+    pub(super) fn text_row_height(&self) -> f32 {
         self.text_properties.metrics.line_height
             + self.left_text_padding.top
             + self.left_text_padding.bottom
     }
 
-    fn left_padding_info(&self) -> LabelPaddingInfo {
+    pub(super) fn left_padding_info(&self) -> LabelPaddingInfo {
         LabelPaddingInfo {
             default_icon_size: self.icon_size,
             text_padding: self.left_text_padding,
@@ -272,7 +287,7 @@ impl DropDownMenuStyle {
         }
     }
 
-    fn right_padding_info(&self) -> LabelPaddingInfo {
+    pub(super) fn right_padding_info(&self) -> LabelPaddingInfo {
         LabelPaddingInfo {
             default_icon_size: 0.0,
             text_padding: self.right_text_padding,
@@ -342,6 +357,28 @@ impl ElementStyle for DropDownMenuStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        if let Some(right_text_properties) = &mut style.right_text_properties {
+            scale_text_properties(right_text_properties, text_scale);
+        }
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.text_color_hover.multiply_alpha(multiplier);
+        self.icon_color.multiply_alpha(multiplier);
+        self.icon_color_hover.multiply_alpha(multiplier);
+        self.right_text_color.multiply_alpha(multiplier);
+        self.right_text_color_hover.multiply_alpha(multiplier);
+        self.text_color_disabled.multiply_alpha(multiplier);
+        self.back_quad.multiply_alpha(multiplier);
+        self.entry_bg_quad_hover.multiply_alpha(multiplier);
+        self.divider_color.multiply_alpha(multiplier);
+    }
 }
 
 #[element_builder]
@@ -351,6 +388,8 @@ pub struct DropDownMenuBuilder<A: Clone + 'static> {
     pub action: Option<Box<dyn FnMut(usize) -> A>>,
     pub entries: Vec<MenuEntry>,
     pub position: Point,
+    /// Called with the unique ID of the selected entry before `action`.
+    pub(crate) on_entry_selected_internal: Option<Box<dyn FnMut(usize)>>,
 }
 
 impl<A: Clone + 'static> DropDownMenuBuilder<A> {
@@ -372,11 +411,14 @@ impl<A: Clone + 'static> DropDownMenuBuilder<A> {
             z_index,
             position,
             scissor_rect,
+            on_entry_selected_internal,
         } = self;
 
         let shared_state = Rc::new(RefCell::new(SharedState {
             new_entries: None,
             open_requested: false,
+            open_position: None,
+            open_highlighted_entry: None,
         }));
 
         let style = window_cx
@@ -392,6 +434,7 @@ impl<A: Clone + 'static> DropDownMenuBuilder<A> {
         let el = ElementBuilder::new(DropDownMenuElement {
             shared_state: Rc::clone(&shared_state),
             action,
+            on_entry_selected_internal,
             entries,
             size,
             active: false,
@@ -405,6 +448,7 @@ impl<A: Clone + 'static> DropDownMenuBuilder<A> {
                 | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
                 | ElementFlags::LISTENS_TO_FOCUS_CHANGE
                 | ElementFlags::LISTENS_TO_POINTER_OUTSIDE_BOUNDS_WHEN_FOCUSED
+                | ElementFlags::LISTENS_TO_KEYS_WHEN_FOCUSED
                 | ElementFlags::LISTENS_TO_POSITION_CHANGE,
         )
         .build(window_cx);
@@ -416,6 +460,7 @@ impl<A: Clone + 'static> DropDownMenuBuilder<A> {
 struct DropDownMenuElement<A: Clone + 'static> {
     shared_state: Rc<RefCell<SharedState>>,
     action: Option<Box<dyn FnMut(usize) -> A>>,
+    on_entry_selected_internal: Option<Box<dyn FnMut(usize)>>,
     entries: Vec<MenuEntryInner>,
     size: Size,
     active: bool,
@@ -423,6 +468,63 @@ struct DropDownMenuElement<A: Clone + 'static> {
     cursor_icon: Option<CursorIcon>,
 }
 
+impl<A: Clone + 'static> DropDownMenuElement<A> {
+    /// Select the option entry with the given index and close the menu.
+    fn select_entry(&mut self, entry_index: usize, cx: &mut ElementContext<'_, A>) {
+        let Some(MenuEntryInner::Option { unique_id, .. }) = self.entries.get(entry_index) else {
+            return;
+        };
+        let unique_id = *unique_id;
+
+        if let Some(f) = &mut self.on_entry_selected_internal {
+            (f)(unique_id);
+        }
+
+        if let Some(action) = &mut self.action {
+            cx.send_action((action)(unique_id));
+        }
+
+        cx.release_focus();
+        cx.cursor_icon = CursorIcon::Default;
+    }
+
+    /// Move the highlighted entry up (`-1`) or down (`1`), skipping dividers.
+    fn move_highlighted_entry(&mut self, direction: isize) -> bool {
+        let options: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| match entry {
+                MenuEntryInner::Option { .. } => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        if options.is_empty() {
+            return false;
+        }
+
+        let new_index = match self
+            .hovered_entry_index
+            .and_then(|hovered| options.iter().position(|&i| i == hovered))
+        {
+            Some(pos) => {
+                let new_pos = (pos as isize + direction).clamp(0, options.len() as isize - 1);
+                options[new_pos as usize]
+            }
+            None if direction < 0 => *options.last().unwrap(),
+            None => options[0],
+        };
+
+        if self.hovered_entry_index != Some(new_index) {
+            self.hovered_entry_index = Some(new_index);
+            true
+        } else {
+            false
+        }
+    }
+}
+
 impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
     fn on_event(
         &mut self,
@@ -439,8 +541,11 @@ impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
                     self.active = true;
                     show = true;
                     request_focus = true;
+                    self.hovered_entry_index = shared_state.open_highlighted_entry;
                 }
                 shared_state.open_requested = false;
+                shared_state.open_highlighted_entry = None;
+                let open_position = shared_state.open_position.take();
 
                 if let Some(new_entries) = shared_state.new_entries.take() {
                     let style = cx.res.style_system.get(cx.class());
@@ -466,7 +571,7 @@ impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
                 }
 
                 if show {
-                    let rect = Rect::new(cx.rect().origin, self.size);
+                    let rect = Rect::new(open_position.unwrap_or(cx.rect().origin), self.size);
                     let layout_info = layout(rect, cx.window_size());
                     if let Some(new_bounds) = layout_info.new_bounds {
                         cx.set_rect(new_bounds);
@@ -541,18 +646,13 @@ impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
                 }
 
                 if button == PointerButton::Primary && cx.rect().contains(position) {
-                    let mut selected_entry_id = None;
+                    let mut selected_entry_index = None;
                     let pointer_y = position.y - cx.rect().min_y();
-                    for entry in self.entries.iter() {
+                    for (i, entry) in self.entries.iter().enumerate() {
                         match entry {
-                            MenuEntryInner::Option {
-                                start_y,
-                                end_y,
-                                unique_id,
-                                ..
-                            } => {
+                            MenuEntryInner::Option { start_y, end_y, .. } => {
                                 if pointer_y >= *start_y && pointer_y < *end_y {
-                                    selected_entry_id = Some(*unique_id);
+                                    selected_entry_index = Some(i);
                                     break;
                                 }
                             }
@@ -560,14 +660,38 @@ impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
                         }
                     }
 
-                    if let Some(id) = selected_entry_id {
-                        if let Some(action) = &mut self.action {
-                            cx.send_action((action)(id)).unwrap();
-                        }
+                    if let Some(i) = selected_entry_index {
+                        self.select_entry(i, cx);
+                    }
+                }
 
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Keyboard(KeyboardEvent {
+                state: KeyState::Down,
+                code,
+                ..
+            }) => {
+                if !self.active {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                match code {
+                    Code::ArrowDown | Code::ArrowUp => {
+                        let direction = if code == Code::ArrowDown { 1 } else { -1 };
+                        if self.move_highlighted_entry(direction) {
+                            cx.request_repaint();
+                        }
+                    }
+                    Code::Enter | Code::NumpadEnter => {
+                        if let Some(i) = self.hovered_entry_index {
+                            self.select_entry(i, cx);
+                        }
+                    }
+                    Code::Escape => {
                         cx.release_focus();
-                        cx.cursor_icon = CursorIcon::Default;
                     }
+                    _ => {}
                 }
 
                 return EventCaptureStatus::Captured;
@@ -596,7 +720,10 @@ impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
     }
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
-        let style: &DropDownMenuStyle = cx.res.style_system.get(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<DropDownMenuStyle>(cx.class, cx.opacity);
 
         let (left_style_idle, right_style_idle) = style.label_styles(false);
         let (left_style_hover, right_style_hover) = style.label_styles(true);
@@ -699,6 +826,29 @@ impl<A: Clone + 'static> Element<A> for DropDownMenuElement<A> {
 struct SharedState {
     new_entries: Option<Vec<MenuEntry>>,
     open_requested: bool,
+    open_position: Option<Point>,
+    open_highlighted_entry: Option<usize>,
+}
+
+/// Opens a [`DropDownMenu`] from inside of another element.
+pub(crate) struct DropDownMenuOpener {
+    shared_state: Rc<RefCell<SharedState>>,
+    notifier: CustomStateNotifier,
+}
+
+impl DropDownMenuOpener {
+    /// Open the menu at the given position (in window coordinates) with the
+    /// entry at the given index highlighted.
+    pub fn open(&mut self, position: Point, highlighted_entry: Option<usize>) {
+        {
+            let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+            shared_state.open_requested = true;
+            shared_state.open_position = Some(position);
+            shared_state.open_highlighted_entry = highlighted_entry;
+        }
+
+        self.notifier.notify();
+    }
 }
 
 /// A handle to a [`DropDownMenuElement`].
@@ -740,6 +890,13 @@ impl DropDownMenu {
         RefCell::borrow_mut(&self.shared_state).open_requested = true;
         self.el.notify_custom_state_change();
     }
+
+    pub(crate) fn opener(&self) -> DropDownMenuOpener {
+        DropDownMenuOpener {
+            shared_state: Rc::clone(&self.shared_state),
+            notifier: self.el.custom_state_notifier(),
+        }
+    }
 }
 
 fn build_entries(
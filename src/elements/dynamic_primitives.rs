@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rootvg::PrimitiveGroup;
+
+use crate::derive::*;
+use crate::prelude::*;
+
+#[element_builder]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[derive(Default)]
+pub struct DynamicPrimitivesBuilder {
+    pub primitives: PrimitiveGroup,
+}
+
+impl DynamicPrimitivesBuilder {
+    /// The initial primitives to draw.
+    pub fn primitives(mut self, primitives: PrimitiveGroup) -> Self {
+        self.primitives = primitives;
+        self
+    }
+
+    pub fn build<A: Clone + 'static>(
+        self,
+        window_cx: &mut WindowContext<'_, A>,
+    ) -> DynamicPrimitives {
+        let DynamicPrimitivesBuilder {
+            primitives,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let shared_state = Rc::new(RefCell::new(SharedState { primitives }));
+
+        let el = ElementBuilder::new(DynamicPrimitivesElement {
+            shared_state: Rc::clone(&shared_state),
+        })
+        .builder_values(z_index, scissor_rect, None, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(ElementFlags::PAINTS)
+        .build(window_cx);
+
+        DynamicPrimitives { el, shared_state }
+    }
+}
+
+struct DynamicPrimitivesElement {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl DynamicPrimitivesElement {
+    fn record_primitives(&self, primitives: &mut PrimitiveGroup) {
+        primitives.clone_from(&RefCell::borrow(&self.shared_state).primitives);
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for DynamicPrimitivesElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        if let ElementEvent::CustomStateChanged = event {
+            cx.request_repaint();
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, _cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        self.record_primitives(primitives);
+    }
+}
+
+struct SharedState {
+    primitives: PrimitiveGroup,
+}
+
+/// An element which draws an arbitrary [`PrimitiveGroup`] supplied by the
+/// application.
+///
+/// This is an escape hatch for fully custom, frequently-changing drawing that
+/// doesn't fit the style/cache model of the other elements (i.e. a spectrum
+/// analyzer). The primitives are positioned relative to the top-left corner of
+/// the element's bounds. Every time the primitives are set, the element is
+/// re-recorded on the next frame.
+///
+/// The primitives are drawn as they are given, so the application is
+/// responsible for fading them if the opacity of the element is changed.
+#[element_handle]
+#[element_handle_set_rect]
+pub struct DynamicPrimitives {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl DynamicPrimitives {
+    pub fn builder() -> DynamicPrimitivesBuilder {
+        DynamicPrimitivesBuilder::default()
+    }
+
+    /// Replace the primitives to draw.
+    ///
+    /// This will always trigger an element update.
+    pub fn set_primitives(&mut self, primitives: PrimitiveGroup) {
+        RefCell::borrow_mut(&self.shared_state).primitives = primitives;
+        self.el.notify_custom_state_change();
+    }
+
+    /// Rebuild the primitives to draw in place, reusing the existing
+    /// allocations.
+    ///
+    /// The group is cleared before `f` is called.
+    ///
+    /// This will always trigger an element update.
+    pub fn update_primitives<F: FnOnce(&mut PrimitiveGroup)>(&mut self, f: F) {
+        {
+            let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+            shared_state.primitives.clear();
+            (f)(&mut shared_state.primitives);
+        }
+        self.el.notify_custom_state_change();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_quad(color: RGBA8, rect: Rect) -> PrimitiveGroup {
+        let mut primitives = PrimitiveGroup::new();
+        primitives.add(
+            QuadStyle {
+                bg: Background::Solid(color),
+                border: BorderStyle::TRANSPARENT,
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            }
+            .create_primitive(rect),
+        );
+        primitives
+    }
+
+    #[test]
+    fn test_dynamic_primitives_are_recorded_every_time() {
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            primitives: PrimitiveGroup::new(),
+        }));
+        let element = DynamicPrimitivesElement {
+            shared_state: Rc::clone(&shared_state),
+        };
+
+        let first = solid_quad(color::WHITE, rect(0.0, 0.0, 10.0, 10.0));
+        RefCell::borrow_mut(&shared_state).primitives = first.clone();
+
+        let mut output = PrimitiveGroup::new();
+        element.record_primitives(&mut output);
+        assert_eq!(output, first);
+
+        let second = solid_quad(color::BLACK, rect(5.0, 5.0, 20.0, 4.0));
+        RefCell::borrow_mut(&shared_state).primitives = second.clone();
+
+        element.record_primitives(&mut output);
+        assert_eq!(output, second);
+        assert_ne!(output, first);
+    }
+}
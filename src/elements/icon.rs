@@ -11,6 +11,11 @@ use crate::vg::{
 
 /// The style of an [`Icon`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct IconStyle {
     /// The width and height of the icon in points (if the user hasn't
     /// manually set a size for the icon).
@@ -76,6 +81,11 @@ impl ElementStyle for IconStyle {
             ..Default::default()
         }
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.color.multiply_alpha(multiplier);
+        self.back_quad.multiply_alpha(multiplier);
+    }
 }
 
 // Information used to calculate icon padding.
@@ -298,7 +308,9 @@ impl<A: Clone + 'static> Element<A> for IconElement {
 
         let icon_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
-            cx.res.style_system.get(cx.class),
+            &cx.res
+                .style_system
+                .get_with_opacity::<IconStyle>(cx.class, cx.opacity),
         );
 
         if let Some(quad_primitive) = icon_primitives.bg_quad {
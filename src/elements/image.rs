@@ -0,0 +1,340 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rootvg::image::{ImagePrimitive, RcTexture};
+
+use crate::derive::*;
+use crate::prelude::*;
+
+/// How an image is scaled to fit the bounds of an [`Image`] element.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Scale the image uniformly so that it fits entirely within the
+    /// bounds, leaving empty space along one axis if the aspect ratios
+    /// differ.
+    #[default]
+    Fit,
+    /// Scale the image uniformly so that it covers the bounds entirely,
+    /// cropping the parts of the image which fall outside of the bounds.
+    Fill,
+    /// Stretch the image to the size of the bounds, ignoring its aspect
+    /// ratio.
+    Stretch,
+    /// Draw the image at its natural size in the center of the bounds,
+    /// cropping the parts of the image which fall outside of the bounds.
+    Center,
+}
+
+/// The area of the bounds an image is drawn into, along with the part of
+/// the image that is visible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageLayout {
+    /// The rectangle to draw the image into, relative to the origin of the
+    /// bounds.
+    pub dest: Rect,
+    /// The visible part of the image in normalized texture coordinates,
+    /// where `(0.0, 0.0)` is the top-left corner and `(1.0, 1.0)` is the
+    /// bottom-right corner of the image.
+    pub uv: Rect,
+}
+
+impl ScaleMode {
+    /// Compute where an image of the given size (in logical points) is drawn
+    /// within bounds of the given size.
+    ///
+    /// Returns `None` if nothing would be drawn.
+    pub fn layout(&self, bounds_size: Size, image_size: Size) -> Option<ImageLayout> {
+        if bounds_size.is_empty() || image_size.is_empty() {
+            return None;
+        }
+
+        let scale_x = bounds_size.width / image_size.width;
+        let scale_y = bounds_size.height / image_size.height;
+
+        let scaled_size = match self {
+            Self::Fit => image_size * scale_x.min(scale_y),
+            Self::Fill => image_size * scale_x.max(scale_y),
+            Self::Stretch => bounds_size,
+            Self::Center => image_size,
+        };
+
+        let scaled_rect = Rect::new(
+            Point::new(
+                (bounds_size.width - scaled_size.width) * 0.5,
+                (bounds_size.height - scaled_size.height) * 0.5,
+            ),
+            scaled_size,
+        );
+
+        let dest = scaled_rect.intersection(&Rect::from_size(bounds_size))?;
+        if dest.is_empty() {
+            return None;
+        }
+
+        let uv = Rect::new(
+            Point::new(
+                (dest.min_x() - scaled_rect.min_x()) / scaled_rect.width(),
+                (dest.min_y() - scaled_rect.min_y()) / scaled_rect.height(),
+            ),
+            Size::new(
+                dest.width() / scaled_rect.width(),
+                dest.height() / scaled_rect.height(),
+            ),
+        );
+
+        Some(ImageLayout { dest, uv })
+    }
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[derive(Default)]
+pub struct ImageBuilder {
+    pub texture: Option<RcTexture>,
+    pub scale_mode: ScaleMode,
+}
+
+impl ImageBuilder {
+    /// The texture to draw.
+    pub fn texture(mut self, texture: RcTexture) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    /// Draw a bitmap of RGBA8 pixels. See [`texture_from_rgba8`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels` is not `width * height * 4` bytes long.
+    pub fn rgba8(self, width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        self.texture(texture_from_rgba8(width, height, pixels))
+    }
+
+    /// How the image is scaled to fit the bounds of the element.
+    ///
+    /// By default this is set to [`ScaleMode::Fit`].
+    pub const fn scale_mode(mut self, mode: ScaleMode) -> Self {
+        self.scale_mode = mode;
+        self
+    }
+
+    pub fn build<A: Clone + 'static>(self, window_cx: &mut WindowContext<'_, A>) -> Image {
+        let ImageBuilder {
+            texture,
+            scale_mode,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            texture,
+            scale_mode,
+        }));
+
+        let el = ElementBuilder::new(ImageElement {
+            shared_state: Rc::clone(&shared_state),
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(ElementFlags::PAINTS)
+        .build(window_cx);
+
+        Image { el, shared_state }
+    }
+}
+
+struct ImageElement {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl<A: Clone + 'static> Element<A> for ImageElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        if let ElementEvent::CustomStateChanged = event {
+            cx.request_repaint();
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        let Some(texture) = &shared_state.texture else {
+            return;
+        };
+
+        let texture_size = texture.size();
+        let image_size = Size::new(
+            texture_size.width as f32 / cx.scale.0,
+            texture_size.height as f32 / cx.scale.0,
+        );
+
+        let Some(layout) = shared_state.scale_mode.layout(cx.bounds_size, image_size) else {
+            return;
+        };
+
+        primitives.add_image(ImagePrimitive::new_with_uv(
+            texture.clone(),
+            layout.dest.origin,
+            layout.dest.size,
+            layout.uv.origin,
+            layout.uv.size,
+        ));
+    }
+}
+
+struct SharedState {
+    texture: Option<RcTexture>,
+    scale_mode: ScaleMode,
+}
+
+/// An element which draws a texture scaled to its bounds.
+///
+/// Note, rootvg image primitives have no tint color, so an image is only
+/// hidden by an opacity of `0.0` and is otherwise drawn fully opaque.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_layout_aligned]
+pub struct Image {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl Image {
+    pub fn builder() -> ImageBuilder {
+        ImageBuilder::default()
+    }
+
+    /// Set the texture to draw. Set this to `None` to draw nothing.
+    ///
+    /// The texture handle is cheap to clone, so the same texture can be
+    /// shared between many elements. Swapping the texture does not rebuild
+    /// the element, it only repaints it.
+    pub fn set_image(&mut self, texture: Option<RcTexture>) {
+        RefCell::borrow_mut(&self.shared_state).texture = texture;
+        self.el.notify_custom_state_change();
+    }
+
+    /// Swap the drawn bitmap for the given RGBA8 pixels. See
+    /// [`texture_from_rgba8`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels` is not `width * height * 4` bytes long.
+    pub fn set_rgba8(&mut self, width: u32, height: u32, pixels: Vec<u8>) {
+        self.set_image(Some(texture_from_rgba8(width, height, pixels)));
+    }
+
+    pub fn image(&self) -> Option<RcTexture> {
+        RefCell::borrow(&self.shared_state).texture.clone()
+    }
+
+    /// Set how the image is scaled to fit the bounds of the element.
+    ///
+    /// Returns `true` if the scale mode has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_scale_mode(&mut self, mode: ScaleMode) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.scale_mode != mode {
+            shared_state.scale_mode = mode;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn scale_mode(&self) -> ScaleMode {
+        RefCell::borrow(&self.shared_state).scale_mode
+    }
+}
+
+/// Create a texture from a bitmap of `width * height` pixels, where each
+/// pixel is four bytes in RGBA order, row by row from the top-left corner.
+///
+/// The texture is uploaded to the GPU the first time it is drawn.
+///
+/// # Panics
+///
+/// Panics if `pixels` is not `width * height * 4` bytes long.
+pub fn texture_from_rgba8(width: u32, height: u32, pixels: Vec<u8>) -> RcTexture {
+    let len = pixels.len();
+    let image = ::image::RgbaImage::from_raw(width, height, pixels).unwrap_or_else(|| {
+        panic!(
+            "expected {} bytes of RGBA8 pixels for a {}x{} image, got {}",
+            width as usize * height as usize * 4,
+            width,
+            height,
+            len
+        )
+    });
+
+    RcTexture::new(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn test_scale_mode_layout() {
+        let bounds = Size::new(200.0, 100.0);
+        let image = Size::new(50.0, 50.0);
+
+        let layout = ScaleMode::Fit.layout(bounds, image).unwrap();
+        assert_eq!(layout.dest, rect(50.0, 0.0, 100.0, 100.0));
+        assert_eq!(layout.uv, rect(0.0, 0.0, 1.0, 1.0));
+
+        // The image is scaled to 200x200, so only the middle half of it is
+        // visible vertically.
+        let layout = ScaleMode::Fill.layout(bounds, image).unwrap();
+        assert_eq!(layout.dest, rect(0.0, 0.0, 200.0, 100.0));
+        assert_eq!(layout.uv, rect(0.0, 0.25, 1.0, 0.5));
+
+        let layout = ScaleMode::Stretch.layout(bounds, image).unwrap();
+        assert_eq!(layout.dest, rect(0.0, 0.0, 200.0, 100.0));
+        assert_eq!(layout.uv, rect(0.0, 0.0, 1.0, 1.0));
+
+        let layout = ScaleMode::Center.layout(bounds, image).unwrap();
+        assert_eq!(layout.dest, rect(75.0, 25.0, 50.0, 50.0));
+        assert_eq!(layout.uv, rect(0.0, 0.0, 1.0, 1.0));
+
+        let layout = ScaleMode::Center
+            .layout(bounds, Size::new(400.0, 50.0))
+            .unwrap();
+        assert_eq!(layout.dest, rect(0.0, 25.0, 200.0, 50.0));
+        assert_eq!(layout.uv, rect(0.25, 0.0, 0.5, 1.0));
+
+        assert!(ScaleMode::Fit.layout(Size::zero(), image).is_none());
+    }
+
+    #[test]
+    fn test_texture_from_rgba8_has_the_size_of_the_bitmap() {
+        let texture = texture_from_rgba8(3, 2, vec![255; 3 * 2 * 4]);
+        assert_eq!(texture.size().width, 3);
+        assert_eq!(texture.size().height, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 24 bytes")]
+    fn test_texture_from_rgba8_rejects_the_wrong_number_of_bytes() {
+        texture_from_rgba8(3, 2, vec![255; 3 * 2 * 3]);
+    }
+}
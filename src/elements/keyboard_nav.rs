@@ -0,0 +1,277 @@
+use crate::event::{Code, KeyState, KeyboardEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyboardNavConfig {
+    /// The number of items in each row. Set this to `1` for a vertical list.
+    ///
+    /// The left and right arrow keys are ignored when this is `1`.
+    ///
+    /// By default this is set to `1`.
+    pub columns: usize,
+    /// The number of rows that are visible at once, used by the `PageUp`
+    /// and `PageDown` keys.
+    ///
+    /// By default this is set to `10`.
+    pub page_rows: usize,
+    /// Whether the arrow keys wrap around to the other end of the
+    /// collection when the cursor is at the first or last item.
+    ///
+    /// `Home`, `End`, `PageUp`, and `PageDown` never wrap.
+    ///
+    /// By default this is set to `false`.
+    pub wrap: bool,
+}
+
+impl Default for KeyboardNavConfig {
+    fn default() -> Self {
+        Self {
+            columns: 1,
+            page_rows: 10,
+            wrap: false,
+        }
+    }
+}
+
+/// A selection cursor for list, grid, and tree elements that is moved with
+/// the arrow keys, `Home`, `End`, `PageUp`, and `PageDown`.
+///
+/// Items are indexed in row-major order. For trees, the indices of the
+/// currently visible rows should be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyboardNav {
+    pub config: KeyboardNavConfig,
+    num_items: usize,
+    selected: Option<usize>,
+}
+
+impl KeyboardNav {
+    pub fn new(config: KeyboardNavConfig, num_items: usize) -> Self {
+        Self {
+            config,
+            num_items,
+            selected: None,
+        }
+    }
+
+    pub fn num_items(&self) -> usize {
+        self.num_items
+    }
+
+    /// Set the number of items in the collection. If the selected item no
+    /// longer exists, then the selection is moved to the last item.
+    ///
+    /// Returns `true` if the selection has changed.
+    pub fn set_num_items(&mut self, num_items: usize) -> bool {
+        self.num_items = num_items;
+
+        match self.selected {
+            Some(i) if i >= num_items => self.set_selected(num_items.checked_sub(1)),
+            _ => false,
+        }
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Set the selected item. Indices past the end of the collection are
+    /// clamped to the last item.
+    ///
+    /// Returns `true` if the selection has changed.
+    pub fn set_selected(&mut self, selected: Option<usize>) -> bool {
+        let selected = selected.and_then(|i| {
+            if self.num_items == 0 {
+                None
+            } else {
+                Some(i.min(self.num_items - 1))
+            }
+        });
+
+        if self.selected != selected {
+            self.selected = selected;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Handle a keyboard event.
+    ///
+    /// Returns `Some(new_selected_index)` if the selection has changed, or
+    /// `None` if the event was not a navigation key or the selection did not
+    /// move.
+    pub fn on_keyboard_event(&mut self, event: &KeyboardEvent) -> Option<usize> {
+        if event.state != KeyState::Down {
+            return None;
+        }
+
+        self.on_key(event.code)
+    }
+
+    /// Move the cursor in response to the given key.
+    ///
+    /// Returns `Some(new_selected_index)` if the selection has changed, or
+    /// `None` if the key is not a navigation key or the selection did not
+    /// move.
+    pub fn on_key(&mut self, code: Code) -> Option<usize> {
+        if self.num_items == 0 {
+            return None;
+        }
+
+        let last = self.num_items - 1;
+        let columns = self.config.columns.max(1);
+        let page = self.config.page_rows.max(1) * columns;
+
+        let new_index = match (self.selected, code) {
+            (_, Code::Home) => 0,
+            (_, Code::End) => last,
+            (None, Code::ArrowDown | Code::ArrowRight | Code::PageDown) => 0,
+            (None, Code::ArrowUp | Code::ArrowLeft | Code::PageUp) => last,
+            (Some(i), Code::ArrowDown) => self.step_down(i, columns, last),
+            (Some(i), Code::ArrowUp) => self.step_up(i, columns, last),
+            (Some(i), Code::ArrowRight) if columns > 1 => {
+                if i < last {
+                    i + 1
+                } else if self.config.wrap {
+                    0
+                } else {
+                    i
+                }
+            }
+            (Some(i), Code::ArrowLeft) if columns > 1 => {
+                if i > 0 {
+                    i - 1
+                } else if self.config.wrap {
+                    last
+                } else {
+                    i
+                }
+            }
+            (Some(i), Code::PageDown) => {
+                if i + page <= last {
+                    i + page
+                } else {
+                    // Stay in the same column if possible.
+                    let last_in_column = last - (last % columns) + (i % columns);
+                    if last_in_column <= last {
+                        last_in_column
+                    } else {
+                        last_in_column - columns
+                    }
+                }
+            }
+            (Some(i), Code::PageUp) => i.checked_sub(page).unwrap_or(i % columns),
+            _ => return None,
+        };
+
+        if self.set_selected(Some(new_index)) {
+            Some(new_index)
+        } else {
+            None
+        }
+    }
+
+    fn step_down(&self, i: usize, columns: usize, last: usize) -> usize {
+        if i + columns <= last {
+            i + columns
+        } else if self.config.wrap {
+            i % columns
+        } else {
+            i
+        }
+    }
+
+    fn step_up(&self, i: usize, columns: usize, last: usize) -> usize {
+        if i >= columns {
+            i - columns
+        } else if self.config.wrap {
+            // Move to the last row which has an item in this column.
+            let last_in_column = last - (last % columns) + i;
+            if last_in_column <= last {
+                last_in_column
+            } else {
+                last_in_column - columns
+            }
+        } else {
+            i
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrow_navigation() {
+        let mut nav = KeyboardNav::new(KeyboardNavConfig::default(), 3);
+
+        assert_eq!(nav.on_key(Code::ArrowDown), Some(0));
+        assert_eq!(nav.on_key(Code::ArrowDown), Some(1));
+        assert_eq!(nav.on_key(Code::ArrowDown), Some(2));
+        assert_eq!(nav.on_key(Code::ArrowDown), None);
+        // Left and right are ignored in a list.
+        assert_eq!(nav.on_key(Code::ArrowLeft), None);
+        assert_eq!(nav.selected(), Some(2));
+
+        nav.config.wrap = true;
+        assert_eq!(nav.on_key(Code::ArrowDown), Some(0));
+        assert_eq!(nav.on_key(Code::ArrowUp), Some(2));
+
+        // A grid with 3 columns and 8 items:
+        // 0 1 2
+        // 3 4 5
+        // 6 7
+        let mut nav = KeyboardNav::new(
+            KeyboardNavConfig {
+                columns: 3,
+                wrap: true,
+                ..Default::default()
+            },
+            8,
+        );
+        nav.set_selected(Some(2));
+
+        assert_eq!(nav.on_key(Code::ArrowDown), Some(5));
+        // There is no item below 5, so the cursor wraps to the top row.
+        assert_eq!(nav.on_key(Code::ArrowDown), Some(2));
+        assert_eq!(nav.on_key(Code::ArrowUp), Some(5));
+        assert_eq!(nav.on_key(Code::ArrowRight), Some(6));
+        assert_eq!(nav.on_key(Code::ArrowLeft), Some(5));
+        nav.set_selected(Some(1));
+        assert_eq!(nav.on_key(Code::ArrowUp), Some(7));
+    }
+
+    #[test]
+    fn test_home_and_end_jump_to_ends() {
+        let mut nav = KeyboardNav::new(KeyboardNavConfig::default(), 20);
+
+        assert_eq!(nav.on_key(Code::End), Some(19));
+        assert_eq!(nav.on_key(Code::End), None);
+        assert_eq!(nav.on_key(Code::Home), Some(0));
+
+        // Shrinking the collection moves the selection to the last item.
+        nav.on_key(Code::End);
+        assert!(nav.set_num_items(5));
+        assert_eq!(nav.selected(), Some(4));
+    }
+
+    #[test]
+    fn test_page_down_moves_by_visible_page_size() {
+        let mut nav = KeyboardNav::new(
+            KeyboardNavConfig {
+                page_rows: 4,
+                ..Default::default()
+            },
+            10,
+        );
+        nav.set_selected(Some(1));
+
+        assert_eq!(nav.on_key(Code::PageDown), Some(5));
+        assert_eq!(nav.on_key(Code::PageDown), Some(9));
+        assert_eq!(nav.on_key(Code::PageDown), None);
+        assert_eq!(nav.on_key(Code::PageUp), Some(5));
+        assert_eq!(nav.on_key(Code::PageUp), Some(1));
+        assert_eq!(nav.on_key(Code::PageUp), Some(0));
+    }
+}
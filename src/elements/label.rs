@@ -14,8 +14,14 @@ use crate::vg::text::CustomGlyph;
 
 /// The style of a [`Label`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct LabelStyle {
     /// The properties of the text.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
 
     /// The width and height of the icon in points (if the user hasn't
@@ -66,6 +72,20 @@ pub struct LabelStyle {
     ///
     /// By default this is set to `Align::Center`.
     pub vertical_align: crate::layout::Align,
+
+    /// The horizontal alignment of the text within its bounds.
+    ///
+    /// If this is `None`, then the text is aligned to the side given by the
+    /// label's [`TextIconLayout`].
+    ///
+    /// By default this is set to `None`.
+    pub horizontal_align: Option<crate::layout::Align>,
+
+    /// Whether or not the text should be kept to a single line and truncated
+    /// with an ellipsis ("…") when it overflows its bounds.
+    ///
+    /// By default this is set to `false`.
+    pub truncate_with_ellipsis: bool,
 }
 
 impl LabelStyle {
@@ -92,6 +112,8 @@ impl Default for LabelStyle {
             text_icon_spacing: 0.0,
             back_quad: QuadStyle::TRANSPARENT,
             vertical_align: crate::layout::Align::Center,
+            horizontal_align: None,
+            truncate_with_ellipsis: false,
         }
     }
 }
@@ -109,6 +131,18 @@ impl ElementStyle for LabelStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.icon_color.multiply_alpha(multiplier);
+        self.back_quad.multiply_alpha(multiplier);
+    }
 }
 
 /// How to align the text and the icon.
@@ -142,6 +176,82 @@ pub struct LabelPrimitives {
 struct TextInner {
     text: String,
     text_buffer: RcTextBuffer,
+    /// Whether the text buffer currently holds a truncated copy of `text`.
+    truncated: bool,
+    ellipsis_width: Option<f32>,
+}
+
+impl TextInner {
+    fn new(text: String, text_properties: TextProperties, font_system: &mut FontSystem) -> Self {
+        let text_buffer = RcTextBuffer::new(&text, text_properties, None, None, false, font_system);
+
+        Self {
+            text,
+            text_buffer,
+            truncated: false,
+            ellipsis_width: None,
+        }
+    }
+
+    /// Shape the text so that it fits within the given width, replacing the
+    /// end of the text with an ellipsis if it overflows.
+    fn truncate_to_width(&mut self, max_width: f32, font_system: &mut FontSystem) {
+        if self.truncated {
+            self.truncated = false;
+            self.text_buffer.set_text(&self.text, font_system);
+        }
+
+        self.text_buffer.set_bounds(None, None, font_system);
+
+        let glyph_edges: Vec<(usize, f32)> = self
+            .text_buffer
+            .raw_buffer()
+            .layout_runs()
+            .next()
+            .map(|run| run.glyphs.iter().map(|g| (g.end, g.x + g.w)).collect())
+            .unwrap_or_default();
+
+        let full_width = glyph_edges.last().map(|(_, x)| *x).unwrap_or(0.0);
+
+        if full_width > max_width {
+            let ellipsis_width = match self.ellipsis_width {
+                Some(w) => w,
+                None => {
+                    self.text_buffer.set_text(ELLIPSIS, font_system);
+                    let w = self.text_buffer.measure().width;
+                    self.ellipsis_width = Some(w);
+                    w
+                }
+            };
+
+            let cutoff = ellipsis_cutoff(&glyph_edges, max_width - ellipsis_width);
+
+            let mut truncated_text = String::from(self.text.get(..cutoff).unwrap_or("").trim_end());
+            truncated_text.push_str(ELLIPSIS);
+
+            self.text_buffer.set_text(&truncated_text, font_system);
+            self.truncated = true;
+        }
+
+        self.text_buffer
+            .set_bounds(Some(max_width), None, font_system);
+    }
+}
+
+const ELLIPSIS: &str = "\u{2026}";
+
+/// Returns the byte index at which the text should be cut so that the glyphs
+/// before it fit within `max_width`.
+///
+/// * `glyph_edges` - The byte index of the end of each glyph and the x
+/// position of its right edge, in order.
+fn ellipsis_cutoff(glyph_edges: &[(usize, f32)], max_width: f32) -> usize {
+    glyph_edges
+        .iter()
+        .take_while(|(_, right_edge)| *right_edge <= max_width)
+        .last()
+        .map(|(end, _)| *end)
+        .unwrap_or(0)
 }
 
 /// A reusable label with text and icon struct that can be used by other elements.
@@ -163,6 +273,8 @@ pub struct LabelInner {
     padded_size: Size,
     padded_size_needs_calculated: bool,
     text_icon_layout: TextIconLayout,
+    horizontal_align: Option<Align>,
+    truncate_with_ellipsis: bool,
     icon: Option<IconID>,
 }
 
@@ -179,20 +291,16 @@ impl LabelInner {
         font_system: &mut FontSystem,
     ) -> Self {
         let text_inner = text.map(|text| {
-            let text: String = text.into();
-
-            let mut text_properties = style.text_properties.clone();
-            text_properties.align = Some(match text_icon_layout {
-                TextIconLayout::LeftAlignTextThenIcon
-                | TextIconLayout::LeftAlignIconThenText
-                | TextIconLayout::LeftAlignTextRightAlignIcon => rootvg::text::Align::Left,
-                _ => rootvg::text::Align::Right,
-            });
-
-            let text_buffer =
-                RcTextBuffer::new(&text, text_properties, None, None, false, font_system);
-
-            TextInner { text, text_buffer }
+            TextInner::new(
+                text.into(),
+                text_properties(
+                    style.text_properties,
+                    text_icon_layout,
+                    style.horizontal_align,
+                    style.truncate_with_ellipsis,
+                ),
+                font_system,
+            )
         });
 
         Self {
@@ -212,6 +320,8 @@ impl LabelInner {
             padded_size: Size::default(),
             padded_size_needs_calculated: true,
             text_icon_layout,
+            horizontal_align: style.horizontal_align,
+            truncate_with_ellipsis: style.truncate_with_ellipsis,
         }
     }
 
@@ -299,6 +409,7 @@ impl LabelInner {
             if let Some(new_text) = text {
                 if inner.text.as_str() != new_text.as_ref() {
                     inner.text = new_text.into();
+                    inner.truncated = false;
                     self.text_size_needs_calculated = true;
                     self.padded_size_needs_calculated = true;
 
@@ -315,24 +426,18 @@ impl LabelInner {
                 true
             }
         } else if let Some(new_text) = text {
-            let new_text: String = new_text.into();
-
-            let mut text_properties = (get_text_props)();
-
-            text_properties.align = Some(match self.text_icon_layout {
-                TextIconLayout::LeftAlignTextThenIcon
-                | TextIconLayout::LeftAlignIconThenText
-                | TextIconLayout::LeftAlignTextRightAlignIcon => rootvg::text::Align::Left,
-                _ => rootvg::text::Align::Right,
-            });
-
-            let text_buffer =
-                RcTextBuffer::new(&new_text, text_properties, None, None, false, font_system);
-
-            self.text_inner = Some(TextInner {
-                text: new_text,
-                text_buffer,
-            });
+            self.text_inner = Some(TextInner::new(
+                new_text.into(),
+                text_properties(
+                    (get_text_props)(),
+                    self.text_icon_layout,
+                    self.horizontal_align,
+                    self.truncate_with_ellipsis,
+                ),
+                font_system,
+            ));
+            self.text_size_needs_calculated = true;
+            self.padded_size_needs_calculated = true;
 
             true
         } else {
@@ -377,18 +482,22 @@ impl LabelInner {
     }
 
     pub fn sync_new_style(&mut self, style: &LabelStyle, font_system: &mut FontSystem) {
+        self.horizontal_align = style.horizontal_align;
+        self.truncate_with_ellipsis = style.truncate_with_ellipsis;
+
         if let Some(inner) = &mut self.text_inner {
-            let mut text_properties = style.text_properties.clone();
-            text_properties.align = Some(match self.text_icon_layout {
-                TextIconLayout::LeftAlignTextThenIcon
-                | TextIconLayout::LeftAlignIconThenText
-                | TextIconLayout::LeftAlignTextRightAlignIcon => rootvg::text::Align::Left,
-                _ => rootvg::text::Align::Right,
-            });
+            let text_properties = text_properties(
+                style.text_properties,
+                self.text_icon_layout,
+                self.horizontal_align,
+                self.truncate_with_ellipsis,
+            );
 
             inner
                 .text_buffer
                 .set_text_and_props(&inner.text, text_properties, font_system);
+            inner.truncated = false;
+            inner.ellipsis_width = None;
 
             self.text_size_needs_calculated = true;
         }
@@ -429,11 +538,15 @@ impl LabelInner {
             self.icon_bounds_rect = layout_res.icon_bounds_rect;
 
             if let Some(inner) = &mut self.text_inner {
-                inner.text_buffer.set_bounds(
-                    Some(self.text_bounds_rect.width()),
-                    None,
-                    font_system,
-                );
+                if self.truncate_with_ellipsis {
+                    inner.truncate_to_width(self.text_bounds_rect.width(), font_system);
+                } else {
+                    inner.text_buffer.set_bounds(
+                        Some(self.text_bounds_rect.width()),
+                        None,
+                        font_system,
+                    );
+                }
             }
         }
 
@@ -655,19 +768,42 @@ impl<A: Clone + 'static> Element<A> for LabelElement {
         event: ElementEvent,
         cx: &mut ElementContext<'_, A>,
     ) -> EventCaptureStatus {
-        if let ElementEvent::CustomStateChanged = event {
-            cx.request_repaint();
+        match event {
+            ElementEvent::CustomStateChanged => {
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<LabelStyle>(cx.class());
+                RefCell::borrow_mut(&self.shared_state)
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
+            }
+            _ => {}
         }
 
         EventCaptureStatus::NotCaptured
     }
 
+    #[cfg(feature = "accessibility")]
+    fn accessibility_node(&self) -> Option<accesskit::Node> {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        let mut node = accesskit::Node::new(accesskit::Role::Label);
+        if let Some(text) = shared_state.inner.text() {
+            node.set_value(text);
+        }
+
+        Some(node)
+    }
+
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
         let label_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
-            cx.res.style_system.get(cx.class),
+            &cx.res
+                .style_system
+                .get_with_opacity::<LabelStyle>(cx.class, cx.opacity),
             &mut cx.res.font_system,
         );
 
@@ -880,6 +1016,32 @@ impl Label {
     }
 }
 
+/// The properties of the text buffer of a label.
+fn text_properties(
+    mut text_properties: TextProperties,
+    text_icon_layout: TextIconLayout,
+    horizontal_align: Option<Align>,
+    truncate_with_ellipsis: bool,
+) -> TextProperties {
+    text_properties.align = Some(match horizontal_align {
+        Some(Align::Start) => rootvg::text::Align::Left,
+        Some(Align::Center) => rootvg::text::Align::Center,
+        Some(Align::End) => rootvg::text::Align::Right,
+        None => match text_icon_layout {
+            TextIconLayout::LeftAlignTextThenIcon
+            | TextIconLayout::LeftAlignIconThenText
+            | TextIconLayout::LeftAlignTextRightAlignIcon => rootvg::text::Align::Left,
+            _ => rootvg::text::Align::Right,
+        },
+    });
+
+    if truncate_with_ellipsis {
+        text_properties.wrap = Wrap::None;
+    }
+
+    text_properties
+}
+
 struct LayoutResult {
     text_bounds_rect: Rect,
     icon_bounds_rect: Rect,
@@ -1067,3 +1229,129 @@ fn layout_icon_only(size: Size, padding: &Padding, bounds_size: Size) -> Rect {
         size,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ellipsis_cutoff_keeps_whole_glyphs_that_fit() {
+        // "abc" followed by a two byte character, each glyph 10 points wide.
+        let glyph_edges = [(1, 10.0), (2, 20.0), (3, 30.0), (5, 40.0)];
+
+        assert_eq!(ellipsis_cutoff(&glyph_edges, 100.0), 5);
+        assert_eq!(ellipsis_cutoff(&glyph_edges, 40.0), 5);
+        assert_eq!(ellipsis_cutoff(&glyph_edges, 29.9), 2);
+        assert_eq!(ellipsis_cutoff(&glyph_edges, 5.0), 0);
+        assert_eq!(ellipsis_cutoff(&glyph_edges, -3.0), 0);
+        assert_eq!(ellipsis_cutoff(&[], 10.0), 0);
+    }
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_label_text_alignment_and_truncation() {
+        let props = TextProperties::default();
+
+        let p = text_properties(props, TextIconLayout::RightAlignIconThenText, None, false);
+        assert_eq!(p.align, Some(rootvg::text::Align::Right));
+        assert_eq!(p.wrap, props.wrap);
+
+        // An explicit alignment overrides the text/icon layout.
+        let p = text_properties(
+            props,
+            TextIconLayout::RightAlignIconThenText,
+            Some(Align::Center),
+            true,
+        );
+        assert_eq!(p.align, Some(rootvg::text::Align::Center));
+        assert_eq!(p.wrap, Wrap::None);
+
+        let mut font_system = FontSystem::new();
+        let style = LabelStyle {
+            truncate_with_ellipsis: true,
+            ..Default::default()
+        };
+        let text = "The quick brown fox jumps over the lazy dog";
+
+        let mut inner = LabelInner::new(
+            Some(text),
+            None,
+            Vector::default(),
+            Vector::default(),
+            None,
+            IconScale::default(),
+            TextIconLayout::default(),
+            &style,
+            &mut font_system,
+        );
+
+        let full_size = inner.unclipped_text_size();
+        assert!(!full_size.is_empty());
+
+        let bounds = Rect::new(
+            Point::zero(),
+            Size::new(full_size.width * 0.5, full_size.height),
+        );
+        let _ = inner.render(bounds, &style, &mut font_system);
+
+        let text_inner = inner.text_inner.as_mut().unwrap();
+        assert!(text_inner.truncated);
+        assert!(text_inner.text_buffer.measure().width <= bounds.width());
+        // The original text is kept.
+        assert_eq!(inner.text(), Some(text));
+        assert_eq!(inner.desired_size(|| style.padding_info()), full_size);
+
+        // The full text is shown again once it fits.
+        let bounds = Rect::new(
+            Point::zero(),
+            Size::new(full_size.width + 10.0, full_size.height),
+        );
+        let _ = inner.render(bounds, &style, &mut font_system);
+        assert!(!inner.text_inner.as_ref().unwrap().truncated);
+    }
+
+    #[test]
+    fn test_label_is_faded_by_the_opacity_of_the_element() {
+        let mut font_system = FontSystem::new();
+        let mut style_system = StyleSystem::new(true);
+        let text_color = RGBA8::new(255, 255, 255, 200);
+        style_system.add(
+            CLASS_DEFAULT,
+            true,
+            LabelStyle {
+                text_color,
+                ..Default::default()
+            },
+        );
+
+        let mut inner = LabelInner::new(
+            Some("abc"),
+            None,
+            Vector::default(),
+            Vector::default(),
+            None,
+            IconScale::default(),
+            TextIconLayout::default(),
+            style_system.get(CLASS_DEFAULT),
+            &mut font_system,
+        );
+        let bounds = Rect::from_size(Size::new(100.0, 20.0));
+
+        let p = inner.render(
+            bounds,
+            &style_system.get_with_opacity::<LabelStyle>(CLASS_DEFAULT, 1.0),
+            &mut font_system,
+        );
+        assert_eq!(p.text.unwrap().color, text_color);
+
+        let p = inner.render(
+            bounds,
+            &style_system.get_with_opacity::<LabelStyle>(CLASS_DEFAULT, 0.5),
+            &mut font_system,
+        );
+        assert_eq!(
+            p.text.unwrap().color,
+            color::multiply_alpha(text_color, 0.5)
+        );
+    }
+}
@@ -0,0 +1,799 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::element_system::element::CustomStateNotifier;
+use crate::prelude::*;
+
+/// The style of a [`ListView`] element
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ListViewStyle {
+    /// The style of the quad behind the whole list.
+    ///
+    /// By default this is set to `QuadStyle::TRANSPARENT`.
+    pub back_quad: QuadStyle,
+    /// The style of the quad behind the row that the pointer is hovering
+    /// over.
+    ///
+    /// By default this is set to `QuadStyle::TRANSPARENT`.
+    pub row_quad_hover: QuadStyle,
+    /// The style of the quad behind selected rows.
+    pub row_quad_selected: QuadStyle,
+}
+
+impl Default for ListViewStyle {
+    fn default() -> Self {
+        Self {
+            back_quad: QuadStyle::TRANSPARENT,
+            row_quad_hover: QuadStyle::TRANSPARENT,
+            row_quad_selected: QuadStyle {
+                bg: Background::Solid(color::rgba(80, 130, 230, 90)),
+                border: BorderStyle::TRANSPARENT,
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            },
+        }
+    }
+}
+
+impl ElementStyle for ListViewStyle {
+    const ID: &'static str = "lstvw";
+
+    fn default_dark_style() -> Self {
+        Self::default()
+    }
+
+    fn default_light_style() -> Self {
+        Self::default()
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.back_quad.multiply_alpha(multiplier);
+        self.row_quad_hover.multiply_alpha(multiplier);
+        self.row_quad_selected.multiply_alpha(multiplier);
+    }
+}
+
+/// How rows in a [`ListView`] can be selected.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListSelectionMode {
+    /// Rows cannot be selected.
+    None,
+    /// At most one row can be selected.
+    #[default]
+    Single,
+    /// Any number of rows can be selected. Ctrl/Cmd+click toggles a row, and
+    /// Shift+click selects the range from the previously clicked row.
+    Multi,
+}
+
+/// A row element which a [`ListView`] can recycle.
+pub trait ListRow {
+    fn set_rect(&mut self, rect: Rect) -> bool;
+    fn set_hidden(&mut self, hidden: bool) -> bool;
+}
+
+macro_rules! impl_list_row {
+    ($($handle:ty),*) => {
+        $(
+            impl ListRow for $handle {
+                fn set_rect(&mut self, rect: Rect) -> bool {
+                    <$handle>::set_rect(self, rect)
+                }
+
+                fn set_hidden(&mut self, hidden: bool) -> bool {
+                    <$handle>::set_hidden(self, hidden)
+                }
+            }
+        )*
+    };
+}
+
+impl_list_row!(
+    Label,
+    Button,
+    ToggleButton,
+    Checkbox,
+    Switch,
+    QuadElement,
+    DynamicPrimitives
+);
+
+/// The range of rows which intersect the visible area of the list.
+fn visible_range(
+    scroll_y: f32,
+    view_height: f32,
+    row_height: f32,
+    item_count: usize,
+) -> Range<usize> {
+    if row_height <= 0.0 || view_height <= 0.0 || item_count == 0 {
+        return 0..0;
+    }
+
+    let start = ((scroll_y.max(0.0) / row_height).floor() as usize).min(item_count);
+    let end = (((scroll_y.max(0.0) + view_height) / row_height).ceil() as usize).min(item_count);
+
+    start..end
+}
+
+/// The selected rows of a [`ListView`].
+#[derive(Default, Debug, Clone, PartialEq)]
+struct ListSelection {
+    selected: BTreeSet<usize>,
+    /// The row which Shift+click selects a range from.
+    anchor: Option<usize>,
+}
+
+impl ListSelection {
+    /// Update the selection in response to the user clicking the given
+    /// row.
+    ///
+    /// Returns `true` if the selection has changed.
+    fn click(&mut self, index: usize, modifiers: Modifiers, mode: ListSelectionMode) -> bool {
+        let prev = self.selected.clone();
+
+        match mode {
+            ListSelectionMode::None => return false,
+            ListSelectionMode::Single => {
+                self.selected.clear();
+                self.selected.insert(index);
+            }
+            ListSelectionMode::Multi => {
+                if modifiers.contains(Modifiers::SHIFT) {
+                    let anchor = self.anchor.unwrap_or(index);
+                    if !modifiers.intersects(Modifiers::CONTROL | Modifiers::META) {
+                        self.selected.clear();
+                    }
+                    self.selected.extend(anchor.min(index)..=anchor.max(index));
+
+                    return self.selected != prev;
+                } else if modifiers.intersects(Modifiers::CONTROL | Modifiers::META) {
+                    if !self.selected.remove(&index) {
+                        self.selected.insert(index);
+                    }
+                } else {
+                    self.selected.clear();
+                    self.selected.insert(index);
+                }
+            }
+        }
+
+        self.anchor = Some(index);
+        self.selected != prev
+    }
+
+    /// Remove the rows at or past `item_count`.
+    fn truncate(&mut self, item_count: usize) -> bool {
+        let removed = self.selected.split_off(&item_count);
+        if self.anchor.is_some_and(|a| a >= item_count) {
+            self.anchor = None;
+        }
+
+        !removed.is_empty()
+    }
+}
+
+struct SharedState {
+    row_height: f32,
+    item_count: usize,
+    selection: ListSelection,
+    selection_mode: ListSelectionMode,
+    disabled: bool,
+    hovered_row: Option<usize>,
+}
+
+impl SharedState {
+    /// The index of the row under the given point, where `rect` is the
+    /// rectangle of the list.
+    fn row_at(&self, pos: Point, rect: Rect) -> Option<usize> {
+        if self.row_height <= 0.0 {
+            return None;
+        }
+
+        let y = pos.y - rect.min_y();
+        if y < 0.0 {
+            return None;
+        }
+
+        let index = (y / self.row_height) as usize;
+        (index < self.item_count).then_some(index)
+    }
+}
+
+struct RowSlot<R: ListRow> {
+    row: R,
+    index: Option<usize>,
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_disabled]
+pub struct ListViewBuilder<A: Clone + 'static> {
+    pub on_row_clicked: Option<Box<dyn FnMut(usize) -> A>>,
+    pub on_selection_changed: Option<Box<dyn FnMut(Vec<usize>) -> A>>,
+    pub row_height: f32,
+    pub item_count: usize,
+    pub selection_mode: ListSelectionMode,
+}
+
+impl<A: Clone + 'static> Default for ListViewBuilder<A> {
+    fn default() -> Self {
+        Self {
+            on_row_clicked: None,
+            on_selection_changed: None,
+            row_height: 24.0,
+            item_count: 0,
+            selection_mode: ListSelectionMode::default(),
+            class: None,
+            z_index: None,
+            scissor_rect: None,
+            disabled: false,
+        }
+    }
+}
+
+impl<A: Clone + 'static> ListViewBuilder<A> {
+    /// The action to send when the user clicks on a row. The index of the
+    /// row is passed to the closure.
+    pub fn on_row_clicked<F: FnMut(usize) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_row_clicked = Some(Box::new(f));
+        self
+    }
+
+    /// The action to send when the user changes the selection. The indexes
+    /// of the selected rows (in ascending order) are passed to the closure.
+    pub fn on_selection_changed<F: FnMut(Vec<usize>) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_selection_changed = Some(Box::new(f));
+        self
+    }
+
+    pub const fn row_height(mut self, height: f32) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    pub const fn item_count(mut self, count: usize) -> Self {
+        self.item_count = count;
+        self
+    }
+
+    pub const fn selection_mode(mut self, mode: ListSelectionMode) -> Self {
+        self.selection_mode = mode;
+        self
+    }
+
+    /// Build the list.
+    ///
+    /// * `create_row` - Called to create a new row element. Rows are
+    /// created lazily and then recycled, so this is only called as many
+    /// times as there are rows visible at once.
+    /// * `populate_row` - Called to fill in a row element with the contents
+    /// of the item at the given index.
+    ///
+    /// The rows are placed into the scissoring rectangle of the list (set
+    /// with [`ListViewBuilder::scissor_rect`]). The list sets its rectangle
+    /// to the bounds of the list, and rows are only built for the part of it
+    /// that is visible. The list is scrolled by changing the scroll offset of
+    /// this scissoring rectangle, i.e. with a [`ScrollArea`] that controls it.
+    ///
+    /// # Panics
+    /// This will panic if the scissoring rectangle of the list is
+    /// `ScissorRectID::DEFAULT`.
+    pub fn build<R, C, P>(
+        self,
+        create_row: C,
+        populate_row: P,
+        window_cx: &mut WindowContext<'_, A>,
+    ) -> ListView<A, R>
+    where
+        R: ListRow + 'static,
+        C: FnMut(&mut WindowContext<'_, A>) -> R + 'static,
+        P: FnMut(&mut R, usize, &mut ResourceCtx) + 'static,
+    {
+        let ListViewBuilder {
+            on_row_clicked,
+            on_selection_changed,
+            row_height,
+            item_count,
+            selection_mode,
+            class,
+            z_index,
+            scissor_rect,
+            disabled,
+        } = self;
+
+        let rows_scissor_rect = scissor_rect.unwrap_or_else(|| window_cx.scissor_rect());
+        if rows_scissor_rect == ScissorRectID::DEFAULT {
+            panic!("a ListView needs its own scissoring rectangle");
+        }
+        let z_index = z_index.unwrap_or_else(|| window_cx.z_index());
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            row_height,
+            item_count,
+            selection: ListSelection::default(),
+            selection_mode,
+            disabled,
+            hovered_row: None,
+        }));
+
+        let el = ElementBuilder::new(ListViewElement {
+            shared_state: Rc::clone(&shared_state),
+        })
+        .builder_values(Some(z_index), Some(rows_scissor_rect), class, window_cx)
+        .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+        .build(window_cx);
+
+        // The rows are placed above the element that draws the selection, and
+        // the element that handles clicks is placed above the rows so that it
+        // sees a click before the contents of the row can capture it.
+        let input_el = ElementBuilder::new(ListViewInputElement {
+            shared_state: Rc::clone(&shared_state),
+            on_row_clicked,
+            on_selection_changed,
+            painter: el.custom_state_notifier(),
+        })
+        .builder_values(
+            Some(z_index.saturating_add(2)),
+            Some(rows_scissor_rect),
+            class,
+            window_cx,
+        )
+        .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+        .build(window_cx);
+
+        ListView {
+            el,
+            input_el,
+            shared_state,
+            rows: Vec::new(),
+            create_row: Box::new(create_row),
+            populate_row: Box::new(populate_row),
+            rows_scissor_rect,
+            bounds: Rect::default(),
+            z_index,
+        }
+    }
+}
+
+/// The element behind the rows which draws the selection.
+struct ListViewElement {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl<A: Clone + 'static> Element<A> for ListViewElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        match event {
+            ElementEvent::CustomStateChanged => {
+                if shared_state.disabled {
+                    shared_state.hovered_row = None;
+                }
+
+                cx.request_repaint();
+            }
+            // Clicks on a row which its contents didn't capture don't fall
+            // through to the elements behind the list.
+            ElementEvent::Pointer(PointerEvent::Moved { .. }) => {
+                if !shared_state.disabled {
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position, button, ..
+            }) => {
+                if !shared_state.disabled
+                    && button == PointerButton::Primary
+                    && shared_state.row_at(position, cx.rect()).is_some()
+                {
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let shared_state = RefCell::borrow(&self.shared_state);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<ListViewStyle>(cx.class, cx.opacity);
+
+        if !style.back_quad.is_transparent() {
+            primitives.add(
+                style
+                    .back_quad
+                    .create_primitive(Rect::from_size(cx.bounds_size)),
+            );
+        }
+
+        // Only draw the rows which are visible, no matter how many items
+        // there are.
+        let range = visible_range(
+            cx.visible_bounds.min_y() - cx.bounds_origin.y,
+            cx.visible_bounds.height(),
+            shared_state.row_height,
+            shared_state.item_count,
+        );
+
+        let row_rect = |index: usize| {
+            Rect::new(
+                Point::new(0.0, index as f32 * shared_state.row_height),
+                Size::new(cx.bounds_size.width, shared_state.row_height),
+            )
+        };
+
+        if !style.row_quad_selected.is_transparent() {
+            for index in shared_state.selection.selected.range(range.clone()) {
+                primitives.add(style.row_quad_selected.create_primitive(row_rect(*index)));
+            }
+        }
+
+        if let Some(index) = shared_state.hovered_row.filter(|i| range.contains(i)) {
+            if !style.row_quad_hover.is_transparent() {
+                primitives.set_z_index(1);
+                primitives.add(style.row_quad_hover.create_primitive(row_rect(index)));
+            }
+        }
+    }
+}
+
+/// The element in front of the rows which handles hovering and clicking on
+/// the rows.
+///
+/// It never captures pointer events, so the contents of the rows still
+/// receive them afterwards.
+struct ListViewInputElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    on_row_clicked: Option<Box<dyn FnMut(usize) -> A>>,
+    on_selection_changed: Option<Box<dyn FnMut(Vec<usize>) -> A>>,
+    painter: CustomStateNotifier,
+}
+
+impl<A: Clone + 'static> Element<A> for ListViewInputElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        match event {
+            ElementEvent::Pointer(PointerEvent::Moved { position, .. }) => {
+                if shared_state.disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let hovered_row = shared_state.row_at(position, cx.rect());
+                if shared_state.hovered_row != hovered_row {
+                    shared_state.hovered_row = hovered_row;
+                    self.painter.notify();
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::PointerLeft) => {
+                if shared_state.hovered_row.take().is_some() {
+                    self.painter.notify();
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position,
+                button,
+                modifiers,
+                ..
+            }) => {
+                if shared_state.disabled || button != PointerButton::Primary {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let Some(index) = shared_state.row_at(position, cx.rect()) else {
+                    return EventCaptureStatus::NotCaptured;
+                };
+
+                let mode = shared_state.selection_mode;
+                if shared_state.selection.click(index, modifiers, mode) {
+                    self.painter.notify();
+
+                    if let Some(action) = &mut self.on_selection_changed {
+                        let selected = shared_state.selection.selected.iter().copied().collect();
+                        cx.send_action((action)(selected));
+                    }
+                }
+
+                if let Some(action) = &mut self.on_row_clicked {
+                    cx.send_action((action)(index));
+                }
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+}
+
+/// A virtualized list of rows.
+///
+/// Only the rows which are visible are built and laid out, and row elements
+/// are recycled as the list is scrolled, so the number of elements stays
+/// bounded no matter how many items there are.
+///
+/// The rows are placed into their own scissoring rectangle (see
+/// [`ListViewBuilder::build`]). Call [`ListView::update`] whenever its scroll
+/// offset changes.
+pub struct ListView<A: Clone + 'static, R: ListRow> {
+    el: ElementHandle,
+    input_el: ElementHandle,
+    shared_state: Rc<RefCell<SharedState>>,
+    rows: Vec<RowSlot<R>>,
+    create_row: Box<dyn FnMut(&mut WindowContext<'_, A>) -> R>,
+    populate_row: Box<dyn FnMut(&mut R, usize, &mut ResourceCtx)>,
+    rows_scissor_rect: ScissorRectID,
+    bounds: Rect,
+    z_index: ZIndex,
+}
+
+impl<A: Clone + 'static, R: ListRow + 'static> ListView<A, R> {
+    pub fn builder() -> ListViewBuilder<A> {
+        ListViewBuilder::default()
+    }
+
+    /// Lay out the list into the given rectangle, and build the rows which
+    /// are now visible.
+    pub fn layout(&mut self, bounds: Rect, window_cx: &mut WindowContext<'_, A>) {
+        self.bounds = bounds;
+        window_cx.update_scissor_rect(self.rows_scissor_rect, Some(bounds), None);
+
+        self.update(window_cx);
+    }
+
+    /// Build the rows which are visible at the current scroll offset of the
+    /// list's scissoring rectangle, recycling the rows which are not.
+    ///
+    /// Rows which stay visible are not populated again.
+    pub fn update(&mut self, window_cx: &mut WindowContext<'_, A>) {
+        let (row_height, item_count) = {
+            let shared_state = RefCell::borrow(&self.shared_state);
+            (shared_state.row_height, shared_state.item_count)
+        };
+
+        let rect = Rect::new(
+            Point::zero(),
+            Size::new(self.bounds.width(), row_height * item_count as f32),
+        );
+        self.el.set_rect(rect);
+        self.input_el.set_rect(rect);
+
+        let scroll_offset = window_cx.get_scissor_rect_scroll_offset(self.rows_scissor_rect);
+        let range = visible_range(
+            scroll_offset.y,
+            self.bounds.height(),
+            row_height,
+            item_count,
+        );
+
+        for slot in self.rows.iter_mut() {
+            if slot.index.is_some_and(|i| !range.contains(&i)) {
+                slot.index = None;
+            }
+        }
+
+        // Rows are placed above the element that draws the selection.
+        let z_index = self.z_index.saturating_add(1);
+        let scissor_rect = self.rows_scissor_rect;
+        while self.rows.len() < range.len() {
+            let row = window_cx
+                .with_z_index_and_scissor_rect(z_index, scissor_rect, |cx| (self.create_row)(cx));
+            self.rows.push(RowSlot { row, index: None });
+        }
+
+        let mut shown = vec![false; range.len()];
+        for slot in self.rows.iter() {
+            if let Some(i) = slot.index {
+                shown[i - range.start] = true;
+            }
+        }
+
+        let mut free_slots = self.rows.iter_mut().filter(|slot| slot.index.is_none());
+        for (offset, _) in shown.iter().enumerate().filter(|(_, shown)| !**shown) {
+            let index = range.start + offset;
+            let slot = free_slots.next().unwrap();
+
+            (self.populate_row)(&mut slot.row, index, window_cx.res);
+            slot.index = Some(index);
+        }
+
+        for slot in self.rows.iter_mut() {
+            if let Some(index) = slot.index {
+                slot.row.set_rect(Rect::new(
+                    Point::new(0.0, index as f32 * row_height),
+                    Size::new(self.bounds.width(), row_height),
+                ));
+                slot.row.set_hidden(false);
+            } else {
+                slot.row.set_hidden(true);
+            }
+        }
+    }
+
+    /// Set the number of items in the list.
+    ///
+    /// Rows past the new count are deselected. Call [`ListView::update`]
+    /// afterwards to rebuild the visible rows.
+    pub fn set_item_count(&mut self, count: usize) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.item_count != count {
+            shared_state.item_count = count;
+            shared_state.selection.truncate(count);
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn item_count(&self) -> usize {
+        RefCell::borrow(&self.shared_state).item_count
+    }
+
+    /// Populate all of the visible rows again, i.e. when the contents of
+    /// the items have changed.
+    pub fn refresh(&mut self, res: &mut ResourceCtx) {
+        for slot in self.rows.iter_mut() {
+            if let Some(index) = slot.index {
+                (self.populate_row)(&mut slot.row, index, res);
+            }
+        }
+    }
+
+    /// The total height of all of the rows. This can be used as the content
+    /// size of a [`ScrollArea`].
+    pub fn content_height(&self) -> f32 {
+        let shared_state = RefCell::borrow(&self.shared_state);
+        shared_state.row_height * shared_state.item_count as f32
+    }
+
+    /// The number of row elements that have been built.
+    pub fn num_row_elements(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The indexes of the selected rows in ascending order.
+    pub fn selection(&self) -> Vec<usize> {
+        RefCell::borrow(&self.shared_state)
+            .selection
+            .selected
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        RefCell::borrow(&self.shared_state)
+            .selection
+            .selected
+            .contains(&index)
+    }
+
+    /// Set the selected rows.
+    ///
+    /// Indexes past the number of items are ignored, and only the first
+    /// index is used if the selection mode is `ListSelectionMode::Single`.
+    pub fn set_selection(&mut self, selection: impl IntoIterator<Item = usize>) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        let item_count = shared_state.item_count;
+        let mut selected: BTreeSet<usize> =
+            selection.into_iter().filter(|i| *i < item_count).collect();
+
+        match shared_state.selection_mode {
+            ListSelectionMode::None => selected.clear(),
+            ListSelectionMode::Single => {
+                if let Some(first) = selected.first().copied() {
+                    selected = BTreeSet::from([first]);
+                }
+            }
+            ListSelectionMode::Multi => {}
+        }
+
+        if shared_state.selection.selected != selected {
+            shared_state.selection.anchor = selected.first().copied();
+            shared_state.selection.selected = selected;
+            self.el.notify_custom_state_change();
+        }
+    }
+
+    /// Set the disabled state of this element.
+    ///
+    /// Returns `true` if the disabled state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_disabled(&mut self, disabled: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.disabled != disabled {
+            shared_state.disabled = disabled;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.el.set_hidden(hidden);
+        self.input_el.set_hidden(hidden);
+
+        for slot in self.rows.iter_mut() {
+            slot.row.set_hidden(hidden || slot.index.is_none());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_range_is_bounded_by_the_view() {
+        let item_count = 1_000_000;
+
+        for scroll_y in [0.0, 10.0, 12_345.6, 23_999_990.0] {
+            let range = visible_range(scroll_y, 200.0, 24.0, item_count);
+            assert!(range.len() <= (200.0f32 / 24.0).ceil() as usize + 1);
+            assert!(range.end <= item_count);
+        }
+
+        assert_eq!(visible_range(0.0, 100.0, 25.0, 1_000), 0..4);
+        assert_eq!(visible_range(10.0, 100.0, 25.0, 1_000), 0..5);
+        assert_eq!(visible_range(990.0, 100.0, 25.0, 42), 39..42);
+        assert_eq!(visible_range(0.0, 100.0, 25.0, 0), 0..0);
+    }
+
+    #[test]
+    fn test_single_and_multi_selection() {
+        let mut selection = ListSelection::default();
+        let selected = |s: &ListSelection| s.selected.iter().copied().collect::<Vec<_>>();
+
+        assert!(selection.click(3, Modifiers::empty(), ListSelectionMode::Single));
+        assert!(selection.click(5, Modifiers::SHIFT, ListSelectionMode::Single));
+        assert_eq!(selected(&selection), vec![5]);
+        assert!(!selection.click(5, Modifiers::empty(), ListSelectionMode::Single));
+        assert!(!selection.click(1, Modifiers::empty(), ListSelectionMode::None));
+
+        let mut selection = ListSelection::default();
+        selection.click(2, Modifiers::empty(), ListSelectionMode::Multi);
+        selection.click(5, Modifiers::SHIFT, ListSelectionMode::Multi);
+        assert_eq!(selected(&selection), vec![2, 3, 4, 5]);
+
+        selection.click(4, Modifiers::CONTROL, ListSelectionMode::Multi);
+        assert_eq!(selected(&selection), vec![2, 3, 5]);
+        selection.click(9, Modifiers::META, ListSelectionMode::Multi);
+        assert_eq!(selected(&selection), vec![2, 3, 5, 9]);
+
+        // Shift+click ranges from the last clicked row.
+        selection.click(7, Modifiers::SHIFT, ListSelectionMode::Multi);
+        assert_eq!(selected(&selection), vec![7, 8, 9]);
+
+        assert!(selection.truncate(8));
+        assert_eq!(selected(&selection), vec![7]);
+    }
+}
@@ -0,0 +1,114 @@
+use rootvg::PrimitiveGroup;
+
+use crate::prelude::*;
+
+use super::spinner::SpinnerStyle;
+
+/// The style of the overlay shown over a region while it is loading.
+///
+/// See [`WindowContext::set_region_loading`](crate::WindowContext::set_region_loading).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct LoadingOverlayStyle {
+    /// The quad which dims the contents of the region.
+    pub back_quad: QuadStyle,
+    /// The spinner drawn in the center of the region.
+    pub spinner: SpinnerStyle,
+}
+
+impl Default for LoadingOverlayStyle {
+    fn default() -> Self {
+        Self {
+            back_quad: QuadStyle {
+                bg: Background::Solid(RGBA8::new(0, 0, 0, 120)),
+                border: BorderStyle::TRANSPARENT,
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            },
+            spinner: SpinnerStyle::default(),
+        }
+    }
+}
+
+impl ElementStyle for LoadingOverlayStyle {
+    const ID: &'static str = "ldov";
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.back_quad.multiply_alpha(multiplier);
+        self.spinner.multiply_alpha(multiplier);
+    }
+}
+
+/// The builder of the overlay element for a loading region.
+///
+/// The overlay sits above every other element in the scissoring rectangle,
+/// and it captures all pointer and touch events inside of its bounds so that
+/// the elements below it cannot be interacted with.
+pub(crate) fn loading_overlay_builder<A: Clone + 'static>(
+    rect: Rect,
+    scissor_rect: ScissorRectID,
+) -> ElementBuilder<A> {
+    ElementBuilder::new(LoadingOverlayElement { phase: 0.0 })
+        .rect(rect)
+        .z_index(ZIndex::MAX)
+        .scissor_rect(scissor_rect)
+        .flags(
+            ElementFlags::PAINTS
+                | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+                | ElementFlags::LISTENS_TO_TOUCH
+                | ElementFlags::LISTENS_TO_INIT,
+        )
+}
+
+struct LoadingOverlayElement {
+    phase: f32,
+}
+
+impl<A: Clone + 'static> Element<A> for LoadingOverlayElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        match event {
+            ElementEvent::Init => {
+                cx.set_animating(true);
+            }
+            ElementEvent::Animation { delta_seconds } => {
+                let class = cx.class();
+                let style = cx.res.style_system.get::<LoadingOverlayStyle>(class);
+                self.phase = style.spinner.advance_phase(self.phase, delta_seconds);
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                cx.request_repaint();
+            }
+            ElementEvent::Pointer(_) | ElementEvent::Touch(_) => {
+                return EventCaptureStatus::Captured;
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<LoadingOverlayStyle>(cx.class, cx.opacity);
+        let bounds = Rect::from_size(cx.bounds_size);
+
+        if !style.back_quad.is_transparent() {
+            primitives.add(style.back_quad.create_primitive(bounds));
+        }
+
+        primitives.set_z_index(1);
+        style
+            .spinner
+            .add_primitives(bounds.center(), self.phase, primitives);
+    }
+}
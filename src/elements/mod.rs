@@ -1,19 +1,39 @@
 pub mod button;
+pub mod checkbox;
 pub mod click_area;
+pub mod context_menu;
+pub mod drop_down;
 pub mod drop_down_menu;
+pub mod dynamic_primitives;
+pub mod keyboard_nav;
 pub mod label;
+pub mod list_view;
+pub mod loading_overlay;
+pub mod panel;
 pub mod paragraph;
 pub mod quad;
 pub mod radio_button;
+pub mod reorderable;
 pub mod resize_handle;
+pub mod rich_text;
 pub mod scroll_area;
 pub mod separator;
+mod sizable;
+pub mod spinner;
 pub mod switch;
 pub mod tab;
+pub mod tab_bar;
 pub mod text_input;
 pub mod toggle_button;
 pub mod tooltip;
+pub mod value_changed;
 pub mod virtual_slider;
 
 #[cfg(feature = "svg-icons")]
 pub mod icon;
+#[cfg(feature = "image")]
+pub mod image;
+#[cfg(feature = "image")]
+pub mod nine_slice;
+#[cfg(feature = "tessellation")]
+pub mod pie_chart;
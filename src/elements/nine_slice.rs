@@ -0,0 +1,258 @@
+use rootvg::image::{ImagePrimitive, RcTexture};
+
+use crate::derive::*;
+use crate::elements::image::ImageLayout;
+use crate::layout::Padding;
+use crate::prelude::*;
+
+/// The style of a [`NineSlice`] element.
+///
+/// The source bitmap is split into 9 slices by the insets. The corners are
+/// drawn unscaled, the edges are stretched along one axis, and the center is
+/// stretched along both axes to fill the bounds.
+#[derive(Default, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct NineSliceStyle {
+    /// The source bitmap. If this is `None`, then nothing is drawn.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub texture: Option<RcTexture>,
+
+    /// The distances from the edges of the source bitmap to the edges of the
+    /// center slice, in physical pixels of the source bitmap.
+    pub insets: Padding,
+}
+
+impl NineSliceStyle {
+    /// Create the image primitives that fill the given bounds.
+    pub fn create_primitives(&self, bounds: Rect, scale_factor: f32) -> Vec<ImagePrimitive> {
+        let Some(texture) = &self.texture else {
+            return Vec::new();
+        };
+
+        let texture_size = texture.size();
+        let image_size = Size::new(texture_size.width as f32, texture_size.height as f32);
+
+        nine_slice_layout(bounds.size, image_size, self.insets, scale_factor)
+            .into_iter()
+            .map(|layout| {
+                ImagePrimitive::new_with_uv(
+                    texture.clone(),
+                    layout.dest.origin + bounds.origin.to_vector(),
+                    layout.dest.size,
+                    layout.uv.origin,
+                    layout.uv.size,
+                )
+            })
+            .collect()
+    }
+}
+
+impl ElementStyle for NineSliceStyle {
+    const ID: &'static str = "9sl";
+}
+
+/// Split an image into 9 slices which fill bounds of the given size.
+///
+/// * `image_size` - The size of the source bitmap in physical pixels.
+/// * `insets` - The insets of the center slice in physical pixels.
+///
+/// If the bounds are too small to fit the corners unscaled, then the corners
+/// are shrunk proportionally. Empty slices are skipped.
+pub fn nine_slice_layout(
+    bounds_size: Size,
+    image_size: Size,
+    insets: Padding,
+    scale_factor: f32,
+) -> Vec<ImageLayout> {
+    if bounds_size.is_empty() || image_size.is_empty() {
+        return Vec::new();
+    }
+
+    let src_x = slice_lines(image_size.width, insets.left, insets.right, 1.0);
+    let src_y = slice_lines(image_size.height, insets.top, insets.bottom, 1.0);
+    let dst_x = slice_lines(bounds_size.width, insets.left, insets.right, scale_factor);
+    let dst_y = slice_lines(bounds_size.height, insets.top, insets.bottom, scale_factor);
+
+    let mut slices = Vec::with_capacity(9);
+
+    for row in 0..3 {
+        for col in 0..3 {
+            let dest = Rect::new(
+                Point::new(dst_x[col], dst_y[row]),
+                Size::new(dst_x[col + 1] - dst_x[col], dst_y[row + 1] - dst_y[row]),
+            );
+            if dest.is_empty() {
+                continue;
+            }
+
+            let uv = Rect::new(
+                Point::new(
+                    src_x[col] / image_size.width,
+                    src_y[row] / image_size.height,
+                ),
+                Size::new(
+                    (src_x[col + 1] - src_x[col]) / image_size.width,
+                    (src_y[row + 1] - src_y[row]) / image_size.height,
+                ),
+            );
+            if uv.is_empty() {
+                continue;
+            }
+
+            slices.push(ImageLayout { dest, uv });
+        }
+    }
+
+    slices
+}
+
+/// The four lines which split a span into a start slice, a center slice,
+/// and an end slice.
+fn slice_lines(span: f32, start_inset: f32, end_inset: f32, scale_factor: f32) -> [f32; 4] {
+    let mut start = start_inset.max(0.0) / scale_factor;
+    let mut end = end_inset.max(0.0) / scale_factor;
+
+    if start + end > span {
+        let shrink = span / (start + end);
+        start *= shrink;
+        end *= shrink;
+    }
+
+    [0.0, start, span - end, span]
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[derive(Default)]
+pub struct NineSliceBuilder {}
+
+impl NineSliceBuilder {
+    pub fn build<A: Clone + 'static>(self, window_cx: &mut WindowContext<'_, A>) -> NineSlice {
+        let NineSliceBuilder {
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let el = ElementBuilder::new(NineSliceElement {})
+            .builder_values(z_index, scissor_rect, class, window_cx)
+            .rect(rect)
+            .hidden(manually_hidden)
+            .flags(ElementFlags::PAINTS)
+            .build(window_cx);
+
+        NineSlice { el }
+    }
+}
+
+struct NineSliceElement {}
+
+impl<A: Clone + 'static> Element<A> for NineSliceElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        if let ElementEvent::StyleChanged = event {
+            cx.request_repaint();
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let style = cx.res.style_system.get::<NineSliceStyle>(cx.class);
+
+        for primitive in style
+            .create_primitives(Rect::from_size(cx.bounds_size), cx.scale.0)
+            .into_iter()
+        {
+            primitives.add_image(primitive);
+        }
+    }
+}
+
+/// An element which fills its bounds with a stretchable bitmap, useful as a
+/// skinned background for buttons and panels.
+///
+/// Note, rootvg image primitives have no tint color, so the bitmap is only
+/// hidden by an opacity of `0.0` and is otherwise drawn fully opaque.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_layout_aligned]
+pub struct NineSlice {}
+
+impl NineSlice {
+    pub fn builder() -> NineSliceBuilder {
+        NineSliceBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::padding_all_same;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn test_nine_slice_corners_are_unscaled() {
+        let slices = nine_slice_layout(
+            Size::new(100.0, 50.0),
+            Size::new(40.0, 40.0),
+            padding_all_same(10.0),
+            1.0,
+        );
+
+        assert_eq!(slices.len(), 9);
+
+        // Top-left corner.
+        assert_eq!(slices[0].dest, rect(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(slices[0].uv, rect(0.0, 0.0, 0.25, 0.25));
+        // Top edge is stretched horizontally.
+        assert_eq!(slices[1].dest, rect(10.0, 0.0, 80.0, 10.0));
+        assert_eq!(slices[1].uv, rect(0.25, 0.0, 0.5, 0.25));
+        // Center is stretched in both directions.
+        assert_eq!(slices[4].dest, rect(10.0, 10.0, 80.0, 30.0));
+        assert_eq!(slices[4].uv, rect(0.25, 0.25, 0.5, 0.5));
+        // Bottom-right corner.
+        assert_eq!(slices[8].dest, rect(90.0, 40.0, 10.0, 10.0));
+        assert_eq!(slices[8].uv, rect(0.75, 0.75, 0.25, 0.25));
+
+        // The insets are in physical pixels.
+        let slices = nine_slice_layout(
+            Size::new(100.0, 50.0),
+            Size::new(40.0, 40.0),
+            padding_all_same(10.0),
+            2.0,
+        );
+        assert_eq!(slices[0].dest, rect(0.0, 0.0, 5.0, 5.0));
+    }
+
+    #[test]
+    fn test_nine_slice_shrinks_corners_to_fit() {
+        let slices = nine_slice_layout(
+            Size::new(10.0, 40.0),
+            Size::new(40.0, 40.0),
+            padding_all_same(10.0),
+            1.0,
+        );
+
+        // The center column is empty, so only the left and right columns
+        // are drawn.
+        assert_eq!(slices.len(), 6);
+        assert_eq!(slices[0].dest, rect(0.0, 0.0, 5.0, 10.0));
+        assert_eq!(slices[1].dest, rect(5.0, 0.0, 5.0, 10.0));
+    }
+}
@@ -0,0 +1,660 @@
+use derive_where::derive_where;
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::prelude::*;
+
+use super::label::LabelInner;
+
+/// The style of a [`Panel`] element
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct PanelStyle {
+    /// The properties of the header text.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub text_properties: TextProperties,
+
+    /// The color of the header text.
+    ///
+    /// By default this is set to `color::WHITE`.
+    pub text_color: RGBA8,
+    pub text_color_hover: Option<RGBA8>,
+    pub text_color_disabled: DisabledColor,
+
+    /// The padding around the header text.
+    ///
+    /// By default this is set to `Padding::new(0.0, 8.0, 0.0, 8.0)`.
+    pub text_padding: Padding,
+
+    /// The height of the clickable header row in points.
+    ///
+    /// By default this is set to `24.0`.
+    pub header_height: f32,
+
+    pub header_bg: Background,
+    pub header_bg_hover: Option<Background>,
+    pub header_bg_disabled: DisabledBackground,
+    pub header_border: BorderStyle,
+
+    /// The style of the quad behind the content area.
+    ///
+    /// By default this is set to `QuadStyle::TRANSPARENT`.
+    pub body_quad: QuadStyle,
+
+    /// How the height of the panel animates when it is expanded or
+    /// collapsed.
+    ///
+    /// By default this is set to `AnimationMode::EaseOut { duration_seconds: 0.15 }`.
+    pub collapse_animation: AnimationMode,
+
+    /// The cursor icon to show when the user hovers over the header.
+    ///
+    /// If this is `None`, then the cursor icon will not be changed.
+    ///
+    /// By default this is set to `None`.
+    pub cursor_icon: Option<CursorIcon>,
+
+    /// Additional flags for the quad primitives.
+    ///
+    /// By default this is set to `QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL`.
+    pub quad_flags: QuadFlags,
+}
+
+impl Default for PanelStyle {
+    fn default() -> Self {
+        Self {
+            text_properties: Default::default(),
+            text_color: color::WHITE,
+            text_color_hover: None,
+            text_color_disabled: Default::default(),
+            text_padding: Padding::new(0.0, 8.0, 0.0, 8.0),
+            header_height: 24.0,
+            header_bg: Background::TRANSPARENT,
+            header_bg_hover: None,
+            header_bg_disabled: Default::default(),
+            header_border: BorderStyle::TRANSPARENT,
+            body_quad: QuadStyle::TRANSPARENT,
+            collapse_animation: AnimationMode::default(),
+            cursor_icon: None,
+            quad_flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+        }
+    }
+}
+
+impl ElementStyle for PanelStyle {
+    const ID: &'static str = "panel";
+
+    fn default_dark_style() -> Self {
+        Self::default()
+    }
+
+    fn default_light_style() -> Self {
+        Self {
+            text_color: color::BLACK,
+            ..Default::default()
+        }
+    }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.text_color_hover.multiply_alpha(multiplier);
+        self.text_color_disabled.multiply_alpha(multiplier);
+        self.header_bg.multiply_alpha(multiplier);
+        self.header_bg_hover.multiply_alpha(multiplier);
+        self.header_bg_disabled.multiply_alpha(multiplier);
+        self.header_border.multiply_alpha(multiplier);
+        self.body_quad.multiply_alpha(multiplier);
+    }
+}
+
+impl PanelStyle {
+    pub fn label_style(&self, hovered: bool, disabled: bool) -> LabelStyle {
+        let mut text_color = if hovered {
+            self.text_color_hover.unwrap_or(self.text_color)
+        } else {
+            self.text_color
+        };
+        if disabled {
+            text_color = self.text_color_disabled.get(text_color);
+        }
+
+        LabelStyle {
+            text_properties: self.text_properties,
+            text_color,
+            text_padding: self.text_padding,
+            ..Default::default()
+        }
+    }
+
+    pub fn header_quad(&self, hovered: bool, disabled: bool) -> QuadStyle {
+        let mut bg = if hovered {
+            self.header_bg_hover.unwrap_or(self.header_bg)
+        } else {
+            self.header_bg
+        };
+        if disabled {
+            bg = self.header_bg_disabled.get(bg);
+        }
+
+        QuadStyle {
+            bg,
+            border: self.header_border,
+            flags: self.quad_flags,
+        }
+    }
+
+    /// The height of a panel with the given content height, where
+    /// `open_amount` is `0.0` when fully collapsed and `1.0` when fully
+    /// expanded.
+    pub fn panel_height(&self, content_height: f32, open_amount: f32) -> f32 {
+        self.header_height + content_height.max(0.0) * open_amount.clamp(0.0, 1.0)
+    }
+}
+
+/// The rectangle of the header row of a panel with the given bounds.
+fn header_rect(bounds: Rect, header_height: f32) -> Rect {
+    Rect::new(
+        bounds.origin,
+        Size::new(bounds.width(), header_height.min(bounds.height())),
+    )
+}
+
+/// The rectangle below the header row of a panel with the given bounds.
+fn content_rect(bounds: Rect, header_height: f32) -> Rect {
+    Rect::new(
+        Point::new(bounds.min_x(), bounds.min_y() + header_height),
+        Size::new(bounds.width(), (bounds.height() - header_height).max(0.0)),
+    )
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[element_builder_disabled]
+#[element_builder_tooltip]
+#[derive_where(Default)]
+pub struct PanelBuilder<A: Clone + 'static> {
+    pub action: Option<Box<dyn FnMut(bool) -> A>>,
+    pub title: Option<String>,
+    pub expanded: bool,
+    pub content_height: f32,
+    pub text_offset: Vector,
+}
+
+impl<A: Clone + 'static> PanelBuilder<A> {
+    /// The action to send when the user toggles the panel by clicking on
+    /// its header. The argument is `true` if the panel is now expanded.
+    pub fn on_toggled<F: FnMut(bool) -> A + 'static>(mut self, f: F) -> Self {
+        self.action = Some(Box::new(f));
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub const fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// The height of the content area when the panel is fully expanded.
+    pub const fn content_height(mut self, height: f32) -> Self {
+        self.content_height = height;
+        self
+    }
+
+    /// An offset that can be used mainly to correct the position of the
+    /// header text.
+    pub const fn text_offset(mut self, offset: Vector) -> Self {
+        self.text_offset = offset;
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> Panel {
+        let PanelBuilder {
+            action,
+            title,
+            expanded,
+            content_height,
+            text_offset,
+            tooltip_data,
+            disabled,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let style = window_cx
+            .res
+            .style_system
+            .get::<PanelStyle>(window_cx.builder_class(class));
+        let cursor_icon = style.cursor_icon;
+        let collapse_animation = style.collapse_animation;
+        let label_style = style.label_style(false, disabled);
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            title: LabelInner::new(
+                title,
+                None,
+                text_offset,
+                Vector::default(),
+                None,
+                IconScale::default(),
+                Default::default(),
+                &label_style,
+                &mut window_cx.res.font_system,
+            ),
+            expanded,
+            open_amount: AnimatedF32::new(if expanded { 1.0 } else { 0.0 }, collapse_animation),
+            content_height,
+            disabled,
+            tooltip_inner: TooltipInner::new(tooltip_data),
+        }));
+
+        let el = ElementBuilder::new(PanelElement {
+            shared_state: Rc::clone(&shared_state),
+            action,
+            header_hovered: false,
+            cursor_icon,
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+        .build(window_cx);
+
+        Panel { el, shared_state }
+    }
+}
+
+struct PanelElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    action: Option<Box<dyn FnMut(bool) -> A>>,
+    header_hovered: bool,
+    cursor_icon: Option<CursorIcon>,
+}
+
+impl<A: Clone + 'static> PanelElement<A> {
+    fn resize_to_open_amount(&self, shared_state: &SharedState, cx: &mut ElementContext<'_, A>) {
+        let style = cx.res.style_system.get::<PanelStyle>(cx.class());
+        let height = style.panel_height(
+            shared_state.content_height,
+            shared_state.open_amount.value(),
+        );
+
+        let rect = cx.rect();
+        if rect.height() != height {
+            cx.set_rect(Rect::new(rect.origin, Size::new(rect.width(), height)));
+        }
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for PanelElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        shared_state
+            .tooltip_inner
+            .handle_event(&event, shared_state.disabled, cx);
+
+        match event {
+            ElementEvent::CustomStateChanged => {
+                cx.request_repaint();
+
+                if shared_state.disabled && self.header_hovered {
+                    self.header_hovered = false;
+                }
+
+                // The expanded state may have been changed from the handle.
+                if !shared_state.open_amount.is_settled() {
+                    cx.set_animating(true);
+                }
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<PanelStyle>(cx.class());
+                self.cursor_icon = style.cursor_icon;
+                shared_state.open_amount.mode = style.collapse_animation;
+
+                let label_style = style.label_style(false, shared_state.disabled);
+                shared_state
+                    .title
+                    .sync_new_style(&label_style, &mut cx.res.font_system);
+            }
+            ElementEvent::Animation { delta_seconds } => {
+                if shared_state.open_amount.on_animation(delta_seconds, cx) {
+                    self.resize_to_open_amount(&shared_state, cx);
+                    cx.request_repaint();
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { position, .. }) => {
+                if shared_state.disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let header_height = cx
+                    .res
+                    .style_system
+                    .get::<PanelStyle>(cx.class())
+                    .header_height;
+                let hovered = header_rect(cx.rect(), header_height).contains(position);
+
+                if self.header_hovered != hovered {
+                    self.header_hovered = hovered;
+                    cx.request_repaint();
+                }
+
+                if hovered {
+                    if let Some(cursor_icon) = self.cursor_icon {
+                        cx.cursor_icon = cursor_icon;
+                    }
+
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::PointerLeft) => {
+                if self.header_hovered {
+                    self.header_hovered = false;
+                    cx.request_repaint();
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position, button, ..
+            }) => {
+                if shared_state.disabled || button != PointerButton::Primary {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let header_height = cx
+                    .res
+                    .style_system
+                    .get::<PanelStyle>(cx.class())
+                    .header_height;
+                if !header_rect(cx.rect(), header_height).contains(position) {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let expanded = !shared_state.expanded;
+                if shared_state.set_expanded(expanded) {
+                    cx.set_animating(true);
+                }
+                cx.request_repaint();
+
+                if let Some(action) = &mut self.action {
+                    cx.send_action((action)(expanded));
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<PanelStyle>(cx.class, cx.opacity);
+
+        let bounds = Rect::from_size(cx.bounds_size);
+        let header_bounds = header_rect(bounds, style.header_height);
+        let body_bounds = content_rect(bounds, style.header_height);
+
+        if !style.body_quad.is_transparent() && body_bounds.height() > 0.0 {
+            primitives.add(style.body_quad.create_primitive(body_bounds));
+        }
+
+        let header_quad = style.header_quad(self.header_hovered, shared_state.disabled);
+        if !header_quad.is_transparent() {
+            primitives.add(header_quad.create_primitive(header_bounds));
+        }
+
+        let label_primitives = shared_state.title.render(
+            header_bounds,
+            &style.label_style(self.header_hovered, shared_state.disabled),
+            &mut cx.res.font_system,
+        );
+
+        if let Some(text_primitive) = label_primitives.text {
+            primitives.set_z_index(1);
+            primitives.add_text(text_primitive);
+        }
+    }
+}
+
+struct SharedState {
+    title: LabelInner,
+    expanded: bool,
+    /// `0.0` when fully collapsed and `1.0` when fully expanded.
+    open_amount: AnimatedF32,
+    content_height: f32,
+    disabled: bool,
+    tooltip_inner: TooltipInner,
+}
+
+impl SharedState {
+    /// Returns `true` if the collapse animation has started.
+    fn set_expanded(&mut self, expanded: bool) -> bool {
+        self.expanded = expanded;
+        self.open_amount
+            .set_target(if expanded { 1.0 } else { 0.0 })
+    }
+}
+
+/// A handle to a [`PanelElement`], a collapsible group with a clickable
+/// header row.
+///
+/// The panel does not manage any child elements itself. Use
+/// [`Panel::content_rect`] to lay out elements into the content area, and
+/// [`Panel::desired_height`] to lay out the elements below the panel. While
+/// the panel is animating, the panel resizes itself, so these should be
+/// queried every frame to follow along.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_set_tooltip]
+pub struct Panel {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl Panel {
+    pub fn builder<A: Clone + 'static>() -> PanelBuilder<A> {
+        PanelBuilder::default()
+    }
+
+    /// The height of the panel in its current (possibly animating) state.
+    ///
+    /// This is only the height of the header row when the panel is fully
+    /// collapsed.
+    pub fn desired_height(&self, res: &mut ResourceCtx) -> f32 {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        res.style_system
+            .get::<PanelStyle>(self.el.class())
+            .panel_height(
+                shared_state.content_height,
+                shared_state.open_amount.value(),
+            )
+    }
+
+    /// The rectangle of the content area below the header row, in window
+    /// coordinates.
+    ///
+    /// While the panel is animating, this only covers the part of the
+    /// content area that is currently uncovered.
+    pub fn content_rect(&self, res: &mut ResourceCtx) -> Rect {
+        let header_height = res
+            .style_system
+            .get::<PanelStyle>(self.el.class())
+            .header_height;
+
+        content_rect(self.el.rect(), header_height)
+    }
+
+    /// Set the expanded state of this element. The height of the panel will
+    /// animate towards the new state.
+    ///
+    /// Returns `true` if the expanded state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_expanded(&mut self, expanded: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.expanded != expanded {
+            shared_state.set_expanded(expanded);
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expanded(&self) -> bool {
+        RefCell::borrow(&self.shared_state).expanded
+    }
+
+    /// Set the height of the content area when the panel is fully expanded.
+    ///
+    /// Returns `true` if the height has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_content_height(&mut self, height: f32) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.content_height != height {
+            shared_state.content_height = height;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn content_height(&self) -> f32 {
+        RefCell::borrow(&self.shared_state).content_height
+    }
+
+    /// Set the text of the header row.
+    ///
+    /// Returns `true` if the text has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently. However, this method still
+    /// involves a string comparison so you may want to call this method
+    /// sparingly.
+    pub fn set_title<T: AsRef<str> + Into<String>>(
+        &mut self,
+        title: Option<T>,
+        res: &mut ResourceCtx,
+    ) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state
+            .title
+            .set_text(title, &mut res.font_system, || {
+                res.style_system
+                    .get::<PanelStyle>(self.el.class())
+                    .text_properties
+            })
+        {
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn title<'a>(&'a self) -> Option<Ref<'a, str>> {
+        Ref::filter_map(RefCell::borrow(&self.shared_state), |s| s.title.text()).ok()
+    }
+
+    /// Set the disabled state of this element.
+    ///
+    /// Returns `true` if the disabled state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_disabled(&mut self, disabled: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.disabled != disabled {
+            shared_state.disabled = disabled;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn disabled(&self) -> bool {
+        RefCell::borrow(&self.shared_state).disabled
+    }
+
+    /// Layout out the element with the top-left corner of the bounds set to
+    /// `origin` and the given width.
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout(&mut self, origin: Point, width: f32, res: &mut ResourceCtx) -> bool {
+        let height = self.desired_height(res);
+        self.el
+            .set_rect(Rect::new(origin, Size::new(width, height)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapsed_panel_reports_header_height() {
+        let style = PanelStyle {
+            header_height: 20.0,
+            ..Default::default()
+        };
+
+        assert_eq!(style.panel_height(100.0, 1.0), 120.0);
+        assert_eq!(style.panel_height(100.0, 0.5), 70.0);
+        assert_eq!(style.panel_height(100.0, 0.0), 20.0);
+
+        let bounds = Rect::new(Point::new(10.0, 30.0), Size::new(200.0, 70.0));
+        assert_eq!(
+            header_rect(bounds, 20.0),
+            Rect::new(Point::new(10.0, 30.0), Size::new(200.0, 20.0))
+        );
+        assert_eq!(
+            content_rect(bounds, 20.0),
+            Rect::new(Point::new(10.0, 50.0), Size::new(200.0, 50.0))
+        );
+
+        // A fully collapsed panel has an empty content area.
+        let collapsed = Rect::new(Point::new(10.0, 30.0), Size::new(200.0, 20.0));
+        assert_eq!(content_rect(collapsed, 20.0).height(), 0.0);
+    }
+}
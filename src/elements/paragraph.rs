@@ -11,8 +11,14 @@ use super::label::LabelPrimitives;
 
 /// The style of a [`Paragraph`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct ParagraphStyle {
     /// The text properties.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
 
     /// The color of the font
@@ -67,6 +73,17 @@ impl ElementStyle for ParagraphStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.back_quad.multiply_alpha(multiplier);
+    }
 }
 
 /// A reusable Paragraph struct that can be used by other elements.
@@ -354,8 +371,17 @@ impl<A: Clone + 'static> Element<A> for ParagraphElement {
         event: ElementEvent,
         cx: &mut ElementContext<'_, A>,
     ) -> EventCaptureStatus {
-        if let ElementEvent::CustomStateChanged = event {
-            cx.request_repaint();
+        match event {
+            ElementEvent::CustomStateChanged => {
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<ParagraphStyle>(cx.class());
+                RefCell::borrow_mut(&self.shared_state)
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
+            }
+            _ => {}
         }
 
         EventCaptureStatus::NotCaptured
@@ -366,7 +392,9 @@ impl<A: Clone + 'static> Element<A> for ParagraphElement {
 
         let paragraph_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
-            cx.res.style_system.get(cx.class),
+            &cx.res
+                .style_system
+                .get_with_opacity::<ParagraphStyle>(cx.class, cx.opacity),
         );
 
         if let Some(quad_primitive) = paragraph_primitives.bg_quad {
@@ -384,6 +412,32 @@ struct SharedState {
     inner: ParagraphInner,
 }
 
+impl SizableElement for Paragraph {
+    /// The size of the paragraph if its text were wrapped to the available
+    /// width.
+    fn measure(&self, available: Size, res: &mut ResourceCtx) -> Size {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        let style = res.style_system.get::<ParagraphStyle>(self.el.class());
+
+        let bounds_width = shared_state.inner.bounds_width();
+        if bounds_width == available.width {
+            return shared_state.inner.desired_size(|| style.padding);
+        }
+
+        shared_state
+            .inner
+            .set_bounds_width(available.width, style, &mut res.font_system);
+        let size = shared_state.inner.desired_size(|| style.padding);
+
+        // Restore the wrapping of the current bounds.
+        shared_state
+            .inner
+            .set_bounds_width(bounds_width, style, &mut res.font_system);
+
+        size
+    }
+}
+
 /// A handle to a [`ParagraphElement`], a Paragraph with an optional quad background.
 #[element_handle]
 #[element_handle_class]
@@ -0,0 +1,454 @@
+use derive_where::derive_where;
+use std::cell::{Ref, RefCell};
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::rc::Rc;
+
+use rootvg::mesh::MeshPrimitive;
+use rootvg::tessellation::{
+    fill::FillStyle,
+    path::{ArcPath, PathBuilder},
+    stroke::{LineCap, LineDash, LineJoin, Stroke},
+    Tessellator,
+};
+
+use crate::derive::*;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct PieChartStyle {
+    /// The radius of the hole in the center of the chart, as a fraction of
+    /// the radius of the chart. Set this to a value greater than `0.0` to
+    /// draw a donut chart.
+    ///
+    /// By default this is set to `0.0`.
+    pub hole_radius_scale: f32,
+
+    /// The angle of the gap between two adjacent segments.
+    ///
+    /// By default this is set to `0.0`.
+    pub segment_gap: Angle,
+
+    /// The angle at which the first segment starts, where `0.0` points to
+    /// the right and the angle increases clockwise.
+    ///
+    /// By default this is set to `-PI / 2` (pointing up).
+    pub start_angle: Angle,
+
+    /// How many points the hovered segment is extended outwards.
+    ///
+    /// The chart is shrunk by this amount so that the hovered segment stays
+    /// within the bounds of the element.
+    ///
+    /// By default this is set to `3.0`.
+    pub hover_expand: f32,
+}
+
+impl Default for PieChartStyle {
+    fn default() -> Self {
+        Self {
+            hole_radius_scale: 0.0,
+            segment_gap: Angle { radians: 0.0 },
+            start_angle: Angle {
+                radians: -FRAC_PI_2,
+            },
+            hover_expand: 3.0,
+        }
+    }
+}
+
+impl PieChartStyle {
+    /// The outer and inner radius of the chart for the given bounds size.
+    pub fn radii(&self, bounds_size: Size) -> (f32, f32) {
+        let outer = (bounds_size.width.min(bounds_size.height) * 0.5 - self.hover_expand).max(0.0);
+        let inner = outer * self.hole_radius_scale.clamp(0.0, 1.0);
+
+        (outer, inner)
+    }
+}
+
+impl ElementStyle for PieChartStyle {
+    const ID: &'static str = "pie";
+
+    fn default_dark_style() -> Self {
+        Self::default()
+    }
+
+    fn default_light_style() -> Self {
+        Self::default()
+    }
+}
+
+/// The start and end angles (in radians) of each segment, relative to
+/// [`PieChartStyle::start_angle`].
+///
+/// Negative values are treated as `0.0`.
+pub fn segment_spans(values: impl Iterator<Item = f32> + Clone) -> Vec<(f32, f32)> {
+    let total: f32 = values.clone().map(|v| v.max(0.0)).sum();
+
+    let mut start = 0.0;
+    values
+        .map(|v| {
+            let span = if total > 0.0 {
+                v.max(0.0) / total * TAU
+            } else {
+                0.0
+            };
+
+            let s = (start, start + span);
+            start += span;
+            s
+        })
+        .collect()
+}
+
+/// Find the index of the segment under the given point.
+///
+/// * `offset` - The position of the point relative to the center of the
+/// chart.
+/// * `spans` - The spans returned by [`segment_spans`].
+pub fn segment_index_at(
+    offset: Vector,
+    outer_radius: f32,
+    inner_radius: f32,
+    start_angle: Angle,
+    spans: &[(f32, f32)],
+) -> Option<usize> {
+    let dist = offset.length();
+    if dist > outer_radius || dist < inner_radius {
+        return None;
+    }
+
+    let angle = (offset.y.atan2(offset.x) - start_angle.radians).rem_euclid(TAU);
+
+    spans.iter().position(|(s, e)| angle >= *s && angle < *e)
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[derive_where(Default)]
+pub struct PieChartBuilder<A: Clone + 'static> {
+    pub on_segment_hovered: Option<Box<dyn FnMut(Option<usize>) -> A>>,
+    pub data: Vec<(f32, RGBA8)>,
+}
+
+impl<A: Clone + 'static> PieChartBuilder<A> {
+    /// The action to send when the hovered segment changes. The index of
+    /// the newly hovered segment is passed to the closure, or `None` if no
+    /// segment is hovered anymore.
+    pub fn on_segment_hovered<F: FnMut(Option<usize>) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_segment_hovered = Some(Box::new(f));
+        self
+    }
+
+    /// The value and the color of each segment. The size of each segment is
+    /// proportional to its value.
+    pub fn data(mut self, data: Vec<(f32, RGBA8)>) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> PieChart {
+        let PieChartBuilder {
+            on_segment_hovered,
+            data,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            data,
+            data_changed: false,
+        }));
+
+        let el = ElementBuilder::new(PieChartElement {
+            shared_state: Rc::clone(&shared_state),
+            on_segment_hovered,
+            hovered_segment: None,
+            cache: None,
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+        .build(window_cx);
+
+        PieChart { el, shared_state }
+    }
+}
+
+struct MeshCache {
+    bounds_size: Size,
+    hovered_segment: Option<usize>,
+    opacity: f32,
+    meshes: Vec<MeshPrimitive>,
+}
+
+struct PieChartElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    on_segment_hovered: Option<Box<dyn FnMut(Option<usize>) -> A>>,
+    hovered_segment: Option<usize>,
+    cache: Option<MeshCache>,
+}
+
+impl<A: Clone + 'static> PieChartElement<A> {
+    fn set_hovered_segment(
+        &mut self,
+        hovered_segment: Option<usize>,
+        cx: &mut ElementContext<'_, A>,
+    ) {
+        if self.hovered_segment == hovered_segment {
+            return;
+        }
+
+        self.hovered_segment = hovered_segment;
+        cx.request_repaint();
+
+        if let Some(f) = self.on_segment_hovered.as_mut() {
+            cx.send_action((f)(hovered_segment));
+        }
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for PieChartElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        match event {
+            ElementEvent::CustomStateChanged => {
+                let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+                if shared_state.data_changed {
+                    shared_state.data_changed = false;
+                    self.cache = None;
+                    cx.request_repaint();
+
+                    let num_segments = shared_state.data.len();
+                    drop(shared_state);
+
+                    if self.hovered_segment.is_some_and(|i| i >= num_segments) {
+                        self.set_hovered_segment(None, cx);
+                    }
+                }
+            }
+            ElementEvent::StyleChanged => {
+                self.cache = None;
+                cx.request_repaint();
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { position, .. }) => {
+                let rect = cx.rect();
+                let style = cx.res.style_system.get::<PieChartStyle>(cx.class());
+                let (outer_radius, inner_radius) = style.radii(rect.size);
+                let start_angle = style.start_angle;
+
+                let hovered_segment = {
+                    let shared_state = RefCell::borrow(&self.shared_state);
+                    let spans = segment_spans(shared_state.data.iter().map(|(v, _)| *v));
+
+                    segment_index_at(
+                        position - rect.center(),
+                        outer_radius,
+                        inner_radius,
+                        start_angle,
+                        &spans,
+                    )
+                };
+
+                self.set_hovered_segment(hovered_segment, cx);
+
+                if hovered_segment.is_some() {
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::PointerLeft) => {
+                self.set_hovered_segment(None, cx);
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let needs_rebuild = self.cache.as_ref().map_or(true, |cache| {
+            cache.bounds_size != cx.bounds_size
+                || cache.hovered_segment != self.hovered_segment
+                || cache.opacity != cx.opacity
+        });
+
+        if needs_rebuild {
+            let style = cx.res.style_system.get::<PieChartStyle>(cx.class);
+            let shared_state = RefCell::borrow(&self.shared_state);
+
+            let (outer_radius, inner_radius) = style.radii(cx.bounds_size);
+            let center = Point::new(cx.bounds_size.width * 0.5, cx.bounds_size.height * 0.5);
+            let half_gap = style.segment_gap.radians * 0.5;
+
+            let spans = segment_spans(shared_state.data.iter().map(|(v, _)| *v));
+
+            let meshes = spans
+                .iter()
+                .zip(shared_state.data.iter())
+                .enumerate()
+                .filter_map(|(i, ((start, end), (_, color)))| {
+                    let start = start + half_gap;
+                    let end = end - half_gap;
+                    if end <= start {
+                        return None;
+                    }
+
+                    let outer_radius = if self.hovered_segment == Some(i) {
+                        outer_radius + style.hover_expand
+                    } else {
+                        outer_radius
+                    };
+                    let width = outer_radius - inner_radius;
+                    if width <= 0.0 {
+                        return None;
+                    }
+
+                    // The segment is drawn as a thick arc which spans from
+                    // the inner radius to the outer radius.
+                    let arc_path = PathBuilder::new()
+                        .arc(ArcPath {
+                            center,
+                            radius: inner_radius + width * 0.5,
+                            start_angle: style.start_angle + Angle { radians: start },
+                            end_angle: style.start_angle + Angle { radians: end },
+                        })
+                        .build();
+
+                    let mut color = *color;
+                    if cx.opacity < 1.0 {
+                        color.multiply_alpha(cx.opacity);
+                    }
+
+                    let stroke = Stroke {
+                        style: FillStyle::Solid(color.into()),
+                        width,
+                        line_cap: LineCap::Butt,
+                        line_join: LineJoin::default(),
+                        line_dash: LineDash::default(),
+                    };
+
+                    Tessellator::new()
+                        .stroke(&arc_path, stroke)
+                        .into_primitive()
+                })
+                .collect();
+
+            self.cache = Some(MeshCache {
+                bounds_size: cx.bounds_size,
+                hovered_segment: self.hovered_segment,
+                opacity: cx.opacity,
+                meshes,
+            });
+        }
+
+        if let Some(cache) = &self.cache {
+            for mesh in cache.meshes.iter() {
+                primitives.add_mesh(mesh.clone());
+            }
+        }
+    }
+}
+
+struct SharedState {
+    data: Vec<(f32, RGBA8)>,
+    data_changed: bool,
+}
+
+/// A pie chart (or donut chart) which displays proportional segments.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_layout_aligned]
+pub struct PieChart {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl PieChart {
+    pub fn builder<A: Clone + 'static>() -> PieChartBuilder<A> {
+        PieChartBuilder::default()
+    }
+
+    /// Set the value and the color of each segment.
+    ///
+    /// Returns `true` if the data has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_data(&mut self, data: Vec<(f32, RGBA8)>) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.data != data {
+            shared_state.data = data;
+            shared_state.data_changed = true;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn data<'a>(&'a self) -> Ref<'a, [(f32, RGBA8)]> {
+        Ref::map(RefCell::borrow(&self.shared_state), |s| s.data.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_values_produce_equal_segments() {
+        let spans = segment_spans([1.0, 1.0, 1.0].into_iter());
+
+        assert_eq!(spans.len(), 3);
+        for (i, (start, end)) in spans.iter().enumerate() {
+            assert!((start.to_degrees() - 120.0 * i as f32).abs() < 1e-3);
+            assert!(((end - start).to_degrees() - 120.0).abs() < 1e-3);
+        }
+
+        // Negative values produce empty segments.
+        let spans = segment_spans([1.0, -1.0].into_iter());
+        assert_eq!(spans[1].0, spans[1].1);
+    }
+
+    #[test]
+    fn test_segment_index_at_hovered_point() {
+        let spans = segment_spans([1.0, 1.0, 1.0].into_iter());
+        let style = PieChartStyle {
+            hole_radius_scale: 0.5,
+            ..Default::default()
+        };
+        let (outer, inner) = style.radii(Size::new(106.0, 106.0));
+        assert_eq!((outer, inner), (50.0, 25.0));
+
+        let index_at = |x: f32, y: f32| {
+            segment_index_at(Vector::new(x, y), outer, inner, style.start_angle, &spans)
+        };
+
+        // The first segment starts at the top and goes clockwise.
+        assert_eq!(index_at(1.0, -40.0), Some(0));
+        assert_eq!(index_at(40.0, 0.0), Some(0));
+        assert_eq!(index_at(0.0, 40.0), Some(1));
+        assert_eq!(index_at(-40.0, 0.0), Some(2));
+        assert_eq!(index_at(-1.0, -40.0), Some(2));
+
+        // Inside the hole and outside of the chart.
+        assert_eq!(index_at(0.0, 10.0), None);
+        assert_eq!(index_at(0.0, 60.0), None);
+    }
+}
@@ -48,12 +48,12 @@ impl<A: Clone + 'static> Element<A> for QuadElementInternal {
     }
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
-        primitives.add(
-            cx.res
-                .style_system
-                .get::<QuadStyle>(cx.class)
-                .create_primitive(Rect::from_size(cx.bounds_size)),
-        );
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<QuadStyle>(cx.class, cx.opacity);
+
+        primitives.add(style.create_primitive(Rect::from_size(cx.bounds_size)));
     }
 }
 
@@ -1,14 +1,20 @@
 use derive_where::derive_where;
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 use crate::derive::*;
+use crate::element_system::element::CustomStateNotifier;
 use crate::prelude::*;
 
 use super::label::Label;
 
 /// The style of a [`RadioButton`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct RadioButtonStyle {
     pub size: f32,
     pub radius: Radius,
@@ -86,6 +92,26 @@ impl ElementStyle for RadioButtonStyle {
     fn default_light_style() -> Self {
         Self::default()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.outer_border_color_off.multiply_alpha(multiplier);
+        self.outer_border_color_off_hover.multiply_alpha(multiplier);
+        self.outer_border_color_off_disabled
+            .multiply_alpha(multiplier);
+        self.outer_border_color_on.multiply_alpha(multiplier);
+        self.outer_border_color_on_hover.multiply_alpha(multiplier);
+        self.outer_border_color_on_disabled
+            .multiply_alpha(multiplier);
+        self.off_bg.multiply_alpha(multiplier);
+        self.off_bg_hover.multiply_alpha(multiplier);
+        self.off_bg_disabled.multiply_alpha(multiplier);
+        self.on_bg.multiply_alpha(multiplier);
+        self.on_bg_hover.multiply_alpha(multiplier);
+        self.on_bg_disabled.multiply_alpha(multiplier);
+        self.dot_bg.multiply_alpha(multiplier);
+        self.dot_bg_hover.multiply_alpha(multiplier);
+        self.dot_bg_disabled.multiply_alpha(multiplier);
+    }
 }
 
 #[element_builder]
@@ -98,6 +124,7 @@ impl ElementStyle for RadioButtonStyle {
 pub struct RadioButtonBuilder<A: Clone + 'static> {
     pub action: Option<A>,
     pub toggled: bool,
+    pub group: Option<(RadioGroupID, usize)>,
 }
 
 impl<A: Clone + 'static> RadioButtonBuilder<A> {
@@ -111,11 +138,23 @@ impl<A: Clone + 'static> RadioButtonBuilder<A> {
         self
     }
 
+    /// Add this radio button to a mutually-exclusive group with the given
+    /// index in that group.
+    ///
+    /// When the user selects this radio button, every other radio button in
+    /// the group is deselected. The toggled state of this radio button is
+    /// taken from the group, so [`RadioButtonBuilder::toggled`] is ignored.
+    pub fn group(mut self, group: &RadioGroupID, index: usize) -> Self {
+        self.group = Some((group.clone(), index));
+        self
+    }
+
     pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> RadioButton {
         let RadioButtonBuilder {
             action,
             tooltip_data,
             toggled,
+            group,
             class,
             z_index,
             rect,
@@ -130,6 +169,12 @@ impl<A: Clone + 'static> RadioButtonBuilder<A> {
             .get::<RadioButtonStyle>(window_cx.builder_class(class));
         let cursor_icon = style.cursor_icon;
 
+        let toggled = if let Some((group, index)) = &group {
+            group.selected_index() == Some(*index)
+        } else {
+            toggled
+        };
+
         let shared_state = Rc::new(RefCell::new(SharedState {
             toggled,
             disabled,
@@ -139,6 +184,7 @@ impl<A: Clone + 'static> RadioButtonBuilder<A> {
         let el = ElementBuilder::new(RadioButtonElement {
             shared_state: Rc::clone(&shared_state),
             action,
+            group: group.clone(),
             hovered: false,
             cursor_icon,
         })
@@ -148,13 +194,26 @@ impl<A: Clone + 'static> RadioButtonBuilder<A> {
         .flags(ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
         .build(window_cx);
 
-        RadioButton { el, shared_state }
+        if let Some((group, index)) = &group {
+            RefCell::borrow_mut(&group.state).members.push(GroupMember {
+                index: *index,
+                shared_state: Rc::downgrade(&shared_state),
+                notifier: el.custom_state_notifier(),
+            });
+        }
+
+        RadioButton {
+            el,
+            shared_state,
+            group,
+        }
     }
 }
 
 struct RadioButtonElement<A: Clone + 'static> {
     shared_state: Rc<RefCell<SharedState>>,
     action: Option<A>,
+    group: Option<(RadioGroupID, usize)>,
     hovered: bool,
     cursor_icon: Option<CursorIcon>,
 }
@@ -213,11 +272,17 @@ impl<A: Clone + 'static> Element<A> for RadioButtonElement<A> {
                 }
 
                 if button == PointerButton::Primary {
+                    // Selecting the already-selected radio button is a no-op.
                     if !shared_state.toggled {
                         shared_state.toggled = true;
 
+                        if let Some((group, index)) = &self.group {
+                            RefCell::borrow_mut(&group.state)
+                                .select(Some(*index), Some(&self.shared_state));
+                        }
+
                         if let Some(action) = &self.action {
-                            cx.send_action(action.clone()).unwrap();
+                            cx.send_action(action.clone());
                         }
 
                         cx.request_repaint();
@@ -235,7 +300,10 @@ impl<A: Clone + 'static> Element<A> for RadioButtonElement<A> {
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let shared_state = RefCell::borrow(&self.shared_state);
 
-        let style = cx.res.style_system.get::<RadioButtonStyle>(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<RadioButtonStyle>(cx.class, cx.opacity);
 
         let bg_quad_style = if shared_state.disabled {
             if shared_state.toggled {
@@ -371,6 +439,7 @@ impl<A: Clone + 'static> Element<A> for RadioButtonElement<A> {
 #[element_handle_set_tooltip]
 pub struct RadioButton {
     shared_state: Rc<RefCell<SharedState>>,
+    group: Option<(RadioGroupID, usize)>,
 }
 
 struct SharedState {
@@ -379,6 +448,94 @@ struct SharedState {
     tooltip_inner: TooltipInner,
 }
 
+/// An identifier shared by the radio buttons in a mutually-exclusive group.
+///
+/// Cloning this is cheap, and all clones refer to the same group.
+#[derive(Clone)]
+pub struct RadioGroupID {
+    state: Rc<RefCell<RadioGroupState>>,
+}
+
+impl RadioGroupID {
+    pub fn new(selected_index: Option<usize>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(RadioGroupState {
+                selected_index,
+                members: Vec::new(),
+            })),
+        }
+    }
+
+    /// The index of the selected radio button in this group.
+    pub fn selected_index(&self) -> Option<usize> {
+        RefCell::borrow(&self.state).selected_index
+    }
+
+    /// Select the radio button with the given index and deselect the others.
+    ///
+    /// Returns `true` if the selected index has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_selected(&self, index: Option<usize>) -> bool {
+        RefCell::borrow_mut(&self.state).select(index, None)
+    }
+}
+
+impl std::fmt::Debug for RadioGroupID {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RadioGroupID")
+            .field("selected_index", &self.selected_index())
+            .finish()
+    }
+}
+
+struct RadioGroupState {
+    selected_index: Option<usize>,
+    members: Vec<GroupMember>,
+}
+
+struct GroupMember {
+    index: usize,
+    shared_state: Weak<RefCell<SharedState>>,
+    notifier: CustomStateNotifier,
+}
+
+impl RadioGroupState {
+    /// Update the toggled state of every member in the group.
+    ///
+    /// `caller` is the already-borrowed state of the radio button which was
+    /// selected by the user, if any.
+    fn select(&mut self, index: Option<usize>, caller: Option<&Rc<RefCell<SharedState>>>) -> bool {
+        if self.selected_index == index {
+            return false;
+        }
+        self.selected_index = index;
+
+        self.members.retain_mut(|member| {
+            // The radio button has been dropped.
+            let Some(shared_state) = member.shared_state.upgrade() else {
+                return false;
+            };
+
+            if caller.is_some_and(|caller| Rc::ptr_eq(caller, &shared_state)) {
+                return true;
+            }
+
+            let toggled = index == Some(member.index);
+            let mut shared_state = RefCell::borrow_mut(&shared_state);
+            if shared_state.toggled != toggled {
+                shared_state.toggled = toggled;
+                member.notifier.notify();
+            }
+
+            true
+        });
+
+        true
+    }
+}
+
 impl RadioButton {
     pub fn builder<A: Clone + 'static>() -> RadioButtonBuilder<A> {
         RadioButtonBuilder::default()
@@ -394,11 +551,23 @@ impl RadioButton {
 
     /// Set the toggled state of this element.
     ///
+    /// If this radio button is in a group, then toggling it on deselects the
+    /// other radio buttons in the group, and toggling it off leaves the group
+    /// with no selection.
+    ///
     /// Returns `true` if the toggle state has changed.
     ///
     /// This will *NOT* trigger an element update unless the value has changed,
     /// so this method is relatively cheap to call frequently.
     pub fn set_toggled(&mut self, toggled: bool) -> bool {
+        if let Some((group, index)) = &self.group {
+            if (group.selected_index() == Some(*index)) == toggled {
+                return false;
+            }
+
+            return group.set_selected(toggled.then_some(*index));
+        }
+
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
         if shared_state.toggled != toggled {
@@ -457,9 +626,12 @@ impl RadioButton {
 
 // TODO: Different alignment options.
 /// A helper struct to make a group of radio buttons with labels.
+///
+/// Selecting one of the radio buttons deselects the others, and clicking the
+/// already-selected radio button does nothing.
 pub struct RadioButtonGroup {
     rows: Vec<(RadioButton, Label)>,
-    selected_index: usize,
+    group: RadioGroupID,
     bounds: Rect,
 }
 
@@ -483,6 +655,8 @@ impl RadioButtonGroup {
         let label_class = label_class.unwrap_or_else(|| window_cx.class());
         let radio_btn_class = radio_btn_class.unwrap_or_else(|| window_cx.class());
 
+        let group = RadioGroupID::new(Some(selected_index));
+
         let rows: Vec<(RadioButton, Label)> = options
             .into_iter()
             .enumerate()
@@ -490,7 +664,7 @@ impl RadioButtonGroup {
                 (
                     RadioButton::builder()
                         .on_toggled_on((on_selected)(i))
-                        .toggled(i == selected_index)
+                        .group(&group, i)
                         .class(radio_btn_class)
                         .z_index(z_index)
                         .scissor_rect(scissor_rect)
@@ -507,7 +681,7 @@ impl RadioButtonGroup {
 
         Self {
             rows,
-            selected_index,
+            group,
             bounds: Rect::default(),
         }
     }
@@ -576,24 +750,37 @@ impl RadioButtonGroup {
         self.bounds.size.width = max_row_width;
     }
 
-    pub fn updated_selected(&mut self, selected_index: usize) {
+    /// The index of the selected radio button.
+    pub fn selected_index(&self) -> usize {
+        self.group.selected_index().unwrap_or(0)
+    }
+
+    /// Select the radio button with the given index and deselect the others.
+    ///
+    /// If the index is out of range, then the first radio button is selected.
+    ///
+    /// Returns `true` if the selected index has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_selected(&mut self, selected_index: usize) -> bool {
         let selected_index = if selected_index >= self.rows.len() {
             0
         } else {
             selected_index
         };
 
-        if self.selected_index == selected_index {
-            return;
-        }
-
-        if let Some((prev_selected_btn, _)) = self.rows.get_mut(self.selected_index) {
-            prev_selected_btn.set_toggled(false);
-        }
+        self.group.set_selected(Some(selected_index))
+    }
 
-        self.selected_index = selected_index;
+    /// An alias for [`RadioButtonGroup::set_selected`].
+    pub fn updated_selected(&mut self, selected_index: usize) {
+        self.set_selected(selected_index);
+    }
 
-        self.rows[selected_index].0.set_toggled(true);
+    /// The group identifier shared by the radio buttons in this group.
+    pub fn group_id(&self) -> &RadioGroupID {
+        &self.group
     }
 
     pub fn bounds(&self) -> Rect {
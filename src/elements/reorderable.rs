@@ -0,0 +1,674 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::prelude::*;
+
+/// The style of a [`Reorderable`] element
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ReorderableStyle {
+    /// The quad drawn in the gap where the dragged item will be dropped.
+    pub gap_quad: QuadStyle,
+
+    /// The thickness of the gap indicator in points.
+    ///
+    /// By default this is set to `2.0`.
+    pub gap_thickness: f32,
+
+    /// The quad drawn over the item which is being dragged.
+    pub dragged_item_quad: QuadStyle,
+
+    /// The cursor icon to show while an item is being dragged.
+    ///
+    /// By default this is set to `Some(CursorIcon::Grabbing)`.
+    pub cursor_icon: Option<CursorIcon>,
+}
+
+impl Default for ReorderableStyle {
+    fn default() -> Self {
+        Self {
+            gap_quad: QuadStyle {
+                bg: Background::Solid(RGBA8::new(150, 150, 150, 200)),
+                border: BorderStyle::default(),
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            },
+            gap_thickness: 2.0,
+            dragged_item_quad: QuadStyle {
+                bg: Background::Solid(RGBA8::new(150, 150, 150, 30)),
+                border: BorderStyle::default(),
+                flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+            },
+            cursor_icon: Some(CursorIcon::Grabbing),
+        }
+    }
+}
+
+impl ElementStyle for ReorderableStyle {
+    const ID: &'static str = "rordr";
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.gap_quad.multiply_alpha(multiplier);
+        self.dragged_item_quad.multiply_alpha(multiplier);
+    }
+}
+
+/// The state of an item which is currently being dragged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReorderDragState {
+    /// The index of the item being dragged.
+    pub from: usize,
+    /// The index the item will have once it is dropped.
+    pub to: usize,
+    /// The gap between items where the item will be dropped, relative to the
+    /// top left corner of the element.
+    pub gap_rect: Rect,
+}
+
+/// The drag-to-reorder logic of a [`Reorderable`], independent of any
+/// element.
+///
+/// The items are assumed to be stacked vertically with a uniform height,
+/// starting at the top of the element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReorderInner {
+    pub item_count: usize,
+    pub item_height: f32,
+    /// How far the pointer must move before a press turns into a drag.
+    pub drag_threshold: f32,
+    /// The distance from the top and bottom edges of the visible part of the
+    /// list in which dragging an item auto-scrolls the list.
+    pub auto_scroll_margin: f32,
+    /// The maximum speed of the auto-scroll in points per second.
+    pub auto_scroll_speed: f32,
+
+    pressed: Option<PressedState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PressedState {
+    from: usize,
+    start_pos: Point,
+    pointer_pos: Point,
+    dragging: bool,
+}
+
+impl ReorderInner {
+    pub fn new(item_count: usize, item_height: f32) -> Self {
+        Self {
+            item_count,
+            item_height,
+            drag_threshold: 4.0,
+            auto_scroll_margin: 24.0,
+            auto_scroll_speed: 400.0,
+            pressed: None,
+        }
+    }
+
+    /// Returns `true` if an item is currently being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.pressed.map(|p| p.dragging).unwrap_or(false)
+    }
+
+    /// Call when the primary pointer button was pressed.
+    ///
+    /// * `bounds` - The rectangle of the element.
+    /// * `visible_rect` - The part of the element that is visible inside of
+    /// its scissoring rectangle.
+    ///
+    /// Returns `true` if the press landed on an item.
+    pub fn on_pressed(&mut self, position: Point, bounds: Rect, visible_rect: Rect) -> bool {
+        self.pressed = None;
+
+        if !visible_rect.contains(position) || self.item_height <= 0.0 {
+            return false;
+        }
+
+        let index = ((position.y - bounds.min_y()) / self.item_height).floor();
+        if index < 0.0 || index as usize >= self.item_count {
+            return false;
+        }
+
+        self.pressed = Some(PressedState {
+            from: index as usize,
+            start_pos: position,
+            pointer_pos: position,
+            dragging: false,
+        });
+
+        true
+    }
+
+    /// Call when the pointer has moved.
+    ///
+    /// Returns `true` if an item is being dragged.
+    pub fn on_pointer_moved(&mut self, position: Point) -> bool {
+        let Some(pressed) = &mut self.pressed else {
+            return false;
+        };
+
+        pressed.pointer_pos = position;
+
+        if !pressed.dragging
+            && (position - pressed.start_pos).length() >= self.drag_threshold.max(0.0)
+        {
+            pressed.dragging = true;
+        }
+
+        pressed.dragging
+    }
+
+    /// Call when the primary pointer button was released.
+    ///
+    /// Returns the `(from, to)` indexes of the reorder if the item was dropped
+    /// somewhere other than where it started.
+    pub fn on_released(&mut self, bounds: Rect, visible_rect: Rect) -> Option<(usize, usize)> {
+        let drag_state = self.drag_state(bounds, visible_rect);
+        self.pressed = None;
+
+        drag_state
+            .filter(|s| s.from != s.to)
+            .map(|s| (s.from, s.to))
+    }
+
+    /// Cancel the current drag, if any.
+    pub fn cancel(&mut self) {
+        self.pressed = None;
+    }
+
+    /// The state of the current drag, or `None` if no item is being dragged.
+    ///
+    /// The pointer is clamped to the visible part of the list, so items
+    /// hidden by the scissoring rectangle can't be dropped onto.
+    pub fn drag_state(&self, bounds: Rect, visible_rect: Rect) -> Option<ReorderDragState> {
+        let pressed = self.pressed.filter(|p| p.dragging)?;
+
+        let y = pressed
+            .pointer_pos
+            .y
+            .clamp(visible_rect.min_y(), visible_rect.max_y());
+
+        // The gap lies before the item whose top half the pointer is over.
+        let slot = ((y - bounds.min_y()) / self.item_height + 0.5)
+            .floor()
+            .clamp(0.0, self.item_count as f32) as usize;
+
+        let to = if slot > pressed.from { slot - 1 } else { slot };
+
+        Some(ReorderDragState {
+            from: pressed.from,
+            to,
+            gap_rect: Rect::new(
+                Point::new(0.0, slot as f32 * self.item_height),
+                Size::new(bounds.width(), 0.0),
+            ),
+        })
+    }
+
+    /// The amount to scroll the list by this frame while an item is dragged
+    /// near the top or bottom edge of the visible part of the list.
+    ///
+    /// The speed increases the closer the pointer is to the edge.
+    pub fn auto_scroll_delta(&self, visible_rect: Rect, delta_seconds: f64) -> f32 {
+        let Some(pressed) = self.pressed.filter(|p| p.dragging) else {
+            return 0.0;
+        };
+
+        if self.auto_scroll_margin <= 0.0 {
+            return 0.0;
+        }
+
+        let y = pressed.pointer_pos.y;
+        let top_dist = y - visible_rect.min_y();
+        let bottom_dist = visible_rect.max_y() - y;
+
+        let amount = if top_dist < self.auto_scroll_margin {
+            -(1.0 - (top_dist / self.auto_scroll_margin)).min(1.0)
+        } else if bottom_dist < self.auto_scroll_margin {
+            (1.0 - (bottom_dist / self.auto_scroll_margin)).min(1.0)
+        } else {
+            0.0
+        };
+
+        amount * self.auto_scroll_speed * delta_seconds as f32
+    }
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+pub struct ReorderableBuilder<A: Clone + 'static> {
+    pub reordered_action: Option<Box<dyn FnMut(usize, usize) -> A>>,
+    pub auto_scroll_action: Option<Box<dyn FnMut(f32) -> A>>,
+    pub item_count: usize,
+    pub item_height: f32,
+    pub drag_threshold: f32,
+    pub auto_scroll_margin: f32,
+    pub auto_scroll_speed: f32,
+}
+
+impl<A: Clone + 'static> Default for ReorderableBuilder<A> {
+    fn default() -> Self {
+        let inner = ReorderInner::new(0, 24.0);
+
+        Self {
+            reordered_action: None,
+            auto_scroll_action: None,
+            item_count: inner.item_count,
+            item_height: inner.item_height,
+            drag_threshold: inner.drag_threshold,
+            auto_scroll_margin: inner.auto_scroll_margin,
+            auto_scroll_speed: inner.auto_scroll_speed,
+            class: None,
+            z_index: None,
+            rect: Rect::default(),
+            manually_hidden: false,
+            scissor_rect: None,
+        }
+    }
+}
+
+impl<A: Clone + 'static> ReorderableBuilder<A> {
+    /// The action to send when an item has been dropped at a new position.
+    ///
+    /// The arguments are `(from, to)`, where `to` is the index the item
+    /// should have after it has been removed from index `from`.
+    pub fn on_reordered<F: FnMut(usize, usize) -> A + 'static>(mut self, f: F) -> Self {
+        self.reordered_action = Some(Box::new(f));
+        self
+    }
+
+    /// The action to send on every frame that the list should be scrolled
+    /// vertically by the given amount in points, because an item is being
+    /// dragged near the top or bottom edge of the list. This is usually
+    /// forwarded to [`ScrollArea::set_scroll_offset`].
+    pub fn on_auto_scroll<F: FnMut(f32) -> A + 'static>(mut self, f: F) -> Self {
+        self.auto_scroll_action = Some(Box::new(f));
+        self
+    }
+
+    pub const fn item_count(mut self, item_count: usize) -> Self {
+        self.item_count = item_count;
+        self
+    }
+
+    pub const fn item_height(mut self, item_height: f32) -> Self {
+        self.item_height = item_height;
+        self
+    }
+
+    /// How far the pointer must move in points before a press turns into a
+    /// drag.
+    ///
+    /// By default this is set to `4.0`.
+    pub const fn drag_threshold(mut self, drag_threshold: f32) -> Self {
+        self.drag_threshold = drag_threshold;
+        self
+    }
+
+    /// The distance in points from the top and bottom edges of the visible
+    /// part of the list in which dragging an item auto-scrolls the list.
+    ///
+    /// By default this is set to `24.0`.
+    pub const fn auto_scroll_margin(mut self, margin: f32) -> Self {
+        self.auto_scroll_margin = margin;
+        self
+    }
+
+    /// The maximum speed of the auto-scroll in points per second.
+    ///
+    /// By default this is set to `400.0`.
+    pub const fn auto_scroll_speed(mut self, speed: f32) -> Self {
+        self.auto_scroll_speed = speed;
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> Reorderable {
+        let ReorderableBuilder {
+            reordered_action,
+            auto_scroll_action,
+            item_count,
+            item_height,
+            drag_threshold,
+            auto_scroll_margin,
+            auto_scroll_speed,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let mut inner = ReorderInner::new(item_count, item_height);
+        inner.drag_threshold = drag_threshold;
+        inner.auto_scroll_margin = auto_scroll_margin;
+        inner.auto_scroll_speed = auto_scroll_speed;
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            inner,
+            drag_state: None,
+        }));
+
+        let el = ElementBuilder::new(ReorderableElement {
+            shared_state: Rc::clone(&shared_state),
+            reordered_action,
+            auto_scroll_action,
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(
+            ElementFlags::PAINTS
+                | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+                | ElementFlags::LISTENS_TO_POINTER_OUTSIDE_BOUNDS_WHEN_FOCUSED
+                | ElementFlags::LISTENS_TO_POSITION_CHANGE
+                | ElementFlags::LISTENS_TO_FOCUS_CHANGE,
+        )
+        .build(window_cx);
+
+        Reorderable { el, shared_state }
+    }
+}
+
+/// An element which lets the user drag the items of a list to reorder them.
+struct ReorderableElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    reordered_action: Option<Box<dyn FnMut(usize, usize) -> A>>,
+    auto_scroll_action: Option<Box<dyn FnMut(f32) -> A>>,
+}
+
+impl<A: Clone + 'static> ReorderableElement<A> {
+    fn sync_drag_state(&self, shared_state: &mut SharedState, cx: &mut ElementContext<'_, A>) {
+        let drag_state = cx
+            .visible_rect()
+            .and_then(|visible_rect| shared_state.inner.drag_state(cx.rect(), visible_rect));
+
+        if shared_state.drag_state != drag_state {
+            shared_state.drag_state = drag_state;
+            cx.request_repaint();
+        }
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for ReorderableElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        match event {
+            ElementEvent::CustomStateChanged | ElementEvent::PositionChanged => {
+                self.sync_drag_state(&mut shared_state, cx);
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position, button, ..
+            }) => {
+                let Some(visible_rect) = cx.visible_rect() else {
+                    return EventCaptureStatus::NotCaptured;
+                };
+
+                if button == PointerButton::Primary
+                    && shared_state
+                        .inner
+                        .on_pressed(position, cx.rect(), visible_rect)
+                {
+                    cx.steal_temporary_focus();
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { position, .. }) => {
+                if !shared_state.inner.on_pointer_moved(position) {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let class = cx.class();
+                if let Some(cursor_icon) = cx
+                    .res
+                    .style_system
+                    .get::<ReorderableStyle>(class)
+                    .cursor_icon
+                {
+                    cx.cursor_icon = cursor_icon;
+                }
+
+                if !cx.is_animating() {
+                    cx.set_animating(true);
+                }
+
+                self.sync_drag_state(&mut shared_state, cx);
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Animation { delta_seconds } => {
+                let delta = cx
+                    .visible_rect()
+                    .map(|visible_rect| {
+                        shared_state
+                            .inner
+                            .auto_scroll_delta(visible_rect, delta_seconds)
+                    })
+                    .unwrap_or(0.0);
+
+                if delta != 0.0 {
+                    if let Some(f) = &mut self.auto_scroll_action {
+                        cx.send_action((f)(delta));
+                    }
+                }
+
+                if !shared_state.inner.is_dragging() {
+                    cx.set_animating(false);
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustReleased { button, .. }) => {
+                if button != PointerButton::Primary || !cx.has_focus() {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let reordered = cx.visible_rect().and_then(|visible_rect| {
+                    shared_state.inner.on_released(cx.rect(), visible_rect)
+                });
+                shared_state.inner.cancel();
+
+                if let Some((from, to)) = reordered {
+                    if let Some(f) = &mut self.reordered_action {
+                        cx.send_action((f)(from, to));
+                    }
+                }
+
+                cx.release_focus();
+                self.sync_drag_state(&mut shared_state, cx);
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Focus(false) => {
+                cx.cursor_icon = CursorIcon::Default;
+                cx.set_animating(false);
+
+                shared_state.inner.cancel();
+                self.sync_drag_state(&mut shared_state, cx);
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let shared_state = RefCell::borrow(&self.shared_state);
+
+        let Some(drag_state) = shared_state.drag_state else {
+            return;
+        };
+
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<ReorderableStyle>(cx.class, cx.opacity);
+        let item_height = shared_state.inner.item_height;
+
+        if !style.dragged_item_quad.is_transparent() {
+            primitives.add(style.dragged_item_quad.create_primitive(Rect::new(
+                Point::new(0.0, drag_state.from as f32 * item_height),
+                Size::new(cx.bounds_size.width, item_height),
+            )));
+        }
+
+        if !style.gap_quad.is_transparent() && style.gap_thickness > 0.0 {
+            primitives.set_z_index(1);
+            primitives.add(style.gap_quad.create_primitive(Rect::new(
+                Point::new(
+                    drag_state.gap_rect.min_x(),
+                    drag_state.gap_rect.min_y() - (style.gap_thickness * 0.5),
+                ),
+                Size::new(drag_state.gap_rect.width(), style.gap_thickness),
+            )));
+        }
+    }
+}
+
+struct SharedState {
+    inner: ReorderInner,
+    drag_state: Option<ReorderDragState>,
+}
+
+/// A handle to a [`ReorderableElement`], which lets the user drag the items
+/// of a list to reorder them.
+///
+/// This element is placed over a vertical list of items with a uniform
+/// height, and it should belong to the same scissoring rectangle as the
+/// items. The element itself only draws the gap where the dragged item will
+/// be dropped, it is up to the application to move the items once the
+/// `on_reordered` action is received.
+///
+/// Give this element a lower z index than the items so that only presses
+/// which the items don't capture start a drag, or a higher z index to make
+/// every item a drag handle.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+pub struct Reorderable {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl Reorderable {
+    pub fn builder<A: Clone + 'static>() -> ReorderableBuilder<A> {
+        ReorderableBuilder::default()
+    }
+
+    /// Set the number of items in the list.
+    ///
+    /// Returns `true` if the number of items has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_item_count(&mut self, item_count: usize) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        if shared_state.inner.item_count != item_count {
+            shared_state.inner.item_count = item_count;
+            shared_state.inner.cancel();
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn item_count(&self) -> usize {
+        RefCell::borrow(&self.shared_state).inner.item_count
+    }
+
+    /// Set the height of each item in the list.
+    ///
+    /// Returns `true` if the height has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_item_height(&mut self, item_height: f32) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        if shared_state.inner.item_height != item_height {
+            shared_state.inner.item_height = item_height;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn item_height(&self) -> f32 {
+        RefCell::borrow(&self.shared_state).inner.item_height
+    }
+
+    /// The state of the item which is currently being dragged, if any.
+    ///
+    /// This can be used to move the other items out of the way while
+    /// dragging.
+    pub fn drag_state(&self) -> Option<ReorderDragState> {
+        RefCell::borrow(&self.shared_state).drag_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drag_item_0_to_position_2() {
+        let bounds = Rect::new(Point::new(10.0, 100.0), Size::new(200.0, 100.0));
+        let mut inner = ReorderInner::new(5, 20.0);
+
+        assert!(inner.on_pressed(Point::new(50.0, 110.0), bounds, bounds));
+        // A small movement does not start a drag.
+        assert!(!inner.on_pointer_moved(Point::new(51.0, 111.0)));
+        assert_eq!(inner.drag_state(bounds, bounds), None);
+
+        // Over the bottom half of item 2.
+        assert!(inner.on_pointer_moved(Point::new(50.0, 155.0)));
+        let drag_state = inner.drag_state(bounds, bounds).unwrap();
+        assert_eq!(drag_state.from, 0);
+        assert_eq!(drag_state.to, 2);
+        // The gap is drawn between items 2 and 3.
+        assert_eq!(drag_state.gap_rect.min_y(), 60.0);
+        assert_eq!(drag_state.gap_rect.width(), 200.0);
+
+        assert_eq!(inner.on_released(bounds, bounds), Some((0, 2)));
+        assert!(!inner.is_dragging());
+
+        // Dropping an item where it started is not a reorder.
+        assert!(inner.on_pressed(Point::new(50.0, 145.0), bounds, bounds));
+        inner.on_pointer_moved(Point::new(50.0, 152.0));
+        assert_eq!(inner.drag_state(bounds, bounds).unwrap().to, 2);
+        assert_eq!(inner.on_released(bounds, bounds), None);
+    }
+
+    #[test]
+    fn test_drag_is_clamped_to_the_scissor_rect_and_auto_scrolls() {
+        // The list is taller than the visible part of its scissor rect.
+        let bounds = Rect::new(Point::new(0.0, -40.0), Size::new(100.0, 400.0));
+        let visible_rect = Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0));
+        let mut inner = ReorderInner::new(20, 20.0);
+
+        // Items scrolled out of view can't be pressed.
+        assert!(!inner.on_pressed(Point::new(10.0, -10.0), bounds, visible_rect));
+        assert!(inner.on_pressed(Point::new(10.0, 50.0), bounds, visible_rect));
+        assert!(inner.on_pointer_moved(Point::new(10.0, 60.0)));
+        assert_eq!(inner.auto_scroll_delta(visible_rect, 1.0), 0.0);
+
+        // Dragging past the bottom edge drops into the last visible slot and
+        // scrolls down at full speed.
+        inner.on_pointer_moved(Point::new(10.0, 300.0));
+        assert_eq!(inner.drag_state(bounds, visible_rect).unwrap().to, 6);
+        assert_eq!(inner.auto_scroll_delta(visible_rect, 0.5), 200.0);
+
+        // Near the top edge it scrolls up, slower the further from the edge.
+        inner.on_pointer_moved(Point::new(10.0, 12.0));
+        let delta = inner.auto_scroll_delta(visible_rect, 0.5);
+        assert!(delta < 0.0 && delta > -200.0);
+    }
+}
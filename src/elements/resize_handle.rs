@@ -7,6 +7,9 @@ use crate::vg::quad::SolidQuadBuilder;
 
 // TODO: Make this configurable?
 const DRAG_HANDLE_WIDTH: f32 = 5.0;
+/// The priority of the resize cursor while dragging, so that it is not
+/// replaced by the cursor of an element under the pointer.
+const DRAG_CURSOR_PRIORITY: i32 = 100;
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -16,10 +19,59 @@ pub enum ResizeDirection {
     Right,
     Top,
     Bottom,
+    /// A corner handle which can be dragged along both axes.
+    ///
+    /// The span of a corner handle is laid out along the x axis, and it
+    /// follows the pointer along whichever axis it was dragged the furthest.
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ResizeDirection {
+    /// The cursor icon to show while the handle is hovered or dragged.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        match self {
+            Self::Left | Self::Right => CursorIcon::ColResize,
+            Self::Top | Self::Bottom => CursorIcon::RowResize,
+            Self::TopLeft | Self::BottomRight => CursorIcon::NwseResize,
+            Self::TopRight | Self::BottomLeft => CursorIcon::NeswResize,
+        }
+    }
+
+    /// The pointer offset along the axes this handle can be dragged along.
+    fn drag_offset(&self, offset: Vector) -> Vector {
+        match self {
+            Self::Left | Self::Right => Vector::new(offset.x, 0.0),
+            Self::Top | Self::Bottom => Vector::new(0.0, offset.y),
+            _ => offset,
+        }
+    }
+
+    /// How much the span changes when the pointer is dragged by `offset`.
+    fn span_delta(&self, offset: Vector) -> f32 {
+        match self {
+            Self::Left | Self::Right => offset.x,
+            Self::Top | Self::Bottom => offset.y,
+            Self::TopLeft | Self::TopRight | Self::BottomLeft | Self::BottomRight => {
+                if offset.y.abs() > offset.x.abs() {
+                    offset.y
+                } else {
+                    offset.x
+                }
+            }
+        }
+    }
 }
 
 /// The style of a [`ResizeHandle`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct ResizeHandleStyle {
     pub drag_handle_width: f32,
     pub drag_handle_color: RGBA8,
@@ -58,6 +110,11 @@ impl ElementStyle for ResizeHandleStyle {
     fn default_light_style() -> Self {
         Self::default()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.drag_handle_color.multiply_alpha(multiplier);
+        self.drag_handle_color_hover.multiply_alpha(multiplier);
+    }
 }
 
 /// The style of a [`ResizeHandle`] element
@@ -75,11 +132,15 @@ impl ResizeHandleLayout {
         }
 
         match direction {
-            ResizeDirection::Left => Rect::new(
-                Point::new(self.anchor.x - current_span, self.anchor.y),
-                Size::new(current_span, self.length),
-            ),
-            ResizeDirection::Right => Rect::new(self.anchor, Size::new(current_span, self.length)),
+            ResizeDirection::Left | ResizeDirection::TopLeft | ResizeDirection::BottomLeft => {
+                Rect::new(
+                    Point::new(self.anchor.x - current_span, self.anchor.y),
+                    Size::new(current_span, self.length),
+                )
+            }
+            ResizeDirection::Right | ResizeDirection::TopRight | ResizeDirection::BottomRight => {
+                Rect::new(self.anchor, Size::new(current_span, self.length))
+            }
             ResizeDirection::Top => Rect::new(
                 Point::new(self.anchor.x, self.anchor.y - current_span),
                 Size::new(self.length, current_span),
@@ -96,6 +157,8 @@ impl ResizeHandleLayout {
 pub struct ResizeHandleBuilder<A: Clone + 'static> {
     pub resized_action: Option<Box<dyn FnMut(f32) -> A>>,
     pub resize_finished_action: Option<Box<dyn FnMut(f32) -> A>>,
+    pub drag_action: Option<Box<dyn FnMut(Vector) -> A>>,
+    pub drag_limits: Option<Box<dyn FnMut() -> (Vector, Vector)>>,
     pub direction: ResizeDirection,
     pub min_span: f32,
     pub max_span: f32,
@@ -109,6 +172,8 @@ impl<A: Clone + 'static> Default for ResizeHandleBuilder<A> {
         Self {
             resized_action: None,
             resize_finished_action: None,
+            drag_action: None,
+            drag_limits: None,
             direction: ResizeDirection::default(),
             min_span: 150.0,
             max_span: 500.0,
@@ -135,6 +200,31 @@ impl<A: Clone + 'static> ResizeHandleBuilder<A> {
         self
     }
 
+    /// The action to send when the handle is dragged, with the distance the
+    /// handle moved since the previous drag action.
+    ///
+    /// This is useful for splitters between two panels which are laid out by
+    /// the application. Only the axes the handle can be dragged along (the
+    /// x axis for `Left`/`Right`, the y axis for `Top`/`Bottom`, and both
+    /// for corners) are reported.
+    pub fn on_drag<F: FnMut(Vector) -> A + 'static>(mut self, f: F) -> Self {
+        self.drag_action = Some(Box::new(f));
+        self
+    }
+
+    /// A callback returning the `(min, max)` offset the handle can be
+    /// dragged to, relative to where the drag started.
+    ///
+    /// This is called every time the pointer moves during a drag, so the
+    /// limits can depend on the current layout (i.e. to keep both panels of
+    /// a splitter above a minimum size).
+    ///
+    /// By default the drag offset is not limited.
+    pub fn drag_limits<F: FnMut() -> (Vector, Vector) + 'static>(mut self, f: F) -> Self {
+        self.drag_limits = Some(Box::new(f));
+        self
+    }
+
     pub const fn direction(mut self, direction: ResizeDirection) -> Self {
         self.direction = direction;
         self
@@ -169,6 +259,8 @@ impl<A: Clone + 'static> ResizeHandleBuilder<A> {
         let ResizeHandleBuilder {
             resized_action,
             resize_finished_action,
+            drag_action,
+            drag_limits,
             direction,
             min_span,
             max_span,
@@ -205,6 +297,8 @@ impl<A: Clone + 'static> ResizeHandleBuilder<A> {
             shared_state: Rc::clone(&shared_state),
             resized_action,
             resize_finished_action,
+            drag_action,
+            drag_limits,
             direction,
             min_span,
             max_span,
@@ -238,6 +332,8 @@ impl<A: Clone + 'static> ResizeHandleBuilder<A> {
 struct DragState {
     drag_start_pos: Point,
     drag_start_span: f32,
+    /// The total drag offset reported by the drag action so far.
+    reported_offset: Vector,
 }
 
 struct ResizeHandleElement<A: Clone + 'static> {
@@ -245,6 +341,8 @@ struct ResizeHandleElement<A: Clone + 'static> {
 
     resized_action: Option<Box<dyn FnMut(f32) -> A>>,
     resize_finished_action: Option<Box<dyn FnMut(f32) -> A>>,
+    drag_action: Option<Box<dyn FnMut(Vector) -> A>>,
+    drag_limits: Option<Box<dyn FnMut() -> (Vector, Vector)>>,
 
     direction: ResizeDirection,
     min_span: f32,
@@ -289,22 +387,32 @@ impl<A: Clone + 'static> Element<A> for ResizeHandleElement<A> {
 
                 let pointer_hovered = cx.rect().contains(position);
 
-                if pointer_hovered || self.drag_state.is_some() {
-                    cx.cursor_icon = match self.direction {
-                        ResizeDirection::Left | ResizeDirection::Right => CursorIcon::ColResize,
-                        ResizeDirection::Top | ResizeDirection::Bottom => CursorIcon::RowResize,
-                    };
+                if self.drag_state.is_some() {
+                    cx.request_cursor_icon(self.direction.cursor_icon(), DRAG_CURSOR_PRIORITY);
+                } else if pointer_hovered {
+                    cx.cursor_icon = self.direction.cursor_icon();
                 }
 
                 if let Some(drag_state) = &mut self.drag_state {
-                    let delta = match self.direction {
-                        ResizeDirection::Left | ResizeDirection::Right => {
-                            position.x - drag_state.drag_start_pos.x
-                        }
-                        ResizeDirection::Top | ResizeDirection::Bottom => {
-                            position.y - drag_state.drag_start_pos.y
+                    if let Some(f) = &mut self.drag_action {
+                        let limits = self.drag_limits.as_mut().map(|f| (f)());
+                        let offset = clamp_drag_offset(
+                            self.direction
+                                .drag_offset(position - drag_state.drag_start_pos),
+                            limits,
+                        );
+
+                        if offset != drag_state.reported_offset {
+                            let delta = offset - drag_state.reported_offset;
+                            drag_state.reported_offset = offset;
+
+                            cx.send_action((f)(delta));
                         }
-                    };
+                    }
+
+                    let delta = self
+                        .direction
+                        .span_delta(position - drag_state.drag_start_pos);
 
                     let new_span =
                         (drag_state.drag_start_span + delta).clamp(self.min_span, self.max_span);
@@ -324,7 +432,7 @@ impl<A: Clone + 'static> Element<A> for ResizeHandleElement<A> {
                         self.queued_resize_finished_span = Some(new_span);
 
                         if let Some(f) = &mut self.resized_action {
-                            cx.send_action((f)(new_span)).unwrap();
+                            cx.send_action((f)(new_span));
                         }
                     }
                 } else if pointer_hovered {
@@ -354,6 +462,7 @@ impl<A: Clone + 'static> Element<A> for ResizeHandleElement<A> {
                     self.drag_state = Some(DragState {
                         drag_start_pos: position,
                         drag_start_span: current_span,
+                        reported_offset: Vector::zero(),
                     });
 
                     cx.steal_temporary_focus();
@@ -392,12 +501,12 @@ impl<A: Clone + 'static> Element<A> for ResizeHandleElement<A> {
                             cx.request_repaint();
 
                             if let Some(f) = &mut self.resized_action {
-                                cx.send_action((f)(self.default_span)).unwrap();
+                                cx.send_action((f)(self.default_span));
                             }
 
                             self.queued_resize_finished_span = None;
                             if let Some(f) = &mut self.resize_finished_action {
-                                cx.send_action((f)(self.default_span)).unwrap();
+                                cx.send_action((f)(self.default_span));
                             }
                         }
                     }
@@ -424,7 +533,7 @@ impl<A: Clone + 'static> Element<A> for ResizeHandleElement<A> {
 
                 if let Some(span) = self.queued_resize_finished_span.take() {
                     if let Some(f) = &mut self.resize_finished_action {
-                        cx.send_action((f)(span)).unwrap();
+                        cx.send_action((f)(span));
                     }
                 }
             }
@@ -437,7 +546,10 @@ impl<A: Clone + 'static> Element<A> for ResizeHandleElement<A> {
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let bounds_rect = Rect::new(Point::zero(), cx.bounds_size);
 
-        let style = cx.res.style_system.get::<ResizeHandleStyle>(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<ResizeHandleStyle>(cx.class, cx.opacity);
 
         struct DragHandleDrawOpts {
             width: f32,
@@ -586,11 +698,25 @@ struct SharedState {
     disabled: bool,
 }
 
+/// Clamp the drag offset to the `(min, max)` limits, if any.
+fn clamp_drag_offset(offset: Vector, limits: Option<(Vector, Vector)>) -> Vector {
+    let Some((min, max)) = limits else {
+        return offset;
+    };
+
+    Vector::new(
+        offset.x.max(min.x).min(max.x.max(min.x)),
+        offset.y.max(min.y).min(max.y.max(min.y)),
+    )
+}
+
 fn calc_drag_handle_rect(bounds: Rect, direction: ResizeDirection, handle_width: f32) -> Rect {
     if bounds.size.is_empty() {
         return Rect::zero();
     }
 
+    let corner_size = Size::new(handle_width, handle_width);
+
     match direction {
         ResizeDirection::Left => Rect::new(bounds.origin, Size::new(handle_width, bounds.height())),
         ResizeDirection::Right => Rect::new(
@@ -608,5 +734,62 @@ fn calc_drag_handle_rect(bounds: Rect, direction: ResizeDirection, handle_width:
             ),
             Size::new(bounds.width(), handle_width),
         ),
+        ResizeDirection::TopLeft => Rect::new(bounds.origin, corner_size),
+        ResizeDirection::TopRight => Rect::new(
+            Point::new(bounds.max_x() - handle_width, bounds.min_y()),
+            corner_size,
+        ),
+        ResizeDirection::BottomLeft => Rect::new(
+            Point::new(bounds.min_x(), bounds.max_y() - handle_width),
+            corner_size,
+        ),
+        ResizeDirection::BottomRight => Rect::new(
+            Point::new(bounds.max_x() - handle_width, bounds.max_y() - handle_width),
+            corner_size,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drag_offset_follows_orientation_and_limits() {
+        let offset = Vector::new(30.0, -40.0);
+
+        assert_eq!(
+            ResizeDirection::Right.drag_offset(offset),
+            Vector::new(30.0, 0.0)
+        );
+        assert_eq!(
+            ResizeDirection::Top.drag_offset(offset),
+            Vector::new(0.0, -40.0)
+        );
+        assert_eq!(ResizeDirection::BottomRight.drag_offset(offset), offset);
+
+        assert_eq!(ResizeDirection::Right.span_delta(offset), 30.0);
+        assert_eq!(ResizeDirection::Bottom.span_delta(offset), -40.0);
+        // Corners follow the axis the pointer was dragged the furthest along.
+        assert_eq!(ResizeDirection::BottomRight.span_delta(offset), -40.0);
+        assert_eq!(
+            ResizeDirection::TopLeft.span_delta(Vector::new(30.0, 10.0)),
+            30.0
+        );
+
+        let limits = Some((Vector::new(-10.0, -20.0), Vector::new(20.0, 50.0)));
+        assert_eq!(clamp_drag_offset(offset, limits), Vector::new(20.0, -20.0));
+        assert_eq!(clamp_drag_offset(offset, None), offset);
+
+        assert_eq!(
+            ResizeDirection::TopRight.cursor_icon(),
+            CursorIcon::NeswResize
+        );
+
+        let bounds = Rect::new(Point::new(10.0, 10.0), Size::new(100.0, 50.0));
+        assert_eq!(
+            calc_drag_handle_rect(bounds, ResizeDirection::BottomRight, 5.0),
+            Rect::new(Point::new(105.0, 55.0), Size::new(5.0, 5.0))
+        );
     }
 }
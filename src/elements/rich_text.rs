@@ -0,0 +1,707 @@
+use std::cell::{Ref, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::prelude::*;
+use crate::vg::text::glyphon::{cosmic_text::Color, Edit};
+use crate::vg::text::{EditorBorrowStatus, RcTextBuffer, TextPrimitive};
+
+use super::label::LabelPrimitives;
+use super::paragraph::layout_text_bounds;
+
+/// The style of a [`RichText`] element
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct RichTextStyle {
+    /// The text properties.
+    ///
+    /// The attributes are used for any span which doesn't have its own
+    /// attributes.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub text_properties: TextProperties,
+
+    /// The color of any span which doesn't have its own color.
+    ///
+    /// By default this is set to `color::WHITE`.
+    pub text_color: RGBA8,
+
+    /// The vertical alignment of the text.
+    ///
+    /// By default this is set to `Align::Center`.
+    pub vertical_align: Align,
+
+    /// The style of the padded background rectangle behind the text.
+    ///
+    /// Set to `QuadStyle::TRANSPARENT` for no background rectangle.
+    ///
+    /// By default this is set to `QuadStyle::TRANSPARENT`.
+    pub back_quad: QuadStyle,
+
+    /// The padding between the text and the bounding rectangle.
+    ///
+    /// By default this has all values set to `0.0`.
+    pub padding: Padding,
+}
+
+impl Default for RichTextStyle {
+    fn default() -> Self {
+        Self {
+            text_properties: TextProperties {
+                shaping: rootvg::text::Shaping::Advanced,
+                ..Default::default()
+            },
+            text_color: color::WHITE,
+            vertical_align: Align::Center,
+            back_quad: QuadStyle::TRANSPARENT,
+            padding: Padding::default(),
+        }
+    }
+}
+
+impl ElementStyle for RichTextStyle {
+    const ID: &'static str = "rchtxt";
+
+    fn default_dark_style() -> Self {
+        Self::default()
+    }
+
+    fn default_light_style() -> Self {
+        Self {
+            text_color: color::BLACK,
+            ..Default::default()
+        }
+    }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.back_quad.multiply_alpha(multiplier);
+    }
+}
+
+/// A run of text with its own color and font attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    /// The color of the span.
+    ///
+    /// If this is `None`, then the text color from the style will be used.
+    ///
+    /// Note that a span with its own color is not faded by the opacity of
+    /// the element.
+    pub color: Option<RGBA8>,
+    /// The font attributes (family, weight, style, etc.) of the span.
+    ///
+    /// If this is `None`, then the attributes from the style will be used.
+    pub attrs: Option<Attrs<'static>>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: None,
+            attrs: None,
+        }
+    }
+
+    pub const fn color(mut self, color: RGBA8) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub const fn attrs(mut self, attrs: Attrs<'static>) -> Self {
+        self.attrs = Some(attrs);
+        self
+    }
+}
+
+/// The span under a point, returned by [`RichText::span_at`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanHit {
+    /// The index of the span.
+    pub span_index: usize,
+    /// The byte range of the glyph under the point, relative to the start
+    /// of the concatenated text of all spans.
+    pub byte_range: Range<usize>,
+}
+
+/// A reusable rich text struct that can be used by other elements.
+pub struct RichTextInner {
+    /// An offset that can be used mainly to correct the position of the text.
+    /// This does not effect the position of the background quad.
+    pub text_offset: Vector,
+    spans: Vec<TextSpan>,
+    span_ranges: Vec<Range<usize>>,
+    line_starts: Vec<usize>,
+    text_buffer: RcTextBuffer,
+    line_height: f32,
+    unclipped_text_size: Size,
+    text_size_needs_calculated: bool,
+    padded_size_needs_calculated: bool,
+    prev_bounds_size: Size,
+    text_bounds_rect: Rect,
+    padded_size: Size,
+}
+
+impl RichTextInner {
+    pub fn new(
+        spans: Vec<TextSpan>,
+        style: &RichTextStyle,
+        font_system: &mut FontSystem,
+        text_offset: Vector,
+    ) -> Self {
+        let text_buffer =
+            RcTextBuffer::new("", style.text_properties, None, None, true, font_system);
+
+        let mut new_self = Self {
+            text_offset,
+            spans,
+            span_ranges: Vec::new(),
+            line_starts: Vec::new(),
+            text_buffer,
+            line_height: style.text_properties.metrics.line_height,
+            // This will be overwritten later.
+            unclipped_text_size: Size::default(),
+            text_size_needs_calculated: true,
+            padded_size_needs_calculated: true,
+            prev_bounds_size: Size::new(-1.0, -1.0),
+            // This will be overwritten later.
+            text_bounds_rect: Rect::default(),
+            padded_size: Size::default(),
+        };
+
+        new_self.shape_spans(style, font_system);
+
+        new_self
+    }
+
+    /// Returns the size of the padded background rectangle if it were to
+    /// cover the entire size of the unclipped text.
+    ///
+    /// If the padded size needs calculated, then the given closure will be used to
+    /// extract the padding from the current style.
+    pub fn desired_size<F: FnOnce() -> Padding>(&mut self, get_padding: F) -> Size {
+        if self.padded_size_needs_calculated {
+            self.padded_size_needs_calculated = false;
+
+            let padding = (get_padding)();
+            let text_size = self.unclipped_text_size();
+
+            self.padded_size = Size::new(
+                text_size.width + padding.left + padding.right,
+                text_size.height + padding.top + padding.bottom,
+            );
+        }
+
+        self.padded_size
+    }
+
+    /// Returns the size of the unclipped text.
+    ///
+    /// This can be useful to lay out elements that depend on text size.
+    pub fn unclipped_text_size(&mut self) -> Size {
+        if self.text_size_needs_calculated {
+            self.text_size_needs_calculated = false;
+
+            self.unclipped_text_size = self.text_buffer.measure();
+        }
+
+        self.unclipped_text_size
+    }
+
+    /// Returns `true` if the spans have changed.
+    pub fn set_spans(
+        &mut self,
+        spans: Vec<TextSpan>,
+        style: &RichTextStyle,
+        font_system: &mut FontSystem,
+    ) -> bool {
+        if self.spans != spans {
+            self.spans = spans;
+            self.shape_spans(style, font_system);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    pub fn sync_new_style(&mut self, style: &RichTextStyle, font_system: &mut FontSystem) {
+        self.text_buffer
+            .set_text_and_props("", style.text_properties, font_system);
+        self.line_height = style.text_properties.metrics.line_height;
+        self.shape_spans(style, font_system);
+    }
+
+    /// Build the text buffer from the spans, with the attributes of each span
+    /// applied to its range of the text.
+    fn shape_spans(&mut self, style: &RichTextStyle, font_system: &mut FontSystem) {
+        self.span_ranges = span_ranges(&self.spans);
+
+        let text: String = self.spans.iter().map(|s| s.text.as_str()).collect();
+        self.line_starts = line_starts(&text);
+
+        let default_attrs = style.text_properties.attrs;
+        let shaping = style.text_properties.shaping;
+        let spans = &self.spans;
+
+        self.text_buffer.with_editor_mut(
+            |editor, font_system| -> EditorBorrowStatus {
+                editor.with_buffer_mut(|buffer| {
+                    buffer.set_rich_text(
+                        font_system,
+                        spans
+                            .iter()
+                            .map(|span| (span.text.as_str(), span_attrs(span, default_attrs))),
+                        default_attrs,
+                        shaping,
+                    );
+                });
+                editor.shape_as_needed(font_system, true);
+
+                EditorBorrowStatus {
+                    text_changed: true,
+                    has_text: !text.is_empty(),
+                }
+            },
+            font_system,
+        );
+
+        self.text_size_needs_calculated = true;
+        self.padded_size_needs_calculated = true;
+    }
+
+    /// Returns the span under the given point, where the point is relative
+    /// to the top-left corner of the element's bounds.
+    ///
+    /// This can be used to make spans clickable (i.e. for links).
+    pub fn span_at(&self, point: Point) -> Option<SpanHit> {
+        let point = point - self.text_bounds_rect.origin.to_vector() - self.text_offset;
+
+        for run in self.text_buffer.raw_buffer().layout_runs() {
+            if point.y < run.line_top || point.y >= run.line_top + self.line_height {
+                continue;
+            }
+
+            let line_start = self.line_starts.get(run.line_i).copied()?;
+
+            for glyph in run.glyphs.iter() {
+                if point.x >= glyph.x && point.x < glyph.x + glyph.w {
+                    let byte_range = (line_start + glyph.start)..(line_start + glyph.end);
+
+                    return span_index_at(&self.span_ranges, byte_range.start).map(|span_index| {
+                        SpanHit {
+                            span_index,
+                            byte_range,
+                        }
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&mut self, bounds: Rect, style: &RichTextStyle) -> LabelPrimitives {
+        let mut needs_layout = self.text_size_needs_calculated;
+
+        if self.prev_bounds_size != bounds.size {
+            self.prev_bounds_size = bounds.size;
+            needs_layout = true;
+        }
+
+        if needs_layout {
+            let _ = self.unclipped_text_size();
+
+            self.text_bounds_rect = layout_text_bounds(
+                bounds.size,
+                self.unclipped_text_size,
+                style.padding,
+                style.vertical_align,
+            );
+        }
+
+        let text = if !self.spans.is_empty() {
+            Some(TextPrimitive::new(
+                self.text_buffer.clone(),
+                bounds.origin + self.text_bounds_rect.origin.to_vector() + self.text_offset,
+                style.text_color,
+                Some(Rect::new(
+                    Point::new(-1.0, -1.0),
+                    Size::new(bounds.width() + 2.0, bounds.height() + 2.0),
+                )),
+            ))
+        } else {
+            None
+        };
+
+        let bg_quad = if !style.back_quad.is_transparent() {
+            Some(style.back_quad.create_primitive(bounds))
+        } else {
+            None
+        };
+
+        LabelPrimitives {
+            icon: None,
+            text,
+            bg_quad,
+        }
+    }
+}
+
+fn span_attrs(span: &TextSpan, default_attrs: Attrs<'static>) -> Attrs<'static> {
+    let attrs = span.attrs.unwrap_or(default_attrs);
+
+    if let Some(c) = span.color {
+        attrs.color(Color::rgba(c.r, c.g, c.b, c.a))
+    } else {
+        attrs
+    }
+}
+
+/// The byte range of each span in the concatenated text of all spans.
+fn span_ranges(spans: &[TextSpan]) -> Vec<Range<usize>> {
+    let mut start = 0;
+    spans
+        .iter()
+        .map(|span| {
+            let range = start..start + span.text.len();
+            start = range.end;
+            range
+        })
+        .collect()
+}
+
+/// The byte index of the start of each line in the text.
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+fn span_index_at(span_ranges: &[Range<usize>], byte_index: usize) -> Option<usize> {
+    span_ranges.iter().position(|r| r.contains(&byte_index))
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+pub struct RichTextBuilder<A: Clone + 'static> {
+    pub span_clicked_action: Option<Box<dyn FnMut(SpanHit) -> A>>,
+    pub spans: Vec<TextSpan>,
+    pub text_offset: Vector,
+}
+
+impl<A: Clone + 'static> Default for RichTextBuilder<A> {
+    fn default() -> Self {
+        Self {
+            span_clicked_action: None,
+            spans: Vec::new(),
+            text_offset: Vector::default(),
+            class: None,
+            z_index: None,
+            rect: Rect::default(),
+            manually_hidden: false,
+            scissor_rect: None,
+        }
+    }
+}
+
+impl<A: Clone + 'static> RichTextBuilder<A> {
+    /// The spans of text, in order.
+    pub fn spans(mut self, spans: Vec<TextSpan>) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// The action to send when a span is clicked with the primary pointer
+    /// button (i.e. to open a link).
+    pub fn on_span_clicked<F: FnMut(SpanHit) -> A + 'static>(mut self, f: F) -> Self {
+        self.span_clicked_action = Some(Box::new(f));
+        self
+    }
+
+    /// An offset that can be used mainly to correct the position of the text.
+    /// This does not effect the position of the background quad.
+    ///
+    /// By default this is set to an offset of zero.
+    pub const fn text_offset(mut self, offset: Vector) -> Self {
+        self.text_offset = offset;
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> RichText {
+        let RichTextBuilder {
+            span_clicked_action,
+            spans,
+            text_offset,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let style = window_cx
+            .res
+            .style_system
+            .get(window_cx.builder_class(class));
+
+        let shared_state = Rc::new(RefCell::new(SharedState {
+            inner: RichTextInner::new(spans, &style, &mut window_cx.res.font_system, text_offset),
+        }));
+
+        let flags = if span_clicked_action.is_some() {
+            ElementFlags::PAINTS | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+        } else {
+            ElementFlags::PAINTS
+        };
+
+        let el = ElementBuilder::new(RichTextElement {
+            shared_state: Rc::clone(&shared_state),
+            span_clicked_action,
+        })
+        .builder_values(z_index, scissor_rect, class, window_cx)
+        .rect(rect)
+        .hidden(manually_hidden)
+        .flags(flags)
+        .build(window_cx);
+
+        RichText { el, shared_state }
+    }
+}
+
+/// A single block of text made up of spans with different colors and font
+/// attributes.
+struct RichTextElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    span_clicked_action: Option<Box<dyn FnMut(SpanHit) -> A>>,
+}
+
+impl<A: Clone + 'static> Element<A> for RichTextElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        match event {
+            ElementEvent::CustomStateChanged => {
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<RichTextStyle>(cx.class());
+                RefCell::borrow_mut(&self.shared_state)
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position, button, ..
+            }) => {
+                if button != PointerButton::Primary {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let hit = RefCell::borrow(&self.shared_state)
+                    .inner
+                    .span_at(position - cx.rect().origin.to_vector());
+
+                if let (Some(hit), Some(f)) = (hit, &mut self.span_clicked_action) {
+                    cx.send_action((f)(hit));
+                    return EventCaptureStatus::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        let text_primitives = shared_state.inner.render(
+            Rect::from_size(cx.bounds_size),
+            &cx.res
+                .style_system
+                .get_with_opacity::<RichTextStyle>(cx.class, cx.opacity),
+        );
+
+        if let Some(quad_primitive) = text_primitives.bg_quad {
+            primitives.add(quad_primitive);
+        }
+
+        if let Some(text_primitive) = text_primitives.text {
+            primitives.set_z_index(1);
+            primitives.add_text(text_primitive);
+        }
+    }
+}
+
+struct SharedState {
+    inner: RichTextInner,
+}
+
+/// A handle to a [`RichTextElement`], a single block of text made up of
+/// spans with different colors and font attributes.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+pub struct RichText {
+    shared_state: Rc<RefCell<SharedState>>,
+}
+
+impl RichText {
+    pub fn builder<A: Clone + 'static>() -> RichTextBuilder<A> {
+        RichTextBuilder::default()
+    }
+
+    /// Returns the size of the padded background rectangle if it were to
+    /// cover the entire size of the unclipped text.
+    ///
+    /// This size is automatically cached, so it should be relatively
+    /// inexpensive to call.
+    pub fn desired_size(&self, res: &mut ResourceCtx) -> Size {
+        RefCell::borrow_mut(&self.shared_state)
+            .inner
+            .desired_size(|| {
+                res.style_system
+                    .get::<RichTextStyle>(self.el.class())
+                    .padding
+            })
+    }
+
+    /// Set the spans of text.
+    ///
+    /// Returns `true` if the spans have changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed.
+    /// However, this method still involves comparing every span, so you may
+    /// want to call this method sparingly.
+    pub fn set_spans(&mut self, spans: Vec<TextSpan>, res: &mut ResourceCtx) -> bool {
+        let changed = RefCell::borrow_mut(&self.shared_state).inner.set_spans(
+            spans,
+            res.style_system.get(self.el.class()),
+            &mut res.font_system,
+        );
+
+        if changed {
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn spans<'a>(&'a self) -> Ref<'a, [TextSpan]> {
+        Ref::map(RefCell::borrow(&self.shared_state), |s| s.inner.spans())
+    }
+
+    /// Returns the span under the given point in window coordinates, or
+    /// `None` if there is no text under the point.
+    ///
+    /// Note, the result is only accurate once the element has been rendered
+    /// with its current rectangle.
+    pub fn span_at(&self, point: Point) -> Option<SpanHit> {
+        RefCell::borrow(&self.shared_state)
+            .inner
+            .span_at(point - self.el.rect().origin.to_vector())
+    }
+
+    /// Layout out the element (with the top-left corner of the bounds set to `origin`).
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout(&mut self, origin: Point, res: &mut ResourceCtx) -> bool {
+        let size = self.desired_size(res);
+        self.el.set_rect(Rect::new(origin, size))
+    }
+
+    /// Layout out the element aligned to the given point.
+    ///
+    /// Returns `true` if the layout has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout_aligned(&mut self, point: Point, align: Align2, res: &mut ResourceCtx) -> bool {
+        let size = self.desired_size(res);
+        self.el.set_rect(align.align_rect_to_point(point, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_byte_ranges() {
+        let spans = vec![
+            TextSpan::new("Find "),
+            TextSpan::new("match").color(color::BLACK),
+            TextSpan::new(""),
+            TextSpan::new("\u{e9}s\nnext"),
+        ];
+
+        let ranges = span_ranges(&spans);
+        assert_eq!(ranges, vec![0..5, 5..10, 10..10, 10..18]);
+
+        assert_eq!(span_index_at(&ranges, 0), Some(0));
+        assert_eq!(span_index_at(&ranges, 7), Some(1));
+        // Empty spans are never hit.
+        assert_eq!(span_index_at(&ranges, 10), Some(3));
+        assert_eq!(span_index_at(&ranges, 18), None);
+
+        let text: String = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(line_starts(&text), vec![0, 14]);
+    }
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_span_at_reports_the_span_under_a_point() {
+        let mut font_system = FontSystem::new();
+        let style = RichTextStyle::default();
+
+        let mut inner = RichTextInner::new(
+            vec![
+                TextSpan::new("aaaa"),
+                TextSpan::new("bbbb").color(color::BLACK),
+            ],
+            &style,
+            &mut font_system,
+            Vector::zero(),
+        );
+
+        let size = inner.unclipped_text_size();
+        assert!(!size.is_empty());
+
+        let _ = inner.render(Rect::from_size(size), &style);
+
+        let y = size.height * 0.5;
+        let first = inner.span_at(Point::new(1.0, y)).unwrap();
+        assert_eq!(first.span_index, 0);
+        assert_eq!(first.byte_range, 0..1);
+
+        let last = inner.span_at(Point::new(size.width - 1.0, y)).unwrap();
+        assert_eq!(last.span_index, 1);
+        assert_eq!(last.byte_range, 7..8);
+
+        assert_eq!(inner.span_at(Point::new(size.width + 5.0, y)), None);
+        assert_eq!(inner.span_at(Point::new(1.0, size.height + 5.0)), None);
+    }
+}
@@ -6,6 +6,11 @@ use crate::prelude::*;
 
 /// The style of a scroll bar in a [`ScrollArea`] element.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct ScrollBarStyle {
     pub back_quad_bg: Background,
     pub back_quad_bg_content_hover: Option<Background>,
@@ -84,6 +89,88 @@ impl ElementStyle for ScrollBarStyle {
     fn default_light_style() -> Self {
         Self::default()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.back_quad_bg.multiply_alpha(multiplier);
+        self.back_quad_bg_content_hover.multiply_alpha(multiplier);
+        self.back_quad_bg_slider_hover.multiply_alpha(multiplier);
+        self.back_quad_border_color.multiply_alpha(multiplier);
+        self.back_quad_border_color_content_hover
+            .multiply_alpha(multiplier);
+        self.back_quad_border_color_slider_hover
+            .multiply_alpha(multiplier);
+        self.slider_bg.multiply_alpha(multiplier);
+        self.slider_bg_content_hover.multiply_alpha(multiplier);
+        self.slider_bg_slider_hover.multiply_alpha(multiplier);
+        self.slider_bg_slider_dragging.multiply_alpha(multiplier);
+        self.slider_border_color.multiply_alpha(multiplier);
+        self.slider_border_color_content_hover
+            .multiply_alpha(multiplier);
+        self.slider_border_color_slider_hover
+            .multiply_alpha(multiplier);
+        self.slider_border_color_slider_dragging
+            .multiply_alpha(multiplier);
+    }
+}
+
+/// The configuration of the elastic overscroll behavior in a [`ScrollArea`]
+/// element.
+///
+/// When enabled, scrolling past the edge of the content with the scroll
+/// wheel will offset the content by a small amount, which then springs back
+/// to the edge over the next few animation frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElasticOverscroll {
+    /// The maximum distance (in points) the content can be scrolled past
+    /// its edge.
+    ///
+    /// By default this is set to `48.0`.
+    pub max_overscroll: f32,
+    /// How much of the scroll wheel delta past the edge is applied to the
+    /// overscroll offset, in the range `(0.0, 1.0]`.
+    ///
+    /// By default this is set to `0.5`.
+    pub resistance: f32,
+    /// The rate at which the overscroll offset springs back to zero, in
+    /// units of "1/seconds". Higher values spring back faster.
+    ///
+    /// By default this is set to `12.0`.
+    pub spring_back_rate: f32,
+}
+
+impl Default for ElasticOverscroll {
+    fn default() -> Self {
+        Self {
+            max_overscroll: 48.0,
+            resistance: 0.5,
+            spring_back_rate: 12.0,
+        }
+    }
+}
+
+impl ElasticOverscroll {
+    /// If the overscroll offset falls below this magnitude (in points), then
+    /// it will snap to zero.
+    const SETTLE_THRESHOLD: f32 = 0.1;
+
+    /// Apply the amount of scroll delta that went past the edge of the
+    /// content to the given overscroll offset along a single axis.
+    fn apply(&self, overscroll: f32, excess: f32) -> f32 {
+        (overscroll + (excess * self.resistance)).clamp(-self.max_overscroll, self.max_overscroll)
+    }
+
+    /// Spring the given overscroll offset along a single axis back towards
+    /// zero.
+    fn spring_back(&self, overscroll: f32, delta_seconds: f64) -> f32 {
+        let decay = (-(self.spring_back_rate as f64) * delta_seconds).exp() as f32;
+        let new_overscroll = overscroll * decay;
+
+        if new_overscroll.abs() < Self::SETTLE_THRESHOLD {
+            0.0
+        } else {
+            new_overscroll
+        }
+    }
 }
 
 #[element_builder]
@@ -102,6 +189,7 @@ pub struct ScrollAreaBuilder<A: Clone + 'static> {
     pub show_slider_when_content_fits: bool,
     pub capture_scroll_wheel: bool,
     pub points_per_line: f32,
+    pub elastic_overscroll: Option<ElasticOverscroll>,
 }
 
 impl<A: Clone + 'static> Default for ScrollAreaBuilder<A> {
@@ -117,6 +205,7 @@ impl<A: Clone + 'static> Default for ScrollAreaBuilder<A> {
             show_slider_when_content_fits: false,
             capture_scroll_wheel: true,
             points_per_line: 24.0,
+            elastic_overscroll: None,
             class: None,
             z_index: None,
             rect: Rect::default(),
@@ -182,6 +271,18 @@ impl<A: Clone + 'static> ScrollAreaBuilder<A> {
         self
     }
 
+    /// Allow the content to be scrolled slightly past its edges with the
+    /// scroll wheel, springing back once the user stops scrolling.
+    ///
+    /// By default this is set to `None` (disabled).
+    pub const fn elastic_overscroll(
+        mut self,
+        elastic_overscroll: Option<ElasticOverscroll>,
+    ) -> Self {
+        self.elastic_overscroll = elastic_overscroll;
+        self
+    }
+
     pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> ScrollArea {
         let ScrollAreaBuilder {
             scrolled_action,
@@ -195,6 +296,7 @@ impl<A: Clone + 'static> ScrollAreaBuilder<A> {
             show_slider_when_content_fits,
             capture_scroll_wheel,
             points_per_line,
+            elastic_overscroll,
 
             class,
             z_index,
@@ -223,6 +325,7 @@ impl<A: Clone + 'static> ScrollAreaBuilder<A> {
         let shared_state = Rc::new(RefCell::new(SharedState {
             content_size,
             scroll_offset: res.scroll_offset,
+            overscroll: Vector::zero(),
             disabled,
         }));
 
@@ -246,6 +349,7 @@ impl<A: Clone + 'static> ScrollAreaBuilder<A> {
             show_slider_when_content_fits,
             capture_scroll_wheel,
             points_per_line,
+            elastic_overscroll,
             vertical_state: ScrollBarState::Idle,
             horizontal_state: ScrollBarState::Idle,
             sliders_state: res,
@@ -288,6 +392,7 @@ struct ScrollAreaElement<A: Clone + 'static> {
     show_slider_when_content_fits: bool,
     capture_scroll_wheel: bool,
     points_per_line: f32,
+    elastic_overscroll: Option<ElasticOverscroll>,
 
     vertical_state: ScrollBarState,
     horizontal_state: ScrollBarState,
@@ -312,7 +417,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     cx.update_scissor_rect(
                         scissor_rect,
                         Some(cx.rect()),
-                        Some(shared_state.scroll_offset),
+                        Some(shared_state.content_offset()),
                     );
                 }
             }
@@ -336,7 +441,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                 }
 
                 if let Some(scissor_rect) = self.control_scissor_rect {
-                    cx.update_scissor_rect(scissor_rect, None, Some(shared_state.scroll_offset));
+                    cx.update_scissor_rect(scissor_rect, None, Some(shared_state.content_offset()));
                 }
             }
             ElementEvent::PositionChanged => {
@@ -344,7 +449,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     cx.update_scissor_rect(
                         scissor_rect,
                         Some(cx.rect()),
-                        Some(shared_state.scroll_offset),
+                        Some(shared_state.content_offset()),
                     );
                 }
             }
@@ -371,8 +476,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     shared_state.scroll_offset = self.sliders_state.scroll_offset;
 
                     if let Some(action) = self.scrolled_action.as_mut() {
-                        cx.send_action((action)(shared_state.scroll_offset))
-                            .unwrap();
+                        cx.send_action((action)(shared_state.scroll_offset));
                     }
                 }
 
@@ -380,7 +484,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     cx.update_scissor_rect(
                         scissor_rect,
                         Some(cx.rect()),
-                        Some(shared_state.scroll_offset),
+                        Some(shared_state.content_offset()),
                     );
                 }
             }
@@ -436,8 +540,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                         );
 
                         if let Some(action) = self.scrolled_action.as_mut() {
-                            cx.send_action((action)(shared_state.scroll_offset))
-                                .unwrap();
+                            cx.send_action((action)(shared_state.scroll_offset));
                         }
 
                         cx.request_repaint();
@@ -446,7 +549,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                             cx.update_scissor_rect(
                                 scissor_rect,
                                 None,
-                                Some(shared_state.scroll_offset),
+                                Some(shared_state.content_offset()),
                             );
                         }
                     }
@@ -563,8 +666,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                             );
 
                             if let Some(action) = self.scrolled_action.as_mut() {
-                                cx.send_action((action)(shared_state.scroll_offset))
-                                    .unwrap();
+                                cx.send_action((action)(shared_state.scroll_offset));
                             }
 
                             cx.request_repaint();
@@ -573,7 +675,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                                 cx.update_scissor_rect(
                                     scissor_rect,
                                     None,
-                                    Some(shared_state.scroll_offset),
+                                    Some(shared_state.content_offset()),
                                 );
                             }
                         }
@@ -624,8 +726,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                             );
 
                             if let Some(action) = self.scrolled_action.as_mut() {
-                                cx.send_action((action)(shared_state.scroll_offset))
-                                    .unwrap();
+                                cx.send_action((action)(shared_state.scroll_offset));
                             }
 
                             cx.request_repaint();
@@ -634,7 +735,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                                 cx.update_scissor_rect(
                                     scissor_rect,
                                     None,
-                                    Some(shared_state.scroll_offset),
+                                    Some(shared_state.content_offset()),
                                 );
                             }
                         }
@@ -707,6 +808,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
             ElementEvent::Pointer(PointerEvent::ScrollWheel {
                 position,
                 delta_type,
+                modifiers,
                 ..
             }) => {
                 if shared_state.disabled
@@ -716,15 +818,52 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     return EventCaptureStatus::NotCaptured;
                 }
 
-                let delta = delta_type.points(self.points_per_line, cx.rect().height());
+                let mut delta = delta_type
+                    .shift_to_horizontal(modifiers)
+                    .points(self.points_per_line, cx.rect().height());
+
+                // Vertical scroll wheels are the most common, so use them to
+                // scroll areas which only scroll horizontally as well.
+                if self.scroll_horizontally && !self.scroll_vertically && delta.x == 0.0 {
+                    delta = Vector::new(delta.y, 0.0);
+                }
 
+                let unclamped_scroll_offset = self.sliders_state.scroll_offset + delta;
                 let new_scroll_offset = Vector::new(
-                    (self.sliders_state.scroll_offset.x + (delta.x))
+                    unclamped_scroll_offset
+                        .x
                         .clamp(0.0, self.sliders_state.max_scroll_offset.x),
-                    (self.sliders_state.scroll_offset.y + (delta.y))
+                    unclamped_scroll_offset
+                        .y
                         .clamp(0.0, self.sliders_state.max_scroll_offset.y),
                 );
 
+                if let Some(elastic_overscroll) = &self.elastic_overscroll {
+                    let excess = unclamped_scroll_offset - new_scroll_offset;
+                    let mut new_overscroll = shared_state.overscroll;
+
+                    if self.scroll_horizontally {
+                        new_overscroll.x = elastic_overscroll.apply(new_overscroll.x, excess.x);
+                    }
+                    if self.scroll_vertically {
+                        new_overscroll.y = elastic_overscroll.apply(new_overscroll.y, excess.y);
+                    }
+
+                    if shared_state.overscroll != new_overscroll {
+                        shared_state.overscroll = new_overscroll;
+
+                        cx.set_animating(true);
+
+                        if let Some(scissor_rect) = self.control_scissor_rect {
+                            cx.update_scissor_rect(
+                                scissor_rect,
+                                None,
+                                Some(shared_state.content_offset()),
+                            );
+                        }
+                    }
+                }
+
                 if self.sliders_state.scroll_offset != new_scroll_offset {
                     self.sliders_state.scroll_offset = new_scroll_offset;
                     shared_state.scroll_offset = self.sliders_state.scroll_offset;
@@ -740,8 +879,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     );
 
                     if let Some(action) = self.scrolled_action.as_mut() {
-                        cx.send_action((action)(shared_state.scroll_offset))
-                            .unwrap();
+                        cx.send_action((action)(shared_state.scroll_offset));
                     }
 
                     cx.request_repaint();
@@ -750,7 +888,7 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                         cx.update_scissor_rect(
                             scissor_rect,
                             None,
-                            Some(shared_state.scroll_offset),
+                            Some(shared_state.content_offset()),
                         );
                     }
                 }
@@ -759,6 +897,25 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
                     return EventCaptureStatus::Captured;
                 }
             }
+            ElementEvent::Animation { delta_seconds } => {
+                let Some(elastic_overscroll) = &self.elastic_overscroll else {
+                    cx.set_animating(false);
+                    return EventCaptureStatus::NotCaptured;
+                };
+
+                shared_state.overscroll = Vector::new(
+                    elastic_overscroll.spring_back(shared_state.overscroll.x, delta_seconds),
+                    elastic_overscroll.spring_back(shared_state.overscroll.y, delta_seconds),
+                );
+
+                if shared_state.overscroll == Vector::zero() {
+                    cx.set_animating(false);
+                }
+
+                if let Some(scissor_rect) = self.control_scissor_rect {
+                    cx.update_scissor_rect(scissor_rect, None, Some(shared_state.content_offset()));
+                }
+            }
             ElementEvent::Focus(false) => {
                 self.drag_state = None;
             }
@@ -769,7 +926,10 @@ impl<A: Clone + 'static> Element<A> for ScrollAreaElement<A> {
     }
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
-        let style = cx.res.style_system.get::<ScrollBarStyle>(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<ScrollBarStyle>(cx.class, cx.opacity);
 
         let bg_style = |state| -> QuadStyle {
             match state {
@@ -936,9 +1096,17 @@ enum ScrollBarState {
 struct SharedState {
     content_size: Size,
     scroll_offset: Vector,
+    overscroll: Vector,
     disabled: bool,
 }
 
+impl SharedState {
+    /// The offset of the content, including any elastic overscroll.
+    fn content_offset(&self) -> Vector {
+        self.scroll_offset + self.overscroll
+    }
+}
+
 #[element_handle]
 #[element_handle_class]
 #[element_handle_set_rect]
@@ -974,6 +1142,13 @@ impl ScrollArea {
         RefCell::borrow(&self.shared_state).scroll_offset
     }
 
+    /// The current elastic overscroll offset.
+    ///
+    /// This is always zero if elastic overscroll is disabled.
+    pub fn overscroll(&self) -> Vector {
+        RefCell::borrow(&self.shared_state).overscroll
+    }
+
     /// Set the content size.
     ///
     /// Returns `true` if the content size has changed.
@@ -1140,3 +1315,37 @@ fn update_sliders_state(
         slider_to_content_ratio: Vector::new(slider_to_content_ratio_x, slider_to_content_ratio_y),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elastic_overscroll_springs_back() {
+        let elastic_overscroll = ElasticOverscroll::default();
+
+        // Scroll far past the end of the content.
+        let mut overscroll = 0.0;
+        for _ in 0..20 {
+            overscroll = elastic_overscroll.apply(overscroll, 30.0);
+            assert!(overscroll > 0.0);
+            assert!(overscroll <= elastic_overscroll.max_overscroll);
+        }
+        assert_eq!(overscroll, elastic_overscroll.max_overscroll);
+
+        // Scrolling past the start overscrolls in the other direction.
+        assert!(elastic_overscroll.apply(0.0, -30.0) < 0.0);
+
+        // The overscroll decays back to zero over subsequent frames.
+        let mut frames = 0;
+        while overscroll != 0.0 {
+            let prev_overscroll = overscroll;
+            overscroll = elastic_overscroll.spring_back(overscroll, 1.0 / 60.0);
+            assert!(overscroll < prev_overscroll);
+            assert!(overscroll >= 0.0);
+
+            frames += 1;
+            assert!(frames < 120, "overscroll did not settle within two seconds");
+        }
+    }
+}
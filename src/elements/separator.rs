@@ -1,4 +1,5 @@
 use crate::derive::*;
+use crate::math::ScaleFactor;
 use crate::prelude::*;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -31,11 +32,52 @@ impl Default for SeparatorSizeType {
     }
 }
 
+/// The thickness of the line of a [`Separator`], perpendicular to its length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SeparatorThickness {
+    /// The line fills the bounds of the element.
+    Fill,
+    /// A fixed thickness in logical points.
+    Points(f32),
+    /// A fixed thickness in physical pixels, so the line stays equally crisp
+    /// at any scale factor.
+    PhysicalPixels(f32),
+}
+
+impl SeparatorThickness {
+    pub fn points(&self, span_points: f32, scale_factor: ScaleFactor) -> f32 {
+        match self {
+            Self::Fill => span_points,
+            Self::Points(p) => p.min(span_points),
+            Self::PhysicalPixels(px) => (*px / scale_factor.0).min(span_points),
+        }
+    }
+}
+
+impl Default for SeparatorThickness {
+    fn default() -> Self {
+        Self::Fill
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct SeparatorStyle {
     pub quad_style: QuadStyle,
+    /// The length of the line.
     pub size: SeparatorSizeType,
+    /// The alignment of the line along its length.
     pub align: Align,
+    /// The thickness of the line. The line is centered in the bounds of the
+    /// element.
+    ///
+    /// By default this is set to `SeparatorThickness::Fill`.
+    pub thickness: SeparatorThickness,
 }
 
 impl Default for SeparatorStyle {
@@ -48,6 +90,34 @@ impl Default for SeparatorStyle {
             },
             size: SeparatorSizeType::default(),
             align: Align::Center,
+            thickness: SeparatorThickness::default(),
+        }
+    }
+}
+
+impl SeparatorStyle {
+    /// The rectangle of the line relative to the bounds of the element.
+    pub fn line_rect(&self, bounds_size: Size, vertical: bool, scale_factor: ScaleFactor) -> Rect {
+        let (length, cross) = if vertical {
+            (bounds_size.height, bounds_size.width)
+        } else {
+            (bounds_size.width, bounds_size.height)
+        };
+
+        let span = self.size.points(length);
+        let start = match self.align {
+            Align::Start => 0.0,
+            Align::Center => (length - span) * 0.5,
+            Align::End => length - span,
+        };
+
+        let thickness = self.thickness.points(cross, scale_factor);
+        let cross_start = (cross - thickness) * 0.5;
+
+        if vertical {
+            Rect::new(Point::new(cross_start, start), Size::new(thickness, span))
+        } else {
+            Rect::new(Point::new(start, cross_start), Size::new(span, thickness))
         }
     }
 }
@@ -62,6 +132,10 @@ impl ElementStyle for SeparatorStyle {
     fn default_light_style() -> Self {
         Self::default()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.quad_style.multiply_alpha(multiplier);
+    }
 }
 
 #[element_builder]
@@ -79,6 +153,14 @@ impl SeparatorBuilder {
         self
     }
 
+    /// The direction of the line.
+    ///
+    /// By default this is set to `LayoutDirection::Horizontal`.
+    pub const fn orientation(mut self, orientation: LayoutDirection) -> Self {
+        self.vertical = matches!(orientation, LayoutDirection::Vertical);
+        self
+    }
+
     pub fn build<A: Clone + 'static>(self, window_cx: &mut WindowContext<'_, A>) -> Separator {
         let SeparatorBuilder {
             class,
@@ -119,29 +201,12 @@ impl<A: Clone + 'static> Element<A> for SeparatorElement {
     }
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
-        let style = cx.res.style_system.get::<SeparatorStyle>(cx.class);
-
-        let rect = if self.vertical {
-            let span = style.size.points(cx.bounds_size.height);
-
-            let y = match style.align {
-                Align::Start => 0.0,
-                Align::Center => (cx.bounds_size.height - span) * 0.5,
-                Align::End => cx.bounds_size.height - span,
-            };
-
-            Rect::new(Point::new(0.0, y), Size::new(cx.bounds_size.width, span))
-        } else {
-            let span = style.size.points(cx.bounds_size.width);
-
-            let x = match style.align {
-                Align::Start => 0.0,
-                Align::Center => (cx.bounds_size.width - span) * 0.5,
-                Align::End => cx.bounds_size.width - span,
-            };
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<SeparatorStyle>(cx.class, cx.opacity);
 
-            Rect::new(Point::new(x, 0.0), Size::new(span, cx.bounds_size.height))
-        };
+        let rect = style.line_rect(cx.bounds_size, self.vertical, cx.scale);
 
         primitives.add(style.quad_style.create_primitive(rect));
     }
@@ -159,3 +224,34 @@ impl Separator {
         SeparatorBuilder::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separator_line_is_centered_and_one_physical_pixel_thick() {
+        let style = SeparatorStyle {
+            thickness: SeparatorThickness::PhysicalPixels(1.0),
+            ..Default::default()
+        };
+        let bounds_size = Size::new(100.0, 9.0);
+
+        let rect = style.line_rect(bounds_size, false, 1.0f64.into());
+        assert_eq!(rect, Rect::new(Point::new(0.0, 4.0), Size::new(100.0, 1.0)));
+
+        let rect = style.line_rect(bounds_size, false, 2.0f64.into());
+        assert_eq!(
+            rect,
+            Rect::new(Point::new(0.0, 4.25), Size::new(100.0, 0.5))
+        );
+
+        let rect = style.line_rect(Size::new(9.0, 100.0), true, 1.0f64.into());
+        assert_eq!(rect, Rect::new(Point::new(4.0, 0.0), Size::new(1.0, 100.0)));
+
+        // By default the line fills the bounds of the element.
+        let style = SeparatorStyle::default();
+        let rect = style.line_rect(bounds_size, false, 2.0f64.into());
+        assert_eq!(rect, Rect::from_size(bounds_size));
+    }
+}
@@ -0,0 +1,42 @@
+//! [`SizableElement`] implementations for the built-in elements whose size
+//! does not depend on the space available to them.
+
+use crate::elements::button::Button;
+use crate::elements::checkbox::Checkbox;
+use crate::elements::drop_down::DropDown;
+#[cfg(feature = "svg-icons")]
+use crate::elements::icon::Icon;
+use crate::elements::label::Label;
+use crate::elements::radio_button::RadioButton;
+use crate::elements::rich_text::RichText;
+use crate::elements::switch::Switch;
+use crate::elements::tab::Tab;
+use crate::elements::toggle_button::ToggleButton;
+use crate::math::Size;
+use crate::prelude::{ResourceCtx, SizableElement};
+
+macro_rules! impl_sizable_element {
+    ($($handle:ty),*) => {
+        $(
+            impl SizableElement for $handle {
+                fn measure(&self, _available: Size, res: &mut ResourceCtx) -> Size {
+                    self.desired_size(res)
+                }
+            }
+        )*
+    };
+}
+
+impl_sizable_element!(
+    Label,
+    Button,
+    ToggleButton,
+    Checkbox,
+    Switch,
+    RadioButton,
+    DropDown,
+    Tab,
+    RichText
+);
+#[cfg(feature = "svg-icons")]
+impl_sizable_element!(Icon);
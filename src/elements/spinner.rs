@@ -0,0 +1,248 @@
+use std::f32::consts::TAU;
+
+use rootvg::PrimitiveGroup;
+
+use crate::derive::*;
+use crate::prelude::*;
+
+/// The style of a [`Spinner`] element.
+///
+/// The spinner is drawn as a ring of dots, with a bright dot travelling
+/// around the ring followed by a fading tail.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SpinnerStyle {
+    pub dot_color: RGBA8,
+
+    /// The number of dots in the ring.
+    ///
+    /// By default this is set to `8`.
+    pub num_dots: u16,
+
+    /// The radius of each dot in logical points.
+    ///
+    /// By default this is set to `2.5`.
+    pub dot_radius: f32,
+
+    /// The distance from the center of the spinner to the center of each
+    /// dot in logical points.
+    ///
+    /// By default this is set to `9.0`.
+    pub ring_radius: f32,
+
+    /// The number of times the bright dot travels around the ring per second.
+    ///
+    /// By default this is set to `1.0`.
+    pub revolutions_per_second: f32,
+
+    /// The alpha multiplier of the dots at the end of the tail.
+    ///
+    /// By default this is set to `0.15`.
+    pub min_alpha_multiplier: f32,
+}
+
+impl Default for SpinnerStyle {
+    fn default() -> Self {
+        Self {
+            dot_color: RGBA8::new(235, 235, 235, 255),
+            num_dots: 8,
+            dot_radius: 2.5,
+            ring_radius: 9.0,
+            revolutions_per_second: 1.0,
+            min_alpha_multiplier: 0.15,
+        }
+    }
+}
+
+impl ElementStyle for SpinnerStyle {
+    const ID: &'static str = "spnr";
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.dot_color.multiply_alpha(multiplier);
+    }
+}
+
+impl SpinnerStyle {
+    /// The total size of the spinner in logical points.
+    pub fn size(&self) -> Size {
+        let span = (self.ring_radius + self.dot_radius) * 2.0;
+        Size::new(span, span)
+    }
+
+    /// Add the dots of the spinner to the given primitive group.
+    ///
+    /// * `center` - The center of the spinner.
+    /// * `phase` - How far along the ring the bright dot is, in the range
+    /// `[0.0, 1.0)`.
+    pub fn add_primitives(&self, center: Point, phase: f32, primitives: &mut PrimitiveGroup) {
+        if self.num_dots == 0 {
+            return;
+        }
+
+        let dot_size = Size::new(self.dot_radius * 2.0, self.dot_radius * 2.0);
+
+        for i in 0..self.num_dots {
+            let t = f32::from(i) / f32::from(self.num_dots);
+            let angle = t * TAU - TAU * 0.25;
+
+            let dot_center = Point::new(
+                center.x + angle.cos() * self.ring_radius,
+                center.y + angle.sin() * self.ring_radius,
+            );
+
+            let style = QuadStyle {
+                bg: Background::Solid(color::multiply_alpha(
+                    self.dot_color,
+                    self.dot_alpha_multiplier(t, phase),
+                )),
+                border: BorderStyle {
+                    radius: radius(self.dot_radius),
+                    ..BorderStyle::TRANSPARENT
+                },
+                flags: QuadFlags::empty(),
+            };
+
+            primitives.add(style.create_primitive(Rect::new(
+                Point::new(
+                    dot_center.x - self.dot_radius,
+                    dot_center.y - self.dot_radius,
+                ),
+                dot_size,
+            )));
+        }
+    }
+
+    /// The alpha multiplier of the dot at position `t` around the ring.
+    ///
+    /// The dot at the current phase is fully opaque, and the dots behind it
+    /// fade out linearly.
+    fn dot_alpha_multiplier(&self, t: f32, phase: f32) -> f32 {
+        let behind = (phase - t).rem_euclid(1.0);
+        let min = self.min_alpha_multiplier.clamp(0.0, 1.0);
+
+        1.0 - (1.0 - min) * behind
+    }
+
+    /// Advance the given phase by the given amount of time.
+    pub fn advance_phase(&self, phase: f32, delta_seconds: f64) -> f32 {
+        (phase + self.revolutions_per_second * delta_seconds as f32).rem_euclid(1.0)
+    }
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[derive(Default)]
+pub struct SpinnerBuilder {}
+
+impl SpinnerBuilder {
+    pub fn build<A: Clone + 'static>(self, window_cx: &mut WindowContext<'_, A>) -> Spinner {
+        let SpinnerBuilder {
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let el = ElementBuilder::new(SpinnerElement { phase: 0.0 })
+            .builder_values(z_index, scissor_rect, class, window_cx)
+            .rect(rect)
+            .hidden(manually_hidden)
+            .flags(
+                ElementFlags::PAINTS
+                    | ElementFlags::LISTENS_TO_INIT
+                    | ElementFlags::LISTENS_TO_VISIBILITY_CHANGE,
+            )
+            .build(window_cx);
+
+        Spinner { el }
+    }
+}
+
+struct SpinnerElement {
+    phase: f32,
+}
+
+impl<A: Clone + 'static> Element<A> for SpinnerElement {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        match event {
+            ElementEvent::Init | ElementEvent::Shown => {
+                cx.set_animating(true);
+            }
+            ElementEvent::Hidden => {
+                cx.set_animating(false);
+            }
+            ElementEvent::Animation { delta_seconds } => {
+                let class = cx.class();
+                let style = cx.res.style_system.get::<SpinnerStyle>(class);
+                self.phase = style.advance_phase(self.phase, delta_seconds);
+                cx.request_repaint();
+            }
+            ElementEvent::StyleChanged => {
+                cx.request_repaint();
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<SpinnerStyle>(cx.class, cx.opacity);
+
+        style.add_primitives(
+            Rect::from_size(cx.bounds_size).center(),
+            self.phase,
+            primitives,
+        );
+    }
+}
+
+/// An animated indicator that an operation is in progress.
+///
+/// The spinner is drawn in the center of its bounds, and it animates for as
+/// long as it is visible.
+#[element_handle]
+#[element_handle_class]
+#[element_handle_set_rect]
+#[element_handle_layout_aligned]
+pub struct Spinner {}
+
+impl Spinner {
+    pub fn builder() -> SpinnerBuilder {
+        SpinnerBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spinner_tail_fades_behind_the_phase() {
+        let style = SpinnerStyle {
+            min_alpha_multiplier: 0.2,
+            ..Default::default()
+        };
+
+        assert_eq!(style.dot_alpha_multiplier(0.5, 0.5), 1.0);
+        // The dot just ahead of the bright dot is at the end of the tail.
+        assert!(style.dot_alpha_multiplier(0.625, 0.5) < 0.35);
+        assert!(style.dot_alpha_multiplier(0.375, 0.5) > 0.85);
+
+        assert_eq!(style.advance_phase(0.75, 0.5), 0.25);
+    }
+}
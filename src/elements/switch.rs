@@ -9,6 +9,11 @@ use crate::prelude::*;
 
 /// The style of a [`Switch`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct SwitchStyle {
     pub size: f32,
     pub rounding: f32,
@@ -110,6 +115,38 @@ impl ElementStyle for SwitchStyle {
     fn default_light_style() -> Self {
         Self::default()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.outer_border_color_off.multiply_alpha(multiplier);
+        self.outer_border_color_off_hover.multiply_alpha(multiplier);
+        self.outer_border_color_off_disabled
+            .multiply_alpha(multiplier);
+        self.outer_border_color_on.multiply_alpha(multiplier);
+        self.outer_border_color_on_hover.multiply_alpha(multiplier);
+        self.outer_border_color_on_disabled
+            .multiply_alpha(multiplier);
+        self.off_bg.multiply_alpha(multiplier);
+        self.off_bg_hover.multiply_alpha(multiplier);
+        self.off_bg_disabled.multiply_alpha(multiplier);
+        self.on_bg.multiply_alpha(multiplier);
+        self.on_bg_hover.multiply_alpha(multiplier);
+        self.on_bg_disabled.multiply_alpha(multiplier);
+        self.slider_bg_off.multiply_alpha(multiplier);
+        self.slider_bg_off_hover.multiply_alpha(multiplier);
+        self.slider_bg_off_disabled.multiply_alpha(multiplier);
+        self.slider_bg_on.multiply_alpha(multiplier);
+        self.slider_bg_on_hover.multiply_alpha(multiplier);
+        self.slider_bg_on_disabled.multiply_alpha(multiplier);
+        self.slider_border_color_off.multiply_alpha(multiplier);
+        self.slider_border_color_off_hover
+            .multiply_alpha(multiplier);
+        self.slider_border_color_off_disabled
+            .multiply_alpha(multiplier);
+        self.slider_border_color_on.multiply_alpha(multiplier);
+        self.slider_border_color_on_hover.multiply_alpha(multiplier);
+        self.slider_border_color_on_disabled
+            .multiply_alpha(multiplier);
+    }
 }
 
 #[element_builder]
@@ -241,7 +278,7 @@ impl<A: Clone + 'static> Element<A> for SwitchElement<A> {
                     cx.request_repaint();
 
                     if let Some(action) = &mut self.action {
-                        cx.send_action((action)(shared_state.toggled)).unwrap();
+                        cx.send_action((action)(shared_state.toggled));
                     }
 
                     return EventCaptureStatus::Captured;
@@ -256,7 +293,10 @@ impl<A: Clone + 'static> Element<A> for SwitchElement<A> {
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let shared_state = RefCell::borrow(&self.shared_state);
 
-        let style = cx.res.style_system.get::<SwitchStyle>(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<SwitchStyle>(cx.class, cx.opacity);
 
         let get_colors = || -> (Background, Background, RGBA8, RGBA8) {
             let bg_quad_bg = if shared_state.toggled {
@@ -10,6 +10,11 @@ use super::toggle_button::ToggleButtonInner;
 
 /// The style of a [`Tab`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TabStyle {
     pub toggle_btn_style: ToggleButtonStyle,
 
@@ -39,6 +44,18 @@ impl ElementStyle for TabStyle {
     fn default_light_style() -> Self {
         todo!()
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        Some(Self {
+            toggle_btn_style: self.toggle_btn_style.with_text_scale(text_scale)?,
+            ..self.clone()
+        })
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.toggle_btn_style.multiply_alpha(multiplier);
+        self.on_indicator_line_style.multiply_alpha(multiplier);
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -248,6 +265,9 @@ impl<A: Clone + 'static> Element<A> for TabElement<A> {
             ElementEvent::StyleChanged => {
                 let style = cx.res.style_system.get::<TabStyle>(cx.class());
                 self.cursor_icon = style.toggle_btn_style.cursor_icon;
+                shared_state
+                    .inner
+                    .sync_new_style(&style.toggle_btn_style, &mut cx.res.font_system);
             }
             ElementEvent::Pointer(PointerEvent::Moved { .. }) => {
                 if shared_state.inner.state() == ButtonState::Disabled {
@@ -292,7 +312,7 @@ impl<A: Clone + 'static> Element<A> for TabElement<A> {
                         shared_state.inner.toggled = true;
 
                         if let Some(action) = &self.action {
-                            cx.send_action(action.clone()).unwrap();
+                            cx.send_action(action.clone());
                         }
 
                         cx.request_repaint();
@@ -309,7 +329,7 @@ impl<A: Clone + 'static> Element<A> for TabElement<A> {
                     && (shared_state.inner.state() == ButtonState::Down
                         || shared_state.inner.state() == ButtonState::Hovered)
                 {
-                    let new_state = if cx.is_point_within_visible_bounds(position) {
+                    let new_state = if cx.is_point_within_hit_bounds(position) {
                         ButtonState::Hovered
                     } else {
                         ButtonState::Idle
@@ -333,7 +353,10 @@ impl<A: Clone + 'static> Element<A> for TabElement<A> {
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
-        let style: &TabStyle = cx.res.style_system.get(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<TabStyle>(cx.class, cx.opacity);
 
         let label_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
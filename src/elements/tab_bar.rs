@@ -0,0 +1,1063 @@
+use derive_where::derive_where;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::derive::*;
+use crate::prelude::*;
+
+use super::label::LabelInner;
+
+/// The style of a [`TabBar`] element
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct TabBarStyle {
+    /// The properties of the tab text.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub text_properties: TextProperties,
+
+    /// The color of the text of the inactive tabs.
+    ///
+    /// By default this is set to `color::rgb(170, 170, 170)`.
+    pub text_color: RGBA8,
+    pub text_color_hover: Option<RGBA8>,
+    /// The color of the text of the active tab.
+    ///
+    /// By default this is set to `color::WHITE`.
+    pub text_color_active: RGBA8,
+    pub text_color_disabled: DisabledColor,
+
+    /// The padding around the text of each tab.
+    ///
+    /// By default this is set to `Padding::new(0.0, 10.0, 0.0, 10.0)`.
+    pub text_padding: Padding,
+
+    /// The style of the quad behind the whole bar.
+    ///
+    /// By default this is set to `QuadStyle::TRANSPARENT`.
+    pub bar_quad: QuadStyle,
+
+    pub tab_quad: QuadStyle,
+    pub tab_quad_hover: Option<QuadStyle>,
+    pub tab_quad_active: QuadStyle,
+
+    /// The height of the line drawn at the bottom of the active tab.
+    ///
+    /// By default this is set to `2.0`.
+    pub active_indicator_height: f32,
+    pub active_indicator_color: RGBA8,
+
+    /// The spacing between tabs in points.
+    ///
+    /// By default this is set to `1.0`.
+    pub tab_spacing: f32,
+
+    /// The minimum width of each tab in points.
+    ///
+    /// By default this is set to `40.0`.
+    pub min_tab_width: f32,
+
+    /// The width and height of the close button of closable tabs in points.
+    ///
+    /// By default this is set to `16.0`.
+    pub close_button_size: f32,
+    /// The spacing between the close button and the right edge of the tab.
+    ///
+    /// By default this is set to `4.0`.
+    pub close_button_padding: f32,
+    /// The color of the "×" glyph of the close button.
+    ///
+    /// If this is `None`, then the color of the tab's text will be used.
+    ///
+    /// By default this is set to `None`.
+    pub close_button_color: Option<RGBA8>,
+    pub close_button_quad_hover: QuadStyle,
+
+    /// The number of points to scroll per line when the tabs overflow the
+    /// width of the bar.
+    ///
+    /// By default this is set to `24.0`.
+    pub points_per_line: f32,
+
+    /// The cursor icon to show when the user hovers over a tab.
+    ///
+    /// If this is `None`, then the cursor icon will not be changed.
+    ///
+    /// By default this is set to `None`.
+    pub cursor_icon: Option<CursorIcon>,
+}
+
+impl Default for TabBarStyle {
+    fn default() -> Self {
+        Self {
+            text_properties: Default::default(),
+            text_color: color::rgb(170, 170, 170),
+            text_color_hover: None,
+            text_color_active: color::WHITE,
+            text_color_disabled: Default::default(),
+            text_padding: Padding::new(0.0, 10.0, 0.0, 10.0),
+            bar_quad: QuadStyle::TRANSPARENT,
+            tab_quad: QuadStyle::TRANSPARENT,
+            tab_quad_hover: None,
+            tab_quad_active: QuadStyle::TRANSPARENT,
+            active_indicator_height: 2.0,
+            active_indicator_color: color::WHITE,
+            tab_spacing: 1.0,
+            min_tab_width: 40.0,
+            close_button_size: 16.0,
+            close_button_padding: 4.0,
+            close_button_color: None,
+            close_button_quad_hover: QuadStyle::TRANSPARENT,
+            points_per_line: 24.0,
+            cursor_icon: None,
+        }
+    }
+}
+
+impl ElementStyle for TabBarStyle {
+    const ID: &'static str = "tabbar";
+
+    fn default_dark_style() -> Self {
+        Self::default()
+    }
+
+    fn default_light_style() -> Self {
+        Self {
+            text_color: color::rgb(90, 90, 90),
+            text_color_active: color::BLACK,
+            active_indicator_color: color::BLACK,
+            ..Default::default()
+        }
+    }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.text_color_hover.multiply_alpha(multiplier);
+        self.text_color_active.multiply_alpha(multiplier);
+        self.text_color_disabled.multiply_alpha(multiplier);
+        self.bar_quad.multiply_alpha(multiplier);
+        self.tab_quad.multiply_alpha(multiplier);
+        self.tab_quad_hover.multiply_alpha(multiplier);
+        self.tab_quad_active.multiply_alpha(multiplier);
+        self.active_indicator_color.multiply_alpha(multiplier);
+        self.close_button_color.multiply_alpha(multiplier);
+        self.close_button_quad_hover.multiply_alpha(multiplier);
+    }
+}
+
+impl TabBarStyle {
+    pub fn label_style(&self, text_color: RGBA8) -> LabelStyle {
+        LabelStyle {
+            text_properties: self.text_properties,
+            text_color,
+            text_padding: self.text_padding,
+            ..Default::default()
+        }
+    }
+
+    fn text_color(&self, active: bool, hovered: bool, disabled: bool) -> RGBA8 {
+        let color = if active {
+            self.text_color_active
+        } else if hovered {
+            self.text_color_hover.unwrap_or(self.text_color)
+        } else {
+            self.text_color
+        };
+
+        if disabled {
+            self.text_color_disabled.get(color)
+        } else {
+            color
+        }
+    }
+
+    fn close_button_width(&self) -> f32 {
+        self.close_button_size + self.close_button_padding
+    }
+}
+
+/// A tab in a [`TabBar`].
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct TabBarTab {
+    pub text: String,
+    /// Whether or not to show a close button on this tab.
+    pub closable: bool,
+}
+
+impl TabBarTab {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            closable: false,
+        }
+    }
+
+    pub const fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+impl From<&str> for TabBarTab {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for TabBarTab {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// The horizontal position and width of each tab, given the width of each
+/// tab. Returns the total width of all of the tabs as well.
+fn tab_spans(widths: impl IntoIterator<Item = f32>, spacing: f32) -> (Vec<(f32, f32)>, f32) {
+    let mut x = 0.0;
+    let spans: Vec<(f32, f32)> = widths
+        .into_iter()
+        .map(|width| {
+            let span = (x, width);
+            x += width + spacing;
+            span
+        })
+        .collect();
+
+    let total_width = if spans.is_empty() { 0.0 } else { x - spacing };
+
+    (spans, total_width)
+}
+
+/// The scroll offset which reveals as much of the given span as possible
+/// while moving as little as possible.
+fn scroll_to_reveal(scroll_x: f32, span: (f32, f32), view_width: f32) -> f32 {
+    let (x, width) = span;
+
+    if x < scroll_x || width > view_width {
+        x
+    } else if x + width > scroll_x + view_width {
+        x + width - view_width
+    } else {
+        scroll_x
+    }
+}
+
+fn clamp_scroll(scroll_x: f32, content_width: f32, view_width: f32) -> f32 {
+    scroll_x.clamp(0.0, (content_width - view_width).max(0.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HoveredPart {
+    Tab(usize),
+    CloseButton(usize),
+}
+
+#[element_builder]
+#[element_builder_class]
+#[element_builder_rect]
+#[element_builder_hidden]
+#[element_builder_disabled]
+#[derive_where(Default)]
+pub struct TabBarBuilder<A: Clone + 'static> {
+    pub on_selected: Option<Box<dyn FnMut(usize) -> A>>,
+    pub on_close: Option<Box<dyn FnMut(usize) -> A>>,
+    pub tabs: Vec<TabBarTab>,
+    pub selected_index: usize,
+    pub overflow_scissor_rect: Option<ScissorRectID>,
+}
+
+impl<A: Clone + 'static> TabBarBuilder<A> {
+    /// The action to send when the user selects a tab, either by clicking
+    /// on it or with the Left/Right arrow keys while the bar has focus. The
+    /// index of the tab is passed to the closure.
+    pub fn on_selected<F: FnMut(usize) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_selected = Some(Box::new(f));
+        self
+    }
+
+    /// The action to send when the user clicks the close button of a
+    /// closable tab. The index of the tab is passed to the closure.
+    ///
+    /// The tab is not removed automatically, use [`TabBar::remove_tab`] in
+    /// response to this action.
+    pub fn on_close<F: FnMut(usize) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_close = Some(Box::new(f));
+        self
+    }
+
+    pub fn tabs(mut self, tabs: impl IntoIterator<Item = impl Into<TabBarTab>>) -> Self {
+        self.tabs = tabs.into_iter().map(|t| t.into()).collect();
+        self
+    }
+
+    pub const fn selected_index(mut self, index: usize) -> Self {
+        self.selected_index = index;
+        self
+    }
+
+    /// A scissoring rectangle that this element will place itself into and
+    /// control, so that the tabs can be scrolled horizontally when they
+    /// overflow the width of the bar.
+    ///
+    /// If this is not set, then tabs which overflow the bar will not be
+    /// clipped.
+    ///
+    /// If `scissor_rect_id == ScissorRectID::DEFAULT`, then this will
+    /// be ignored.
+    pub const fn overflow_scissor_rect(mut self, scissor_rect_id: ScissorRectID) -> Self {
+        self.overflow_scissor_rect = Some(scissor_rect_id);
+        self
+    }
+
+    pub fn build(self, window_cx: &mut WindowContext<'_, A>) -> TabBar {
+        let TabBarBuilder {
+            on_selected,
+            on_close,
+            tabs,
+            selected_index,
+            overflow_scissor_rect,
+            disabled,
+            class,
+            z_index,
+            rect,
+            manually_hidden,
+            scissor_rect,
+        } = self;
+
+        let overflow_scissor_rect =
+            overflow_scissor_rect.filter(|id| *id != ScissorRectID::DEFAULT);
+
+        let style = window_cx
+            .res
+            .style_system
+            .get::<TabBarStyle>(window_cx.builder_class(class))
+            .clone();
+
+        let mut shared_state = SharedState {
+            tabs: Vec::new(),
+            close_label: new_label("×", &style, &mut window_cx.res.font_system),
+            spans: Vec::new(),
+            content_width: 0.0,
+            bar_rect: rect,
+            scroll_x: 0.0,
+            selected_index,
+            disabled,
+        };
+        shared_state.set_tabs(tabs, &style, &mut window_cx.res.font_system);
+
+        let el_rect = if overflow_scissor_rect.is_some() {
+            shared_state.content_rect()
+        } else {
+            rect
+        };
+
+        let shared_state = Rc::new(RefCell::new(shared_state));
+
+        let el = ElementBuilder::new(TabBarElement {
+            shared_state: Rc::clone(&shared_state),
+            on_selected,
+            on_close,
+            overflow_scissor_rect,
+            hovered: None,
+            focused: false,
+            cursor_icon: style.cursor_icon,
+        })
+        .builder_values(
+            z_index,
+            overflow_scissor_rect.or(scissor_rect),
+            class,
+            window_cx,
+        )
+        .rect(el_rect)
+        .hidden(manually_hidden)
+        .flags(
+            ElementFlags::PAINTS
+                | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+                | ElementFlags::LISTENS_TO_KEYS_WHEN_FOCUSED
+                | ElementFlags::LISTENS_TO_FOCUS_CHANGE
+                | ElementFlags::LISTENS_TO_INIT
+                | ElementFlags::FOCUSABLE,
+        )
+        .build(window_cx);
+
+        TabBar {
+            el,
+            shared_state,
+            overflow: overflow_scissor_rect.is_some(),
+        }
+    }
+}
+
+fn new_label(text: &str, style: &TabBarStyle, font_system: &mut FontSystem) -> LabelInner {
+    LabelInner::new(
+        Some(text),
+        None,
+        Vector::default(),
+        Vector::default(),
+        None,
+        IconScale::default(),
+        Default::default(),
+        &style.label_style(style.text_color),
+        font_system,
+    )
+}
+
+struct TabBarElement<A: Clone + 'static> {
+    shared_state: Rc<RefCell<SharedState>>,
+    on_selected: Option<Box<dyn FnMut(usize) -> A>>,
+    on_close: Option<Box<dyn FnMut(usize) -> A>>,
+    overflow_scissor_rect: Option<ScissorRectID>,
+    hovered: Option<HoveredPart>,
+    focused: bool,
+    cursor_icon: Option<CursorIcon>,
+}
+
+impl<A: Clone + 'static> TabBarElement<A> {
+    fn part_at(
+        &self,
+        shared_state: &SharedState,
+        pos: Point,
+        cx: &mut ElementContext<'_, A>,
+    ) -> Option<HoveredPart> {
+        let style = cx.res.style_system.get::<TabBarStyle>(cx.class());
+
+        let rect = cx.rect();
+        let pos = pos - rect.origin.to_vector();
+        if pos.y < 0.0 || pos.y > rect.height() {
+            return None;
+        }
+
+        let index = shared_state
+            .spans
+            .iter()
+            .position(|(x, width)| pos.x >= *x && pos.x < *x + *width)?;
+
+        let (x, width) = shared_state.spans[index];
+        if shared_state.tabs[index].closable && pos.x >= x + width - style.close_button_width() {
+            Some(HoveredPart::CloseButton(index))
+        } else {
+            Some(HoveredPart::Tab(index))
+        }
+    }
+
+    fn select(
+        &mut self,
+        index: usize,
+        shared_state: &mut SharedState,
+        cx: &mut ElementContext<'_, A>,
+    ) {
+        if shared_state.selected_index == index {
+            return;
+        }
+
+        shared_state.selected_index = index;
+        self.reveal_selected(shared_state, cx);
+        cx.request_repaint();
+
+        if let Some(action) = &mut self.on_selected {
+            cx.send_action((action)(index));
+        }
+    }
+
+    fn reveal_selected(&self, shared_state: &mut SharedState, cx: &mut ElementContext<'_, A>) {
+        if let Some(span) = shared_state.spans.get(shared_state.selected_index) {
+            shared_state.scroll_x = clamp_scroll(
+                scroll_to_reveal(shared_state.scroll_x, *span, shared_state.bar_rect.width()),
+                shared_state.content_width,
+                shared_state.bar_rect.width(),
+            );
+        }
+
+        self.sync_scissor_rect(shared_state, cx);
+    }
+
+    fn sync_scissor_rect(&self, shared_state: &SharedState, cx: &mut ElementContext<'_, A>) {
+        if let Some(scissor_rect) = self.overflow_scissor_rect {
+            cx.update_scissor_rect(
+                scissor_rect,
+                Some(shared_state.bar_rect),
+                Some(Vector::new(shared_state.scroll_x, 0.0)),
+            );
+        }
+    }
+}
+
+impl<A: Clone + 'static> Element<A> for TabBarElement<A> {
+    fn on_event(
+        &mut self,
+        event: ElementEvent,
+        cx: &mut ElementContext<'_, A>,
+    ) -> EventCaptureStatus {
+        let shared_state_rc = Rc::clone(&self.shared_state);
+        let mut shared_state = RefCell::borrow_mut(&shared_state_rc);
+
+        match event {
+            ElementEvent::Init => {
+                self.reveal_selected(&mut shared_state, cx);
+            }
+            ElementEvent::CustomStateChanged => {
+                cx.request_repaint();
+
+                if shared_state.disabled {
+                    self.hovered = None;
+                }
+
+                self.reveal_selected(&mut shared_state, cx);
+            }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get::<TabBarStyle>(cx.class()).clone();
+                self.cursor_icon = style.cursor_icon;
+                shared_state.sync_new_style(&style, &mut cx.res.font_system);
+            }
+            ElementEvent::Pointer(PointerEvent::Moved { position, .. }) => {
+                if shared_state.disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let hovered = self.part_at(&shared_state, position, cx);
+                if self.hovered != hovered {
+                    self.hovered = hovered;
+                    cx.request_repaint();
+                }
+
+                if hovered.is_some() {
+                    if let Some(cursor_icon) = self.cursor_icon {
+                        cx.cursor_icon = cursor_icon;
+                    }
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(PointerEvent::PointerLeft) => {
+                if self.hovered.take().is_some() {
+                    cx.request_repaint();
+                }
+            }
+            ElementEvent::Pointer(PointerEvent::ButtonJustPressed {
+                position, button, ..
+            }) => {
+                if shared_state.disabled || button != PointerButton::Primary {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                match self.part_at(&shared_state, position, cx) {
+                    Some(HoveredPart::Tab(index)) => {
+                        self.select(index, &mut shared_state, cx);
+
+                        // Receive keyboard events so the selection can be
+                        // moved with the arrow keys.
+                        if !cx.has_focus() {
+                            cx.steal_focus();
+                        }
+                    }
+                    Some(HoveredPart::CloseButton(index)) => {
+                        if let Some(action) = &mut self.on_close {
+                            cx.send_action((action)(index));
+                        }
+                    }
+                    None => return EventCaptureStatus::NotCaptured,
+                }
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(PointerEvent::ScrollWheel {
+                position,
+                delta_type,
+                ..
+            }) => {
+                if shared_state.disabled
+                    || self.overflow_scissor_rect.is_none()
+                    || !shared_state.bar_rect.contains(position)
+                {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let points_per_line = cx
+                    .res
+                    .style_system
+                    .get::<TabBarStyle>(cx.class())
+                    .points_per_line;
+                let delta = delta_type.points(points_per_line, shared_state.bar_rect.width());
+
+                // Vertical scroll wheels are the most common, so use them
+                // to scroll horizontally as well.
+                let delta = if delta.x != 0.0 { delta.x } else { delta.y };
+
+                let scroll_x = clamp_scroll(
+                    shared_state.scroll_x + delta,
+                    shared_state.content_width,
+                    shared_state.bar_rect.width(),
+                );
+
+                if scroll_x == shared_state.scroll_x {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                shared_state.scroll_x = scroll_x;
+                self.sync_scissor_rect(&shared_state, cx);
+
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Keyboard(KeyboardEvent {
+                state: KeyState::Down,
+                code,
+                ..
+            }) => {
+                if shared_state.disabled || shared_state.tabs.is_empty() {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let selected_index = shared_state.selected_index;
+                let index = match code {
+                    Code::ArrowLeft => selected_index.saturating_sub(1),
+                    Code::ArrowRight => (selected_index + 1).min(shared_state.tabs.len() - 1),
+                    Code::Home => 0,
+                    Code::End => shared_state.tabs.len() - 1,
+                    _ => return EventCaptureStatus::NotCaptured,
+                };
+
+                self.select(index, &mut shared_state, cx);
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Focus(focused) => {
+                if focused {
+                    cx.listen_to_pointer_clicked_off();
+                }
+
+                self.focused = focused;
+            }
+            ElementEvent::ClickedOff => {
+                if cx.has_focus() {
+                    cx.release_focus();
+                }
+            }
+            _ => {}
+        }
+
+        EventCaptureStatus::NotCaptured
+    }
+
+    fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<TabBarStyle>(cx.class, cx.opacity);
+
+        let bounds = Rect::from_size(cx.bounds_size);
+        let disabled = shared_state.disabled;
+        let selected_index = shared_state.selected_index;
+
+        if !style.bar_quad.is_transparent() {
+            primitives.add(style.bar_quad.create_primitive(bounds));
+        }
+
+        let SharedState {
+            tabs,
+            close_label,
+            spans,
+            ..
+        } = &mut *shared_state;
+
+        for (i, (tab, (x, width))) in tabs.iter_mut().zip(spans.iter()).enumerate() {
+            let active = i == selected_index;
+            let hovered = !disabled
+                && matches!(
+                    self.hovered,
+                    Some(HoveredPart::Tab(h)) | Some(HoveredPart::CloseButton(h)) if h == i
+                );
+
+            let tab_bounds = Rect::new(Point::new(*x, 0.0), Size::new(*width, bounds.height()));
+
+            let quad = if active {
+                &style.tab_quad_active
+            } else if hovered {
+                style.tab_quad_hover.as_ref().unwrap_or(&style.tab_quad)
+            } else {
+                &style.tab_quad
+            };
+            if !quad.is_transparent() {
+                primitives.set_z_index(0);
+                primitives.add(quad.create_primitive(tab_bounds));
+            }
+
+            let text_color = style.text_color(active, hovered, disabled);
+
+            let label_width = if tab.closable {
+                (tab_bounds.width() - style.close_button_width()).max(0.0)
+            } else {
+                tab_bounds.width()
+            };
+            let label_primitives = tab.label.render(
+                Rect::new(
+                    tab_bounds.origin,
+                    Size::new(label_width, tab_bounds.height()),
+                ),
+                &style.label_style(text_color),
+                &mut cx.res.font_system,
+            );
+            if let Some(text_primitive) = label_primitives.text {
+                primitives.set_z_index(2);
+                primitives.add_text(text_primitive);
+            }
+
+            if tab.closable {
+                let close_bounds = Rect::new(
+                    Point::new(
+                        tab_bounds.max_x() - style.close_button_width(),
+                        (tab_bounds.height() - style.close_button_size) * 0.5,
+                    ),
+                    Size::new(style.close_button_size, style.close_button_size),
+                );
+
+                if self.hovered == Some(HoveredPart::CloseButton(i))
+                    && !style.close_button_quad_hover.is_transparent()
+                {
+                    primitives.set_z_index(1);
+                    primitives.add(style.close_button_quad_hover.create_primitive(close_bounds));
+                }
+
+                let close_style = LabelStyle {
+                    text_padding: Padding::zero(),
+                    horizontal_align: Some(Align::Center),
+                    ..style.label_style(style.close_button_color.unwrap_or(text_color))
+                };
+                let close_primitives =
+                    close_label.render(close_bounds, &close_style, &mut cx.res.font_system);
+                if let Some(text_primitive) = close_primitives.text {
+                    primitives.set_z_index(2);
+                    primitives.add_text(text_primitive);
+                }
+            }
+
+            if active && style.active_indicator_height > 0.0 {
+                primitives.set_z_index(1);
+                primitives.add(
+                    QuadStyle {
+                        bg: Background::Solid(style.active_indicator_color),
+                        border: BorderStyle::TRANSPARENT,
+                        flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+                    }
+                    .create_primitive(Rect::new(
+                        Point::new(
+                            tab_bounds.min_x(),
+                            tab_bounds.max_y() - style.active_indicator_height,
+                        ),
+                        Size::new(tab_bounds.width(), style.active_indicator_height),
+                    )),
+                );
+            }
+        }
+    }
+}
+
+struct TabEntry {
+    label: LabelInner,
+    closable: bool,
+}
+
+struct SharedState {
+    tabs: Vec<TabEntry>,
+    close_label: LabelInner,
+    /// The horizontal position and width of each tab, relative to the start
+    /// of the first tab.
+    spans: Vec<(f32, f32)>,
+    content_width: f32,
+    /// The rectangle of the visible area of the bar.
+    bar_rect: Rect,
+    scroll_x: f32,
+    selected_index: usize,
+    disabled: bool,
+}
+
+impl SharedState {
+    fn set_tabs(
+        &mut self,
+        tabs: Vec<TabBarTab>,
+        style: &TabBarStyle,
+        font_system: &mut FontSystem,
+    ) {
+        self.tabs = tabs
+            .into_iter()
+            .map(|tab| TabEntry {
+                label: new_label(&tab.text, style, font_system),
+                closable: tab.closable,
+            })
+            .collect();
+
+        self.layout_tabs(style);
+    }
+
+    fn layout_tabs(&mut self, style: &TabBarStyle) {
+        let label_style = style.label_style(style.text_color);
+
+        let widths: Vec<f32> = self
+            .tabs
+            .iter_mut()
+            .map(|tab| {
+                let mut width = tab.label.desired_size(|| label_style.padding_info()).width;
+                if tab.closable {
+                    width += style.close_button_width();
+                }
+                width.max(style.min_tab_width)
+            })
+            .collect();
+
+        (self.spans, self.content_width) = tab_spans(widths, style.tab_spacing);
+
+        if self.selected_index >= self.tabs.len() {
+            self.selected_index = self.tabs.len().saturating_sub(1);
+        }
+    }
+
+    fn sync_new_style(&mut self, style: &TabBarStyle, font_system: &mut FontSystem) {
+        let label_style = style.label_style(style.text_color);
+
+        for tab in self.tabs.iter_mut() {
+            tab.label.sync_new_style(&label_style, font_system);
+        }
+        self.close_label.sync_new_style(&label_style, font_system);
+
+        self.layout_tabs(style);
+    }
+
+    /// The rectangle of the element when it is placed in its own
+    /// scissoring rectangle, relative to that scissoring rectangle.
+    fn content_rect(&self) -> Rect {
+        Rect::new(
+            Point::zero(),
+            Size::new(
+                self.content_width.max(self.bar_rect.width()),
+                self.bar_rect.height(),
+            ),
+        )
+    }
+}
+
+/// A handle to a [`TabBarElement`], a horizontal row of selectable tabs.
+#[element_handle]
+#[element_handle_class]
+pub struct TabBar {
+    shared_state: Rc<RefCell<SharedState>>,
+    overflow: bool,
+}
+
+impl TabBar {
+    pub fn builder<A: Clone + 'static>() -> TabBarBuilder<A> {
+        TabBarBuilder::default()
+    }
+
+    /// Replace all of the tabs.
+    ///
+    /// The selected index is clamped to the new number of tabs.
+    pub fn set_tabs(
+        &mut self,
+        tabs: impl IntoIterator<Item = impl Into<TabBarTab>>,
+        res: &mut ResourceCtx,
+    ) {
+        let style = res.style_system.get::<TabBarStyle>(self.el.class()).clone();
+
+        RefCell::borrow_mut(&self.shared_state).set_tabs(
+            tabs.into_iter().map(|t| t.into()).collect(),
+            &style,
+            &mut res.font_system,
+        );
+
+        self.sync_layout();
+    }
+
+    /// Add a tab to the end of the bar.
+    pub fn add_tab(&mut self, tab: impl Into<TabBarTab>, res: &mut ResourceCtx) {
+        self.insert_tab(self.num_tabs(), tab, res);
+    }
+
+    /// Insert a tab at the given index.
+    ///
+    /// If `index` is greater than the number of tabs, then the tab will be
+    /// added to the end.
+    pub fn insert_tab(&mut self, index: usize, tab: impl Into<TabBarTab>, res: &mut ResourceCtx) {
+        let tab: TabBarTab = tab.into();
+        let style = res.style_system.get::<TabBarStyle>(self.el.class()).clone();
+
+        {
+            let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+            let index = index.min(shared_state.tabs.len());
+            shared_state.tabs.insert(
+                index,
+                TabEntry {
+                    label: new_label(&tab.text, &style, &mut res.font_system),
+                    closable: tab.closable,
+                },
+            );
+
+            // Keep the same tab selected.
+            if index <= shared_state.selected_index && shared_state.tabs.len() > 1 {
+                shared_state.selected_index += 1;
+            }
+
+            shared_state.layout_tabs(&style);
+        }
+
+        self.sync_layout();
+    }
+
+    /// Remove the tab at the given index.
+    ///
+    /// Returns `false` if there is no tab at the given index.
+    pub fn remove_tab(&mut self, index: usize, res: &mut ResourceCtx) -> bool {
+        let style = res.style_system.get::<TabBarStyle>(self.el.class()).clone();
+
+        {
+            let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+            if index >= shared_state.tabs.len() {
+                return false;
+            }
+
+            shared_state.tabs.remove(index);
+
+            // Keep the same tab selected.
+            if index < shared_state.selected_index {
+                shared_state.selected_index -= 1;
+            }
+
+            shared_state.layout_tabs(&style);
+        }
+
+        self.sync_layout();
+        true
+    }
+
+    pub fn num_tabs(&self) -> usize {
+        RefCell::borrow(&self.shared_state).tabs.len()
+    }
+
+    /// Set the selected tab.
+    ///
+    /// Returns `true` if the selected tab has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_selected(&mut self, index: usize) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        let index = index.min(shared_state.tabs.len().saturating_sub(1));
+        if shared_state.selected_index != index {
+            shared_state.selected_index = index;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        RefCell::borrow(&self.shared_state).selected_index
+    }
+
+    /// Set the disabled state of this element.
+    ///
+    /// Returns `true` if the disabled state has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_disabled(&mut self, disabled: bool) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.disabled != disabled {
+            shared_state.disabled = disabled;
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn disabled(&self) -> bool {
+        RefCell::borrow(&self.shared_state).disabled
+    }
+
+    /// The total width of all of the tabs, which may be larger than the
+    /// width of the bar.
+    pub fn content_width(&self) -> f32 {
+        RefCell::borrow(&self.shared_state).content_width
+    }
+
+    /// The rectangle of the visible area of the bar.
+    pub fn bounds(&self) -> Rect {
+        RefCell::borrow(&self.shared_state).bar_rect
+    }
+
+    /// Layout out the bar into the given rectangle.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn layout(&mut self, rect: Rect) {
+        let changed = {
+            let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+            if shared_state.bar_rect != rect {
+                shared_state.bar_rect = rect;
+                true
+            } else {
+                false
+            }
+        };
+
+        if changed {
+            self.sync_layout();
+        }
+    }
+
+    fn sync_layout(&mut self) {
+        let rect = {
+            let shared_state = RefCell::borrow(&self.shared_state);
+
+            if self.overflow {
+                shared_state.content_rect()
+            } else {
+                shared_state.bar_rect
+            }
+        };
+
+        self.el.set_rect(rect);
+        self.el.notify_custom_state_change();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflowing_tabs_scroll_to_reveal_selected() {
+        let (spans, total_width) = tab_spans([50.0, 80.0, 60.0], 2.0);
+        assert_eq!(spans, vec![(0.0, 50.0), (52.0, 80.0), (134.0, 60.0)]);
+        assert_eq!(total_width, 194.0);
+        assert_eq!(tab_spans([], 2.0).1, 0.0);
+
+        let view_width = 100.0;
+
+        // Already visible.
+        assert_eq!(scroll_to_reveal(0.0, spans[0], view_width), 0.0);
+        // Past the right edge.
+        assert_eq!(scroll_to_reveal(0.0, spans[2], view_width), 94.0);
+        // Past the left edge.
+        assert_eq!(scroll_to_reveal(94.0, spans[0], view_width), 0.0);
+
+        assert_eq!(clamp_scroll(500.0, total_width, view_width), 94.0);
+        assert_eq!(clamp_scroll(-5.0, total_width, view_width), 0.0);
+        // Tabs which fit never scroll.
+        assert_eq!(clamp_scroll(20.0, 80.0, view_width), 0.0);
+    }
+}
@@ -160,7 +160,11 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
         let res = match event {
-            ElementEvent::Animation { .. } => shared_state.inner.on_animation(),
+            ElementEvent::Animation { delta_seconds } => shared_state.inner.on_animation(
+                delta_seconds,
+                cx.animation_clock_secs(),
+                &mut cx.res.font_system,
+            ),
             ElementEvent::CustomStateChanged => {
                 if let Some((element_rect, align, padding)) = shared_state.show_with_info.take() {
                     self.start_text = String::from(shared_state.inner.text());
@@ -192,6 +196,13 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
                     .inner
                     .on_custom_state_changed(cx.clipboard, &mut cx.res.font_system)
             }
+            ElementEvent::StyleChanged => {
+                let style = cx.res.style_system.get(cx.class());
+                shared_state
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
+                TextInputUpdateResult::default()
+            }
             ElementEvent::SizeChanged => {
                 let bounds_size = cx.rect().size;
                 let style = cx.res.style_system.get(cx.class());
@@ -215,6 +226,9 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
                 cx.rect(),
                 &mut cx.res.font_system,
             ),
+            ElementEvent::Pointer(PointerEvent::ContextMenu { position }) => {
+                shared_state.inner.on_context_menu(position, cx.rect())
+            }
             ElementEvent::Pointer(PointerEvent::ButtonJustReleased {
                 button, position, ..
             }) => shared_state
@@ -225,12 +239,17 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
             }
             ElementEvent::Keyboard(key_event) => shared_state.inner.on_keyboard_event(
                 &key_event,
+                cx.animation_clock_secs(),
                 cx.clipboard,
                 &mut cx.res.font_system,
             ),
-            ElementEvent::TextComposition(comp_event) => shared_state
-                .inner
-                .on_text_composition_event(&comp_event, &mut cx.res.font_system),
+            ElementEvent::TextComposition(comp_event) => {
+                shared_state.inner.on_text_composition_event(
+                    &comp_event,
+                    cx.animation_clock_secs(),
+                    &mut cx.res.font_system,
+                )
+            }
             ElementEvent::Focus(has_focus) => {
                 if !has_focus {
                     cx.set_rect(Rect::new(cx.rect().origin, Size::zero()));
@@ -244,12 +263,13 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
                                 Some(String::from(shared_state.inner.text()))
                             };
 
-                        cx.send_action((action)(new_text)).unwrap();
+                        cx.send_action((action)(new_text));
                     }
                 }
 
                 shared_state.inner.on_focus_changed(
                     has_focus,
+                    cx.animation_clock_secs(),
                     cx.clipboard,
                     &mut cx.res.font_system,
                 )
@@ -264,10 +284,18 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
 
         if res.needs_repaint {
             cx.request_repaint();
+
+            if shared_state.inner.focused() {
+                let area = shared_state.inner.ime_cursor_area(
+                    cx.res.style_system.get::<TextInputStyle>(cx.class()),
+                    shared_state.text_offset,
+                );
+                cx.set_ime_cursor_area(area);
+            }
         }
         if let Some(pos) = res.right_clicked_at {
             if let Some(action) = self.right_click_action.as_mut() {
-                cx.send_action((action)(pos)).unwrap();
+                cx.send_action((action)(pos));
             }
         }
         if res.hovered {
@@ -292,10 +320,13 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let shared_state = RefCell::borrow(&self.shared_state);
-        let style: &TextInputStyle = cx.res.style_system.get(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<TextInputStyle>(cx.class, cx.opacity);
 
         let mut p = shared_state.inner.create_primitives(
-            style,
+            &style,
             Rect::from_size(cx.bounds_size),
             shared_state.text_offset,
             self.hovered,
@@ -312,6 +343,14 @@ impl<A: Clone + 'static> Element<A> for FloatingTextInputElement<A> {
             primitives.set_z_index(2);
             primitives.add_text(text);
         }
+        if let Some(preedit_text) = p.preedit_text.take() {
+            primitives.set_z_index(2);
+            primitives.add_text(preedit_text);
+        }
+        if let Some(preedit_underline) = p.preedit_underline.take() {
+            primitives.set_z_index(3);
+            primitives.add_solid_quad(preedit_underline);
+        }
         if let Some(cursor) = p.cursor.take() {
             primitives.set_z_index(3);
             primitives.add_solid_quad(cursor);
@@ -466,6 +505,34 @@ impl FloatingTextInput {
         RefCell::borrow(&self.shared_state).inner.max_characters()
     }
 
+    /// The current horizontal scroll offset of the text in points.
+    pub fn scroll_offset_x(&self) -> f32 {
+        RefCell::borrow(&self.shared_state).inner.scroll_offset_x()
+    }
+
+    /// Set the horizontal scroll offset of the text in points. The offset is
+    /// clamped to the range between the start of the text and the point
+    /// where the end of the text is aligned with the right edge.
+    ///
+    /// Note, while the element is focused the text is scrolled to keep the
+    /// cursor in view instead, and this offset is used again once focus is
+    /// lost.
+    ///
+    /// Returns `true` if the offset has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_scroll_offset_x(&mut self, scroll_offset_x: f32) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.inner.set_scroll_offset_x(scroll_offset_x) {
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Perform an action on the text input.
     ///
     /// This will do nothing if the element is currently disabled.
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cell::{Ref, RefCell};
 use std::rc::Rc;
 
@@ -11,6 +12,11 @@ use super::{TextInputAction, TextInputInner, TextInputStyle, TextInputUpdateResu
 
 /// The style of an [`IconTextInput`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct IconTextInputStyle {
     pub text_input: TextInputStyle,
 
@@ -111,6 +117,21 @@ impl ElementStyle for IconTextInputStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        Some(Self {
+            text_input: self.text_input.with_text_scale(text_scale)?,
+            ..self.clone()
+        })
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_input.multiply_alpha(multiplier);
+        self.icon_color.multiply_alpha(multiplier);
+        self.icon_color_hover.multiply_alpha(multiplier);
+        self.icon_color_focused.multiply_alpha(multiplier);
+        self.icon_color_disabled.multiply_alpha(multiplier);
+    }
 }
 
 #[element_builder]
@@ -304,7 +325,8 @@ impl<A: Clone + 'static> IconTextInputBuilder<A> {
                 | ElementFlags::LISTENS_TO_TEXT_COMPOSITION_WHEN_FOCUSED
                 | ElementFlags::LISTENS_TO_KEYS_WHEN_FOCUSED
                 | ElementFlags::LISTENS_TO_SIZE_CHANGE
-                | ElementFlags::LISTENS_TO_FOCUS_CHANGE,
+                | ElementFlags::LISTENS_TO_FOCUS_CHANGE
+                | ElementFlags::FOCUSABLE,
         )
         .build(window_cx);
 
@@ -335,10 +357,21 @@ impl<A: Clone + 'static> Element<A> for IconTextInputElement<A> {
             .handle_event(&event, shared_state.inner.disabled(), cx);
 
         let res = match event {
-            ElementEvent::Animation { .. } => shared_state.inner.on_animation(),
+            ElementEvent::Animation { delta_seconds } => shared_state.inner.on_animation(
+                delta_seconds,
+                cx.animation_clock_secs(),
+                &mut cx.res.font_system,
+            ),
             ElementEvent::CustomStateChanged => shared_state
                 .inner
                 .on_custom_state_changed(cx.clipboard, &mut cx.res.font_system),
+            ElementEvent::StyleChanged => {
+                let style: &IconTextInputStyle = cx.res.style_system.get(cx.class());
+                shared_state
+                    .inner
+                    .sync_new_style(&style.text_input, &mut cx.res.font_system);
+                TextInputUpdateResult::default()
+            }
             ElementEvent::SizeChanged => {
                 let bounds_size = cx.rect().size;
                 let style: &IconTextInputStyle = cx.res.style_system.get(cx.class());
@@ -376,6 +409,9 @@ impl<A: Clone + 'static> Element<A> for IconTextInputElement<A> {
                 cx.rect(),
                 &mut cx.res.font_system,
             ),
+            ElementEvent::Pointer(PointerEvent::ContextMenu { position }) => {
+                shared_state.inner.on_context_menu(position, cx.rect())
+            }
             ElementEvent::Pointer(PointerEvent::ButtonJustReleased {
                 button, position, ..
             }) => shared_state
@@ -386,14 +422,20 @@ impl<A: Clone + 'static> Element<A> for IconTextInputElement<A> {
             }
             ElementEvent::Keyboard(key_event) => shared_state.inner.on_keyboard_event(
                 &key_event,
+                cx.animation_clock_secs(),
                 cx.clipboard,
                 &mut cx.res.font_system,
             ),
-            ElementEvent::TextComposition(comp_event) => shared_state
-                .inner
-                .on_text_composition_event(&comp_event, &mut cx.res.font_system),
+            ElementEvent::TextComposition(comp_event) => {
+                shared_state.inner.on_text_composition_event(
+                    &comp_event,
+                    cx.animation_clock_secs(),
+                    &mut cx.res.font_system,
+                )
+            }
             ElementEvent::Focus(has_focus) => shared_state.inner.on_focus_changed(
                 has_focus,
+                cx.animation_clock_secs(),
                 cx.clipboard,
                 &mut cx.res.font_system,
             ),
@@ -403,16 +445,23 @@ impl<A: Clone + 'static> Element<A> for IconTextInputElement<A> {
 
         if res.needs_repaint {
             cx.request_repaint();
+
+            if shared_state.inner.focused() {
+                cx.set_ime_cursor_area(
+                    shared_state
+                        .inner
+                        .ime_cursor_area(&self.text_input_style, shared_state.text_offset),
+                );
+            }
         }
         if res.send_action {
             if let Some(action) = self.action.as_mut() {
-                cx.send_action((action)(String::from(shared_state.inner.text())))
-                    .unwrap();
+                cx.send_action((action)(String::from(shared_state.inner.text())));
             }
         }
         if let Some(pos) = res.right_clicked_at {
             if let Some(action) = self.right_click_action.as_mut() {
-                cx.send_action((action)(pos)).unwrap();
+                cx.send_action((action)(pos));
             }
         }
         if let Some(focus) = res.set_focus {
@@ -440,11 +489,19 @@ impl<A: Clone + 'static> Element<A> for IconTextInputElement<A> {
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let shared_state = RefCell::borrow(&self.shared_state);
-        let style: &IconTextInputStyle = cx.res.style_system.get(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<IconTextInputStyle>(cx.class, cx.opacity);
         let disabled = shared_state.inner.disabled;
 
+        let mut text_input_style = Cow::Borrowed(&self.text_input_style);
+        if cx.opacity < 1.0 {
+            text_input_style.to_mut().multiply_alpha(cx.opacity);
+        }
+
         let mut p = shared_state.inner.create_primitives(
-            &self.text_input_style,
+            &text_input_style,
             Rect::from_size(cx.bounds_size),
             shared_state.text_offset,
             self.hovered,
@@ -470,6 +527,14 @@ impl<A: Clone + 'static> Element<A> for IconTextInputElement<A> {
         primitives.set_z_index(2);
         primitives.add_text(icon_primitives.icon);
 
+        if let Some(preedit_text) = p.preedit_text.take() {
+            primitives.set_z_index(2);
+            primitives.add_text(preedit_text);
+        }
+        if let Some(preedit_underline) = p.preedit_underline.take() {
+            primitives.set_z_index(3);
+            primitives.add_solid_quad(preedit_underline);
+        }
         if let Some(cursor) = p.cursor.take() {
             primitives.set_z_index(3);
             primitives.add_solid_quad(cursor);
@@ -605,6 +670,34 @@ impl IconTextInput {
         RefCell::borrow(&self.shared_state).inner.max_characters()
     }
 
+    /// The current horizontal scroll offset of the text in points.
+    pub fn scroll_offset_x(&self) -> f32 {
+        RefCell::borrow(&self.shared_state).inner.scroll_offset_x()
+    }
+
+    /// Set the horizontal scroll offset of the text in points. The offset is
+    /// clamped to the range between the start of the text and the point
+    /// where the end of the text is aligned with the right edge.
+    ///
+    /// Note, while the element is focused the text is scrolled to keep the
+    /// cursor in view instead, and this offset is used again once focus is
+    /// lost.
+    ///
+    /// Returns `true` if the offset has changed.
+    ///
+    /// This will *NOT* trigger an element update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    pub fn set_scroll_offset_x(&mut self, scroll_offset_x: f32) -> bool {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+
+        if shared_state.inner.set_scroll_offset_x(scroll_offset_x) {
+            self.el.notify_custom_state_change();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Perform an action on the text input.
     ///
     /// This will do nothing if the element is currently disabled.
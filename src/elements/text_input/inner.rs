@@ -1,5 +1,6 @@
+use keyboard_types::CompositionState;
 use smallvec::SmallVec;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::clipboard::{Clipboard, ClipboardKind};
@@ -14,8 +15,14 @@ use crate::vg::text::{EditorBorrowStatus, RcTextBuffer, TextPrimitive};
 
 /// The style of a [`TextInput`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TextInputStyle {
     /// The text properties.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
 
     /// The attritbutes of the placeholder text
@@ -23,6 +30,7 @@ pub struct TextInputStyle {
     /// If this is `None`, then the attributes from `text_properties` will be used.
     ///
     /// By default this is set to `None`.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub placeholder_text_attrs: Option<Attrs<'static>>,
 
     /// The color of the font
@@ -145,10 +153,24 @@ pub struct TextInputStyle {
     /// The border radius of the background quad.
     pub back_border_radius: Radius,
 
-    /// The interval at which the text cursor blinks.
+    /// The interval at which the text cursor blinks. Set this to `None` for
+    /// a solid cursor that does not blink.
     ///
-    /// By default this is set to half a second.
-    pub cursor_blink_interval: Duration,
+    /// By default this is set to `Some(500ms)`.
+    pub cursor_blink_interval: Option<Duration>,
+
+    /// When selecting text by dragging the pointer past the left or right
+    /// edge of the text, the text is scrolled by this many points per second
+    /// for every point the pointer is past the edge.
+    ///
+    /// By default this is set to `8.0`.
+    pub drag_auto_scroll_speed: f32,
+    /// The maximum distance past the edge of the text that increases the
+    /// auto-scroll speed. Moving the pointer further away than this will not
+    /// scroll any faster.
+    ///
+    /// By default this is set to `40.0`.
+    pub drag_auto_scroll_max_distance: f32,
 
     /// Additional flags for the quad primitives.
     ///
@@ -187,7 +209,9 @@ impl Default for TextInputStyle {
             back_border_width_hover: None,
             back_border_width_focused: None,
             back_border_radius: Radius::default(),
-            cursor_blink_interval: Duration::from_millis(500),
+            cursor_blink_interval: Some(Duration::from_millis(500)),
+            drag_auto_scroll_speed: 8.0,
+            drag_auto_scroll_max_distance: 40.0,
             quad_flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
         }
     }
@@ -206,6 +230,36 @@ impl ElementStyle for TextInputStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.text_color_placeholder.multiply_alpha(multiplier);
+        self.text_color_hover.multiply_alpha(multiplier);
+        self.text_color_disabled.multiply_alpha(multiplier);
+        self.text_color_placeholder_hover.multiply_alpha(multiplier);
+        self.text_color_placeholder_disabled
+            .multiply_alpha(multiplier);
+        self.text_color_focused.multiply_alpha(multiplier);
+        self.text_color_placeholder_focused
+            .multiply_alpha(multiplier);
+        self.text_color_highlighted.multiply_alpha(multiplier);
+        self.highlight_bg_color.multiply_alpha(multiplier);
+        self.cursor_color.multiply_alpha(multiplier);
+        self.back_bg.multiply_alpha(multiplier);
+        self.back_bg_hover.multiply_alpha(multiplier);
+        self.back_bg_focused.multiply_alpha(multiplier);
+        self.back_bg_disabled.multiply_alpha(multiplier);
+        self.back_border_color.multiply_alpha(multiplier);
+        self.back_border_color_hover.multiply_alpha(multiplier);
+        self.back_border_color_focused.multiply_alpha(multiplier);
+        self.back_border_color_disabled.multiply_alpha(multiplier);
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
@@ -229,8 +283,11 @@ pub struct TextInputInner {
     buffer: RcTextBuffer,
     placeholder_buffer: Option<RcTextBuffer>,
     password_buffer: Option<RcTextBuffer>,
+    preedit_buffer: RcTextBuffer,
     text: String,
     placeholder_text: String,
+    preedit_text: String,
+    preedit_width: f32,
     queued_actions: SmallVec<[TextInputAction; 4]>,
     max_characters: usize,
     focused: bool,
@@ -240,9 +297,27 @@ pub struct TextInputInner {
     cursor_x: f32,
     select_highlight_range: Option<(f32, f32)>,
     dragging: bool,
+    /// The last position of the pointer while dragging, relative to the
+    /// origin of the element.
+    drag_pointer_pos: Point,
+    /// How far the text has been auto-scrolled while dragging the pointer
+    /// past the edge of the text.
+    drag_scroll_x: f32,
+    drag_auto_scroll_speed: f32,
+    drag_auto_scroll_max_distance: f32,
+    /// The scroll offset to use while the element is not focused.
+    scroll_offset_x: f32,
+    cursor_width: f32,
+    padding_left: f32,
     cursor_blink_state_on: bool,
-    cursor_blink_last_toggle_instant: Instant,
-    cursor_blink_interval: Duration,
+    /// The animation clock at which the cursor was last reset to be solid,
+    /// i.e. because the text changed.
+    cursor_blink_reset_secs: f64,
+    /// Whether the cursor was reset by a method which has no access to the
+    /// animation clock (i.e. `set_text`). The reset is applied with the next
+    /// clock value that is passed in.
+    cursor_blink_reset_pending: bool,
+    cursor_blink_interval: Option<Duration>,
     pointer_hovered: bool,
     select_all_when_focused: bool,
 }
@@ -320,12 +395,17 @@ impl TextInputInner {
             None
         };
 
+        let preedit_buffer = RcTextBuffer::new("", text_properties, None, None, false, font_system);
+
         Self {
             buffer,
             placeholder_buffer,
             password_buffer,
+            preedit_buffer,
             text,
             placeholder_text,
+            preedit_text: String::new(),
+            preedit_width: 0.0,
             queued_actions: SmallVec::new(),
             show_password: false,
             max_characters,
@@ -338,8 +418,16 @@ impl TextInputInner {
             cursor_x: 0.0,
             select_highlight_range: None,
             dragging: false,
+            drag_pointer_pos: Point::default(),
+            drag_scroll_x: 0.0,
+            drag_auto_scroll_speed: style.drag_auto_scroll_speed,
+            drag_auto_scroll_max_distance: style.drag_auto_scroll_max_distance,
+            scroll_offset_x: 0.0,
+            cursor_width: style.cursor_width,
+            padding_left: style.padding.left,
             cursor_blink_state_on: false,
-            cursor_blink_last_toggle_instant: Instant::now(),
+            cursor_blink_reset_secs: 0.0,
+            cursor_blink_reset_pending: false,
             cursor_blink_interval: style.cursor_blink_interval,
             pointer_hovered: false,
             select_all_when_focused,
@@ -452,6 +540,10 @@ impl TextInputInner {
         &self.placeholder_text
     }
 
+    pub fn password_mode(&self) -> bool {
+        self.password_buffer.is_some()
+    }
+
     pub fn max_characters(&self) -> usize {
         self.max_characters
     }
@@ -488,20 +580,62 @@ impl TextInputInner {
             );
         }
 
+        self.preedit_buffer
+            .set_text_and_props(&self.preedit_text, text_properties, font_system);
+        self.preedit_width = if self.preedit_text.is_empty() {
+            0.0
+        } else {
+            self.preedit_buffer.measure().width
+        };
+
         self.cursor_blink_interval = style.cursor_blink_interval;
+        self.drag_auto_scroll_speed = style.drag_auto_scroll_speed;
+        self.drag_auto_scroll_max_distance = style.drag_auto_scroll_max_distance;
+        self.cursor_width = style.cursor_width;
+        self.padding_left = style.padding.left;
     }
 
-    pub fn on_animation(&mut self) -> TextInputUpdateResult {
+    /// * `animation_clock_secs` - The animation clock shared by every element
+    /// in the window (see `ElementContext::animation_clock_secs`). The cursor
+    /// blinks in phase with this clock so that all cursors blink in sync.
+    pub fn on_animation(
+        &mut self,
+        delta_seconds: f64,
+        animation_clock_secs: f64,
+        font_system: &mut FontSystem,
+    ) -> TextInputUpdateResult {
         let mut res = TextInputUpdateResult::default();
 
+        self.apply_cursor_blink_reset(animation_clock_secs);
+
         if !self.focused {
             return res;
         }
 
-        if self.cursor_blink_last_toggle_instant.elapsed() >= self.cursor_blink_interval {
-            self.cursor_blink_state_on = !self.cursor_blink_state_on;
-            self.cursor_blink_last_toggle_instant = Instant::now();
-            res.needs_repaint = true;
+        if self.dragging {
+            self.auto_scroll_drag(delta_seconds, font_system, &mut res);
+        }
+
+        if let Some(interval) = self.cursor_blink_interval {
+            let interval = interval.as_secs_f64();
+
+            // The cursor stays solid for at least one interval after it was
+            // reset, and then it follows the phase of the shared clock.
+            let on = animation_clock_secs < self.cursor_blink_reset_secs + interval
+                || interval <= 0.0
+                || (animation_clock_secs / interval).floor() as u64 % 2 == 0;
+
+            if self.cursor_blink_state_on != on {
+                self.cursor_blink_state_on = on;
+                res.needs_repaint = true;
+            }
+        } else {
+            // The cursor is solid, so there is nothing left to animate
+            // once dragging has stopped.
+            self.cursor_blink_state_on = true;
+            if !self.dragging {
+                res.set_animating = Some(false);
+            }
         }
 
         res
@@ -585,19 +719,8 @@ impl TextInputInner {
         self.pointer_hovered = pointer_in_bounds;
 
         if self.focused && self.dragging {
-            let (buf_x, buf_y) = pos_to_buffer_pos(position, bounds.origin, self.text_bounds_rect);
-
-            self.buffer.with_editor_mut(
-                |editor, font_system| -> EditorBorrowStatus {
-                    editor.action(font_system, Action::Drag { x: buf_x, y: buf_y });
-
-                    EditorBorrowStatus {
-                        text_changed: false,
-                        has_text: !self.text.is_empty(),
-                    }
-                },
-                font_system,
-            );
+            self.drag_pointer_pos = position - bounds.origin.to_vector();
+            self.drag_to_pointer_pos(font_system);
 
             result.hovered = true;
             result.needs_repaint = true;
@@ -629,15 +752,9 @@ impl TextInputInner {
         }
 
         if button == PointerButton::Secondary {
-            result.send_action = self.do_send_action;
-            self.do_send_action = false;
+            // The context menu is opened by the `PointerEvent::ContextMenu`
+            // event which follows this one.
             result.capture_status = EventCaptureStatus::Captured;
-            result.right_clicked_at = Some(pointer_position);
-
-            if !self.focused {
-                result.set_focus = Some(true);
-            }
-
             return result;
         } else if button != PointerButton::Primary {
             return result;
@@ -650,6 +767,14 @@ impl TextInputInner {
         }
 
         self.dragging = true;
+        self.drag_pointer_pos = pointer_position - bounds.origin.to_vector();
+        self.drag_scroll_x = 0.0;
+
+        // Auto-scrolling while dragging needs animation events even if the
+        // cursor does not blink.
+        if self.cursor_blink_interval.is_none() {
+            result.set_animating = Some(true);
+        }
         let (buf_x, buf_y) =
             pos_to_buffer_pos(pointer_position, bounds.origin, self.text_bounds_rect);
 
@@ -677,6 +802,31 @@ impl TextInputInner {
         result
     }
 
+    /// Called on `PointerEvent::ContextMenu`, i.e. on a secondary click or a
+    /// long press.
+    pub fn on_context_menu(
+        &mut self,
+        pointer_position: Point,
+        bounds: Rect,
+    ) -> TextInputUpdateResult {
+        let mut result = TextInputUpdateResult::default();
+
+        if self.disabled || !bounds.contains(pointer_position) {
+            return result;
+        }
+
+        result.send_action = self.do_send_action;
+        self.do_send_action = false;
+        result.capture_status = EventCaptureStatus::Captured;
+        result.right_clicked_at = Some(pointer_position);
+
+        if !self.focused {
+            result.set_focus = Some(true);
+        }
+
+        result
+    }
+
     pub fn on_pointer_button_just_released(
         &mut self,
         pointer_position: Point,
@@ -708,6 +858,7 @@ impl TextInputInner {
     pub fn on_keyboard_event(
         &mut self,
         event: &KeyboardEvent,
+        animation_clock_secs: f64,
         clipboard: &mut Clipboard,
         font_system: &mut FontSystem,
     ) -> TextInputUpdateResult {
@@ -895,12 +1046,15 @@ impl TextInputInner {
             self.layout_contents(font_system);
         }
 
+        self.apply_cursor_blink_reset(animation_clock_secs);
+
         result
     }
 
     pub fn on_text_composition_event(
         &mut self,
         event: &CompositionEvent,
+        animation_clock_secs: f64,
         font_system: &mut FontSystem,
     ) -> TextInputUpdateResult {
         let mut result = TextInputUpdateResult::default();
@@ -911,7 +1065,20 @@ impl TextInputInner {
 
         result.capture_status = EventCaptureStatus::Captured;
 
-        if event.data.is_empty() || self.text.len() >= self.max_characters {
+        // Preedit text is only displayed, it is not inserted into the text
+        // until the composition has ended.
+        let preedit_text = match event.state {
+            CompositionState::Start | CompositionState::Update => event.data.as_str(),
+            CompositionState::End => "",
+        };
+        if self.set_preedit_text(preedit_text, font_system) {
+            result.needs_repaint = true;
+        }
+
+        if event.state != CompositionState::End
+            || event.data.is_empty()
+            || self.text.len() >= self.max_characters
+        {
             return result;
         }
 
@@ -955,12 +1122,17 @@ impl TextInputInner {
             self.layout_contents(font_system);
         }
 
+        self.apply_cursor_blink_reset(animation_clock_secs);
+
         result
     }
 
+    /// * `animation_clock_secs` - The animation clock shared by every element
+    /// in the window (see `ElementContext::animation_clock_secs`).
     pub fn on_focus_changed(
         &mut self,
         has_focus: bool,
+        animation_clock_secs: f64,
         clipboard: &mut Clipboard,
         font_system: &mut FontSystem,
     ) -> TextInputUpdateResult {
@@ -969,7 +1141,7 @@ impl TextInputInner {
         if has_focus {
             result.listen_to_pointer_clicked_off = true;
             self.cursor_blink_state_on = true;
-            self.cursor_blink_last_toggle_instant = Instant::now();
+            self.cursor_blink_reset_pending = true;
             self.focused = true;
 
             if self.select_all_when_focused && !self.text.is_empty() {
@@ -981,9 +1153,12 @@ impl TextInputInner {
             if result.needs_repaint {
                 self.layout_contents(font_system);
             }
+
+            self.apply_cursor_blink_reset(animation_clock_secs);
         } else {
             self.focused = false;
             self.dragging = false;
+            self.set_preedit_text("", font_system);
 
             if self.do_send_action {
                 self.do_send_action = false;
@@ -991,7 +1166,9 @@ impl TextInputInner {
             }
         }
 
-        result.set_animating = Some(has_focus);
+        // A solid cursor doesn't need animation events.
+        result.set_animating =
+            Some(has_focus && (self.cursor_blink_interval.is_some() || self.dragging));
         result.needs_repaint = true;
 
         result
@@ -1008,6 +1185,60 @@ impl TextInputInner {
         result
     }
 
+    /// Move the selection to the last drag position of the pointer, offset by
+    /// the auto-scroll amount.
+    fn drag_to_pointer_pos(&mut self, font_system: &mut FontSystem) {
+        let (buf_x, buf_y) = pos_to_buffer_pos(
+            self.drag_pointer_pos + Vector::new(self.drag_scroll_x, 0.0),
+            Point::zero(),
+            self.text_bounds_rect,
+        );
+
+        self.buffer.with_editor_mut(
+            |editor, font_system| -> EditorBorrowStatus {
+                editor.action(font_system, Action::Drag { x: buf_x, y: buf_y });
+
+                EditorBorrowStatus {
+                    text_changed: false,
+                    has_text: !self.text.is_empty(),
+                }
+            },
+            font_system,
+        );
+    }
+
+    /// Scroll the text while the pointer is dragged past the left or right
+    /// edge of the text, proportional to how far past the edge it is.
+    fn auto_scroll_drag(
+        &mut self,
+        delta_seconds: f64,
+        font_system: &mut FontSystem,
+        result: &mut TextInputUpdateResult,
+    ) {
+        let x = self.drag_pointer_pos.x;
+        let past_edge = if x > self.text_bounds_rect.max_x() {
+            x - self.text_bounds_rect.max_x()
+        } else if x < self.text_bounds_rect.min_x() {
+            x - self.text_bounds_rect.min_x()
+        } else {
+            return;
+        };
+
+        let max_distance = self.drag_auto_scroll_max_distance;
+        let speed = past_edge.clamp(-max_distance, max_distance) * self.drag_auto_scroll_speed;
+
+        // Don't scroll past the start or end of the text.
+        let new_scroll_x =
+            (self.drag_scroll_x + (speed * delta_seconds as f32)).clamp(0.0, self.max_scroll_x());
+
+        if new_scroll_x != self.drag_scroll_x {
+            self.drag_scroll_x = new_scroll_x;
+            self.drag_to_pointer_pos(font_system);
+            self.layout_contents(font_system);
+            result.needs_repaint = true;
+        }
+    }
+
     pub fn queue_action(&mut self, action: TextInputAction) {
         self.queued_actions.push(action);
     }
@@ -1136,13 +1367,102 @@ impl TextInputInner {
         }
     }
 
+    /// Returns `true` if the preedit text has changed.
+    fn set_preedit_text(&mut self, text: &str, font_system: &mut FontSystem) -> bool {
+        if self.preedit_text == text {
+            return false;
+        }
+
+        self.preedit_text = String::from(text);
+        self.preedit_buffer.set_text(text, font_system);
+        self.preedit_width = if text.is_empty() {
+            0.0
+        } else {
+            self.preedit_buffer.measure().width
+        };
+
+        true
+    }
+
+    /// The maximum horizontal scroll offset, where the end of the text is
+    /// aligned with the right edge of the text bounds.
+    fn max_scroll_x(&self) -> f32 {
+        (self.buffer.measure().width - self.text_bounds_rect.width()).max(0.0)
+    }
+
+    /// The current horizontal scroll offset of the text in points.
+    ///
+    /// While focused, the text is scrolled to keep the cursor in view.
+    /// Otherwise, the offset set with [`TextInputInner::set_scroll_offset_x`]
+    /// is used.
+    pub fn scroll_offset_x(&self) -> f32 {
+        self.scroll_x()
+    }
+
+    /// Set the horizontal scroll offset to use while the element is not
+    /// focused, clamped to the range of the text.
+    ///
+    /// Returns `true` if the offset has changed.
+    pub fn set_scroll_offset_x(&mut self, scroll_offset_x: f32) -> bool {
+        let scroll_offset_x = scroll_offset_x.clamp(0.0, self.max_scroll_x());
+
+        if self.scroll_offset_x != scroll_offset_x {
+            self.scroll_offset_x = scroll_offset_x;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn scroll_x(&self) -> f32 {
+        if !self.focused {
+            // The text may have been made shorter since the offset was set.
+            return self.scroll_offset_x.min(self.max_scroll_x());
+        }
+
+        let cursor_max_x =
+            self.cursor_x + self.preedit_width + (self.cursor_width * 0.5) + self.padding_left;
+        if cursor_max_x >= self.text_bounds_rect.max_x() {
+            cursor_max_x - self.text_bounds_rect.max_x()
+        } else {
+            0.0
+        }
+    }
+
+    /// The area of the text cursor relative to the element's bounds. This is
+    /// used to position the OS IME candidate window.
+    pub fn ime_cursor_area(&self, style: &TextInputStyle, text_offset: Vector) -> Rect {
+        let highlight_height = self.text_bounds_rect.height()
+            + style.highlight_padding.top
+            + style.highlight_padding.bottom;
+        let highlight_y = self.text_bounds_rect.min_y() - style.highlight_padding.top;
+
+        Rect::new(
+            Point::new(
+                self.text_bounds_rect.min_x() + self.cursor_x + self.preedit_width
+                    - self.scroll_x(),
+                highlight_y,
+            ) + text_offset,
+            Size::new(style.cursor_width, highlight_height),
+        )
+    }
+
+    /// Start the solid interval of the cursor at the given animation clock if
+    /// the cursor was reset since the clock was last passed in.
+    fn apply_cursor_blink_reset(&mut self, animation_clock_secs: f64) {
+        if self.cursor_blink_reset_pending {
+            self.cursor_blink_reset_pending = false;
+            self.cursor_blink_reset_secs = animation_clock_secs;
+        }
+    }
+
     fn layout_contents(&mut self, font_system: &mut FontSystem) {
         self.cursor_x = 0.0;
         self.select_highlight_range = None;
 
         if self.focused {
             self.cursor_blink_state_on = true;
-            self.cursor_blink_last_toggle_instant = Instant::now();
+            self.cursor_blink_reset_pending = true;
         }
 
         if let Some(password_buffer) = self.password_buffer.as_mut() {
@@ -1245,6 +1565,8 @@ impl TextInputInner {
             back_quad: None,
             highlight_range: None,
             text: None,
+            preedit_text: None,
+            preedit_underline: None,
             cursor: None,
         };
 
@@ -1330,16 +1652,7 @@ impl TextInputInner {
             + style.highlight_padding.bottom;
         let highlight_y = self.text_bounds_rect.min_y() - style.highlight_padding.top;
 
-        let scroll_x = if self.focused {
-            let cursor_max_x = self.cursor_x + (style.cursor_width * 0.5) + style.padding.left;
-            if cursor_max_x >= self.text_bounds_rect.max_x() {
-                cursor_max_x - self.text_bounds_rect.max_x()
-            } else {
-                0.0
-            }
-        } else {
-            0.0
-        };
+        let scroll_x = self.scroll_x();
 
         if self.focused {
             if let Some((start_x, end_x)) = self.select_highlight_range {
@@ -1426,11 +1739,47 @@ impl TextInputInner {
             }
         }
 
+        if self.focused && !self.preedit_text.is_empty() {
+            let preedit_x = self.text_bounds_rect.min_x() + self.cursor_x - scroll_x;
+            let visible_width = self
+                .preedit_width
+                .min(self.text_bounds_rect.max_x() - preedit_x)
+                .max(0.0);
+            let color = style.text_color_focused.unwrap_or(style.text_color);
+
+            primitives.preedit_text = Some(TextPrimitive {
+                buffer: Some(self.preedit_buffer.clone()),
+                pos: Point::new(preedit_x, self.text_bounds_rect.min_y())
+                    + text_offset
+                    + bounds.origin.to_vector(),
+                color,
+                clipping_bounds: Some(Rect::new(
+                    bounds.origin,
+                    Size::new(visible_width, self.text_bounds_rect.height()),
+                )),
+                #[cfg(feature = "svg-icons")]
+                icons: SmallVec::new(),
+            });
+
+            if visible_width > 0.0 {
+                primitives.preedit_underline = Some(
+                    SolidQuadBuilder::new(Size::new(visible_width, 1.0))
+                        .position(Point::new(
+                            preedit_x + text_offset.x + bounds.min_x(),
+                            self.text_bounds_rect.max_y() + text_offset.y + bounds.min_y(),
+                        ))
+                        .bg_color(color)
+                        .flags(style.quad_flags)
+                        .into(),
+                );
+            }
+        }
+
         if self.focused && self.cursor_blink_state_on {
             primitives.cursor = Some(
                 SolidQuadBuilder::new(Size::new(style.cursor_width, highlight_height))
                     .position(Point::new(
-                        (self.text_bounds_rect.min_x() + self.cursor_x
+                        (self.text_bounds_rect.min_x() + self.cursor_x + self.preedit_width
                             - (style.cursor_width * 0.5)
                             - scroll_x
                             + bounds.min_x())
@@ -1463,6 +1812,8 @@ pub struct TextInputPrimitives {
     pub back_quad: Option<QuadPrimitive>,
     pub highlight_range: Option<SolidQuadPrimitive>,
     pub text: Option<TextPrimitive>,
+    pub preedit_text: Option<TextPrimitive>,
+    pub preedit_underline: Option<SolidQuadPrimitive>,
     pub cursor: Option<SolidQuadPrimitive>,
 }
 
@@ -1508,3 +1859,184 @@ fn text_to_password_text(buffer: &RcTextBuffer) -> String {
         String::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_drag_past_edge_auto_scrolls_over_animation_frames() {
+        let mut font_system = FontSystem::new();
+        let style = TextInputStyle::default();
+        let bounds = Rect::new(Point::new(50.0, 50.0), Size::new(100.0, 30.0));
+
+        let mut inner = TextInputInner::new(
+            "The quick brown fox jumps over the lazy dog. ".repeat(4),
+            String::new(),
+            false,
+            usize::MAX,
+            bounds.size,
+            false,
+            false,
+            &style,
+            &mut font_system,
+        );
+
+        assert!(inner.buffer.measure().width > inner.text_bounds_rect.width());
+
+        inner.focused = true;
+        inner.on_pointer_button_just_pressed(
+            Point::new(60.0, 60.0),
+            PointerButton::Primary,
+            1,
+            bounds,
+            &mut font_system,
+        );
+
+        // Pointer inside the text bounds does not scroll.
+        inner.on_animation(1.0 / 60.0, 0.0, &mut font_system);
+        assert_eq!(inner.drag_scroll_x, 0.0);
+
+        // Drag 20 points past the right edge.
+        inner.on_pointer_moved(Point::new(170.0, 60.0), bounds, &mut font_system);
+
+        let mut prev_scroll_x = inner.drag_scroll_x;
+        for _ in 0..5 {
+            let res = inner.on_animation(1.0 / 60.0, 0.0, &mut font_system);
+            assert!(res.needs_repaint);
+            assert!(inner.drag_scroll_x > prev_scroll_x);
+            prev_scroll_x = inner.drag_scroll_x;
+        }
+
+        // The scroll speed is proportional to the distance past the edge.
+        let expected = 5.0 * 20.0 * style.drag_auto_scroll_speed / 60.0;
+        assert!((inner.drag_scroll_x - expected).abs() < 0.01);
+
+        // Moving back inside stops the auto-scroll.
+        inner.on_pointer_moved(Point::new(100.0, 60.0), bounds, &mut font_system);
+        inner.on_animation(1.0 / 60.0, 0.0, &mut font_system);
+        assert_eq!(inner.drag_scroll_x, prev_scroll_x);
+
+        // Releasing the pointer ends the drag.
+        inner.on_pointer_button_just_released(
+            Point::new(170.0, 60.0),
+            PointerButton::Primary,
+            bounds,
+        );
+        inner.on_pointer_moved(Point::new(170.0, 60.0), bounds, &mut font_system);
+        inner.on_animation(1.0 / 60.0, 0.0, &mut font_system);
+        assert_eq!(inner.drag_scroll_x, prev_scroll_x);
+    }
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_set_scroll_offset_x_shifts_visible_text() {
+        let mut font_system = FontSystem::new();
+        let style = TextInputStyle::default();
+        let bounds = Rect::new(Point::new(50.0, 50.0), Size::new(100.0, 30.0));
+
+        let mut inner = TextInputInner::new(
+            "The quick brown fox jumps over the lazy dog. ".repeat(4),
+            String::new(),
+            false,
+            usize::MAX,
+            bounds.size,
+            false,
+            false,
+            &style,
+            &mut font_system,
+        );
+
+        assert!(inner.buffer.measure().width > inner.text_bounds_rect.width());
+
+        let text_x = |inner: &TextInputInner| {
+            inner
+                .create_primitives(&style, bounds, Vector::zero(), false)
+                .text
+                .unwrap()
+                .pos
+                .x
+        };
+
+        let unscrolled_x = text_x(&inner);
+        assert_eq!(inner.scroll_offset_x(), 0.0);
+
+        assert!(inner.set_scroll_offset_x(30.0));
+        assert!(!inner.set_scroll_offset_x(30.0));
+        assert_eq!(inner.scroll_offset_x(), 30.0);
+        assert_eq!(text_x(&inner), unscrolled_x - 30.0);
+
+        // The offset is clamped to the range of the text.
+        inner.set_scroll_offset_x(f32::MAX);
+        assert_eq!(inner.scroll_offset_x(), inner.max_scroll_x());
+        inner.set_scroll_offset_x(-10.0);
+        assert_eq!(inner.scroll_offset_x(), 0.0);
+    }
+
+    #[test]
+    fn test_solid_cursor_does_not_animate() {
+        let mut font_system = FontSystem::new();
+        let mut clipboard = Clipboard {
+            state: crate::clipboard::State::Unavailable,
+        };
+        let bounds = Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 30.0));
+
+        let mut new_focused_inner =
+            |style: &TextInputStyle, animation_clock_secs: f64, font_system: &mut FontSystem| {
+                let mut inner = TextInputInner::new(
+                    String::from("hello"),
+                    String::new(),
+                    false,
+                    usize::MAX,
+                    bounds.size,
+                    false,
+                    false,
+                    style,
+                    font_system,
+                );
+                let res =
+                    inner.on_focus_changed(true, animation_clock_secs, &mut clipboard, font_system);
+                (inner, res)
+            };
+
+        let solid_style = TextInputStyle {
+            cursor_blink_interval: None,
+            ..Default::default()
+        };
+        let (mut inner, res) = new_focused_inner(&solid_style, 0.0, &mut font_system);
+        assert_eq!(res.set_animating, Some(false));
+
+        // More than a blink interval has passed.
+        let res = inner.on_animation(1.0, 1.5, &mut font_system);
+        assert!(!res.needs_repaint);
+        assert_eq!(res.set_animating, Some(false));
+        assert!(inner
+            .create_primitives(&solid_style, bounds, Vector::zero(), false)
+            .cursor
+            .is_some());
+
+        // A blinking cursor keeps the element animating and toggles off.
+        let blink_style = TextInputStyle::default();
+        let (mut inner, res) = new_focused_inner(&blink_style, 0.0, &mut font_system);
+        assert_eq!(res.set_animating, Some(true));
+
+        // The default interval is 500ms, so 1.5s is in an "off" phase.
+        let res = inner.on_animation(1.0, 1.5, &mut font_system);
+        assert!(res.needs_repaint);
+        assert!(inner
+            .create_primitives(&blink_style, bounds, Vector::zero(), false)
+            .cursor
+            .is_none());
+
+        // A second cursor which was focused at a different time blinks in
+        // phase with the first one once its solid interval has passed.
+        let (mut other, _) = new_focused_inner(&blink_style, 1.2, &mut font_system);
+        assert!(other.cursor_blink_state_on);
+        other.on_animation(0.5, 1.7, &mut font_system);
+        assert!(!other.cursor_blink_state_on);
+        inner.on_animation(0.5, 2.2, &mut font_system);
+        other.on_animation(0.5, 2.2, &mut font_system);
+        assert!(inner.cursor_blink_state_on && other.cursor_blink_state_on);
+    }
+}
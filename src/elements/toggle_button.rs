@@ -11,8 +11,14 @@ use super::label::{LabelInner, LabelPaddingInfo, LabelPrimitives};
 
 /// The style of a [`ToggleButton`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct ToggleButtonStyle {
     /// The properties of the text
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
 
     /// The width and height of the icon in points (if the user hasn't
@@ -490,6 +496,8 @@ impl ToggleButtonStyle {
             icon_padding: self.icon_padding,
             text_icon_spacing: self.text_icon_spacing,
             vertical_align: Align::Center,
+            horizontal_align: None,
+            truncate_with_ellipsis: false,
         }
     }
 }
@@ -507,6 +515,49 @@ impl ElementStyle for ToggleButtonStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.text_color_on.multiply_alpha(multiplier);
+        self.text_color_on_hover.multiply_alpha(multiplier);
+        self.text_color_on_down.multiply_alpha(multiplier);
+        self.text_color_on_disabled.multiply_alpha(multiplier);
+        self.text_color_off_hover.multiply_alpha(multiplier);
+        self.text_color_off_down.multiply_alpha(multiplier);
+        self.text_color_off_disabled.multiply_alpha(multiplier);
+        self.icon_color.multiply_alpha(multiplier);
+        self.icon_color_on.multiply_alpha(multiplier);
+        self.icon_color_on_hover.multiply_alpha(multiplier);
+        self.icon_color_on_down.multiply_alpha(multiplier);
+        self.icon_color_on_disabled.multiply_alpha(multiplier);
+        self.icon_color_off_hover.multiply_alpha(multiplier);
+        self.icon_color_off_down.multiply_alpha(multiplier);
+        self.icon_color_off_disabled.multiply_alpha(multiplier);
+        self.back_bg.multiply_alpha(multiplier);
+        self.back_bg_on.multiply_alpha(multiplier);
+        self.back_bg_on_hover.multiply_alpha(multiplier);
+        self.back_bg_on_down.multiply_alpha(multiplier);
+        self.back_bg_on_disabled.multiply_alpha(multiplier);
+        self.back_bg_off_hover.multiply_alpha(multiplier);
+        self.back_bg_off_down.multiply_alpha(multiplier);
+        self.back_bg_off_disabled.multiply_alpha(multiplier);
+        self.back_border_color.multiply_alpha(multiplier);
+        self.back_border_color_on.multiply_alpha(multiplier);
+        self.back_border_color_on_hover.multiply_alpha(multiplier);
+        self.back_border_color_on_down.multiply_alpha(multiplier);
+        self.back_border_color_on_disabled
+            .multiply_alpha(multiplier);
+        self.back_border_color_off_hover.multiply_alpha(multiplier);
+        self.back_border_color_off_down.multiply_alpha(multiplier);
+        self.back_border_color_off_disabled
+            .multiply_alpha(multiplier);
+    }
 }
 
 /// A reusable button struct that can be used by other elements.
@@ -863,6 +914,9 @@ impl<A: Clone + 'static> Element<A> for ToggleButtonElement<A> {
             ElementEvent::StyleChanged => {
                 let style = cx.res.style_system.get::<ToggleButtonStyle>(cx.class());
                 self.cursor_icon = style.cursor_icon;
+                shared_state
+                    .inner
+                    .sync_new_style(style, &mut cx.res.font_system);
             }
             ElementEvent::Pointer(PointerEvent::Moved { .. }) => {
                 if shared_state.inner.state == ButtonState::Disabled {
@@ -907,8 +961,7 @@ impl<A: Clone + 'static> Element<A> for ToggleButtonElement<A> {
                     cx.request_repaint();
 
                     if let Some(action) = &mut self.action {
-                        cx.send_action((action)(shared_state.inner.toggled))
-                            .unwrap();
+                        cx.send_action((action)(shared_state.inner.toggled));
                     }
 
                     return EventCaptureStatus::Captured;
@@ -921,7 +974,7 @@ impl<A: Clone + 'static> Element<A> for ToggleButtonElement<A> {
                     && (shared_state.inner.state == ButtonState::Down
                         || shared_state.inner.state == ButtonState::Hovered)
                 {
-                    let new_state = if cx.is_point_within_visible_bounds(position) {
+                    let new_state = if cx.is_point_within_hit_bounds(position) {
                         ButtonState::Hovered
                     } else {
                         ButtonState::Idle
@@ -947,7 +1000,9 @@ impl<A: Clone + 'static> Element<A> for ToggleButtonElement<A> {
 
         let label_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
-            cx.res.style_system.get(cx.class),
+            &cx.res
+                .style_system
+                .get_with_opacity::<ToggleButtonStyle>(cx.class, cx.opacity),
             &mut cx.res.font_system,
         );
 
@@ -1,18 +1,45 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
 
 use crate::derive::*;
 use crate::prelude::*;
 
 use super::label::LabelInner;
 
+/// Where to place a tooltip
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TooltipPositioning {
+    /// Align the tooltip relative to the bounds of the element
+    AnchoredToElement(Align2),
+    /// Place the tooltip below and to the right of the pointer, offset by the
+    /// given amount, and move it along with the pointer
+    FollowCursor { offset: Vector },
+}
+
+impl Default for TooltipPositioning {
+    fn default() -> Self {
+        Self::AnchoredToElement(Align2::default())
+    }
+}
+
 /// Tooltip data assigned to an element
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct TooltipData {
     /// The tooltip text
     pub text: String,
-    /// Where to align the tooltip relative to this element
-    pub align: Align2,
+    /// Where to place the tooltip
+    pub positioning: TooltipPositioning,
+    /// An optional icon to show alongside the text
+    pub icon: Option<IconID>,
+    /// An optional secondary line of text, i.e. the current value of a
+    /// parameter
+    pub description: Option<String>,
+    /// How long the pointer must hover over the element before the tooltip
+    /// is shown
+    ///
+    /// If this is `None`, then the window's `hover_timeout_duration` is used.
+    pub delay: Option<Duration>,
 }
 
 impl TooltipData {
@@ -23,9 +50,81 @@ impl TooltipData {
     pub fn new(text: impl Into<String>, align: Align2) -> Self {
         Self {
             text: text.into(),
-            align,
+            positioning: TooltipPositioning::AnchoredToElement(align),
+            icon: None,
+            description: None,
+            delay: None,
         }
     }
+
+    /// Construct tooltip data for an element which follows the pointer
+    ///
+    /// * `text` - The tooltip text
+    /// * `offset` - The offset of the tooltip from the pointer
+    pub fn follow_cursor(text: impl Into<String>, offset: Vector) -> Self {
+        Self {
+            text: text.into(),
+            positioning: TooltipPositioning::FollowCursor { offset },
+            icon: None,
+            description: None,
+            delay: None,
+        }
+    }
+
+    /// Show an icon alongside the text
+    pub fn with_icon(mut self, icon: IconID) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Add a secondary line of text
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Override how long the pointer must hover over the element before the
+    /// tooltip is shown (i.e. `Duration::ZERO` for toolbar buttons)
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// Custom content of a [`Tooltip`] which is drawn by the application, such as
+/// a small preview of the hovered item.
+#[derive(Clone)]
+pub struct TooltipContent {
+    /// The size of the content, not including the padding of the tooltip.
+    pub size: Size,
+    render: Rc<RefCell<dyn FnMut(Rect, &mut PrimitiveGroup)>>,
+}
+
+impl TooltipContent {
+    /// Construct custom tooltip content
+    ///
+    /// * `size` - The size of the content, not including the padding of the
+    /// tooltip.
+    /// * `render` - Adds the primitives of the content. The given rectangle
+    /// is the bounds of the content relative to the tooltip.
+    pub fn new<F: FnMut(Rect, &mut PrimitiveGroup) + 'static>(size: Size, render: F) -> Self {
+        Self {
+            size,
+            render: Rc::new(RefCell::new(render)),
+        }
+    }
+
+    fn render(&self, bounds: Rect, primitives: &mut PrimitiveGroup) {
+        (RefCell::borrow_mut(&self.render))(bounds, primitives);
+    }
+}
+
+impl std::fmt::Debug for TooltipContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TooltipContent")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
 }
 
 /// A struct that can be used by elements to simplify tooltip handling
@@ -47,9 +146,10 @@ impl TooltipInner {
 
         if let Some(old_data) = &mut self.data {
             if let Some(text) = text {
-                if old_data.text.as_str() != text.as_ref() || old_data.align != align {
+                let positioning = TooltipPositioning::AnchoredToElement(align);
+                if old_data.text.as_str() != text.as_ref() || old_data.positioning != positioning {
                     old_data.text = text.into();
-                    old_data.align = align;
+                    old_data.positioning = positioning;
                     state_changed = true;
                 }
             } else {
@@ -57,10 +157,7 @@ impl TooltipInner {
                 state_changed = true;
             }
         } else if let Some(text) = text {
-            self.data = Some(TooltipData {
-                text: text.into(),
-                align,
-            });
+            self.data = Some(TooltipData::new(text, align));
             state_changed = true;
         }
 
@@ -80,7 +177,10 @@ impl TooltipInner {
         match event {
             ElementEvent::Pointer(PointerEvent::Moved { just_entered, .. }) => {
                 if *just_entered {
-                    cx.start_hover_timeout();
+                    match self.data.as_ref().and_then(|data| data.delay) {
+                        Some(delay) => cx.start_hover_timeout_with_delay(delay),
+                        None => cx.start_hover_timeout(),
+                    }
                 }
             }
             ElementEvent::Pointer(PointerEvent::HoverTimeout { .. }) => {
@@ -93,8 +193,14 @@ impl TooltipInner {
 
 /// The style of a [`Tooltip`] element
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TooltipStyle {
     /// The properties of the text.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub text_properties: TextProperties,
 
     /// The color of the text
@@ -151,13 +257,29 @@ impl ElementStyle for TooltipStyle {
             ..Default::default()
         }
     }
+
+    fn with_text_scale(&self, text_scale: f32) -> Option<Self> {
+        let mut style = self.clone();
+        scale_text_properties(&mut style.text_properties, text_scale);
+        Some(style)
+    }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.text_color.multiply_alpha(multiplier);
+        self.back_quad.multiply_alpha(multiplier);
+    }
 }
 
+/// The default z index of a [`Tooltip`] when no z index is set on its
+/// builder, which is the base of [`ZLayer::Tooltip`].
+pub const DEFAULT_TOOLTIP_Z_INDEX: ZIndex = ZLayer::Tooltip.base();
+
 #[element_builder]
 #[element_builder_class]
 pub struct TooltipBuilder {
     pub text_offset: Vector,
     pub element_padding: Padding,
+    pub interactive: bool,
 }
 
 impl TooltipBuilder {
@@ -166,6 +288,7 @@ impl TooltipBuilder {
             text_offset: Vector::default(),
             class: None,
             element_padding: Padding::new(10.0, 10.0, 10.0, 10.0),
+            interactive: false,
             z_index: None,
             scissor_rect: None,
         }
@@ -186,11 +309,26 @@ impl TooltipBuilder {
         self
     }
 
+    /// Whether or not the pointer can be moved onto the tooltip to interact
+    /// with it.
+    ///
+    /// If this is `true`, then the tooltip stays open while the pointer is
+    /// over it, and it captures the pointer events inside of its bounds.
+    /// Elements placed on top of the tooltip's rectangle (with a higher z
+    /// index) can be used for interactive content.
+    ///
+    /// By default this is set to `false`.
+    pub const fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
     pub fn build<A: Clone + 'static>(self, window_cx: &mut WindowContext<'_, A>) -> Tooltip {
         let TooltipBuilder {
             text_offset,
             class,
             element_padding,
+            interactive,
             z_index,
             scissor_rect,
         } = self;
@@ -213,15 +351,29 @@ impl TooltipBuilder {
                 &mut window_cx.res.font_system,
             ),
             show_with_info: None,
+            content: None,
         }));
 
+        let flags = if interactive {
+            ElementFlags::PAINTS
+                | ElementFlags::KEEPS_TOOLTIP_OPEN
+                | ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS
+        } else {
+            ElementFlags::PAINTS
+        };
+
         let el = ElementBuilder::new(TooltipElement {
             shared_state: Rc::clone(&shared_state),
             element_padding,
         })
-        .builder_values(z_index, scissor_rect, class, window_cx)
+        .builder_values(
+            Some(z_index.unwrap_or(DEFAULT_TOOLTIP_Z_INDEX)),
+            scissor_rect,
+            class,
+            window_cx,
+        )
         .hidden(true)
-        .flags(ElementFlags::PAINTS)
+        .flags(flags)
         .build(window_cx);
 
         Tooltip { el, shared_state }
@@ -247,16 +399,30 @@ impl<A: Clone + 'static> Element<A> for TooltipElement {
                 let SharedState {
                     inner,
                     show_with_info,
+                    content,
                 } = &mut *shared_state;
 
                 if let Some((element_rect, align)) = show_with_info.take() {
-                    let size = inner.desired_size(|| {
-                        cx.res
+                    let size = if let Some(content) = content {
+                        let padding = cx
+                            .res
                             .style_system
                             .get::<TooltipStyle>(cx.class())
-                            .label_style()
-                            .padding_info()
-                    });
+                            .text_padding;
+
+                        Size::new(
+                            content.size.width + padding.left + padding.right,
+                            content.size.height + padding.top + padding.bottom,
+                        )
+                    } else {
+                        inner.desired_size(|| {
+                            cx.res
+                                .style_system
+                                .get::<TooltipStyle>(cx.class())
+                                .label_style()
+                                .padding_info()
+                        })
+                    };
 
                     let origin =
                         align.align_floating_element(element_rect, size, self.element_padding);
@@ -280,6 +446,8 @@ impl<A: Clone + 'static> Element<A> for TooltipElement {
                     cx.set_rect(rect);
                 }
             }
+            // Only interactive tooltips listen to pointer events.
+            ElementEvent::Pointer(_) => return EventCaptureStatus::Captured,
             _ => {}
         }
 
@@ -288,7 +456,32 @@ impl<A: Clone + 'static> Element<A> for TooltipElement {
 
     fn render(&mut self, cx: RenderContext, primitives: &mut PrimitiveGroup) {
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
-        let style: &TooltipStyle = cx.res.style_system.get(cx.class);
+        let style = cx
+            .res
+            .style_system
+            .get_with_opacity::<TooltipStyle>(cx.class, cx.opacity);
+
+        if let Some(content) = &shared_state.content {
+            let bounds = Rect::from_size(cx.bounds_size);
+
+            if !style.back_quad.is_transparent() {
+                primitives.add(style.back_quad.create_primitive(bounds));
+            }
+
+            let padding = style.text_padding;
+            let content_bounds = Rect::new(
+                Point::new(padding.left, padding.top),
+                Size::new(
+                    (bounds.width() - padding.left - padding.right).max(0.0),
+                    (bounds.height() - padding.top - padding.bottom).max(0.0),
+                ),
+            );
+
+            primitives.set_z_index(1);
+            content.render(content_bounds, primitives);
+
+            return;
+        }
 
         let label_primitives = shared_state.inner.render(
             Rect::from_size(cx.bounds_size),
@@ -304,12 +497,18 @@ impl<A: Clone + 'static> Element<A> for TooltipElement {
             primitives.set_z_index(1);
             primitives.add_text(text_primitive);
         }
+
+        if let Some(icon_primitive) = label_primitives.icon {
+            primitives.set_z_index(1);
+            primitives.add_text(icon_primitive);
+        }
     }
 }
 
 struct SharedState {
     inner: LabelInner,
     show_with_info: Option<(Rect, Align2)>,
+    content: Option<TooltipContent>,
 }
 
 /// A handle to a [`TooltipElement`]
@@ -330,6 +529,28 @@ impl Tooltip {
         align: Align2,
         element_bounds: Rect,
         res: &mut ResourceCtx,
+    ) {
+        self.show_with_icon(text, None, align, element_bounds, res);
+    }
+
+    /// Show the tooltip for the given info sent by an element, including its
+    /// icon and its description (as a second line of text), if any.
+    pub fn show_info(&mut self, info: &TooltipInfo, res: &mut ResourceCtx) {
+        let text = match &info.description {
+            Some(description) => format!("{}\n{}", info.text, description),
+            None => info.text.clone(),
+        };
+
+        self.show_with_icon(text, info.icon, info.align, info.element_bounds, res);
+    }
+
+    fn show_with_icon<T: AsRef<str> + Into<String>>(
+        &mut self,
+        text: T,
+        icon: Option<IconID>,
+        align: Align2,
+        element_bounds: Rect,
+        res: &mut ResourceCtx,
     ) {
         let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
@@ -340,15 +561,31 @@ impl Tooltip {
                     .get::<TooltipStyle>(self.el.class())
                     .text_properties
             });
+        shared_state.inner.set_icon(icon);
+
+        shared_state.show_with_info = Some((element_bounds, align));
+        shared_state.content = None;
+
+        self.el.notify_custom_state_change();
+        self.el.set_hidden(false);
+    }
+
+    /// Show the tooltip with custom content drawn by the application instead
+    /// of text.
+    pub fn show_custom(&mut self, content: TooltipContent, align: Align2, element_bounds: Rect) {
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
 
         shared_state.show_with_info = Some((element_bounds, align));
+        shared_state.content = Some(content);
 
         self.el.notify_custom_state_change();
         self.el.set_hidden(false);
     }
 
     pub fn hide(&mut self) {
-        RefCell::borrow_mut(&self.shared_state).show_with_info = None;
+        let mut shared_state = RefCell::borrow_mut(&self.shared_state);
+        shared_state.show_with_info = None;
+        shared_state.content = None;
 
         self.el.set_hidden(true);
     }
@@ -373,3 +610,58 @@ impl Tooltip {
         }
     }
 }
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+    use crate::window::WindowConfig;
+    use crate::{AppConfig, AppContext, Application};
+
+    struct CustomTooltipApp {
+        _tooltip: Tooltip,
+    }
+
+    impl Application for CustomTooltipApp {
+        type Action = ();
+
+        fn init(cx: &mut AppContext<()>) -> Result<Self, Box<dyn std::error::Error>> {
+            let mut tooltip = Tooltip::builder()
+                .interactive(true)
+                .build(&mut cx.main_window());
+
+            tooltip.show_custom(
+                TooltipContent::new(Size::new(100.0, 100.0), |bounds, primitives| {
+                    primitives.add(
+                        QuadStyle {
+                            bg: Background::Solid(RGBA8::new(255, 0, 0, 255)),
+                            border: BorderStyle::TRANSPARENT,
+                            flags: QuadFlags::empty(),
+                        }
+                        .create_primitive(bounds),
+                    );
+                }),
+                Align2::TOP_CENTER,
+                Rect::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0)),
+            );
+
+            Ok(Self { _tooltip: tooltip })
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn test_custom_content_tooltip_renders_its_primitives() {
+        let config = AppConfig {
+            main_window_config: WindowConfig {
+                size: Size::new(40.0, 40.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let frames = crate::run_headless::<CustomTooltipApp>(config, 2).unwrap();
+
+        let frame = frames.last().unwrap();
+        assert_eq!(frame.pixel(20, 20), Some([255, 0, 0, 255]));
+    }
+}
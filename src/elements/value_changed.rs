@@ -0,0 +1,106 @@
+//! A single callback name for controls which hold a value.
+//!
+//! Each control names its callback after what the user does with it
+//! (`on_toggled`, `on_selected`, `on_changed`, ...). When mapping many
+//! controls to a single action enum it is often simpler to use the same
+//! name everywhere, so every such control builder also implements
+//! [`ValueChangedBuilder`]:
+//!
+//! ```ignore
+//! #[derive(Clone)]
+//! enum Action {
+//!     Mute(bool),
+//!     Mode(usize),
+//!     Gain(f32),
+//! }
+//!
+//! let mute = Checkbox::builder().on_value_changed(Action::Mute);
+//! let mode = DropDown::builder().on_value_changed(Action::Mode);
+//! let gain = Knob::builder("gain").on_value_changed(Action::Gain);
+//! ```
+//!
+//! The convention is that the callback is called with the new value of the
+//! control every time the *user* changes it, and that the type of the value
+//! is the same type used by the control's handle to get and set the value.
+//! Setting the value from the handle never calls the callback.
+
+use crate::elements::checkbox::CheckboxBuilder;
+use crate::elements::drop_down::DropDownBuilder;
+use crate::elements::drop_down_menu::DropDownMenuBuilder;
+use crate::elements::panel::PanelBuilder;
+use crate::elements::switch::SwitchBuilder;
+use crate::elements::tab_bar::TabBarBuilder;
+#[cfg(feature = "svg-icons")]
+use crate::elements::text_input::IconTextInputBuilder;
+use crate::elements::text_input::TextInputBuilder;
+use crate::elements::toggle_button::ToggleButtonBuilder;
+use crate::elements::virtual_slider::VirtualSliderBuilder;
+
+/// A builder of a control which holds a value that the user can change.
+///
+/// See the [module-level documentation](self) for the convention.
+pub trait ValueChangedBuilder<A: Clone + 'static>: Sized {
+    /// The type of the value held by the control.
+    type Value;
+
+    /// The action to send when the user changes the value of the control.
+    /// The new value is passed to the closure.
+    ///
+    /// This is equivalent to the control's own callback method (i.e.
+    /// `on_toggled` for a checkbox).
+    fn on_value_changed<F: FnMut(Self::Value) -> A + 'static>(self, f: F) -> Self;
+}
+
+macro_rules! impl_value_changed_builder {
+    ($builder:ident, $value:ty, $method:ident) => {
+        impl<A: Clone + 'static> ValueChangedBuilder<A> for $builder<A> {
+            type Value = $value;
+
+            fn on_value_changed<F: FnMut($value) -> A + 'static>(self, f: F) -> Self {
+                self.$method(f)
+            }
+        }
+    };
+}
+
+impl_value_changed_builder!(CheckboxBuilder, bool, on_toggled);
+impl_value_changed_builder!(SwitchBuilder, bool, on_toggled);
+impl_value_changed_builder!(ToggleButtonBuilder, bool, on_toggled);
+impl_value_changed_builder!(PanelBuilder, bool, on_toggled);
+impl_value_changed_builder!(DropDownBuilder, usize, on_selected);
+impl_value_changed_builder!(DropDownMenuBuilder, usize, on_entry_selected);
+impl_value_changed_builder!(TabBarBuilder, usize, on_selected);
+impl_value_changed_builder!(VirtualSliderBuilder, f32, on_changed);
+impl_value_changed_builder!(TextInputBuilder, String, on_changed);
+#[cfg(feature = "svg-icons")]
+impl_value_changed_builder!(IconTextInputBuilder, String, on_changed);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Action {
+        Mute(bool),
+        Mode(usize),
+        Gain(f32),
+        Name(String),
+    }
+
+    #[test]
+    fn test_controls_share_one_action_enum() {
+        let checkbox = Checkbox::builder().on_value_changed(Action::Mute);
+        let drop_down = DropDown::builder().on_value_changed(Action::Mode);
+        let knob = Knob::builder("gain").on_value_changed(Action::Gain);
+        let text_input = TextInput::builder().on_value_changed(Action::Name);
+
+        assert_eq!((checkbox.on_toggled.unwrap())(true), Action::Mute(true));
+        assert_eq!((drop_down.on_selected.unwrap())(2), Action::Mode(2));
+        assert_eq!((knob.on_changed.unwrap())(0.5), Action::Gain(0.5));
+        assert_eq!(
+            (text_input.action.unwrap())(String::from("bus")),
+            Action::Name(String::from("bus"))
+        );
+    }
+}
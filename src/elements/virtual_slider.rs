@@ -198,6 +198,25 @@ pub struct VirtualSliderConfig {
     ///
     /// By default this is set to `false`.
     pub disable_pointer_locking: bool,
+
+    /// Whether or not the arrow keys should nudge this parameter while
+    /// this element has focus.
+    ///
+    /// If this is `true`, then this element keeps focus after it has been
+    /// clicked until the user clicks elsewhere.
+    ///
+    /// By default this is set to `false`.
+    pub use_arrow_keys: bool,
+
+    /// The amount (in normalized units) to nudge a continuous (non-stepped)
+    /// parameter by when an arrow key is pressed. Stepped parameters are
+    /// always nudged by one step.
+    ///
+    /// The fine adjustment scalar is applied when the fine adjustment
+    /// modifier is held down.
+    ///
+    /// By default this is set to `0.05`.
+    pub arrow_key_step_normal: f32,
 }
 
 impl Default for VirtualSliderConfig {
@@ -215,6 +234,8 @@ impl Default for VirtualSliderConfig {
             cursor_icon_hover: None,
             cursor_icon_gesturing: None,
             disable_pointer_locking: false,
+            use_arrow_keys: false,
+            arrow_key_step_normal: 0.05,
         }
     }
 }
@@ -239,6 +260,7 @@ pub struct ParamRightClickInfo {
 #[element_builder_disabled]
 pub struct VirtualSliderBuilder<A: Clone + 'static> {
     pub on_gesture: Option<Box<dyn FnMut(ParamUpdate) -> A>>,
+    pub on_changed: Option<Box<dyn FnMut(f32) -> A>>,
     pub on_right_click: Option<Box<dyn FnMut(ParamRightClickInfo) -> A>>,
     pub on_open_text_entry: Option<Box<dyn FnMut(ParamOpenTextEntryInfo) -> A>>,
     pub on_tooltip_request: Option<Box<dyn FnMut(ParamElementTooltipInfo) -> A>>,
@@ -259,6 +281,7 @@ impl<A: Clone + 'static> VirtualSliderBuilder<A> {
     pub fn new(param_id: impl ToSmolStr) -> Self {
         Self {
             on_gesture: None,
+            on_changed: None,
             on_right_click: None,
             on_open_text_entry: None,
             on_tooltip_request: None,
@@ -287,6 +310,17 @@ impl<A: Clone + 'static> VirtualSliderBuilder<A> {
         self
     }
 
+    /// Called with the new normalized value whenever the user changes the
+    /// value.
+    ///
+    /// Unlike [`VirtualSliderBuilder::on_gesture`], this is not called when
+    /// a gesture starts or finishes without changing the value, or when the
+    /// value is set with [`VirtualSlider::set_normal_value`] and friends.
+    pub fn on_changed<F: FnMut(f32) -> A + 'static>(mut self, f: F) -> Self {
+        self.on_changed = Some(Box::new(f));
+        self
+    }
+
     pub fn on_right_click<F: FnMut(ParamRightClickInfo) -> A + 'static>(mut self, f: F) -> Self {
         self.on_right_click = Some(Box::new(f));
         self
@@ -367,6 +401,7 @@ impl<A: Clone + 'static> VirtualSliderBuilder<A> {
     ) -> VirtualSlider<R> {
         let VirtualSliderBuilder {
             on_gesture,
+            on_changed,
             on_right_click,
             on_open_text_entry,
             on_tooltip_request,
@@ -404,6 +439,9 @@ impl<A: Clone + 'static> VirtualSliderBuilder<A> {
         if renderer.does_paint() {
             flags.insert(ElementFlags::PAINTS);
         }
+        if config.use_arrow_keys {
+            flags.insert(ElementFlags::LISTENS_TO_KEYS_WHEN_FOCUSED);
+        }
 
         let shared_state = Rc::new(RefCell::new(SharedState {
             inner: VirtualSliderInner::new(
@@ -425,9 +463,15 @@ impl<A: Clone + 'static> VirtualSliderBuilder<A> {
             queued_new_val: None,
         }));
 
+        let last_normal = RefCell::borrow(&shared_state).inner.normal_value();
+
         let el = ElementBuilder::new(VirtualSliderElement {
             shared_state: Rc::clone(&shared_state),
-            on_gesture,
+            actions: ParamActions {
+                on_gesture,
+                on_changed,
+                last_normal,
+            },
             on_right_click,
             on_open_text_entry,
             on_tooltip_request,
@@ -451,10 +495,40 @@ impl<A: Clone + 'static> VirtualSliderBuilder<A> {
     }
 }
 
+struct ParamActions<A: Clone + 'static> {
+    on_gesture: Option<Box<dyn FnMut(ParamUpdate) -> A>>,
+    on_changed: Option<Box<dyn FnMut(f32) -> A>>,
+    last_normal: f64,
+}
+
+impl<A: Clone + 'static> ParamActions<A> {
+    /// Remember a value which was set by the application, so that it isn't
+    /// reported back to it with `on_changed`.
+    fn sync_normal(&mut self, normal_value: f64) {
+        self.last_normal = normal_value;
+    }
+
+    fn send(&mut self, param_update: ParamUpdate, cx: &mut ElementContext<'_, A>) {
+        let normal_value = param_update.param_info.normal_value;
+
+        if let Some(f) = self.on_gesture.as_mut() {
+            cx.send_action((f)(param_update));
+        }
+
+        if self.last_normal != normal_value {
+            self.last_normal = normal_value;
+
+            if let Some(f) = self.on_changed.as_mut() {
+                cx.send_action((f)(normal_value as f32));
+            }
+        }
+    }
+}
+
 struct VirtualSliderElement<A: Clone + 'static, R: VirtualSliderRenderer + 'static> {
     shared_state: Rc<RefCell<SharedState<R>>>,
 
-    on_gesture: Option<Box<dyn FnMut(ParamUpdate) -> A>>,
+    actions: ParamActions<A>,
     on_right_click: Option<Box<dyn FnMut(ParamRightClickInfo) -> A>>,
     on_open_text_entry: Option<Box<dyn FnMut(ParamOpenTextEntryInfo) -> A>>,
     on_tooltip_request: Option<Box<dyn FnMut(ParamElementTooltipInfo) -> A>>,
@@ -487,59 +561,55 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
             queued_new_val,
         } = &mut *shared_state;
 
-        let send_param_update =
-            |param_update: InnerParamUpdate,
-             cx: &mut ElementContext<'_, A>,
-             renderer: &mut R,
-             prev_state: Option<VirtualSliderState>,
-             state: VirtualSliderState,
-             on_gesture: &mut Option<Box<dyn FnMut(ParamUpdate) -> A>>| {
-                if let Some(f) = on_gesture.as_mut() {
-                    cx.send_action((f)(param_update.inner)).unwrap();
-                }
+        let send_param_update = |param_update: InnerParamUpdate,
+                                 cx: &mut ElementContext<'_, A>,
+                                 renderer: &mut R,
+                                 prev_state: Option<VirtualSliderState>,
+                                 state: VirtualSliderState,
+                                 actions: &mut ParamActions<A>| {
+            actions.send(param_update.inner, cx);
 
-                if renderer.does_paint() {
-                    cx.request_repaint();
-                }
+            if renderer.does_paint() {
+                cx.request_repaint();
+            }
 
-                if let Some(prev_state) = prev_state {
-                    let res = renderer.on_state_changed(prev_state, state);
-                    cx.set_animating(res.animating);
-                }
+            if let Some(prev_state) = prev_state {
+                let res = renderer.on_state_changed(prev_state, state);
+                cx.set_animating(res.animating);
+            }
 
-                if let Some(lock) = param_update.pointer_lock_request {
-                    cx.request_pointer_lock(lock);
-                }
-            };
-
-        let finish_gesture =
-            |inner: &mut VirtualSliderInner,
-             cx: &mut ElementContext<'_, A>,
-             hovered: bool,
-             state: &mut VirtualSliderState,
-             renderer: &mut R,
-             disabled: bool,
-             on_gesture: &mut Option<Box<dyn FnMut(ParamUpdate) -> A>>| {
-                if let Some(param_update) = inner.finish_gesture() {
-                    let prev_state = if disabled {
-                        let p = Some(*state);
-                        *state = VirtualSliderState::Disabled;
-                        p
-                    } else if !hovered && *state != VirtualSliderState::Idle {
-                        let p = Some(*state);
-                        *state = VirtualSliderState::Idle;
-                        p
-                    } else if hovered && *state != VirtualSliderState::Hovered {
-                        let p = Some(*state);
-                        *state = VirtualSliderState::Hovered;
-                        p
-                    } else {
-                        None
-                    };
+            if let Some(lock) = param_update.pointer_lock_request {
+                cx.request_pointer_lock(lock);
+            }
+        };
+
+        let finish_gesture = |inner: &mut VirtualSliderInner,
+                              cx: &mut ElementContext<'_, A>,
+                              hovered: bool,
+                              state: &mut VirtualSliderState,
+                              renderer: &mut R,
+                              disabled: bool,
+                              actions: &mut ParamActions<A>| {
+            if let Some(param_update) = inner.finish_gesture() {
+                let prev_state = if disabled {
+                    let p = Some(*state);
+                    *state = VirtualSliderState::Disabled;
+                    p
+                } else if !hovered && *state != VirtualSliderState::Idle {
+                    let p = Some(*state);
+                    *state = VirtualSliderState::Idle;
+                    p
+                } else if hovered && *state != VirtualSliderState::Hovered {
+                    let p = Some(*state);
+                    *state = VirtualSliderState::Hovered;
+                    p
+                } else {
+                    None
+                };
 
-                    send_param_update(param_update, cx, renderer, prev_state, *state, on_gesture);
-                }
-            };
+                send_param_update(param_update, cx, renderer, prev_state, *state, actions);
+            }
+        };
 
         match event {
             ElementEvent::Animation { delta_seconds } => {
@@ -591,7 +661,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                         &mut self.state,
                         renderer,
                         *disabled,
-                        &mut self.on_gesture,
+                        &mut self.actions,
                     );
 
                     cx.set_animating(false);
@@ -608,13 +678,15 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                 if let Some(new_val) = queued_new_val.take() {
                     if inner.value() != new_val {
                         if let Some(param_update) = inner.set_value(new_val) {
+                            self.actions.sync_normal(inner.normal_value());
+
                             send_param_update(
                                 param_update,
                                 cx,
                                 renderer,
                                 None,
                                 self.state,
-                                &mut self.on_gesture,
+                                &mut self.actions,
                             );
                         }
                     }
@@ -626,7 +698,6 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
             }
             ElementEvent::Pointer(PointerEvent::Moved {
                 position,
-                delta,
                 modifiers,
                 just_entered,
                 ..
@@ -669,17 +740,34 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                     cx.start_hover_timeout();
                 }
 
-                let delta = if let Some(delta) = delta {
-                    if cx.is_pointer_locked() {
-                        Some(delta)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
+                // Relative motion while the pointer is locked is handled in
+                // `PointerEvent::DeltaMoved`.
+                if let Some(param_update) = inner.handle_pointer_moved(position, None, modifiers) {
+                    send_param_update(
+                        InnerParamUpdate {
+                            inner: param_update,
+                            pointer_lock_request: None,
+                        },
+                        cx,
+                        renderer,
+                        None,
+                        self.state,
+                        &mut self.actions,
+                    );
+                }
+
+                if !hovered && !inner.is_gesturing() {
+                    return EventCaptureStatus::NotCaptured;
+                }
 
-                if let Some(param_update) = inner.handle_pointer_moved(position, delta, modifiers) {
+                return EventCaptureStatus::Captured;
+            }
+            ElementEvent::Pointer(PointerEvent::DeltaMoved { delta, modifiers }) => {
+                if *disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if let Some(param_update) = inner.handle_pointer_delta(delta, modifiers) {
                     send_param_update(
                         InnerParamUpdate {
                             inner: param_update,
@@ -689,7 +777,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                         renderer,
                         None,
                         self.state,
-                        &mut self.on_gesture,
+                        &mut self.actions,
                     );
                 }
 
@@ -720,7 +808,16 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                 modifiers,
                 ..
             }) => {
-                if *disabled || !cx.rect.contains(position) {
+                if *disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                if !cx.rect.contains(position) {
+                    // Clicking elsewhere releases the focus kept for the arrow keys.
+                    if cx.has_focus() && !inner.is_gesturing() {
+                        cx.release_focus();
+                    }
+
                     return EventCaptureStatus::NotCaptured;
                 }
 
@@ -742,14 +839,13 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                             &mut self.state,
                             renderer,
                             *disabled,
-                            &mut self.on_gesture,
+                            &mut self.actions,
                         );
 
                         cx.send_action((f)(ParamRightClickInfo {
                             param_info: inner.param_info(),
                             pointer_pos: position,
-                        }))
-                        .unwrap();
+                        }));
 
                         return EventCaptureStatus::Captured;
                     } else if inner.config.open_text_entry_on_right_click
@@ -776,14 +872,13 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                             &mut self.state,
                             renderer,
                             *disabled,
-                            &mut self.on_gesture,
+                            &mut self.actions,
                         );
 
                         cx.send_action((f)(ParamOpenTextEntryInfo {
                             param_info: inner.param_info(),
                             bounds: cx.rect(),
-                        }))
-                        .unwrap();
+                        }));
                     }
 
                     return EventCaptureStatus::Captured;
@@ -798,7 +893,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                     &mut self.state,
                     renderer,
                     *disabled,
-                    &mut self.on_gesture,
+                    &mut self.actions,
                 );
 
                 if click_count == 1 {
@@ -812,7 +907,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                             renderer,
                             prev_state,
                             self.state,
-                            &mut self.on_gesture,
+                            &mut self.actions,
                         );
 
                         cx.steal_focus();
@@ -838,7 +933,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                             renderer,
                             prev_state,
                             self.state,
-                            &mut self.on_gesture,
+                            &mut self.actions,
                         );
                     }
                 }
@@ -862,7 +957,22 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                     }
                 }
 
-                if cx.has_focus() {
+                if inner.config.use_arrow_keys {
+                    // Keep focus so that the arrow keys can nudge the value.
+                    if !inner.is_gesturing() && !in_bounds {
+                        return EventCaptureStatus::NotCaptured;
+                    }
+
+                    finish_gesture(
+                        inner,
+                        cx,
+                        self.hovered,
+                        &mut self.state,
+                        renderer,
+                        *disabled,
+                        &mut self.actions,
+                    );
+                } else if cx.has_focus() {
                     cx.release_focus();
                 }
 
@@ -879,8 +989,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                             param_info: inner.param_info(),
                             rect: cx.rect(),
                             tooltip_align: self.tooltip_align,
-                        }))
-                        .unwrap();
+                        }));
                     }
                 }
             }
@@ -907,7 +1016,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                         renderer,
                         prev_state,
                         self.state,
-                        &mut self.on_gesture,
+                        &mut self.actions,
                     );
 
                     cx.steal_focus();
@@ -924,7 +1033,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                         renderer,
                         None,
                         self.state,
-                        &mut self.on_gesture,
+                        &mut self.actions,
                     );
                 }
 
@@ -935,6 +1044,35 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                     cx.release_focus();
                 }
             }
+            ElementEvent::Keyboard(KeyboardEvent {
+                state: KeyState::Down,
+                code,
+                modifiers,
+                ..
+            }) => {
+                if *disabled {
+                    return EventCaptureStatus::NotCaptured;
+                }
+
+                let increment = match code {
+                    Code::ArrowUp | Code::ArrowRight => true,
+                    Code::ArrowDown | Code::ArrowLeft => false,
+                    _ => return EventCaptureStatus::NotCaptured,
+                };
+
+                if let Some(param_update) = inner.handle_arrow_key(increment, modifiers) {
+                    send_param_update(
+                        param_update,
+                        cx,
+                        renderer,
+                        None,
+                        self.state,
+                        &mut self.actions,
+                    );
+                }
+
+                return EventCaptureStatus::Captured;
+            }
             ElementEvent::Focus(focused) => {
                 if !focused {
                     finish_gesture(
@@ -944,7 +1082,7 @@ impl<A: Clone + 'static, R: VirtualSliderRenderer + 'static> Element<A>
                         &mut self.state,
                         renderer,
                         *disabled,
-                        &mut self.on_gesture,
+                        &mut self.actions,
                     );
                 }
             }
@@ -266,6 +266,23 @@ impl VirtualSliderInner {
         }
     }
 
+    /// Handle relative pointer motion while the pointer is locked.
+    pub fn handle_pointer_delta(
+        &mut self,
+        pointer_delta: Vector,
+        modifiers: Modifiers,
+    ) -> Option<ParamUpdate> {
+        let Some(BeginGestureType::Dragging {
+            pointer_start_pos, ..
+        }) = &self.current_gesture
+        else {
+            return None;
+        };
+        let pointer_pos = *pointer_start_pos;
+
+        self.handle_pointer_moved(pointer_pos, Some(pointer_delta), modifiers)
+    }
+
     pub fn handle_scroll_wheel(
         &mut self,
         delta_type: WheelDeltaType,
@@ -308,6 +325,49 @@ impl VirtualSliderInner {
         self.set_new_gesture_normal(new_gesture_normal)
     }
 
+    /// Nudge the value by one step in response to an arrow key.
+    ///
+    /// If the parameter is stepped, then this moves the value by one
+    /// step. Otherwise the value is moved by
+    /// [`VirtualSliderConfig::arrow_key_step_normal`].
+    ///
+    /// This does nothing if the slider is currently gesturing.
+    pub fn handle_arrow_key(
+        &mut self,
+        increment: bool,
+        modifiers: Modifiers,
+    ) -> Option<InnerParamUpdate> {
+        if !self.config.use_arrow_keys || self.current_gesture.is_some() {
+            return None;
+        }
+
+        if let Some(stepped_value) = self.stepped_value {
+            let new_val = if increment {
+                stepped_value.value.saturating_add(1)
+            } else {
+                stepped_value.value.saturating_sub(1)
+            };
+
+            return self.set_stepped_value(new_val);
+        }
+
+        let apply_fine_adjustment_scalar = if let Some(m) = self.config.fine_adjustment_modifier {
+            modifiers == m
+        } else {
+            false
+        };
+
+        let mut delta_normal = self.config.arrow_key_step_normal;
+        if apply_fine_adjustment_scalar {
+            delta_normal *= self.config.fine_adjustment_scalar;
+        }
+        if !increment {
+            delta_normal = -delta_normal;
+        }
+
+        self.set_normal_value(self.normal_value + f64::from(delta_normal))
+    }
+
     fn set_new_gesture_normal(&mut self, mut new_gesture_normal: f64) -> Option<ParamUpdate> {
         new_gesture_normal = new_gesture_normal.clamp(0.0, 1.0);
 
@@ -532,3 +592,100 @@ pub fn param_normal_to_quantized(normal: f64, num_steps: u32) -> u32 {
 pub fn param_snap_normal(normal: f64, num_steps: u32) -> f64 {
     param_quantized_to_normal(param_normal_to_quantized(normal, num_steps), num_steps)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inner(normal_value: f64, num_quantized_steps: Option<u32>) -> VirtualSliderInner {
+        inner_with_config(
+            normal_value,
+            num_quantized_steps,
+            VirtualSliderConfig {
+                use_arrow_keys: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn inner_with_config(
+        normal_value: f64,
+        num_quantized_steps: Option<u32>,
+        config: VirtualSliderConfig,
+    ) -> VirtualSliderInner {
+        VirtualSliderInner::new(
+            SmolStr::new_static("param"),
+            normal_value,
+            0.0,
+            num_quantized_steps,
+            config,
+            false,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_pointer_delta_while_dragging() {
+        let mut slider = inner(0.5, None);
+        let drag_scalar = f64::from(slider.config.drag_scalar);
+
+        // Deltas are ignored when not dragging.
+        assert!(slider
+            .handle_pointer_delta(Vector::new(0.0, -10.0), Modifiers::empty())
+            .is_none());
+
+        slider.begin_drag_gesture(Point::new(100.0, 100.0));
+
+        // Dragging upwards increases the value, and the deltas accumulate.
+        slider
+            .handle_pointer_delta(Vector::new(0.0, -10.0), Modifiers::empty())
+            .unwrap();
+        slider
+            .handle_pointer_delta(Vector::new(0.0, -10.0), Modifiers::empty())
+            .unwrap();
+        assert!((slider.normal_value() - (0.5 + 20.0 * drag_scalar)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_arrow_key_nudges_by_one_step() {
+        let mut slider = inner(0.0, Some(5));
+
+        let update = slider.handle_arrow_key(true, Modifiers::empty()).unwrap();
+        assert_eq!(update.inner.gesture_state, None);
+        assert_eq!(slider.stepped_value().unwrap().value, 1);
+        assert_eq!(slider.normal_value(), 0.25);
+
+        assert!(slider.handle_arrow_key(false, Modifiers::empty()).is_some());
+        assert_eq!(slider.stepped_value().unwrap().value, 0);
+
+        // Already at the minimum.
+        assert!(slider.handle_arrow_key(false, Modifiers::empty()).is_none());
+    }
+
+    #[test]
+    fn test_arrow_keys_are_ignored_by_default() {
+        let mut slider = inner_with_config(0.0, Some(5), VirtualSliderConfig::default());
+
+        assert!(slider.handle_arrow_key(true, Modifiers::empty()).is_none());
+        assert_eq!(slider.stepped_value().unwrap().value, 0);
+    }
+
+    #[test]
+    fn test_arrow_key_continuous_with_fine_modifier() {
+        let mut slider = inner(0.5, None);
+        let config = slider.config;
+
+        slider.handle_arrow_key(true, Modifiers::empty()).unwrap();
+        let expected = 0.5 + f64::from(config.arrow_key_step_normal);
+        assert!((slider.normal_value() - expected).abs() < 1e-6);
+
+        slider.handle_arrow_key(false, Modifiers::SHIFT).unwrap();
+        let expected =
+            expected - f64::from(config.arrow_key_step_normal * config.fine_adjustment_scalar);
+        assert!((slider.normal_value() - expected).abs() < 1e-6);
+
+        // Arrow keys are ignored while dragging.
+        slider.begin_drag_gesture(Point::new(0.0, 0.0));
+        assert!(slider.handle_arrow_key(true, Modifiers::empty()).is_none());
+    }
+}
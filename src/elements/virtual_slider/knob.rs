@@ -2,7 +2,7 @@ use rootvg::{
     math::{Rect, Size},
     PrimitiveGroup,
 };
-use std::{any::Any, rc::Rc};
+use std::{any::Any, borrow::Cow, rc::Rc};
 
 use crate::{
     layout::SizeType,
@@ -38,6 +38,11 @@ pub use quad::{KnobBackStyleQuad, KnobNotchStyleQuad};
 pub use arc::KnobMarkersArcStyle;
 
 #[derive(Default, Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct KnobStyle {
     pub back: KnobBackStyle,
     pub notch: KnobNotchStyle,
@@ -61,9 +66,31 @@ impl ElementStyle for KnobStyle {
     fn default_light_style() -> Self {
         todo!()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        match &mut self.back {
+            KnobBackStyle::Quad(s) => s.multiply_alpha(multiplier),
+            KnobBackStyle::None => {}
+        }
+
+        match &mut self.notch {
+            KnobNotchStyle::Quad(s) => s.multiply_alpha(multiplier),
+            #[cfg(feature = "mesh")]
+            KnobNotchStyle::Line(s) => s.multiply_alpha(multiplier),
+            KnobNotchStyle::None => {}
+        }
+
+        match &mut self.markers {
+            KnobMarkersStyle::Dots(s) => s.multiply_alpha(multiplier),
+            #[cfg(feature = "tessellation")]
+            KnobMarkersStyle::Arc(s) => s.multiply_alpha(multiplier),
+            KnobMarkersStyle::None => {}
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KnobBackStyle {
     Quad(KnobBackStyleQuad),
     None,
@@ -92,6 +119,7 @@ impl Default for KnobBackStyle {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KnobNotchStyle {
     Quad(KnobNotchStyleQuad),
     #[cfg(feature = "mesh")]
@@ -106,6 +134,7 @@ impl Default for KnobNotchStyle {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KnobMarkersStyle {
     Dots(KnobMarkersDotStyle),
     #[cfg(feature = "tessellation")]
@@ -172,7 +201,10 @@ impl VirtualSliderRenderer for KnobRenderer {
         mut cx: RenderContext,
         primitives: &mut PrimitiveGroup,
     ) {
-        let style = self.style.downcast_ref::<KnobStyle>().unwrap();
+        let mut style = Cow::Borrowed(self.style.downcast_ref::<KnobStyle>().unwrap());
+        if cx.opacity < 1.0 {
+            style.to_mut().multiply_alpha(cx.opacity);
+        }
 
         let back_bounds = style.back_bounds(cx.bounds_size);
 
@@ -208,7 +240,8 @@ impl VirtualSliderRenderer for KnobRenderer {
                     render_cache
                         .marker_arc_back_mesh(
                             cx.class,
-                            style,
+                            cx.opacity,
+                            &style,
                             back_bounds,
                             info.state == VirtualSliderState::Disabled,
                         )
@@ -222,7 +255,8 @@ impl VirtualSliderRenderer for KnobRenderer {
 
                 if let Some(front_mesh) = self.cached_arc_marker_front_mesh.create_primitive(
                     cx.class,
-                    style,
+                    cx.opacity,
+                    &style,
                     back_bounds,
                     normal_val,
                     info.state,
@@ -266,7 +300,7 @@ impl VirtualSliderRenderer for KnobRenderer {
                     .unwrap();
 
                 let meshes = render_cache
-                    .notch_line_mesh(cx.class, style, back_bounds.width())
+                    .notch_line_mesh(cx.class, cx.opacity, &style, back_bounds.width())
                     .unwrap();
 
                 primitives.set_z_index(1);
@@ -17,17 +17,23 @@ use rootvg::gradient::PackedGradient;
 use crate::{
     elements::virtual_slider::VirtualSliderState,
     layout::SizeType,
-    style::{Background, ClassID, DisabledBackground},
+    style::{Background, ClassID, DisabledBackground, MultiplyAlpha},
     theme::DEFAULT_ACCENT_COLOR,
 };
 
 use super::{KnobAngleRange, KnobMarkersStyle, KnobStyle};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct KnobMarkersArcStyle {
     pub width: SizeType,
     pub back_width: SizeType,
     pub edge_offset: SizeType,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub line_cap: LineCap,
     pub fill_bg: Background,
     pub fill_bg_hover: Option<Background>,
@@ -57,6 +63,17 @@ impl Default for KnobMarkersArcStyle {
 }
 
 impl KnobMarkersArcStyle {
+    /// Multiply the alpha channel of every color in this style by the given
+    /// multiplier.
+    pub fn multiply_alpha(&mut self, multiplier: f32) {
+        self.fill_bg.multiply_alpha(multiplier);
+        self.fill_bg_hover.multiply_alpha(multiplier);
+        self.fill_bg_gesturing.multiply_alpha(multiplier);
+        self.fill_bg_disabled.multiply_alpha(multiplier);
+        self.back_bg.multiply_alpha(multiplier);
+        self.back_bg_disabled.multiply_alpha(multiplier);
+    }
+
     pub fn create_back_primitive(
         &self,
         back_size: f32,
@@ -208,6 +225,7 @@ impl KnobMarkersArcStyle {
 pub(super) struct CachedKnobMarkerArcFrontMesh {
     mesh: Option<MeshPrimitive>,
     class: ClassID,
+    opacity: f32,
     back_bounds: Rect,
     normal_val: f32,
     state: VirtualSliderState,
@@ -218,6 +236,7 @@ impl CachedKnobMarkerArcFrontMesh {
     pub fn create_primitive(
         &mut self,
         class: ClassID,
+        opacity: f32,
         style: &KnobStyle,
         back_bounds: Rect,
         normal_val: f32,
@@ -233,8 +252,10 @@ impl CachedKnobMarkerArcFrontMesh {
             self.normal_val != normal_val || self.state != state || self.mesh.is_none();
 
         if !changed {
-            changed =
-                self.class != class || self.back_bounds != back_bounds || self.bipolar != bipolar;
+            changed = self.class != class
+                || self.opacity != opacity
+                || self.back_bounds != back_bounds
+                || self.bipolar != bipolar;
         }
 
         if changed {
@@ -247,6 +268,7 @@ impl CachedKnobMarkerArcFrontMesh {
             );
 
             self.class = class;
+            self.opacity = opacity;
             self.back_bounds = back_bounds;
             self.normal_val = normal_val;
             self.state = state;
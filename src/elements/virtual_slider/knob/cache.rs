@@ -17,6 +17,8 @@ use super::{KnobNotchLinePrimitives, KnobNotchStyle, KnobStyle};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct KnobNotchLineCacheKey {
     class: ClassID,
+    /// The bits of the opacity of the element.
+    opacity: u32,
     back_size: i32,
 }
 
@@ -24,6 +26,8 @@ struct KnobNotchLineCacheKey {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct KnobMarkersArcCacheKey {
     class: ClassID,
+    /// The bits of the opacity of the element.
+    opacity: u32,
     back_size: i32,
     disabled: bool,
 }
@@ -55,6 +59,7 @@ impl KnobRenderCacheInner {
     pub fn notch_line_mesh(
         &mut self,
         class: ClassID,
+        opacity: f32,
         style: &KnobStyle,
         back_size: f32,
     ) -> Option<&KnobNotchLinePrimitives> {
@@ -64,6 +69,7 @@ impl KnobRenderCacheInner {
 
         let key = KnobNotchLineCacheKey {
             class,
+            opacity: opacity.to_bits(),
             back_size: back_size.round() as i32,
         };
 
@@ -82,6 +88,7 @@ impl KnobRenderCacheInner {
     pub fn marker_arc_back_mesh(
         &mut self,
         class: ClassID,
+        opacity: f32,
         style: &KnobStyle,
         back_bounds: crate::math::Rect,
         disabled: bool,
@@ -94,6 +101,7 @@ impl KnobRenderCacheInner {
 
         let key = KnobMarkersArcCacheKey {
             class,
+            opacity: opacity.to_bits(),
             back_size: back_bounds.width().round() as i32,
             disabled,
         };
@@ -8,12 +8,17 @@ use rootvg::{
 
 use crate::{
     prelude::{ParamMarkersConfig, ParamerMarkerType},
-    style::QuadStyle,
+    style::{MultiplyAlpha, QuadStyle},
 };
 
 use super::KnobAngleRange;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct KnobMarkersDotStyle {
     pub primary_quad_style: QuadStyle,
     pub secondary_quad_style: Option<QuadStyle>,
@@ -43,6 +48,14 @@ impl Default for KnobMarkersDotStyle {
 }
 
 impl KnobMarkersDotStyle {
+    /// Multiply the alpha channel of every color in this style by the given
+    /// multiplier.
+    pub fn multiply_alpha(&mut self, multiplier: f32) {
+        self.primary_quad_style.multiply_alpha(multiplier);
+        self.secondary_quad_style.multiply_alpha(multiplier);
+        self.third_quad_style.multiply_alpha(multiplier);
+    }
+
     pub fn add_primitives(
         &self,
         markers: &ParamMarkersConfig,
@@ -7,7 +7,11 @@ use rootvg::{
 #[cfg(feature = "gradient")]
 use rootvg::{gradient::Gradient, mesh::GradientMeshPrimitive};
 
-use crate::{elements::virtual_slider::VirtualSliderState, layout::SizeType, style::DisabledColor};
+use crate::{
+    elements::virtual_slider::VirtualSliderState,
+    layout::SizeType,
+    style::{DisabledColor, MultiplyAlpha},
+};
 
 #[cfg(feature = "gradient")]
 use crate::style::DisabledGradient;
@@ -16,6 +20,7 @@ use super::KnobAngleRange;
 
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KnobNotchStyleLineBg {
     Solid {
         idle: RGBA8,
@@ -44,6 +49,11 @@ impl Default for KnobNotchStyleLineBg {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct KnobNotchStyleLine {
     pub bg: KnobNotchStyleLineBg,
     pub width: SizeType,
@@ -56,6 +66,36 @@ pub struct KnobNotchStyleLine {
 }
 
 impl KnobNotchStyleLine {
+    /// Multiply the alpha channel of every color in this style by the given
+    /// multiplier.
+    pub fn multiply_alpha(&mut self, multiplier: f32) {
+        match &mut self.bg {
+            KnobNotchStyleLineBg::Solid {
+                idle,
+                hovered,
+                gesturing,
+                disabled,
+            } => {
+                idle.multiply_alpha(multiplier);
+                hovered.multiply_alpha(multiplier);
+                gesturing.multiply_alpha(multiplier);
+                disabled.multiply_alpha(multiplier);
+            }
+            #[cfg(feature = "gradient")]
+            KnobNotchStyleLineBg::Gradient {
+                idle,
+                hovered,
+                gesturing,
+                disabled,
+            } => {
+                idle.multiply_alpha(multiplier);
+                hovered.multiply_alpha(multiplier);
+                gesturing.multiply_alpha(multiplier);
+                disabled.multiply_alpha(multiplier);
+            }
+        }
+    }
+
     pub fn create_primitives(&self, back_size: f32) -> KnobNotchLinePrimitives {
         KnobNotchLinePrimitives::new(self, back_size)
     }
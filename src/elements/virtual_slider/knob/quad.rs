@@ -9,12 +9,17 @@ use rootvg::{
 use crate::{
     elements::virtual_slider::VirtualSliderState,
     layout::SizeType,
-    style::{Background, BorderStyle, DisabledBackground, DisabledColor, QuadStyle},
+    style::{Background, BorderStyle, DisabledBackground, DisabledColor, MultiplyAlpha, QuadStyle},
 };
 
 use super::KnobAngleRange;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct KnobBackStyleQuad {
     pub bg: Background,
     pub bg_hover: Option<Background>,
@@ -38,6 +43,19 @@ pub struct KnobBackStyleQuad {
 }
 
 impl KnobBackStyleQuad {
+    /// Multiply the alpha channel of every color in this style by the given
+    /// multiplier.
+    pub fn multiply_alpha(&mut self, multiplier: f32) {
+        self.bg.multiply_alpha(multiplier);
+        self.bg_hover.multiply_alpha(multiplier);
+        self.bg_gesturing.multiply_alpha(multiplier);
+        self.bg_disabled.multiply_alpha(multiplier);
+        self.border_color.multiply_alpha(multiplier);
+        self.border_color_hover.multiply_alpha(multiplier);
+        self.border_color_gesturing.multiply_alpha(multiplier);
+        self.border_color_disabled.multiply_alpha(multiplier);
+    }
+
     pub fn quad_style(&self, state: VirtualSliderState) -> QuadStyle {
         match state {
             VirtualSliderState::Idle => QuadStyle {
@@ -128,6 +146,11 @@ impl Default for KnobBackStyleQuad {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct KnobNotchStyleQuad {
     pub bg: Background,
     pub bg_hover: Option<Background>,
@@ -156,6 +179,19 @@ pub struct KnobNotchStyleQuad {
 }
 
 impl KnobNotchStyleQuad {
+    /// Multiply the alpha channel of every color in this style by the given
+    /// multiplier.
+    pub fn multiply_alpha(&mut self, multiplier: f32) {
+        self.bg.multiply_alpha(multiplier);
+        self.bg_hover.multiply_alpha(multiplier);
+        self.bg_gesturing.multiply_alpha(multiplier);
+        self.bg_disabled.multiply_alpha(multiplier);
+        self.border_color.multiply_alpha(multiplier);
+        self.border_color_hover.multiply_alpha(multiplier);
+        self.border_color_gesturing.multiply_alpha(multiplier);
+        self.border_color_disabled.multiply_alpha(multiplier);
+    }
+
     pub fn quad_style(&self, state: VirtualSliderState) -> QuadStyle {
         match state {
             VirtualSliderState::Idle => QuadStyle {
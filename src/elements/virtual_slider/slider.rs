@@ -11,7 +11,7 @@ use crate::{
     element_system::element::RenderContext,
     layout::{Padding, SizeType},
     prelude::ElementStyle,
-    style::{Background, BorderStyle, DisabledBackground, DisabledColor, QuadStyle},
+    style::{Background, BorderStyle, DisabledBackground, DisabledColor, MultiplyAlpha, QuadStyle},
 };
 
 use super::{
@@ -19,6 +19,7 @@ use super::{
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SliderStyle {
     Modern(SliderStyleModern),
 }
@@ -39,6 +40,35 @@ impl ElementStyle for SliderStyle {
     fn default_light_style() -> Self {
         todo!()
     }
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        match self {
+            Self::Modern(style) => {
+                style.back_bg.multiply_alpha(multiplier);
+                style.back_bg_hover.multiply_alpha(multiplier);
+                style.back_bg_disabled.multiply_alpha(multiplier);
+                style.back_border_color.multiply_alpha(multiplier);
+                style.back_border_color_hover.multiply_alpha(multiplier);
+                style.back_border_color_disabled.multiply_alpha(multiplier);
+                style.handle_bg.multiply_alpha(multiplier);
+                style.handle_bg_hover.multiply_alpha(multiplier);
+                style.handle_bg_gesturing.multiply_alpha(multiplier);
+                style.handle_bg_disabled.multiply_alpha(multiplier);
+                style.handle_border_color.multiply_alpha(multiplier);
+                style.handle_border_color_hover.multiply_alpha(multiplier);
+                style
+                    .handle_border_color_gesturing
+                    .multiply_alpha(multiplier);
+                style
+                    .handle_border_color_disabled
+                    .multiply_alpha(multiplier);
+                style.fill_bg.multiply_alpha(multiplier);
+                style.fill_bg_hover.multiply_alpha(multiplier);
+                style.fill_bg_gesturing.multiply_alpha(multiplier);
+                style.fill_bg_disabled.multiply_alpha(multiplier);
+            }
+        }
+    }
 }
 
 struct SliderStyleModernState {
@@ -53,6 +83,11 @@ struct SliderStyleModernState {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct SliderStyleModern {
     pub back_bg: Background,
     pub back_bg_hover: Option<Background>,
@@ -329,6 +364,7 @@ impl Default for SliderStyleModern {
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SliderFillMode {
     #[default]
     CoverHandle,
@@ -372,7 +408,12 @@ impl VirtualSliderRenderer for SliderRenderer {
 
         match style {
             SliderStyle::Modern(style) => {
-                let style_state = style.state(info.state);
+                let mut style_state = style.state(info.state);
+                if cx.opacity < 1.0 {
+                    style_state.back_quad.multiply_alpha(cx.opacity);
+                    style_state.handle_quad.multiply_alpha(cx.opacity);
+                    style_state.fill_quad.multiply_alpha(cx.opacity);
+                }
 
                 if info.horizontal {
                     let to_horizontal = |r: Rect| -> Rect {
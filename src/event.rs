@@ -1,26 +1,56 @@
 pub use keyboard_types::{Code, CompositionEvent, KeyState, Location, Modifiers};
-use rootvg::math::Vector;
+use rootvg::math::{ScaleFactor, Vector};
 
-use crate::{math::Point, window::OpenWindowError};
+use crate::{
+    math::{PhysicalPoint, Point},
+    window::OpenWindowError,
+};
 
 #[derive(Debug)]
 pub enum AppWindowEvent {
     WindowOpened,
     WindowClosed,
     WindowResized,
+    /// The window was moved. This contains the new position of the top-left
+    /// corner of the window (including its decorations) in physical pixels
+    /// relative to the top-left corner of the desktop.
+    WindowMoved(PhysicalPoint),
     WindowShown,
     WindowHidden,
     WindowFocused,
     WindowUnfocused,
     OpenWindowFailed(OpenWindowError),
+    /// The color scheme of the OS has changed.
+    ThemeChanged(ColorScheme),
 }
 
-pub(crate) enum CanvasEvent {
+/// The light or dark appearance of the OS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub fn is_dark(&self) -> bool {
+        *self == Self::Dark
+    }
+}
+
+/// An event sent to the elements of a window.
+///
+/// The windowing backend translates OS events into these. They can also be
+/// injected with [`WindowContext::inject_event`](crate::WindowContext::inject_event)
+/// to script the UI, i.e. in automated tests.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CanvasEvent {
     Animation {
         delta_seconds: f64,
         pointer_position: Option<Point>,
     },
     Pointer(PointerEvent),
+    Touch(TouchEvent),
     Keyboard(KeyboardEvent),
     TextComposition(CompositionEvent),
     WindowHidden,
@@ -32,11 +62,15 @@ pub(crate) enum CanvasEvent {
 #[derive(Debug, Clone, PartialEq)]
 pub enum ElementEvent {
     CustomStateChanged,
-    Animation { delta_seconds: f64 },
+    Animation {
+        delta_seconds: f64,
+    },
     Hidden,
     Shown,
     StyleChanged,
     Pointer(PointerEvent),
+    /// Only sent to elements with the `ElementFlags::LISTENS_TO_TOUCH` flag.
+    Touch(TouchEvent),
     Keyboard(KeyboardEvent),
     TextComposition(CompositionEvent),
     SizeChanged,
@@ -45,6 +79,9 @@ pub enum ElementEvent {
     Focus(bool),
     ClickedOff,
     Init,
+    /// The scale factor of the window has changed, i.e. the window was moved
+    /// to a monitor with a different DPI.
+    ScaleFactorChanged(ScaleFactor),
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -122,12 +159,50 @@ impl Default for PointerButtonState {
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub enum PointerType {
     Mouse,
-    Pen,
+    /// A stylus/pen.
+    Pen {
+        /// The pressure of the pen tip in the range `[0.0, 1.0]`.
+        pressure: f32,
+        /// The tilt of the pen away from the perpendicular of the surface in
+        /// radians, in the `(x, y)` directions.
+        ///
+        /// This is `(0.0, 0.0)` if the backend doesn't report the tilt.
+        tilt: (f32, f32),
+    },
     Touch,
     #[default]
     Unknown,
 }
 
+impl PointerType {
+    /// The pressure of the pointer in the range `[0.0, 1.0]`.
+    ///
+    /// This is always `1.0` for pointer types other than `PointerType::Pen`.
+    pub fn pressure(&self) -> f32 {
+        match self {
+            Self::Pen { pressure, .. } => *pressure,
+            _ => 1.0,
+        }
+    }
+
+    /// The tilt of the pointer in radians in the `(x, y)` directions.
+    ///
+    /// This is always `(0.0, 0.0)` for pointer types other than
+    /// `PointerType::Pen`.
+    pub fn tilt(&self) -> (f32, f32) {
+        match self {
+            Self::Pen { tilt, .. } => *tilt,
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// Returns `true` if both are the same type of pointer, ignoring the
+    /// pressure and tilt of pens.
+    pub fn is_same_type(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PointerButton {
     #[default]
@@ -146,6 +221,40 @@ pub enum WheelDeltaType {
 }
 
 impl WheelDeltaType {
+    /// Returns `true` if the delta is a precise amount of points (i.e. from
+    /// a trackpad), or `false` if it is in steps of lines or pages (i.e. from
+    /// a notched mouse wheel).
+    pub fn is_precise(&self) -> bool {
+        matches!(self, Self::Points(_))
+    }
+
+    /// Turn a vertical scroll into a horizontal one while Shift is held, so
+    /// that content can be scrolled horizontally with a mouse that only has
+    /// a vertical scroll wheel.
+    ///
+    /// macOS already does this for mouse wheels, so this returns the delta
+    /// unchanged there. Deltas which already have a horizontal component
+    /// (i.e. from a trackpad) are also returned unchanged.
+    pub fn shift_to_horizontal(&self, modifiers: Modifiers) -> Self {
+        if cfg!(target_os = "macos") || !modifiers.contains(Modifiers::SHIFT) {
+            return *self;
+        }
+
+        let swap = |delta: Vector| {
+            if delta.x == 0.0 {
+                Vector::new(delta.y, 0.0)
+            } else {
+                delta
+            }
+        };
+
+        match self {
+            Self::Points(delta) => Self::Points(swap(*delta)),
+            Self::Lines(delta) => Self::Lines(swap(*delta)),
+            Self::Pages(delta) => Self::Pages(swap(*delta)),
+        }
+    }
+
     pub fn points(&self, points_per_line: f32, points_per_page: f32) -> Vector {
         match self {
             Self::Points(delta) => *delta,
@@ -168,12 +277,24 @@ pub enum PointerEvent {
         /// * The backend supports locking the pointer.
         ///
         /// Note if this is `false`, then you will generally want to use
-        /// `position` instead of `delta` for better accuracy.
+        /// `position` instead of `delta` for better accuracy. While the
+        /// pointer is locked, `PointerEvent::DeltaMoved` is sent instead of
+        /// this event.
         is_locked: bool,
         pointer_type: PointerType,
         modifiers: Modifiers,
         just_entered: bool,
     },
+    /// The pointer has moved by the given relative amount while the pointer
+    /// is locked in place.
+    ///
+    /// This is only sent to the element which requested the pointer lock
+    /// with `ElementContext::request_pointer_lock`. While the pointer is
+    /// locked, `PointerEvent::Moved` events are not sent.
+    DeltaMoved {
+        delta: Vector,
+        modifiers: Modifiers,
+    },
     ButtonJustPressed {
         position: Point,
         button: PointerButton,
@@ -191,6 +312,13 @@ pub enum PointerEvent {
     ScrollWheel {
         position: Point,
         delta_type: WheelDeltaType,
+        /// The phase of the scroll gesture, which can be used to implement
+        /// momentum scrolling.
+        ///
+        /// Trackpads report when the gesture starts and ends. Notched mouse
+        /// wheels and backends which don't report the phase always use
+        /// `TouchPhase::Moved`.
+        phase: TouchPhase,
         pointer_type: PointerType,
         modifiers: Modifiers,
     },
@@ -199,6 +327,15 @@ pub enum PointerEvent {
     },
     ScrollWheelTimeout,
     PointerLeft,
+    /// The user has requested a context menu at the given position, either
+    /// with a secondary click or by holding down a touch for at least
+    /// `WindowConfig::long_press_duration`.
+    ///
+    /// When triggered by a secondary click, this is sent right after the
+    /// `PointerEvent::ButtonJustPressed` event.
+    ContextMenu {
+        position: Point,
+    },
 }
 
 impl PointerEvent {
@@ -209,12 +346,41 @@ impl PointerEvent {
             Self::ButtonJustReleased { position, .. } => *position,
             Self::ScrollWheel { position, .. } => *position,
             Self::HoverTimeout { position } => *position,
+            Self::ContextMenu { position } => *position,
+            Self::DeltaMoved { .. } => Point::zero(),
             Self::ScrollWheelTimeout => Point::zero(),
             Self::PointerLeft => Point::zero(),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPhase {
+    /// A finger has touched the screen.
+    Started,
+    Moved,
+    /// The finger has been lifted from the screen.
+    Ended,
+    /// The system has cancelled tracking this touch, i.e. because the window
+    /// lost focus.
+    Cancelled,
+}
+
+/// A single touch on a touchscreen.
+///
+/// Each finger touching the screen has a unique `id` for the duration of the
+/// touch, so multiple touches can be tracked at the same time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchEvent {
+    pub id: u64,
+    pub phase: TouchPhase,
+    pub position: Point,
+    /// Either `PointerType::Touch` for a finger or `PointerType::Pen` for a
+    /// stylus.
+    pub pointer_type: PointerType,
+    pub modifiers: Modifiers,
+}
+
 /// Whether or not the event was captured by this element.
 ///
 /// Note, this is only relevant for `Event::Pointer`, `Event::Keyboard`,
@@ -0,0 +1,370 @@
+//! A thin immediate-mode style layer on top of the retained elements, useful
+//! for quick prototypes.
+//!
+//! Each frame, a closure declares the widgets it wants with calls such as
+//! `ui.button("Play", MyAction::Play)`. The declarations are diffed against
+//! the elements created in the previous frame, so elements which are declared
+//! again are reused, new declarations create new elements, and elements
+//! which are no longer declared are dropped.
+//!
+//! ```ignore
+//! self.ui.run(window_cx, |ui| {
+//!     ui.label(format!("Position: {}", position));
+//!     ui.button("Play", MyAction::Play);
+//!     if playing {
+//!         ui.button("Stop", MyAction::Stop);
+//!     }
+//! });
+//! ```
+
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+
+use crate::elements::button::Button;
+use crate::elements::label::Label;
+use crate::math::Point;
+use crate::prelude::ResourceCtx;
+use crate::WindowContext;
+
+/// A list of retained values which is reconciled against a list of declared
+/// items by key on every frame.
+pub struct KeyedList<K, T> {
+    items: Vec<(K, T)>,
+}
+
+impl<K: Eq + Hash, T> KeyedList<K, T> {
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Reconcile the list with the given declared items.
+    ///
+    /// * Items whose key already exists in the list are passed to `update`
+    /// along with the existing value.
+    /// * Items with a new key are passed to `create`.
+    /// * Existing values whose key was not declared are dropped.
+    ///
+    /// After this call, the values are in the same order as the declared
+    /// items. Keys are expected to be unique.
+    pub fn reconcile<C, D>(
+        &mut self,
+        cx: &mut C,
+        declared: impl IntoIterator<Item = (K, D)>,
+        mut create: impl FnMut(&mut C, &K, D) -> T,
+        mut update: impl FnMut(&mut C, &K, D, &mut T),
+    ) {
+        let mut existing: FxHashMap<K, T> = self.items.drain(..).collect();
+
+        for (key, data) in declared {
+            let value = if let Some(mut value) = existing.remove(&key) {
+                (update)(cx, &key, data, &mut value);
+                value
+            } else {
+                (create)(cx, &key, data)
+            };
+
+            self.items.push((key, value));
+        }
+
+        // Any remaining values in `existing` are dropped here.
+    }
+
+    pub fn get(&self, key: &K) -> Option<&T> {
+        self.items.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.items.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut T)> {
+        self.items.iter_mut().map(|(k, v)| (&*k, v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<K: Eq + Hash, T> Default for KeyedList<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The key that a declared widget is reconciled by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WidgetKey {
+    /// A button is keyed by its text and by how many buttons with the same
+    /// text were declared before it, since its action cannot be changed
+    /// after it is created.
+    Button { text: String, occurrence: usize },
+    /// A label is keyed by its position among the other labels, so that its
+    /// text can be updated in place.
+    Label { index: usize },
+}
+
+enum Declaration<A: Clone + 'static> {
+    Button { text: String, action: A },
+    Label { text: String },
+}
+
+enum Widget {
+    Button(Button),
+    Label(Label),
+}
+
+/// The widgets declared in a single frame.
+pub struct Ui<A: Clone + 'static> {
+    declarations: Vec<Declaration<A>>,
+}
+
+impl<A: Clone + 'static> Ui<A> {
+    fn new() -> Self {
+        Self {
+            declarations: Vec::new(),
+        }
+    }
+
+    /// Declare a button which sends `action` when clicked.
+    ///
+    /// Note, the action is taken from the frame in which the button was
+    /// first declared.
+    pub fn button(&mut self, text: impl Into<String>, action: A) {
+        self.declarations.push(Declaration::Button {
+            text: text.into(),
+            action,
+        });
+    }
+
+    /// Declare a text label.
+    pub fn label(&mut self, text: impl Into<String>) {
+        self.declarations
+            .push(Declaration::Label { text: text.into() });
+    }
+
+    /// The keys of the declarations, in declaration order.
+    fn into_keyed(self) -> Vec<(WidgetKey, Declaration<A>)> {
+        let mut button_occurrences: FxHashMap<String, usize> = FxHashMap::default();
+        let mut num_labels = 0;
+
+        self.declarations
+            .into_iter()
+            .map(|declaration| {
+                let key = match &declaration {
+                    Declaration::Button { text, .. } => {
+                        let occurrence = button_occurrences.entry(text.clone()).or_default();
+                        let key = WidgetKey::Button {
+                            text: text.clone(),
+                            occurrence: *occurrence,
+                        };
+                        *occurrence += 1;
+                        key
+                    }
+                    Declaration::Label { .. } => {
+                        num_labels += 1;
+                        WidgetKey::Label {
+                            index: num_labels - 1,
+                        }
+                    }
+                };
+
+                (key, declaration)
+            })
+            .collect()
+    }
+}
+
+/// Maps immediate-mode style declarations onto retained elements.
+///
+/// The widgets are stacked vertically starting at [`ImmediateUi::origin`].
+pub struct ImmediateUi {
+    widgets: KeyedList<WidgetKey, Widget>,
+    /// The top-left corner of the first widget.
+    pub origin: Point,
+    /// The vertical spacing between widgets.
+    ///
+    /// By default this is set to `6.0`.
+    pub spacing: f32,
+}
+
+impl ImmediateUi {
+    pub fn new(origin: Point) -> Self {
+        Self {
+            widgets: KeyedList::new(),
+            origin,
+            spacing: 6.0,
+        }
+    }
+
+    /// Run the given closure to declare the widgets for this frame, and
+    /// update the retained elements to match.
+    ///
+    /// The elements are created with the z index, scissoring rectangle, and
+    /// class of the current `window_cx` stacks.
+    pub fn run<A: Clone + 'static, F: FnOnce(&mut Ui<A>)>(
+        &mut self,
+        window_cx: &mut WindowContext<'_, A>,
+        f: F,
+    ) {
+        let mut ui = Ui::new();
+        (f)(&mut ui);
+
+        self.widgets.reconcile(
+            window_cx,
+            ui.into_keyed(),
+            |window_cx, _, declaration| match declaration {
+                Declaration::Button { text, action } => Widget::Button(
+                    Button::builder()
+                        .text(text)
+                        .on_select(action)
+                        .build(window_cx),
+                ),
+                Declaration::Label { text } => {
+                    Widget::Label(Label::builder().text(text).build(window_cx))
+                }
+            },
+            |window_cx, _, declaration, widget| {
+                if let (Declaration::Label { text }, Widget::Label(label)) = (declaration, widget) {
+                    label.set_text(Some(text), window_cx.res);
+                }
+            },
+        );
+
+        self.layout(window_cx.res);
+    }
+
+    fn layout(&mut self, res: &mut ResourceCtx) {
+        let mut y = self.origin.y;
+
+        for (_, widget) in self.widgets.iter_mut() {
+            let origin = Point::new(self.origin.x, y);
+
+            let height = match widget {
+                Widget::Button(button) => {
+                    button.layout(origin, res);
+                    button.rect().height()
+                }
+                Widget::Label(label) => {
+                    label.layout(origin, res);
+                    label.rect().height()
+                }
+            };
+
+            y += height + self.spacing;
+        }
+    }
+
+    /// The number of widgets declared in the last frame.
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Stands in for an element handle, counting how many are alive.
+    struct FakeHandle {
+        id: usize,
+        text: String,
+        alive: Rc<Cell<usize>>,
+    }
+
+    impl Drop for FakeHandle {
+        fn drop(&mut self) {
+            self.alive.set(self.alive.get() - 1);
+        }
+    }
+
+    struct Counters {
+        next_id: usize,
+        alive: Rc<Cell<usize>>,
+    }
+
+    fn run_frame(
+        list: &mut KeyedList<WidgetKey, FakeHandle>,
+        counters: &mut Counters,
+        f: impl FnOnce(&mut Ui<()>),
+    ) -> Vec<usize> {
+        let mut ui = Ui::new();
+        (f)(&mut ui);
+
+        list.reconcile(
+            counters,
+            ui.into_keyed(),
+            |counters, _, declaration| {
+                let text = match declaration {
+                    Declaration::Button { text, .. } | Declaration::Label { text } => text,
+                };
+                counters.next_id += 1;
+                counters.alive.set(counters.alive.get() + 1);
+                FakeHandle {
+                    id: counters.next_id,
+                    text,
+                    alive: Rc::clone(&counters.alive),
+                }
+            },
+            |_, _, declaration, handle| {
+                if let Declaration::Label { text } = declaration {
+                    handle.text = text;
+                }
+            },
+        );
+
+        list.iter().map(|(_, h)| h.id).collect()
+    }
+
+    #[test]
+    fn test_immediate_ui_reuses_and_reconciles_handles() {
+        let mut list = KeyedList::new();
+        let mut counters = Counters {
+            next_id: 0,
+            alive: Rc::new(Cell::new(0)),
+        };
+
+        let ids = run_frame(&mut list, &mut counters, |ui| {
+            ui.label("Position: 0");
+            ui.button("Play", ());
+            ui.button("Play", ());
+        });
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        // The same declarations reuse every handle, and label text is updated
+        // in place.
+        let ids = run_frame(&mut list, &mut counters, |ui| {
+            ui.label("Position: 1");
+            ui.button("Play", ());
+            ui.button("Play", ());
+        });
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(list.iter().next().unwrap().1.text, "Position: 1");
+        assert_eq!(counters.alive.get(), 3);
+
+        // Changed declarations add and remove handles.
+        let ids = run_frame(&mut list, &mut counters, |ui| {
+            ui.label("Position: 2");
+            ui.button("Play", ());
+            ui.button("Stop", ());
+        });
+        assert_eq!(ids, vec![1, 2, 4]);
+        assert_eq!(counters.alive.get(), 3);
+
+        let ids = run_frame(&mut list, &mut counters, |ui| {
+            ui.button("Stop", ());
+        });
+        assert_eq!(ids, vec![4]);
+        assert_eq!(counters.alive.get(), 1);
+    }
+}
@@ -1,5 +1,12 @@
 use crate::math::{Point, Rect, SideOffsets, Size};
 
+mod flex;
+mod snap;
+mod z_layer;
+pub use flex::*;
+pub use snap::*;
+pub use z_layer::*;
+
 pub type Padding = SideOffsets;
 pub type Margin = SideOffsets;
 
@@ -0,0 +1,403 @@
+use crate::math::{Point, Rect, Size};
+
+use super::LayoutDirection;
+
+/// The size of a [`FlexItem`] along the main axis before it is grown or
+/// shrunk.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlexBasis {
+    /// Use the size of the item's content along the main axis.
+    #[default]
+    Auto,
+    /// A fixed size in points.
+    Fixed(f32),
+}
+
+/// An item laid out with [`layout_flex`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexItem {
+    /// The size of the item along the main axis before it is grown or
+    /// shrunk.
+    ///
+    /// By default this is set to `FlexBasis::Auto`.
+    pub basis: FlexBasis,
+    /// The desired size of the item's content. This is used for
+    /// `FlexBasis::Auto` and for the size along the cross axis (unless the
+    /// items are stretched).
+    ///
+    /// By default this is set to a size of zero.
+    pub content_size: Size,
+    /// How much of the free space this item takes relative to the other
+    /// items when the items don't fill the container.
+    ///
+    /// By default this is set to `0.0`.
+    pub grow: f32,
+    /// How much this item shrinks relative to the other items when the
+    /// items overflow the container. This is weighted by the basis of the
+    /// item, so larger items shrink more.
+    ///
+    /// By default this is set to `1.0`.
+    pub shrink: f32,
+    /// The minimum size of the item along the main axis when shrinking.
+    ///
+    /// By default this is set to `0.0`.
+    pub min_size: f32,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self {
+            basis: FlexBasis::Auto,
+            content_size: Size::zero(),
+            grow: 0.0,
+            shrink: 1.0,
+            min_size: 0.0,
+        }
+    }
+}
+
+impl FlexItem {
+    /// An item with a fixed size along the main axis which neither grows nor
+    /// shrinks.
+    pub fn fixed(size: f32) -> Self {
+        Self {
+            basis: FlexBasis::Fixed(size),
+            shrink: 0.0,
+            ..Default::default()
+        }
+    }
+
+    /// An item sized to its content.
+    pub fn auto(content_size: Size) -> Self {
+        Self {
+            content_size,
+            ..Default::default()
+        }
+    }
+
+    /// An item which has no size of its own and takes the given share of
+    /// the free space.
+    pub fn grow(grow: f32) -> Self {
+        Self {
+            basis: FlexBasis::Fixed(0.0),
+            grow,
+            ..Default::default()
+        }
+    }
+}
+
+/// How items are distributed along the main axis when there is free space
+/// left over after growing.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlexJustify {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// The free space is put between the items.
+    SpaceBetween,
+    /// The free space is put around each item, so the space at the ends is
+    /// half the space between the items.
+    SpaceAround,
+    /// The free space is divided evenly between the items and the ends.
+    SpaceEvenly,
+}
+
+/// How items are aligned along the cross axis.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlexAlign {
+    Start,
+    Center,
+    End,
+    /// The items fill the container along the cross axis.
+    #[default]
+    Stretch,
+}
+
+/// Lay out items in a single row or column, similar to a CSS flexbox.
+///
+/// Returns the resolved rectangle of each item in the same order as `items`.
+/// The rectangles can then be passed to the `set_rect` methods of element
+/// handles.
+///
+/// If the items cannot shrink enough to fit in the container, then they
+/// overflow past its end.
+pub fn layout_flex(
+    items: &[FlexItem],
+    container: Rect,
+    direction: LayoutDirection,
+    gap: f32,
+    justify: FlexJustify,
+    align: FlexAlign,
+) -> Vec<Rect> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+
+    let main = |size: Size| match direction {
+        LayoutDirection::Horizontal => size.width,
+        LayoutDirection::Vertical => size.height,
+    };
+    let cross = |size: Size| match direction {
+        LayoutDirection::Horizontal => size.height,
+        LayoutDirection::Vertical => size.width,
+    };
+
+    let container_main = main(container.size);
+    let container_cross = cross(container.size);
+    let total_gap = gap * (items.len() - 1) as f32;
+
+    let mut sizes: Vec<f32> = items
+        .iter()
+        .map(|item| match item.basis {
+            FlexBasis::Auto => main(item.content_size),
+            FlexBasis::Fixed(size) => size,
+        })
+        .collect();
+
+    let free = container_main - total_gap - sizes.iter().sum::<f32>();
+    if free > 0.0 {
+        grow(items, &mut sizes, free);
+    } else if free < 0.0 {
+        shrink(items, &mut sizes, -free);
+    }
+
+    let leftover = (container_main - total_gap - sizes.iter().sum::<f32>()).max(0.0);
+    let n = items.len() as f32;
+    let (mut pos, spacing) = match justify {
+        FlexJustify::Start => (0.0, 0.0),
+        FlexJustify::Center => (leftover * 0.5, 0.0),
+        FlexJustify::End => (leftover, 0.0),
+        FlexJustify::SpaceBetween if items.len() > 1 => (0.0, leftover / (n - 1.0)),
+        FlexJustify::SpaceBetween => (0.0, 0.0),
+        FlexJustify::SpaceAround => (leftover / n * 0.5, leftover / n),
+        FlexJustify::SpaceEvenly => (leftover / (n + 1.0), leftover / (n + 1.0)),
+    };
+
+    items
+        .iter()
+        .zip(sizes)
+        .map(|(item, main_size)| {
+            let (cross_pos, cross_size) = match align {
+                FlexAlign::Stretch => (0.0, container_cross),
+                FlexAlign::Start => (0.0, cross(item.content_size)),
+                FlexAlign::Center => {
+                    let size = cross(item.content_size);
+                    ((container_cross - size) * 0.5, size)
+                }
+                FlexAlign::End => {
+                    let size = cross(item.content_size);
+                    (container_cross - size, size)
+                }
+            };
+
+            let rect = match direction {
+                LayoutDirection::Horizontal => Rect::new(
+                    Point::new(container.min_x() + pos, container.min_y() + cross_pos),
+                    Size::new(main_size, cross_size),
+                ),
+                LayoutDirection::Vertical => Rect::new(
+                    Point::new(container.min_x() + cross_pos, container.min_y() + pos),
+                    Size::new(cross_size, main_size),
+                ),
+            };
+
+            pos += main_size + gap + spacing;
+
+            rect
+        })
+        .collect()
+}
+
+/// Distribute `free` space between the items in proportion to their grow
+/// factors.
+fn grow(items: &[FlexItem], sizes: &mut [f32], free: f32) {
+    let total_grow: f32 = items.iter().map(|item| item.grow.max(0.0)).sum();
+    if total_grow <= 0.0 {
+        return;
+    }
+
+    for (item, size) in items.iter().zip(sizes.iter_mut()) {
+        *size += free * item.grow.max(0.0) / total_grow;
+    }
+}
+
+/// Remove `overflow` from the items in proportion to their shrink factors
+/// weighted by their basis, without shrinking any item below its minimum
+/// size.
+fn shrink(items: &[FlexItem], sizes: &mut [f32], mut overflow: f32) {
+    let mut frozen: Vec<bool> = items
+        .iter()
+        .zip(sizes.iter())
+        .map(|(item, size)| item.shrink <= 0.0 || *size <= item.min_size)
+        .collect();
+
+    // Items which hit their minimum size are frozen and the remaining
+    // overflow is distributed between the others.
+    while overflow > 0.0 {
+        let total_scaled: f32 = items
+            .iter()
+            .zip(sizes.iter())
+            .zip(frozen.iter())
+            .filter(|(_, frozen)| !**frozen)
+            .map(|((item, size), _)| item.shrink * size)
+            .sum();
+        if total_scaled <= 0.0 {
+            return;
+        }
+
+        let mut removed = 0.0;
+        let mut froze_any = false;
+        for ((item, size), frozen) in items.iter().zip(sizes.iter_mut()).zip(frozen.iter_mut()) {
+            if *frozen {
+                continue;
+            }
+
+            let target = *size - overflow * (item.shrink * *size) / total_scaled;
+            let new_size = if target <= item.min_size {
+                *frozen = true;
+                froze_any = true;
+                item.min_size.min(*size)
+            } else {
+                target
+            };
+
+            removed += *size - new_size;
+            *size = new_size;
+        }
+
+        overflow -= removed;
+
+        if !froze_any {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widths(rects: &[Rect]) -> Vec<f32> {
+        rects.iter().map(|r| r.width()).collect()
+    }
+
+    fn xs(rects: &[Rect]) -> Vec<f32> {
+        rects.iter().map(|r| r.min_x()).collect()
+    }
+
+    #[test]
+    fn test_free_space_is_distributed_by_grow_factor() {
+        let container = Rect::new(Point::new(10.0, 20.0), Size::new(320.0, 30.0));
+        let items = [
+            FlexItem::fixed(20.0),
+            FlexItem::grow(1.0),
+            FlexItem::grow(3.0),
+        ];
+
+        let rects = layout_flex(
+            &items,
+            container,
+            LayoutDirection::Horizontal,
+            10.0,
+            FlexJustify::Start,
+            FlexAlign::Stretch,
+        );
+
+        // 320 - 20 - 2 * 10 = 280 points are shared 1:3.
+        assert_eq!(widths(&rects), vec![20.0, 70.0, 210.0]);
+        assert_eq!(xs(&rects), vec![10.0, 40.0, 120.0]);
+        assert!(rects
+            .iter()
+            .all(|r| r.min_y() == 20.0 && r.height() == 30.0));
+    }
+
+    #[test]
+    fn test_free_space_is_justified_when_nothing_grows() {
+        let container = Rect::new(Point::zero(), Size::new(100.0, 50.0));
+        let items = [
+            FlexItem::auto(Size::new(20.0, 10.0)),
+            FlexItem::auto(Size::new(20.0, 20.0)),
+        ];
+
+        let layout = |justify, align| {
+            layout_flex(
+                &items,
+                container,
+                LayoutDirection::Horizontal,
+                0.0,
+                justify,
+                align,
+            )
+        };
+
+        assert_eq!(
+            xs(&layout(FlexJustify::End, FlexAlign::Start)),
+            vec![60.0, 80.0]
+        );
+        assert_eq!(
+            xs(&layout(FlexJustify::SpaceBetween, FlexAlign::Start)),
+            vec![0.0, 80.0]
+        );
+        assert_eq!(
+            xs(&layout(FlexJustify::SpaceEvenly, FlexAlign::Start)),
+            vec![20.0, 60.0]
+        );
+
+        let centered = layout(FlexJustify::Center, FlexAlign::Center);
+        assert_eq!(xs(&centered), vec![30.0, 50.0]);
+        assert_eq!(centered[0].min_y(), 20.0);
+        assert_eq!(centered[1].min_y(), 15.0);
+    }
+
+    #[test]
+    fn test_overflowing_items_shrink_down_to_their_minimum() {
+        let container = Rect::new(Point::zero(), Size::new(30.0, 150.0));
+        let items = [
+            FlexItem {
+                basis: FlexBasis::Fixed(100.0),
+                min_size: 80.0,
+                ..Default::default()
+            },
+            FlexItem {
+                basis: FlexBasis::Fixed(100.0),
+                ..Default::default()
+            },
+        ];
+
+        let rects = layout_flex(
+            &items,
+            container,
+            LayoutDirection::Vertical,
+            0.0,
+            FlexJustify::Start,
+            FlexAlign::Stretch,
+        );
+
+        // The first item would shrink to 75 but is held at its minimum, so
+        // the second item takes the rest of the overflow.
+        assert_eq!(
+            rects.iter().map(|r| r.height()).collect::<Vec<_>>(),
+            vec![80.0, 70.0]
+        );
+        assert_eq!(rects[1].min_y(), 80.0);
+        assert_eq!(rects[1].width(), 30.0);
+
+        // Items which cannot shrink overflow past the end of the container.
+        let items = [FlexItem::fixed(100.0), FlexItem::fixed(100.0)];
+        let rects = layout_flex(
+            &items,
+            container,
+            LayoutDirection::Vertical,
+            10.0,
+            FlexJustify::Center,
+            FlexAlign::Stretch,
+        );
+        assert_eq!(rects[0].min_y(), 0.0);
+        assert_eq!(rects[1].max_y(), 210.0);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::math::{Point, Rect, Vector};
+
+/// The part of a rectangle that a snapping guide is aligned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    /// An edge of the dragged rect is aligned with an edge of a candidate
+    /// rect.
+    Edge,
+    /// The center of the dragged rect is aligned with the center of a
+    /// candidate rect.
+    Center,
+}
+
+/// A line segment to draw while dragging to show which rects the dragged
+/// rect has been aligned to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapGuide {
+    pub start: Point,
+    pub end: Point,
+    pub kind: SnapKind,
+}
+
+impl SnapGuide {
+    /// Returns `true` if this is a vertical guide (the dragged rect was
+    /// snapped along the x axis).
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapConfig {
+    /// The maximum distance in logical points between two lines for the
+    /// dragged rect to be snapped.
+    ///
+    /// By default this is set to `6.0`.
+    pub threshold: f32,
+    /// Whether to snap edges of the dragged rect to edges of the candidates.
+    ///
+    /// By default this is set to `true`.
+    pub snap_to_edges: bool,
+    /// Whether to snap the center of the dragged rect to the centers of the
+    /// candidates.
+    ///
+    /// By default this is set to `true`.
+    pub snap_to_centers: bool,
+}
+
+impl Default for SnapConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 6.0,
+            snap_to_edges: true,
+            snap_to_centers: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapResult {
+    /// The new origin of the dragged rect.
+    pub position: Point,
+    /// Whether the dragged rect was snapped along the x axis.
+    pub snapped_x: bool,
+    /// Whether the dragged rect was snapped along the y axis.
+    pub snapped_y: bool,
+    /// The guides to draw.
+    pub guides: Vec<SnapGuide>,
+}
+
+impl SnapResult {
+    /// The offset from the original position of the dragged rect to the
+    /// snapped position.
+    pub fn offset(&self, dragged: Rect) -> Vector {
+        self.position - dragged.origin
+    }
+}
+
+/// The lines of a rect along one axis: (start, center, end).
+fn lines(min: f32, max: f32) -> [(f32, SnapKind); 3] {
+    [
+        (min, SnapKind::Edge),
+        ((min + max) * 0.5, SnapKind::Center),
+        (max, SnapKind::Edge),
+    ]
+}
+
+/// Find the smallest offset along one axis which aligns a line of the
+/// dragged rect with a line of a candidate rect.
+fn find_snap_delta(
+    dragged: (f32, f32),
+    candidates: &[(f32, f32)],
+    config: &SnapConfig,
+) -> Option<f32> {
+    let mut best: Option<f32> = None;
+
+    for &(c_min, c_max) in candidates.iter() {
+        for (d_line, d_kind) in lines(dragged.0, dragged.1) {
+            for (c_line, c_kind) in lines(c_min, c_max) {
+                let enabled = match (d_kind, c_kind) {
+                    (SnapKind::Edge, SnapKind::Edge) => config.snap_to_edges,
+                    (SnapKind::Center, SnapKind::Center) => config.snap_to_centers,
+                    _ => false,
+                };
+                if !enabled {
+                    continue;
+                }
+
+                let delta = c_line - d_line;
+                if delta.abs() <= config.threshold
+                    && best.map(|b| delta.abs() < b.abs()).unwrap_or(true)
+                {
+                    best = Some(delta);
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// Snap the `dragged` rect to the edges and centers of the `candidates`
+/// rects.
+///
+/// Each axis is snapped independently to the closest candidate line within
+/// [`SnapConfig::threshold`]. The returned guides span from the dragged rect
+/// to every candidate rect that it is aligned with after snapping.
+pub fn snap_rect(dragged: Rect, candidates: &[Rect], config: &SnapConfig) -> SnapResult {
+    let x_ranges: Vec<(f32, f32)> = candidates.iter().map(|r| (r.min_x(), r.max_x())).collect();
+    let y_ranges: Vec<(f32, f32)> = candidates.iter().map(|r| (r.min_y(), r.max_y())).collect();
+
+    let delta_x = find_snap_delta((dragged.min_x(), dragged.max_x()), &x_ranges, config);
+    let delta_y = find_snap_delta((dragged.min_y(), dragged.max_y()), &y_ranges, config);
+
+    let snapped = dragged.translate(Vector::new(delta_x.unwrap_or(0.0), delta_y.unwrap_or(0.0)));
+
+    let mut guides = Vec::new();
+
+    for candidate in candidates.iter() {
+        if delta_x.is_some() {
+            for (d_line, d_kind) in lines(snapped.min_x(), snapped.max_x()) {
+                for (c_line, c_kind) in lines(candidate.min_x(), candidate.max_x()) {
+                    if d_kind == c_kind && aligned(d_line, c_line) {
+                        guides.push(SnapGuide {
+                            start: Point::new(d_line, snapped.min_y().min(candidate.min_y())),
+                            end: Point::new(d_line, snapped.max_y().max(candidate.max_y())),
+                            kind: d_kind,
+                        });
+                    }
+                }
+            }
+        }
+
+        if delta_y.is_some() {
+            for (d_line, d_kind) in lines(snapped.min_y(), snapped.max_y()) {
+                for (c_line, c_kind) in lines(candidate.min_y(), candidate.max_y()) {
+                    if d_kind == c_kind && aligned(d_line, c_line) {
+                        guides.push(SnapGuide {
+                            start: Point::new(snapped.min_x().min(candidate.min_x()), d_line),
+                            end: Point::new(snapped.max_x().max(candidate.max_x()), d_line),
+                            kind: d_kind,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    SnapResult {
+        position: snapped.origin,
+        snapped_x: delta_x.is_some(),
+        snapped_y: delta_y.is_some(),
+        guides,
+    }
+}
+
+fn aligned(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 0.001
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Size;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn test_snap_rect_to_edge_within_threshold() {
+        let candidates = [rect(100.0, 100.0, 50.0, 50.0)];
+
+        // The left edge of the dragged rect is 4 points right of the right
+        // edge of the candidate.
+        let dragged = rect(154.0, 300.0, 20.0, 20.0);
+        let res = snap_rect(dragged, &candidates, &SnapConfig::default());
+
+        assert_eq!(res.position, Point::new(150.0, 300.0));
+        assert!(res.snapped_x);
+        assert!(!res.snapped_y);
+        assert_eq!(
+            res.guides,
+            vec![SnapGuide {
+                start: Point::new(150.0, 100.0),
+                end: Point::new(150.0, 320.0),
+                kind: SnapKind::Edge,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_snap_rect_to_center_within_threshold() {
+        let candidates = [rect(100.0, 100.0, 100.0, 40.0)];
+
+        // The center of the dragged rect is at y = 123, the center of the
+        // candidate is at y = 120. No edges are within the threshold.
+        let dragged = rect(300.0, 113.0, 30.0, 20.0);
+        let res = snap_rect(dragged, &candidates, &SnapConfig::default());
+
+        assert_eq!(res.position, Point::new(300.0, 110.0));
+        assert!(!res.snapped_x);
+        assert!(res.snapped_y);
+        assert_eq!(
+            res.guides,
+            vec![SnapGuide {
+                start: Point::new(100.0, 120.0),
+                end: Point::new(330.0, 120.0),
+                kind: SnapKind::Center,
+            }]
+        );
+        assert!(!res.guides[0].is_vertical());
+
+        let res = snap_rect(
+            dragged,
+            &candidates,
+            &SnapConfig {
+                snap_to_centers: false,
+                ..Default::default()
+            },
+        );
+        assert_eq!(res.position, dragged.origin);
+    }
+
+    #[test]
+    fn test_snap_rect_no_snap_beyond_threshold() {
+        let candidates = [rect(100.0, 100.0, 50.0, 50.0)];
+
+        let dragged = rect(157.0, 157.0, 20.0, 20.0);
+        let res = snap_rect(dragged, &candidates, &SnapConfig::default());
+
+        assert_eq!(res.position, dragged.origin);
+        assert!(!res.snapped_x);
+        assert!(!res.snapped_y);
+        assert!(res.guides.is_empty());
+
+        // The closest candidate line wins.
+        let candidates = [rect(0.0, 0.0, 10.0, 10.0), rect(0.0, 0.0, 12.0, 12.0)];
+        let dragged = rect(13.0, 100.0, 5.0, 5.0);
+        let res = snap_rect(dragged, &candidates, &SnapConfig::default());
+        assert_eq!(res.position, Point::new(12.0, 100.0));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::math::ZIndex;
+
+/// A named range of z indices, so that applications don't have to invent
+/// their own numbering.
+///
+/// The layers divide the range of [`ZIndex`] like so:
+///
+/// | Layer        | Range           |
+/// |--------------|-----------------|
+/// | `Background` | `0..=999`       |
+/// | `Content`    | `1000..=39999`  |
+/// | `Overlay`    | `40000..=49999` |
+/// | `Popup`      | `50000..=59999` |
+/// | `Tooltip`    | `60000..=65535` |
+///
+/// Popup menus, context menus, and tooltips use the reserved high ranges by
+/// default so that they are always rendered above content.
+///
+/// Note that the default z index of an element is `0`, which is in the
+/// `Background` layer.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ZLayer {
+    Background,
+    #[default]
+    Content,
+    /// Panels and overlays which are shown above the content.
+    Overlay,
+    /// Popup menus and context menus.
+    Popup,
+    Tooltip,
+}
+
+impl ZLayer {
+    pub const ALL: [Self; 5] = [
+        Self::Background,
+        Self::Content,
+        Self::Overlay,
+        Self::Popup,
+        Self::Tooltip,
+    ];
+
+    /// The lowest z index in this layer.
+    pub const fn base(self) -> ZIndex {
+        match self {
+            Self::Background => 0,
+            Self::Content => 1000,
+            Self::Overlay => 40000,
+            Self::Popup => 50000,
+            Self::Tooltip => 60000,
+        }
+    }
+
+    /// The highest z index in this layer.
+    pub const fn max(self) -> ZIndex {
+        match self {
+            Self::Background => Self::Content.base() - 1,
+            Self::Content => Self::Overlay.base() - 1,
+            Self::Overlay => Self::Popup.base() - 1,
+            Self::Popup => Self::Tooltip.base() - 1,
+            Self::Tooltip => ZIndex::MAX,
+        }
+    }
+
+    /// The z index `offset` places above the base of this layer. The result
+    /// is clamped to the range of this layer.
+    pub const fn z_index(self, offset: ZIndex) -> ZIndex {
+        let z_index = self.base().saturating_add(offset);
+        if z_index > self.max() {
+            self.max()
+        } else {
+            z_index
+        }
+    }
+
+    /// The layer which contains the given z index.
+    pub const fn of(z_index: ZIndex) -> Self {
+        if z_index >= Self::Tooltip.base() {
+            Self::Tooltip
+        } else if z_index >= Self::Popup.base() {
+            Self::Popup
+        } else if z_index >= Self::Overlay.base() {
+            Self::Overlay
+        } else if z_index >= Self::Content.base() {
+            Self::Content
+        } else {
+            Self::Background
+        }
+    }
+}
+
+impl From<ZLayer> for ZIndex {
+    fn from(layer: ZLayer) -> Self {
+        layer.base()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_cover_the_whole_z_index_range() {
+        assert_eq!(ZLayer::ALL[0].base(), 0);
+        assert_eq!(ZLayer::ALL[4].max(), ZIndex::MAX);
+        for layers in ZLayer::ALL.windows(2) {
+            assert_eq!(layers[0].max() + 1, layers[1].base());
+        }
+
+        for layer in ZLayer::ALL {
+            assert_eq!(ZLayer::of(layer.base()), layer);
+            assert_eq!(ZLayer::of(layer.max()), layer);
+        }
+
+        assert_eq!(ZLayer::Content.z_index(5), 1005);
+        assert_eq!(ZLayer::Overlay.z_index(ZIndex::MAX), ZLayer::Overlay.max());
+        assert_eq!(ZIndex::from(ZLayer::Popup), 50000);
+    }
+}
@@ -13,12 +13,14 @@
 // ---------------------------------------------------------------------------------
 
 pub mod action_queue;
+pub mod animation;
 mod application;
 pub mod clipboard;
 pub(crate) mod cursor_icon;
 pub(crate) mod element_system;
 pub mod elements;
 pub mod event;
+pub mod immediate;
 pub mod layout;
 pub mod prelude;
 pub(crate) mod stmpsc_queue;
@@ -29,17 +31,24 @@ pub mod window;
 pub use action_queue::action_channel;
 pub use application::{AppConfig, AppContext, Application};
 pub use cursor_icon::CursorIcon;
-pub use element_system::{ScissorRectID, TooltipInfo};
+pub use element_system::{
+    DebugGridConfig, DebugOverlayFlags, DebugZIndexConfig, FocusWrapMode, RenderBackend,
+    RenderBackendCtx, RenderStats, ScissorRectID, TooltipInfo, Viewport,
+};
 pub use window::{WindowContext, WindowID, MAIN_WINDOW};
 pub use yarrow_derive as derive;
 
 #[cfg(feature = "custom-shaders")]
 pub use element_system::CustomPipelines;
+#[cfg(feature = "headless")]
+pub use element_system::{HeadlessRenderBackend, HeadlessRenderBackendError, ImageBuffer};
 
 pub use rootvg as vg;
 pub use rootvg::math;
 
 pub use window::run_blocking;
+#[cfg(feature = "headless")]
+pub use window::run_headless;
 #[cfg(feature = "baseview")]
 pub use window::run_parented;
 
@@ -1,34 +1,64 @@
 pub use crate::action_queue::{ActionReceiver, ActionSender};
+pub use crate::animation::{AnimatedF32, AnimationMode};
 pub use crate::application::*;
 pub use crate::cursor_icon::*;
 pub use crate::element_system::{
     element::{
-        Element, ElementBuilder, ElementContext, ElementFlags, ElementHandle, ElementRenderCache,
-        ElementStyle, RenderContext,
+        Element, ElementBuilder, ElementContext, ElementFlags, ElementHandle, ElementID,
+        ElementInfo, ElementKey, ElementRenderCache, ElementStyle, LayoutTransaction,
+        RenderContext, SharedElementHandle, SizableElement,
     },
-    ScissorRectID, TooltipInfo,
+    DebugGridConfig, DebugOverlayFlags, DebugZIndexConfig, FocusWrapMode, ScissorRectID,
+    TooltipInfo,
 };
 pub use crate::elements::button::{Button, ButtonStyle};
+pub use crate::elements::checkbox::{Checkbox, CheckboxStyle, CheckmarkShape};
 pub use crate::elements::click_area::ClickArea;
+pub use crate::elements::context_menu::{ContextMenu, ContextMenuEntry, MenuItem};
+pub use crate::elements::drop_down::DropDown;
 pub use crate::elements::drop_down_menu::{DropDownMenu, DropDownMenuStyle, MenuEntry};
+pub use crate::elements::dynamic_primitives::DynamicPrimitives;
 #[cfg(feature = "svg-icons")]
 pub use crate::elements::icon::{Icon, IconStyle};
+#[cfg(feature = "image")]
+pub use crate::elements::image::{Image, ImageLayout, ScaleMode};
+pub use crate::elements::keyboard_nav::{KeyboardNav, KeyboardNavConfig};
 pub use crate::elements::label::{Label, LabelStyle, TextIconLayout};
+pub use crate::elements::list_view::{ListRow, ListSelectionMode, ListView, ListViewStyle};
+pub use crate::elements::loading_overlay::LoadingOverlayStyle;
+#[cfg(feature = "image")]
+pub use crate::elements::nine_slice::{NineSlice, NineSliceStyle};
+pub use crate::elements::panel::{Panel, PanelStyle};
 pub use crate::elements::paragraph::{Paragraph, ParagraphStyle};
+#[cfg(feature = "tessellation")]
+pub use crate::elements::pie_chart::{PieChart, PieChartStyle};
 pub use crate::elements::quad::QuadElement;
-pub use crate::elements::radio_button::{RadioButton, RadioButtonGroup, RadioButtonStyle};
+pub use crate::elements::radio_button::{
+    RadioButton, RadioButtonGroup, RadioButtonStyle, RadioGroupID,
+};
+pub use crate::elements::reorderable::{
+    ReorderDragState, ReorderInner, Reorderable, ReorderableStyle,
+};
 pub use crate::elements::resize_handle::{ResizeHandle, ResizeHandleLayout, ResizeHandleStyle};
-pub use crate::elements::scroll_area::{ScrollArea, ScrollBarStyle};
-pub use crate::elements::separator::{Separator, SeparatorSizeType, SeparatorStyle};
+pub use crate::elements::rich_text::{RichText, RichTextInner, RichTextStyle, SpanHit, TextSpan};
+pub use crate::elements::scroll_area::{ElasticOverscroll, ScrollArea, ScrollBarStyle};
+pub use crate::elements::separator::{
+    Separator, SeparatorSizeType, SeparatorStyle, SeparatorThickness,
+};
+pub use crate::elements::spinner::{Spinner, SpinnerStyle};
 pub use crate::elements::switch::{Switch, SwitchStyle};
 pub use crate::elements::tab::{IndicatorLinePlacement, Tab, TabGroup, TabGroupOption, TabStyle};
+pub use crate::elements::tab_bar::{TabBar, TabBarStyle, TabBarTab};
 pub use crate::elements::text_input::{
     FloatingTextInput, TextInput, TextInputAction, TextInputStyle,
 };
 #[cfg(feature = "svg-icons")]
 pub use crate::elements::text_input::{IconTextInput, IconTextInputStyle};
 pub use crate::elements::toggle_button::{ToggleButton, ToggleButtonStyle};
-pub use crate::elements::tooltip::{Tooltip, TooltipData, TooltipInner, TooltipStyle};
+pub use crate::elements::tooltip::{
+    Tooltip, TooltipContent, TooltipData, TooltipInner, TooltipPositioning, TooltipStyle,
+};
+pub use crate::elements::value_changed::ValueChangedBuilder;
 #[cfg(feature = "tessellation")]
 pub use crate::elements::virtual_slider::knob::KnobMarkersArcStyle;
 pub use crate::elements::virtual_slider::knob::{
@@ -9,6 +9,7 @@ use crate::prelude::ElementStyle;
 use crate::theme::DEFAULT_DISABLED_ALPHA_MULTIPLIER;
 use crate::vg::color::RGBA8;
 use crate::vg::quad::{Border, Radius};
+use crate::vg::text::TextProperties;
 
 #[cfg(feature = "gradient")]
 use crate::vg::gradient::Gradient;
@@ -19,6 +20,9 @@ pub type IconID = u16;
 
 pub use style_system::{ClassID, StyleSystem, CLASS_DEFAULT, CLASS_MENU, CLASS_PANEL};
 
+#[cfg(feature = "serde")]
+pub use style_system::StyleLoadError;
+
 /// The scale of an icon, used to make icons look more consistent.
 ///
 /// Note this does not affect any layout, this is just a visual thing.
@@ -201,6 +205,10 @@ impl QuadStyle {
 
 impl ElementStyle for QuadStyle {
     const ID: &'static str = "qd";
+
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        QuadStyle::multiply_alpha(self, multiplier);
+    }
 }
 
 /// An alias for `QuadStyle::new(color, width, radius)`
@@ -305,6 +313,7 @@ pub const fn background_gray(v: u8) -> Background {
 
 /// How to style a color property when an element is disabled.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisabledColor {
     /// Use a multiplier on the alpha channel of the property color.
     AlphaMultiplier(f32),
@@ -332,6 +341,7 @@ impl Default for DisabledColor {
 #[cfg(feature = "gradient")]
 /// How to style a gradient property when an element is disabled.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisabledGradient {
     /// Use a multiplier on the alpha channels of the property gradient.
     AlphaMultiplier(f32),
@@ -362,6 +372,7 @@ impl Default for DisabledGradient {
 
 /// How to style a background property when an element is disabled.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisabledBackground {
     /// Use a multiplier on the alpha channels of the property background.
     AlphaMultiplier(f32),
@@ -388,6 +399,91 @@ impl Default for DisabledBackground {
     }
 }
 
+/// Scale the font size and line height of the given text properties by the
+/// given multiplier.
+///
+/// This is intended for use in [`ElementStyle::with_text_scale`].
+pub fn scale_text_properties(properties: &mut TextProperties, text_scale: f32) {
+    properties.metrics.font_size *= text_scale;
+    properties.metrics.line_height *= text_scale;
+}
+
+/// A style property whose colors can be faded by a multiplier on their alpha
+/// channels.
+///
+/// This is intended for use in [`ElementStyle::multiply_alpha`].
+pub trait MultiplyAlpha {
+    /// Multiply the alpha channel of every color in this property by the
+    /// given multiplier.
+    fn multiply_alpha(&mut self, multiplier: f32);
+}
+
+impl MultiplyAlpha for RGBA8 {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        *self = color::multiply_alpha(*self, multiplier);
+    }
+}
+
+impl<T: MultiplyAlpha> MultiplyAlpha for Option<T> {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        if let Some(v) = self {
+            v.multiply_alpha(multiplier);
+        }
+    }
+}
+
+impl MultiplyAlpha for Background {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        Background::multiply_alpha(self, multiplier);
+    }
+}
+
+impl MultiplyAlpha for BorderStyle {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        self.color.multiply_alpha(multiplier);
+    }
+}
+
+impl MultiplyAlpha for QuadStyle {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        QuadStyle::multiply_alpha(self, multiplier);
+    }
+}
+
+impl MultiplyAlpha for DisabledColor {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        // An alpha multiplier is applied on top of the property color, which
+        // is already faded.
+        if let DisabledColor::Custom(color) = self {
+            color.multiply_alpha(multiplier);
+        }
+    }
+}
+
+impl MultiplyAlpha for DisabledBackground {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        if let DisabledBackground::Custom(bg) = self {
+            bg.multiply_alpha(multiplier);
+        }
+    }
+}
+
+#[cfg(feature = "gradient")]
+impl MultiplyAlpha for Gradient {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        Gradient::multiply_alpha(self, multiplier);
+    }
+}
+
+#[cfg(feature = "gradient")]
+impl MultiplyAlpha for DisabledGradient {
+    fn multiply_alpha(&mut self, multiplier: f32) {
+        if let DisabledGradient::Custom(g) = self {
+            g.multiply_alpha(multiplier);
+        }
+    }
+}
+
 /*
 impl Into<Shadow> for ShadowStyle {
     fn into(self) -> Shadow {
@@ -1,4 +1,5 @@
-use std::any::Any;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
 use std::rc::Rc;
 
 use ahash::AHashMap;
@@ -18,27 +19,260 @@ struct Key {
     is_dark_theme: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ResolvedKey {
+    type_id: TypeId,
+    class: ClassID,
+    is_dark_theme: bool,
+}
+
+struct StyleEntry {
+    style: Rc<dyn Any>,
+    /// A copy of `style` with the text scale applied, if the text scale is
+    /// not `1.0` and the style contains any text.
+    scaled: Option<Rc<dyn Any>>,
+    apply_text_scale: fn(&dyn Any, f32) -> Option<Rc<dyn Any>>,
+}
+
+impl StyleEntry {
+    fn new<T: ElementStyle>(style: T, text_scale: f32) -> Self {
+        let style: Rc<dyn Any> = Rc::new(style);
+        let scaled = apply_text_scale::<T>(style.as_ref(), text_scale);
+
+        Self {
+            style,
+            scaled,
+            apply_text_scale: apply_text_scale::<T>,
+        }
+    }
+
+    fn new_built_in<T: ElementStyle>(is_dark_theme: bool, text_scale: f32) -> Self {
+        Self::new(
+            if is_dark_theme {
+                T::default_dark_style()
+            } else {
+                T::default_light_style()
+            },
+            text_scale,
+        )
+    }
+
+    fn get(&self) -> &Rc<dyn Any> {
+        self.scaled.as_ref().unwrap_or(&self.style)
+    }
+}
+
+/// Parses a style of a registered type from a style file.
+#[cfg(feature = "serde")]
+type StyleLoader = fn(serde_json::Value, f32) -> Result<StyleEntry, serde_json::Error>;
+
+#[cfg(feature = "serde")]
+fn load_style<T: ElementStyle + serde::de::DeserializeOwned>(
+    value: serde_json::Value,
+    text_scale: f32,
+) -> Result<StyleEntry, serde_json::Error> {
+    serde_json::from_value::<T>(value).map(|style| StyleEntry::new(style, text_scale))
+}
+
+/// A single style in a style file.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct StyleFileEntry {
+    /// The [`ElementStyle::ID`] of the style.
+    element: String,
+    #[serde(default)]
+    class: ClassID,
+    /// If this is `None`, then the style is used for both themes.
+    #[serde(default)]
+    dark_theme: Option<bool>,
+    style: serde_json::Value,
+}
+
+/// An error that occured while loading a style file.
+#[cfg(feature = "serde")]
+#[derive(Debug, thiserror::Error)]
+pub enum StyleLoadError {
+    #[error("Failed to read style file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse style file: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("No loader is registered for element style \"{0}\"")]
+    UnregisteredStyle(String),
+    #[error("Invalid style for element style \"{element}\" in class {class}: {error}")]
+    InvalidStyle {
+        element: String,
+        class: ClassID,
+        error: serde_json::Error,
+    },
+}
+
+fn apply_text_scale<T: ElementStyle>(style: &dyn Any, text_scale: f32) -> Option<Rc<dyn Any>> {
+    if text_scale == 1.0 {
+        return None;
+    }
+
+    style
+        .downcast_ref::<T>()
+        .unwrap()
+        .with_text_scale(text_scale)
+        .map(|s| -> Rc<dyn Any> { Rc::new(s) })
+}
+
+/// The system which stores the styles of every element type.
+///
+/// Styles are looked up by element type and [`ClassID`] in this order:
+///
+/// 1. The style added for the given class
+/// 2. The style added for [`CLASS_DEFAULT`]
+/// 3. The built-in default style of the element type (see
+/// [`ElementStyle::default_dark_style`] and
+/// [`ElementStyle::default_light_style`])
+///
+/// The resolved styles are cached until a style is added or removed.
 pub struct StyleSystem {
-    styles: AHashMap<Key, Rc<dyn Any>>,
+    /// The styles added by the application.
+    styles: AHashMap<Key, StyleEntry>,
+    /// The built-in default styles of element types that have been looked
+    /// up.
+    built_in: AHashMap<(&'static str, bool), StyleEntry>,
+    resolved: AHashMap<ResolvedKey, Rc<dyn Any>>,
     pub(crate) use_dark_theme: bool,
+    text_scale: f32,
+    #[cfg(feature = "serde")]
+    loaders: AHashMap<&'static str, StyleLoader>,
+    /// The type that registered each style ID, used to detect two style
+    /// types with the same ID.
+    #[cfg(debug_assertions)]
+    registered_ids: AHashMap<&'static str, (TypeId, &'static str)>,
 }
 
 impl StyleSystem {
     pub fn new(use_dark_theme: bool) -> Self {
-        Self {
+        #[allow(unused_mut)]
+        let mut new_self = Self {
             styles: AHashMap::default(),
+            built_in: AHashMap::default(),
+            resolved: AHashMap::default(),
             use_dark_theme,
+            text_scale: 1.0,
+            #[cfg(feature = "serde")]
+            loaders: AHashMap::default(),
+            #[cfg(debug_assertions)]
+            registered_ids: AHashMap::default(),
+        };
+
+        #[cfg(feature = "serde")]
+        new_self.register_built_in_loadables();
+
+        new_self
+    }
+
+    /// Allow the styles of all built-in elements to be loaded from a style
+    /// file.
+    ///
+    /// Text properties (and the texture of a [`NineSliceStyle`]) can't be
+    /// loaded, so they keep the values of the default style.
+    ///
+    /// [`NineSliceStyle`]: crate::prelude::NineSliceStyle
+    #[cfg(feature = "serde")]
+    fn register_built_in_loadables(&mut self) {
+        use crate::prelude::*;
+
+        self.register_loadable::<QuadStyle>();
+        self.register_loadable::<ButtonStyle>();
+        self.register_loadable::<CheckboxStyle>();
+        self.register_loadable::<DropDownMenuStyle>();
+        self.register_loadable::<LabelStyle>();
+        self.register_loadable::<ListViewStyle>();
+        self.register_loadable::<LoadingOverlayStyle>();
+        self.register_loadable::<PanelStyle>();
+        self.register_loadable::<ParagraphStyle>();
+        self.register_loadable::<RadioButtonStyle>();
+        self.register_loadable::<ReorderableStyle>();
+        self.register_loadable::<ResizeHandleStyle>();
+        self.register_loadable::<RichTextStyle>();
+        self.register_loadable::<ScrollBarStyle>();
+        self.register_loadable::<SeparatorStyle>();
+        self.register_loadable::<SpinnerStyle>();
+        self.register_loadable::<SwitchStyle>();
+        self.register_loadable::<TabStyle>();
+        self.register_loadable::<TabBarStyle>();
+        self.register_loadable::<TextInputStyle>();
+        self.register_loadable::<ToggleButtonStyle>();
+        self.register_loadable::<TooltipStyle>();
+        self.register_loadable::<KnobStyle>();
+        self.register_loadable::<SliderStyle>();
+        #[cfg(feature = "svg-icons")]
+        {
+            self.register_loadable::<IconStyle>();
+            self.register_loadable::<IconTextInputStyle>();
         }
+        #[cfg(feature = "image")]
+        self.register_loadable::<NineSliceStyle>();
+        #[cfg(feature = "tessellation")]
+        self.register_loadable::<PieChartStyle>();
     }
 
     pub fn is_using_dark_theme(&self) -> bool {
         self.use_dark_theme
     }
 
+    /// Switch between the dark and light variants of every style.
+    ///
+    /// Prefer using [`AppContext::set_dark_theme`](crate::AppContext::set_dark_theme),
+    /// which also notifies every element of the change.
+    ///
+    /// Returns `true` if the theme has changed.
+    pub fn set_dark_theme(&mut self, use_dark_theme: bool) -> bool {
+        if self.use_dark_theme == use_dark_theme {
+            return false;
+        }
+
+        self.use_dark_theme = use_dark_theme;
+        true
+    }
+
+    /// The multiplier applied to the font size and line height of every
+    /// style.
+    ///
+    /// By default this is set to `1.0`.
+    pub fn text_scale(&self) -> f32 {
+        self.text_scale
+    }
+
+    /// Set the multiplier applied to the font size and line height of every
+    /// style (a "text zoom").
+    ///
+    /// This is independent of the scale factor of the window, and it does
+    /// not affect the sizes of element rectangles set by the application.
+    ///
+    /// Prefer using [`AppContext::set_text_scale`](crate::AppContext::set_text_scale),
+    /// which also notifies every element of the change.
+    ///
+    /// Returns `true` if the text scale has changed.
+    pub fn set_text_scale(&mut self, text_scale: f32) -> bool {
+        let text_scale = text_scale.max(0.01);
+        if self.text_scale == text_scale {
+            return false;
+        }
+
+        self.text_scale = text_scale;
+
+        for entry in self.styles.values_mut().chain(self.built_in.values_mut()) {
+            entry.scaled = (entry.apply_text_scale)(entry.style.as_ref(), text_scale);
+        }
+        self.resolved.clear();
+
+        true
+    }
+
     /// Insert a new style with the given class ID for the given element type.
     ///
     /// Returns `true` if this style existed before and has been overwritten.
     pub fn add<T: ElementStyle>(&mut self, class: ClassID, is_dark_theme: bool, style: T) -> bool {
+        self.check_style_id::<T>();
+        self.resolved.clear();
+
         self.styles
             .insert(
                 Key {
@@ -46,7 +280,7 @@ impl StyleSystem {
                     class,
                     is_dark_theme,
                 },
-                Rc::new(style),
+                StyleEntry::new(style, self.text_scale),
             )
             .is_some()
     }
@@ -55,6 +289,8 @@ impl StyleSystem {
     ///
     /// Returns `true` if the style existed.
     pub fn remove<T: ElementStyle>(&mut self, class: ClassID, is_dark_theme: bool) -> bool {
+        self.resolved.clear();
+
         self.styles
             .remove(&Key {
                 element_type_id: T::ID,
@@ -64,27 +300,148 @@ impl StyleSystem {
             .is_some()
     }
 
-    /// Get the style from the system.
+    /// Allow styles of type `T` to be loaded from a style file.
     ///
-    /// If the style doesn't exist in the system, the default style will be
-    /// inserted and returned.
-    pub fn get<T: ElementStyle>(&mut self, class: ClassID) -> &T {
-        let key = Key {
+    /// Returns `true` if a style type with the same ID was already
+    /// registered.
+    #[cfg(feature = "serde")]
+    pub fn register_loadable<T: ElementStyle + serde::de::DeserializeOwned>(&mut self) -> bool {
+        self.check_style_id::<T>();
+        self.loaders.insert(T::ID, load_style::<T>).is_some()
+    }
+
+    /// Load styles from the contents of a JSON style file.
+    ///
+    /// The file contains a list of styles, each with the `element` style ID
+    /// of its type (which must have been registered with
+    /// [`StyleSystem::register_loadable`]), an optional `class` (`0` by
+    /// default), an optional `dark_theme` flag (if omitted, then the style
+    /// is used for both themes), and the `style` itself:
+    ///
+    /// ```json
+    /// [
+    ///     { "element": "qd", "class": 3, "dark_theme": true, "style": { ... } }
+    /// ]
+    /// ```
+    ///
+    /// Loaded styles overwrite any existing styles with the same key. Styles
+    /// which are not in the file are left untouched. If any style in the file
+    /// is invalid, then no styles are changed.
+    ///
+    /// Note the elements are not notified of the change. Prefer using
+    /// [`AppContext::reload_styles_from_path`](crate::AppContext::reload_styles_from_path),
+    /// which also restyles every element.
+    ///
+    /// Returns the number of styles that were loaded.
+    #[cfg(feature = "serde")]
+    pub fn load_from_str(&mut self, contents: &str) -> Result<usize, StyleLoadError> {
+        let file_entries: Vec<StyleFileEntry> = serde_json::from_str(contents)?;
+
+        let mut loaded = Vec::with_capacity(file_entries.len() * 2);
+        for file_entry in file_entries {
+            let Some((&element_type_id, loader)) =
+                self.loaders.get_key_value(file_entry.element.as_str())
+            else {
+                return Err(StyleLoadError::UnregisteredStyle(file_entry.element));
+            };
+
+            let themes: &[bool] = match file_entry.dark_theme {
+                Some(true) => &[true],
+                Some(false) => &[false],
+                None => &[true, false],
+            };
+
+            for &is_dark_theme in themes {
+                let entry = loader(file_entry.style.clone(), self.text_scale).map_err(|error| {
+                    StyleLoadError::InvalidStyle {
+                        element: file_entry.element.clone(),
+                        class: file_entry.class,
+                        error,
+                    }
+                })?;
+
+                loaded.push((
+                    Key {
+                        element_type_id,
+                        class: file_entry.class,
+                        is_dark_theme,
+                    },
+                    entry,
+                ));
+            }
+        }
+
+        let num_loaded = loaded.len();
+        self.styles.extend(loaded);
+        self.resolved.clear();
+
+        Ok(num_loaded)
+    }
+
+    /// Load styles from a JSON style file on disk. This can be called again
+    /// whenever the file changes to iterate on a theme while the application
+    /// is running (watching the file for changes is left to the application).
+    ///
+    /// See [`StyleSystem::load_from_str`] for the format of the file.
+    ///
+    /// Returns the number of styles that were loaded.
+    #[cfg(feature = "serde")]
+    pub fn reload_from_path(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<usize, StyleLoadError> {
+        let contents = std::fs::read_to_string(path)?;
+        self.load_from_str(&contents)
+    }
+
+    /// Get the effective style of the given element type for the given class
+    /// in the current theme.
+    ///
+    /// If no style was added for this class, then the style for
+    /// [`CLASS_DEFAULT`] is used, and if that doesn't exist either, then the
+    /// built-in default style of the element type is used. See
+    /// [`StyleSystem`].
+    pub fn resolve<T: ElementStyle>(&mut self, class: ClassID) -> &T {
+        self.resolve_rc::<T>(class).downcast_ref().unwrap()
+    }
+
+    /// Returns `true` if a style was added for the given element type and
+    /// class in the current theme, meaning that [`StyleSystem::resolve`]
+    /// doesn't fall back to a different style.
+    pub fn contains<T: ElementStyle>(&self, class: ClassID) -> bool {
+        self.styles.contains_key(&Key {
             element_type_id: T::ID,
             class,
             is_dark_theme: self.use_dark_theme,
-        };
+        })
+    }
 
-        let entry = self.styles.entry(key);
-        let entry = entry.or_insert_with(|| {
-            Rc::new(if self.use_dark_theme {
-                T::default_dark_style()
-            } else {
-                T::default_light_style()
-            })
-        });
+    /// Get the style from the system.
+    ///
+    /// This is an alias for [`StyleSystem::resolve`].
+    pub fn get<T: ElementStyle>(&mut self, class: ClassID) -> &T {
+        self.resolve::<T>(class)
+    }
 
-        entry.downcast_ref().unwrap()
+    /// Get the style from the system with the alpha channel of all of its
+    /// colors multiplied by the given opacity (see
+    /// [`ElementStyle::multiply_alpha`]).
+    ///
+    /// No copy is made if `opacity` is `1.0`.
+    pub fn get_with_opacity<T: ElementStyle + Clone>(
+        &mut self,
+        class: ClassID,
+        opacity: f32,
+    ) -> Cow<'_, T> {
+        let style = self.resolve::<T>(class);
+
+        if opacity >= 1.0 {
+            Cow::Borrowed(style)
+        } else {
+            let mut style = style.clone();
+            style.multiply_alpha(opacity);
+            Cow::Owned(style)
+        }
     }
 
     /// Get an Rc pointer to the style from the system.
@@ -95,24 +452,262 @@ impl StyleSystem {
     ///
     /// The returned value is gauranteed to be of type `T`.
     ///
-    /// If the style doesn't exist in the system, the default style will be
-    /// inserted and returned.
+    /// The style is resolved the same way as in [`StyleSystem::resolve`].
     pub fn get_rc<T: ElementStyle>(&mut self, class: ClassID) -> Rc<dyn Any> {
-        let key = Key {
-            element_type_id: T::ID,
+        Rc::clone(self.resolve_rc::<T>(class))
+    }
+
+    fn resolve_rc<T: ElementStyle>(&mut self, class: ClassID) -> &Rc<dyn Any> {
+        let key = ResolvedKey {
+            type_id: TypeId::of::<T>(),
             class,
             is_dark_theme: self.use_dark_theme,
         };
 
-        let entry = self.styles.entry(key);
-        let entry = entry.or_insert_with(|| {
-            Rc::new(if self.use_dark_theme {
-                T::default_dark_style()
-            } else {
-                T::default_light_style()
-            })
-        });
+        if !self.resolved.contains_key(&key) {
+            let style = self.lookup::<T>(class);
+            self.resolved.insert(key, style);
+        }
+
+        self.resolved.get(&key).unwrap()
+    }
+
+    fn lookup<T: ElementStyle>(&mut self, class: ClassID) -> Rc<dyn Any> {
+        self.check_style_id::<T>();
+
+        let is_dark_theme = self.use_dark_theme;
+
+        for class in [class, CLASS_DEFAULT] {
+            if let Some(entry) = self.styles.get(&Key {
+                element_type_id: T::ID,
+                class,
+                is_dark_theme,
+            }) {
+                return Rc::clone(entry.get());
+            }
+        }
+
+        let text_scale = self.text_scale;
+        let entry = self
+            .built_in
+            .entry((T::ID, is_dark_theme))
+            .or_insert_with(|| StyleEntry::new_built_in::<T>(is_dark_theme, text_scale));
+
+        Rc::clone(entry.get())
+    }
+
+    /// Panic if a different style type has already used the ID of `T`, since
+    /// the styles of the two types would silently overwrite each other.
+    ///
+    /// This check is only done in debug builds.
+    #[track_caller]
+    fn check_style_id<T: ElementStyle>(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            let (type_id, type_name) = *self
+                .registered_ids
+                .entry(T::ID)
+                .or_insert((TypeId::of::<T>(), std::any::type_name::<T>()));
+
+            if type_id != TypeId::of::<T>() {
+                panic!(
+                    "The style types `{}` and `{}` both use the style ID \"{}\". Each `ElementStyle` must have a unique `ElementStyle::ID`.",
+                    type_name,
+                    std::any::type_name::<T>(),
+                    T::ID,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct WidthStyle(f32);
+
+    impl Default for WidthStyle {
+        fn default() -> Self {
+            Self(1.0)
+        }
+    }
+
+    impl ElementStyle for WidthStyle {
+        const ID: &'static str = "wdth";
+
+        fn default_light_style() -> Self {
+            Self(-1.0)
+        }
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_the_default_class() {
+        let mut style_system = StyleSystem::new(true);
+
+        // Nothing added: the built-in default for the current theme.
+        assert_eq!(style_system.resolve::<WidthStyle>(5), &WidthStyle(1.0));
+        assert!(!style_system.contains::<WidthStyle>(5));
+
+        // The cached result is invalidated when a style is added.
+        style_system.add(CLASS_DEFAULT, true, WidthStyle(2.0));
+        assert_eq!(style_system.resolve::<WidthStyle>(5), &WidthStyle(2.0));
+
+        style_system.add(5, true, WidthStyle(3.0));
+        assert_eq!(style_system.resolve::<WidthStyle>(5), &WidthStyle(3.0));
+        assert_eq!(style_system.resolve::<WidthStyle>(6), &WidthStyle(2.0));
+        assert!(style_system.contains::<WidthStyle>(5));
+
+        style_system.remove::<WidthStyle>(5, true);
+        assert_eq!(style_system.resolve::<WidthStyle>(5), &WidthStyle(2.0));
+
+        // Styles are never borrowed from the other theme.
+        assert!(style_system.set_dark_theme(false));
+        assert!(!style_system.set_dark_theme(false));
+        assert_eq!(style_system.resolve::<WidthStyle>(5), &WidthStyle(-1.0));
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct AlphaStyle(f32);
+
+    impl Default for AlphaStyle {
+        fn default() -> Self {
+            Self(1.0)
+        }
+    }
+
+    impl ElementStyle for AlphaStyle {
+        const ID: &'static str = "alpha";
+
+        fn multiply_alpha(&mut self, multiplier: f32) {
+            self.0 *= multiplier;
+        }
+    }
+
+    #[test]
+    fn test_get_with_opacity_fades_a_copy_of_the_style() {
+        let mut style_system = StyleSystem::new(true);
+        style_system.add(CLASS_DEFAULT, true, AlphaStyle(0.8));
+
+        // A fully opaque element uses the style as is.
+        let style = style_system.get_with_opacity::<AlphaStyle>(CLASS_DEFAULT, 1.0);
+        assert!(matches!(style, Cow::Borrowed(&AlphaStyle(a)) if a == 0.8));
+
+        let style = style_system.get_with_opacity::<AlphaStyle>(CLASS_DEFAULT, 0.5);
+        assert!(matches!(style, Cow::Owned(AlphaStyle(a)) if a == 0.4));
+
+        // The style in the system is left unchanged.
+        assert_eq!(
+            style_system.get::<AlphaStyle>(CLASS_DEFAULT),
+            &AlphaStyle(0.8)
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "both use the style ID \"wdth\"")]
+    fn test_two_style_types_with_the_same_id_panic() {
+        #[derive(Default)]
+        struct OtherWidthStyle;
+
+        impl ElementStyle for OtherWidthStyle {
+            const ID: &'static str = "wdth";
+        }
+
+        let mut style_system = StyleSystem::new(true);
+        style_system.add(CLASS_DEFAULT, true, WidthStyle(2.0));
+        // Using the same type again is fine.
+        style_system.resolve::<WidthStyle>(CLASS_DEFAULT);
+
+        style_system.resolve::<OtherWidthStyle>(CLASS_DEFAULT);
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Default, Debug, Clone, PartialEq, serde::Deserialize)]
+    struct TestStyle {
+        width: f32,
+        #[serde(default)]
+        name: String,
+    }
+
+    #[cfg(feature = "serde")]
+    impl ElementStyle for TestStyle {
+        const ID: &'static str = "test";
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_load_styles_for_registered_types() {
+        let mut style_system = StyleSystem::new(true);
+        assert!(!style_system.register_loadable::<TestStyle>());
+
+        let contents = r#"[
+            { "element": "test", "class": 3, "style": { "width": 2.0 } },
+            { "element": "test", "dark_theme": false, "style": { "width": 5.0, "name": "light" } }
+        ]"#;
+
+        // The first style is used for both themes.
+        assert_eq!(style_system.load_from_str(contents).unwrap(), 3);
+        assert_eq!(style_system.get::<TestStyle>(3).width, 2.0);
+        assert_eq!(style_system.get::<TestStyle>(0), &TestStyle::default());
+
+        style_system.use_dark_theme = false;
+        assert_eq!(style_system.get::<TestStyle>(3).width, 2.0);
+        assert_eq!(style_system.get::<TestStyle>(0).name, "light");
+
+        // Loading again overwrites the previous styles.
+        let contents = r#"[{ "element": "test", "class": 3, "style": { "width": 8.0 } }]"#;
+        assert_eq!(style_system.load_from_str(contents).unwrap(), 2);
+        assert_eq!(style_system.get::<TestStyle>(3).width, 8.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_built_in_styles_are_loadable() {
+        use crate::prelude::LabelStyle;
+
+        let mut style_system = StyleSystem::new(true);
+
+        // Fields which are not in the file keep their default values.
+        let contents = format!(
+            r#"[{{ "element": "{}", "style": {{ "truncate_with_ellipsis": true }} }}]"#,
+            LabelStyle::ID
+        );
+        assert_eq!(style_system.load_from_str(&contents).unwrap(), 2);
+        assert_eq!(
+            style_system.get::<LabelStyle>(0),
+            &LabelStyle {
+                truncate_with_ellipsis: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_invalid_style_files_change_nothing() {
+        let mut style_system = StyleSystem::new(true);
+        style_system.register_loadable::<TestStyle>();
+
+        assert!(matches!(
+            style_system.load_from_str(r#"[{ "element": "not_registered", "style": {} }]"#),
+            Err(StyleLoadError::UnregisteredStyle(_))
+        ));
+        assert!(matches!(
+            style_system.load_from_str(
+                r#"[
+                    { "element": "test", "style": { "width": 1.0 } },
+                    { "element": "test", "class": 2, "style": { "width": "wide" } }
+                ]"#
+            ),
+            Err(StyleLoadError::InvalidStyle { class: 2, .. })
+        ));
+        assert!(matches!(
+            style_system.load_from_str("["),
+            Err(StyleLoadError::Parse(_))
+        ));
 
-        Rc::clone(&entry)
+        assert_eq!(style_system.get::<TestStyle>(0), &TestStyle::default());
     }
 }
@@ -147,6 +147,50 @@ pub fn radio_btn(config: &Config) -> RadioButtonStyle {
     }
 }
 
+pub fn checkbox(config: &Config) -> CheckboxStyle {
+    let box_quad = QuadStyle {
+        bg: background(TOGGLE_OFF_BG_COLOR),
+        border: BorderStyle {
+            color: BUTTON_BORDER_COLOR,
+            width: BORDER_WIDTH,
+            radius: config.radius.into(),
+        },
+        flags: QuadFlags::SNAP_ALL_TO_NEAREST_PIXEL,
+    };
+
+    CheckboxStyle {
+        box_quad_hover: Some(QuadStyle {
+            bg: background(TOGGLE_OFF_BG_COLOR_HOVER),
+            border: BorderStyle {
+                color: BUTTON_BORDER_COLOR_HOVER,
+                ..box_quad.border
+            },
+            ..box_quad
+        }),
+        box_quad_checked: Some(QuadStyle {
+            bg: background(config.accent_color),
+            border: BorderStyle {
+                color: config.accent_color,
+                ..box_quad.border
+            },
+            ..box_quad
+        }),
+        box_quad_checked_hover: Some(QuadStyle {
+            bg: background(config.accent_color_hover),
+            border: BorderStyle {
+                color: config.accent_color_hover,
+                ..box_quad.border
+            },
+            ..box_quad
+        }),
+        box_quad: box_quad.clone(),
+        checkmark_color: TEXT_COLOR,
+        checkmark_color_hover: Some(TEXT_COLOR_BRIGHT),
+        cursor_icon: Some(CursorIcon::Pointer),
+        ..Default::default()
+    }
+}
+
 pub fn resize_handle() -> ResizeHandleStyle {
     ResizeHandleStyle {
         drag_handle_color_hover: Some(SCROLL_BAR_COLOR_HOVER),
@@ -461,6 +505,8 @@ pub fn load(config: Config, res: &mut ResourceCtx) {
         .add(ClassID::default(), true, switch(&config));
     res.style_system
         .add(ClassID::default(), true, radio_btn(&config));
+    res.style_system
+        .add(ClassID::default(), true, checkbox(&config));
     res.style_system
         .add(ClassID::default(), true, resize_handle());
     res.style_system.add(ClassID::default(), true, scroll_bar());
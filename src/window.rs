@@ -1,22 +1,34 @@
-use keyboard_types::{CompositionEvent, Modifiers};
+use keyboard_types::{Code, CompositionEvent, CompositionState, KeyState, Modifiers};
 use rootvg::color::PackedSrgb;
 use rootvg::math::{Rect, RectI32};
-use rootvg::surface::{DefaultSurface, DefaultSurfaceConfig};
+use rootvg::surface::DefaultSurfaceConfig;
 use std::time::{Duration, Instant};
 
 use crate::action_queue::ActionSender;
+use crate::application::{AcceleratorPriority, Accelerators, TimerInterval};
 use crate::clipboard::Clipboard;
-use crate::element_system::ElementSystem;
+use crate::element_system::{
+    ElementSystem, ElementSystemConfig, RenderBackend, RenderStats, Viewport, WgpuRenderBackend,
+};
+#[cfg(feature = "headless")]
+use crate::element_system::{HeadlessRenderBackend, HeadlessRenderBackendError, ImageBuffer};
 use crate::event::{
     CanvasEvent, EventCaptureStatus, KeyboardEvent, PointerButton, PointerEvent, PointerType,
-    WheelDeltaType,
+    TouchEvent, TouchPhase, WheelDeltaType,
 };
 use crate::math::{
-    to_logical_size_i32, PhysicalPoint, PhysicalSizeI32, Point, ScaleFactor, Size, Vector, ZIndex,
+    to_logical_size_i32, PhysicalPoint, PhysicalRect, PhysicalSizeI32, Point, ScaleFactor, Size,
+    Transform, Vector, ZIndex,
+};
+use crate::prelude::{
+    ActionReceiver, ElementBuilder, ElementHandle, ElementInfo, ElementKey, ResourceCtx,
+    SizableElement,
 };
-use crate::prelude::{ActionReceiver, ElementBuilder, ElementHandle, ResourceCtx};
 use crate::style::ClassID;
-use crate::{CursorIcon, ScissorRectID, TooltipInfo};
+use crate::{
+    CursorIcon, DebugGridConfig, DebugOverlayFlags, DebugZIndexConfig, FocusWrapMode,
+    ScissorRectID, TooltipInfo,
+};
 
 #[cfg(feature = "winit")]
 mod winit_backend;
@@ -28,6 +40,11 @@ mod baseview_backend;
 #[cfg(feature = "baseview")]
 use baseview_backend as windowing_backend;
 
+#[cfg(feature = "headless")]
+mod headless_backend;
+#[cfg(feature = "headless")]
+pub use headless_backend::run_headless;
+
 #[cfg(feature = "baseview")]
 pub use windowing_backend::run_parented;
 pub use windowing_backend::{run_blocking, OpenWindowError};
@@ -36,13 +53,11 @@ pub type WindowID = u32;
 
 pub const MAIN_WINDOW: WindowID = 0;
 
-// TODO: Get click intervals from OS.
-const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(300);
-
 #[derive(Clone, Copy)]
 struct PointerBtnState {
     is_down: bool,
     prev_down_instant: Option<Instant>,
+    prev_down_pos: Point,
     click_count: usize,
 }
 
@@ -51,11 +66,42 @@ impl Default for PointerBtnState {
         Self {
             is_down: false,
             prev_down_instant: None,
+            prev_down_pos: Point::zero(),
             click_count: 1,
         }
     }
 }
 
+impl PointerBtnState {
+    /// Register a press of the button and return the new click count.
+    ///
+    /// The press continues a multi-click if it is within `interval` of the
+    /// previous press and within `max_distance` points of it.
+    fn on_pressed(
+        &mut self,
+        now: Instant,
+        position: Point,
+        interval: Duration,
+        max_distance: f32,
+    ) -> usize {
+        let continues_multi_click = self.prev_down_instant.is_some_and(|prev_down_instant| {
+            now.saturating_duration_since(prev_down_instant) < interval
+                && (position - self.prev_down_pos).length() <= max_distance
+        });
+
+        if continues_multi_click {
+            self.click_count += 1;
+        } else {
+            self.click_count = 1;
+        }
+
+        self.prev_down_instant = Some(now);
+        self.prev_down_pos = position;
+
+        self.click_count
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum PointerLockState {
     NotLocked,
@@ -69,6 +115,118 @@ impl PointerLockState {
     }
 }
 
+/// The pointer debounce timer of a window.
+///
+/// Pointer move events which arrive sooner than the debounce interval after
+/// the previously processed pointer move event are held back, and the latest
+/// one is processed once the interval has elapsed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PointerDebounce {
+    /// The debounce interval of this window, or `None` to use the interval
+    /// in the `AppConfig`.
+    pub interval: Option<TimerInterval>,
+    prev_instant: Option<Instant>,
+    requested_resume: Option<Instant>,
+}
+
+impl PointerDebounce {
+    pub fn new(interval: Option<TimerInterval>) -> Self {
+        Self {
+            interval,
+            prev_instant: None,
+            requested_resume: None,
+        }
+    }
+
+    /// The resolved debounce interval of this window.
+    pub fn interval(&self, app_interval: TimerInterval, refresh_rate_millihertz: u32) -> Duration {
+        self.interval
+            .unwrap_or(app_interval)
+            .to_duration(refresh_rate_millihertz)
+    }
+
+    /// Called when a new pointer move event has been queued.
+    ///
+    /// Returns `true` if the event should be processed now. Otherwise the
+    /// event should be processed at the instant returned by
+    /// [`PointerDebounce::requested_resume`].
+    pub fn on_pointer_moved(&mut self, now: Instant, interval: Duration) -> bool {
+        if let Some(prev_instant) = self.prev_instant {
+            let resume_instant = prev_instant + interval;
+
+            if now < resume_instant {
+                self.requested_resume = Some(resume_instant);
+                return false;
+            }
+        }
+
+        self.prev_instant = Some(now);
+        self.requested_resume = None;
+        true
+    }
+
+    /// The instant at which the held back pointer move event should be
+    /// processed, if there is one.
+    pub fn requested_resume(&self) -> Option<Instant> {
+        self.requested_resume
+    }
+
+    /// Returns `true` if a held back pointer move event is due to be
+    /// processed at the given instant.
+    pub fn resume(&mut self, now: Instant) -> bool {
+        match self.requested_resume {
+            Some(resume_instant) if resume_instant <= now => {
+                self.prev_instant = Some(now);
+                self.requested_resume = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Limits how often a window is redrawn (see `AppConfig::max_fps`).
+///
+/// Redraws which are requested sooner than the minimum frame interval after
+/// the previous frame are held back until the interval has elapsed.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct FrameLimiter {
+    prev_frame_instant: Option<Instant>,
+    requested_resume: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// Called when the window needs to be redrawn.
+    ///
+    /// Returns `true` if the redraw should be requested now. Otherwise it
+    /// should be requested at the instant returned by
+    /// [`FrameLimiter::requested_resume`].
+    pub fn can_redraw(&mut self, now: Instant, min_frame_interval: Duration) -> bool {
+        if let Some(prev_frame_instant) = self.prev_frame_instant {
+            let resume_instant = prev_frame_instant + min_frame_interval;
+
+            if now < resume_instant {
+                self.requested_resume = Some(resume_instant);
+                return false;
+            }
+        }
+
+        self.requested_resume = None;
+        true
+    }
+
+    /// Called when a frame has been rendered.
+    pub fn on_frame(&mut self, now: Instant) {
+        self.prev_frame_instant = Some(now);
+    }
+
+    /// The instant at which the held back redraw should be requested, if
+    /// there is one.
+    pub fn requested_resume(&self) -> Option<Instant> {
+        self.requested_resume
+    }
+}
+
 pub(crate) trait WindowBackend {
     fn set_pointer_position(
         &mut self,
@@ -90,6 +248,28 @@ pub(crate) trait WindowBackend {
     fn set_maximized(&mut self, window_id: WindowID, maximized: bool);
     fn focus_window(&mut self, window_id: WindowID);
     fn set_window_title(&mut self, window_id: WindowID, title: String);
+    fn set_window_icon(&mut self, window_id: WindowID, icon: Option<WindowIcon>);
+    fn set_fullscreen(&mut self, window_id: WindowID, mode: Option<FullscreenMode>);
+    fn is_fullscreen(&mut self, window_id: WindowID) -> bool;
+    fn outer_position(&mut self, window_id: WindowID) -> Option<PhysicalPoint>;
+    /// The current geometry of the window, with the size converted to points
+    /// using the given scale factor.
+    fn current_geometry(
+        &mut self,
+        window_id: WindowID,
+        scale_factor: ScaleFactor,
+    ) -> Option<WindowGeometry>;
+    fn available_monitors(&mut self) -> Vec<MonitorInfo>;
+    fn current_monitor(&mut self, window_id: WindowID) -> Option<MonitorInfo>;
+    fn set_outer_position(&mut self, window_id: WindowID, position: PhysicalPoint);
+    fn set_ime_allowed(&mut self, window_id: WindowID, allowed: bool);
+    fn set_ime_cursor_area(&mut self, window_id: WindowID, area: Rect, scale_factor: ScaleFactor);
+    fn drag_window(&mut self, window_id: WindowID) -> Result<(), ()>;
+    fn drag_resize_window(
+        &mut self,
+        window_id: WindowID,
+        direction: ResizeDirection,
+    ) -> Result<(), ()>;
     fn create_window<A: Clone + 'static>(
         &mut self,
         window_id: WindowID,
@@ -100,6 +280,23 @@ pub(crate) trait WindowBackend {
     fn close_window(&mut self, window_id: WindowID);
 }
 
+/// The render backend of a window.
+pub(crate) enum WindowRenderBackend {
+    Surface(WgpuRenderBackend),
+    #[cfg(feature = "headless")]
+    Headless(HeadlessRenderBackend),
+}
+
+impl WindowRenderBackend {
+    fn resize(&mut self, physical_size: PhysicalSizeI32, scale_factor: ScaleFactor) {
+        match self {
+            Self::Surface(backend) => backend.resize(physical_size, scale_factor),
+            #[cfg(feature = "headless")]
+            Self::Headless(backend) => backend.resize(physical_size),
+        }
+    }
+}
+
 pub(crate) struct WindowState<A: Clone + 'static> {
     pub queued_pointer_position: Option<PhysicalPoint>,
     pub queued_pointer_delta: Option<(f64, f64)>,
@@ -110,21 +307,48 @@ pub(crate) struct WindowState<A: Clone + 'static> {
     pub(crate) scale_factor: ScaleFactor,
     pub(crate) scale_factor_recip: f32,
     pub(crate) pointer_lock_state: PointerLockState,
-
-    renderer: rootvg::Canvas,
-    surface: Option<DefaultSurface<'static>>,
-    multisample: wgpu::MultisampleState,
+    pub(crate) is_fullscreen: bool,
+    pub(crate) outer_position: Option<PhysicalPoint>,
+    pub(crate) is_maximized: bool,
+    pub(crate) current_monitor: Option<MonitorInfo>,
+    pub(crate) pointer_debounce: PointerDebounce,
+    pub(crate) frame_limiter: FrameLimiter,
+
+    render_backend: Option<WindowRenderBackend>,
     logical_size: Size,
     physical_size: PhysicalSizeI32,
     system_scale_factor: ScaleFactor,
     scale_factor_config: ScaleFactorConfig,
     pointer_btn_states: [PointerBtnState; 5],
+    multi_click_interval: Duration,
+    multi_click_max_distance: f32,
 
     modifiers: Modifiers,
     current_cursor_icon: CursorIcon,
 }
 
 impl<A: Clone + 'static> WindowState<A> {
+    pub fn geometry(&self) -> WindowGeometry {
+        WindowGeometry {
+            position: self.outer_position,
+            size: self.logical_size,
+            scale_factor: self.scale_factor,
+            maximized: self.is_maximized,
+        }
+    }
+
+    /// The earliest instant at which a held back pointer move event or redraw
+    /// of this window is due, if there is one.
+    pub fn requested_resume(&self) -> Option<Instant> {
+        [
+            self.pointer_debounce.requested_resume(),
+            self.frame_limiter.requested_resume(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
     pub fn set_size(&mut self, new_size: PhysicalSizeI32, new_system_scale_factor: ScaleFactor) {
         if self.physical_size == new_size && self.system_scale_factor == new_system_scale_factor {
             return;
@@ -140,7 +364,7 @@ impl<A: Clone + 'static> WindowState<A> {
         self.scale_factor_recip = scale_factor.recip();
 
         self.element_system.resize(new_size, scale_factor);
-        self.surface
+        self.render_backend
             .as_mut()
             .unwrap()
             .resize(new_size, scale_factor);
@@ -166,7 +390,7 @@ impl<A: Clone + 'static> WindowState<A> {
         self.scale_factor_recip = scale_factor.recip();
 
         self.element_system.resize(self.physical_size, scale_factor);
-        self.surface
+        self.render_backend
             .as_mut()
             .unwrap()
             .resize(self.physical_size, scale_factor);
@@ -215,6 +439,18 @@ impl<A: Clone + 'static> WindowState<A> {
             .handle_event(&CanvasEvent::WindowShown, res, &mut self.clipboard);
     }
 
+    /// Perform an action requested by assistive technology. Returns `false`
+    /// if the action is not supported by the target element.
+    #[cfg(feature = "accessibility")]
+    pub fn handle_accessibility_action(
+        &mut self,
+        request: &accesskit::ActionRequest,
+        res: &mut ResourceCtx,
+    ) -> bool {
+        self.element_system
+            .handle_accessibility_action(request, res, &mut self.clipboard)
+    }
+
     pub fn set_modifiers(&mut self, modifiers: Modifiers) {
         self.modifiers = modifiers;
     }
@@ -280,7 +516,7 @@ impl<A: Clone + 'static> WindowState<A> {
                 position: new_pos,
                 delta,
                 is_locked: false,
-                pointer_type: PointerType::default(),
+                pointer_type: PointerType::Mouse,
                 modifiers: self.modifiers,
                 just_entered: false,
             }),
@@ -289,15 +525,34 @@ impl<A: Clone + 'static> WindowState<A> {
         );
     }
 
+    pub fn handle_touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: PhysicalPoint,
+        pointer_type: PointerType,
+        res: &mut ResourceCtx,
+    ) {
+        let position = crate::math::to_logical_point_from_recip(position, self.scale_factor_recip);
+
+        self.element_system.handle_event(
+            &CanvasEvent::Touch(TouchEvent {
+                id,
+                phase,
+                position,
+                pointer_type,
+                modifiers: self.modifiers,
+            }),
+            res,
+            &mut self.clipboard,
+        );
+    }
+
     pub fn handle_locked_pointer_delta(&mut self, delta: Vector, res: &mut ResourceCtx) {
         self.element_system.handle_event(
-            &CanvasEvent::Pointer(PointerEvent::Moved {
-                position: self.prev_pointer_pos.unwrap_or_default(),
-                delta: Some(delta),
-                is_locked: false,
-                pointer_type: PointerType::default(),
+            &CanvasEvent::Pointer(PointerEvent::DeltaMoved {
+                delta,
                 modifiers: self.modifiers,
-                just_entered: false,
             }),
             res,
             &mut self.clipboard,
@@ -316,19 +571,18 @@ impl<A: Clone + 'static> WindowState<A> {
             JustUnpressed,
         }
 
+        let position = self.prev_pointer_pos.unwrap_or(Point::zero());
+
         let (state, click_count) = {
             let btn_state = &mut self.pointer_btn_states[button as usize];
 
             let s = if !btn_state.is_down && is_down {
-                if let Some(prev_down_instant) = btn_state.prev_down_instant.take() {
-                    if prev_down_instant.elapsed() < DOUBLE_CLICK_INTERVAL {
-                        btn_state.click_count += 1;
-                    } else {
-                        btn_state.click_count = 1;
-                    }
-                }
-
-                btn_state.prev_down_instant = Some(Instant::now());
+                btn_state.on_pressed(
+                    Instant::now(),
+                    position,
+                    self.multi_click_interval,
+                    self.multi_click_max_distance,
+                );
 
                 State::JustPressed
             } else if btn_state.is_down && !is_down {
@@ -342,15 +596,13 @@ impl<A: Clone + 'static> WindowState<A> {
             (s, btn_state.click_count)
         };
 
-        let position = self.prev_pointer_pos.unwrap_or(Point::zero());
-
         match state {
             State::JustPressed => {
                 self.element_system.handle_event(
                     &CanvasEvent::Pointer(PointerEvent::ButtonJustPressed {
                         position,
                         button,
-                        pointer_type: PointerType::default(),
+                        pointer_type: PointerType::Mouse,
                         click_count,
                         modifiers: self.modifiers,
                     }),
@@ -363,7 +615,7 @@ impl<A: Clone + 'static> WindowState<A> {
                     &CanvasEvent::Pointer(PointerEvent::ButtonJustReleased {
                         position,
                         button,
-                        pointer_type: PointerType::default(),
+                        pointer_type: PointerType::Mouse,
                         click_count,
                         modifiers: self.modifiers,
                     }),
@@ -375,14 +627,20 @@ impl<A: Clone + 'static> WindowState<A> {
         }
     }
 
-    pub fn handle_mouse_wheel(&mut self, delta_type: WheelDeltaType, res: &mut ResourceCtx) {
+    pub fn handle_mouse_wheel(
+        &mut self,
+        delta_type: WheelDeltaType,
+        phase: TouchPhase,
+        res: &mut ResourceCtx,
+    ) {
         let position = self.prev_pointer_pos.unwrap_or(Point::zero());
 
         self.element_system.handle_event(
             &CanvasEvent::Pointer(PointerEvent::ScrollWheel {
                 position,
                 delta_type,
-                pointer_type: PointerType::default(),
+                phase,
+                pointer_type: PointerType::Mouse,
                 modifiers: self.modifiers,
             }),
             res,
@@ -390,23 +648,42 @@ impl<A: Clone + 'static> WindowState<A> {
         );
     }
 
+    /// Set how far the next frame is between the last tick and the next one.
+    ///
+    /// See [`RenderContext::interpolation_alpha`](crate::prelude::RenderContext::interpolation_alpha).
+    #[allow(unused)]
+    pub fn set_interpolation_alpha(&mut self, alpha: f32) {
+        self.element_system.set_interpolation_alpha(alpha);
+    }
+
     pub fn render<P: FnOnce()>(
         &mut self,
         pre_present_notify: P,
         res: &mut ResourceCtx,
     ) -> Result<(), wgpu::SurfaceError> {
-        let surface = self.surface.as_ref().unwrap();
-
-        self.element_system.render(
-            &surface.surface,
-            &surface.device,
-            &surface.queue,
-            surface.format(),
-            self.multisample,
-            &mut self.renderer,
-            pre_present_notify,
-            res,
-        )
+        self.frame_limiter.on_frame(Instant::now());
+
+        match self.render_backend.as_mut().unwrap() {
+            WindowRenderBackend::Surface(backend) => {
+                self.element_system.render(backend, pre_present_notify, res)
+            }
+            #[cfg(feature = "headless")]
+            WindowRenderBackend::Headless(backend) => self
+                .element_system
+                .render(backend, pre_present_notify, res)
+                .map_err(|e| match e {}),
+        }
+    }
+
+    /// Read back the pixels of the last rendered frame.
+    ///
+    /// Returns `None` if this window is not rendered with a headless backend.
+    #[cfg(feature = "headless")]
+    pub fn capture(&mut self) -> Option<Result<ImageBuffer, HeadlessRenderBackendError>> {
+        match self.render_backend.as_mut() {
+            Some(WindowRenderBackend::Headless(backend)) => Some(backend.capture()),
+            _ => None,
+        }
     }
 
     pub fn logical_size(&self) -> Size {
@@ -418,6 +695,7 @@ impl<A: Clone + 'static> WindowState<A> {
         res: &'b mut ResourceCtx,
         action_sender: &'b mut ActionSender<A>,
         action_receiver: &'b mut ActionReceiver<A>,
+        accelerators: &'b Accelerators<A>,
     ) -> WindowContext<'b, A> {
         WindowContext {
             element_system: &mut self.element_system,
@@ -425,6 +703,7 @@ impl<A: Clone + 'static> WindowState<A> {
             clipboard: &mut self.clipboard,
             action_sender,
             action_receiver,
+            accelerators,
             z_index_stack: Vec::new(),
             scissor_rect_stack: Vec::new(),
             class_stack: Vec::new(),
@@ -449,6 +728,14 @@ impl<A: Clone + 'static> WindowState<A> {
         self.element_system.pointer_lock_request()
     }
 
+    pub fn new_ime_cursor_area_request(&mut self) -> Option<Rect> {
+        self.element_system.ime_cursor_area_request()
+    }
+
+    pub fn new_ime_allowed_request(&mut self) -> Option<bool> {
+        self.element_system.ime_allowed_request()
+    }
+
     pub fn on_theme_changed(&mut self, res: &mut ResourceCtx) {
         self.element_system
             .on_theme_changed(res, &mut self.clipboard);
@@ -469,7 +756,7 @@ impl<A: Clone + 'static> Drop for WindowState<A> {
         // For some reason if the surface isn't dropped before the other
         // structs it causes a segfault. This is probably a bug in wgpu
         // or winit.
-        self.surface = None;
+        self.render_backend = None;
     }
 }
 
@@ -486,6 +773,16 @@ pub struct WindowConfig {
     /// The clear color.
     pub clear_color: PackedSrgb,
 
+    /// Skip clearing the window to `clear_color` before rendering each frame.
+    ///
+    /// This is a performance option for UIs that are fully covered by opaque
+    /// elements, where clearing is wasted work. If the window is *not* fully
+    /// covered, the contents of the previous frames may show through the
+    /// uncovered regions, causing visual artifacts.
+    ///
+    /// By default this is set to `false`.
+    pub skip_clear: bool,
+
     /// An estimate for how many elements are expected to be in this view in a
     /// typical use case. This is used to pre-allocate capacity to improve slightly
     /// improve load-up times.
@@ -500,6 +797,64 @@ pub struct WindowConfig {
     pub hover_timeout_duration: Duration,
 
     pub scroll_wheel_timeout_duration: Duration,
+
+    /// The maximum duration between two presses of a pointer button for them
+    /// to count as a double click (or triple click, and so on). This is
+    /// reported in the `click_count` of `PointerEvent::ButtonJustPressed` and
+    /// `PointerEvent::ButtonJustReleased`.
+    ///
+    /// By default this is set to 0.3 seconds.
+    pub multi_click_interval: Duration,
+
+    /// The maximum distance in points between two presses of a pointer button
+    /// for them to count as a double click (or triple click, and so on).
+    ///
+    /// By default this is set to `4.0`.
+    pub multi_click_max_distance: f32,
+
+    /// How long a touch has to be held down before it sends a
+    /// `PointerEvent::ContextMenu` event, the same as a secondary click.
+    ///
+    /// By default this is set to 0.5 seconds.
+    pub long_press_duration: Duration,
+
+    /// The icon of the window.
+    ///
+    /// Note, this is not supported on all platforms (i.e. Wayland and MacOS).
+    ///
+    /// By default this is set to `None`.
+    pub icon: Option<WindowIcon>,
+
+    /// Whether or not the window should open in fullscreen mode.
+    ///
+    /// By default this is set to `None` (windowed).
+    pub fullscreen: Option<FullscreenMode>,
+
+    /// The minimum interval between processed pointer move events in this
+    /// window, or `None` to use `AppConfig::pointer_debounce_interval`.
+    ///
+    /// Set this to `Some(TimerInterval::Fixed(Duration::ZERO))` to disable
+    /// pointer throttling in this window (i.e. for a window with a control
+    /// that needs every pointer event).
+    ///
+    /// By default this is set to `None`.
+    pub pointer_debounce_interval: Option<TimerInterval>,
+
+    /// A previously saved geometry to restore when the window is opened (see
+    /// [`AppContext::window_geometry`](crate::AppContext::window_geometry)).
+    ///
+    /// If this is set, then it overrides `size`. If the saved position is not
+    /// visible on any of the currently available monitors (i.e. the window
+    /// was saved on a display which has since been disconnected), then the
+    /// window is moved onto the nearest available monitor.
+    ///
+    /// Note, the baseview backend only restores the size, since the position
+    /// of plugin windows is controlled by the host. The headless backend
+    /// restores the position and maximized state as-is, since it has no
+    /// monitors to move the window onto.
+    ///
+    /// By default this is set to `None`.
+    pub geometry: Option<WindowGeometry>,
 }
 
 impl Default for WindowConfig {
@@ -512,9 +867,211 @@ impl Default for WindowConfig {
             focus_on_creation: true,
             scale_factor: ScaleFactorConfig::default(),
             clear_color: PackedSrgb::BLACK,
+            skip_clear: false,
             preallocate_for_this_many_elements: 0,
             hover_timeout_duration: Duration::from_millis(500),
             scroll_wheel_timeout_duration: Duration::from_millis(250),
+            multi_click_interval: Duration::from_millis(300),
+            multi_click_max_distance: 4.0,
+            long_press_duration: Duration::from_millis(500),
+            icon: None,
+            fullscreen: None,
+            pointer_debounce_interval: None,
+            geometry: None,
+        }
+    }
+}
+
+impl WindowConfig {
+    /// The size of the window when it is opened, taking the saved geometry
+    /// into account.
+    pub(crate) fn initial_size(&self) -> Size {
+        self.geometry
+            .map(|geometry| geometry.size)
+            .unwrap_or(self.size)
+    }
+
+    pub(crate) fn element_system_config(&self) -> ElementSystemConfig {
+        ElementSystemConfig {
+            clear_color: self.clear_color,
+            skip_clear: self.skip_clear,
+            preallocate_for_this_many_elements: self.preallocate_for_this_many_elements,
+            hover_timeout_duration: self.hover_timeout_duration,
+            scroll_wheel_timeout_duration: self.scroll_wheel_timeout_duration,
+            long_press_duration: self.long_press_duration,
+        }
+    }
+}
+
+/// The position, size, and state of a window, i.e. to save it when the
+/// application closes and restore it with [`WindowConfig::geometry`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowGeometry {
+    /// The position of the top-left corner of the window (including its
+    /// decorations) in physical pixels relative to the top-left corner of
+    /// the desktop.
+    ///
+    /// This is `None` if the backend does not support querying the position
+    /// of windows (i.e. Wayland).
+    pub position: Option<PhysicalPoint>,
+    /// The size of the contents of the window in points.
+    pub size: Size,
+    /// The scale factor of the window.
+    pub scale_factor: ScaleFactor,
+    /// Whether or not the window is maximized.
+    pub maximized: bool,
+}
+
+/// Information about a monitor connected to the system.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorInfo {
+    /// The human-readable name of the monitor (if the platform provides one).
+    pub name: Option<String>,
+    /// The position of the top-left corner of the monitor in physical pixels
+    /// relative to the top-left corner of the desktop.
+    pub position: PhysicalPoint,
+    /// The size of the monitor in physical pixels.
+    pub size: PhysicalSizeI32,
+    /// The scale factor of the monitor.
+    pub scale_factor: ScaleFactor,
+    /// The refresh rate of the monitor in millihertz (if the platform can
+    /// determine it).
+    pub refresh_rate_millihertz: Option<u32>,
+}
+
+impl MonitorInfo {
+    /// The bounds of the monitor in physical pixels relative to the top-left
+    /// corner of the desktop.
+    pub fn rect(&self) -> PhysicalRect {
+        PhysicalRect::new(self.position, self.size.cast())
+    }
+}
+
+/// The minimum number of physical pixels along each axis of a restored window
+/// which must be visible on a monitor for it to be left in place.
+const MIN_VISIBLE_RESTORED_PIXELS: f32 = 50.0;
+
+/// The position to restore a window with the given bounds (in physical
+/// pixels) to, given the bounds of the currently available monitors.
+///
+/// If not enough of the window is visible on any of the monitors, then the
+/// window is moved onto the nearest monitor.
+pub(crate) fn clamp_window_to_monitors(
+    window: PhysicalRect,
+    monitors: &[PhysicalRect],
+) -> PhysicalPoint {
+    let min_width = MIN_VISIBLE_RESTORED_PIXELS.min(window.width());
+    let min_height = MIN_VISIBLE_RESTORED_PIXELS.min(window.height());
+
+    let is_visible = |monitor: &PhysicalRect| {
+        monitor
+            .intersection(&window)
+            .map(|visible| visible.width() >= min_width && visible.height() >= min_height)
+            .unwrap_or(false)
+    };
+
+    if monitors.is_empty() || monitors.iter().any(is_visible) {
+        return window.origin;
+    }
+
+    let center = window.center();
+    let distance_squared = |monitor: &PhysicalRect| {
+        let dx = (monitor.min_x() - center.x)
+            .max(center.x - monitor.max_x())
+            .max(0.0);
+        let dy = (monitor.min_y() - center.y)
+            .max(center.y - monitor.max_y())
+            .max(0.0);
+        dx * dx + dy * dy
+    };
+
+    let monitor = monitors
+        .iter()
+        .min_by(|a, b| distance_squared(a).total_cmp(&distance_squared(b)))
+        .unwrap();
+
+    // If the window is larger than the monitor, then keep its top-left corner
+    // (and its title bar) on the monitor.
+    PhysicalPoint::new(
+        window
+            .min_x()
+            .min(monitor.max_x() - window.width())
+            .max(monitor.min_x()),
+        window
+            .min_y()
+            .min(monitor.max_y() - window.height())
+            .max(monitor.min_y()),
+    )
+}
+
+/// The fullscreen mode of a window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FullscreenMode {
+    /// Borderless fullscreen on the monitor with the given index in the list
+    /// of available monitors.
+    ///
+    /// If `None`, then the monitor the window is currently on will be used.
+    Borderless(Option<usize>),
+    /// Exclusive fullscreen on the monitor the window is currently on,
+    /// switching the monitor to the given video mode.
+    ///
+    /// If the monitor does not support the given video mode, then the window
+    /// will stay windowed.
+    Exclusive(VideoMode),
+}
+
+/// The edge or corner of a window to resize from when calling
+/// [`AppContext::drag_resize_window`](crate::AppContext::drag_resize_window).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResizeDirection {
+    East,
+    North,
+    NorthEast,
+    NorthWest,
+    South,
+    SouthEast,
+    SouthWest,
+    West,
+}
+
+/// A video mode of a monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoMode {
+    /// The resolution in physical pixels.
+    pub size: PhysicalSizeI32,
+    /// The bit depth of this video mode (i.e. `24` for 8 bits per color channel).
+    pub bit_depth: u16,
+    /// The refresh rate in millihertz.
+    pub refresh_rate_millihertz: u32,
+}
+
+/// The pixel data of a window icon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowIcon {
+    /// The pixels of the icon in RGBA8 format (4 bytes per pixel, row-major).
+    pub rgba: Vec<u8>,
+    /// The width of the icon in pixels.
+    pub width: u32,
+    /// The height of the icon in pixels.
+    pub height: u32,
+}
+
+impl WindowIcon {
+    /// Create a new window icon from pixels in RGBA8 format.
+    ///
+    /// The length of `rgba` must be `width * height * 4`, or else the icon
+    /// will fail to be applied to the window.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Self {
+        Self {
+            rgba,
+            width,
+            height,
         }
     }
 }
@@ -549,6 +1106,7 @@ pub struct WindowContext<'a, A: Clone + 'static> {
     pub action_sender: &'a mut ActionSender<A>,
     /// The receiving end of the action queue.
     pub action_receiver: &'a mut ActionReceiver<A>,
+    accelerators: &'a Accelerators<A>,
     element_system: &'a mut ElementSystem<A>,
     z_index_stack: Vec<ZIndex>,
     scissor_rect_stack: Vec<ScissorRectID>,
@@ -641,11 +1199,18 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
         self.scissor_rect_stack.clear();
     }
 
+    /// Reset the style class ID stack
+    pub fn reset_class(&mut self) {
+        self.class_stack.clear();
+    }
+
     /// Get the class ID from the builder value
     pub fn builder_class(&self, class: Option<ClassID>) -> ClassID {
         class.unwrap_or_else(|| self.class())
     }
 
+    /// Build elements with the given z index. Elements built inside of `f`
+    /// use this z index unless a z index is set on their builder.
     pub fn with_z_index<T, F: FnOnce(&mut Self) -> T>(&mut self, z_index: ZIndex, f: F) -> T {
         self.push_z_index(z_index);
         let r = (f)(self);
@@ -653,6 +1218,9 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
         r
     }
 
+    /// Assign elements to the given scissoring rectangle. Elements built
+    /// inside of `f` are assigned to it unless a scissoring rectangle is set
+    /// on their builder (i.e. all the children of a scroll area).
     pub fn with_scissor_rect<T, F: FnOnce(&mut Self) -> T>(
         &mut self,
         scissor_rect: ScissorRectID,
@@ -664,6 +1232,15 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
         r
     }
 
+    /// Build elements with the given style class. Elements built inside of
+    /// `f` use this class unless a class is set on their builder.
+    pub fn with_class<T, F: FnOnce(&mut Self) -> T>(&mut self, class: ClassID, f: F) -> T {
+        self.push_class(class);
+        let r = (f)(self);
+        self.pop_class();
+        r
+    }
+
     pub fn with_z_index_and_scissor_rect<T, F: FnOnce(&mut Self) -> T>(
         &mut self,
         z_index: ZIndex,
@@ -678,11 +1255,28 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
         r
     }
 
+    /// Drop every element in this window, i.e. when switching between pages
+    /// of an app. The scissoring rectangles are kept.
+    ///
+    /// Returns the number of elements that were dropped.
+    pub fn clear_elements(&mut self) -> usize {
+        self.element_system.clear(self.res, self.clipboard)
+    }
+
     pub fn add_element(&mut self, element_builder: ElementBuilder<A>) -> ElementHandle {
         self.element_system
             .add_element(element_builder, &mut self.res, &mut self.clipboard)
     }
 
+    /// The system clipboard.
+    ///
+    /// This can be used to implement app-level commands such as a "Paste"
+    /// menu item or a global keyboard shortcut, regardless of which element
+    /// (if any) has focus.
+    pub fn clipboard(&mut self) -> &mut Clipboard {
+        self.clipboard
+    }
+
     pub fn set_clear_color(&mut self, color: impl Into<PackedSrgb>) {
         self.element_system.clear_color = color.into()
     }
@@ -691,6 +1285,18 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
         self.element_system.clear_color
     }
 
+    /// Set whether or not to skip clearing the window before rendering each
+    /// frame.
+    ///
+    /// See [`WindowConfig::skip_clear`] for the risks of enabling this.
+    pub fn set_skip_clear(&mut self, skip_clear: bool) {
+        self.element_system.skip_clear = skip_clear;
+    }
+
+    pub fn skip_clear(&self) -> bool {
+        self.element_system.skip_clear
+    }
+
     pub fn set_tooltip_actions<S, H>(&mut self, on_show_tooltip: S, on_hide_tooltip: H)
     where
         S: FnMut(TooltipInfo) -> A + 'static,
@@ -740,6 +1346,268 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
             .update_scissor_rect(scissor_rect_id, new_rect, new_scroll_offset)
     }
 
+    /// Nest the given scissoring rectangle inside of another one, or pass
+    /// `None` to un-nest it.
+    ///
+    /// The rectangle of a nested scissoring rectangle is relative to the
+    /// content of its parent, so it moves when the parent moves or
+    /// scrolls (the scroll offsets compose). Its elements are clipped to the
+    /// intersection of its rectangle and the clipping rectangle of its
+    /// parent.
+    ///
+    /// Returns `false` and does nothing if this would create a cycle, or if
+    /// `scissor_rect_id == ScissorRectID::DEFAULT`.
+    ///
+    /// If a scissoring rectangle with either ID does not exist, then one will
+    /// be created.
+    pub fn set_scissor_rect_parent(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        parent: Option<ScissorRectID>,
+    ) -> bool {
+        self.element_system
+            .set_scissor_rect_parent(scissor_rect_id, parent)
+    }
+
+    /// The scissoring rectangle that the given one is nested inside of.
+    pub fn scissor_rect_parent(&mut self, scissor_rect_id: ScissorRectID) -> Option<ScissorRectID> {
+        self.element_system.scissor_rect_parent(scissor_rect_id)
+    }
+
+    /// Set the z index offset of every element assigned to the given
+    /// scissoring rectangle.
+    ///
+    /// Elements are rendered and hit tested in the order of their z index
+    /// plus this offset. Giving one scissoring rectangle (i.e. a popup) a
+    /// base z index higher than the highest z index of the elements in
+    /// another one makes all of its elements appear above all of the
+    /// elements of the other one, regardless of their individual z indexes.
+    ///
+    /// By default this is set to `0`.
+    ///
+    /// If a scissoring rectangle with the given ID does not exist, then
+    /// one will be created.
+    pub fn set_scissor_rect_base_z_index(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        base_z_index: ZIndex,
+    ) {
+        self.element_system
+            .set_scissor_rect_base_z_index(scissor_rect_id, base_z_index)
+    }
+
+    pub fn scissor_rect_base_z_index(&mut self, scissor_rect_id: ScissorRectID) -> ZIndex {
+        self.element_system
+            .scissor_rect_base_z_index(scissor_rect_id)
+    }
+
+    /// Set the opacity and the transform that the given scissoring
+    /// rectangle and all of its elements are drawn with. This is useful for
+    /// animating transitions (i.e. a panel sliding, zooming, or fading in)
+    /// without touching the individual elements.
+    ///
+    /// If `opacity` or `transform` is `None`, then the current respecting
+    /// value will not be changed.
+    ///
+    /// Like with [`ElementHandle::set_opacity`], the opacity is applied by
+    /// the elements themselves, so custom elements which ignore
+    /// `RenderContext::opacity` are not faded (only hidden at `0.0`).
+    ///
+    /// The transform is relative to the origin of the scissoring rectangle,
+    /// and only its scale and its translation are used. It is applied to the
+    /// layout, so hit testing follows the transformed rectangles. Text and
+    /// icons are not scaled.
+    ///
+    /// If `scissor_rect_id == ScissorRectID::DEFAULT`, then this
+    /// will do nothing.
+    pub fn update_scissor_rect_transform(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        opacity: Option<f32>,
+        transform: Option<Transform>,
+    ) {
+        self.element_system
+            .update_scissor_rect_transform(scissor_rect_id, opacity, transform)
+    }
+
+    pub fn scissor_rect_transform(&mut self, scissor_rect_id: ScissorRectID) -> (f32, Transform) {
+        self.element_system.scissor_rect_transform(scissor_rect_id)
+    }
+
+    /// The size (in points) that the given element wants to be when it is
+    /// given the `available` space.
+    ///
+    /// See [`SizableElement::measure`].
+    pub fn measure<E: SizableElement>(&mut self, element: &E, available: Size) -> Size {
+        element.measure(available, self.res)
+    }
+
+    /// Inject a synthetic event into this window.
+    ///
+    /// The event takes the same path as events from the OS, so it is hit
+    /// tested, routed to the focused element, etc. Pending element updates are
+    /// processed first so that the event sees the current layout.
+    ///
+    /// This is mainly meant for automated UI tests, but it can also be used
+    /// to script the UI.
+    pub fn inject_event(&mut self, event: CanvasEvent) -> EventCaptureStatus {
+        self.element_system
+            .process_updates(self.res, self.clipboard);
+
+        self.element_system
+            .handle_event(&event, self.res, self.clipboard)
+    }
+
+    /// Inject a synthetic primary click at the given position in window
+    /// coordinates. This moves the pointer to the position, then presses and
+    /// releases the primary button.
+    ///
+    /// Returns `EventCaptureStatus::Captured` if the press was captured.
+    ///
+    /// See [`WindowContext::inject_event`].
+    pub fn click_at(&mut self, position: Point) -> EventCaptureStatus {
+        self.inject_event(CanvasEvent::Pointer(PointerEvent::Moved {
+            position,
+            delta: None,
+            is_locked: false,
+            pointer_type: PointerType::Mouse,
+            modifiers: Modifiers::empty(),
+            just_entered: false,
+        }));
+
+        let status = self.inject_event(CanvasEvent::Pointer(PointerEvent::ButtonJustPressed {
+            position,
+            button: PointerButton::Primary,
+            pointer_type: PointerType::Mouse,
+            click_count: 1,
+            modifiers: Modifiers::empty(),
+        }));
+
+        self.inject_event(CanvasEvent::Pointer(PointerEvent::ButtonJustReleased {
+            position,
+            button: PointerButton::Primary,
+            pointer_type: PointerType::Mouse,
+            click_count: 1,
+            modifiers: Modifiers::empty(),
+        }));
+
+        status
+    }
+
+    /// Inject a synthetic press and release of the given key.
+    ///
+    /// Like a real key press, the application's keyboard shortcuts (see
+    /// [`AppContext::accelerators`](crate::AppContext::accelerators)) are
+    /// matched before and after the elements handle it.
+    ///
+    /// Returns `EventCaptureStatus::Captured` if the press was captured by an
+    /// element or a keyboard shortcut.
+    ///
+    /// See [`WindowContext::inject_event`].
+    pub fn press_key(&mut self, code: Code, modifiers: Modifiers) -> EventCaptureStatus {
+        let status = self.inject_key_event(KeyboardEvent {
+            state: KeyState::Down,
+            code,
+            modifiers,
+            ..Default::default()
+        });
+
+        self.inject_key_event(KeyboardEvent {
+            state: KeyState::Up,
+            code,
+            modifiers,
+            ..Default::default()
+        });
+
+        status
+    }
+
+    fn inject_key_event(&mut self, event: KeyboardEvent) -> EventCaptureStatus {
+        if self.accelerators.dispatch(
+            &event,
+            AcceleratorPriority::BeforeElements,
+            self.action_sender,
+        ) {
+            return EventCaptureStatus::Captured;
+        }
+
+        if let EventCaptureStatus::Captured =
+            self.inject_event(CanvasEvent::Keyboard(event.clone()))
+        {
+            return EventCaptureStatus::Captured;
+        }
+
+        if self.accelerators.dispatch(
+            &event,
+            AcceleratorPriority::AfterElements,
+            self.action_sender,
+        ) {
+            EventCaptureStatus::Captured
+        } else {
+            EventCaptureStatus::NotCaptured
+        }
+    }
+
+    /// Inject synthetic text input, as if the user typed the given text with
+    /// an IME. The text is sent to the focused element.
+    ///
+    /// See [`WindowContext::inject_event`].
+    pub fn type_text(&mut self, text: &str) -> EventCaptureStatus {
+        self.inject_event(CanvasEvent::TextComposition(CompositionEvent {
+            state: CompositionState::Start,
+            data: String::new(),
+        }));
+
+        self.inject_event(CanvasEvent::TextComposition(CompositionEvent {
+            state: CompositionState::End,
+            data: text.to_string(),
+        }))
+    }
+
+    /// Render the contents of a scissoring rectangle into a secondary
+    /// target, i.e. a detachable preview pane.
+    ///
+    /// The primitives recorded for this window are reused, so this does not
+    /// render the elements again unless they have changed.
+    ///
+    /// If a scissoring rectangle with the given ID does not exist, then
+    /// one will be created.
+    pub fn render_viewport<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        viewport: &Viewport,
+    ) -> Result<(), B::Error> {
+        self.element_system
+            .render_viewport(backend, viewport, self.res)
+    }
+
+    /// Dim the given scissoring rectangle, show a spinner in its center, and
+    /// block all pointer input to the elements inside of it while an
+    /// operation is in progress. Set `loading` to `false` to restore
+    /// interaction.
+    ///
+    /// The overlay can be styled with [`LoadingOverlayStyle`] using the
+    /// default class.
+    ///
+    /// This will *NOT* trigger an update unless the value has changed,
+    /// so this method is relatively cheap to call frequently.
+    ///
+    /// If a scissoring rectangle with the given ID does not exist, then
+    /// one will be created.
+    ///
+    /// [`LoadingOverlayStyle`]: crate::elements::loading_overlay::LoadingOverlayStyle
+    pub fn set_region_loading(&mut self, scissor_rect_id: ScissorRectID, loading: bool) {
+        self.element_system
+            .set_region_loading(scissor_rect_id, loading, self.res, self.clipboard)
+    }
+
+    /// Returns `true` if the given scissoring rectangle is currently loading.
+    ///
+    /// See [`WindowContext::set_region_loading`].
+    pub fn region_is_loading(&self, scissor_rect_id: ScissorRectID) -> bool {
+        self.element_system.region_is_loading(scissor_rect_id)
+    }
+
     /// Returns the bounding rectangle of the given element, accounting for scroll offset.
     ///
     /// If the element has been dropped, then this will return `None`.
@@ -747,13 +1615,191 @@ impl<'a, A: Clone + 'static> WindowContext<'a, A> {
         self.element_system.element_rect(handle)
     }
 
+    /// Iterate over a read-only snapshot of every element instance in this
+    /// window in arbitrary order. This is useful for test harnesses and debug
+    /// overlays.
+    ///
+    /// Any pending changes to elements are applied first.
+    pub fn iter_elements(&mut self) -> impl Iterator<Item = ElementInfo> + '_ {
+        self.element_system
+            .process_updates(self.res, self.clipboard);
+
+        self.element_system.iter_elements()
+    }
+
+    /// The lowest and highest z index of the elements assigned to the given
+    /// scissoring rectangle, or `None` if no elements are assigned to it.
+    ///
+    /// Any pending changes to elements are applied first.
+    pub fn z_index_extents(&mut self, scissor_rect_id: ScissorRectID) -> Option<(ZIndex, ZIndex)> {
+        self.element_system
+            .process_updates(self.res, self.clipboard);
+
+        self.element_system.z_index_extents(scissor_rect_id)
+    }
+
+    /// The lowest and highest z index of the other elements assigned to the
+    /// same scissoring rectangle as the given element.
+    ///
+    /// Any pending changes to elements are applied first.
+    pub(crate) fn sibling_z_index_extents(
+        &mut self,
+        handle: &ElementHandle,
+    ) -> Option<(ZIndex, ZIndex)> {
+        self.element_system
+            .process_updates(self.res, self.clipboard);
+
+        self.element_system.sibling_z_index_extents(handle.id())
+    }
+
     pub fn element_is_hovered(&self, element: &ElementHandle) -> bool {
         self.element_system.element_is_hovered(element)
     }
 
+    /// The elements which can be given focus with the Tab key, in the order
+    /// they are focused.
+    ///
+    /// These are the visible elements with the `ElementFlags::FOCUSABLE` flag,
+    /// in the order in which they were created.
+    ///
+    /// The returned handles do not own their elements.
+    pub fn focusable_elements_in_tab_order(&self) -> Vec<ElementHandle> {
+        self.element_system
+            .focusable_elements_in_tab_order()
+            .into_iter()
+            .map(|element_id| self.element_system.non_owning_handle(element_id))
+            .collect()
+    }
+
+    /// The element which currently has exclusive focus, if any.
+    ///
+    /// The returned handle does not own the element.
+    pub fn focused_element(&self) -> Option<ElementHandle> {
+        self.element_system
+            .focused_element()
+            .map(|element_id| self.element_system.non_owning_handle(element_id))
+    }
+
+    /// Whether or not the given element currently has exclusive focus.
+    pub fn element_is_focused(&self, element: &ElementHandle) -> bool {
+        self.element_system.element_is_focused(element)
+    }
+
+    /// Give focus to the next focusable element in tab order, the same as
+    /// pressing the Tab key.
+    ///
+    /// Returns the newly focused element, or `None` if there are no focusable
+    /// elements. The returned handle does not own the element.
+    pub fn focus_next(&mut self) -> Option<ElementHandle> {
+        self.element_system
+            .focus_next(false, self.res, self.clipboard)
+            .map(|element_id| self.element_system.non_owning_handle(element_id))
+    }
+
+    /// Give focus to the previous focusable element in tab order, the same as
+    /// pressing Shift+Tab.
+    ///
+    /// Returns the newly focused element, or `None` if there are no focusable
+    /// elements. The returned handle does not own the element.
+    pub fn focus_prev(&mut self) -> Option<ElementHandle> {
+        self.element_system
+            .focus_next(true, self.res, self.clipboard)
+            .map(|element_id| self.element_system.non_owning_handle(element_id))
+    }
+
+    /// Set what happens when tab traversal reaches the end of the given
+    /// scissoring rectangle.
+    ///
+    /// Setting this to `Some` makes the scissoring rectangle a focus scope:
+    /// while one of its elements has focus, Tab and Shift+Tab move between
+    /// the elements of the scope before moving anywhere else. Setting it
+    /// to `None` removes the scope.
+    ///
+    /// The whole window (`ScissorRectID::DEFAULT`) is always a scope that
+    /// wraps by default. Setting it to `None` resets it to wrapping.
+    pub fn set_focus_wrap_mode(
+        &mut self,
+        scissor_rect_id: ScissorRectID,
+        mode: Option<FocusWrapMode>,
+    ) {
+        self.element_system
+            .set_focus_wrap_mode(scissor_rect_id, mode);
+    }
+
+    pub fn focus_wrap_mode(&mut self, scissor_rect_id: ScissorRectID) -> Option<FocusWrapMode> {
+        self.element_system.focus_wrap_mode(scissor_rect_id)
+    }
+
+    /// Assign a stable key to the given element, or `None` to remove its key.
+    ///
+    /// If another element with the same key already exists, then that element
+    /// will no longer be reachable by that key.
+    pub fn set_element_key(&mut self, element: &ElementHandle, key: Option<ElementKey>) {
+        self.element_system.set_element_key(element, key)
+    }
+
+    /// Returns the key assigned to the given element.
+    ///
+    /// If the element has been dropped or has no key, then this will return `None`.
+    pub fn element_key(&self, element: &ElementHandle) -> Option<ElementKey> {
+        self.element_system.element_key(element)
+    }
+
+    /// Returns `true` if the given element is the one currently assigned to
+    /// the given key.
+    pub fn element_has_key(&self, element: &ElementHandle, key: ElementKey) -> bool {
+        self.element_system.element_with_key(key) == Some(element.id())
+    }
+
+    /// Returns the bounding rectangle of the element with the given key, accounting
+    /// for scroll offset.
+    ///
+    /// If no element with that key exists, then this will return `None`.
+    pub fn element_rect_by_key(&self, key: ElementKey) -> Option<Rect> {
+        self.element_system
+            .element_with_key(key)
+            .and_then(|id| self.element_system.element_rect_by_id(id))
+    }
+
     pub fn auto_hide_tooltip(&mut self) {
         self.element_system.auto_hide_tooltip()
     }
+
+    /// Show the debug grid overlay with the given configuration, or `None`
+    /// to hide it.
+    ///
+    /// This is a developer tool for pixel-precise layout work.
+    pub fn set_debug_grid(&mut self, config: Option<DebugGridConfig>) {
+        self.element_system.set_debug_grid(config)
+    }
+
+    /// Show the z index heatmap overlay with the given configuration, or
+    /// `None` to hide it.
+    ///
+    /// This is a developer tool for verifying the stacking order of elements.
+    pub fn set_debug_z_index(&mut self, config: Option<DebugZIndexConfig>) {
+        self.element_system.set_debug_z_index(config)
+    }
+
+    /// Show the given layers of the debug overlay, or
+    /// `DebugOverlayFlags::empty()` to hide it.
+    ///
+    /// This is a developer tool for inspecting the bounds of elements and
+    /// scissoring rectangles. See also [`WindowContext::iter_elements`].
+    pub fn set_debug_overlay(&mut self, flags: DebugOverlayFlags) {
+        self.element_system.set_debug_overlay(flags)
+    }
+
+    pub fn debug_overlay(&self) -> DebugOverlayFlags {
+        self.element_system.debug_overlay()
+    }
+
+    /// Statistics about the last frame that was rendered to this window,
+    /// i.e. how many elements were painted and how long the frame took to
+    /// render.
+    pub fn last_render_stats(&self) -> RenderStats {
+        self.element_system.last_render_stats()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -761,3 +1807,753 @@ pub enum LinuxBackendType {
     Wayland,
     X11,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_clicks_are_grouped_by_time_and_distance() {
+        let interval = Duration::from_millis(300);
+        let max_distance = 4.0;
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let mut state = PointerBtnState::default();
+        let mut press =
+            |ms: u64, x: f32| state.on_pressed(at(ms), Point::new(x, 10.0), interval, max_distance);
+
+        assert_eq!(press(0, 10.0), 1);
+        assert_eq!(press(100, 12.0), 2);
+        assert_eq!(press(200, 13.0), 3);
+        // Too long after the previous press.
+        assert_eq!(press(600, 13.0), 1);
+        assert_eq!(press(700, 13.0), 2);
+        // Too far from the previous press.
+        assert_eq!(press(800, 30.0), 1);
+        assert_eq!(press(900, 34.5), 1);
+    }
+
+    #[test]
+    fn test_restored_windows_are_moved_onto_an_available_monitor() {
+        let rect = |x: f32, y: f32, w: f32, h: f32| {
+            PhysicalRect::new(
+                PhysicalPoint::new(x, y),
+                crate::math::PhysicalSize::new(w, h),
+            )
+        };
+        let monitors = [
+            rect(0.0, 0.0, 1920.0, 1080.0),
+            rect(1920.0, 0.0, 1280.0, 1024.0),
+        ];
+
+        // Visible on the second monitor, or partially visible on the first.
+        let on_second = rect(2000.0, 100.0, 800.0, 600.0);
+        assert_eq!(
+            clamp_window_to_monitors(on_second, &monitors),
+            on_second.origin
+        );
+        let partially_visible = rect(-700.0, 500.0, 800.0, 600.0);
+        assert_eq!(
+            clamp_window_to_monitors(partially_visible, &monitors),
+            partially_visible.origin
+        );
+
+        // Saved on a disconnected monitor to the right of the second one.
+        assert_eq!(
+            clamp_window_to_monitors(rect(3500.0, 200.0, 800.0, 600.0), &monitors),
+            PhysicalPoint::new(2400.0, 200.0)
+        );
+
+        // Saved on a disconnected monitor above the first one, and larger
+        // than the monitor it is moved to.
+        assert_eq!(
+            clamp_window_to_monitors(rect(100.0, -1500.0, 2500.0, 1200.0), &monitors),
+            PhysicalPoint::new(0.0, 0.0)
+        );
+
+        let nowhere = rect(5000.0, 5000.0, 100.0, 100.0);
+        assert_eq!(clamp_window_to_monitors(nowhere, &[]), nowhere.origin);
+    }
+
+    #[test]
+    fn test_redraws_are_held_back_until_the_frame_interval_elapses() {
+        let min_frame_interval = Duration::from_millis(10);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let mut limiter = FrameLimiter::default();
+        assert!(limiter.can_redraw(at(0), min_frame_interval));
+        limiter.on_frame(at(0));
+
+        assert!(!limiter.can_redraw(at(4), min_frame_interval));
+        assert_eq!(limiter.requested_resume(), Some(at(10)));
+
+        assert!(limiter.can_redraw(at(10), min_frame_interval));
+        assert_eq!(limiter.requested_resume(), None);
+        limiter.on_frame(at(12));
+        assert!(!limiter.can_redraw(at(20), min_frame_interval));
+        assert_eq!(limiter.requested_resume(), Some(at(22)));
+    }
+
+    #[test]
+    fn test_pointer_debounce_interval_is_per_window() {
+        let app_interval = TimerInterval::Fixed(Duration::from_millis(10));
+
+        let mut throttled = PointerDebounce::new(None);
+        let mut unthrottled = PointerDebounce::new(Some(TimerInterval::Fixed(Duration::ZERO)));
+
+        let throttled_interval = throttled.interval(app_interval, 60_000);
+        let unthrottled_interval = unthrottled.interval(app_interval, 60_000);
+        assert_eq!(throttled_interval, Duration::from_millis(10));
+        assert_eq!(unthrottled_interval, Duration::ZERO);
+
+        let start = Instant::now();
+        assert!(throttled.on_pointer_moved(start, throttled_interval));
+        assert!(unthrottled.on_pointer_moved(start, unthrottled_interval));
+
+        // A second event soon after is held back only in the throttled window.
+        let t = start + Duration::from_millis(2);
+        assert!(!throttled.on_pointer_moved(t, throttled_interval));
+        assert!(unthrottled.on_pointer_moved(t, unthrottled_interval));
+        assert_eq!(unthrottled.requested_resume(), None);
+
+        // The held back event is scheduled for when the interval has elapsed
+        // since the previously processed event, even if more events arrive.
+        let t = start + Duration::from_millis(6);
+        assert!(!throttled.on_pointer_moved(t, throttled_interval));
+        let resume_instant = start + Duration::from_millis(10);
+        assert_eq!(throttled.requested_resume(), Some(resume_instant));
+
+        assert!(!throttled.resume(t));
+        assert!(throttled.resume(resume_instant));
+        assert_eq!(throttled.requested_resume(), None);
+
+        // The interval can be changed at runtime.
+        throttled.interval = Some(TimerInterval::PercentageOfFrameRate(1.0));
+        assert_eq!(
+            throttled.interval(app_interval, 50_000),
+            Duration::from_millis(20)
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum TestAction {
+        Clicked,
+        RowClicked(usize),
+        Dropped,
+        Saved,
+        Changed(f32),
+    }
+
+    /// An element which does nothing.
+    struct TestElement;
+
+    impl crate::element_system::element::Element<TestAction> for TestElement {}
+
+    /// Run `f` with the context of a 200x100 window.
+    fn with_window_cx<R>(f: impl FnOnce(&mut WindowContext<'_, TestAction>) -> R) -> R {
+        with_window_cx_and_accelerators(&Accelerators::new(), f)
+    }
+
+    /// Run `f` with the context of a 200x100 window in an application with
+    /// the given keyboard shortcuts.
+    fn with_window_cx_and_accelerators<R>(
+        accelerators: &Accelerators<TestAction>,
+        f: impl FnOnce(&mut WindowContext<'_, TestAction>) -> R,
+    ) -> R {
+        use crate::action_queue::action_channel;
+        use crate::clipboard::State as ClipboardState;
+        use crate::style::StyleSystem;
+        use rootvg::text::FontSystem;
+
+        let (mut action_sender, mut action_receiver) = action_channel::<TestAction>();
+        let physical_size = PhysicalSizeI32::new(200, 100);
+        let mut element_system = ElementSystem::new(
+            physical_size,
+            1.0f64.into(),
+            ElementSystemConfig {
+                clear_color: PackedSrgb::BLACK,
+                skip_clear: false,
+                preallocate_for_this_many_elements: 0,
+                hover_timeout_duration: Duration::from_millis(500),
+                scroll_wheel_timeout_duration: Duration::from_millis(250),
+                long_press_duration: Duration::from_millis(500),
+            },
+            action_sender.clone(),
+            MAIN_WINDOW,
+        );
+        let mut res = ResourceCtx {
+            style_system: StyleSystem::new(true),
+            font_system: FontSystem::new(),
+            #[cfg(feature = "svg-icons")]
+            svg_icon_system: Default::default(),
+        };
+        let mut clipboard = Clipboard {
+            state: ClipboardState::Unavailable,
+        };
+
+        let mut window_cx = WindowContext {
+            res: &mut res,
+            clipboard: &mut clipboard,
+            action_sender: &mut action_sender,
+            action_receiver: &mut action_receiver,
+            accelerators,
+            element_system: &mut element_system,
+            z_index_stack: Vec::new(),
+            scissor_rect_stack: Vec::new(),
+            class_stack: Vec::new(),
+            logical_size: Size::new(200.0, 100.0),
+            physical_size,
+            scale_factor: 1.0f64.into(),
+            scale_factor_config: ScaleFactorConfig::System,
+            system_scale_factor: 1.0f64.into(),
+        };
+
+        f(&mut window_cx)
+    }
+
+    #[test]
+    fn test_injected_click_selects_a_button() {
+        use crate::elements::button::Button;
+
+        with_window_cx(|window_cx| {
+            let _button = Button::builder()
+                .text("OK")
+                .on_select(TestAction::Clicked)
+                .rect(Rect::new(Point::new(10.0, 10.0), Size::new(80.0, 30.0)))
+                .build(window_cx);
+
+            // A click that misses the button does nothing.
+            assert_eq!(
+                window_cx.click_at(Point::new(150.0, 80.0)),
+                EventCaptureStatus::NotCaptured
+            );
+            assert!(window_cx.action_receiver.try_recv().is_err());
+
+            assert_eq!(
+                window_cx.click_at(Point::new(50.0, 25.0)),
+                EventCaptureStatus::Captured
+            );
+            assert_eq!(
+                window_cx.action_receiver.try_recv(),
+                Ok(TestAction::Clicked)
+            );
+        });
+    }
+
+    #[test]
+    fn test_elements_built_inside_with_class_inherit_the_class() {
+        use crate::elements::button::Button;
+
+        with_window_cx(|window_cx| {
+            let (inherited, overridden) = window_cx.with_class(2, |window_cx| {
+                let inherited = Button::builder().build(window_cx);
+                let overridden = window_cx
+                    .with_z_index(3, |window_cx| Button::builder().class(1).build(window_cx));
+                (inherited, overridden)
+            });
+            let outside = Button::builder().build(window_cx);
+
+            assert_eq!(inherited.class(), 2);
+            assert_eq!(overridden.class(), 1);
+            assert_eq!(overridden.z_index(), 3);
+            assert_eq!(outside.class(), 0);
+            assert_eq!(window_cx.class(), 0);
+        });
+    }
+
+    #[test]
+    fn test_elements_built_inside_with_scissor_rect_are_assigned_to_it() {
+        use crate::elements::button::Button;
+
+        with_window_cx(|window_cx| {
+            let scroll_area = ScissorRectID(1);
+            window_cx.update_scissor_rect(
+                scroll_area,
+                Some(Rect::new(Point::new(50.0, 20.0), Size::new(100.0, 50.0))),
+                None,
+            );
+
+            let rect = Rect::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0));
+            let (inside, overridden) = window_cx.with_scissor_rect(scroll_area, |window_cx| {
+                (
+                    Button::builder().rect(rect).build(window_cx),
+                    Button::builder()
+                        .rect(rect)
+                        .scissor_rect(ScissorRectID::DEFAULT)
+                        .build(window_cx),
+                )
+            });
+            let outside = Button::builder().rect(rect).build(window_cx);
+            assert_eq!(window_cx.scissor_rect(), ScissorRectID::DEFAULT);
+
+            let origins: Vec<Point> = [&inside, &overridden, &outside]
+                .iter()
+                .map(|button| window_cx.element_rect(&button.el).unwrap().origin)
+                .collect();
+            assert_eq!(
+                origins,
+                vec![
+                    Point::new(60.0, 30.0),
+                    Point::new(10.0, 10.0),
+                    Point::new(10.0, 10.0)
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_horizontal_scroll_wheel_deltas_scroll_a_scroll_area() {
+        use crate::elements::scroll_area::ScrollArea;
+
+        with_window_cx(|window_cx| {
+            let content = ScissorRectID(1);
+            let _area = ScrollArea::builder()
+                .rect(Rect::new(Point::zero(), Size::new(100.0, 50.0)))
+                .content_size(Size::new(400.0, 200.0))
+                .control_scissor_rect(content)
+                .points_per_line(10.0)
+                .build(window_cx);
+
+            let mut scroll = |delta_type: WheelDeltaType, modifiers: Modifiers| {
+                window_cx.inject_event(CanvasEvent::Pointer(PointerEvent::ScrollWheel {
+                    position: Point::new(50.0, 25.0),
+                    delta_type,
+                    phase: TouchPhase::Moved,
+                    pointer_type: PointerType::Mouse,
+                    modifiers,
+                }));
+                window_cx
+                    .element_system
+                    .process_updates(window_cx.res, window_cx.clipboard);
+                window_cx.element_system.scissor_rect_scroll_offset(content)
+            };
+
+            assert_eq!(
+                scroll(
+                    WheelDeltaType::Lines(Vector::new(2.0, 0.0)),
+                    Modifiers::empty()
+                ),
+                Vector::new(20.0, 0.0)
+            );
+
+            // Shift turns a vertical scroll wheel into a horizontal one, except
+            // on macOS where the system already does this.
+            let offset = scroll(
+                WheelDeltaType::Lines(Vector::new(0.0, 3.0)),
+                Modifiers::SHIFT,
+            );
+            if cfg!(target_os = "macos") {
+                assert_eq!(offset, Vector::new(20.0, 30.0));
+            } else {
+                assert_eq!(offset, Vector::new(50.0, 0.0));
+            }
+        });
+    }
+
+    #[test]
+    fn test_set_toggled_on_a_radio_button_updates_its_group() {
+        use crate::elements::radio_button::{RadioButton, RadioGroupID};
+
+        with_window_cx(|window_cx| {
+            let group = RadioGroupID::new(Some(0));
+            let mut buttons: Vec<RadioButton> = (0..3)
+                .map(|i| {
+                    RadioButton::builder::<TestAction>()
+                        .group(&group, i)
+                        .build(window_cx)
+                })
+                .collect();
+            let toggled =
+                |buttons: &[RadioButton]| buttons.iter().map(|b| b.toggled()).collect::<Vec<_>>();
+
+            assert!(buttons[2].set_toggled(true));
+            assert_eq!(group.selected_index(), Some(2));
+            assert_eq!(toggled(&buttons), vec![false, false, true]);
+
+            // Toggling on the selected radio button again does nothing.
+            assert!(!buttons[2].set_toggled(true));
+            // Neither does toggling off one which isn't selected.
+            assert!(!buttons[0].set_toggled(false));
+            assert_eq!(group.selected_index(), Some(2));
+
+            assert!(buttons[2].set_toggled(false));
+            assert_eq!(group.selected_index(), None);
+            assert_eq!(toggled(&buttons), vec![false, false, false]);
+        });
+    }
+
+    #[test]
+    fn test_knob_only_reports_changes_made_by_the_user() {
+        use crate::elements::virtual_slider::knob::Knob;
+
+        with_window_cx(|window_cx| {
+            let mut knob: Knob = Knob::builder("gain")
+                .on_changed(TestAction::Changed)
+                .rect(Rect::new(Point::zero(), Size::new(40.0, 40.0)))
+                .build(window_cx);
+
+            let scroll = |window_cx: &mut WindowContext<'_, TestAction>| {
+                window_cx.inject_event(CanvasEvent::Pointer(PointerEvent::ScrollWheel {
+                    position: Point::new(20.0, 20.0),
+                    delta_type: WheelDeltaType::Lines(Vector::new(0.0, 1.0)),
+                    phase: TouchPhase::Moved,
+                    pointer_type: PointerType::Mouse,
+                    modifiers: Modifiers::empty(),
+                }));
+                window_cx
+                    .element_system
+                    .process_updates(window_cx.res, window_cx.clipboard);
+            };
+
+            scroll(window_cx);
+            let Ok(TestAction::Changed(scrolled)) = window_cx.action_receiver.try_recv() else {
+                panic!("expected a change");
+            };
+            assert!((f64::from(scrolled) - knob.normal_value()).abs() < 1e-6);
+
+            // Setting the value from the application is not reported back.
+            assert!(knob.set_normal_value(0.25));
+            window_cx
+                .element_system
+                .process_updates(window_cx.res, window_cx.clipboard);
+            assert!(window_cx.action_receiver.try_recv().is_err());
+
+            // The next change made by the user starts from that value.
+            scroll(window_cx);
+            let Ok(TestAction::Changed(scrolled_again)) = window_cx.action_receiver.try_recv()
+            else {
+                panic!("expected a change");
+            };
+            assert!((scrolled_again - scrolled).abs() > 0.1);
+        });
+    }
+
+    #[test]
+    fn test_scroll_wheel_phase_reaches_the_element() {
+        use crate::element_system::element::{
+            Element, ElementBuilder, ElementContext, ElementFlags,
+        };
+        use crate::event::ElementEvent;
+        use std::{cell::RefCell, rc::Rc};
+
+        struct PhaseRecorder(Rc<RefCell<Vec<TouchPhase>>>);
+
+        impl Element<TestAction> for PhaseRecorder {
+            fn on_event(
+                &mut self,
+                event: ElementEvent,
+                _cx: &mut ElementContext<'_, TestAction>,
+            ) -> EventCaptureStatus {
+                if let ElementEvent::Pointer(PointerEvent::ScrollWheel { phase, .. }) = event {
+                    self.0.borrow_mut().push(phase);
+                    return EventCaptureStatus::Captured;
+                }
+                EventCaptureStatus::NotCaptured
+            }
+        }
+
+        with_window_cx(|window_cx| {
+            let phases = Rc::new(RefCell::new(Vec::new()));
+            let _recorder = ElementBuilder::new(PhaseRecorder(Rc::clone(&phases)))
+                .rect(Rect::new(Point::zero(), Size::new(100.0, 50.0)))
+                .flags(ElementFlags::LISTENS_TO_POINTER_INSIDE_BOUNDS)
+                .build(window_cx);
+
+            for phase in [TouchPhase::Started, TouchPhase::Moved, TouchPhase::Ended] {
+                let status =
+                    window_cx.inject_event(CanvasEvent::Pointer(PointerEvent::ScrollWheel {
+                        position: Point::new(50.0, 25.0),
+                        delta_type: WheelDeltaType::Points(Vector::new(0.0, 4.0)),
+                        phase,
+                        pointer_type: PointerType::Mouse,
+                        modifiers: Modifiers::empty(),
+                    }));
+                assert_eq!(status, EventCaptureStatus::Captured);
+            }
+
+            assert_eq!(
+                *phases.borrow(),
+                vec![TouchPhase::Started, TouchPhase::Moved, TouchPhase::Ended]
+            );
+        });
+    }
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_every_text_input_exposes_its_scroll_offset() {
+        use crate::elements::text_input::FloatingTextInput;
+
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(4);
+        let rect = Rect::new(Point::new(10.0, 10.0), Size::new(100.0, 30.0));
+
+        with_window_cx(|window_cx| {
+            let mut floating = FloatingTextInput::builder::<TestAction>()
+                .text(text.clone())
+                .rect(rect)
+                .build(window_cx);
+            assert_eq!(floating.scroll_offset_x(), 0.0);
+            assert!(floating.set_scroll_offset_x(30.0));
+            assert!(!floating.set_scroll_offset_x(30.0));
+            assert_eq!(floating.scroll_offset_x(), 30.0);
+
+            #[cfg(feature = "svg-icons")]
+            {
+                use crate::elements::text_input::IconTextInput;
+
+                let mut icon = IconTextInput::builder::<TestAction>()
+                    .text(text.clone())
+                    .rect(rect)
+                    .build(window_cx);
+                assert_eq!(icon.scroll_offset_x(), 0.0);
+                assert!(icon.set_scroll_offset_x(30.0));
+                assert!(!icon.set_scroll_offset_x(30.0));
+                assert_eq!(icon.scroll_offset_x(), 30.0);
+            }
+        });
+    }
+
+    #[test]
+    #[ignore = "requires system fonts"]
+    fn test_measure_wraps_paragraphs_to_the_available_width() {
+        use crate::elements::paragraph::Paragraph;
+
+        with_window_cx(|window_cx| {
+            let paragraph = Paragraph::builder()
+                .text("The quick brown fox jumps over the lazy dog")
+                .rect(Rect::new(Point::zero(), Size::new(1000.0, 100.0)))
+                .build(window_cx);
+
+            let wide = window_cx.measure(&paragraph, Size::new(1000.0, 100.0));
+            assert!(!wide.is_empty());
+
+            let narrow = window_cx.measure(&paragraph, Size::new(60.0, 100.0));
+            assert!(narrow.height > wide.height);
+            assert!(narrow.width < wide.width);
+
+            // Measuring doesn't change the wrapping of the paragraph itself.
+            assert_eq!(paragraph.bounds_width(), 1000.0);
+            assert_eq!(paragraph.desired_size(window_cx.res), wide);
+        });
+    }
+
+    #[test]
+    fn test_iter_elements() {
+        use crate::element_system::element::{ElementBuilder, ElementFlags};
+
+        with_window_cx(|window_cx| {
+            let rect = Rect::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0));
+
+            let shown = ElementBuilder::new(TestElement)
+                .rect(rect)
+                .z_index(4)
+                .class(2)
+                .flags(ElementFlags::PAINTS)
+                .build(window_cx);
+            let mut hidden = ElementBuilder::new(TestElement)
+                .rect(rect)
+                .flags(ElementFlags::PAINTS)
+                .build(window_cx);
+            hidden.set_hidden(true);
+
+            let mut infos: Vec<ElementInfo> = window_cx.iter_elements().collect();
+            assert_eq!(infos.len(), 2);
+            infos.sort_by_key(|info| info.id != shown.id());
+
+            assert_eq!(
+                infos[0],
+                ElementInfo {
+                    id: shown.id(),
+                    rect,
+                    z_index: 4,
+                    visible: true,
+                    class: 2,
+                    manually_hidden: false,
+                }
+            );
+            assert_eq!(infos[1].id, hidden.id());
+            assert!(!infos[1].visible);
+            assert!(infos[1].manually_hidden);
+        });
+    }
+
+    #[test]
+    fn test_focus_api_returns_non_owning_handles() {
+        use crate::element_system::element::{ElementBuilder, ElementFlags};
+
+        with_window_cx(|window_cx| {
+            // The Tab key is left to the application while nothing can be
+            // focused.
+            assert_eq!(
+                window_cx.press_key(Code::Tab, Modifiers::empty()),
+                EventCaptureStatus::NotCaptured
+            );
+
+            let rect = Rect::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0));
+            let first = ElementBuilder::new(TestElement)
+                .rect(rect)
+                .flags(ElementFlags::FOCUSABLE)
+                .build(window_cx);
+            let second = ElementBuilder::new(TestElement)
+                .rect(rect.translate(Vector::new(30.0, 0.0)))
+                .flags(ElementFlags::FOCUSABLE)
+                .build(window_cx);
+
+            let ids: Vec<_> = window_cx
+                .focusable_elements_in_tab_order()
+                .iter()
+                .map(|handle| handle.id())
+                .collect();
+            assert_eq!(ids, vec![first.id(), second.id()]);
+
+            let focused = window_cx.focus_next().unwrap();
+            assert_eq!(focused.id(), first.id());
+            assert_eq!(focused.rect(), rect);
+            // Dropping a returned handle does not drop the element.
+            drop(focused);
+            window_cx
+                .element_system
+                .process_updates(window_cx.res, window_cx.clipboard);
+            assert_eq!(window_cx.iter_elements().count(), 2);
+            assert!(window_cx.element_is_focused(&first));
+
+            assert_eq!(
+                window_cx.press_key(Code::Tab, Modifiers::empty()),
+                EventCaptureStatus::Captured
+            );
+            assert_eq!(window_cx.focused_element().unwrap().id(), second.id());
+            assert_eq!(window_cx.focus_prev().unwrap().id(), first.id());
+        });
+    }
+
+    #[test]
+    fn test_bring_to_front_and_send_to_back() {
+        use crate::element_system::element::ElementBuilder;
+
+        with_window_cx(|window_cx| {
+            let mut handles: Vec<ElementHandle> = [2, 5, 3]
+                .into_iter()
+                .map(|z_index| {
+                    ElementBuilder::new(TestElement)
+                        .z_index(z_index)
+                        .build(window_cx)
+                })
+                .collect();
+
+            assert_eq!(
+                window_cx.z_index_extents(ScissorRectID::DEFAULT),
+                Some((2, 5))
+            );
+
+            assert_eq!(handles[0].bring_to_front(window_cx), 6);
+            // Already in front, so nothing changes.
+            assert_eq!(handles[0].bring_to_front(window_cx), 6);
+
+            assert_eq!(handles[0].send_to_back(window_cx), 2);
+            assert_eq!(handles[2].send_to_back(window_cx), 1);
+            assert_eq!(
+                window_cx.z_index_extents(ScissorRectID::DEFAULT),
+                Some((1, 5))
+            );
+
+            // Elements in other scissoring rectangles are not siblings.
+            let mut other = ElementBuilder::new(TestElement)
+                .z_index(9)
+                .scissor_rect(ScissorRectID(3))
+                .build(window_cx);
+            assert_eq!(other.bring_to_front(window_cx), 9);
+            assert_eq!(handles[1].bring_to_front(window_cx), 5);
+        });
+    }
+
+    #[test]
+    fn test_list_view_sees_row_clicks_before_the_row_contents() {
+        use crate::elements::button::Button;
+        use crate::elements::list_view::ListView;
+
+        with_window_cx(|window_cx| {
+            let mut list = ListView::builder()
+                .on_row_clicked(TestAction::RowClicked)
+                .row_height(20.0)
+                .item_count(10)
+                .scissor_rect(ScissorRectID(1))
+                .build(
+                    |cx| Button::builder().on_select(TestAction::Clicked).build(cx),
+                    |_, _, _| {},
+                    window_cx,
+                );
+            list.layout(Rect::new(Point::zero(), Size::new(100.0, 100.0)), window_cx);
+
+            assert_eq!(
+                window_cx.click_at(Point::new(50.0, 30.0)),
+                EventCaptureStatus::Captured
+            );
+            assert_eq!(
+                window_cx.action_receiver.try_recv(),
+                Ok(TestAction::RowClicked(1))
+            );
+            assert_eq!(
+                window_cx.action_receiver.try_recv(),
+                Ok(TestAction::Clicked)
+            );
+        });
+    }
+
+    #[test]
+    fn test_button_sends_its_on_dropped_action() {
+        use crate::elements::button::Button;
+
+        with_window_cx(|window_cx| {
+            let button = Button::builder()
+                .on_dropped(|| TestAction::Dropped)
+                .build(window_cx);
+            let silent_button = Button::builder::<TestAction>().build(window_cx);
+            window_cx
+                .element_system
+                .process_updates(window_cx.res, window_cx.clipboard);
+
+            drop(silent_button);
+            window_cx
+                .element_system
+                .process_updates(window_cx.res, window_cx.clipboard);
+            assert!(window_cx.action_receiver.try_recv().is_err());
+
+            drop(button);
+            window_cx
+                .element_system
+                .process_updates(window_cx.res, window_cx.clipboard);
+            assert_eq!(
+                window_cx.action_receiver.try_recv(),
+                Ok(TestAction::Dropped)
+            );
+        });
+    }
+
+    #[test]
+    fn test_injected_key_presses_match_keyboard_shortcuts() {
+        use crate::application::CMD_OR_CTRL;
+
+        let mut accelerators = Accelerators::new();
+        accelerators.register(
+            CMD_OR_CTRL,
+            Code::KeyS,
+            TestAction::Saved,
+            AcceleratorPriority::AfterElements,
+        );
+
+        with_window_cx_and_accelerators(&accelerators, |window_cx| {
+            assert_eq!(
+                window_cx.press_key(Code::KeyS, CMD_OR_CTRL),
+                EventCaptureStatus::Captured
+            );
+            assert_eq!(window_cx.action_receiver.try_recv(), Ok(TestAction::Saved));
+
+            assert_eq!(
+                window_cx.press_key(Code::KeyS, Modifiers::empty()),
+                EventCaptureStatus::NotCaptured
+            );
+            assert!(window_cx.action_receiver.try_recv().is_err());
+        });
+    }
+}
@@ -18,15 +18,16 @@ use std::ptr::NonNull;
 mod convert;
 
 use super::{
-    LinuxBackendType, ScaleFactorConfig, WindowBackend, WindowConfig, WindowID, WindowState,
-    MAIN_WINDOW,
+    FrameLimiter, FullscreenMode, LinuxBackendType, MonitorInfo, PointerDebounce, ResizeDirection,
+    ScaleFactorConfig, WindowBackend, WindowConfig, WindowGeometry, WindowID, WindowIcon,
+    WindowRenderBackend, WindowState, MAIN_WINDOW,
 };
 use crate::action_queue::ActionSender;
-use crate::application::Application;
+use crate::application::{AcceleratorPriority, Application};
 use crate::clipboard::Clipboard;
-use crate::element_system::{ElementSystem, ElementSystemConfig};
-use crate::event::{EventCaptureStatus, PointerButton, WheelDeltaType};
-use crate::math::{PhysicalPoint, PhysicalSizeI32, ScaleFactor, Size};
+use crate::element_system::{ElementSystem, WgpuRenderBackend};
+use crate::event::{EventCaptureStatus, PointerButton, TouchPhase, WheelDeltaType};
+use crate::math::{PhysicalPoint, PhysicalSizeI32, Rect, ScaleFactor, Size};
 use crate::prelude::{ActionReceiver, AppHandler, ResourceCtx};
 use crate::style::StyleSystem;
 use crate::window::{PointerBtnState, PointerLockState};
@@ -145,6 +146,73 @@ impl<'a, 'b> WindowBackend for BaseviewWindowBackend<'a, 'b> {
         // Baseview does not support setting the window title yet.
     }
 
+    fn set_window_icon(&mut self, _window_id: WindowID, _icon: Option<WindowIcon>) {
+        // Baseview does not support setting the window icon yet.
+    }
+
+    fn set_fullscreen(&mut self, _window_id: WindowID, _mode: Option<FullscreenMode>) {
+        // Baseview does not support fullscreen windows yet.
+    }
+
+    fn is_fullscreen(&mut self, _window_id: WindowID) -> bool {
+        false
+    }
+
+    fn outer_position(&mut self, _window_id: WindowID) -> Option<PhysicalPoint> {
+        // Baseview does not support querying the window position yet.
+        None
+    }
+
+    fn set_outer_position(&mut self, _window_id: WindowID, _position: PhysicalPoint) {
+        // Baseview does not support moving the window yet.
+    }
+
+    fn current_geometry(
+        &mut self,
+        _window_id: WindowID,
+        _scale_factor: ScaleFactor,
+    ) -> Option<WindowGeometry> {
+        // Baseview does not support querying the window geometry yet.
+        None
+    }
+
+    fn available_monitors(&mut self) -> Vec<MonitorInfo> {
+        // Baseview does not support enumerating monitors yet.
+        Vec::new()
+    }
+
+    fn current_monitor(&mut self, _window_id: WindowID) -> Option<MonitorInfo> {
+        // Baseview does not support enumerating monitors yet.
+        None
+    }
+
+    fn set_ime_allowed(&mut self, _window_id: WindowID, _allowed: bool) {
+        // Baseview does not support IME yet.
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        _window_id: WindowID,
+        _area: Rect,
+        _scale_factor: ScaleFactor,
+    ) {
+        // Baseview does not support IME yet.
+    }
+
+    fn drag_window(&mut self, _window_id: WindowID) -> Result<(), ()> {
+        // Baseview does not support dragging windows yet.
+        Err(())
+    }
+
+    fn drag_resize_window(
+        &mut self,
+        _window_id: WindowID,
+        _direction: ResizeDirection,
+    ) -> Result<(), ()> {
+        // Baseview does not support drag-resizing windows yet.
+        Err(())
+    }
+
     fn create_window<A: Clone + 'static>(
         &mut self,
         _window_id: WindowID,
@@ -210,6 +278,7 @@ impl<A: Application> BaseviewAppHandler<A> {
             config,
             res,
             linux_backend_type,
+            Vec::new(),
         )?;
 
         Ok(Self {
@@ -315,10 +384,12 @@ impl<A: Application> BaseviewWindowHandler for BaseviewAppHandler<A> {
                         }
                     };
 
-                    self.app_handler
-                        .cx
-                        .main_window
-                        .handle_mouse_wheel(delta_type, &mut self.app_handler.cx.res)
+                    self.app_handler.cx.main_window.handle_mouse_wheel(
+                        delta_type,
+                        // Baseview doesn't report the phase of scroll gestures.
+                        TouchPhase::Moved,
+                        &mut self.app_handler.cx.res,
+                    )
                 }
                 baseview::MouseEvent::CursorEntered => (),
                 baseview::MouseEvent::CursorLeft => self
@@ -346,12 +417,19 @@ impl<A: Application> BaseviewWindowHandler for BaseviewAppHandler<A> {
             baseview::Event::Keyboard(keyboard_event) => {
                 let key_event = self::convert::convert_keyboard_event(&keyboard_event);
 
-                let mut captured = self
-                    .app_handler
-                    .cx
-                    .main_window
-                    .handle_keyboard_event(key_event.clone(), &mut self.app_handler.cx.res)
-                    == EventCaptureStatus::Captured;
+                let cx = &mut self.app_handler.cx;
+                let mut captured = cx.accelerators.dispatch(
+                    &key_event,
+                    AcceleratorPriority::BeforeElements,
+                    &mut cx.action_sender,
+                );
+
+                if !captured {
+                    captured = cx
+                        .main_window
+                        .handle_keyboard_event(key_event.clone(), &mut cx.res)
+                        == EventCaptureStatus::Captured;
+                }
 
                 if !captured && keyboard_event.state == KeyState::Down {
                     if let Some(text) =
@@ -384,6 +462,15 @@ impl<A: Application> BaseviewWindowHandler for BaseviewAppHandler<A> {
                     }
                 }
 
+                if !captured {
+                    let cx = &mut self.app_handler.cx;
+                    captured = cx.accelerators.dispatch(
+                        &key_event,
+                        AcceleratorPriority::AfterElements,
+                        &mut cx.action_sender,
+                    );
+                }
+
                 if !captured {
                     self.app_handler.user_app.on_keyboard_event(
                         key_event,
@@ -467,6 +554,9 @@ impl<A: Application> BaseviewWindowHandler for BaseviewAppHandler<A> {
 pub enum OpenWindowError {
     #[error("Baseview does not yet support multiple windows")]
     MultiWindowNotSupported,
+    #[cfg(feature = "headless")]
+    #[error("{0}")]
+    HeadlessRenderBackendError(#[from] crate::element_system::HeadlessRenderBackendError),
 }
 
 pub fn run_blocking<A: Application + 'static>(app_config: AppConfig) -> Result<(), Box<dyn Error>> {
@@ -477,8 +567,8 @@ pub fn run_blocking<A: Application + 'static>(app_config: AppConfig) -> Result<(
             ScaleFactorConfig::Custom(c) => WindowScalePolicy::ScaleFactor(c.into()),
         },
         size: baseview::Size::new(
-            app_config.main_window_config.size.width as f64,
-            app_config.main_window_config.size.height as f64,
+            app_config.main_window_config.initial_size().width as f64,
+            app_config.main_window_config.initial_size().height as f64,
         ),
     };
 
@@ -501,8 +591,8 @@ pub fn run_parented<P: HasRawWindowHandle, A: Application + 'static, B>(
             ScaleFactorConfig::Custom(c) => WindowScalePolicy::ScaleFactor(c.into()),
         },
         size: baseview::Size::new(
-            app_config.main_window_config.size.width as f64,
-            app_config.main_window_config.size.height as f64,
+            app_config.main_window_config.initial_size().width as f64,
+            app_config.main_window_config.initial_size().height as f64,
         ),
     };
 
@@ -579,31 +669,19 @@ fn new_window<A: Application>(
         },
     };
 
-    let physical_size = PhysicalSizeI32::new(config.size.width as i32, config.size.height as i32);
+    let logical_size = config.initial_size();
+    let physical_size = PhysicalSizeI32::new(logical_size.width as i32, logical_size.height as i32);
 
     let surface = unsafe {
         DefaultSurface::new_unsafe(physical_size, scale_factor, target, config.surface_config)?
     };
 
-    let canvas_config = surface.canvas_config();
-
-    let renderer = rootvg::Canvas::new(
-        &surface.device,
-        &surface.queue,
-        surface.format(),
-        canvas_config,
-        &mut res.font_system,
-    );
+    let render_backend = WgpuRenderBackend::new(surface, &mut res.font_system);
 
     let element_system = ElementSystem::new(
         physical_size,
         scale_factor,
-        ElementSystemConfig {
-            clear_color: config.clear_color,
-            preallocate_for_this_many_elements: config.preallocate_for_this_many_elements,
-            hover_timeout_duration: config.hover_timeout_duration,
-            scroll_wheel_timeout_duration: config.scroll_wheel_timeout_duration,
-        },
+        config.element_system_config(),
         action_sender,
         MAIN_WINDOW,
     );
@@ -612,10 +690,8 @@ fn new_window<A: Application>(
 
     Ok(WindowState {
         element_system,
-        renderer,
-        surface: Some(surface),
-        multisample: canvas_config.multisample,
-        logical_size: config.size,
+        render_backend: Some(WindowRenderBackend::Surface(render_backend)),
+        logical_size,
         physical_size,
         scale_factor,
         scale_factor_recip: scale_factor.recip(),
@@ -625,9 +701,17 @@ fn new_window<A: Application>(
         queued_pointer_delta: None,
         prev_pointer_pos: None,
         pointer_btn_states: [PointerBtnState::default(); 5],
+        multi_click_interval: config.multi_click_interval,
+        multi_click_max_distance: config.multi_click_max_distance,
         modifiers: Modifiers::empty(),
         current_cursor_icon: CursorIcon::Default,
         pointer_lock_state: PointerLockState::NotLocked,
+        is_fullscreen: false,
+        outer_position: None,
+        is_maximized: false,
+        current_monitor: None,
+        pointer_debounce: PointerDebounce::new(config.pointer_debounce_interval),
+        frame_limiter: FrameLimiter::default(),
         clipboard,
     })
 }
@@ -0,0 +1,427 @@
+use keyboard_types::Modifiers;
+use rootvg::math::{PhysicalPoint, PhysicalSizeI32, Rect, ScaleFactor, Size};
+use rootvg::text::FontSystem;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::error::Error;
+
+use super::{
+    FrameLimiter, FullscreenMode, MonitorInfo, OpenWindowError, PointerBtnState, PointerDebounce,
+    PointerLockState, ResizeDirection, WindowBackend, WindowConfig, WindowGeometry, WindowID,
+    WindowIcon, WindowRenderBackend, WindowState, MAIN_WINDOW,
+};
+use crate::action_queue::ActionSender;
+use crate::application::{AppHandler, Application, TimerInterval};
+use crate::clipboard::{Clipboard, State as ClipboardState};
+use crate::element_system::{ElementSystem, HeadlessRenderBackend, ImageBuffer};
+use crate::prelude::ResourceCtx;
+use crate::style::StyleSystem;
+use crate::{AppConfig, CursorIcon};
+
+/// Headless windows have no monitor to query, so assume the common 60Hz.
+const ASSUMED_MILLIHERTZ: f64 = 60_000.0;
+
+/// The system scale factor of every headless window.
+const SYSTEM_SCALE_FACTOR: f64 = 1.0;
+
+#[derive(Default)]
+struct HeadlessWindowBackend {
+    /// Resize requests which are applied after the window requests have
+    /// been drained.
+    queued_resizes: Vec<(WindowID, PhysicalSizeI32)>,
+    fullscreen_windows: FxHashSet<WindowID>,
+    outer_positions: FxHashMap<WindowID, PhysicalPoint>,
+    main_window_closed: bool,
+}
+
+impl WindowBackend for HeadlessWindowBackend {
+    fn set_pointer_position(
+        &mut self,
+        _window_id: WindowID,
+        _position: PhysicalPoint,
+    ) -> Result<(), ()> {
+        // Headless windows don't have a pointer.
+        Err(())
+    }
+
+    fn unlock_pointer(&mut self, _window_id: WindowID, _prev_lock_state: PointerLockState) {
+        // Headless windows don't have a pointer.
+    }
+
+    fn request_redraw(&mut self, _window_id: WindowID) {
+        // Every window is redrawn on every tick.
+    }
+
+    fn has_focus(&mut self, _window_id: WindowID) -> bool {
+        false
+    }
+
+    fn try_lock_pointer(&mut self, _window_id: WindowID) -> PointerLockState {
+        PointerLockState::NotLocked
+    }
+
+    fn set_cursor_icon(&mut self, _window_id: WindowID, _icon: CursorIcon) {
+        // Headless windows don't have a pointer.
+    }
+
+    fn resize(
+        &mut self,
+        window_id: WindowID,
+        logical_size: Size,
+        scale_factor: ScaleFactor,
+    ) -> Result<(), ()> {
+        let physical_size = crate::math::to_physical_size(logical_size, scale_factor)
+            .round()
+            .cast();
+
+        self.queued_resizes.push((window_id, physical_size));
+        Ok(())
+    }
+
+    fn set_minimized(&mut self, _window_id: WindowID, _minimized: bool) {
+        // Not relevant for headless windows.
+    }
+
+    fn set_maximized(&mut self, _window_id: WindowID, _maximized: bool) {
+        // Not relevant for headless windows.
+    }
+
+    fn focus_window(&mut self, _window_id: WindowID) {
+        // Not relevant for headless windows.
+    }
+
+    fn set_window_title(&mut self, _window_id: WindowID, _title: String) {
+        // Not relevant for headless windows.
+    }
+
+    fn set_window_icon(&mut self, _window_id: WindowID, _icon: Option<WindowIcon>) {
+        // Not relevant for headless windows.
+    }
+
+    fn set_fullscreen(&mut self, window_id: WindowID, mode: Option<FullscreenMode>) {
+        if mode.is_some() {
+            self.fullscreen_windows.insert(window_id);
+        } else {
+            self.fullscreen_windows.remove(&window_id);
+        }
+    }
+
+    fn is_fullscreen(&mut self, window_id: WindowID) -> bool {
+        self.fullscreen_windows.contains(&window_id)
+    }
+
+    fn outer_position(&mut self, window_id: WindowID) -> Option<PhysicalPoint> {
+        self.outer_positions.get(&window_id).copied()
+    }
+
+    fn set_outer_position(&mut self, window_id: WindowID, position: PhysicalPoint) {
+        // There is no desktop to move headless windows on, so just remember
+        // the position.
+        self.outer_positions.insert(window_id, position);
+    }
+
+    fn current_geometry(
+        &mut self,
+        _window_id: WindowID,
+        _scale_factor: ScaleFactor,
+    ) -> Option<WindowGeometry> {
+        // The geometry of headless windows is tracked in `WindowState`.
+        None
+    }
+
+    fn available_monitors(&mut self) -> Vec<MonitorInfo> {
+        // Headless windows have no monitor.
+        Vec::new()
+    }
+
+    fn current_monitor(&mut self, _window_id: WindowID) -> Option<MonitorInfo> {
+        // Headless windows have no monitor.
+        None
+    }
+
+    fn set_ime_allowed(&mut self, _window_id: WindowID, _allowed: bool) {
+        // Headless windows don't receive text input.
+    }
+
+    fn set_ime_cursor_area(
+        &mut self,
+        _window_id: WindowID,
+        _area: Rect,
+        _scale_factor: ScaleFactor,
+    ) {
+        // Headless windows don't receive text input.
+    }
+
+    fn drag_window(&mut self, _window_id: WindowID) -> Result<(), ()> {
+        // Headless windows can't be dragged.
+        Err(())
+    }
+
+    fn drag_resize_window(
+        &mut self,
+        _window_id: WindowID,
+        _direction: ResizeDirection,
+    ) -> Result<(), ()> {
+        // Headless windows can't be dragged.
+        Err(())
+    }
+
+    fn create_window<A: Clone + 'static>(
+        &mut self,
+        window_id: WindowID,
+        config: &WindowConfig,
+        action_sender: &ActionSender<A>,
+        res: &mut ResourceCtx,
+    ) -> Result<WindowState<A>, OpenWindowError> {
+        let window_state = create_window(window_id, config, action_sender, res)?;
+
+        if let Some(position) = window_state.outer_position {
+            self.outer_positions.insert(window_id, position);
+        }
+
+        Ok(window_state)
+    }
+
+    fn close_window(&mut self, window_id: WindowID) {
+        self.fullscreen_windows.remove(&window_id);
+
+        if window_id == MAIN_WINDOW {
+            self.main_window_closed = true;
+        }
+    }
+}
+
+/// Run the application without opening an OS window.
+///
+/// Every window is rendered into an offscreen buffer using a
+/// [`HeadlessRenderBackend`]. The application is ticked `num_frames` times
+/// with a fixed delta time derived from [`AppConfig::tick_timer_interval`]
+/// (assuming a 60Hz display), and the contents of the main window are
+/// captured after each tick.
+///
+/// This stops early if the main window is closed.
+///
+/// This is useful for CI and snapshot tests.
+pub fn run_headless<A: Application>(
+    config: AppConfig,
+    num_frames: usize,
+) -> Result<Vec<ImageBuffer>, Box<dyn Error>> {
+    let mut res = ResourceCtx {
+        style_system: StyleSystem::new(config.use_dark_theme),
+        font_system: FontSystem::new(),
+        #[cfg(feature = "svg-icons")]
+        svg_icon_system: Default::default(),
+    };
+
+    let (action_sender, action_receiver) = crate::action_channel::<A::Action>();
+
+    let main_window_state = create_window(
+        MAIN_WINDOW,
+        &config.main_window_config,
+        &action_sender,
+        &mut res,
+    )?;
+
+    let dt = match config.tick_timer_interval {
+        TimerInterval::Fixed(interval) => interval.as_secs_f64(),
+        TimerInterval::PercentageOfFrameRate(percentage) => {
+            percentage * 1_000.0 / ASSUMED_MILLIHERTZ
+        }
+    };
+
+    let mut app_handler = AppHandler::<A>::new(
+        main_window_state,
+        action_sender,
+        action_receiver,
+        config,
+        res,
+        None,
+        Vec::new(),
+    )?;
+
+    let mut backend = HeadlessWindowBackend::default();
+    if let Some(position) = app_handler.cx.main_window.outer_position {
+        backend.outer_positions.insert(MAIN_WINDOW, position);
+    }
+    let mut frames = Vec::with_capacity(num_frames);
+
+    // Process any updates made during `Application::init`.
+    process_updates(&mut app_handler, &mut backend);
+
+    for _ in 0..num_frames {
+        if backend.main_window_closed {
+            break;
+        }
+
+        app_handler.on_tick_with_delta(dt);
+        process_updates(&mut app_handler, &mut backend);
+
+        for window_state in app_handler.cx.window_map.values_mut() {
+            window_state.render(|| {}, &mut app_handler.cx.res)?;
+        }
+        app_handler
+            .cx
+            .main_window
+            .render(|| {}, &mut app_handler.cx.res)?;
+
+        if let Some(frame) = app_handler.cx.main_window.capture() {
+            frames.push(frame?);
+        }
+    }
+
+    Ok(frames)
+}
+
+fn process_updates<A: Application>(
+    app_handler: &mut AppHandler<A>,
+    backend: &mut HeadlessWindowBackend,
+) {
+    app_handler.process_updates(backend);
+
+    if backend.queued_resizes.is_empty() {
+        return;
+    }
+
+    for (window_id, new_size) in std::mem::take(&mut backend.queued_resizes) {
+        let window_state = if window_id == MAIN_WINDOW {
+            &mut app_handler.cx.main_window
+        } else if let Some(window_state) = app_handler.cx.window_map.get_mut(&window_id) {
+            window_state
+        } else {
+            continue;
+        };
+
+        window_state.set_size(new_size, SYSTEM_SCALE_FACTOR.into());
+        window_state.is_fullscreen = backend.fullscreen_windows.contains(&window_id);
+
+        app_handler.user_app.on_window_event(
+            crate::event::AppWindowEvent::WindowResized,
+            window_id,
+            &mut app_handler.cx,
+        );
+    }
+
+    // Process any updates made in response to the resize.
+    app_handler.process_updates(backend);
+}
+
+fn create_window<A: Clone + 'static>(
+    id: WindowID,
+    config: &WindowConfig,
+    action_sender: &ActionSender<A>,
+    res: &mut ResourceCtx,
+) -> Result<WindowState<A>, OpenWindowError> {
+    let system_scale_factor: ScaleFactor = SYSTEM_SCALE_FACTOR.into();
+    let scale_factor = config.scale_factor.scale_factor(system_scale_factor);
+
+    let logical_size = config.initial_size();
+    let physical_size: PhysicalSizeI32 = crate::math::to_physical_size(logical_size, scale_factor)
+        .round()
+        .cast();
+
+    let render_backend = HeadlessRenderBackend::new(physical_size, &mut res.font_system)?;
+
+    let element_system = ElementSystem::new(
+        physical_size,
+        scale_factor,
+        config.element_system_config(),
+        action_sender.clone(),
+        id,
+    );
+
+    Ok(WindowState {
+        element_system,
+        render_backend: Some(WindowRenderBackend::Headless(render_backend)),
+        logical_size,
+        physical_size,
+        scale_factor,
+        scale_factor_recip: scale_factor.recip(),
+        system_scale_factor,
+        scale_factor_config: config.scale_factor,
+        queued_pointer_position: None,
+        queued_pointer_delta: None,
+        prev_pointer_pos: None,
+        pointer_btn_states: [PointerBtnState::default(); 5],
+        multi_click_interval: config.multi_click_interval,
+        multi_click_max_distance: config.multi_click_max_distance,
+        modifiers: Modifiers::empty(),
+        current_cursor_icon: CursorIcon::Default,
+        pointer_lock_state: PointerLockState::NotLocked,
+        is_fullscreen: false,
+        outer_position: config.geometry.and_then(|geometry| geometry.position),
+        is_maximized: config.geometry.is_some_and(|geometry| geometry.maximized),
+        current_monitor: None,
+        pointer_debounce: PointerDebounce::new(config.pointer_debounce_interval),
+        frame_limiter: FrameLimiter::default(),
+        clipboard: Clipboard {
+            state: ClipboardState::Unavailable,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppContext;
+
+    fn saved_geometry() -> WindowGeometry {
+        WindowGeometry {
+            position: Some(PhysicalPoint::new(100.0, 50.0)),
+            size: Size::new(30.0, 20.0),
+            scale_factor: SYSTEM_SCALE_FACTOR.into(),
+            maximized: true,
+        }
+    }
+
+    struct GeometryApp;
+
+    impl Application for GeometryApp {
+        type Action = ();
+
+        fn init(cx: &mut AppContext<()>) -> Result<Self, Box<dyn Error>> {
+            assert_eq!(cx.window_geometry(MAIN_WINDOW), Some(saved_geometry()));
+            Ok(Self)
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn test_headless_windows_are_opened_with_the_saved_geometry() {
+        let config = AppConfig {
+            main_window_config: WindowConfig {
+                size: Size::new(40.0, 40.0),
+                geometry: Some(saved_geometry()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let frames = run_headless::<GeometryApp>(config, 1).unwrap();
+        assert_eq!(frames[0].size, PhysicalSizeI32::new(30, 20));
+    }
+
+    struct MoveApp;
+
+    impl Application for MoveApp {
+        type Action = ();
+
+        fn init(cx: &mut AppContext<()>) -> Result<Self, Box<dyn Error>> {
+            assert_eq!(cx.outer_position(MAIN_WINDOW), None);
+            cx.set_outer_position(MAIN_WINDOW, PhysicalPoint::new(12.0, 34.0));
+            Ok(Self)
+        }
+
+        fn on_tick(&mut self, _dt: f64, cx: &mut AppContext<()>) {
+            assert_eq!(
+                cx.outer_position(MAIN_WINDOW),
+                Some(PhysicalPoint::new(12.0, 34.0))
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn test_set_outer_position_updates_the_window_position() {
+        let frames = run_headless::<MoveApp>(AppConfig::default(), 1).unwrap();
+        assert_eq!(frames.len(), 1);
+    }
+}
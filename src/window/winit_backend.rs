@@ -8,29 +8,38 @@ use std::time::{Duration, Instant};
 use winit::application::ApplicationHandler as WinitApplicationHandler;
 use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::{
-    ElementState, MouseButton as WinitMouseButton, MouseScrollDelta, StartCause,
-    WindowEvent as WinitWindowEvent,
+    ElementState, Ime, MouseButton as WinitMouseButton, StartCause, WindowEvent as WinitWindowEvent,
 };
-use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
-use winit::window::{CursorGrabMode, Window as WinitWindow, WindowId as WinitWindowId};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
+use winit::monitor::MonitorHandle;
+use winit::window::{CursorGrabMode, Fullscreen, Window as WinitWindow, WindowId as WinitWindowId};
 
 use crate::action_queue::ActionSender;
-use crate::application::{Application, TimerInterval};
-use crate::element_system::ElementSystemConfig;
-use crate::event::{AppWindowEvent, EventCaptureStatus, PointerButton, WheelDeltaType};
-use crate::math::{PhysicalPoint, PhysicalSizeI32, ScaleFactor, Size, Vector};
+use crate::application::{interpolation_alpha, AcceleratorPriority, Application, TimerInterval};
+use crate::element_system::WgpuRenderBackend;
+use crate::event::{AppWindowEvent, EventCaptureStatus, PointerButton};
+use crate::math::{PhysicalPoint, PhysicalRect, PhysicalSizeI32, Rect, ScaleFactor, Size};
 use crate::prelude::{AppHandler, ResourceCtx};
 use crate::style::StyleSystem;
 use crate::window::{WindowID, MAIN_WINDOW};
 use crate::AppConfig;
 
 use super::{
-    Clipboard, CursorIcon, ElementSystem, LinuxBackendType, PointerBtnState, PointerLockState,
-    ScaleFactorConfig, WindowBackend, WindowCloseRequest, WindowConfig, WindowState,
+    clamp_window_to_monitors, Clipboard, CursorIcon, ElementSystem, FrameLimiter, FullscreenMode,
+    LinuxBackendType, MonitorInfo, PointerBtnState, PointerDebounce, PointerLockState,
+    ResizeDirection, ScaleFactorConfig, WindowBackend, WindowCloseRequest, WindowConfig,
+    WindowGeometry, WindowIcon, WindowRenderBackend, WindowState,
 };
 
 mod convert;
 
+/// The user event of the event loop. AccessKit sends its requests through the
+/// event loop so that they can be handled on the main thread.
+#[cfg(feature = "accessibility")]
+type UserEvent = accesskit_winit::Event;
+#[cfg(not(feature = "accessibility"))]
+type UserEvent = ();
+
 struct WinitWindowBackend<'a> {
     inner: &'a mut WinitAppHandlerInner,
     event_loop: &'a ActiveEventLoop,
@@ -216,6 +225,133 @@ impl<'a> WindowBackend for WinitWindowBackend<'a> {
         }
     }
 
+    fn set_fullscreen(&mut self, window_id: WindowID, mode: Option<FullscreenMode>) {
+        if let Some(window_handle) = self.inner.windows.get(&window_id) {
+            let fullscreen = mode.and_then(|mode| {
+                to_winit_fullscreen(
+                    &mode,
+                    window_handle.available_monitors(),
+                    window_handle.current_monitor(),
+                )
+            });
+
+            if mode.is_some() && fullscreen.is_none() {
+                return;
+            }
+
+            window_handle.set_fullscreen(fullscreen);
+        }
+    }
+
+    fn is_fullscreen(&mut self, window_id: WindowID) -> bool {
+        self.inner
+            .windows
+            .get(&window_id)
+            .map(|w| w.fullscreen().is_some())
+            .unwrap_or(false)
+    }
+
+    fn outer_position(&mut self, window_id: WindowID) -> Option<PhysicalPoint> {
+        self.inner
+            .windows
+            .get(&window_id)
+            .and_then(|w| w.outer_position().ok())
+            .map(|pos| PhysicalPoint::new(pos.x as f32, pos.y as f32))
+    }
+
+    fn set_outer_position(&mut self, window_id: WindowID, position: PhysicalPoint) {
+        if let Some(window_handle) = self.inner.windows.get(&window_id) {
+            window_handle.set_outer_position(PhysicalPosition::new(
+                position.x.round() as i32,
+                position.y.round() as i32,
+            ));
+        }
+    }
+
+    fn current_geometry(
+        &mut self,
+        window_id: WindowID,
+        scale_factor: ScaleFactor,
+    ) -> Option<WindowGeometry> {
+        let window_handle = self.inner.windows.get(&window_id)?;
+
+        let physical_size = window_handle.inner_size();
+        let physical_size =
+            PhysicalSizeI32::new(physical_size.width as i32, physical_size.height as i32);
+
+        Some(WindowGeometry {
+            position: window_handle
+                .outer_position()
+                .ok()
+                .map(|pos| PhysicalPoint::new(pos.x as f32, pos.y as f32)),
+            size: crate::math::to_logical_size_i32(physical_size, scale_factor),
+            scale_factor,
+            maximized: window_handle.is_maximized(),
+        })
+    }
+
+    fn available_monitors(&mut self) -> Vec<MonitorInfo> {
+        available_monitors(self.event_loop)
+    }
+
+    fn current_monitor(&mut self, window_id: WindowID) -> Option<MonitorInfo> {
+        self.inner
+            .windows
+            .get(&window_id)
+            .and_then(|w| w.current_monitor())
+            .map(|m| to_monitor_info(&m))
+    }
+
+    fn set_ime_allowed(&mut self, window_id: WindowID, allowed: bool) {
+        if let Some(window_handle) = self.inner.windows.get(&window_id) {
+            // While allowed, the window receives `WindowEvent::Ime` events so
+            // that text can be composed with the OS input method.
+            window_handle.set_ime_allowed(allowed);
+        }
+    }
+
+    fn set_ime_cursor_area(&mut self, window_id: WindowID, area: Rect, scale_factor: ScaleFactor) {
+        if let Some(window_handle) = self.inner.windows.get(&window_id) {
+            window_handle.set_ime_cursor_area(
+                PhysicalPosition::new(area.min_x() * scale_factor, area.min_y() * scale_factor),
+                PhysicalSize::new(area.width() * scale_factor, area.height() * scale_factor),
+            );
+        }
+    }
+
+    fn drag_window(&mut self, window_id: WindowID) -> Result<(), ()> {
+        let Some(window_handle) = self.inner.windows.get(&window_id) else {
+            return Err(());
+        };
+
+        window_handle.drag_window().map_err(|_| ())
+    }
+
+    fn drag_resize_window(
+        &mut self,
+        window_id: WindowID,
+        direction: ResizeDirection,
+    ) -> Result<(), ()> {
+        let Some(window_handle) = self.inner.windows.get(&window_id) else {
+            return Err(());
+        };
+
+        window_handle
+            .drag_resize_window(convert::convert_resize_direction_to_winit(direction))
+            .map_err(|_| ())
+    }
+
+    fn set_window_icon(&mut self, window_id: WindowID, icon: Option<WindowIcon>) {
+        if let Some(window_handle) = self.inner.windows.get(&window_id) {
+            if icon.is_some() && !window_icon_supported(window_handle) {
+                log::debug!("Setting the window icon is not supported on this platform");
+                return;
+            }
+
+            window_handle.set_window_icon(icon.as_ref().and_then(to_winit_icon));
+        }
+    }
+
     fn create_window<A: Clone + 'static>(
         &mut self,
         window_id: WindowID,
@@ -225,6 +361,10 @@ impl<'a> WindowBackend for WinitWindowBackend<'a> {
     ) -> Result<WindowState<A>, OpenWindowError> {
         match create_window(window_id, config, self.event_loop, action_sender, res) {
             Ok((window_handle, window_state)) => {
+                #[cfg(feature = "accessibility")]
+                self.inner
+                    .init_accessibility(window_id, &window_handle, self.event_loop);
+
                 self.inner
                     .winit_id_to_window_id_map
                     .insert(window_handle.id(), window_id);
@@ -237,6 +377,9 @@ impl<'a> WindowBackend for WinitWindowBackend<'a> {
     }
 
     fn close_window(&mut self, window_id: WindowID) {
+        #[cfg(feature = "accessibility")]
+        self.inner.accesskit_adapters.remove(&window_id);
+
         if let Some(window_handle) = self.inner.windows.remove(&window_id) {
             self.inner
                 .winit_id_to_window_id_map
@@ -254,15 +397,107 @@ struct PreMainWindowData {
 
 struct WinitAppHandlerInner {
     tick_interval: Duration,
-    pointer_debounce_interval: Duration,
-    prev_cursor_debounce_instant: Instant,
+    pointer_debounce_interval: TimerInterval,
+    /// The refresh rate of the main window's monitor, used for windows whose
+    /// monitor is unknown.
+    refresh_rate_millihertz: u32,
     requested_tick_resume: Instant,
+    /// The instant at which a held back pointer move event (or a redraw held
+    /// back by a `FrameLimiter`) is due.
     requested_cursor_debounce_resume: Option<Instant>,
 
     winit_id_to_window_id_map: FxHashMap<WinitWindowId, WindowID>,
     windows: FxHashMap<WindowID, Arc<winit::window::Window>>,
+    #[cfg(feature = "accessibility")]
+    accesskit_adapters: FxHashMap<WindowID, accesskit_winit::Adapter>,
+    #[cfg(feature = "accessibility")]
+    event_loop_proxy: EventLoopProxy<UserEvent>,
 
     tick_wait_cancelled: bool,
+    /// See `AppConfig::render_on_demand`.
+    render_on_demand: bool,
+    /// Whether the tick timer is currently paused because nothing needs it.
+    tick_paused: bool,
+}
+
+impl WinitAppHandlerInner {
+    /// Hold back a pointer move event in the given window if it arrived too
+    /// soon after the previously processed one.
+    ///
+    /// Returns `true` if the event should be processed now.
+    fn debounce_pointer<A: Clone + 'static>(
+        &mut self,
+        window_state: &mut WindowState<A>,
+        event_loop: &ActiveEventLoop,
+    ) -> bool {
+        // Use the refresh rate of the monitor the window is currently on.
+        let refresh_rate_millihertz = window_state
+            .current_monitor
+            .as_ref()
+            .and_then(|m| m.refresh_rate_millihertz)
+            .unwrap_or(self.refresh_rate_millihertz);
+
+        let pointer_debounce = &mut window_state.pointer_debounce;
+        let interval =
+            pointer_debounce.interval(self.pointer_debounce_interval, refresh_rate_millihertz);
+
+        if pointer_debounce.on_pointer_moved(Instant::now(), interval) {
+            return true;
+        }
+
+        // Make sure that the latest cursor move event is always sent.
+        if let Some(resume_instant) = pointer_debounce.requested_resume() {
+            self.request_pointer_resume(resume_instant, event_loop);
+        }
+
+        false
+    }
+
+    fn request_pointer_resume(
+        &mut self,
+        mut resume_instant: Instant,
+        event_loop: &ActiveEventLoop,
+    ) {
+        if let Some(prev_resume_instant) = self.requested_cursor_debounce_resume {
+            if prev_resume_instant <= resume_instant {
+                // The event loop will already wake up in time.
+                return;
+            }
+        }
+
+        if resume_instant == self.requested_tick_resume {
+            // Make sure we don't clash with the tick timer.
+            resume_instant += Duration::from_micros(1);
+        }
+        self.requested_cursor_debounce_resume = Some(resume_instant);
+
+        event_loop.set_control_flow(ControlFlow::WaitUntil(resume_instant));
+    }
+}
+
+#[cfg(feature = "accessibility")]
+impl WinitAppHandlerInner {
+    /// Create the AccessKit adapter for a newly created window and then show
+    /// the window.
+    ///
+    /// The adapter must be created before the window is shown for the first
+    /// time, so windows are created invisible when this feature is enabled.
+    fn init_accessibility(
+        &mut self,
+        window_id: WindowID,
+        window_handle: &WinitWindow,
+        event_loop: &ActiveEventLoop,
+    ) {
+        let adapter = accesskit_winit::Adapter::with_event_loop_proxy(
+            event_loop,
+            window_handle,
+            self.event_loop_proxy.clone(),
+        );
+
+        self.accesskit_adapters.insert(window_id, adapter);
+
+        window_handle.set_visible(true);
+    }
 }
 
 struct WinitAppHandler<A: Application> {
@@ -272,20 +507,29 @@ struct WinitAppHandler<A: Application> {
 }
 
 impl<A: Application> WinitAppHandler<A> {
-    fn new(config: AppConfig) -> Result<Self, Box<dyn Error>> {
+    fn new(
+        config: AppConfig,
+        #[allow(unused)] event_loop_proxy: EventLoopProxy<UserEvent>,
+    ) -> Result<Self, Box<dyn Error>> {
         let use_dark_theme = config.use_dark_theme;
 
         Ok(Self {
             app_handler: None,
             inner: WinitAppHandlerInner {
                 tick_interval: Duration::default(),
-                pointer_debounce_interval: Duration::default(),
-                prev_cursor_debounce_instant: Instant::now(),
+                pointer_debounce_interval: TimerInterval::default(),
+                refresh_rate_millihertz: FALLBACK_REFRESH_RATE_MILLIHERTZ,
                 requested_tick_resume: Instant::now(),
                 requested_cursor_debounce_resume: None,
                 winit_id_to_window_id_map: FxHashMap::default(),
                 windows: FxHashMap::default(),
+                #[cfg(feature = "accessibility")]
+                accesskit_adapters: FxHashMap::default(),
+                #[cfg(feature = "accessibility")]
+                event_loop_proxy,
                 tick_wait_cancelled: false,
+                render_on_demand: false,
+                tick_paused: false,
             },
             pre_main_window_data: Some(PreMainWindowData {
                 config,
@@ -299,17 +543,54 @@ impl<A: Application> WinitAppHandler<A> {
         })
     }
 
+    /// Mark the held back pointer move events which are now due as processed.
+    fn resume_debounced_pointers(&mut self) {
+        self.inner.requested_cursor_debounce_resume = None;
+
+        let Some(app_handler) = &mut self.app_handler else {
+            return;
+        };
+
+        let now = Instant::now();
+        for window_state in app_handler
+            .cx
+            .window_map
+            .values_mut()
+            .chain([&mut app_handler.cx.main_window])
+        {
+            window_state.pointer_debounce.resume(now);
+        }
+    }
+
     fn process_updates(&mut self, event_loop: &ActiveEventLoop) {
         if let Some(app_handler) = &mut self.app_handler {
             app_handler.process_updates(&mut WinitWindowBackend {
                 inner: &mut self.inner,
                 event_loop,
             });
+
+            #[cfg(feature = "accessibility")]
+            for (window_id, window_state) in app_handler
+                .cx
+                .window_map
+                .iter_mut()
+                .chain([(&MAIN_WINDOW, &mut app_handler.cx.main_window)])
+            {
+                if !window_state.element_system.accessibility_tree_changed() {
+                    continue;
+                }
+
+                if let Some(adapter) = self.inner.accesskit_adapters.get_mut(window_id) {
+                    adapter.update_if_active(|| {
+                        window_state.element_system.accessibility_tree_update()
+                    });
+                }
+            }
         }
     }
 }
 
-impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
+impl<A: Application> WinitApplicationHandler<UserEvent> for WinitAppHandler<A> {
     fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
         self.inner.tick_wait_cancelled = false;
 
@@ -318,6 +599,8 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                 requested_resume, ..
             } => {
                 if requested_resume == self.inner.requested_tick_resume {
+                    self.resume_debounced_pointers();
+
                     if let Some(app_handler) = &mut self.app_handler {
                         app_handler.on_tick();
                     }
@@ -327,6 +610,7 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                     self.inner.requested_cursor_debounce_resume
                 {
                     if pointer_resume_instant == requested_resume {
+                        self.resume_debounced_pointers();
                         self.process_updates(event_loop);
                     }
                 }
@@ -355,62 +639,30 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                 }
             };
 
+            #[cfg(feature = "accessibility")]
+            self.inner
+                .init_accessibility(MAIN_WINDOW, &window_handle, event_loop);
+
             self.inner
                 .windows
                 .insert(MAIN_WINDOW, Arc::clone(&window_handle));
 
-            let find_millihertz =
-                if let TimerInterval::PercentageOfFrameRate(_) = data.config.tick_timer_interval {
-                    true
-                } else if let TimerInterval::PercentageOfFrameRate(_) =
-                    data.config.pointer_debounce_interval
-                {
-                    true
-                } else {
-                    false
-                };
-            let millihertz = if find_millihertz {
-                // Attempt to get the refresh rate of the current monitor. If that's
-                // not possible, try other methods.
-                let mut millihertz = None;
-                if let Some(monitor) = window_handle.current_monitor() {
-                    millihertz = monitor.refresh_rate_millihertz();
-                }
-                if millihertz.is_none() {
-                    if let Some(monitor) = window_handle.primary_monitor() {
-                        millihertz = monitor.refresh_rate_millihertz();
-                    }
-                }
-                if millihertz.is_none() {
-                    if let Some(monitor) = event_loop.primary_monitor() {
-                        millihertz = monitor.refresh_rate_millihertz();
-                    }
-                }
-                if millihertz.is_none() {
-                    for monitor in event_loop.available_monitors() {
-                        if let Some(m) = monitor.refresh_rate_millihertz() {
-                            millihertz = Some(m);
-                            break;
-                        }
-                    }
-                }
-                millihertz.unwrap_or(60_000)
-            } else {
-                60_000
-            };
+            // Always query the refresh rate, since an interval relative to the
+            // frame rate can also be set after the application has started.
+            let millihertz = refresh_rate_millihertz(&window_handle, event_loop);
 
-            self.inner.tick_interval = match data.config.tick_timer_interval {
-                TimerInterval::Fixed(interval) => interval,
-                TimerInterval::PercentageOfFrameRate(percentage) => {
-                    Duration::from_secs_f64(percentage * 1_000.0 / millihertz as f64)
-                }
-            };
-            self.inner.pointer_debounce_interval = match data.config.pointer_debounce_interval {
-                TimerInterval::Fixed(interval) => interval,
-                TimerInterval::PercentageOfFrameRate(percentage) => {
-                    Duration::from_secs_f64(percentage * 1_000.0 / millihertz as f64)
+            self.inner.refresh_rate_millihertz = millihertz;
+            self.inner.tick_interval = data.config.tick_timer_interval.to_duration(millihertz);
+            self.inner.pointer_debounce_interval = data.config.pointer_debounce_interval;
+            self.inner.render_on_demand = data.config.render_on_demand;
+
+            if data.config.follow_system_theme {
+                if let Some(theme) = window_handle.theme() {
+                    let use_dark_theme = self::convert::convert_theme(theme).is_dark();
+                    data.res.style_system.set_dark_theme(use_dark_theme);
+                    data.config.use_dark_theme = use_dark_theme;
                 }
-            };
+            }
 
             self.inner
                 .winit_id_to_window_id_map
@@ -453,6 +705,7 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                 data.config,
                 data.res,
                 linux_backend_type,
+                available_monitors(event_loop),
             ) {
                 Ok(a) => a,
                 Err(e) => {
@@ -495,6 +748,14 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
             return;
         };
 
+        #[cfg(feature = "accessibility")]
+        if let (Some(adapter), Some(window_handle)) = (
+            self.inner.accesskit_adapters.get_mut(&window_id),
+            self.inner.windows.get(&window_id),
+        ) {
+            adapter.process_event(window_handle, &event);
+        }
+
         let mut process_updates = true;
 
         match event {
@@ -520,6 +781,8 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                                 .winit_id_to_window_id_map
                                 .remove(&winit_window_id);
                             self.inner.windows.remove(&window_id);
+                            #[cfg(feature = "accessibility")]
+                            self.inner.accesskit_adapters.remove(&window_id);
                         }
                         WindowCloseRequest::DoNotCloseYet => {}
                     }
@@ -530,6 +793,12 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
 
                 let window_handle = self.inner.windows.get(&window_id).unwrap();
 
+                window_state.set_interpolation_alpha(interpolation_alpha(
+                    app_handler.prev_tick_instant,
+                    self.inner.tick_interval,
+                    Instant::now(),
+                ));
+
                 match window_state.render(
                     || window_handle.pre_present_notify(),
                     &mut app_handler.cx.res,
@@ -560,6 +829,8 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
 
                 let scale_factor = window_handle.scale_factor().into();
                 window_state.set_size(new_size, scale_factor);
+                window_state.is_fullscreen = window_handle.fullscreen().is_some();
+                window_state.is_maximized = window_handle.is_maximized();
                 window_handle.request_redraw();
 
                 app_handler.user_app.on_window_event(
@@ -568,6 +839,22 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                     &mut app_handler.cx,
                 );
             }
+            WinitWindowEvent::Moved(position) => {
+                let position = PhysicalPoint::new(position.x as f32, position.y as f32);
+                window_state.outer_position = Some(position);
+
+                // The window may have been moved onto another monitor.
+                app_handler.update_monitors(&mut WinitWindowBackend {
+                    inner: &mut self.inner,
+                    event_loop,
+                });
+
+                app_handler.user_app.on_window_event(
+                    AppWindowEvent::WindowMoved(position),
+                    window_id,
+                    &mut app_handler.cx,
+                );
+            }
             WinitWindowEvent::ScaleFactorChanged {
                 scale_factor,
                 mut inner_size_writer,
@@ -586,6 +873,13 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
 
                 window_state.set_size(new_size, scale_factor.into());
 
+                // The scale factor changes when the window is moved onto a
+                // monitor with a different DPI.
+                app_handler.update_monitors(&mut WinitWindowBackend {
+                    inner: &mut self.inner,
+                    event_loop,
+                });
+
                 app_handler.user_app.on_window_event(
                     AppWindowEvent::WindowResized,
                     window_id,
@@ -626,21 +920,8 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
 
                 window_state.queued_pointer_position = Some(pos);
 
-                let now = Instant::now();
-                if now - self.inner.prev_cursor_debounce_instant
-                    < self.inner.pointer_debounce_interval
-                {
+                if !self.inner.debounce_pointer(window_state, event_loop) {
                     process_updates = false;
-
-                    // Make sure that the latest cursor move event is always sent.
-                    let mut resume_instant = now + self.inner.pointer_debounce_interval;
-                    if resume_instant == self.inner.requested_tick_resume {
-                        // Make sure we don't clash with the tick timer.
-                        resume_instant += Duration::from_micros(1);
-                    }
-                    self.inner.requested_cursor_debounce_resume = Some(resume_instant);
-
-                    event_loop.set_control_flow(ControlFlow::WaitUntil(resume_instant));
                 }
             }
             WinitWindowEvent::MouseInput {
@@ -663,23 +944,35 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                     &mut app_handler.cx.res,
                 );
             }
+            WinitWindowEvent::Touch(touch) => {
+                let phase = self::convert::convert_touch_phase(touch.phase);
+
+                window_state.handle_touch(
+                    touch.id,
+                    phase,
+                    PhysicalPoint::new(touch.location.x as f32, touch.location.y as f32),
+                    self::convert::convert_touch_pointer_type(touch.force),
+                    &mut app_handler.cx.res,
+                );
+            }
             WinitWindowEvent::CursorLeft { device_id: _ } => {
                 window_state.handle_pointer_left(&mut app_handler.cx.res);
             }
             WinitWindowEvent::MouseWheel {
                 device_id: _,
                 delta,
-                phase: _,
+                phase,
             } => {
-                let delta_type = match delta {
-                    MouseScrollDelta::LineDelta(x, y) => WheelDeltaType::Lines(Vector::new(x, -y)),
-                    MouseScrollDelta::PixelDelta(pos) => WheelDeltaType::Points(Vector::new(
-                        pos.x as f32 * window_state.scale_factor_recip,
-                        -pos.y as f32 * window_state.scale_factor_recip,
-                    )),
-                };
+                let delta_type = self::convert::convert_mouse_scroll_delta(
+                    delta,
+                    window_state.scale_factor_recip,
+                );
 
-                window_state.handle_mouse_wheel(delta_type, &mut app_handler.cx.res);
+                window_state.handle_mouse_wheel(
+                    delta_type,
+                    self::convert::convert_touch_phase(phase),
+                    &mut app_handler.cx.res,
+                );
             }
             WinitWindowEvent::Destroyed => {
                 app_handler.user_app.on_window_event(
@@ -707,9 +1000,17 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                 let key_event =
                     self::convert::convert_keyboard_event(&event, window_state.modifiers);
 
-                let mut captured = window_state
-                    .handle_keyboard_event(key_event.clone(), &mut app_handler.cx.res)
-                    == EventCaptureStatus::Captured;
+                let mut captured = app_handler.cx.accelerators.dispatch(
+                    &key_event,
+                    AcceleratorPriority::BeforeElements,
+                    &mut app_handler.cx.action_sender,
+                );
+
+                if !captured {
+                    captured = window_state
+                        .handle_keyboard_event(key_event.clone(), &mut app_handler.cx.res)
+                        == EventCaptureStatus::Captured;
+                }
 
                 if !captured {
                     if let Some(text) = &event.text {
@@ -732,6 +1033,14 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                     }
                 }
 
+                if !captured {
+                    captured = app_handler.cx.accelerators.dispatch(
+                        &key_event,
+                        AcceleratorPriority::AfterElements,
+                        &mut app_handler.cx.action_sender,
+                    );
+                }
+
                 if !captured {
                     app_handler.user_app.on_keyboard_event(
                         key_event,
@@ -740,6 +1049,43 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                     );
                 }
             }
+            WinitWindowEvent::Ime(ime) => {
+                let event = match ime {
+                    Ime::Enabled => CompositionEvent {
+                        state: CompositionState::Start,
+                        data: String::new(),
+                    },
+                    // The preedit text is only displayed by the focused element,
+                    // it is not committed until `CompositionState::End`.
+                    Ime::Preedit(text, _cursor_range) => CompositionEvent {
+                        state: CompositionState::Update,
+                        data: text,
+                    },
+                    Ime::Commit(text) => CompositionEvent {
+                        state: CompositionState::End,
+                        data: text,
+                    },
+                    Ime::Disabled => CompositionEvent {
+                        state: CompositionState::End,
+                        data: String::new(),
+                    },
+                };
+
+                window_state.handle_text_composition_event(event, &mut app_handler.cx.res);
+            }
+            WinitWindowEvent::ThemeChanged(theme) => {
+                let color_scheme = self::convert::convert_theme(theme);
+
+                if app_handler.cx.config.follow_system_theme {
+                    app_handler.cx.set_dark_theme(color_scheme.is_dark());
+                }
+
+                app_handler.user_app.on_window_event(
+                    AppWindowEvent::ThemeChanged(color_scheme),
+                    window_id,
+                    &mut app_handler.cx,
+                );
+            }
             _ => (),
         }
 
@@ -748,6 +1094,50 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
         }
     }
 
+    #[cfg(feature = "accessibility")]
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: accesskit_winit::Event) {
+        let Some(app_handler) = &mut self.app_handler else {
+            return;
+        };
+
+        let Some(window_id) = self
+            .inner
+            .winit_id_to_window_id_map
+            .get(&event.window_id)
+            .copied()
+        else {
+            return;
+        };
+
+        let window_state = if window_id == MAIN_WINDOW {
+            &mut app_handler.cx.main_window
+        } else if let Some(window_state) = app_handler.cx.window_map.get_mut(&window_id) {
+            window_state
+        } else {
+            return;
+        };
+
+        match event.window_event {
+            accesskit_winit::WindowEvent::InitialTreeRequested => {
+                // Assistive technology has just been activated, so the full
+                // tree must be sent even if nothing has changed.
+                if let Some(adapter) = self.inner.accesskit_adapters.get_mut(&window_id) {
+                    adapter.update_if_active(|| {
+                        window_state.element_system.accessibility_tree_update()
+                    });
+                }
+            }
+            accesskit_winit::WindowEvent::ActionRequested(request) => {
+                if !window_state.handle_accessibility_action(&request, &mut app_handler.cx.res) {
+                    log::debug!("Unhandled accessibility action request: {:?}", request);
+                }
+            }
+            accesskit_winit::WindowEvent::AccessibilityDeactivated => {}
+        }
+
+        self.process_updates(event_loop);
+    }
+
     fn device_event(
         &mut self,
         event_loop: &ActiveEventLoop,
@@ -773,33 +1163,52 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                         window.queued_pointer_delta = Some(delta);
                     }
 
-                    let now = Instant::now();
-                    if now - self.inner.prev_cursor_debounce_instant
-                        < self.inner.pointer_debounce_interval
-                    {
-                        // Make sure that the latest cursor move event is always sent.
-                        let mut resume_instant = now + self.inner.pointer_debounce_interval;
-                        if resume_instant == self.inner.requested_tick_resume {
-                            // Make sure we don't clash with the tick timer.
-                            resume_instant += Duration::from_micros(1);
-                        }
-                        self.inner.requested_cursor_debounce_resume = Some(resume_instant);
-
-                        event_loop.set_control_flow(ControlFlow::WaitUntil(resume_instant));
-                    }
+                    self.inner.debounce_pointer(window, event_loop);
                 }
             }
         }
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
-        if !self.inner.tick_wait_cancelled {
+        // While the tick timer is paused there is no deadline to keep, so an
+        // input event which started an animation must be able to resume it.
+        if !self.inner.tick_wait_cancelled || self.inner.tick_paused {
             let now = Instant::now();
 
             let Some(app_handler) = &mut self.app_handler else {
                 return;
             };
 
+            if self.inner.render_on_demand && !app_handler.needs_ticks() {
+                self.inner.tick_paused = true;
+
+                // Still wake up for held back pointer move events and redraws.
+                let pointer_resume_instant = app_handler
+                    .cx
+                    .window_map
+                    .values()
+                    .chain([&app_handler.cx.main_window])
+                    .filter_map(|w| w.requested_resume())
+                    .min();
+
+                self.inner.requested_cursor_debounce_resume = pointer_resume_instant;
+                match pointer_resume_instant {
+                    Some(pointer_resume_instant) => {
+                        event_loop.set_control_flow(ControlFlow::WaitUntil(pointer_resume_instant))
+                    }
+                    None => event_loop.set_control_flow(ControlFlow::Wait),
+                }
+
+                return;
+            }
+
+            if self.inner.tick_paused {
+                // Don't send the time spent sleeping as the delta of the next
+                // tick.
+                self.inner.tick_paused = false;
+                app_handler.prev_tick_instant = now;
+            }
+
             let mut next_instant = if app_handler.prev_tick_instant + self.inner.tick_interval > now
             {
                 app_handler.prev_tick_instant + self.inner.tick_interval
@@ -809,7 +1218,17 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
                 now + self.inner.tick_interval
             };
 
-            if let Some(pointer_resume_instant) = self.inner.requested_cursor_debounce_resume {
+            // Wake up early if a window has a held back pointer move event or
+            // redraw which is due before the next tick.
+            let pointer_resume_instant = app_handler
+                .cx
+                .window_map
+                .values()
+                .chain([&app_handler.cx.main_window])
+                .filter_map(|w| w.requested_resume())
+                .min();
+
+            if let Some(pointer_resume_instant) = pointer_resume_instant {
                 if next_instant == pointer_resume_instant {
                     // Make sure we don't clash with the pointer debounce timer.
                     next_instant += Duration::from_micros(1);
@@ -818,7 +1237,15 @@ impl<A: Application> WinitApplicationHandler for WinitAppHandler<A> {
 
             self.inner.requested_tick_resume = next_instant;
 
-            event_loop.set_control_flow(ControlFlow::WaitUntil(next_instant));
+            match pointer_resume_instant {
+                Some(pointer_resume_instant) if pointer_resume_instant < next_instant => {
+                    self.inner.requested_cursor_debounce_resume = Some(pointer_resume_instant);
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(pointer_resume_instant));
+                }
+                _ => {
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(next_instant));
+                }
+            }
         }
     }
 }
@@ -829,18 +1256,156 @@ pub enum OpenWindowError {
     OsError(#[from] winit::error::OsError),
     #[error("{0}")]
     SurfaceError(#[from] rootvg::surface::NewSurfaceError),
+    #[cfg(feature = "headless")]
+    #[error("{0}")]
+    HeadlessRenderBackendError(#[from] crate::element_system::HeadlessRenderBackendError),
 }
 
 pub fn run_blocking<A: Application>(config: AppConfig) -> Result<(), Box<dyn Error>>
 where
     A::Action: Send,
 {
-    let event_loop = EventLoop::new()?;
-    let mut app_handler = WinitAppHandler::<A>::new(config)?;
+    let event_loop = EventLoop::<UserEvent>::with_user_event().build()?;
+    let mut app_handler = WinitAppHandler::<A>::new(config, event_loop.create_proxy())?;
 
     event_loop.run_app(&mut app_handler).map_err(Into::into)
 }
 
+fn to_winit_fullscreen(
+    mode: &FullscreenMode,
+    mut available_monitors: impl Iterator<Item = MonitorHandle>,
+    current_monitor: Option<MonitorHandle>,
+) -> Option<Fullscreen> {
+    match mode {
+        FullscreenMode::Borderless(monitor_index) => {
+            let monitor = if let Some(i) = monitor_index {
+                let monitor = available_monitors.nth(*i);
+                if monitor.is_none() {
+                    log::debug!(
+                        "No monitor with index {} exists, using the current monitor",
+                        i
+                    );
+                }
+                monitor.or(current_monitor)
+            } else {
+                current_monitor
+            };
+
+            Some(Fullscreen::Borderless(monitor))
+        }
+        FullscreenMode::Exclusive(video_mode) => {
+            let Some(monitor) = current_monitor else {
+                log::debug!("Could not find the current monitor for exclusive fullscreen");
+                return None;
+            };
+
+            let handle = monitor.video_modes().find(|m| {
+                m.size().width as i32 == video_mode.size.width
+                    && m.size().height as i32 == video_mode.size.height
+                    && m.bit_depth() == video_mode.bit_depth
+                    && m.refresh_rate_millihertz() == video_mode.refresh_rate_millihertz
+            });
+
+            if handle.is_none() {
+                log::debug!(
+                    "The current monitor does not support the video mode {:?}",
+                    video_mode
+                );
+            }
+
+            handle.map(Fullscreen::Exclusive)
+        }
+    }
+}
+
+fn to_winit_icon(icon: &WindowIcon) -> Option<winit::window::Icon> {
+    match winit::window::Icon::from_rgba(icon.rgba.clone(), icon.width, icon.height) {
+        Ok(icon) => Some(icon),
+        Err(e) => {
+            log::debug!("Could not create window icon: {}", e);
+            None
+        }
+    }
+}
+
+/// Whether or not the platform supports setting the window icon.
+#[allow(unused_variables, unreachable_code)]
+fn window_icon_supported(window: &WinitWindow) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        return false;
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "openbsd",
+        target_os = "netbsd",
+    ))]
+    {
+        use raw_window_handle_06::{HasWindowHandle, RawWindowHandle};
+
+        if let Ok(window_handle) = window.window_handle() {
+            if let RawWindowHandle::Wayland(_) = window_handle.as_raw() {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Describe the given monitor with the backend-independent [`MonitorInfo`].
+fn to_monitor_info(monitor: &MonitorHandle) -> MonitorInfo {
+    let position = monitor.position();
+    let size = monitor.size();
+
+    MonitorInfo {
+        name: monitor.name(),
+        position: PhysicalPoint::new(position.x as f32, position.y as f32),
+        size: PhysicalSizeI32::new(size.width as i32, size.height as i32),
+        scale_factor: monitor.scale_factor().into(),
+        refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+    }
+}
+
+fn available_monitors(event_loop: &ActiveEventLoop) -> Vec<MonitorInfo> {
+    event_loop
+        .available_monitors()
+        .map(|m| to_monitor_info(&m))
+        .collect()
+}
+
+/// The refresh rate to assume when no monitor reports one.
+const FALLBACK_REFRESH_RATE_MILLIHERTZ: u32 = 60_000;
+
+/// The refresh rate of the monitor the window is on.
+///
+/// If that can't be determined, then the refresh rate of the primary monitor
+/// (or any other monitor) is used instead, falling back to 60Hz.
+fn refresh_rate_millihertz(window: &WinitWindow, event_loop: &ActiveEventLoop) -> u32 {
+    window
+        .current_monitor()
+        .and_then(|m| m.refresh_rate_millihertz())
+        .or_else(|| {
+            window
+                .primary_monitor()
+                .and_then(|m| m.refresh_rate_millihertz())
+        })
+        .or_else(|| {
+            event_loop
+                .primary_monitor()
+                .and_then(|m| m.refresh_rate_millihertz())
+        })
+        .or_else(|| {
+            event_loop
+                .available_monitors()
+                .find_map(|m| m.refresh_rate_millihertz())
+        })
+        .unwrap_or(FALLBACK_REFRESH_RATE_MILLIHERTZ)
+}
+
 fn create_window<A: Clone + 'static>(
     id: WindowID,
     config: &WindowConfig,
@@ -854,15 +1419,23 @@ fn create_window<A: Clone + 'static>(
         .with_resizable(config.resizable)
         .with_active(config.focus_on_creation);
 
+    // The AccessKit adapter must be created before the window is shown.
+    #[cfg(feature = "accessibility")]
+    {
+        attributes = attributes.with_visible(false);
+    }
+
+    let logical_size = config.initial_size();
+
     match config.scale_factor {
         ScaleFactorConfig::System => {
             attributes = attributes.with_inner_size(winit::dpi::LogicalSize::new(
-                config.size.width,
-                config.size.height,
+                logical_size.width,
+                logical_size.height,
             ));
         }
         ScaleFactorConfig::Custom(scale_factor) => {
-            let size: PhysicalSizeI32 = crate::math::to_physical_size(config.size, scale_factor)
+            let size: PhysicalSizeI32 = crate::math::to_physical_size(logical_size, scale_factor)
                 .round()
                 .cast();
 
@@ -896,8 +1469,43 @@ fn create_window<A: Clone + 'static>(
         }
     }
 
+    if let Some(icon) = &config.icon {
+        attributes = attributes.with_window_icon(to_winit_icon(icon));
+    }
+
+    if let Some(geometry) = &config.geometry {
+        attributes = attributes.with_maximized(geometry.maximized);
+
+        if let Some(position) = geometry.position {
+            let scale_factor = config.scale_factor.scale_factor(geometry.scale_factor);
+            let size = crate::math::to_physical_size(logical_size, scale_factor);
+            let monitors: Vec<PhysicalRect> = available_monitors(event_loop)
+                .iter()
+                .map(|m| m.rect())
+                .collect();
+
+            let position = clamp_window_to_monitors(PhysicalRect::new(position, size), &monitors);
+            attributes = attributes.with_position(PhysicalPosition::new(
+                position.x.round() as i32,
+                position.y.round() as i32,
+            ));
+        }
+    }
+
+    if let Some(mode) = &config.fullscreen {
+        attributes = attributes.with_fullscreen(to_winit_fullscreen(
+            mode,
+            event_loop.available_monitors(),
+            event_loop.primary_monitor(),
+        ));
+    }
+
     let window = event_loop.create_window(attributes).map(|w| Arc::new(w))?;
 
+    if config.icon.is_some() && !window_icon_supported(&window) {
+        log::debug!("Setting the window icon is not supported on this platform");
+    }
+
     // Might fix an issue in MacOS with wgpu
     // https://github.com/gfx-rs/wgpu/issues/5722
     window.request_redraw();
@@ -916,39 +1524,29 @@ fn create_window<A: Clone + 'static>(
         config.surface_config.clone(),
     )?;
 
-    let canvas_config = surface.canvas_config();
-
-    let renderer = rootvg::Canvas::new(
-        &surface.device,
-        &surface.queue,
-        surface.format(),
-        canvas_config,
-        &mut res.font_system,
-    );
+    let render_backend = WgpuRenderBackend::new(surface, &mut res.font_system);
 
     let element_system = ElementSystem::new(
         physical_size,
         scale_factor,
-        ElementSystemConfig {
-            clear_color: config.clear_color,
-            preallocate_for_this_many_elements: config.preallocate_for_this_many_elements,
-            hover_timeout_duration: config.hover_timeout_duration,
-            scroll_wheel_timeout_duration: config.scroll_wheel_timeout_duration,
-        },
+        config.element_system_config(),
         action_sender.clone(),
         id,
     );
 
     let clipboard = new_clipboard(&window);
+    let is_fullscreen = window.fullscreen().is_some();
+    let outer_position = window
+        .outer_position()
+        .ok()
+        .map(|pos| PhysicalPoint::new(pos.x as f32, pos.y as f32));
 
     Ok((
         window,
         WindowState {
             element_system,
-            renderer,
-            surface: Some(surface),
-            multisample: canvas_config.multisample,
-            logical_size: config.size,
+            render_backend: Some(WindowRenderBackend::Surface(render_backend)),
+            logical_size,
             physical_size,
             scale_factor,
             scale_factor_recip: scale_factor.recip(),
@@ -958,9 +1556,17 @@ fn create_window<A: Clone + 'static>(
             queued_pointer_delta: None,
             prev_pointer_pos: None,
             pointer_btn_states: [PointerBtnState::default(); 5],
+            multi_click_interval: config.multi_click_interval,
+            multi_click_max_distance: config.multi_click_max_distance,
             modifiers: Modifiers::empty(),
             current_cursor_icon: CursorIcon::Default,
             pointer_lock_state: PointerLockState::NotLocked,
+            is_fullscreen,
+            outer_position,
+            is_maximized: window.is_maximized(),
+            current_monitor: window.current_monitor().map(|m| to_monitor_info(&m)),
+            pointer_debounce: PointerDebounce::new(config.pointer_debounce_interval),
+            frame_limiter: FrameLimiter::default(),
             clipboard,
         },
     ))
@@ -1,12 +1,14 @@
 use keyboard_types::{Code, KeyState, Location, Modifiers};
 use winit::{
-    event::ElementState,
+    event::{ElementState, Force, MouseScrollDelta},
     keyboard::{KeyCode, KeyLocation, NativeKeyCode as WinitNativeKeyCode, PhysicalKey},
-    window::CursorIcon as WinitCursorIcon,
+    window::{CursorIcon as WinitCursorIcon, ResizeDirection as WinitResizeDirection, Theme},
 };
 
 use crate::{
-    event::{KeyboardEvent, NativeKey},
+    event::{ColorScheme, KeyboardEvent, NativeKey, PointerType, TouchPhase, WheelDeltaType},
+    math::Vector,
+    window::ResizeDirection,
     CursorIcon,
 };
 
@@ -92,6 +94,82 @@ pub fn convert_cursor_icon_to_winit(icon: CursorIcon) -> WinitCursorIcon {
     }
 }
 
+pub fn convert_resize_direction_to_winit(direction: ResizeDirection) -> WinitResizeDirection {
+    match direction {
+        ResizeDirection::East => WinitResizeDirection::East,
+        ResizeDirection::North => WinitResizeDirection::North,
+        ResizeDirection::NorthEast => WinitResizeDirection::NorthEast,
+        ResizeDirection::NorthWest => WinitResizeDirection::NorthWest,
+        ResizeDirection::South => WinitResizeDirection::South,
+        ResizeDirection::SouthEast => WinitResizeDirection::SouthEast,
+        ResizeDirection::SouthWest => WinitResizeDirection::SouthWest,
+        ResizeDirection::West => WinitResizeDirection::West,
+    }
+}
+
+pub fn convert_touch_phase(phase: winit::event::TouchPhase) -> TouchPhase {
+    match phase {
+        winit::event::TouchPhase::Started => TouchPhase::Started,
+        winit::event::TouchPhase::Moved => TouchPhase::Moved,
+        winit::event::TouchPhase::Ended => TouchPhase::Ended,
+        winit::event::TouchPhase::Cancelled => TouchPhase::Cancelled,
+    }
+}
+
+/// Winit reports the direction the content should move in, so both axes are
+/// flipped to get the direction to scroll in.
+pub fn convert_mouse_scroll_delta(
+    delta: MouseScrollDelta,
+    scale_factor_recip: f32,
+) -> WheelDeltaType {
+    match delta {
+        MouseScrollDelta::LineDelta(x, y) => WheelDeltaType::Lines(Vector::new(-x, -y)),
+        MouseScrollDelta::PixelDelta(pos) => WheelDeltaType::Points(Vector::new(
+            -pos.x as f32 * scale_factor_recip,
+            -pos.y as f32 * scale_factor_recip,
+        )),
+    }
+}
+
+/// Winit reports stylus input as touch events without saying where it came
+/// from, so pens are told apart from fingers by the force data they carry.
+/// Only a stylus reports an altitude angle. A normalized force is reported for
+/// fingers on touch screens as well, so it is not enough to detect a pen.
+pub fn convert_touch_pointer_type(force: Option<Force>) -> PointerType {
+    match force {
+        Some(
+            force @ Force::Calibrated {
+                altitude_angle: Some(altitude_angle),
+                ..
+            },
+        ) => PointerType::Pen {
+            pressure: force.normalized() as f32,
+            // Winit does not report the azimuth angle, so the pen is assumed
+            // to be tilted along the x axis.
+            tilt: pen_tilt(altitude_angle, 0.0),
+        },
+        _ => PointerType::Touch,
+    }
+}
+
+/// The tilt of a pen in the `(x, y)` directions, from the angle between the
+/// pen and the surface and the angle of the pen around the perpendicular of
+/// the surface (measured from the positive x axis).
+fn pen_tilt(altitude_angle: f64, azimuth_angle: f64) -> (f32, f32) {
+    let tan_altitude = altitude_angle.tan();
+    (
+        azimuth_angle.cos().atan2(tan_altitude) as f32,
+        azimuth_angle.sin().atan2(tan_altitude) as f32,
+    )
+}
+
+pub fn convert_theme(theme: Theme) -> ColorScheme {
+    match theme {
+        Theme::Light => ColorScheme::Light,
+        Theme::Dark => ColorScheme::Dark,
+    }
+}
+
 pub fn convert_modifiers(winit_modifiers: winit::event::Modifiers) -> Modifiers {
     let mut modifiers = Modifiers::empty();
     if winit_modifiers.state().shift_key() {
@@ -356,3 +434,101 @@ fn convert_physical_key(key: PhysicalKey, location: KeyLocation) -> (Code, Nativ
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use winit::dpi::PhysicalPosition;
+
+    use super::*;
+
+    #[test]
+    fn test_mouse_scroll_delta_is_flipped_on_both_axes() {
+        assert_eq!(
+            convert_mouse_scroll_delta(MouseScrollDelta::LineDelta(1.0, 2.0), 1.0),
+            WheelDeltaType::Lines(Vector::new(-1.0, -2.0))
+        );
+        assert_eq!(
+            convert_mouse_scroll_delta(
+                MouseScrollDelta::PixelDelta(PhysicalPosition::new(10.0, -20.0)),
+                0.5
+            ),
+            WheelDeltaType::Points(Vector::new(-5.0, 10.0))
+        );
+    }
+
+    #[test]
+    fn test_touch_phases_are_converted() {
+        use winit::event::TouchPhase as WinitTouchPhase;
+
+        assert_eq!(
+            convert_touch_phase(WinitTouchPhase::Started),
+            TouchPhase::Started
+        );
+        assert_eq!(
+            convert_touch_phase(WinitTouchPhase::Moved),
+            TouchPhase::Moved
+        );
+        assert_eq!(
+            convert_touch_phase(WinitTouchPhase::Ended),
+            TouchPhase::Ended
+        );
+        assert_eq!(
+            convert_touch_phase(WinitTouchPhase::Cancelled),
+            TouchPhase::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_only_touches_with_an_altitude_angle_are_pens() {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+        assert_eq!(convert_touch_pointer_type(None), PointerType::Touch);
+        assert_eq!(
+            convert_touch_pointer_type(Some(Force::Normalized(0.5))),
+            PointerType::Touch
+        );
+        assert_eq!(
+            convert_touch_pointer_type(Some(Force::Calibrated {
+                force: 1.0,
+                max_possible_force: 2.0,
+                altitude_angle: None,
+            })),
+            PointerType::Touch
+        );
+
+        let pen = |altitude_angle: f64| match convert_touch_pointer_type(Some(Force::Calibrated {
+            force: 1.0,
+            max_possible_force: 2.0,
+            altitude_angle: Some(altitude_angle),
+        })) {
+            PointerType::Pen { pressure, tilt } => (pressure, tilt),
+            pointer_type => panic!("expected a pen, got {:?}", pointer_type),
+        };
+
+        let (pressure, tilt) = pen(FRAC_PI_2);
+        assert_eq!(pressure, 0.5);
+        assert!(tilt.0.abs() < 1e-5 && tilt.1.abs() < 1e-5);
+
+        let (_, tilt) = pen(FRAC_PI_4);
+        assert!((tilt.0 - FRAC_PI_4 as f32).abs() < 1e-5);
+        assert!(tilt.1.abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_pen_tilt_is_split_by_the_azimuth_angle() {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+        let approx_eq = |a: (f32, f32), b: (f64, f64)| {
+            (a.0 - b.0 as f32).abs() < 1e-5 && (a.1 - b.1 as f32).abs() < 1e-5
+        };
+
+        // Perpendicular to the surface, regardless of the azimuth.
+        assert!(approx_eq(pen_tilt(FRAC_PI_2, 1.0), (0.0, 0.0)));
+        assert!(approx_eq(pen_tilt(FRAC_PI_4, 0.0), (FRAC_PI_4, 0.0)));
+        assert!(approx_eq(pen_tilt(FRAC_PI_4, FRAC_PI_2), (0.0, FRAC_PI_4)));
+        assert!(approx_eq(
+            pen_tilt(FRAC_PI_4, -FRAC_PI_2),
+            (0.0, -FRAC_PI_4)
+        ));
+    }
+}
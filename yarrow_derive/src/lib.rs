@@ -64,6 +64,14 @@ pub fn element_builder(_args: TokenStream, input: TokenStream) -> TokenStream {
                         self
                     }
 
+                    /// Place the element at the base of the given z layer (see
+                    /// `ZLayer` for the ranges). This is the same as
+                    /// `z_index(layer.base())`.
+                    pub const fn layer(mut self, layer: #crate_name::layout::ZLayer) -> Self {
+                        self.z_index = Some(layer.base());
+                        self
+                    }
+
                     /// The ID of the scissoring rectangle this element belongs to.
                     ///
                     /// If this method is not used, then the current scissoring rectangle ID from the
@@ -312,6 +320,14 @@ pub fn element_builder_tooltip(_args: TokenStream, input: TokenStream) -> TokenS
                         self.tooltip_data = Some(#crate_name::elements::tooltip::TooltipData::new(text, align));
                         self
                     }
+
+                    /// Show a tooltip when the user hovers over this element, using
+                    /// the given tooltip data (i.e. to set a custom show delay with
+                    /// `TooltipData::delay`)
+                    pub fn tooltip_data(mut self, data: #crate_name::elements::tooltip::TooltipData) -> Self {
+                        self.tooltip_data = Some(data);
+                        self
+                    }
                 }
             }
             .into()
@@ -325,6 +341,56 @@ pub fn element_builder_tooltip(_args: TokenStream, input: TokenStream) -> TokenS
     }
 }
 
+/// Add an `on_dropped` action to an element builder.
+///
+/// The builder must have a generic action type named `A`. The element is
+/// responsible for sending the action: add `ElementFlags::LISTENS_TO_ON_DROPPED`
+/// to its flags when the action is `Some`, and call it in `Element::on_dropped`.
+#[proc_macro_attribute]
+pub fn element_builder_on_drop(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut ast = parse_macro_input!(input as DeriveInput);
+    let name = ast.ident.clone();
+    let generics = ast.generics.clone();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    match &mut ast.data {
+        syn::Data::Struct(ref mut struct_data) => {
+            if let syn::Fields::Named(ref mut fields) = struct_data.fields {
+                fields.named.push(
+                    syn::Field::parse_named
+                        .parse2(quote! {
+                            /// The action to send when this element is dropped
+                            ///
+                            /// By default this is set to `None`.
+                            pub on_dropped: Option<Box<dyn FnMut() -> A>>
+                        })
+                        .unwrap(),
+                );
+            }
+
+            quote! {
+                #ast
+
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// Send an action when this element is dropped (i.e. to clean
+                    /// up any application state associated with it)
+                    pub fn on_dropped<F_: FnMut() -> A + 'static>(mut self, f: F_) -> Self {
+                        self.on_dropped = Some(Box::new(f));
+                        self
+                    }
+                }
+            }
+            .into()
+        }
+        _ => syn::Error::new(
+            ast.span(),
+            "`element_builder_on_drop` has to be used with structs ",
+        )
+        .to_compile_error()
+        .into(),
+    }
+}
+
 #[proc_macro_attribute]
 pub fn element_handle(_args: TokenStream, input: TokenStream) -> TokenStream {
     let mut ast = parse_macro_input!(input as DeriveInput);
@@ -477,6 +543,16 @@ pub fn element_handle(_args: TokenStream, input: TokenStream) -> TokenStream {
                     pub fn rect_in_window<A_: Clone + 'static>(&self, cx: &#crate_name::WindowContext<'_, A_>) -> Rect {
                         self.el.rect_in_window(cx)
                     }
+
+                    /// Whether or not the pointer is currently over this element.
+                    pub fn is_hovered<A_: Clone + 'static>(&self, cx: &#crate_name::WindowContext<'_, A_>) -> bool {
+                        self.el.is_hovered(cx)
+                    }
+
+                    /// Whether or not this element currently has exclusive focus.
+                    pub fn is_focused<A_: Clone + 'static>(&self, cx: &#crate_name::WindowContext<'_, A_>) -> bool {
+                        self.el.is_focused(cx)
+                    }
                 }
             }
             .into()
@@ -615,6 +691,17 @@ pub fn element_handle_set_rect(_args: TokenStream, input: TokenStream) -> TokenS
                     pub fn offset_pos(&mut self, offset: #crate_name::math::Vector) {
                         self.el.offset_pos(offset)
                     }
+
+                    /// Begin a batch of changes to the rectangular area of this element
+                    /// instance.
+                    ///
+                    /// The changes are sent to the view as a single update when the returned
+                    /// transaction is dropped, and only if the rectangle has changed.
+                    pub fn begin_layout(
+                        &mut self,
+                    ) -> #crate_name::prelude::LayoutTransaction<'_> {
+                        self.el.begin_layout()
+                    }
                 }
             }
             .into()